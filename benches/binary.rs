@@ -0,0 +1,44 @@
+#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+/// Returns an arithmetic expression nested `depth` binary operations deep
+fn deep(depth: usize) -> String {
+    let operators = ["+", "*", "-", "^", "|", "&", ">>", "<<"];
+
+    (0..depth).fold("[0, 0xffff]".to_owned(), |expr, i| {
+        format!("({} {} {})", expr, operators[i % operators.len()], i % 7 + 1)
+    })
+}
+
+fn model(flatten: bool) -> rvs::Model {
+    let source = format!("a = {};", deep(20));
+    let search_path: rvs::SearchPath = Default::default();
+    let mut parser = rvs::Parser::new(&search_path);
+    parser.parse(&source).unwrap();
+
+    let mut model = rvs::Model::new();
+    let mut transform = rvs::Transform::new(Default::default());
+    transform.set_flatten_operations(flatten);
+    transform.transform(&mut model, parser.ast()).unwrap();
+
+    model
+}
+
+#[bench]
+fn tree(b: &mut Bencher) {
+    let model = model(false);
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    b.iter(|| variable.next());
+}
+
+#[bench]
+fn flattened(b: &mut Bencher) {
+    let model = model(true);
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    b.iter(|| variable.next());
+}
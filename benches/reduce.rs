@@ -0,0 +1,40 @@
+#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+/// Returns a chain of 16 `&` operations
+fn chain() -> String {
+    (0..16).map(|i| format!("[0, 0x{:x}]", u32::MAX >> i)).collect::<Vec<_>>().join(" & ")
+}
+
+fn model(reduce: bool) -> rvs::Model {
+    let source = format!("a = {};", chain());
+    let search_path: rvs::SearchPath = Default::default();
+    let mut parser = rvs::Parser::new(&search_path);
+    parser.parse(&source).unwrap();
+
+    let mut model = rvs::Model::new();
+    let mut transform = rvs::Transform::new(Default::default());
+    transform.set_reduce_operations(reduce);
+    transform.transform(&mut model, parser.ast()).unwrap();
+
+    model
+}
+
+#[bench]
+fn tree(b: &mut Bencher) {
+    let model = model(false);
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    b.iter(|| variable.next());
+}
+
+#[bench]
+fn reduced(b: &mut Bencher) {
+    let model = model(true);
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    b.iter(|| variable.next());
+}
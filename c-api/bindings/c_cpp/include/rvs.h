@@ -3,8 +3,11 @@
 
 #ifdef __cplusplus
 #include <cstdint>
+#include <cstddef>
 #else
 #include <stdint.h>
+#include <stddef.h>
+#include <stdbool.h>
 #endif
 
 #ifdef __cplusplus
@@ -14,24 +17,106 @@ extern "C" {
 typedef struct rvs_error rvs_error;
 typedef struct rvs_context rvs_context;
 typedef struct rvs_model rvs_model;
+/* Identifies a variable of a model.  Only valid for the model it was returned for and copies of it. */
+typedef uint32_t rvs_handle;
+typedef uint32_t (*rvs_value_transform)(uint32_t value, void* user_data);
+typedef bool (*rvs_stream_callback)(uint32_t value, bool done, void* user_data);
+
+#define RVS_ERROR_NONE 0
+#define RVS_ERROR_PARSE 1
+#define RVS_ERROR_TRANSFORM 2
+#define RVS_ERROR_IO 3
+#define RVS_ERROR_CONSTRAINT 4
+#define RVS_ERROR_BUDGET 5
+#define RVS_ERROR_UNDEFINED 6
+#define RVS_ERROR_NOT_TRANSFORMED 7
 
 rvs_context* rvs_context_new(const char* search_path, uint32_t seed, rvs_error* error);
+rvs_context* rvs_context_new_random(const char* search_path, rvs_error* error);
+rvs_context* rvs_context_new_from_env(const char* search_path, uint32_t default_seed, rvs_error* error);
+void rvs_get_seed(const rvs_context* context, uint32_t* seed);
+bool rvs_get_seed_bytes(const rvs_context* context, uint8_t* buf, size_t len);
+size_t rvs_context_serialize(const rvs_context* context, uint8_t* buf, size_t len, rvs_error* error);
+rvs_context* rvs_context_deserialize(const uint8_t* buf, size_t len, rvs_error* error);
 void rvs_context_free(rvs_context* context);
 void rvs_parse(rvs_context* context, const char* s, rvs_error* error);
+void rvs_parse_ex(rvs_context* context, const char* s, rvs_error* error, size_t* defined_count);
+bool rvs_set_max_resample_attempts(rvs_context* context, uint32_t max_resample_attempts);
+void rvs_set_aligned_binary_done(rvs_context* context, bool enabled);
+void rvs_set_output_mask(rvs_context* context, uint32_t output_mask);
+void rvs_set_rng_draw_counting(rvs_context* context, bool enabled);
+void rvs_set_lazy(rvs_context* context, bool enabled);
+void rvs_parse_file(rvs_context* context, const char* path, rvs_error* error);
 
 rvs_model* rvs_model_new();
 rvs_model* rvs_transform(rvs_context* context, rvs_model* model, rvs_error* error);
+void rvs_parse_and_transform(rvs_context* context, const char* s, rvs_model* model, rvs_error* error);
+size_t rvs_preview(rvs_context* context, const char* s, size_t n, char* buf, size_t len, rvs_error* error);
+size_t rvs_preview_ex(rvs_context* context, const char* s, size_t n, bool hex_strings, char* buf, size_t len, rvs_error* error);
+size_t rvs_parse_ast_string(const char* s, const char* search_path, char* buf, size_t len, rvs_error* error);
 void rvs_model_free(rvs_model* model);
+rvs_model* rvs_model_clone(const rvs_model* model);
+
+rvs_handle rvs_get(rvs_model* model, const char* id);
+rvs_handle rvs_get_checked(rvs_model* model, const char* id, rvs_error* error);
+uint32_t rvs_next(rvs_model* model, rvs_handle handle);
+uint32_t rvs_next_with_entropy(rvs_model* model, rvs_handle handle, const uint8_t* entropy, size_t len);
+bool rvs_next_with_override(rvs_model* model, rvs_handle handle, const char* name, uint32_t value, uint32_t* result);
+bool rvs_next_ex(rvs_model* model, rvs_handle handle, uint32_t* value, bool* done, uint32_t* prev);
+bool rvs_next_checked(rvs_model* model, rvs_handle handle, uint32_t* value, rvs_error* error);
+void rvs_set_eval_budget(rvs_model* model, uint32_t max_iterations);
+bool rvs_next_until(rvs_model* model, rvs_handle handle, uint32_t target, size_t cap, size_t* count);
+bool rvs_skip(rvs_model* model, rvs_handle handle, size_t n);
+uint32_t rvs_prev(rvs_model* model, rvs_handle handle);
+bool rvs_current(rvs_model* model, rvs_handle handle, uint32_t* value);
+bool rvs_done(rvs_model* model, rvs_handle handle);
+bool rvs_done_edge(rvs_model* model, rvs_handle handle, bool* edge);
+bool rvs_last_index(rvs_model* model, rvs_handle handle, size_t* index);
+bool rvs_cycle_position(rvs_model* model, rvs_handle handle, size_t* position);
+bool rvs_cycle_count(rvs_model* model, rvs_handle handle, uint64_t* count);
+bool rvs_variable_state(rvs_model* model, rvs_handle handle, uint32_t* prev, bool* done, uint64_t* count, uint64_t* cycle_count, size_t* cycle_position);
+bool rvs_set_enabled(rvs_model* model, rvs_handle handle, bool enabled);
+bool rvs_set_disabled_value(rvs_model* model, rvs_handle handle, uint32_t value);
+bool rvs_freeze(rvs_model* model, rvs_handle handle);
+bool rvs_unfreeze(rvs_model* model, rvs_handle handle);
+void rvs_reset(rvs_model* model, rvs_handle handle);
+void rvs_reset_rng(rvs_model* model, rvs_handle handle);
+void rvs_reset_all(rvs_model* model);
+bool rvs_checkpoint(rvs_model* model, const char* name);
+bool rvs_restore(rvs_model* model, const char* name);
+void rvs_set_stats_enabled(rvs_model* model, rvs_handle handle, bool enabled);
+bool rvs_stats(rvs_model* model, rvs_handle handle, uint32_t* min, uint32_t* max, uint32_t* mean);
+bool rvs_rng_draws(rvs_model* model, rvs_handle handle, uint64_t* draws);
+bool rvs_value_bounds(rvs_model* model, rvs_handle handle, uint32_t* min, uint32_t* max);
+bool rvs_is_deterministic(rvs_model* model, rvs_handle handle, bool* result);
+bool rvs_get_range(rvs_model* model, rvs_handle handle, uint32_t* low, uint32_t* high);
+bool rvs_set_range(rvs_model* model, rvs_handle handle, uint32_t low, uint32_t high);
+bool rvs_weights(rvs_model* model, rvs_handle handle, uint32_t* weights, size_t cap, size_t* len);
+void rvs_set_value_transform(rvs_model* model, rvs_handle handle, rvs_value_transform callback, void* user_data);
+size_t rvs_stream(rvs_model* model, rvs_handle handle, size_t n, rvs_stream_callback callback, void* user_data);
+void rvs_find_prefix(const rvs_model* model, const char* prefix, rvs_handle* handles, size_t cap, size_t* len);
+size_t rvs_enum_count(const rvs_model* model);
+bool rvs_enum_name(const rvs_model* model, size_t index, char* buf, size_t len);
+bool rvs_enum_member(const rvs_model* model, const char* enum_name, size_t index, char* name_buf, size_t name_len, uint32_t* value);
+size_t rvs_variable_dot(rvs_model* model, rvs_handle handle, char* buf, size_t len);
+bool rvs_variable_attribute(rvs_model* model, rvs_handle handle, const char* key, char* buf, size_t len);
+bool rvs_variable_doc(rvs_model* model, rvs_handle handle, char* buf, size_t len);
+size_t rvs_warning_count(const rvs_model* model);
+bool rvs_warning_message(const rvs_model* model, size_t index, char* buf, size_t len);
+void rvs_warnings_clear(rvs_model* model);
 
-uint32_t rvs_get(rvs_model* model, const char* id);
-uint32_t rvs_next(rvs_model* model, uint32_t handle);
-uint32_t rvs_prev(rvs_model* model, uint32_t handle);
-bool rvs_done(rvs_model* model, uint32_t handle);
+size_t rvs_version(char* buf, size_t len);
+bool rvs_has_feature(const char* name);
+bool rvs_self_test(void);
+bool rvs_get_limit(const char* name, uint64_t* value);
+bool rvs_set_limit(rvs_context* context, const char* name, uint64_t value);
 
 rvs_error* rvs_error_new();
 void rvs_error_free(rvs_error* error);
 bool rvs_error_test(rvs_error* error);
 const char* rvs_error_message(rvs_error* error);
+uint32_t rvs_error_code(const rvs_error* error);
+size_t rvs_error_code_string(uint32_t code, char* buf, size_t len);
 
 #ifdef __cplusplus
 }
@@ -0,0 +1,22 @@
+use libc::c_char;
+use std::ptr;
+
+/// Copies a string into a caller provided buffer as a null terminated C string
+///
+/// At most `len - 1` bytes of `s` are copied so the result is always null terminated.  Nothing is
+/// copied if `buf` is null or `len` is 0.
+///
+/// Returns the length of `s` in bytes, excluding the null terminator.  A return value greater than
+/// or equal to `len` indicates truncation.
+pub fn write_str(s: &str, buf: *mut c_char, len: usize) -> usize {
+    if !buf.is_null() && len > 0 {
+        let count = s.len().min(len - 1);
+
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buf, count);
+            *buf.add(count) = 0;
+        }
+    }
+
+    s.len()
+}
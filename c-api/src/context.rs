@@ -1,37 +1,436 @@
-use rvs;
-
 use std::io;
+use std::io::prelude::*;
+use std::fs::File;
 use std::path::Path;
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::env;
+use std::hash::{BuildHasher, Hasher};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The id of the next context
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// The environment variable read by `Context::new_from_env`
+pub const SEED_ENV_VAR: &str = "RVS_SEED";
+
+const MAGIC: &[u8; 4] = b"RVSC";
+
+/// The version of the encoding of `Context::serialize`
+///
+/// Covers the context settings only.  The AST and the model embedded in the encoding carry their
+/// own versions.
+pub const CONTEXT_FORMAT_VERSION: u32 = 1;
 
 pub struct Context {
+    /// Identifies the context in the handles of the variables of the models it transforms
+    id: u32,
     parser: rvs::Parser,
+    /// The 32-bit seed `seed` was generated from
+    seed_value: u32,
     seed: rvs::Seed,
     search_path: rvs::SearchPath,
+    options: TransformOptions,
+    lazy: bool,
+    /// The model restored by `deserialize`.  Used by `transform` until the context changes.
+    cached: Option<CachedModel>,
+}
+
+/// A model serialized by `Context::serialize`
+struct CachedModel {
+    bytes: Vec<u8>,
+    /// The options the model was transformed with
+    options: TransformOptions,
+}
+
+/// The transform settings of a `Context` shared by `Context::transform` and
+/// `Context::spawn_producer`
+#[derive(Clone, Copy, PartialEq)]
+struct TransformOptions {
+    max_resample_attempts: u32,
+    max_children: usize,
+    binary_done_mode: rvs::BinaryDoneMode,
+    output_mask: u32,
+    count_rng_draws: bool,
+}
+
+impl TransformOptions {
+    fn apply(&self, transform: &mut rvs::Transform) {
+        transform.set_max_resample_attempts(self.max_resample_attempts);
+        transform.set_max_children(self.max_children);
+        transform.set_binary_done_mode(self.binary_done_mode);
+        transform.set_output_mask(self.output_mask);
+        transform.set_rng_draw_counting(self.count_rng_draws);
+    }
 }
 
 impl Context {
-    pub fn new(search_path: rvs::SearchPath, seed: rvs::Seed) -> Context {
+    pub fn new(search_path: rvs::SearchPath, seed: u32) -> Context {
         Context {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             parser: rvs::Parser::new(&search_path),
-            seed,
+            seed_value: seed,
+            seed: rvs::Seed::from_u32(seed),
             search_path,
+            options: TransformOptions {
+                max_resample_attempts: rvs::DEFAULT_MAX_RESAMPLE_ATTEMPTS,
+                max_children: usize::MAX,
+                binary_done_mode: Default::default(),
+                output_mask: u32::MAX,
+                count_rng_draws: false,
+            },
+            lazy: false,
+            cached: None,
         }
     }
 
+    /// Creates a Context seeded from the `RVS_SEED` environment variable
+    ///
+    /// Uses `default_seed` if `RVS_SEED` is not set or is empty.  Returns an error if `RVS_SEED`
+    /// is set to anything other than a decimal or `0x` prefixed hexadecimal `u32`.
+    pub fn new_from_env(search_path: rvs::SearchPath, default_seed: u32) -> io::Result<Context> {
+        let seed = Context::seed_from_env()?.unwrap_or(default_seed);
+
+        Ok(Context::new(search_path, seed))
+    }
+
+    /// Returns the seed given by the `RVS_SEED` environment variable
+    ///
+    /// Returns `None` if `RVS_SEED` is not set or is empty.  Surrounding whitespace and
+    /// underscores between digits are ignored.  E.g. `1234` and `0x1234_abcd`.
+    fn seed_from_env() -> io::Result<Option<u32>> {
+        let value = match env::var(SEED_ENV_VAR) {
+            Ok(value) => value,
+            Err(env::VarError::NotPresent) => return Ok(None),
+            Err(env::VarError::NotUnicode(value)) => {
+                return Err(invalid_seed(&value.to_string_lossy()));
+            }
+        };
+
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+
+        parse_seed(trimmed).map(Some).ok_or_else(|| invalid_seed(&value))
+    }
+
+    /// Returns a seed that differs between calls and between processes
+    pub fn random_seed() -> u32 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(NEXT_ID.load(Ordering::Relaxed));
+
+        hasher.finish() as u32
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed_value
+    }
+
+    /// Returns the 128-bit seed generated from `seed`
+    pub fn seed_bytes(&self) -> &[u8; 16] {
+        self.seed.as_bytes()
+    }
+
+    pub fn set_lazy(&mut self, enabled: bool) {
+        self.lazy = enabled;
+    }
+
+    pub fn set_rng_draw_counting(&mut self, enabled: bool) {
+        self.options.count_rng_draws = enabled;
+    }
+
+    pub fn set_output_mask(&mut self, output_mask: u32) {
+        self.options.output_mask = output_mask;
+    }
+
+    pub fn set_binary_done_mode(&mut self, binary_done_mode: rvs::BinaryDoneMode) {
+        self.options.binary_done_mode = binary_done_mode;
+    }
+
+    pub fn set_max_import_depth(&mut self, max_import_depth: usize) {
+        self.parser.set_max_import_depth(max_import_depth);
+    }
+
+    pub fn set_max_variables(&mut self, max_variables: usize) {
+        self.parser.set_max_variables(max_variables);
+    }
+
+    pub fn set_max_children(&mut self, max_children: usize) {
+        self.parser.set_max_children(max_children);
+        self.options.max_children = max_children;
+    }
+
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.parser.set_max_nodes(max_nodes);
+    }
+
+    pub fn set_max_resample_attempts(&mut self, max_resample_attempts: u32) {
+        self.options.max_resample_attempts = max_resample_attempts;
+    }
+
     pub fn parse(&mut self, s: &str) -> rvs::Result<()> {
+        self.cached = None;
         self.parser.parse(s)
     }
 
+    pub fn definition_count(&self) -> usize {
+        self.parser.definition_count()
+    }
+
     pub fn transform(&self, model: &mut rvs::Model) -> rvs::Result<()> {
+        model.set_tag(self.id);
+
+        if let Some(ref cached) = self.cached {
+            if cached.options == self.options {
+                return model.deserialize(&cached.bytes);
+            }
+        }
+
         let mut transform = rvs::Transform::new(self.seed.clone());
+        self.options.apply(&mut transform);
+        transform.set_lazy(self.lazy);
+        transform.set_search_path(self.search_path.clone());
 
         transform.transform(model, self.parser.ast())?;
 
         Ok(())
     }
 
+    /// Returns the context in a compact binary format
+    ///
+    /// The encoding holds the transformed expression trees of all variables in addition to the
+    /// seed, the settings, the search path, and the AST.  `deserialize` restores the context and
+    /// `transform` of the restored context restores the model from the expression trees instead
+    /// of transforming the AST again.  Models restored from the same bytes return the same values
+    /// as models transformed by this context.  The format is versioned.  See
+    /// `CONTEXT_FORMAT_VERSION` and `rvs::MODEL_FORMAT_VERSION`.  Parser limits are not included.
+    ///
+    /// Returns an error if the AST fails to transform.
+    pub fn serialize(&self) -> rvs::Result<Vec<u8>> {
+        let mut model = rvs::Model::new();
+        self.transform(&mut model)?;
+        let model = model.serialize()?;
+
+        let mut bytes = MAGIC.to_vec();
+        put_u32(&mut bytes, CONTEXT_FORMAT_VERSION);
+        put_u32(&mut bytes, self.seed_value);
+        put_u32(&mut bytes, self.options.max_resample_attempts);
+        bytes.push(match self.options.binary_done_mode {
+            rvs::BinaryDoneMode::Latched => 0,
+            rvs::BinaryDoneMode::Aligned => 1,
+        });
+        put_u32(&mut bytes, self.options.output_mask);
+        bytes.push(self.options.count_rng_draws as u8);
+        bytes.push(self.lazy as u8);
+
+        let paths = self.search_path.paths();
+        put_len(&mut bytes, paths.len());
+        for path in paths {
+            put_bytes(&mut bytes, path.to_string_lossy().as_bytes());
+        }
+
+        put_bytes(&mut bytes, &self.parser.serialize_ast());
+        put_bytes(&mut bytes, &model);
+
+        Ok(bytes)
+    }
+
+    /// Creates a context from the bytes returned by `serialize`
+    ///
+    /// Nothing is parsed.  Returns an error if the bytes were not returned by `serialize`, are
+    /// corrupt, or are of a different format version.  The restored context has a new id so
+    /// handles of models of the original context are rejected by models of the restored one.
+    /// Parsing into the restored context or changing its settings discards the serialized model
+    /// so the next `transform` transforms the AST.  Limits (e.g. `set_max_children`) are not
+    /// serialized and are unlimited in the restored context.
+    pub fn deserialize(bytes: &[u8]) -> rvs::Result<Context> {
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(rvs::TransformError::new("not a serialized context".into()).into());
+        }
+
+        let mut reader = ContextReader { bytes, position: MAGIC.len() };
+        let version = reader.u32()?;
+        if version != CONTEXT_FORMAT_VERSION {
+            return Err(rvs::TransformError::new(format!(
+                "serialized context format version {} is not supported (expected {})",
+                version, CONTEXT_FORMAT_VERSION
+            ))
+            .into());
+        }
+
+        let seed = reader.u32()?;
+        let max_resample_attempts = reader.u32()?;
+        let binary_done_mode = match reader.u8()? {
+            0 => rvs::BinaryDoneMode::Latched,
+            1 => rvs::BinaryDoneMode::Aligned,
+            _ => return Err(reader.error("unknown done mode")),
+        };
+        let output_mask = reader.u32()?;
+        let count_rng_draws = reader.bool()?;
+        let lazy = reader.bool()?;
+
+        let paths = (0..reader.len()?)
+            .map(|_| {
+                let path = reader.bytes()?;
+                String::from_utf8(path.to_vec())
+                    .map(PathBuf::from)
+                    .map_err(|_| reader.error("invalid UTF-8"))
+            })
+            .collect::<rvs::Result<_>>()?;
+        let search_path = rvs::SearchPath::new(paths);
+
+        let parser = rvs::Parser::deserialize_ast(&search_path, reader.bytes()?)?;
+        let model = reader.bytes()?.to_vec();
+        if reader.position != bytes.len() {
+            return Err(reader.error("trailing bytes"));
+        }
+
+        // Reject a corrupt model now instead of on the first transform
+        rvs::Model::new().deserialize(&model)?;
+
+        let mut context = Context::new(search_path, seed);
+        context.parser = parser;
+        context.options = TransformOptions {
+            max_resample_attempts,
+            max_children: usize::MAX,
+            binary_done_mode,
+            output_mask,
+            count_rng_draws,
+        };
+        context.lazy = lazy;
+        context.cached = Some(CachedModel {
+            bytes: model,
+            options: context.options,
+        });
+
+        Ok(context)
+    }
+
+    /// Starts evaluating the variable `name` on a background thread
+    ///
+    /// The values are the same as those of the variable of a model transformed by `transform`.
+    /// Lazy building does not apply.  See `rvs::spawn_producer_with`.
+    pub fn spawn_producer(&self, name: &str, capacity: usize) -> rvs::Result<rvs::ProducerHandle> {
+        let options = self.options;
+
+        rvs::spawn_producer_with(&self.parser, self.seed.clone(), name, capacity, move |transform| {
+            options.apply(transform)
+        })
+    }
+
     pub fn find_file(&self, path: &Path) -> io::Result<PathBuf> {
         self.search_path.find(path)
     }
+
+    /// Finds a file in the search path and returns its contents
+    pub fn read_file(&self, path: &Path) -> io::Result<String> {
+        let path = self.find_file(path)?;
+        let mut file = File::open(&path)?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+
+        Ok(contents)
+    }
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_len(bytes: &mut Vec<u8>, len: usize) {
+    put_u32(bytes, u32::try_from(len).expect("length exceeds u32"));
+}
+
+/// Writes `value` prefixed with its length
+fn put_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    put_len(bytes, value.len());
+    bytes.extend_from_slice(value);
+}
+
+/// Reads the fields written by `Context::serialize`
+struct ContextReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ContextReader<'a> {
+    fn error(&self, message: &str) -> rvs::Error {
+        rvs::TransformError::new(format!(
+            "invalid serialized context at byte {}: {}",
+            self.position, message
+        ))
+        .into()
+    }
+
+    fn take(&mut self, len: usize) -> rvs::Result<&'a [u8]> {
+        if self.bytes.len() - self.position < len {
+            return Err(self.error("unexpected end of input"));
+        }
+
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> rvs::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> rvs::Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(self.error("invalid bool")),
+        }
+    }
+
+    fn u32(&mut self) -> rvs::Result<u32> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn len(&mut self) -> rvs::Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    /// Reads bytes written by `put_bytes`
+    fn bytes(&mut self) -> rvs::Result<&'a [u8]> {
+        let len = self.len()?;
+
+        self.take(len)
+    }
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal `u32`
+fn parse_seed(s: &str) -> Option<u32> {
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(digits) => (digits, 16),
+        None => (s, 10),
+    };
+
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') {
+        return None;
+    }
+
+    let digits: String = digits.chars().filter(|&c| c != '_').collect();
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+
+    u32::from_str_radix(&digits, radix).ok()
+}
+
+fn invalid_seed(value: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "{} must be a decimal or 0x prefixed hexadecimal 32-bit value but is '{}'",
+            SEED_ENV_VAR, value
+        ),
+    )
 }
@@ -1,11 +1,11 @@
-#![cfg_attr(feature = "cargo-clippy", allow(not_unsafe_ptr_arg_deref))]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 
 use std::fmt;
 use std::io;
 use std::ffi::CString;
 use libc::c_char;
 
-use rvs;
+use crate::buffer;
 
 #[derive(Debug)]
 pub struct Error {
@@ -13,6 +13,18 @@ pub struct Error {
     pub kind: ErrorKind,
 }
 
+/// Numeric error codes returned by `rvs_error_code`
+///
+/// Codes are stable across releases.  New codes may be added.
+pub const RVS_ERROR_NONE: u32 = 0;
+pub const RVS_ERROR_PARSE: u32 = 1;
+pub const RVS_ERROR_TRANSFORM: u32 = 2;
+pub const RVS_ERROR_IO: u32 = 3;
+pub const RVS_ERROR_CONSTRAINT: u32 = 4;
+pub const RVS_ERROR_BUDGET: u32 = 5;
+pub const RVS_ERROR_UNDEFINED: u32 = 6;
+pub const RVS_ERROR_NOT_TRANSFORMED: u32 = 7;
+
 #[derive(Debug)]
 pub enum ErrorKind {
     None,
@@ -46,6 +58,39 @@ impl fmt::Display for Error {
     }
 }
 
+impl ErrorKind {
+    /// Returns the numeric code for the kind of error
+    pub fn code(&self) -> u32 {
+        match *self {
+            ErrorKind::None => RVS_ERROR_NONE,
+            ErrorKind::Rvs(rvs::Error::Parse(_)) => RVS_ERROR_PARSE,
+            ErrorKind::Rvs(rvs::Error::Transform(_)) => RVS_ERROR_TRANSFORM,
+            ErrorKind::Rvs(rvs::Error::Constraint(_)) => RVS_ERROR_CONSTRAINT,
+            ErrorKind::Rvs(rvs::Error::Budget(_)) => RVS_ERROR_BUDGET,
+            ErrorKind::Rvs(rvs::Error::Lookup(rvs::LookupError::Undefined(_))) => RVS_ERROR_UNDEFINED,
+            ErrorKind::Rvs(rvs::Error::Lookup(rvs::LookupError::NotTransformed(_))) => {
+                RVS_ERROR_NOT_TRANSFORMED
+            }
+            ErrorKind::Rvs(rvs::Error::Io(_)) | ErrorKind::Io(_) => RVS_ERROR_IO,
+        }
+    }
+}
+
+/// Returns a short description of a numeric error code
+fn code_str(code: u32) -> &'static str {
+    match code {
+        RVS_ERROR_NONE => "no error",
+        RVS_ERROR_PARSE => "parse error",
+        RVS_ERROR_TRANSFORM => "transform error",
+        RVS_ERROR_IO => "io error",
+        RVS_ERROR_CONSTRAINT => "constraint error",
+        RVS_ERROR_BUDGET => "budget exceeded",
+        RVS_ERROR_UNDEFINED => "undefined variable",
+        RVS_ERROR_NOT_TRANSFORMED => "not transformed",
+        _ => "unknown",
+    }
+}
+
 impl From<rvs::Error> for ErrorKind {
     fn from(err: rvs::Error) -> ErrorKind {
         ErrorKind::Rvs(err)
@@ -60,7 +105,7 @@ pub extern "C" fn rvs_error_new() -> *mut Error {
 #[no_mangle]
 pub extern "C" fn rvs_error_free(err: *mut Error) {
     unsafe {
-        Box::from_raw(err);
+        drop(Box::from_raw(err));
     }
 }
 
@@ -82,3 +127,24 @@ pub extern "C" fn rvs_error_test(err: *const Error) -> bool {
 
     err.is_err()
 }
+
+/// Returns the numeric code for the kind of error
+///
+/// See `rvs_error_code_string` for a description of the code.
+#[no_mangle]
+pub extern "C" fn rvs_error_code(err: *const Error) -> u32 {
+    let err = unsafe { &*err };
+
+    err.kind.code()
+}
+
+/// Copies a short description of a numeric error code into a buffer
+///
+/// Unknown codes are described as "unknown".
+///
+/// Returns the length of the description excluding the null terminator.  The description is
+/// truncated if the return value is greater than or equal to `len`.
+#[no_mangle]
+pub extern "C" fn rvs_error_code_string(code: u32, buf: *mut c_char, len: usize) -> usize {
+    buffer::write_str(code_str(code), buf, len)
+}
@@ -1,9 +1,16 @@
 mod rvsc;
 mod context;
 mod error;
+mod buffer;
+mod version;
+mod limits;
+
+pub mod safe;
 
 pub use rvsc::*;
 pub use error::*;
+pub use version::*;
+pub use limits::*;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,148 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use libc::c_char;
+use std::convert::TryFrom;
+use std::ffi::CStr;
+
+use crate::context::Context;
+
+/// A named numeric limit or default
+struct Limit {
+    name: &'static str,
+    default: u64,
+    /// Range of values accepted by `rvs_set_limit`.  `None` if the limit cannot be set.
+    settable: Option<(u64, u64)>,
+}
+
+const LIMITS: &[Limit] = &[
+    Limit {
+        name: "max_resample_attempts",
+        default: rvs::DEFAULT_MAX_RESAMPLE_ATTEMPTS as u64,
+        settable: Some((1, u32::MAX as u64)),
+    },
+    Limit {
+        name: "max_import_depth",
+        default: rvs::DEFAULT_MAX_IMPORT_DEPTH as u64,
+        settable: Some((0, u32::MAX as u64)),
+    },
+    Limit {
+        name: "max_variables",
+        default: u64::MAX,
+        settable: Some((0, u64::MAX)),
+    },
+    Limit {
+        name: "max_children",
+        default: u64::MAX,
+        settable: Some((0, u64::MAX)),
+    },
+    Limit {
+        name: "max_nodes",
+        default: u64::MAX,
+        settable: Some((0, u64::MAX)),
+    },
+    Limit {
+        name: "default_seed",
+        default: 0,
+        settable: None,
+    },
+    Limit {
+        name: "value_width",
+        default: 32,
+        settable: None,
+    },
+];
+
+fn find(name: *const c_char) -> Option<&'static Limit> {
+    let name = unsafe { CStr::from_ptr(name) }.to_str().ok()?;
+
+    LIMITS.iter().find(|limit| limit.name == name)
+}
+
+/// Returns the default value of a named limit via the result pointer
+///
+/// The following limits are available:
+///
+/// * `max_resample_attempts` - Maximum number of times an expression (e.g. `except`) re-samples
+///   a sub-expression.  Settable.
+/// * `max_import_depth` - Maximum number of nested imports.  Settable.
+/// * `max_variables` - Maximum number of variable and constant definitions parsed.  Unlimited
+///   (`UINT64_MAX`) by default.  Settable.
+/// * `max_children` - Maximum number of arguments of a type (e.g. `Pattern`) or entries of a
+///   weighted sample.  Also limits the values expanded when transforming (i.e. entries added by
+///   `Expand()`, pools of samples without replacement, and the values of a `PatternFile`).
+///   Unlimited (`UINT64_MAX`) by default.  Settable.
+/// * `max_nodes` - Maximum total number of AST nodes parsed.  Unlimited (`UINT64_MAX`) by
+///   default.  Settable.
+/// * `default_seed` - Seed used when none is given.
+/// * `value_width` - Width of values in bits.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if the name is unknown
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_get_limit(name: *const c_char, value: *mut u64) -> bool {
+    assert!(!name.is_null());
+    assert!(!value.is_null());
+
+    match find(name) {
+        Some(limit) => {
+            unsafe {
+                *value = limit.default;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sets a named limit for a context
+///
+/// See `rvs_get_limit` for the available limits.  Applies to parsing and transforming performed
+/// afterwards.  The parse limits (e.g. `max_nodes`) are checked once a source has been parsed.
+/// They don't bound the work of parsing that source.
+///
+/// # Errors
+///
+/// * Returns false and leaves the context untouched if the name is unknown, the limit is not
+///   settable, or the value is out of range
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_limit(context: *mut Context, name: *const c_char, value: u64) -> bool {
+    assert!(!context.is_null());
+    assert!(!name.is_null());
+
+    let limit = match find(name) {
+        Some(limit) => limit,
+        None => return false,
+    };
+
+    match limit.settable {
+        Some((min, max)) if value >= min && value <= max => {}
+        _ => return false,
+    }
+
+    let context = unsafe { &mut *context };
+    match limit.name {
+        "max_resample_attempts" => context.set_max_resample_attempts(value as u32),
+        "max_import_depth" => context.set_max_import_depth(value as usize),
+        "max_variables" => context.set_max_variables(to_usize(value)),
+        "max_children" => context.set_max_children(to_usize(value)),
+        "max_nodes" => context.set_max_nodes(to_usize(value)),
+        _ => unreachable!(),
+    }
+
+    true
+}
+
+/// Saturates values that do not fit in a `usize`
+fn to_usize(value: u64) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
@@ -2,38 +2,76 @@
 //!
 //! Provides a C API for parsing and evaluating random variables.
 
-#![cfg_attr(feature = "cargo-clippy", allow(not_unsafe_ptr_arg_deref))]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 
-use libc::c_char;
+use libc::{c_char, c_void};
 use std::ffi::CStr;
 use std::path::Path;
 use std::fs::File;
 use std::io::prelude::*;
-
-use rvs;
+use std::cell::RefCell;
+use std::panic;
+use std::process;
+use std::rc::Rc;
+use std::slice;
 
 use crate::context::Context;
 use crate::error::Error;
 use crate::error::ErrorKind;
+use crate::buffer;
 
 type SequenceHandleRaw = u32;
+
+/// Number of low bits of a handle that hold the variable index
+const HANDLE_INDEX_BITS: u32 = 24;
+
+/// A variable handle
+///
+/// The low 24 bits hold the variable index plus one so that 0 is never a valid handle.  The high 8
+/// bits hold a tag derived from the context the model was transformed from.  A handle used with a
+/// model of a different context is rejected.  Tags repeat every 255 contexts.
 struct SequenceHandle(SequenceHandleRaw);
 
 impl SequenceHandle {
+    fn new(model: &rvs::Model, index: usize) -> SequenceHandle {
+        SequenceHandle(handle_tag(model) << HANDLE_INDEX_BITS | (index + 1) as u32)
+    }
+
     pub fn to_raw(&self) -> SequenceHandleRaw {
         self.0
     }
-}
 
-impl Into<usize> for SequenceHandle {
-    fn into(self) -> usize {
-        (self.to_raw() - 1) as usize
+    /// Returns the variable index if the handle belongs to `model`
+    fn index(&self, model: &rvs::Model) -> Option<usize> {
+        if self.0 >> HANDLE_INDEX_BITS != handle_tag(model) {
+            return None;
+        }
+
+        let index = (self.0 & ((1 << HANDLE_INDEX_BITS) - 1)).checked_sub(1)?;
+
+        Some(index as usize)
+    }
+
+    fn variable<'a>(&self, model: &'a rvs::Model) -> Option<&'a Rc<RefCell<Box<rvs::Variable>>>> {
+        model.get_variable_by_index(self.index(model)?)
     }
 }
 
-impl From<usize> for SequenceHandle {
-    fn from(index: usize) -> SequenceHandle {
-        SequenceHandle((index + 1) as u32)
+fn handle_tag(model: &rvs::Model) -> u32 {
+    model.tag() % 255 + 1
+}
+
+/// Returns an error if a variable of `model` can't be addressed by a handle
+fn check_handle_limit(model: &rvs::Model) -> rvs::Result<()> {
+    let max_variables = (1 << HANDLE_INDEX_BITS) - 1;
+
+    match model.get_variable_indexes_by_prefix("").max() {
+        Some(index) if index >= max_variables => Err(rvs::TransformError::new(format!(
+            "the model has more than {} variables which is the maximum supported by handles",
+            max_variables
+        ))
+        .into()),
+        _ => Ok(()),
     }
 }
 
@@ -61,10 +99,19 @@ pub extern "C" fn rvs_context_new(
     seed: u32,
     error: *mut Error,
 ) -> *mut Context {
+    let search_path = new_search_path(search_path, error);
+
+    Box::into_raw(Box::new(Context::new(search_path, seed)))
+}
+
+/// Parses a colon separated list of paths
+///
+/// Reports an error and returns an empty search path if any of the paths do not exist.
+fn new_search_path(search_path: *const c_char, error: *mut Error) -> rvs::SearchPath {
     let c_str = unsafe { CStr::from_ptr(search_path) };
     let r_str = c_str.to_str().unwrap();
 
-    let search_path = match rvs::SearchPath::from_string(r_str) {
+    match rvs::SearchPath::from_string(r_str) {
         Ok(search_path) => search_path,
         Err(e) => {
             if !error.is_null() {
@@ -73,11 +120,187 @@ pub extern "C" fn rvs_context_new(
 
             Default::default()
         }
+    }
+}
+
+/// Allocates and returns a new Context seeded from the `RVS_SEED` environment variable
+///
+/// The same as `rvs_context_new` except the seed is read from `RVS_SEED` if it is set and not
+/// empty.  `default_seed` is used otherwise.  `RVS_SEED` may be decimal or `0x` prefixed
+/// hexadecimal.  E.g. `RVS_SEED=0x1234`.
+///
+/// # Errors
+///
+/// An error will be reported if `RVS_SEED` is malformed.  `default_seed` is used in that case.
+/// Errors are reported for the search path as with `rvs_context_new`.
+///
+/// A valid Context pointer will be returned and will need to be freed by the caller regardless of
+/// error or no error.
+#[no_mangle]
+pub extern "C" fn rvs_context_new_from_env(
+    search_path: *const c_char,
+    default_seed: u32,
+    error: *mut Error,
+) -> *mut Context {
+    let search_path = new_search_path(search_path, error);
+
+    let context = match Context::new_from_env(search_path.clone(), default_seed) {
+        Ok(context) => context,
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(ErrorKind::Io(e)) }
+            }
+
+            Context::new(search_path, default_seed)
+        }
     };
 
-    let seed = rvs::Seed::from_u32(seed);
+    Box::into_raw(Box::new(context))
+}
 
-    Box::into_raw(Box::new(Context::new(search_path, seed)))
+/// Allocates and returns a new Context with a randomly chosen seed
+///
+/// The same as `rvs_context_new` except the seed differs between calls.  Use `rvs_get_seed` to
+/// record the seed for reproducing the results with `rvs_context_new`.  Like `rvs_context_new`,
+/// the pointer returned is freed by a call to `rvs_transform` or `rvs_context_free`.
+#[no_mangle]
+pub extern "C" fn rvs_context_new_random(
+    search_path: *const c_char,
+    error: *mut Error,
+) -> *mut Context {
+    rvs_context_new(search_path, Context::random_seed(), error)
+}
+
+/// Returns the seed of a Context via the result pointer
+///
+/// The seed is the one given to `rvs_context_new` or chosen by `rvs_context_new_random`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_get_seed(context: *const Context, seed: *mut u32) {
+    assert!(!context.is_null());
+    assert!(!seed.is_null());
+
+    let context = unsafe { &*context };
+    unsafe { *seed = context.seed() };
+}
+
+/// Copies the 128-bit seed of a Context into a buffer
+///
+/// The 128-bit seed is the one generated from the 32-bit seed and used to seed the RNGs of all
+/// variables.  Copies 16 bytes.
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer untouched if `len` is less than 16
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_get_seed_bytes(context: *const Context, buf: *mut u8, len: usize) -> bool {
+    assert!(!context.is_null());
+    assert!(!buf.is_null());
+
+    let context = unsafe { &*context };
+    let bytes = context.seed_bytes();
+    if len < bytes.len() {
+        return false;
+    }
+
+    let buf = unsafe { slice::from_raw_parts_mut(buf, bytes.len()) };
+    buf.copy_from_slice(bytes);
+
+    true
+}
+
+/// Serializes a Context including the transformed expression trees of its variables
+///
+/// The serialized Context is restored by `rvs_context_deserialize` without parsing or transforming
+/// again.  Models transformed by the restored Context return the same values as models transformed
+/// by this Context.  See `Context::serialize` for what is serialized.  The bytes are copied into
+/// `buf` only if they fit.
+///
+/// Returns the length of the serialized Context in bytes.  A return value greater than `len`
+/// indicates that nothing was copied.  Call with a null `buf` and a `len` of 0 to get the length.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  On error, 0 is
+/// returned and the buffer is untouched.
+///
+/// * Transform errors
+///
+/// # Panics
+///
+/// * If the context pointer argument is null.  `buf` may be null if `len` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_context_serialize(
+    context: *const Context,
+    buf: *mut u8,
+    len: usize,
+    error: *mut Error,
+) -> usize {
+    assert!(!context.is_null());
+
+    let context = unsafe { &*context };
+    let bytes = match context.serialize() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(From::from(e)) }
+            }
+
+            return 0;
+        }
+    };
+
+    if !buf.is_null() && bytes.len() <= len {
+        let buf = unsafe { slice::from_raw_parts_mut(buf, bytes.len()) };
+        buf.copy_from_slice(&bytes);
+    }
+
+    bytes.len()
+}
+
+/// Allocates and returns a Context restored from the bytes returned by `rvs_context_serialize`
+///
+/// Like `rvs_context_new`, the pointer returned is owned by the caller and is freed by a call to
+/// `rvs_transform` or `rvs_context_free`.  Limits set by `rvs_set_limit` are not serialized.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  On error, null is
+/// returned.
+///
+/// * A transform error if the bytes were not returned by `rvs_context_serialize`, are corrupt, or
+///   are of a different format version
+/// * A parse error if the serialized AST is invalid
+///
+/// # Panics
+///
+/// * If the buffer pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_context_deserialize(
+    buf: *const u8,
+    len: usize,
+    error: *mut Error,
+) -> *mut Context {
+    assert!(!buf.is_null());
+
+    let bytes = unsafe { slice::from_raw_parts(buf, len) };
+    match Context::deserialize(bytes) {
+        Ok(context) => Box::into_raw(Box::new(context)),
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(From::from(e)) }
+            }
+
+            std::ptr::null_mut()
+        }
+    }
 }
 
 /// Parses a semicolon delimited string of Rvs statements and/or Rvs files.
@@ -123,22 +346,8 @@ pub extern "C" fn rvs_parse(context: *mut Context, s: *const c_char, error: *mut
             let is_file = entry.ends_with(".rvs");
 
             let parser_string = if is_file {
-                let path = Path::new(&entry);
-
-                let path = match context.find_file(path) {
-                    Ok(path) => path,
-                    Err(e) => {
-                        if !error.is_null() {
-                            unsafe {
-                                *error = Error::new(ErrorKind::Io(e));
-                            }
-                        }
-
-                        return;
-                    }
-                };
-
-                let mut file = match File::open(&path) {
+                match context.read_file(Path::new(&entry)) {
+                    Ok(contents) => contents,
                     Err(e) => {
                         if !error.is_null() {
                             unsafe {
@@ -148,21 +357,7 @@ pub extern "C" fn rvs_parse(context: *mut Context, s: *const c_char, error: *mut
 
                         return;
                     }
-                    Ok(file) => file,
-                };
-
-                let mut contents = String::new();
-                if let Err(e) = file.read_to_string(&mut contents) {
-                    if !error.is_null() {
-                        unsafe {
-                            *error = Error::new(ErrorKind::Io(e));
-                        }
-                    }
-
-                    return;
-                };
-
-                contents
+                }
             } else {
                 entry.to_owned() + ";"
             };
@@ -176,164 +371,2113 @@ pub extern "C" fn rvs_parse(context: *mut Context, s: *const c_char, error: *mut
     }
 }
 
-/// Creates a new Model
+/// Parses a semicolon delimited string of Rvs statements and/or Rvs files and returns the number
+/// of definitions via the count pointer
 ///
-/// The pointer returned is owned by the caller and is freed by a call to `rvs_model_free`.
+/// The same as `rvs_parse` except the number of variables and constants defined is returned.
+/// Redefinitions of existing variables count.  Definitions in imported files and groups count.
+/// E.g. the count is 0 for an empty or comment-only string.
+///
+/// # Errors
+///
+/// Errors are reported as with `rvs_parse`.  Statements that fail to parse do not count.
+///
+/// # Panics
+///
+/// If any pointer arguments other than error are null.
 #[no_mangle]
-pub extern "C" fn rvs_model_new() -> *mut rvs::Model {
-    Box::into_raw(Box::new(rvs::Model::new()))
+pub extern "C" fn rvs_parse_ex(
+    context: *mut Context,
+    s: *const c_char,
+    error: *mut Error,
+    defined_count: *mut usize,
+) {
+    assert!(!context.is_null());
+    assert!(!defined_count.is_null());
+
+    let before = unsafe { &*context }.definition_count();
+    rvs_parse(context, s, error);
+    let after = unsafe { &*context }.definition_count();
+
+    unsafe { *defined_count = after - before };
 }
 
-/// Transforms an AST into an object model
+/// Parses a single Rvs file
 ///
-/// # Arguments
-///
-/// * context - (required) A Context pointer.  Created by `rvs_context_new`.  Freed by `rvs_transform`.
-/// * model - (required) A Model pointer.  Created by `rvs_model_new`.  Freed by `rvs_model_free`
-/// * error - (optional) An Error pointer.  Used to report any errors that may occur.
+/// The file is found using the search path of the context.  Unlike `rvs_parse`, the path is
+/// always treated as a file regardless of extension.
 ///
 /// # Errors
 ///
 /// Errors are reported via the optional error struct pointer if available.  The following errors
 /// types are possible:
 ///
-/// * Transform errors
+/// * IO errors E.g. the file does not exist or cannot be read
+/// * Parsing errors
+///
+/// # Panics
+///
+/// If any pointer arguments are null.
 #[no_mangle]
-pub extern "C" fn rvs_transform(context: *mut Context, model: *mut rvs::Model, error: *mut Error) {
+pub extern "C" fn rvs_parse_file(context: *mut Context, path: *const c_char, error: *mut Error) {
     assert!(!context.is_null());
-    assert!(!model.is_null());
+    assert!(!path.is_null());
 
-    let context_deref = unsafe { &mut *context };
-    let mut model = unsafe { &mut *model };
+    let c_str = unsafe { CStr::from_ptr(path) };
+    let r_str = c_str.to_str().unwrap();
+    let context = unsafe { &mut *context };
 
-    if let Err(e) = context_deref.transform(&mut model) {
+    let result = match context.read_file(Path::new(r_str)) {
+        Ok(contents) => context.parse(&contents).map_err(ErrorKind::from),
+        Err(e) => Err(ErrorKind::Io(e)),
+    };
+
+    if let Err(e) = result {
         if !error.is_null() {
-            unsafe { *error = Error::new(From::from(e)) }
+            unsafe { *error = Error::new(e) }
         }
     }
-
-    unsafe { Box::from_raw(context) };
 }
 
-/// Frees a Context previously allocated by `rvs_context_new`
+/// Sets the maximum number of times an expression re-samples a sub-expression
 ///
-/// This is for error scenarios only.  In a non-error scenario, `rvs_transform` is used to free the
-/// Context.
+/// Applies to all re-sampling expressions (e.g. `except`) in the models transformed from this
+/// context.  Defaults to 10000.  An expression that exceeds the maximum returns a fallback value.
+/// See `rvs_next_checked`.
+///
+/// # Errors
+///
+/// * Returns false and leaves the maximum unchanged if `max_resample_attempts` is 0
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
 #[no_mangle]
-pub extern "C" fn rvs_context_free(context: *mut Context) {
+pub extern "C" fn rvs_set_max_resample_attempts(
+    context: *mut Context,
+    max_resample_attempts: u32,
+) -> bool {
     assert!(!context.is_null());
-    unsafe {
-        Box::from_raw(context);
-    }
-}
 
-/// Frees a Model previously allocated by `rvs_transform`
-#[no_mangle]
-pub extern "C" fn rvs_model_free(model: *mut rvs::Model) {
-    assert!(!model.is_null());
-    unsafe {
-        Box::from_raw(model);
+    if max_resample_attempts == 0 {
+        return false;
     }
+
+    let context = unsafe { &mut *context };
+    context.set_max_resample_attempts(max_resample_attempts);
+
+    true
 }
 
-/// Returns the handle of a variable
-///
-/// The callee owns the handle.  The handle is valid until `rvs_model_free()` is called.
+/// Enables or disables aligned doneness for binary operations
 ///
-/// # Errors
-///
-/// * Returns 0 if variable does not exist
+/// When enabled, binary operations in the models transformed from this context are done only
+/// when both operands indicate done on the same call.  When disabled (the default), done latches
+/// once both operands have indicated done at least once.
 ///
 /// # Panics
 ///
 /// * If any pointer arguments are null
 #[no_mangle]
-pub extern "C" fn rvs_get(model: *mut rvs::Model, name: *const c_char) -> SequenceHandleRaw {
-    assert!(!model.is_null());
-    assert!(!name.is_null());
-
-    let name_cstr = unsafe { CStr::from_ptr(name) };
-    let name_rstr = name_cstr.to_str().unwrap();
+pub extern "C" fn rvs_set_aligned_binary_done(context: *mut Context, enabled: bool) {
+    assert!(!context.is_null());
 
-    let model = unsafe { &mut *model };
-    if let Some(index) = model.get_variable_index(name_rstr) {
-        SequenceHandle::from(index).to_raw()
+    let context = unsafe { &mut *context };
+    context.set_binary_done_mode(if enabled {
+        rvs::BinaryDoneMode::Aligned
     } else {
-        0
-    }
+        rvs::BinaryDoneMode::Latched
+    });
 }
 
-/// Returns the next value of a variable via the result pointer
-///
-/// # Errors
+/// Sets a mask applied to the values of all variables
 ///
-/// Returns 0 if handle is invalid.
+/// Applies to all variables in the models transformed from this context.  E.g. a mask of `0xfff`
+/// limits all values to 12 bits.  Defaults to `0xffffffff` (no masking).
 ///
 /// # Panics
 ///
 /// * If any pointer arguments are null
-/// * If handle doesn't exist
 #[no_mangle]
-pub extern "C" fn rvs_next(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
-    assert!(!model.is_null());
+pub extern "C" fn rvs_set_output_mask(context: *mut Context, output_mask: u32) {
+    assert!(!context.is_null());
 
-    let model = unsafe { &mut *model };
-    let handle = SequenceHandle(handle);
-    match model.get_variable_by_index(handle.into()) {
-        Some(variable) => variable.borrow_mut().next(),
-        None => 0,
-    }
+    let context = unsafe { &mut *context };
+    context.set_output_mask(output_mask);
 }
 
-/// Returns the previous value of a variable
+/// Enables or disables counting of the raw 32-bit words each variable draws from its RNG
 ///
-/// # Errors
-///
-/// * Returns 0 if handle is invalid
-/// * Returns 0 if `rvs_next` has not been called
+/// Applies to all variables in the models transformed from this context.  Disabled by default.
+/// See `rvs_rng_draws`.
 ///
 /// # Panics
 ///
 /// * If any pointer arguments are null
-/// * If handle doesn't exist
 #[no_mangle]
-pub extern "C" fn rvs_prev(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
-    assert!(!model.is_null());
-
-    let model = unsafe { &mut *model };
-    let handle = SequenceHandle(handle);
+pub extern "C" fn rvs_set_rng_draw_counting(context: *mut Context, enabled: bool) {
+    assert!(!context.is_null());
 
-    match model.get_variable_by_index(handle.into()) {
-        Some(variable) => variable.borrow().prev(),
-        None => 0,
-    }
+    let context = unsafe { &mut *context };
+    context.set_rng_draw_counting(enabled);
 }
 
-/// Returns the done value of a variable via the result pointer
+/// Enables or disables lazy building of variables
 ///
-/// # Errors
-///
-/// * Returns false if handle is invalid
-/// * Returns false if `rvs_next` has not been called
+/// When enabled, the expression of each variable is built by the first `rvs_get` of the variable
+/// instead of by `rvs_transform`.  Errors in variables that are never retrieved are not reported.
+/// Other functions that take a handle (e.g. from `rvs_find_prefix`) build the variable on first
+/// use and treat a variable that fails to build as an invalid handle.  `rvs_next_checked`
+/// reports the build error.  Disabled by default.
 ///
 /// # Panics
 ///
 /// * If any pointer arguments are null
-/// * If handle doesn't exist
 #[no_mangle]
-pub extern "C" fn rvs_done(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
-    assert!(!model.is_null());
-
-    let model = unsafe { &mut *model };
-    let handle = SequenceHandle(handle);
+pub extern "C" fn rvs_set_lazy(context: *mut Context, enabled: bool) {
+    assert!(!context.is_null());
 
-    match model.get_variable_by_index(handle.into()) {
-        Some(variable) => variable.borrow().done(),
-        None => false,
-    }
+    let context = unsafe { &mut *context };
+    context.set_lazy(enabled);
 }
 
-#[no_mangle]
+/// Creates a new Model
+///
+/// The pointer returned is owned by the caller and is freed by a call to `rvs_model_free`.
+#[no_mangle]
+pub extern "C" fn rvs_model_new() -> *mut rvs::Model {
+    Box::into_raw(Box::new(rvs::Model::new()))
+}
+
+/// Transforms an AST into an object model
+///
+/// # Arguments
+///
+/// * context - (required) A Context pointer.  Created by `rvs_context_new`.  Freed by `rvs_transform`.
+/// * model - (required) A Model pointer.  Created by `rvs_model_new`.  Freed by `rvs_model_free`
+/// * error - (optional) An Error pointer.  Used to report any errors that may occur.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  The following errors
+/// types are possible:
+///
+/// * Transform errors
+/// * A transform error if the model has more than 16,777,215 variables.  Handles can't address
+///   more.
+#[no_mangle]
+pub extern "C" fn rvs_transform(context: *mut Context, model: *mut rvs::Model, error: *mut Error) {
+    assert!(!context.is_null());
+    assert!(!model.is_null());
+
+    let context_deref = unsafe { &mut *context };
+    let model = unsafe { &mut *model };
+
+    let result = context_deref.transform(model).and_then(|_| check_handle_limit(model));
+    if let Err(e) = result {
+        if !error.is_null() {
+            unsafe { *error = Error::new(From::from(e)) }
+        }
+    }
+
+    unsafe { drop(Box::from_raw(context)) };
+}
+
+/// Parses Rvs statements and/or files and transforms them into a Model in a single step
+///
+/// Equivalent to `rvs_parse` followed by `rvs_transform`.  Like `rvs_transform`, the Context is
+/// freed in all cases.  The transform step is skipped if parsing fails.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  The failing step is
+/// indicated by the error code:
+///
+/// * `RVS_ERROR_IO` or `RVS_ERROR_PARSE` if parsing failed
+/// * `RVS_ERROR_TRANSFORM` if transforming failed
+///
+/// # Panics
+///
+/// If any pointer arguments are null.
+#[no_mangle]
+pub extern "C" fn rvs_parse_and_transform(
+    context: *mut Context,
+    s: *const c_char,
+    model: *mut rvs::Model,
+    error: *mut Error,
+) {
+    assert!(!context.is_null());
+    assert!(!s.is_null());
+    assert!(!model.is_null());
+
+    let mut parse_error = Error::new(ErrorKind::None);
+    rvs_parse(context, s, &mut parse_error);
+
+    if parse_error.is_err() {
+        if !error.is_null() {
+            unsafe { *error = parse_error }
+        }
+
+        rvs_context_free(context);
+    } else {
+        rvs_transform(context, model, error);
+    }
+}
+
+/// Parses and transforms Rvs statements and/or files and returns the first values of every variable
+/// as JSON
+///
+/// Composes `rvs_parse_and_transform` with `n` calls to `rvs_next` per variable.  Like
+/// `rvs_transform`, the Context is freed in all cases.  The model is discarded.  Values are JSON
+/// numbers.  See `rvs::Model::sample_to_json` for the format of the JSON.  The JSON is copied into
+/// `buf` as a null terminated string truncated to `len - 1` bytes.
+///
+/// Returns the length of the JSON in bytes excluding the null terminator.  A return value greater
+/// than or equal to `len` indicates truncation.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  On error, 0 is
+/// returned and the buffer is untouched.
+///
+/// * `RVS_ERROR_IO` or `RVS_ERROR_PARSE` if parsing failed
+/// * `RVS_ERROR_TRANSFORM` if transforming failed
+///
+/// # Panics
+///
+/// If the context or source pointer arguments are null.  `buf` may be null if `len` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_preview(
+    context: *mut Context,
+    s: *const c_char,
+    n: usize,
+    buf: *mut c_char,
+    len: usize,
+    error: *mut Error,
+) -> usize {
+    rvs_preview_ex(context, s, n, false, buf, len, error)
+}
+
+/// Equivalent to `rvs_preview` but optionally writes values as hexadecimal JSON strings
+///
+/// Values are JSON strings E.g. `"0xff"` if `hex_strings` is true and JSON numbers otherwise.
+///
+/// # Errors
+///
+/// See `rvs_preview`.
+///
+/// # Panics
+///
+/// See `rvs_preview`.
+#[no_mangle]
+pub extern "C" fn rvs_preview_ex(
+    context: *mut Context,
+    s: *const c_char,
+    n: usize,
+    hex_strings: bool,
+    buf: *mut c_char,
+    len: usize,
+    error: *mut Error,
+) -> usize {
+    assert!(!context.is_null());
+    assert!(!s.is_null());
+
+    let model = rvs_model_new();
+    let mut preview_error = Error::new(ErrorKind::None);
+    rvs_parse_and_transform(context, s, model, &mut preview_error);
+
+    let model = unsafe { Box::from_raw(model) };
+    if preview_error.is_err() {
+        if !error.is_null() {
+            unsafe { *error = preview_error }
+        }
+
+        return 0;
+    }
+
+    let format = if hex_strings {
+        rvs::JsonFormat::HexString
+    } else {
+        rvs::JsonFormat::Number
+    };
+
+    match model.sample_to_json(n, format) {
+        Ok(json) => buffer::write_str(&json, buf, len),
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(From::from(e)) }
+            }
+
+            0
+        }
+    }
+}
+
+/// Parses a string of Rvs statements and returns the AST as a debug string
+///
+/// The AST is rendered like the `{:?}` (`Debug`) output of `rvs::Parser::ast` E.g.
+/// `[Variable("a", Number(1), Span { line: 1, column: 1 }, [])]` for `a = 1;`.  The string is
+/// copied into `buf` as a null terminated string truncated to `len - 1` bytes.  Unlike
+/// `rvs_parse`, `s` is always treated as Rvs statements.  `import`s are found using
+/// `search_path`, a colon separated list of paths.
+///
+/// Returns the length of the string in bytes excluding the null terminator.  A return value
+/// greater than or equal to `len` indicates truncation.
+///
+/// # Errors
+///
+/// Errors are reported via the optional error struct pointer if available.  On error, 0 is
+/// returned and the buffer is untouched.
+///
+/// * `RVS_ERROR_IO` if a path in the search path does not exist
+/// * `RVS_ERROR_PARSE` if parsing failed
+///
+/// # Panics
+///
+/// If the source or search path pointer arguments are null.  `buf` may be null if `len` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_parse_ast_string(
+    s: *const c_char,
+    search_path: *const c_char,
+    buf: *mut c_char,
+    len: usize,
+    error: *mut Error,
+) -> usize {
+    assert!(!s.is_null());
+    assert!(!search_path.is_null());
+
+    let s = unsafe { CStr::from_ptr(s) }.to_str().unwrap();
+    let search_path = unsafe { CStr::from_ptr(search_path) }.to_str().unwrap();
+
+    let result = rvs::SearchPath::from_string(search_path)
+        .map_err(ErrorKind::Io)
+        .and_then(|search_path| {
+            let mut parser = rvs::Parser::new(&search_path);
+            parser.parse(s).map_err(ErrorKind::from)?;
+
+            Ok(format!("{:?}", parser.ast()))
+        });
+
+    match result {
+        Ok(ast) => buffer::write_str(&ast, buf, len),
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(e) }
+            }
+
+            0
+        }
+    }
+}
+
+/// Frees a Context previously allocated by `rvs_context_new`
+///
+/// This is for error scenarios only.  In a non-error scenario, `rvs_transform` is used to free the
+/// Context.
+#[no_mangle]
+pub extern "C" fn rvs_context_free(context: *mut Context) {
+    assert!(!context.is_null());
+    unsafe {
+        drop(Box::from_raw(context));
+    }
+}
+
+/// Frees a Model previously allocated by `rvs_transform`
+#[no_mangle]
+pub extern "C" fn rvs_model_free(model: *mut rvs::Model) {
+    assert!(!model.is_null());
+    unsafe {
+        drop(Box::from_raw(model));
+    }
+}
+
+/// Creates a deep copy of a Model
+///
+/// The copy has independent variable state and RNG streams.  Variable handles are valid for both
+/// the original and the copy.
+///
+/// The pointer returned is owned by the caller and is freed by a call to `rvs_model_free`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_model_clone(model: *const rvs::Model) -> *mut rvs::Model {
+    assert!(!model.is_null());
+
+    let model = unsafe { &*model };
+
+    Box::into_raw(Box::new(model.fork()))
+}
+
+/// Returns the handle of a variable
+///
+/// The callee owns the handle.  The handle is valid until `rvs_model_free()` is called.  The handle
+/// is only valid for `model` and copies of it made by `rvs_model_clone()`.  Using the handle with a
+/// model transformed from a different context is treated as an invalid handle.
+///
+/// # Errors
+///
+/// * Returns 0 if variable does not exist
+/// * Returns 0 if the variable fails to build.  See `rvs_set_lazy`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_get(model: *mut rvs::Model, name: *const c_char) -> SequenceHandleRaw {
+    assert!(!model.is_null());
+    assert!(!name.is_null());
+
+    let name_cstr = unsafe { CStr::from_ptr(name) };
+    let name_rstr = name_cstr.to_str().unwrap();
+
+    let model = unsafe { &mut *model };
+    match model.get_variable_index(name_rstr) {
+        Some(index) if model.build(index).is_ok() => SequenceHandle::new(model, index).to_raw(),
+        _ => 0,
+    }
+}
+
+/// Returns the handle of a variable and sets error on failure
+///
+/// Like `rvs_get` but reports why the variable is unavailable.
+///
+/// # Errors
+///
+/// * Returns 0 and sets error to `RVS_ERROR_UNDEFINED` if the variable does not exist or name is
+///   not valid UTF-8
+/// * Returns 0 and sets error to `RVS_ERROR_NOT_TRANSFORMED` if the model has not been
+///   transformed
+/// * Returns 0 and sets error to `RVS_ERROR_TRANSFORM` if the variable fails to build.  See
+///   `rvs_set_lazy`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null.  `error` may be null.
+#[no_mangle]
+pub extern "C" fn rvs_get_checked(
+    model: *mut rvs::Model,
+    name: *const c_char,
+    error: *mut Error,
+) -> SequenceHandleRaw {
+    assert!(!model.is_null());
+    assert!(!name.is_null());
+
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+
+    let model = unsafe { &mut *model };
+    let result = model
+        .get_result(&name)
+        .map(|_| model.get_variable_index(&name).unwrap());
+
+    match result {
+        Ok(index) => SequenceHandle::new(model, index).to_raw(),
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(ErrorKind::from(e)) }
+            }
+            0
+        }
+    }
+}
+
+/// Finds the handles of all variables whose names start with a prefix
+///
+/// At most `cap` handles are copied into `handles` in the order the variables were defined.
+/// `len` is set to the total number of matching variables which may exceed `cap`.  Variables are
+/// not built until their handles are used.  See `rvs_set_lazy`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null.  `handles` may be null if `cap` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_find_prefix(
+    model: *const rvs::Model,
+    prefix: *const c_char,
+    handles: *mut SequenceHandleRaw,
+    cap: usize,
+    len: *mut usize,
+) {
+    assert!(!model.is_null());
+    assert!(!prefix.is_null());
+    assert!(cap == 0 || !handles.is_null());
+    assert!(!len.is_null());
+
+    let prefix_cstr = unsafe { CStr::from_ptr(prefix) };
+    let prefix_rstr = prefix_cstr.to_str().unwrap();
+
+    let model = unsafe { &*model };
+    let mut count = 0;
+    for index in model.get_variable_indexes_by_prefix(prefix_rstr) {
+        if count < cap {
+            unsafe {
+                *handles.add(count) = SequenceHandle::new(model, index).to_raw();
+            }
+        }
+        count += 1;
+    }
+
+    unsafe {
+        *len = count;
+    }
+}
+
+/// Returns the next value of a variable via the result pointer
+///
+/// # Errors
+///
+/// Returns 0 if handle is invalid.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+/// * If handle doesn't exist
+#[no_mangle]
+pub extern "C" fn rvs_next(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match handle.variable(model) {
+        Some(variable) => variable.borrow_mut().next(),
+        None => 0,
+    }
+}
+
+/// Sets the maximum number of re-samples per call to `rvs_next_checked`
+///
+/// Re-samples are counted across all re-sampling expressions (e.g. `except`) evaluated by a
+/// single call.  The budget can be changed at any time.  A `max_iterations` of 0 removes the
+/// budget (the default).  Does not apply to `rvs_next`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_eval_budget(model: *mut rvs::Model, max_iterations: u32) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    model.set_eval_budget(if max_iterations == 0 { None } else { Some(max_iterations) });
+}
+
+/// Returns the next value of a variable via the value pointer while respecting the evaluation
+/// budget set by `rvs_set_eval_budget` and the resample limit set by
+/// `rvs_set_max_resample_attempts`
+///
+/// # Errors
+///
+/// * Returns false and leaves value untouched if handle is invalid
+/// * Returns false, leaves value untouched, and sets error to `RVS_ERROR_BUDGET` if the budget
+///   is exceeded.  The variable remains usable.
+/// * Returns false, leaves value untouched, and sets error to `RVS_ERROR_CONSTRAINT` if a
+///   resample limit is exceeded.  The variable remains usable.
+/// * Returns false, leaves value untouched, and sets error if the variable was deferred by a lazy
+///   transform and fails to build.  See `rvs_set_lazy`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null.  `error` may be null.
+#[no_mangle]
+pub extern "C" fn rvs_next_checked(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    value: *mut u32,
+    error: *mut Error,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!value.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    if let Some(Err(e)) = handle.index(model).map(|index| model.build(index)) {
+        if !error.is_null() {
+            unsafe { *error = Error::new(ErrorKind::from(e)) }
+        }
+        return false;
+    }
+    let variable = match handle.variable(model) {
+        Some(variable) => variable,
+        None => return false,
+    };
+
+    let mut variable = variable.borrow_mut();
+    let result = match model.eval_budget() {
+        Some(max_iterations) => variable.next_with_budget(max_iterations),
+        None => variable.next_checked(),
+    };
+
+    match result {
+        Ok(result) => {
+            unsafe { *value = result };
+            true
+        }
+        Err(e) => {
+            if !error.is_null() {
+                unsafe { *error = Error::new(ErrorKind::from(e)) }
+            }
+            false
+        }
+    }
+}
+
+/// Returns the next value of a variable using a buffer of entropy as the randomness source
+///
+/// The entropy is used for this call only.  The RNG of the variable is not advanced.  The same
+/// entropy yields the same value for a variable in the same state.  Any number of entropy bytes
+/// is accepted.
+///
+/// # Errors
+///
+/// Returns 0 if handle is invalid.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null.  `entropy` may be null if `len` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_next_with_entropy(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    entropy: *const u8,
+    len: usize,
+) -> u32 {
+    assert!(!model.is_null());
+    assert!(len == 0 || !entropy.is_null());
+
+    let entropy = if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(entropy, len) }
+    };
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match handle.variable(model) {
+        Some(variable) => variable.borrow_mut().next_with_entropy(entropy),
+        None => 0,
+    }
+}
+
+/// Advances a variable with references to another variable overridden and returns the new value
+/// via the result pointer
+///
+/// References to the variable `name` (at any depth) return `value` for this call only.  The
+/// overridden variable is not advanced.  Has no effect if the variable does not reference
+/// `name`.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid or name is not valid
+///   UTF-8
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_next_with_override(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    name: *const c_char,
+    value: u32,
+    result: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!name.is_null());
+    assert!(!result.is_null());
+
+    let name = match unsafe { CStr::from_ptr(name) }.to_str() {
+        Ok(name) => name,
+        Err(_) => return false,
+    };
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match handle.variable(model) {
+        Some(variable) => {
+            unsafe {
+                *result = variable.borrow_mut().next_with_override(name, value);
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Advances a variable and returns the new value, the new done value, and the prior value via
+/// the result pointers
+///
+/// Equivalent to `rvs_prev` followed by `rvs_next` and `rvs_done`.  The prior value is the value
+/// returned by `rvs_prev` before advancing.
+///
+/// # Errors
+///
+/// * Returns false and sets all results to 0 (false for done) if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_next_ex(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    value: *mut u32,
+    done: *mut bool,
+    prev: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!value.is_null());
+    assert!(!done.is_null());
+    assert!(!prev.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let result = handle.variable(model).map(|variable| {
+        let mut variable = variable.borrow_mut();
+        let prev = variable.prev();
+        let value = variable.next();
+
+        (value, variable.done(), prev)
+    });
+
+    let (found, (result_value, result_done, result_prev)) = match result {
+        Some(result) => (true, result),
+        None => (false, (0, false, 0)),
+    };
+
+    unsafe {
+        *value = result_value;
+        *done = result_done;
+        *prev = result_prev;
+    }
+
+    found
+}
+
+/// Advances a variable until it returns `target`
+///
+/// The number of calls to `rvs_next` needed, including the one that returned `target`, is
+/// returned via the count pointer.
+///
+/// # Errors
+///
+/// * Returns false and leaves the count pointer untouched if handle is invalid
+/// * Returns false and leaves the count pointer untouched if `target` was not returned within
+///   `cap` calls
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_next_until(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    target: u32,
+    cap: usize,
+    count: *mut usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!count.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let result = match handle.variable(model) {
+        Some(variable) => variable.borrow_mut().next_until(target, cap),
+        None => None,
+    };
+
+    match result {
+        Some(result) => {
+            unsafe { *count = result };
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Advances a variable by `n` values without returning them
+///
+/// Equivalent to `n` calls to `rvs_next` except the skipped values are not added to the
+/// statistics.  `rvs_prev`, `rvs_done`, and `rvs_cycle_count` reflect the last skipped value.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_skip(model: *mut rvs::Model, handle: SequenceHandleRaw, n: usize) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            variable.borrow_mut().skip(n);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the previous value of a variable
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+/// * Returns 0 if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+/// * If handle doesn't exist
+#[no_mangle]
+pub extern "C" fn rvs_prev(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => variable.borrow().prev(),
+        None => 0,
+    }
+}
+
+/// Returns the value returned by the most recent call to `rvs_next` via the value pointer
+///
+/// Unlike `rvs_prev`, distinguishes a value of 0 from no value.
+///
+/// # Errors
+///
+/// * Returns false and leaves value untouched if handle is invalid
+/// * Returns false and leaves value untouched if `rvs_next` has not been called since the
+///   variable was created or reset
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_current(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    value: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!value.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model).and_then(|variable| variable.borrow().current()) {
+        Some(current) => {
+            unsafe { *value = current };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the done value of a variable via the result pointer
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+/// * If handle doesn't exist
+#[no_mangle]
+pub extern "C" fn rvs_done(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => variable.borrow().done(),
+        None => false,
+    }
+}
+
+/// Returns whether the most recent `rvs_next` changed the done value of a variable from false to
+/// true via the result pointer
+///
+/// The result is true once per cycle on the `rvs_next` that completes the cycle.  The done value
+/// is considered false before the first `rvs_next` so a variable that is done after every
+/// `rvs_next` (e.g. a constant) has a result of true on the first `rvs_next` only.  The result is
+/// false if `rvs_next` has not been called.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_done_edge(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    edge: *mut bool,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!edge.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            unsafe {
+                *edge = variable.borrow().done_edge();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the index of the sub-expression selected by the most recent `rvs_next` via the
+/// result pointer
+///
+/// Only available for variables defined as a sample E.g. `r{...}` or `{...}`.  Sub-expressions
+/// are indexed in the order they were defined starting at 0.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+/// * Returns false and leaves the result untouched if the variable is not a sample
+/// * Returns false and leaves the result untouched if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_last_index(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    index: *mut usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!index.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let last_selected = match handle.variable(model) {
+        Some(variable) => variable.borrow().last_selected(),
+        None => None,
+    };
+
+    match last_selected {
+        Some(last_selected) => {
+            unsafe {
+                *index = last_selected;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the position within the current cycle of the value returned by the most recent
+/// `rvs_next` via the result pointer
+///
+/// Only available for variables defined as a cycle E.g. `Pattern(...)` or `Sequence(...)`.
+/// Positions start at 0 and restart at 0 on each new cycle.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+/// * Returns false and leaves the result untouched if the variable is not a cycle
+/// * Returns false and leaves the result untouched if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_cycle_position(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    position: *mut usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!position.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let cycle_position = match handle.variable(model) {
+        Some(variable) => variable.borrow().cycle_position(),
+        None => None,
+    };
+
+    match cycle_position {
+        Some(cycle_position) => {
+            unsafe {
+                *position = cycle_position;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the number of cycles a variable has completed via the result pointer
+///
+/// A cycle completes each time `rvs_done` changes from false to true except on the first
+/// `rvs_next`.  Variables that are done after every `rvs_next` (e.g. `[0, 3]`) complete no cycles.
+/// Cleared by `rvs_reset`.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_cycle_count(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    count: *mut u64,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!count.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            unsafe {
+                *count = variable.borrow().cycle_count();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Enables or disables a variable
+///
+/// While disabled, `rvs_next` and `rvs_prev` return the disabled value (see
+/// `rvs_set_disabled_value`) without advancing the variable or consuming random numbers.
+/// References to the variable from other variables see the disabled value too.  Variables are
+/// enabled by default.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_enabled(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    enabled: bool,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            variable.borrow_mut().set_enabled(enabled);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sets the value returned by `rvs_next` and `rvs_prev` while a variable is disabled
+///
+/// Until set, a disabled variable holds its most recent value.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_disabled_value(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    value: u32,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            variable.borrow_mut().set_disabled_value(Some(value));
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Freezes a variable until `rvs_unfreeze`
+///
+/// While frozen, `rvs_next` returns the value returned by `rvs_prev` without advancing the
+/// variable or consuming random numbers.  References to the variable from other variables see the
+/// frozen value too.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_freeze(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            variable.borrow_mut().freeze();
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Resumes a variable frozen by `rvs_freeze`
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_unfreeze(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            variable.borrow_mut().unfreeze();
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Resets a variable to its state before the first call to `rvs_next`
+///
+/// Subsequent calls to `rvs_next` replay from the start of the variable's expression.
+/// `rvs_prev` returns 0 until the next call to `rvs_next`.  The random number generator of the
+/// variable is not reset.  See `rvs_reset_rng`.  Random number generators owned by the expression
+/// (i.e. for independent selection) are restored.
+///
+/// # Errors
+///
+/// * Does nothing if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_reset(model: *mut rvs::Model, handle: SequenceHandleRaw) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    if let Some(variable) = handle.variable(model) {
+        variable.borrow_mut().reset();
+    }
+}
+
+/// Resets the random number generator of a variable to its state before the first call to
+/// `rvs_next`
+///
+/// Subsequent calls to `rvs_next` draw the same random numbers as a newly transformed variable.
+/// Unlike `rvs_reset`, the position within the variable's expression and the values returned by
+/// `rvs_prev` and `rvs_done` are not reset.  Does nothing for variables that do not use the random
+/// number generator.
+///
+/// # Errors
+///
+/// * Does nothing if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_reset_rng(model: *mut rvs::Model, handle: SequenceHandleRaw) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    if let Some(variable) = handle.variable(model) {
+        variable.borrow_mut().reset_rng();
+    }
+}
+
+/// Resets all variables of a model
+///
+/// See `rvs_reset`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_reset_all(model: *mut rvs::Model) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    model.reset_all();
+}
+
+/// Saves the evaluation state of all variables of a model under a name
+///
+/// An existing checkpoint of the same name is replaced.  See `rvs_restore`.
+///
+/// # Errors
+///
+/// * Returns false if `name` is not valid UTF-8
+/// * Returns false if a variable fails to build.  See `rvs_set_lazy`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_checkpoint(model: *mut rvs::Model, name: *const c_char) -> bool {
+    assert!(!model.is_null());
+    assert!(!name.is_null());
+
+    let name = unsafe { CStr::from_ptr(name) };
+    let name = match name.to_str() {
+        Ok(name) => name,
+        Err(_) => return false,
+    };
+
+    let model = unsafe { &mut *model };
+    model.checkpoint(name).is_ok()
+}
+
+/// Restores the evaluation state of all variables of a model saved by `rvs_checkpoint`
+///
+/// Subsequent calls to `rvs_next` replay the values returned after the checkpoint.  The
+/// checkpoint is kept so it can be restored again.
+///
+/// # Errors
+///
+/// * Returns false and leaves the model untouched if there is no checkpoint named `name`
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_restore(model: *mut rvs::Model, name: *const c_char) -> bool {
+    assert!(!model.is_null());
+    assert!(!name.is_null());
+
+    let name = unsafe { CStr::from_ptr(name) };
+    let name = match name.to_str() {
+        Ok(name) => name,
+        Err(_) => return false,
+    };
+
+    let model = unsafe { &mut *model };
+    model.restore(name)
+}
+
+/// Enables or disables collection of statistics for a variable
+///
+/// # Errors
+///
+/// * Does nothing if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_stats_enabled(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    enabled: bool,
+) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    if let Some(variable) = handle.variable(model) {
+        variable.borrow_mut().set_stats_enabled(enabled);
+    }
+}
+
+/// Returns the cumulative number of raw 32-bit words a variable has drawn from its RNG via the
+/// result pointer
+///
+/// Draws are only counted after being enabled by `rvs_set_rng_draw_counting`.  Draws made by
+/// referenced variables are counted against the referenced variables.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+/// * Returns false and leaves the result untouched if counting is disabled
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_rng_draws(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    draws: *mut u64,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!draws.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let rng_draws = match handle.variable(model) {
+        Some(variable) => variable.borrow().rng_draws(),
+        None => None,
+    };
+
+    match rng_draws {
+        Some(rng_draws) => {
+            unsafe {
+                *draws = rng_draws;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the minimum and maximum values a variable can return via the result pointers
+///
+/// Bounds are determined statically and are conservative.  E.g. `[0, 10] + [0, 5]` has bounds of
+/// 0 and 15.  See `Expr::value_bounds` for the expressions supported.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result pointers untouched if handle is invalid
+/// * Returns false and leaves the result pointers untouched if the bounds can't be determined
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_value_bounds(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    min: *mut u32,
+    max: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!min.is_null());
+    assert!(!max.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let bounds = match handle.variable(model) {
+        Some(variable) => variable.borrow().value_bounds(),
+        None => None,
+    };
+
+    match bounds {
+        Some(bounds) => {
+            unsafe {
+                *min = bounds.0;
+                *max = bounds.1;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns whether a variable returns the same values regardless of seed via the result pointer
+///
+/// Computed structurally.  Variables that use the RNG (e.g. ranges and samples) directly or via a
+/// referenced variable are not deterministic.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result untouched if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_is_deterministic(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    result: *mut bool,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!result.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            unsafe {
+                *result = variable.borrow().is_deterministic();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the bounds of a variable defined as a range E.g. `a = [0, 10];` via the result
+/// pointers
+///
+/// # Errors
+///
+/// * Returns false and leaves the result pointers untouched if handle is invalid
+/// * Returns false and leaves the result pointers untouched if the variable is not a range
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_get_range(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    low: *mut u32,
+    high: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!low.is_null());
+    assert!(!high.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let bounds = match handle.variable(model) {
+        Some(variable) => variable.borrow().range_bounds(),
+        None => None,
+    };
+
+    match bounds {
+        Some((l, r)) => {
+            unsafe {
+                *low = l;
+                *high = r;
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copies the weights of a variable defined as a weighted sample E.g. `a = {3: 0, 1: 1};`
+///
+/// At most `cap` weights are copied into `weights` in the order of the samples.  `len` is set to
+/// the total number of weights which may exceed `cap`.  Weights are not normalized.  Probability
+/// weights are scaled to the smallest equivalent integers (e.g. `r{0.5: 0, 0.25: 1, 0.25: 2}` has
+/// weights 2, 1, and 1).  See `Expr::weights`.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result pointers untouched if handle is invalid
+/// * Returns false and leaves the result pointers untouched if the variable is not a weighted
+///   sample
+///
+/// # Panics
+///
+/// * If any pointer arguments are null.  `weights` may be null if `cap` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_weights(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    weights: *mut u32,
+    cap: usize,
+    len: *mut usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(cap == 0 || !weights.is_null());
+    assert!(!len.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let variable = match handle.variable(model) {
+        Some(variable) => variable.borrow(),
+        None => return false,
+    };
+
+    match variable.weights() {
+        Some(variable_weights) => {
+            for (i, weight) in variable_weights.iter().take(cap).enumerate() {
+                unsafe {
+                    *weights.add(i) = *weight;
+                }
+            }
+
+            unsafe {
+                *len = variable_weights.len();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Replaces the bounds of a variable defined as a range E.g. `a = [0, 10];`
+///
+/// Takes effect on the next call to `rvs_next`.  The new bounds are kept by `rvs_reset`.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false and leaves the variable unmodified if the variable is not a range
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_range(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    low: u32,
+    high: u32,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => variable.borrow_mut().set_range_bounds(low, high),
+        None => false,
+    }
+}
+
+/// Returns the min, max, and mean of the values returned by a variable via the result pointers
+///
+/// Statistics are only collected after being enabled by `rvs_set_stats_enabled`.  The mean is
+/// rounded to the nearest integer.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result pointers untouched if handle is invalid
+/// * Returns false and leaves the result pointers untouched if no values have been collected
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_stats(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    min: *mut u32,
+    max: *mut u32,
+    mean: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!min.is_null());
+    assert!(!max.is_null());
+    assert!(!mean.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let stats = match handle.variable(model) {
+        Some(variable) => variable.borrow().stats(),
+        None => None,
+    };
+
+    match stats {
+        Some(stats) => {
+            unsafe {
+                *min = stats.min;
+                *max = stats.max;
+                *mean = stats.mean();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns a snapshot of the evaluation state of a variable via the result pointers
+///
+/// Equivalent to calling `rvs_prev`, `rvs_done`, `rvs_cycle_count`, and `rvs_cycle_position`.
+/// `count` is the number of values returned by `rvs_next` (or skipped by `rvs_skip`) since the
+/// variable was created or reset.  `cycle_position` is `SIZE_MAX` where `rvs_cycle_position`
+/// would return false.
+///
+/// # Errors
+///
+/// * Returns false and leaves the result pointers untouched if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_variable_state(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    prev: *mut u32,
+    done: *mut bool,
+    count: *mut u64,
+    cycle_count: *mut u64,
+    cycle_position: *mut usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!prev.is_null());
+    assert!(!done.is_null());
+    assert!(!count.is_null());
+    assert!(!cycle_count.is_null());
+    assert!(!cycle_position.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => {
+            let state = variable.borrow().state();
+
+            unsafe {
+                *prev = state.prev;
+                *done = state.done;
+                *count = state.count;
+                *cycle_count = state.cycle_count;
+                *cycle_position = state.cycle_position.unwrap_or(usize::MAX);
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the number of enums defined in a model
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_enum_count(model: *const rvs::Model) -> usize {
+    assert!(!model.is_null());
+
+    let model = unsafe { &*model };
+
+    model.enum_names().count()
+}
+
+/// Copies the name of the enum at `index` into a buffer
+///
+/// Enums are indexed in the order they were defined starting at 0.  At most `len - 1` bytes of
+/// the name are copied and the result is always null terminated.
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer untouched if index is out of range
+///
+/// # Panics
+///
+/// * If the model pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_enum_name(
+    model: *const rvs::Model,
+    index: usize,
+    buf: *mut c_char,
+    len: usize,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &*model };
+
+    match model.enum_names().nth(index) {
+        Some(name) => {
+            buffer::write_str(name, buf, len);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copies the name of member `index` of an enum into a buffer and returns its value via the
+/// result pointer
+///
+/// Members are indexed in the order they were defined starting at 0.  At most `name_len - 1`
+/// bytes of the name are copied and the result is always null terminated.
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer and result pointer untouched if the enum does not exist
+/// * Returns false and leaves the buffer and result pointer untouched if index is out of range
+///
+/// # Panics
+///
+/// * If the model, enum name, or value pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_enum_member(
+    model: *const rvs::Model,
+    enum_name: *const c_char,
+    index: usize,
+    name_buf: *mut c_char,
+    name_len: usize,
+    value: *mut u32,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!enum_name.is_null());
+    assert!(!value.is_null());
+
+    let model = unsafe { &*model };
+    let enum_name = unsafe { CStr::from_ptr(enum_name) };
+    let enum_name = match enum_name.to_str() {
+        Ok(enum_name) => enum_name,
+        Err(_) => return false,
+    };
+
+    let member = match model.enum_members(enum_name) {
+        Some(mut members) => members.nth(index),
+        None => None,
+    };
+
+    match member {
+        Some((name, member_value)) => {
+            buffer::write_str(name, name_buf, name_len);
+            unsafe { *value = member_value };
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// A C function applied to each value returned by a variable
+pub type ValueTransformFn = extern "C" fn(value: u32, user_data: *mut c_void) -> u32;
+
+/// Sets or removes a function applied to each value returned by `rvs_next`
+///
+/// The callback is called with the value and `user_data`.  The transformed value is also returned
+/// by `rvs_prev` and seen by references to the variable.  Passing a null callback removes the
+/// transform.
+///
+/// The callback must not unwind.  The process is aborted if it does.
+///
+/// # Errors
+///
+/// * Does nothing if handle is invalid
+///
+/// # Panics
+///
+/// * If the model pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_set_value_transform(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    callback: Option<ValueTransformFn>,
+    user_data: *mut c_void,
+) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let value_transform = callback.map(|callback| {
+        Rc::new(move |value| {
+            panic::catch_unwind(|| callback(value, user_data)).unwrap_or_else(|_| process::abort())
+        }) as rvs::ValueTransform
+    });
+
+    if let Some(variable) = handle.variable(model) {
+        variable.borrow_mut().set_value_transform(value_transform);
+    }
+}
+
+/// A C function called with each value produced by `rvs_stream`
+///
+/// Returning false stops the stream.
+pub type StreamFn = extern "C" fn(value: u32, done: bool, user_data: *mut c_void) -> bool;
+
+/// Advances a variable up to `n` times and calls a function with each value
+///
+/// The callback is called with the value, the done value of the variable after producing it,
+/// and `user_data`.  Each call is equivalent to a call to `rvs_next` followed by `rvs_done`.  The
+/// stream stops early if the callback returns false.  Returns the number of values passed to the
+/// callback.
+///
+/// The callback may call other functions on the model.  The callback must not unwind.  The
+/// process is aborted if it does.
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+///
+/// # Panics
+///
+/// * If the model pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_stream(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    n: usize,
+    callback: StreamFn,
+    user_data: *mut c_void,
+) -> usize {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let variable = match handle.variable(model) {
+        Some(variable) => Rc::clone(variable),
+        None => return 0,
+    };
+
+    for count in 0..n {
+        // The variable is not borrowed during the callback so the callback may access it
+        let (value, done) = {
+            let mut variable = variable.borrow_mut();
+            let value = variable.next();
+            (value, variable.done())
+        };
+
+        let proceed = panic::catch_unwind(|| callback(value, done, user_data))
+            .unwrap_or_else(|_| process::abort());
+        if !proceed {
+            return count + 1;
+        }
+    }
+
+    n
+}
+
+/// Returns the number of warnings collected while transforming a model
+///
+/// Warnings identify likely mistakes that do not prevent the transform from succeeding.  E.g. a
+/// range with literal bounds in reverse order.  Returns 0 if there are no warnings.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_warning_count(model: *const rvs::Model) -> usize {
+    assert!(!model.is_null());
+
+    let model = unsafe { &*model };
+    model.warnings().len()
+}
+
+/// Copies the message of a warning into a buffer
+///
+/// Warnings are indexed in the order they were found starting at 0.  At most `len - 1` bytes of
+/// the message are copied and the result is always null terminated.  The message includes the
+/// source location if known E.g. "warning at 1:1: ...".
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer untouched if `index` is out of range
+///
+/// # Panics
+///
+/// * If the model pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_warning_message(
+    model: *const rvs::Model,
+    index: usize,
+    buf: *mut c_char,
+    len: usize,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &*model };
+
+    match model.warnings().get(index) {
+        Some(warning) => {
+            buffer::write_str(&warning.to_string(), buf, len);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Discards all warnings collected while transforming a model
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_warnings_clear(model: *mut rvs::Model) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    model.clear_warnings();
+}
+
+/// Copies the expression tree of a variable in Graphviz DOT format into a buffer
+///
+/// See `rvs::Variable::to_dot` for the format.  At most `len - 1` bytes are copied and the result
+/// is always null terminated.
+///
+/// Returns the length of the DOT excluding the null terminator.  The DOT is truncated if the
+/// return value is greater than or equal to `len`.
+///
+/// # Errors
+///
+/// * Returns 0 and leaves the buffer untouched if handle is invalid
+///
+/// # Panics
+///
+/// * If the model pointer argument is null.  `buf` may be null if `len` is 0.
+#[no_mangle]
+pub extern "C" fn rvs_variable_dot(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match handle.variable(model) {
+        Some(variable) => buffer::write_str(&variable.borrow().to_dot(), buf, len),
+        None => 0,
+    }
+}
+
+/// Copies the value of a variable attribute into a buffer
+///
+/// Attributes are defined with `@key(value)` preceding a variable definition.  At most `len - 1`
+/// bytes of the value are copied and the result is always null terminated.
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer untouched if handle is invalid
+/// * Returns false and leaves the buffer untouched if the variable has no attribute named `key`
+///
+/// # Panics
+///
+/// * If the model or key pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_variable_attribute(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    key: *const c_char,
+    buf: *mut c_char,
+    len: usize,
+) -> bool {
+    assert!(!model.is_null());
+    assert!(!key.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    let key = unsafe { CStr::from_ptr(key) };
+    let key = match key.to_str() {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+
+    let variable = match handle.variable(model) {
+        Some(variable) => variable,
+        None => return false,
+    };
+    let variable = variable.borrow();
+
+    match variable.attribute(key) {
+        Some(value) => {
+            buffer::write_str(value, buf, len);
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copies the doc comment of a variable into a buffer
+///
+/// Doc comments are `///` comments immediately preceding a variable definition.  Consecutive
+/// lines are joined with newlines.  At most `len - 1` bytes are copied and the result is always
+/// null terminated.
+///
+/// # Errors
+///
+/// * Returns false and leaves the buffer untouched if handle is invalid
+/// * Returns false and leaves the buffer untouched if the variable has no doc comment
+///
+/// # Panics
+///
+/// * If the model pointer argument is null
+#[no_mangle]
+pub extern "C" fn rvs_variable_doc(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    buf: *mut c_char,
+    len: usize,
+) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    let variable = match handle.variable(model) {
+        Some(variable) => variable,
+        None => return false,
+    };
+    let variable = variable.borrow();
+
+    match variable.doc() {
+        Some(doc) => {
+            buffer::write_str(doc, buf, len);
+
+            true
+        }
+        None => false,
+    }
+}
+
+#[no_mangle]
 pub extern "C" fn rvs_write_definitions(
     model: *const rvs::Model,
     s: *const c_char,
@@ -348,7 +2492,7 @@ pub extern "C" fn rvs_write_definitions(
 
     let path = Path::new(r_str);
 
-    let mut file = match File::create(&path) {
+    let mut file = match File::create(path) {
         Err(e) => {
             if !error.is_null() {
                 unsafe {
@@ -367,8 +2511,6 @@ pub extern "C" fn rvs_write_definitions(
             unsafe {
                 *error = Error::new(ErrorKind::Io(e));
             }
-
-            return;
         }
     }
 }
@@ -0,0 +1,138 @@
+//! Safe wrappers around the C API
+//!
+//! Owns the raw pointers of the C API and converts C API errors into `Result`s.  Mirrors the
+//! workflow of the C API:
+//!
+//! 1. Create a context with `RvsContext::new`
+//! 2. Parse with `RvsContext::parse`
+//! 3. Transform the context into a model with `RvsContext::transform`
+//! 4. Find variables with `RvsModel::find` and evaluate them with `RvsModel::next`
+
+use crate::error::{rvs_error_free, rvs_error_new, rvs_error_test, Error, ErrorKind};
+use crate::rvsc::*;
+use crate::context::Context;
+
+use std::ffi::CString;
+use std::io;
+
+/// A handle to a variable in an `RvsModel`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Handle(u32);
+
+/// Owns a `Context` created by `rvs_context_new`
+pub struct RvsContext {
+    context: *mut Context,
+}
+
+/// Owns a `Model` created by `rvs_model_new` and transformed by `rvs_transform`
+pub struct RvsModel {
+    model: *mut rvs::Model,
+}
+
+/// Calls `f` with a new C API error and converts the C API error to a `Result`
+///
+/// The value returned by `f` is dropped on error.
+fn with_error<F, T>(f: F) -> Result<T, Error>
+where
+    F: FnOnce(*mut Error) -> T,
+{
+    let error = rvs_error_new();
+    let result = f(error);
+
+    if rvs_error_test(error) {
+        let error = unsafe { Box::from_raw(error) };
+        Err(*error)
+    } else {
+        rvs_error_free(error);
+        Ok(result)
+    }
+}
+
+fn to_cstring(s: &str) -> Result<CString, Error> {
+    CString::new(s)
+        .map_err(|e| Error::new(ErrorKind::Io(io::Error::new(io::ErrorKind::InvalidInput, e))))
+}
+
+impl RvsContext {
+    /// See `rvs_context_new`
+    pub fn new(search_path: &str, seed: u32) -> Result<RvsContext, Error> {
+        let search_path = to_cstring(search_path)?;
+
+        // On error, the context is dropped and freed
+        with_error(|error| RvsContext {
+            context: rvs_context_new(search_path.as_ptr(), seed, error),
+        })
+    }
+
+    /// See `rvs_parse`
+    pub fn parse(&mut self, s: &str) -> Result<(), Error> {
+        let s = to_cstring(s)?;
+
+        with_error(|error| rvs_parse(self.context, s.as_ptr(), error))
+    }
+
+    /// Starts evaluating the variable `name` on a background thread
+    ///
+    /// The context remains usable.  See `rvs::spawn_producer`.
+    pub fn spawn_producer(&self, name: &str, capacity: usize) -> rvs::Result<rvs::ProducerHandle> {
+        let context = unsafe { &*self.context };
+
+        context.spawn_producer(name, capacity)
+    }
+
+    /// See `rvs_transform`
+    pub fn transform(self) -> Result<RvsModel, Error> {
+        let model = RvsModel {
+            model: rvs_model_new(),
+        };
+
+        // `rvs_transform` frees the context
+        let context = self.context;
+        std::mem::forget(self);
+        with_error(|error| rvs_transform(context, model.model, error))?;
+
+        Ok(model)
+    }
+}
+
+impl Drop for RvsContext {
+    fn drop(&mut self) {
+        rvs_context_free(self.context);
+    }
+}
+
+impl RvsModel {
+    /// See `rvs_get`
+    ///
+    /// Returns `None` if the variable does not exist.
+    pub fn find(&self, name: &str) -> Option<Handle> {
+        let name = CString::new(name).ok()?;
+
+        match rvs_get(self.model, name.as_ptr()) {
+            0 => None,
+            handle => Some(Handle(handle)),
+        }
+    }
+
+    /// See `rvs_next`
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self, handle: Handle) -> u32 {
+        rvs_next(self.model, handle.0)
+    }
+
+    /// See `rvs_prev`
+    pub fn prev(&self, handle: Handle) -> u32 {
+        rvs_prev(self.model, handle.0)
+    }
+
+    /// See `rvs_done`
+    pub fn done(&self, handle: Handle) -> bool {
+        rvs_done(self.model, handle.0)
+    }
+}
+
+impl Drop for RvsModel {
+    fn drop(&mut self) {
+        rvs_model_free(self.model);
+    }
+}
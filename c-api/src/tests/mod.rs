@@ -1,10 +1,9 @@
 use super::*;
 
-use rvs;
-
 use std::ffi::CString;
 use std::ffi::CStr;
 
+use crate::context::Context;
 use error::{rvs_error_free, rvs_error_new, rvs_error_test};
 
 fn get_error_message(error: *mut Error) -> String {
@@ -32,9 +31,104 @@ fn next_by_name(model: *mut rvs::Model, name: &str) -> u32 {
     rvs_next(model, handle)
 }
 
+/// Creates a context with an empty search path and a seed of 0
+///
+/// The context is freed by `rvs_transform` and similar functions.
+fn context_new(error: *mut Error) -> *mut Context {
+    context_with_search_path("", error)
+}
+
+/// Creates a context with a seed of 0.  See `context_new`.
+fn context_with_search_path(search_path: &str, error: *mut Error) -> *mut Context {
+    let context = rvs_context_new(CString::new(search_path).unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    context
+}
+
+/// Parses and transforms `s` with a context configured by `configure`
+fn model_from_str_with<F>(s: &str, configure: F) -> *mut rvs::Model
+where
+    F: FnOnce(*mut Context),
+{
+    let error = rvs_error_new();
+    let context = context_new(error);
+    configure(context);
+    rvs_error_free(error);
+
+    model_from_context(context, s)
+}
+
+/// Parses and transforms `s` with `context` and frees the context
+fn model_from_context(context: *mut Context, s: &str) -> *mut rvs::Model {
+    let error = rvs_error_new();
+    let model = rvs_model_new();
+    rvs_parse_and_transform(context, CString::new(s).unwrap().as_ptr(), model, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_error_free(error);
+
+    model
+}
+
+fn model_from_str(s: &str) -> *mut rvs::Model {
+    model_from_str_with(s, |_| {})
+}
+
 mod rvs_context_new;
 mod rvs_seed;
+mod rvs_context_serialize;
 mod rvs_parse;
 mod rvs_get;
+mod rvs_get_checked;
+mod rvs_handle;
 mod rvs_next;
 mod rvs_write_definitions;
+mod rvs_model_clone;
+mod rvs_stats;
+mod rvs_version;
+mod rvs_warning;
+mod rvs_parse_file;
+mod rvs_parse_ast_string;
+mod rvs_variable_attribute;
+mod rvs_variable_doc;
+mod rvs_variable_dot;
+mod rvs_error_code;
+mod rvs_set_value_transform;
+mod rvs_next_ex;
+mod rvs_next_checked;
+mod rvs_next_until;
+mod rvs_skip;
+mod rvs_enum;
+mod rvs_set_max_resample_attempts;
+mod rvs_find_prefix;
+mod rvs_get_seed;
+mod rvs_next_with_entropy;
+mod rvs_next_with_override;
+mod rvs_set_aligned_binary_done;
+mod rvs_set_enabled;
+mod rvs_last_index;
+mod rvs_current;
+mod rvs_cycle_position;
+mod rvs_cycle_count;
+mod rvs_variable_state;
+mod rvs_done_edge;
+mod rvs_parse_and_transform;
+mod rvs_rng_draws;
+mod rvs_range;
+mod rvs_weights;
+mod rvs_self_test;
+mod rvs_set_output_mask;
+mod rvs_set_lazy;
+mod safe;
+mod rvs_reset;
+mod rvs_reset_rng;
+mod rvs_checkpoint;
+mod rvs_preview;
+mod rvs_value_bounds;
+mod rvs_is_deterministic;
+mod rvs_limit;
+mod rvs_freeze;
+mod rvs_stream;
+mod rvs_context_new_from_env;
+mod rvs_parse_ex;
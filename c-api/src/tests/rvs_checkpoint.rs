@@ -0,0 +1,60 @@
+use super::*;
+
+fn checkpoint(model: *mut rvs::Model, name: &str) -> bool {
+    rvs_checkpoint(model, CString::new(name).unwrap().as_ptr())
+}
+
+fn restore(model: *mut rvs::Model, name: &str) -> bool {
+    rvs_restore(model, CString::new(name).unwrap().as_ptr())
+}
+
+fn next_n(model: *mut rvs::Model, name: &str, n: usize) -> Vec<u32> {
+    (0..n).map(|_| next_by_name(model, name)).collect()
+}
+
+#[test]
+fn replays_values() {
+    let model = model_from_str("a = [0, 0xffff_ffff]; b = {Pattern(1, 2), 3, 4};");
+
+    next_n(model, "a", 3);
+    next_n(model, "b", 2);
+
+    assert!(checkpoint(model, "start"));
+    let a = next_n(model, "a", 16);
+    let b = next_n(model, "b", 16);
+
+    assert!(restore(model, "start"));
+    assert_eq!(next_n(model, "a", 16), a);
+    assert_eq!(next_n(model, "b", 16), b);
+
+    assert!(restore(model, "start"));
+    assert_eq!(next_n(model, "a", 16), a);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn overwrite() {
+    let model = model_from_str("a = Pattern(1, 2, 3, 4);");
+
+    assert!(checkpoint(model, "x"));
+    assert_eq!(next_by_name(model, "a"), 1);
+    assert!(checkpoint(model, "x"));
+    assert_eq!(next_by_name(model, "a"), 2);
+
+    assert!(restore(model, "x"));
+    assert_eq!(next_by_name(model, "a"), 2);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn unknown() {
+    let model = model_from_str("a = Pattern(1, 2, 3, 4);");
+
+    assert_eq!(next_by_name(model, "a"), 1);
+    assert!(!restore(model, "missing"));
+    assert_eq!(next_by_name(model, "a"), 2);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+use crate::context::Context;
+use std::env;
+
+/// Returns the first values of `a = [0, 0xffff_ffff];` for a context and frees the context
+fn values(context: *mut Context) -> Vec<u32> {
+    let model = model_from_context(context, "a = [0, 0xffff_ffff];");
+    let values = (0..8).map(|_| next_by_name(model, "a")).collect();
+
+    rvs_model_free(model);
+
+    values
+}
+
+fn seeded_values(seed: u32) -> Vec<u32> {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), seed, error);
+    assert!(!rvs_error_test(error));
+    rvs_error_free(error);
+
+    values(context)
+}
+
+/// Returns the seed and any error message of a context created with `RVS_SEED` set to `value`
+fn from_env(value: Option<&str>, default_seed: u32) -> (u32, Option<String>, Vec<u32>) {
+    match value {
+        Some(value) => env::set_var("RVS_SEED", value),
+        None => env::remove_var("RVS_SEED"),
+    }
+
+    let error = rvs_error_new();
+    let context =
+        rvs_context_new_from_env(CString::new("").unwrap().as_ptr(), default_seed, error);
+    let message = if rvs_error_test(error) {
+        Some(get_error_message(error))
+    } else {
+        None
+    };
+    rvs_error_free(error);
+
+    let mut seed = 0;
+    rvs_get_seed(context, &mut seed);
+
+    (seed, message, values(context))
+}
+
+// The environment is shared by all tests so all cases are in a single test
+#[test]
+fn basic() {
+    let (seed, message, values) = from_env(Some("1234"), 5);
+    assert_eq!(seed, 1234);
+    assert_eq!(message, None);
+    assert_eq!(values, seeded_values(1234));
+
+    let (seed, message, values) = from_env(Some(" 0x1234_abcd\n"), 5);
+    assert_eq!(seed, 0x1234_abcd);
+    assert_eq!(message, None);
+    assert_eq!(values, seeded_values(0x1234_abcd));
+
+    let (seed, message, _) = from_env(Some("0XFFFFFFFF"), 5);
+    assert_eq!(seed, 0xffff_ffff);
+    assert_eq!(message, None);
+
+    let (seed, message, values) = from_env(None, 5);
+    assert_eq!(seed, 5);
+    assert_eq!(message, None);
+    assert_eq!(values, seeded_values(5));
+
+    let (seed, message, _) = from_env(Some(""), 5);
+    assert_eq!(seed, 5);
+    assert_eq!(message, None);
+
+    for malformed in &["abc", "0x", "-1", "0x1_0000_0000", "4294967296", "12_", "0xg"] {
+        let (seed, message, values) = from_env(Some(malformed), 5);
+        assert_eq!(seed, 5);
+        assert_eq!(
+            message,
+            Some(format!(
+                "RVS_SEED must be a decimal or 0x prefixed hexadecimal 32-bit value but is '{}'",
+                malformed
+            ))
+        );
+        assert_eq!(values, seeded_values(5));
+    }
+
+    env::remove_var("RVS_SEED");
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+use std::fs;
+
+/// A file exercising most expression types.  Shared with the serialization tests of rvs.
+const VARIED: &str = "../tests/serialize_model/varied.rvs";
+
+fn varied_context() -> *mut Context {
+    let error = rvs_error_new();
+    let context = context_with_search_path("../tests/pattern_file", error);
+
+    rvs_error_free(error);
+
+    // `rvs_parse` splits on semicolons so parse the file as a whole
+    let context_ref = unsafe { &mut *context };
+    context_ref.parse(&fs::read_to_string(VARIED).unwrap()).unwrap();
+
+    context
+}
+
+fn serialize(context: *const Context) -> Vec<u8> {
+    let len = rvs_context_serialize(context, std::ptr::null_mut(), 0, std::ptr::null_mut());
+    let mut bytes = vec![0u8; len];
+    assert_eq!(rvs_context_serialize(context, bytes.as_mut_ptr(), bytes.len(), std::ptr::null_mut()), len);
+
+    bytes
+}
+
+fn deserialize(bytes: &[u8]) -> *mut Context {
+    let error = rvs_error_new();
+    let context = rvs_context_deserialize(bytes.as_ptr(), bytes.len(), error);
+    assert!(!rvs_error_test(error), "{}", get_error_message(error));
+    rvs_error_free(error);
+
+    context
+}
+
+/// Transforms with `context` and returns the first values of every variable.  Frees the context.
+fn values(context: *mut Context) -> Vec<Vec<u32>> {
+    let model = rvs_model_new();
+    let error = rvs_error_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error), "{}", get_error_message(error));
+    rvs_error_free(error);
+
+    let model_ref = unsafe { &*model };
+    let names: Vec<String> = model_ref.variables_iter().map(|(name, _)| name.to_owned()).collect();
+    let values = names
+        .iter()
+        .map(|name| (0..100).map(|_| next_by_name(model, name)).collect())
+        .collect();
+    rvs_model_free(model);
+
+    values
+}
+
+#[test]
+fn round_trip() {
+    let context = varied_context();
+    let bytes = serialize(context);
+
+    let restored = deserialize(&bytes);
+    assert_eq!(serialize(restored), bytes);
+
+    let expected = values(context);
+    assert_eq!(expected.len(), 22);
+    assert_eq!(values(restored), expected);
+}
+
+#[test]
+fn restored_context_accepts_more_statements() {
+    let bytes = serialize(varied_context());
+    let restored = deserialize(&bytes);
+
+    let s = CString::new("z = a + 1;").unwrap();
+    rvs_parse(restored, s.as_ptr(), std::ptr::null_mut());
+
+    let expected = varied_context();
+    rvs_parse(expected, s.as_ptr(), std::ptr::null_mut());
+
+    assert_eq!(values(restored), values(expected));
+}
+
+#[test]
+fn short_buffer() {
+    let context = varied_context();
+    let len = rvs_context_serialize(context, std::ptr::null_mut(), 0, std::ptr::null_mut());
+
+    let mut short = vec![0xffu8; len - 1];
+    assert_eq!(rvs_context_serialize(context, short.as_mut_ptr(), short.len(), std::ptr::null_mut()), len);
+    assert!(short.iter().all(|&byte| byte == 0xff));
+
+    rvs_context_free(context);
+}
+
+#[test]
+fn invalid() {
+    let mut bytes = serialize(varied_context());
+    bytes[4] += 1;
+
+    let error = rvs_error_new();
+    assert!(rvs_context_deserialize(bytes.as_ptr(), bytes.len(), error).is_null());
+    assert_eq!(
+        get_error_message(error),
+        "serialized context format version 2 is not supported (expected 1)"
+    );
+
+    rvs_error_free(error);
+
+    bytes[4] -= 1;
+    let error = rvs_error_new();
+    assert!(rvs_context_deserialize(bytes.as_ptr(), 10, error).is_null());
+    assert_starts_with(get_error_message(error), "invalid serialized context at byte");
+
+    rvs_error_free(error);
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(0, 1);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut value = 42;
+    assert!(!rvs_current(model, handle, &mut value));
+    assert_eq!(value, 42);
+
+    assert_eq!(rvs_next(model, handle), 0);
+    assert!(rvs_current(model, handle, &mut value));
+    assert_eq!(value, 0);
+
+    assert_eq!(rvs_next(model, handle), 1);
+    assert!(rvs_current(model, handle, &mut value));
+    assert_eq!(value, 1);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn reset() {
+    let model = model_from_str("a = 5;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    rvs_next(model, handle);
+    rvs_reset(model, handle);
+
+    let mut value = 42;
+    assert!(!rvs_current(model, handle, &mut value));
+    assert_eq!(value, 42);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut value = 42;
+    assert!(!rvs_current(model, 0, &mut value));
+    assert_eq!(value, 42);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn pattern() {
+    let model = model_from_str("a = Pattern(10, 11, 12, 13);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut count = 99;
+    assert!(rvs_cycle_count(model, handle, &mut count));
+    assert_eq!(count, 0);
+
+    for _ in 0..10 {
+        rvs_next(model, handle);
+    }
+    assert!(rvs_cycle_count(model, handle, &mut count));
+    assert_eq!(count, 2);
+
+    rvs_reset(model, handle);
+    assert!(rvs_cycle_count(model, handle, &mut count));
+    assert_eq!(count, 0);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = [0, 3];");
+
+    let mut count = 99;
+    assert!(!rvs_cycle_count(model, 2, &mut count));
+    assert_eq!(count, 99);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn pattern() {
+    let model = model_from_str("a = Pattern(10, 11, 12, 13);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut position = 99;
+    assert!(!rvs_cycle_position(model, handle, &mut position));
+    assert_eq!(position, 99);
+
+    for i in 0..12 {
+        rvs_next(model, handle);
+        assert!(rvs_cycle_position(model, handle, &mut position));
+        assert_eq!(position, i % 4);
+    }
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_a_cycle() {
+    let model = model_from_str("a = [0, 3];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut position = 99;
+    rvs_next(model, handle);
+    assert!(!rvs_cycle_position(model, handle, &mut position));
+    assert!(!rvs_cycle_position(model, 2, &mut position));
+    assert_eq!(position, 99);
+
+    rvs_model_free(model);
+}
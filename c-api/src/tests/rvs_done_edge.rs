@@ -0,0 +1,59 @@
+use super::*;
+
+fn edges(model: *mut rvs::Model, handle: u32, count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|_| {
+            rvs_next(model, handle);
+
+            let mut edge = false;
+            assert!(rvs_done_edge(model, handle, &mut edge));
+            edge
+        })
+        .collect()
+}
+
+#[test]
+fn once_per_cycle() {
+    let model = model_from_str("a = Pattern(0, 1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert_eq!(
+        edges(model, handle, 12),
+        vec![false, false, false, true, false, false, false, true, false, false, false, true]
+    );
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn before_first_next() {
+    let model = model_from_str("a = Pattern(0, 1);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut edge = true;
+    assert!(rvs_done_edge(model, handle, &mut edge));
+    assert!(!edge);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn done_every_call() {
+    let model = model_from_str("a = 5;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert_eq!(edges(model, handle, 3), vec![true, false, false]);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 5;");
+
+    let mut edge = true;
+    assert!(!rvs_done_edge(model, 2, &mut edge));
+    assert!(edge);
+
+    rvs_model_free(model);
+}
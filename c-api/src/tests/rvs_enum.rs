@@ -0,0 +1,71 @@
+use super::*;
+
+use libc::c_char;
+
+fn enum_name(model: *mut rvs::Model, index: usize) -> Option<String> {
+    let mut buf = [0 as c_char; 16];
+
+    if rvs_enum_name(model, index, buf.as_mut_ptr(), buf.len()) {
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Some(name.to_str().unwrap().to_owned())
+    } else {
+        None
+    }
+}
+
+fn enum_members(model: *mut rvs::Model, enum_name: &str) -> Vec<(String, u32)> {
+    let enum_name = CString::new(enum_name).unwrap();
+    let mut members = Vec::new();
+    let mut buf = [0 as c_char; 16];
+    let mut value = 0;
+
+    while rvs_enum_member(
+        model,
+        enum_name.as_ptr(),
+        members.len(),
+        buf.as_mut_ptr(),
+        buf.len(),
+        &mut value,
+    ) {
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        members.push((name.to_str().unwrap().to_owned(), value));
+    }
+
+    members
+}
+
+#[test]
+fn basic() {
+    let model = model_from_str(
+        "enum Command { Read, Write = 4, Erase, }
+         enum Size { Byte = 1, Word = 4, }
+         a = Command::Erase;",
+    );
+
+    assert_eq!(rvs_enum_count(model), 2);
+    assert_eq!(enum_name(model, 0), Some("Command".into()));
+    assert_eq!(enum_name(model, 1), Some("Size".into()));
+    assert_eq!(enum_name(model, 2), None);
+
+    assert_eq!(
+        enum_members(model, "Command"),
+        [("Read".into(), 0), ("Write".into(), 4), ("Erase".into(), 5)]
+    );
+    assert_eq!(
+        enum_members(model, "Size"),
+        [("Byte".into(), 1), ("Word".into(), 4)]
+    );
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_found() {
+    let model = model_from_str("a = 1;");
+
+    assert_eq!(rvs_enum_count(model), 0);
+    assert_eq!(enum_name(model, 0), None);
+    assert!(enum_members(model, "Command").is_empty());
+
+    rvs_model_free(model);
+}
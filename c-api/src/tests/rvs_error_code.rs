@@ -0,0 +1,68 @@
+use super::*;
+
+use libc::c_char;
+
+fn code_string(code: u32) -> String {
+    let mut buf = [0 as c_char; 32];
+    rvs_error_code_string(code, buf.as_mut_ptr(), buf.len());
+
+    let s = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    s.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn known_codes() {
+    assert_eq!(code_string(RVS_ERROR_NONE), "no error");
+    assert_eq!(code_string(RVS_ERROR_PARSE), "parse error");
+    assert_eq!(code_string(RVS_ERROR_TRANSFORM), "transform error");
+    assert_eq!(code_string(RVS_ERROR_IO), "io error");
+    assert_eq!(code_string(RVS_ERROR_CONSTRAINT), "constraint error");
+    assert_eq!(code_string(RVS_ERROR_BUDGET), "budget exceeded");
+    assert_eq!(code_string(RVS_ERROR_UNDEFINED), "undefined variable");
+    assert_eq!(code_string(RVS_ERROR_NOT_TRANSFORMED), "not transformed");
+}
+
+#[test]
+fn unknown_code() {
+    assert_eq!(code_string(1000), "unknown");
+}
+
+fn parse_error_code(s: &str) -> u32 {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    rvs_parse(context, CString::new(s).unwrap().as_ptr(), error);
+
+    let model = rvs_model_new();
+    if !rvs_error_test(error) {
+        rvs_transform(context, model, error);
+    } else {
+        rvs_context_free(context);
+    }
+
+    let code = rvs_error_code(error);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+
+    code
+}
+
+#[test]
+fn no_error() {
+    assert_eq!(parse_error_code("a = 1;"), RVS_ERROR_NONE);
+}
+
+#[test]
+fn parse_error() {
+    assert_eq!(parse_error_code("a = ;"), RVS_ERROR_PARSE);
+}
+
+#[test]
+fn transform_error() {
+    assert_eq!(parse_error_code("a = b;"), RVS_ERROR_TRANSFORM);
+}
+
+#[test]
+fn io_error() {
+    assert_eq!(parse_error_code("missing.rvs"), RVS_ERROR_IO);
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+fn find_prefix(model: *mut rvs::Model, prefix: &str, cap: usize) -> (Vec<u32>, usize) {
+    let mut handles = vec![0; cap];
+    let mut len = 0;
+    rvs_find_prefix(
+        model,
+        CString::new(prefix).unwrap().as_ptr(),
+        handles.as_mut_ptr(),
+        cap,
+        &mut len,
+    );
+
+    (handles, len)
+}
+
+fn get(model: *mut rvs::Model, name: &str) -> u32 {
+    rvs_get(model, CString::new(name).unwrap().as_ptr())
+}
+
+#[test]
+fn matches() {
+    let model = model_from_str("pkt_a = 1; other = 2; pkt_b = 3;");
+
+    let (handles, len) = find_prefix(model, "pkt_", 4);
+    assert_eq!(len, 2);
+    assert_eq!(handles[..len], [get(model, "pkt_a"), get(model, "pkt_b")]);
+    assert_eq!(next_by_name(model, "pkt_b"), 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn no_matches() {
+    let model = model_from_str("pkt_a = 1; other = 2;");
+
+    let (_, len) = find_prefix(model, "foo", 4);
+    assert_eq!(len, 0);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn more_matches_than_cap() {
+    let model = model_from_str("pkt_a = 1; pkt_b = 2; pkt_c = 3;");
+
+    let (handles, len) = find_prefix(model, "pkt_", 2);
+    assert_eq!(len, 3);
+    assert_eq!(handles, [get(model, "pkt_a"), get(model, "pkt_b")]);
+
+    let (_, len) = find_prefix(model, "pkt_", 0);
+    assert_eq!(len, 3);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = [0, 0xffffffff]; b = a + 1;");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    let value = rvs_next(model, a);
+    assert!(rvs_freeze(model, a));
+    for _ in 0..10 {
+        assert_eq!(rvs_next(model, a), value);
+        assert_eq!(rvs_next(model, b), value.wrapping_add(1));
+    }
+
+    assert!(rvs_unfreeze(model, a));
+    let values: Vec<u32> = (0..10).map(|_| rvs_next(model, a)).collect();
+    assert!(values.iter().any(|&v| v != value));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    assert!(!rvs_freeze(model, 2));
+    assert!(!rvs_unfreeze(model, 2));
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+fn get_checked(model: *mut rvs::Model, name: &str) -> (u32, u32) {
+    let error = rvs_error_new();
+    let handle = rvs_get_checked(model, CString::new(name).unwrap().as_ptr(), error);
+    let code = rvs_error_code(error);
+    rvs_error_free(error);
+
+    (handle, code)
+}
+
+#[test]
+fn defined() {
+    let model = model_from_str("a = 5;");
+
+    let (handle, code) = get_checked(model, "a");
+    assert_eq!(code, RVS_ERROR_NONE);
+    assert_eq!(rvs_next(model, handle), 5);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn undefined() {
+    let model = model_from_str("a = 5;");
+
+    assert_eq!(get_checked(model, "b"), (0, RVS_ERROR_UNDEFINED));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_transformed() {
+    let model = rvs_model_new();
+
+    assert_eq!(get_checked(model, "a"), (0, RVS_ERROR_NOT_TRANSFORMED));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn build_failure() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    rvs_set_lazy(context, true);
+    let model = rvs_model_new();
+    rvs_parse_and_transform(context, CString::new("a = unknown;").unwrap().as_ptr(), model, error);
+    assert!(!rvs_error_test(error));
+    rvs_error_free(error);
+
+    assert_eq!(get_checked(model, "a"), (0, RVS_ERROR_TRANSFORM));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn null_error() {
+    let model = model_from_str("a = 5;");
+
+    assert_eq!(rvs_get_checked(model, CString::new("b").unwrap().as_ptr(), std::ptr::null_mut()), 0);
+
+    rvs_model_free(model);
+}
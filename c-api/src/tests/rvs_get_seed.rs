@@ -0,0 +1,90 @@
+use super::*;
+
+use crate::context::Context;
+
+fn context(seed: Option<u32>) -> *mut Context {
+    let search_path = CString::new("").unwrap();
+
+    match seed {
+        Some(seed) => rvs_context_new(search_path.as_ptr(), seed, std::ptr::null_mut()),
+        None => rvs_context_new_random(search_path.as_ptr(), std::ptr::null_mut()),
+    }
+}
+
+fn get_seed(context: *const Context) -> u32 {
+    let mut seed = 0;
+    rvs_get_seed(context, &mut seed);
+
+    seed
+}
+
+/// Returns the first values of `a = [0, 0xffff_ffff];` and frees the context
+fn values(context: *mut Context) -> Vec<u32> {
+    let s = CString::new("a = [0, 0xffff_ffff];").unwrap();
+    rvs_parse(context, s.as_ptr(), std::ptr::null_mut());
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, std::ptr::null_mut());
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let values = (0..8).map(|_| rvs_next(model, a)).collect();
+    rvs_model_free(model);
+
+    values
+}
+
+#[test]
+fn known_seed() {
+    for &seed in &[0, 1, 0xdead_beef, u32::MAX] {
+        let context = context(Some(seed));
+        assert_eq!(get_seed(context), seed);
+        rvs_context_free(context);
+    }
+}
+
+#[test]
+fn random_seed_reproduces() {
+    let random = context(None);
+    let seed = get_seed(random);
+
+    // Both contexts are freed by `values`
+    assert_eq!(values(random), values(context(Some(seed))));
+}
+
+#[test]
+fn random_seeds_differ() {
+    let seeds: Vec<u32> = (0..4)
+        .map(|_| {
+            let context = context(None);
+            let seed = get_seed(context);
+            rvs_context_free(context);
+            seed
+        })
+        .collect();
+
+    assert!(seeds.windows(2).any(|pair| pair[0] != pair[1]), "{:?}", seeds);
+}
+
+#[test]
+fn seed_bytes() {
+    let a = context(Some(7));
+    let b = context(Some(7));
+    let c = context(Some(8));
+
+    let mut a_bytes = [0u8; 16];
+    let mut b_bytes = [0u8; 16];
+    let mut c_bytes = [0u8; 16];
+    assert!(rvs_get_seed_bytes(a, a_bytes.as_mut_ptr(), a_bytes.len()));
+    assert!(rvs_get_seed_bytes(b, b_bytes.as_mut_ptr(), b_bytes.len()));
+    assert!(rvs_get_seed_bytes(c, c_bytes.as_mut_ptr(), c_bytes.len()));
+    assert_eq!(a_bytes, b_bytes);
+    assert_ne!(a_bytes, c_bytes);
+
+    let mut short = [0xffu8; 15];
+    assert!(!rvs_get_seed_bytes(a, short.as_mut_ptr(), short.len()));
+    assert_eq!(short, [0xff; 15]);
+
+    rvs_context_free(a);
+    rvs_context_free(b);
+    rvs_context_free(c);
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn handle_from_other_context() {
+    let a = model_from_str("x = 5;");
+    let b = model_from_str("x = 7;");
+    let handle = rvs_get(a, CString::new("x").unwrap().as_ptr());
+
+    let (mut value, mut done, mut prev) = (0xdead, true, 0xdead);
+    assert!(!rvs_next_ex(b, handle, &mut value, &mut done, &mut prev));
+    assert_eq!((value, done, prev), (0, false, 0));
+
+    assert_eq!(rvs_next(b, handle), 0);
+    assert_eq!(rvs_prev(b, handle), 0);
+    assert!(!rvs_done(b, handle));
+
+    assert_eq!(rvs_next(a, handle), 5);
+
+    rvs_model_free(a);
+    rvs_model_free(b);
+}
+
+#[test]
+fn handle_valid_for_clone() {
+    let model = model_from_str("x = 5;");
+    let handle = rvs_get(model, CString::new("x").unwrap().as_ptr());
+    let clone = rvs_model_clone(model);
+
+    assert_eq!(rvs_next(clone, handle), 5);
+
+    rvs_model_free(model);
+    rvs_model_free(clone);
+}
+
+#[test]
+fn index_bits_of_zero() {
+    let model = model_from_str("x = 5;");
+    let handle = rvs_get(model, CString::new("x").unwrap().as_ptr());
+
+    assert_eq!(rvs_next(model, handle & 0xff00_0000), 0);
+
+    rvs_model_free(model);
+}
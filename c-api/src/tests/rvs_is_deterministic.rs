@@ -0,0 +1,32 @@
+use super::*;
+
+fn is_deterministic(s: &str) -> bool {
+    let model = model_from_str(s);
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut result = false;
+    assert!(rvs_is_deterministic(model, handle, &mut result));
+
+    rvs_model_free(model);
+
+    result
+}
+
+#[test]
+fn basic() {
+    assert!(is_deterministic("a = 5;"));
+    assert!(is_deterministic("a = Pattern(1, 2, 3);"));
+    assert!(!is_deterministic("a = [0, 1];"));
+    assert!(!is_deterministic("a = r{1, 2};"));
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut result = true;
+    assert!(!rvs_is_deterministic(model, 0, &mut result));
+    assert!(result);
+
+    rvs_model_free(model);
+}
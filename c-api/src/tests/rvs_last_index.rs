@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn sample() {
+    let model = model_from_str("a = r{0, 10, 20, 30};");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut index = 99;
+    assert!(!rvs_last_index(model, handle, &mut index));
+    assert_eq!(index, 99);
+
+    for _ in 0..20 {
+        let value = rvs_next(model, handle);
+        assert!(rvs_last_index(model, handle, &mut index));
+        assert_eq!(index, (value / 10) as usize);
+    }
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_a_sample() {
+    let model = model_from_str("a = 5;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut index = 99;
+    rvs_next(model, handle);
+    assert!(!rvs_last_index(model, handle, &mut index));
+    assert!(!rvs_last_index(model, 2, &mut index));
+    assert_eq!(index, 99);
+
+    rvs_model_free(model);
+}
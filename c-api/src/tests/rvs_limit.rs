@@ -0,0 +1,154 @@
+use super::*;
+
+use crate::context::Context;
+
+use std::fs;
+
+fn get_limit(name: &str) -> Option<u64> {
+    let mut value = 0;
+    if rvs_get_limit(CString::new(name).unwrap().as_ptr(), &mut value) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn set_limit(context: *mut Context, name: &str, value: u64) -> bool {
+    rvs_set_limit(context, CString::new(name).unwrap().as_ptr(), value)
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(get_limit("max_resample_attempts"), Some(10_000));
+    assert_eq!(get_limit("max_import_depth"), Some(32));
+    assert_eq!(get_limit("default_seed"), Some(0));
+    assert_eq!(get_limit("value_width"), Some(32));
+    assert_eq!(get_limit("max_variables"), Some(u64::MAX));
+    assert_eq!(get_limit("max_children"), Some(u64::MAX));
+    assert_eq!(get_limit("max_nodes"), Some(u64::MAX));
+}
+
+#[test]
+fn unknown() {
+    let mut value = 99;
+    assert!(!rvs_get_limit(CString::new("unknown").unwrap().as_ptr(), &mut value));
+    assert_eq!(value, 99);
+
+    let error = rvs_error_new();
+    let context = context_new(error);
+    assert!(!set_limit(context, "unknown", 1));
+    rvs_context_free(context);
+    rvs_error_free(error);
+}
+
+#[test]
+fn out_of_range_and_not_settable() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    assert!(!set_limit(context, "max_resample_attempts", 0));
+    assert!(!set_limit(context, "max_resample_attempts", 1 << 32));
+    assert!(!set_limit(context, "value_width", 16));
+    assert!(!set_limit(context, "default_seed", 1));
+    assert!(set_limit(context, "max_resample_attempts", 1));
+
+    rvs_context_free(context);
+    rvs_error_free(error);
+}
+
+#[test]
+fn override_max_resample_attempts() {
+    let model = model_from_str_with("a = Pattern(1, 2, 3) except {1, 2};", |context| {
+        assert!(set_limit(context, "max_resample_attempts", 3))
+    });
+
+    assert_eq!(next_by_name(model, "a"), 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn override_max_import_depth() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.rvs"), "import b;\na = 1;\n").unwrap();
+    fs::write(dir.path().join("b.rvs"), "b = 2;\n").unwrap();
+    let search_path = CString::new(dir.path().to_str().unwrap()).unwrap();
+
+    let error = rvs_error_new();
+    let context = rvs_context_new(search_path.as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+    assert!(set_limit(context, "max_import_depth", 1));
+    rvs_parse(context, CString::new("import a;").unwrap().as_ptr(), error);
+    assert!(rvs_error_test(error));
+    assert!(get_error_message(error).contains("exceeds the maximum import depth of 1"));
+    rvs_context_free(context);
+    rvs_error_free(error);
+
+    let error = rvs_error_new();
+    let context = rvs_context_new(search_path.as_ptr(), 0, error);
+    assert!(set_limit(context, "max_import_depth", 2));
+    rvs_parse(context, CString::new("import a;").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+    rvs_context_free(context);
+    rvs_error_free(error);
+}
+
+fn parse_with_limit(name: &str, value: u64, s: &str) -> Option<String> {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    assert!(set_limit(context, name, value));
+    rvs_parse(context, CString::new(s).unwrap().as_ptr(), error);
+    let message = if rvs_error_test(error) {
+        Some(get_error_message(error))
+    } else {
+        None
+    };
+
+    rvs_context_free(context);
+    rvs_error_free(error);
+
+    message
+}
+
+#[test]
+fn override_max_variables() {
+    assert_eq!(parse_with_limit("max_variables", 2, "a = 1; b = 2;"), None);
+    assert_eq!(
+        parse_with_limit("max_variables", 2, "a = 1; b = 2;\nc = 3;"),
+        Some("error at 2:1: the number of variables exceeds the limit of 2".into())
+    );
+}
+
+#[test]
+fn override_max_children() {
+    assert_eq!(parse_with_limit("max_children", 3, "a = Pattern(1, 2, 3);"), None);
+    assert_eq!(
+        parse_with_limit("max_children", 3, "a = Pattern(1, 2, 3, 4);"),
+        Some("error at 1:1: `Pattern` has 4 arguments which exceeds the limit of 3".into())
+    );
+}
+
+#[test]
+fn override_max_children_when_transforming() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+    assert!(set_limit(context, "max_children", 3));
+
+    let model = rvs_model_new();
+    rvs_parse_and_transform(context, CString::new("a = s{4: 1};").unwrap().as_ptr(), model, error);
+    assert!(rvs_error_test(error));
+    assert!(get_error_message(error).contains("the pool of the sample has 4 entries"));
+
+    rvs_model_free(model);
+    rvs_error_free(error);
+}
+
+#[test]
+fn override_max_nodes() {
+    // Variable, BinaryOperation, Number, Number
+    assert_eq!(parse_with_limit("max_nodes", 4, "a = 1 + 2;"), None);
+    assert!(parse_with_limit("max_nodes", 3, "a = 1 + 2;")
+        .unwrap()
+        .contains("the number of AST nodes exceeds the limit of 3"));
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn diverges_mid_sequence() {
+    let model = model_from_str("a = [0, 0xffff_ffff]; b = Pattern(0, 1, 2, 3);");
+
+    for _ in 0..3 {
+        next_by_name(model, "a");
+        next_by_name(model, "b");
+    }
+
+    let clone = rvs_model_clone(model);
+
+    // Deterministic state is copied
+    assert_eq!(next_by_name(model, "b"), 3);
+    assert_eq!(next_by_name(clone, "b"), 3);
+    assert_eq!(next_by_name(clone, "b"), 0);
+
+    // Random state is independent
+    let original: Vec<u32> = (0..8).map(|_| next_by_name(model, "a")).collect();
+    let cloned: Vec<u32> = (0..8).map(|_| next_by_name(clone, "a")).collect();
+    assert_ne!(original, cloned);
+
+    rvs_model_free(model);
+    rvs_model_free(clone);
+}
+
+#[test]
+fn outlives_original() {
+    let model = model_from_str("a = Pattern(1, 2); b = a + 1;");
+    let clone = rvs_model_clone(model);
+    rvs_model_free(model);
+
+    let values: Vec<u32> = (0..4).map(|_| next_by_name(clone, "b")).collect();
+    assert_eq!(values, [2, 3, 2, 3]);
+
+    rvs_model_free(clone);
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+#[test]
+fn no_budget() {
+    let model = model_from_str("a = Pattern(1, 2);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let error = rvs_error_new();
+
+    let mut value = 0;
+    assert!(rvs_next_checked(model, handle, &mut value, error));
+    assert_eq!(value, 1);
+    assert!(rvs_next_checked(model, handle, &mut value, error));
+    assert_eq!(value, 2);
+    assert!(!rvs_error_test(error));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn budget_exceeded() {
+    let model = model_from_str("a = NoRepeat(r{1: 0, 999: 7});");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let error = rvs_error_new();
+
+    rvs_set_eval_budget(model, 5);
+
+    let mut value = 42;
+    let exceeded = (0..100).any(|_| {
+        value = 42;
+        !rvs_next_checked(model, handle, &mut value, error)
+    });
+
+    assert!(exceeded);
+    assert_eq!(value, 42);
+    assert_eq!(rvs_error_code(error), RVS_ERROR_BUDGET);
+
+    // The budget can be removed at run time and the variable remains usable
+    rvs_set_eval_budget(model, 0);
+    rvs_error_free(error);
+    let error = rvs_error_new();
+    assert!(rvs_next_checked(model, handle, &mut value, error));
+    assert!(value == 0 || value == 7);
+    assert!(!rvs_error_test(error));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn budget_applies_to_referenced_variables() {
+    let model = model_from_str("a = NoRepeat(r{1: 0, 999: 7}); b = a + 1;");
+    let handle = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    rvs_set_eval_budget(model, 1);
+
+    let mut value = 0;
+    assert!((0..100).any(|_| !rvs_next_checked(model, handle, &mut value, std::ptr::null_mut())));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn resample_limit_exceeded() {
+    let model = model_from_str_with("a = Pattern(1, 2) except {1};", |context| {
+        assert!(rvs_set_max_resample_attempts(context, 1));
+    });
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let error = rvs_error_new();
+
+    let mut value = 42;
+    assert!(!rvs_next_checked(model, handle, &mut value, error));
+    assert_eq!(value, 42);
+    assert_eq!(rvs_error_code(error), RVS_ERROR_CONSTRAINT);
+
+    // The variable remains usable
+    assert!(rvs_next_checked(model, handle, &mut value, std::ptr::null_mut()));
+    assert_eq!(value, 2);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut value = 42;
+    assert!(!rvs_next_checked(model, 0, &mut value, std::ptr::null_mut()));
+    assert_eq!(value, 42);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+fn next_ex(model: *mut rvs::Model, handle: u32) -> (bool, u32, bool, u32) {
+    let (mut value, mut done, mut prev) = (0xdead, true, 0xdead);
+    let found = rvs_next_ex(model, handle, &mut value, &mut done, &mut prev);
+
+    (found, value, done, prev)
+}
+
+#[test]
+fn matches_separate_calls() {
+    let expr = "a = Pattern(1, [2, 9], Sequence(3), 4);";
+    let ex = model_from_str(expr);
+    let separate = model_from_str(expr);
+    let handle = rvs_get(ex, CString::new("a").unwrap().as_ptr());
+    let separate_handle = rvs_get(separate, CString::new("a").unwrap().as_ptr());
+
+    for _ in 0..20 {
+        let prev = rvs_prev(separate, separate_handle);
+        let value = rvs_next(separate, separate_handle);
+        let done = rvs_done(separate, separate_handle);
+
+        assert_eq!(next_ex(ex, handle), (true, value, done, prev));
+    }
+
+    rvs_model_free(ex);
+    rvs_model_free(separate);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    assert_eq!(next_ex(model, 2), (false, 0, false, 0));
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut count = 0;
+    assert!(rvs_next_until(model, handle, 3, 10, &mut count));
+    assert_eq!(count, 3);
+    assert_eq!(rvs_prev(model, handle), 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn cap() {
+    let model = model_from_str("a = [0, 3];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut count = 42;
+    assert!(!rvs_next_until(model, handle, 4, 100, &mut count));
+    assert_eq!(count, 42);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut count = 42;
+    assert!(!rvs_next_until(model, 2, 1, 100, &mut count));
+    assert_eq!(count, 42);
+
+    rvs_model_free(model);
+}
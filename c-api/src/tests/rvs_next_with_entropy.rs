@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn reproducible() {
+    let model = model_from_str("a = [0, 0xffff_ffff];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let entropy = [1u8, 2, 3, 4];
+    let value = rvs_next_with_entropy(model, handle, entropy.as_ptr(), entropy.len());
+    rvs_next(model, handle);
+    assert_eq!(
+        rvs_next_with_entropy(model, handle, entropy.as_ptr(), entropy.len()),
+        value
+    );
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn null_empty_entropy() {
+    let model = model_from_str("a = [0, 0xffff_ffff];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let value = rvs_next_with_entropy(model, handle, std::ptr::null(), 0);
+    assert_eq!(rvs_next_with_entropy(model, handle, [].as_ptr(), 0), value);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 5;");
+
+    assert_eq!(rvs_next_with_entropy(model, 2, [1u8].as_ptr(), 1), 0);
+
+    rvs_model_free(model);
+}
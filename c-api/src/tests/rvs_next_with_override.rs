@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = [0, 1000]; b = a + 1;");
+    let handle = rvs_get(model, CString::new("b").unwrap().as_ptr());
+    let name = CString::new("a").unwrap();
+
+    for _ in 0..10 {
+        let mut result = 0;
+        assert!(rvs_next_with_override(model, handle, name.as_ptr(), 10, &mut result));
+        assert_eq!(result, 11);
+    }
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut result = 42;
+    assert!(!rvs_next_with_override(model, 2, CString::new("a").unwrap().as_ptr(), 10, &mut result));
+    assert_eq!(result, 42);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,42 @@
+use super::*;
+
+fn parse_and_transform(s: &str) -> (*mut rvs::Model, u32) {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    let model = rvs_model_new();
+
+    rvs_parse_and_transform(context, CString::new(s).unwrap().as_ptr(), model, error);
+
+    let code = rvs_error_code(error);
+    rvs_error_free(error);
+
+    (model, code)
+}
+
+#[test]
+fn basic() {
+    let (model, code) = parse_and_transform("a = 5; b = a + 1;");
+    assert_eq!(code, RVS_ERROR_NONE);
+
+    let handle = rvs_get(model, CString::new("b").unwrap().as_ptr());
+    assert_eq!(rvs_next(model, handle), 6);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn parse_step_failed() {
+    let (model, code) = parse_and_transform("a = ;");
+    assert_eq!(code, RVS_ERROR_PARSE);
+    assert_eq!(rvs_get(model, CString::new("a").unwrap().as_ptr()), 0);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn transform_step_failed() {
+    let (model, code) = parse_and_transform("a = b;");
+    assert_eq!(code, RVS_ERROR_TRANSFORM);
+
+    rvs_model_free(model);
+}
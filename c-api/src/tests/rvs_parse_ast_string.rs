@@ -0,0 +1,76 @@
+use super::*;
+
+use libc::c_char;
+
+fn ast_string(s: &str, search_path: &str) -> (String, usize, u32) {
+    let error = rvs_error_new();
+
+    let mut buf = [1 as c_char; 256];
+    let len = rvs_parse_ast_string(
+        CString::new(s).unwrap().as_ptr(),
+        CString::new(search_path).unwrap().as_ptr(),
+        buf.as_mut_ptr(),
+        buf.len(),
+        error,
+    );
+
+    let code = rvs_error_code(error);
+    rvs_error_free(error);
+
+    let ast = if buf[0] == 1 {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_owned()
+    };
+
+    (ast, len, code)
+}
+
+#[test]
+fn basic() {
+    let (ast, len, code) = ast_string("a = 1 + 2;", "");
+
+    assert_eq!(code, RVS_ERROR_NONE);
+    assert_eq!(
+        ast,
+        "[Variable(\"a\", BinaryOperation(Number(1), Add, Number(2)), Span { line: 1, column: 1 }, [])]"
+    );
+    assert_eq!(len, ast.len());
+}
+
+#[test]
+fn truncated() {
+    let error = rvs_error_new();
+    let mut buf = [1 as c_char; 8];
+    let len = rvs_parse_ast_string(
+        CString::new("a = 1;").unwrap().as_ptr(),
+        CString::new("").unwrap().as_ptr(),
+        buf.as_mut_ptr(),
+        buf.len(),
+        error,
+    );
+
+    assert_eq!(rvs_error_code(error), RVS_ERROR_NONE);
+    assert!(len >= buf.len());
+    assert_eq!(unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap(), "[Variab");
+
+    rvs_error_free(error);
+}
+
+#[test]
+fn parse_error() {
+    let (ast, len, code) = ast_string("a = ;", "");
+
+    assert_eq!(code, RVS_ERROR_PARSE);
+    assert_eq!(ast, "");
+    assert_eq!(len, 0);
+}
+
+#[test]
+fn invalid_search_path() {
+    let (ast, len, code) = ast_string("a = 1;", "/path/does/not/exist");
+
+    assert_eq!(code, RVS_ERROR_IO);
+    assert_eq!(ast, "");
+    assert_eq!(len, 0);
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+use crate::context::Context;
+
+fn parse_ex(context: *mut Context, s: &str) -> usize {
+    let error = rvs_error_new();
+    let mut defined_count = usize::MAX;
+
+    rvs_parse_ex(context, CString::new(s).unwrap().as_ptr(), error, &mut defined_count);
+    assert!(!rvs_error_test(error), "{}: {}", s, get_error_message(error));
+    rvs_error_free(error);
+
+    defined_count
+}
+
+fn context(search_path: &str) -> *mut Context {
+    let error = rvs_error_new();
+    let context = context_with_search_path(search_path, error);
+    rvs_error_free(error);
+
+    context
+}
+
+#[test]
+fn basic() {
+    let context = context("");
+
+    assert_eq!(parse_ex(context, "a = 1; b = [0, 1];"), 2);
+    assert_eq!(parse_ex(context, "// Nothing but a comment"), 0);
+    assert_eq!(parse_ex(context, ""), 0);
+    assert_eq!(parse_ex(context, "distinct(a, b)"), 0);
+
+    // Redefinitions count
+    assert_eq!(parse_ex(context, "a = 2;"), 1);
+
+    assert_eq!(parse_ex(context, "const C = 3; c = C;"), 2);
+
+    rvs_context_free(context);
+}
+
+#[test]
+fn import() {
+    let search_path = ::std::env::current_dir().unwrap().join("../examples");
+    let context = context(search_path.to_str().unwrap());
+
+    // `import.rvs` defines `b` and imports `basic.rvs` which defines `a`
+    assert_eq!(parse_ex(context, "import import;"), 2);
+
+    rvs_context_free(context);
+}
+
+#[test]
+fn error() {
+    let context = context("");
+    let error = rvs_error_new();
+    let mut defined_count = usize::MAX;
+
+    rvs_parse_ex(
+        context,
+        CString::new("a = 1; b = ;").unwrap().as_ptr(),
+        error,
+        &mut defined_count,
+    );
+
+    assert!(rvs_error_test(error));
+    assert_eq!(defined_count, 1);
+
+    rvs_error_free(error);
+    rvs_context_free(context);
+}
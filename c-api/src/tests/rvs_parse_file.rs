@@ -0,0 +1,67 @@
+use super::*;
+use crate::context::Context;
+
+fn context_new(search_path: &str, error: *mut Error) -> *mut Context {
+    let search_path = ::std::env::current_dir().unwrap().join(search_path);
+    let search_path = search_path.to_str().unwrap();
+
+    let context = rvs_context_new(CString::new(search_path).unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    context
+}
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = context_new("../examples", error);
+
+    rvs_parse_file(context, CString::new("basic.rvs").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    assert_eq!(next_by_name(model, "a"), 5);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn missing_file() {
+    let error = rvs_error_new();
+    let context = context_new("../examples", error);
+
+    rvs_parse_file(context, CString::new("missing.rvs").unwrap().as_ptr(), error);
+    assert!(rvs_error_test(error));
+    match unsafe { &(*error).kind } {
+        ErrorKind::Io(_) => (),
+        _ => panic!("expected an IO error"),
+    }
+
+    rvs_error_free(error);
+    rvs_context_free(context);
+}
+
+#[test]
+fn syntax_error() {
+    let error = rvs_error_new();
+    let context = context_new("tests/rvs_parse_file", error);
+
+    rvs_parse_file(
+        context,
+        CString::new("syntax_error.rvs").unwrap().as_ptr(),
+        error,
+    );
+    assert!(rvs_error_test(error));
+    match unsafe { &(*error).kind } {
+        ErrorKind::Rvs(_) => (),
+        _ => panic!("expected a parse error"),
+    }
+    assert_starts_with(get_error_message(error), "error at 3:1: expected one of");
+
+    rvs_error_free(error);
+    rvs_context_free(context);
+}
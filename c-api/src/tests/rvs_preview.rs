@@ -0,0 +1,122 @@
+use super::*;
+
+use libc::c_char;
+
+fn preview(s: &str, n: usize) -> (String, usize, u32) {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    let mut buf = [1 as c_char; 256];
+    let len = rvs_preview(
+        context,
+        CString::new(s).unwrap().as_ptr(),
+        n,
+        buf.as_mut_ptr(),
+        buf.len(),
+        error,
+    );
+
+    let code = rvs_error_code(error);
+    rvs_error_free(error);
+
+    let json = if buf[0] == 1 {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_owned()
+    };
+
+    (json, len, code)
+}
+
+#[test]
+fn constant_and_pattern() {
+    let (json, len, code) = preview("const C = 5; a = C; b = Pattern(1, 2, 3);", 4);
+
+    assert_eq!(code, RVS_ERROR_NONE);
+    assert_eq!(
+        json,
+        "{\"C\": {\"values\": [5, 5, 5, 5], \"first_done\": 0}, \
+         \"a\": {\"values\": [5, 5, 5, 5], \"first_done\": 0}, \
+         \"b\": {\"values\": [1, 2, 3, 1], \"first_done\": 2}}"
+    );
+    assert_eq!(len, json.len());
+}
+
+#[test]
+fn never_done() {
+    let (json, _, _) = preview("a = Sequence(10);", 3);
+
+    assert_eq!(json, "{\"a\": {\"values\": [0, 1, 2], \"first_done\": null}}");
+}
+
+#[test]
+fn parse_error() {
+    let (json, len, code) = preview("a = ;", 4);
+
+    assert_eq!(code, RVS_ERROR_PARSE);
+    assert_eq!(json, "");
+    assert_eq!(len, 0);
+}
+
+#[test]
+fn transform_error() {
+    let (json, len, code) = preview("a = b;", 4);
+
+    assert_eq!(code, RVS_ERROR_TRANSFORM);
+    assert_eq!(json, "");
+    assert_eq!(len, 0);
+}
+
+#[test]
+fn truncated() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    let mut buf = [0 as c_char; 8];
+    let len = rvs_preview(
+        context,
+        CString::new("a = 1;").unwrap().as_ptr(),
+        1,
+        buf.as_mut_ptr(),
+        buf.len(),
+        error,
+    );
+
+    let json = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert_eq!(json, "{\"a\": {");
+    assert!(len >= buf.len());
+
+    rvs_error_free(error);
+}
+
+#[test]
+fn hex_strings() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    let mut buf = [0 as c_char; 128];
+    let len = rvs_preview_ex(
+        context,
+        CString::new("a = Pattern(9, 255);").unwrap().as_ptr(),
+        2,
+        true,
+        buf.as_mut_ptr(),
+        buf.len(),
+        error,
+    );
+
+    let json = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert_eq!(json, "{\"a\": {\"values\": [\"0x9\", \"0xff\"], \"first_done\": 1}}");
+    assert_eq!(len, json.len());
+    assert!(!rvs_error_test(error));
+
+    rvs_error_free(error);
+}
+
+#[test]
+fn numbers_match_rust() {
+    let (json, _, _) = preview("a = [0, 100]; b = a + 1;", 8);
+    let model = rvs::parse(&Default::default(), "a = [0, 100]; b = a + 1;").unwrap();
+
+    assert_eq!(json, model.sample_to_json(8, rvs::JsonFormat::Number).unwrap());
+}
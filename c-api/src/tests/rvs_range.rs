@@ -0,0 +1,56 @@
+use super::*;
+
+#[test]
+fn widen() {
+    let model = model_from_str("a = [0, 10];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let (mut low, mut high) = (99, 99);
+    assert!(rvs_get_range(model, handle, &mut low, &mut high));
+    assert_eq!((low, high), (0, 10));
+
+    for _ in 0..100 {
+        assert!(rvs_next(model, handle) <= 10);
+    }
+
+    assert!(rvs_set_range(model, handle, 0, 100));
+    assert!(rvs_get_range(model, handle, &mut low, &mut high));
+    assert_eq!((low, high), (0, 100));
+
+    let values: Vec<u32> = (0..100).map(|_| rvs_next(model, handle)).collect();
+    assert!(values.iter().all(|&value| value <= 100));
+    assert!(values.iter().any(|&value| value > 10));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn kept_by_reset() {
+    let model = model_from_str("a = [0, 1];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert!(rvs_set_range(model, handle, 50, 50));
+    rvs_reset(model, handle);
+
+    assert_eq!(rvs_next(model, handle), 50);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_a_range() {
+    let model = model_from_str("a = [0, 10] + 1; b = 5;");
+
+    let (mut low, mut high) = (99, 99);
+    for name in &["a", "b"] {
+        let handle = rvs_get(model, CString::new(*name).unwrap().as_ptr());
+
+        assert!(!rvs_get_range(model, handle, &mut low, &mut high));
+        assert!(!rvs_set_range(model, handle, 0, 100));
+    }
+    assert!(!rvs_get_range(model, 3, &mut low, &mut high));
+    assert!(!rvs_set_range(model, 3, 0, 100));
+    assert_eq!((low, high), (99, 99));
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn reset() {
+    let model = model_from_str("a = Pattern(1, 2, 3); b = Pattern(4, 5);");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert_eq!(rvs_next(model, a), 1);
+    assert_eq!(rvs_next(model, a), 2);
+    assert_eq!(next_by_name(model, "b"), 4);
+
+    rvs_reset(model, a);
+    assert_eq!(rvs_prev(model, a), 0);
+    assert_eq!(rvs_next(model, a), 1);
+    assert_eq!(next_by_name(model, "b"), 5);
+
+    rvs_reset(model, 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn reset_all() {
+    let model = model_from_str("a = Pattern(1, 2, 3); b = Pattern(4, 5);");
+
+    assert_eq!(next_by_name(model, "a"), 1);
+    assert_eq!(next_by_name(model, "a"), 2);
+    assert_eq!(next_by_name(model, "b"), 4);
+
+    rvs_reset_all(model);
+    assert_eq!(next_by_name(model, "a"), 1);
+    assert_eq!(next_by_name(model, "b"), 4);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = [0, 0xffff];");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let expected: Vec<u32> = (0..10).map(|_| rvs_next(model, a)).collect();
+
+    rvs_reset_rng(model, a);
+    let actual: Vec<u32> = (0..10).map(|_| rvs_next(model, a)).collect();
+    assert_eq!(actual, expected);
+
+    rvs_reset_rng(model, 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn preserves_position() {
+    let model = model_from_str("a = Pattern([0, 0xffff], 7);");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let first = rvs_next(model, a);
+
+    rvs_reset_rng(model, a);
+    assert_eq!(rvs_prev(model, a), first);
+    assert_eq!(rvs_next(model, a), 7);
+    assert!(rvs_done(model, a));
+    assert_eq!(rvs_next(model, a), first);
+
+    rvs_model_free(model);
+}
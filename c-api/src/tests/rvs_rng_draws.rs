@@ -0,0 +1,37 @@
+use super::*;
+
+fn model_with_counting(s: &str) -> *mut rvs::Model {
+    model_from_str_with(s, |context| rvs_set_rng_draw_counting(context, true))
+}
+
+#[test]
+fn counted() {
+    let model = model_with_counting("a = [0, 9] + [0, 9];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut draws = 99;
+    assert!(rvs_rng_draws(model, handle, &mut draws));
+    assert_eq!(draws, 0);
+
+    for _ in 0..5 {
+        rvs_next(model, handle);
+    }
+    assert!(rvs_rng_draws(model, handle, &mut draws));
+    assert_eq!(draws, 10);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn disabled_or_invalid_handle() {
+    let model = model_from_str("a = [0, 9];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut draws = 99;
+    rvs_next(model, handle);
+    assert!(!rvs_rng_draws(model, handle, &mut draws));
+    assert!(!rvs_rng_draws(model, 2, &mut draws));
+    assert_eq!(draws, 99);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn passes() {
+    assert!(rvs_self_test());
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+fn done_sequence(aligned: bool) -> Vec<bool> {
+    let model = model_from_str_with("a = Pattern(0, 0) + Pattern(0, 0, 0);", |context| {
+        rvs_set_aligned_binary_done(context, aligned)
+    });
+
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let done = (0..6)
+        .map(|_| {
+            rvs_next(model, handle);
+            rvs_done(model, handle)
+        })
+        .collect();
+
+    rvs_model_free(model);
+
+    done
+}
+
+#[test]
+fn aligned() {
+    assert_eq!(done_sequence(true), [false, false, false, false, false, true]);
+}
+
+#[test]
+fn latched() {
+    assert_eq!(done_sequence(false), [false, false, true, true, true, true]);
+}
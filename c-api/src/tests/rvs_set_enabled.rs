@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = [0, 1000]; b = [0, 1000];");
+    let reference = model_from_str("a = [0, 1000]; b = [0, 1000];");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+    let reference_b = rvs_get(reference, CString::new("b").unwrap().as_ptr());
+
+    assert!(rvs_set_enabled(model, a, false));
+    for _ in 0..10 {
+        assert_eq!(rvs_next(model, a), 0);
+        assert_eq!(rvs_next(model, b), rvs_next(reference, reference_b));
+    }
+
+    assert!(rvs_set_disabled_value(model, a, 5));
+    assert_eq!(rvs_next(model, a), 5);
+
+    assert!(rvs_set_enabled(model, a, true));
+    rvs_next(model, a);
+    assert_eq!(rvs_next(model, b), rvs_next(reference, reference_b));
+
+    rvs_model_free(model);
+    rvs_model_free(reference);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    assert!(!rvs_set_enabled(model, 2, false));
+    assert!(!rvs_set_disabled_value(model, 2, 0));
+
+    rvs_model_free(model);
+}
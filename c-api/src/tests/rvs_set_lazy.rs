@@ -0,0 +1,64 @@
+use super::*;
+
+fn lazy_model_from_str(s: &str) -> *mut rvs::Model {
+    model_from_str_with(s, |context| rvs_set_lazy(context, true))
+}
+
+#[test]
+fn built_on_get() {
+    let model = lazy_model_from_str("a = 1; b = a + 1;");
+
+    assert_eq!(next_by_name(model, "b"), 2);
+    assert_eq!(next_by_name(model, "a"), 1);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_variable() {
+    let model = lazy_model_from_str("a = 1; b = unknown;");
+
+    assert_eq!(next_by_name(model, "a"), 1);
+    assert_eq!(rvs_get(model, CString::new("b").unwrap().as_ptr()), 0);
+
+    rvs_model_free(model);
+}
+
+/// Returns the handle of the only variable whose name starts with `prefix`
+fn find(model: *mut rvs::Model, prefix: &str) -> u32 {
+    let mut handle = 0;
+    let mut len = 0;
+    rvs_find_prefix(model, CString::new(prefix).unwrap().as_ptr(), &mut handle, 1, &mut len);
+    assert_eq!(len, 1);
+
+    handle
+}
+
+#[test]
+fn built_on_handle_use() {
+    let model = lazy_model_from_str("a = Pattern(5, 6);");
+
+    let handle = find(model, "a");
+    assert_eq!(rvs_next(model, handle), 5);
+    assert!(!rvs_done(model, handle));
+    assert_eq!(next_by_name(model, "a"), 6);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn handle_build_error() {
+    let model = lazy_model_from_str("b = unknown;");
+
+    let handle = find(model, "b");
+    assert_eq!(rvs_next(model, handle), 0);
+
+    let error = rvs_error_new();
+    let mut value = 7;
+    assert!(!rvs_next_checked(model, handle, &mut value, error));
+    assert_eq!(value, 7);
+    assert!(rvs_error_test(error));
+    rvs_error_free(error);
+
+    rvs_model_free(model);
+}
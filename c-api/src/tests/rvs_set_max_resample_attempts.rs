@@ -0,0 +1,43 @@
+use super::*;
+
+fn next_with_max_resample_attempts(s: &str, max_resample_attempts: u32) -> u32 {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    assert!(rvs_set_max_resample_attempts(context, max_resample_attempts));
+
+    rvs_parse(context, CString::new(s).unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let value = next_by_name(model, "a");
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+
+    value
+}
+
+#[test]
+fn within_limit() {
+    assert_eq!(
+        next_with_max_resample_attempts("a = Pattern(1, 2, 3) except {1, 2};", 3),
+        3
+    );
+}
+
+#[test]
+fn zero_rejected() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+
+    assert!(!rvs_set_max_resample_attempts(context, 0));
+    assert!(rvs_set_max_resample_attempts(context, 1));
+
+    rvs_context_free(context);
+    rvs_error_free(error);
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn masked() {
+    let model = model_from_str_with("a = 0x1234_5678; b = [0, 0xffff_ffff];", |context| {
+        rvs_set_output_mask(context, 0xfff)
+    });
+
+    assert_eq!(next_by_name(model, "a"), 0x678);
+    for _ in 0..100 {
+        assert!(next_by_name(model, "b") <= 0xfff);
+    }
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn producer() {
+    let error = rvs_error_new();
+    let context = context_new(error);
+    rvs_set_output_mask(context, 0xfff);
+    rvs_parse(context, CString::new("a = [0, 0xffff_ffff];").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let mut producer = unsafe { &*context }.spawn_producer("a", 4).unwrap();
+    for _ in 0..100 {
+        assert!(producer.next() <= 0xfff);
+    }
+    producer.stop();
+
+    rvs_context_free(context);
+    rvs_error_free(error);
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+use libc::c_void;
+use std::ptr;
+
+extern "C" fn double(value: u32, _user_data: *mut c_void) -> u32 {
+    value * 2
+}
+
+extern "C" fn count(value: u32, user_data: *mut c_void) -> u32 {
+    let calls = unsafe { &mut *(user_data as *mut u32) };
+    *calls += 1;
+
+    value
+}
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    rvs_set_value_transform(model, handle, Some(double), ptr::null_mut());
+
+    for expected in &[2, 4, 6, 2] {
+        assert_eq!(rvs_next(model, handle), *expected);
+        assert_eq!(rvs_prev(model, handle), *expected);
+    }
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn seen_by_references() {
+    let model = model_from_str("a = Pattern(1, 2, 3); b = a + a.prev;");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    rvs_set_value_transform(model, a, Some(double), ptr::null_mut());
+
+    assert_eq!(rvs_next(model, b), 4);
+    assert_eq!(rvs_next(model, b), 8);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn user_data() {
+    let model = model_from_str("a = [0, 10];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut calls: u32 = 0;
+    rvs_set_value_transform(
+        model,
+        handle,
+        Some(count),
+        &mut calls as *mut u32 as *mut c_void,
+    );
+
+    for _ in 0..10 {
+        rvs_next(model, handle);
+    }
+
+    rvs_model_free(model);
+
+    assert_eq!(calls, 10);
+}
+
+#[test]
+fn remove() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    rvs_set_value_transform(model, handle, Some(double), ptr::null_mut());
+    assert_eq!(rvs_next(model, handle), 2);
+
+    rvs_set_value_transform(model, handle, None, ptr::null_mut());
+    assert_eq!(rvs_next(model, handle), 2);
+    assert_eq!(rvs_prev(model, handle), 2);
+    assert_eq!(rvs_next(model, handle), 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    rvs_set_value_transform(model, 2, Some(double), ptr::null_mut());
+
+    rvs_model_free(model);
+}
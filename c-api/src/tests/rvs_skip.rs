@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert!(rvs_skip(model, handle, 4));
+    assert_eq!(rvs_prev(model, handle), 1);
+    assert_eq!(rvs_next(model, handle), 2);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn matches_next() {
+    let source = "a = [0, 1000] except {5};";
+    let skipped = model_from_str(source);
+    let stepped = model_from_str(source);
+    let skipped_handle = rvs_get(skipped, CString::new("a").unwrap().as_ptr());
+    let stepped_handle = rvs_get(stepped, CString::new("a").unwrap().as_ptr());
+
+    assert!(rvs_skip(skipped, skipped_handle, 100));
+    for _ in 0..100 {
+        rvs_next(stepped, stepped_handle);
+    }
+    assert_eq!(rvs_prev(skipped, skipped_handle), rvs_prev(stepped, stepped_handle));
+    assert_eq!(rvs_next(skipped, skipped_handle), rvs_next(stepped, stepped_handle));
+
+    rvs_model_free(skipped);
+    rvs_model_free(stepped);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    assert!(!rvs_skip(model, 2, 1));
+
+    rvs_model_free(model);
+}
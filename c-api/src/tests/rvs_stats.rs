@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn range() {
+    let model = model_from_str("a = [10, 20];");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let (mut min, mut max, mut mean) = (0, 0, 0);
+    assert!(!rvs_stats(model, handle, &mut min, &mut max, &mut mean));
+
+    rvs_set_stats_enabled(model, handle, true);
+    for _ in 0..10_000 {
+        rvs_next(model, handle);
+    }
+
+    assert!(rvs_stats(model, handle, &mut min, &mut max, &mut mean));
+    assert_eq!(min, 10);
+    assert_eq!(max, 20);
+    assert!((14..=16).contains(&mean));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let (mut min, mut max, mut mean) = (0, 0, 0);
+    assert!(!rvs_stats(model, 2, &mut min, &mut max, &mut mean));
+
+    rvs_model_free(model);
+}
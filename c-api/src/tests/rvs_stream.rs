@@ -0,0 +1,93 @@
+use super::*;
+
+use libc::c_void;
+use std::ptr;
+
+struct Collector {
+    values: Vec<(u32, bool)>,
+    limit: usize,
+}
+
+extern "C" fn collect(value: u32, done: bool, user_data: *mut c_void) -> bool {
+    let collector = unsafe { &mut *(user_data as *mut Collector) };
+    collector.values.push((value, done));
+
+    collector.values.len() < collector.limit
+}
+
+extern "C" fn never_called(_value: u32, _done: bool, _user_data: *mut c_void) -> bool {
+    panic!("the callback should not be called");
+}
+
+fn stream(
+    model: *mut rvs::Model,
+    handle: u32,
+    n: usize,
+    limit: usize,
+) -> (usize, Collector) {
+    let mut collector = Collector {
+        values: Vec::new(),
+        limit,
+    };
+    let count = rvs_stream(
+        model,
+        handle,
+        n,
+        collect,
+        &mut collector as *mut Collector as *mut c_void,
+    );
+
+    (count, collector)
+}
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let (count, collector) = stream(model, handle, 4, usize::MAX);
+
+    assert_eq!(count, 4);
+    assert_eq!(
+        collector.values,
+        vec![(1, false), (2, false), (3, true), (1, false)]
+    );
+    assert_eq!(rvs_prev(model, handle), 1);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn early_stop() {
+    let model = model_from_str("a = Sequence(100);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let (count, collector) = stream(model, handle, 10, 5);
+
+    assert_eq!(count, 5);
+    let values: Vec<u32> = collector.values.iter().map(|&(value, _)| value).collect();
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    assert_eq!(rvs_next(model, handle), 5);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn zero() {
+    let model = model_from_str("a = Sequence(100);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert_eq!(rvs_stream(model, handle, 0, never_called, ptr::null_mut()), 0);
+    assert_eq!(rvs_next(model, handle), 0);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    assert_eq!(rvs_stream(model, 2, 10, never_called, ptr::null_mut()), 0);
+
+    rvs_model_free(model);
+}
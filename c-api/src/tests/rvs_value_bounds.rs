@@ -0,0 +1,51 @@
+use super::*;
+
+fn bounds(s: &str) -> Option<(u32, u32)> {
+    let model = model_from_str(s);
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut min = 0xdead;
+    let mut max = 0xbeef;
+    let result = if rvs_value_bounds(model, handle, &mut min, &mut max) {
+        Some((min, max))
+    } else {
+        assert_eq!((min, max), (0xdead, 0xbeef));
+        None
+    };
+
+    rvs_model_free(model);
+
+    result
+}
+
+#[test]
+fn range() {
+    assert_eq!(bounds("a = [10, 0];"), Some((0, 10)));
+}
+
+#[test]
+fn pattern() {
+    assert_eq!(bounds("a = Pattern(4, [1, 2], 9);"), Some((1, 9)));
+}
+
+#[test]
+fn sum() {
+    assert_eq!(bounds("a = [0, 10] + [0, 5];"), Some((0, 15)));
+}
+
+#[test]
+fn unknown() {
+    assert_eq!(bounds("a = [0, 0xffff_ffff] + 1;"), None);
+    assert_eq!(bounds("a = Sequence(10);"), None);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut min = 0;
+    let mut max = 0;
+    assert!(!rvs_value_bounds(model, 0, &mut min, &mut max));
+
+    rvs_model_free(model);
+}
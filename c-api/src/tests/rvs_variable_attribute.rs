@@ -0,0 +1,65 @@
+use super::*;
+
+use libc::c_char;
+
+fn attribute(model: *mut rvs::Model, name: &str, key: &str) -> Option<String> {
+    let handle = rvs_get(model, CString::new(name).unwrap().as_ptr());
+    let mut buf = [0 as c_char; 16];
+
+    if rvs_variable_attribute(
+        model,
+        handle,
+        CString::new(key).unwrap().as_ptr(),
+        buf.as_mut_ptr(),
+        buf.len(),
+    ) {
+        let value = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Some(value.to_str().unwrap().to_owned())
+    } else {
+        None
+    }
+}
+
+#[test]
+fn basic() {
+    let model = model_from_str("@field(\"addr\") @width(32) a = 5;");
+
+    assert_eq!(attribute(model, "a", "field"), Some("addr".into()));
+    assert_eq!(attribute(model, "a", "width"), Some("32".into()));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_found() {
+    let model = model_from_str("@width(32) a = 5;");
+
+    assert_eq!(attribute(model, "a", "field"), None);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("@width(32) a = 5;");
+
+    let mut buf = [0 as c_char; 16];
+    assert!(!rvs_variable_attribute(
+        model,
+        2,
+        CString::new("width").unwrap().as_ptr(),
+        buf.as_mut_ptr(),
+        buf.len(),
+    ));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn truncated() {
+    let model = model_from_str("@field(\"a_very_long_field_name\") a = 5;");
+
+    assert_eq!(attribute(model, "a", "field"), Some("a_very_long_fie".into()));
+
+    rvs_model_free(model);
+}
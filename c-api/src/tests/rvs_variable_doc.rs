@@ -0,0 +1,35 @@
+use super::*;
+
+use libc::c_char;
+
+fn doc(model: *mut rvs::Model, name: &str) -> Option<String> {
+    let handle = rvs_get(model, CString::new(name).unwrap().as_ptr());
+    let mut buf = [0 as c_char; 64];
+
+    if rvs_variable_doc(model, handle, buf.as_mut_ptr(), buf.len()) {
+        let value = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Some(value.to_str().unwrap().to_owned())
+    } else {
+        None
+    }
+}
+
+#[test]
+fn basic() {
+    let model = model_from_str("/// The address\n/// Word aligned\na = [0, 7] << 2;\nb = 5;");
+
+    assert_eq!(doc(model, "a"), Some("The address\nWord aligned".into()));
+    assert_eq!(doc(model, "b"), None);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("/// A\na = 5;");
+
+    let mut buf = [0 as c_char; 16];
+    assert!(!rvs_variable_doc(model, 2, buf.as_mut_ptr(), buf.len()));
+
+    rvs_model_free(model);
+}
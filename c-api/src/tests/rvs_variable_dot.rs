@@ -0,0 +1,48 @@
+use super::*;
+
+use libc::c_char;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = 1 + 2;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut buf = [0 as c_char; 128];
+    let len = rvs_variable_dot(model, handle, buf.as_mut_ptr(), buf.len());
+
+    let dot = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert_eq!(
+        dot,
+        "digraph {\n    n0 [label=\"+\"];\n    n1 [label=\"0x1\"];\n    n0 -> n1;\n    \
+         n2 [label=\"0x2\"];\n    n0 -> n2;\n}\n"
+    );
+    assert_eq!(len, dot.len());
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn truncated() {
+    let model = model_from_str("a = 1 + 2;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut buf = [0 as c_char; 8];
+    let len = rvs_variable_dot(model, handle, buf.as_mut_ptr(), buf.len());
+
+    let dot = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert_eq!(dot, "digraph");
+    assert!(len >= buf.len());
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut buf = [1 as c_char; 8];
+    assert_eq!(rvs_variable_dot(model, 0, buf.as_mut_ptr(), buf.len()), 0);
+    assert_eq!(buf[0], 1);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,84 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = Pattern(1, 2, 3);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    for _ in 0..5 {
+        rvs_next(model, handle);
+    }
+
+    let mut prev = 0;
+    let mut done = true;
+    let mut count = 0;
+    let mut cycle_count = 0;
+    let mut cycle_position = 0;
+    assert!(rvs_variable_state(
+        model,
+        handle,
+        &mut prev,
+        &mut done,
+        &mut count,
+        &mut cycle_count,
+        &mut cycle_position
+    ));
+    assert_eq!(prev, 2);
+    assert!(!done);
+    assert_eq!(count, 5);
+    assert_eq!(cycle_count, 1);
+    assert_eq!(cycle_position, 1);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_cyclic() {
+    let model = model_from_str("a = 5;");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut prev = 1;
+    let mut done = true;
+    let mut count = 1;
+    let mut cycle_count = 1;
+    let mut cycle_position = 0;
+    assert!(rvs_variable_state(
+        model,
+        handle,
+        &mut prev,
+        &mut done,
+        &mut count,
+        &mut cycle_count,
+        &mut cycle_position
+    ));
+    assert_eq!(prev, 0);
+    assert!(!done);
+    assert_eq!(count, 0);
+    assert_eq!(cycle_count, 0);
+    assert_eq!(cycle_position, usize::MAX);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn invalid_handle() {
+    let model = model_from_str("a = 1;");
+
+    let mut prev = 7;
+    let mut done = false;
+    let mut count = 7;
+    let mut cycle_count = 7;
+    let mut cycle_position = 7;
+    assert!(!rvs_variable_state(
+        model,
+        2,
+        &mut prev,
+        &mut done,
+        &mut count,
+        &mut cycle_count,
+        &mut cycle_position
+    ));
+    assert_eq!((prev, done, count, cycle_count, cycle_position), (7, false, 7, 7, 7));
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+use libc::c_char;
+
+#[test]
+fn version() {
+    let mut buf = [0 as c_char; 32];
+    let len = rvs_version(buf.as_mut_ptr(), buf.len());
+
+    let version = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert!(!version.is_empty());
+    assert_eq!(version.len(), len);
+    assert_eq!(version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn version_truncated() {
+    let mut buf = [0 as c_char; 2];
+    let len = rvs_version(buf.as_mut_ptr(), buf.len());
+
+    let version = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert!(len >= buf.len());
+    assert_eq!(version.len(), 1);
+}
+
+#[test]
+fn has_feature() {
+    assert!(rvs_has_feature(CString::new("enum").unwrap().as_ptr()));
+    assert!(rvs_has_feature(CString::new("except").unwrap().as_ptr()));
+    assert!(!rvs_has_feature(CString::new("bogus").unwrap().as_ptr()));
+    assert!(!rvs_has_feature(CString::new("").unwrap().as_ptr()));
+}
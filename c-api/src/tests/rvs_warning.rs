@@ -0,0 +1,58 @@
+use super::*;
+
+use libc::c_char;
+
+fn warning_message(model: *mut rvs::Model, index: usize) -> Option<String> {
+    let mut buf = [0 as c_char; 128];
+    if !rvs_warning_message(model, index, buf.as_mut_ptr(), buf.len()) {
+        return None;
+    }
+
+    let s = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Some(s.to_str().unwrap().to_owned())
+}
+
+#[test]
+fn swapped_range() {
+    let model = model_from_str("a = [1, 0];\nb = [10, 5];");
+
+    assert_eq!(rvs_warning_count(model), 2);
+    assert_eq!(
+        warning_message(model, 0).unwrap(),
+        "warning at 1:1: the range [0x1, 0x0] has a low bound greater than its high bound and is treated as [0x0, 0x1]"
+    );
+    assert!(warning_message(model, 1).unwrap().starts_with("warning at 2:1: "));
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn no_warnings() {
+    let model = model_from_str("a = [0, 1];");
+
+    assert_eq!(rvs_warning_count(model), 0);
+    assert_eq!(warning_message(model, 0), None);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn out_of_range() {
+    let model = model_from_str("a = [1, 0];");
+
+    let mut buf = [0x55 as c_char; 8];
+    assert!(!rvs_warning_message(model, 1, buf.as_mut_ptr(), buf.len()));
+    assert_eq!(buf, [0x55 as c_char; 8]);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn clear() {
+    let model = model_from_str("a = [1, 0];");
+
+    rvs_warnings_clear(model);
+    assert_eq!(rvs_warning_count(model), 0);
+
+    rvs_model_free(model);
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let model = model_from_str("a = WeightedSample(3: 0, 1: 1);");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut weights = [0u32; 4];
+    let mut len = 0;
+    assert!(rvs_weights(model, handle, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert_eq!(len, 2);
+    assert_eq!(weights[..len], [3, 1]);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn raw_percentages() {
+    let model = model_from_str("a = {25%: 0, 75%: 1};");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut weights = [0u32; 2];
+    let mut len = 0;
+    assert!(rvs_weights(model, handle, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert_eq!(weights, [25, 75]);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn exceeds_cap() {
+    let model = model_from_str("a = r{1: 0, 2: 1, 3: 2};");
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    let mut weights = [0u32; 2];
+    let mut len = 0;
+    assert!(rvs_weights(model, handle, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert_eq!(len, 3);
+    assert_eq!(weights, [1, 2]);
+
+    assert!(rvs_weights(model, handle, std::ptr::null_mut(), 0, &mut len));
+    assert_eq!(len, 3);
+
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_weighted() {
+    let model = model_from_str("a = [0, 1]; b = {3: 0, 1: 1} + 1;");
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    let mut weights = [7u32; 2];
+    let mut len = 7;
+    assert!(!rvs_weights(model, a, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert!(!rvs_weights(model, b, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert!(!rvs_weights(model, 2, weights.as_mut_ptr(), weights.len(), &mut len));
+    assert_eq!(weights, [7, 7]);
+    assert_eq!(len, 7);
+
+    rvs_model_free(model);
+}
@@ -8,18 +8,18 @@ use std::ffi::CStr;
 use tempfile::tempdir;
 
 fn assert_contents_eq(actual: &Path, expected: &Path) {
-    let mut actual_file = File::open(actual).expect(&format!("Cannot open {:?}", actual));
-    let mut expected_file = File::open(expected).expect(&format!("Cannot open {:?}", expected));
+    let mut actual_file = File::open(actual).unwrap_or_else(|_| panic!("Cannot open {:?}", actual));
+    let mut expected_file = File::open(expected).unwrap_or_else(|_| panic!("Cannot open {:?}", expected));
 
     let mut actual_contents = String::new();
     let mut expected_contents = String::new();
 
     actual_file
         .read_to_string(&mut actual_contents)
-        .expect(&format!("Cannot open {:?}", actual));
+        .unwrap_or_else(|_| panic!("Cannot open {:?}", actual));
     expected_file
         .read_to_string(&mut expected_contents)
-        .expect(&format!("Cannot open {:?}", expected));
+        .unwrap_or_else(|_| panic!("Cannot open {:?}", expected));
 
     difference::assert_diff!(&actual_contents, &expected_contents, " ", 0);
 }
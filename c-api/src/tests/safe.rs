@@ -0,0 +1,82 @@
+use crate::safe::*;
+use crate::error::RVS_ERROR_PARSE;
+
+#[test]
+fn workflow() {
+    let mut context = RvsContext::new("", 0).unwrap();
+    context.parse("a=5;").unwrap();
+    let mut model = context.transform().unwrap();
+
+    let handle = model.find("a").unwrap();
+    assert!(!model.done(handle));
+    assert_eq!(model.next(handle), 5);
+    assert!(model.done(handle));
+    assert_eq!(model.prev(handle), 5);
+}
+
+#[test]
+fn multiple_parses() {
+    let mut context = RvsContext::new("", 0).unwrap();
+    context.parse("a = Pattern(1, 2);").unwrap();
+    context.parse("b = a + 10;").unwrap();
+    let mut model = context.transform().unwrap();
+
+    let b = model.find("b").unwrap();
+    assert_eq!(model.next(b), 11);
+    assert_eq!(model.next(b), 12);
+}
+
+#[test]
+fn not_found() {
+    let mut context = RvsContext::new("", 0).unwrap();
+    context.parse("a=5;").unwrap();
+    let model = context.transform().unwrap();
+
+    assert_eq!(model.find("b"), None);
+    assert_eq!(model.find("a\0b"), None);
+}
+
+#[test]
+fn parse_error() {
+    let mut context = RvsContext::new("", 0).unwrap();
+
+    let error = context.parse("a = 5 +;").unwrap_err();
+    assert_eq!(error.kind.code(), RVS_ERROR_PARSE);
+    assert!(error.to_string().starts_with("error at 1:8"), "{}", error);
+}
+
+#[test]
+fn transform_error() {
+    let mut context = RvsContext::new("", 0).unwrap();
+    context.parse("a = b;").unwrap();
+
+    assert!(context.transform().is_err());
+}
+
+#[test]
+fn invalid_search_path() {
+    assert!(RvsContext::new("/does/not/exist", 0).is_err());
+}
+
+#[test]
+fn interior_nul() {
+    let mut context = RvsContext::new("", 0).unwrap();
+
+    assert!(context.parse("a = 5;\0").is_err());
+}
+
+#[test]
+fn spawn_producer() {
+    let mut context = RvsContext::new("", 3).unwrap();
+    context.parse("a = [0, 0xffff_ffff];").unwrap();
+
+    let mut producer = context.spawn_producer("a", 4).unwrap();
+    let actual: Vec<u32> = (0..100).map(|_| producer.next()).collect();
+    producer.stop();
+
+    let mut model = context.transform().unwrap();
+    let a = model.find("a").unwrap();
+    let expected: Vec<u32> = (0..100).map(|_| model.next(a)).collect();
+
+    assert_eq!(expected, actual);
+}
@@ -0,0 +1,128 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use libc::c_char;
+use std::ffi::CStr;
+
+use crate::buffer;
+
+/// DSL and API capabilities supported by this library
+///
+/// Queried by bindings via `rvs_has_feature` to degrade gracefully against older libraries.
+const FEATURES: &[&str] = &[
+    "abs_diff",
+    "accumulate",
+    "addcarry",
+    "aligned_binary_done",
+    "attributes",
+    "bitreverse",
+    "bits",
+    "byteswap",
+    "checkpoint",
+    "const",
+    "context_new_from_env",
+    "current",
+    "cycle_count",
+    "cycle_position",
+    "distinct",
+    "distinct_values",
+    "doc_comments",
+    "done_edge",
+    "dont_care",
+    "dot",
+    "enabled",
+    "enum",
+    "enum_introspection",
+    "error_code",
+    "eval_budget",
+    "except",
+    "find_prefix",
+    "fixed_order_cycle",
+    "freeze",
+    "get_checked",
+    "get_seed",
+    "graycode",
+    "group",
+    "import",
+    "in",
+    "in_order_first_cycle",
+    "is_deterministic",
+    "iter_values",
+    "last_index",
+    "lazy_transform",
+    "let",
+    "limits",
+    "max_resample_attempts",
+    "model_clone",
+    "mulmod",
+    "next_ex",
+    "next_until",
+    "next_with_entropy",
+    "next_with_override",
+    "output_mask",
+    "pair",
+    "parse_and_transform",
+    "parse_ast_string",
+    "parse_ex",
+    "parse_file",
+    "parse_limits",
+    "percent_weights",
+    "preview",
+    "range_bounds",
+    "range_profiles",
+    "repeat",
+    "reset",
+    "reset_rng",
+    "rng_draws",
+    "self_test",
+    "shuffle",
+    "size_suffix",
+    "skip",
+    "stats",
+    "stream",
+    "sum_mean",
+    "tagged_handles",
+    "value_bounds",
+    "value_transform",
+    "variable_state",
+    "warnings",
+    "weighted_group",
+    "weights",
+    "zipf",
+];
+
+/// Copies the library version into a buffer
+///
+/// See `rvs_has_feature` for querying individual capabilities.
+///
+/// Returns the length of the version string excluding the null terminator.  The version is
+/// truncated if the return value is greater than or equal to `len`.
+#[no_mangle]
+pub extern "C" fn rvs_version(buf: *mut c_char, len: usize) -> usize {
+    buffer::write_str(env!("CARGO_PKG_VERSION"), buf, len)
+}
+
+/// Returns true if the library supports the named feature
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_has_feature(name: *const c_char) -> bool {
+    assert!(!name.is_null());
+
+    let name = unsafe { CStr::from_ptr(name) };
+
+    match name.to_str() {
+        Ok(name) => FEATURES.contains(&name),
+        Err(_) => false,
+    }
+}
+
+/// Returns true if evaluation matches golden values recorded with a fixed source and seed
+///
+/// Detects drift in the RNG or in the implementation of expressions E.g. due to a dependency
+/// update.  See `rvs::self_test`.
+#[no_mangle]
+pub extern "C" fn rvs_self_test() -> bool {
+    rvs::self_test()
+}
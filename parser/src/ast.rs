@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOpcode {
     Or,
     Xor,
@@ -9,9 +9,15 @@ pub enum BinaryOpcode {
     Shr,
     Add,
     Sub,
+    /// Absolute difference E.g. `a <-> b` is `|a - b|`
+    AbsDiff,
     Mul,
     Div,
     Mod,
+    /// 1 if less than, else 0
+    Lt,
+    /// 1 if greater than, else 0
+    Gt,
 }
 
 #[derive(Debug, Clone)]
@@ -20,7 +26,7 @@ pub enum UnaryOpcode {
     Neg,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Type {
     Pattern,
     Sequence,
@@ -28,33 +34,173 @@ pub enum Type {
     Expand,
     Done,
     Once,
+    Bits,
+    Shuffle,
+    MulMod,
+    Accumulate,
+    /// A rolling FNV-1a hash of a sub-expression E.g. `Hash(a)`
+    Hash,
+    /// A running timestamp with jittered steps E.g. `Timestamp(100, 10)`
+    Timestamp,
+    AddCarry,
+    ByteSwap,
+    BitReverse,
+    /// The number of set bits of a sub-expression E.g. `PopCount([0, 0xff])`
+    PopCount,
+    GrayCode,
+    /// A counter that wraps to 0 at a modulus E.g. `ModCounter(2, 4)`
+    ModCounter,
+    /// Evenly spaced values from a start to an end E.g. `Ramp(0, 100, 5)`
+    Ramp,
+    /// Selection via cumulative percentages E.g. `CDF({10: 0, 30: 1, 100: 2})`
+    ///
+    /// The arguments alternate cumulative percentages and expressions.
+    Cdf,
+    /// A percent chance of one expression, else another E.g. `Maybe(80, [0, 9], 0xff)`
+    Maybe,
+    Sum,
+    Mean,
+    /// The minimum of the last values of a sub-expression E.g. `WindowMin([0, 9], 4)`
+    WindowMin,
+    /// The maximum of the last values of a sub-expression E.g. `WindowMax([0, 9], 4)`
+    WindowMax,
+    OneHot,
+    Decode,
+    NoRepeat,
+    Repeat,
+    /// Holds each value for a random number of calls E.g. `HoldFor([0, 9], [2, 5])`
+    HoldFor,
+    /// Avoids the last `k` values E.g. `AvoidRecent([0, 15], 4)`
+    AvoidRecent,
+    /// Zipfian distributed indices E.g. `Zipf(100, 3, 2)` for `s = 1.5`
+    Zipf,
+    /// A normal distribution truncated to a range E.g. `TruncNormal(50, 10, 0, 100)`
+    TruncNormal,
+    /// Poisson distributed counts with a rational mean E.g. `Poisson(5, 2)`
+    Poisson,
+    /// A random walk with saturation E.g. `Walk(100, [-5, 5])`
+    Walk,
+    /// A range with a non-uniform sampling profile E.g. `[0, 100]: linear_up` or `[0, 100]~normal`
+    ///
+    /// `~` is an alias of `:`.  Both parse to the same node and display as `:`.
+    ProfiledRange(RangeProfile),
 }
 
-#[derive(Debug)]
+/// How values are sampled from a range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RangeProfile {
+    /// All values are equally likely
+    Uniform,
+    /// Likelihood increases linearly from the low bound to the high bound
+    LinearUp,
+    /// Likelihood decreases linearly from the low bound to the high bound
+    LinearDown,
+    /// Normally distributed around the midpoint with the bounds at three standard deviations
+    Normal,
+    /// Likelihood increases linearly from each bound to the midpoint
+    Triangular,
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum Replacement {
     With,
     Without,
+    /// Without replacement where the first cycle follows declaration order
+    WithoutInOrder,
+    /// Without replacement where no value repeats within a cycle
+    WithoutDistinctValues,
+    /// Without replacement where every cycle follows declaration order
+    WithoutFixedOrder,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum VariableMethod {
     Next,
     Prev,
     Copy,
+    /// 1 if the variable indicated done on its most recent call to `next`, 0 otherwise
+    Done,
 }
 
-#[derive(Debug)]
+/// Source location of a statement or reference
+///
+/// Lines and columns are 1-indexed and relative to the file (or string) the statement was parsed
+/// from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A `//` comment retained by `Parser::parse_with_comments`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The comment text including the leading `//` and excluding the line ending
+    pub text: String,
+    /// The location of the leading `//`
+    pub span: Span,
+    /// `true` if the comment follows code on the same line E.g. `a = 1; // note`
+    pub trailing: bool,
+}
+
+/// A key/value pair attached to a variable E.g. `@width(32)`
+///
+/// Attributes are not evaluated.  Numeric values are stored in decimal.  Attributes without a
+/// value (e.g. `@signed`) have an empty value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone)]
 pub enum Node {
     Number(u32),
+    /// A literal with don't-care bits E.g. `0b10xx` or `0x1?`
+    ///
+    /// Holds the value of the known bits and a mask of the don't-care bits.
+    DontCare(u32, u32),
     UnaryOperation(UnaryOpcode, Box<Node>),
     BinaryOperation(Box<Node>, BinaryOpcode, Box<Node>),
-    Variable(String, Box<Node>),
-    Enum(String, Vec<Box<Node>>),
+    Variable(String, Box<Node>, Span, Vec<Attribute>),
+    Constant(String, Box<Node>, Span),
+    Enum(String, Vec<Box<Node>>, Span),
     EnumMember(String, Option<Box<Node>>),
     Type(Type, Vec<Box<Node>>),
     Weighted(Replacement, Vec<Box<Node>>),
     WeightedSample(u32, Box<Node>),
-    RIdentifier(String, VariableMethod),
+    /// A weighted sample with a percentage weight E.g. `70%: <expr>`
+    PercentWeightedSample(u32, Box<Node>),
+    /// A weighted sample with a probability weight E.g. `0.25: <expr>`
+    ///
+    /// Holds the probability as a numerator and a power of 10 denominator.
+    ProbabilityWeightedSample(u32, u32, Box<Node>),
+    /// A reference to a variable, constant, or enum member
+    ///
+    /// Holds the location of the reference.
+    RIdentifier(String, VariableMethod, Span),
+    /// A variable with more than one method E.g. `a.copy.next`
+    MethodChain(String, Vec<VariableMethod>, Span),
+    Except(Box<Node>, Vec<Box<Node>>),
+    In(Box<Node>, Vec<Box<Node>>),
+    /// `~{<expr>, ...} within [<low>, <high>]`
+    ///
+    /// Holds the excluded values followed by the bounds.
+    Complement(Vec<Box<Node>>, Box<Node>, Box<Node>),
+    /// `distinct(<variable>, ...);`
+    Distinct(Vec<String>, Span),
+    /// `pair(<variable>, <variable>, <rho>);`
+    Pair(String, String, Box<Node>, Span),
+    /// `gate <variable> by <variable>;`
+    Gate(String, String, Span),
+    /// `group <name> { <item> ... }`
+    Group(String, Vec<Box<Node>>, Span),
+    /// `let <name> = <binding> in <body>`
+    Let(String, Box<Node>, Box<Node>),
+    /// `PatternFile('<path>')`
+    ///
+    /// The path is as written.  It is resolved via the search path during transform.
+    PatternFile(::std::path::PathBuf),
 }
 
 /// An abstraction above Node to implement `import`
@@ -75,6 +221,40 @@ pub enum Item {
     /// We can't use normal Rust error handling techniques due to abstraction by rust-peg.
     /// Instead, embed an Item::ImportErrors on a import error.
     ImportError(::std::path::PathBuf, ::std::io::Error),
+
+    /// An `import` of a file that failed to parse
+    ImportParseError(::std::path::PathBuf, String),
+
+    /// An `import` of a file that is currently being imported
+    ImportCycle(::std::path::PathBuf),
+
+    /// An `import` nested deeper than the maximum import depth
+    ImportDepthExceeded(::std::path::PathBuf, usize),
+
+    /// An `import` path that references an unset environment variable
+    ///
+    /// Contains the unexpanded path and the name of the variable.
+    ImportUnsetVariable(String, String),
+}
+
+impl Span {
+    /// Converts a byte offset into `source` to a line and column
+    pub fn from_offset(source: &str, offset: usize) -> Span {
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = match before.rfind('\n') {
+            Some(index) => before[index + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+
+        Span { line, column }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
 }
 
 impl fmt::Display for BinaryOpcode {
@@ -87,15 +267,45 @@ impl fmt::Display for BinaryOpcode {
             BinaryOpcode::Shr => ">>",
             BinaryOpcode::Add => "+",
             BinaryOpcode::Sub => "-",
+            BinaryOpcode::AbsDiff => "<->",
             BinaryOpcode::Mul => "*",
             BinaryOpcode::Div => "/",
             BinaryOpcode::Mod => "%",
+            BinaryOpcode::Lt => "<",
+            BinaryOpcode::Gt => ">",
         };
 
         write!(f, "{}", operator)
     }
 }
 
+impl fmt::Display for RangeProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let profile = match *self {
+            RangeProfile::Uniform => "uniform",
+            RangeProfile::LinearUp => "linear_up",
+            RangeProfile::LinearDown => "linear_down",
+            RangeProfile::Normal => "normal",
+            RangeProfile::Triangular => "triangular",
+        };
+
+        write!(f, "{}", profile)
+    }
+}
+
+impl fmt::Display for VariableMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let method = match *self {
+            VariableMethod::Next => "next",
+            VariableMethod::Prev => "prev",
+            VariableMethod::Copy => "copy",
+            VariableMethod::Done => "done",
+        };
+
+        write!(f, "{}", method)
+    }
+}
+
 impl fmt::Display for UnaryOpcode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let operator = match *self {
@@ -0,0 +1,606 @@
+//! A compact binary encoding of the AST
+//!
+//! Allows hosts to cache parse results.  The encoding starts with a magic number and a format
+//! version.  The version is incremented whenever the encoding changes (e.g. a new `Type`) so stale
+//! caches are rejected instead of misread.  Integers are little-endian.  Strings and lists are
+//! prefixed with their length as a `u32`.
+
+use crate::ast::{
+    Attribute, BinaryOpcode, Node, RangeProfile, Replacement, Span, Type, UnaryOpcode,
+    VariableMethod,
+};
+use crate::error::{ParseError, ParseResult};
+
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+const MAGIC: &[u8; 4] = b"RVSA";
+
+/// The version of the encoding
+pub const FORMAT_VERSION: u32 = 11;
+
+/// Encodes nodes in the binary format
+pub fn encode(nodes: &[Box<Node>]) -> Vec<u8> {
+    let mut writer = Writer(Vec::new());
+
+    writer.0.extend_from_slice(MAGIC);
+    writer.u32(FORMAT_VERSION);
+    writer.nodes(nodes);
+
+    writer.0
+}
+
+/// Decodes nodes encoded by `encode`
+///
+/// Returns an error if `bytes` is not in the binary format, was encoded by a different version of
+/// the format, or is truncated.
+pub fn decode(bytes: &[u8]) -> ParseResult<Vec<Box<Node>>> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(ParseError::new("not a serialized AST".into()));
+    }
+
+    let version = reader.u32()?;
+    if version != FORMAT_VERSION {
+        return Err(ParseError::new(format!(
+            "serialized AST format version {} is not supported (expected {})",
+            version, FORMAT_VERSION
+        )));
+    }
+
+    let nodes = reader.nodes()?;
+    if reader.position != bytes.len() {
+        return Err(reader.error("trailing bytes"));
+    }
+
+    Ok(nodes)
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("length exceeds u32"));
+    }
+
+    fn str(&mut self, s: &str) {
+        self.len(s.len());
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    fn span(&mut self, span: Span) {
+        self.len(span.line);
+        self.len(span.column);
+    }
+
+    fn strs(&mut self, strs: &[String]) {
+        self.len(strs.len());
+        for s in strs {
+            self.str(s);
+        }
+    }
+
+    fn nodes(&mut self, nodes: &[Box<Node>]) {
+        self.len(nodes.len());
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match *node {
+            Node::Number(value) => {
+                self.u8(0);
+                self.u32(value);
+            }
+            Node::DontCare(value, mask) => {
+                self.u8(1);
+                self.u32(value);
+                self.u32(mask);
+            }
+            Node::UnaryOperation(ref op, ref a) => {
+                self.u8(2);
+                self.u8(encode_unary_opcode(op));
+                self.node(a);
+            }
+            Node::BinaryOperation(ref a, ref op, ref b) => {
+                self.u8(3);
+                self.node(a);
+                self.u8(encode_binary_opcode(op));
+                self.node(b);
+            }
+            Node::Variable(ref name, ref a, span, ref attributes) => {
+                self.u8(4);
+                self.str(name);
+                self.node(a);
+                self.span(span);
+                self.len(attributes.len());
+                for attribute in attributes {
+                    self.str(&attribute.key);
+                    self.str(&attribute.value);
+                }
+            }
+            Node::Constant(ref name, ref a, span) => {
+                self.u8(5);
+                self.str(name);
+                self.node(a);
+                self.span(span);
+            }
+            Node::Enum(ref name, ref members, span) => {
+                self.u8(6);
+                self.str(name);
+                self.nodes(members);
+                self.span(span);
+            }
+            Node::EnumMember(ref name, ref value) => {
+                self.u8(7);
+                self.str(name);
+                match *value {
+                    Some(ref value) => {
+                        self.u8(1);
+                        self.node(value);
+                    }
+                    None => self.u8(0),
+                }
+            }
+            Node::Type(ref typ, ref args) => {
+                self.u8(8);
+                match *typ {
+                    Type::ProfiledRange(profile) => {
+                        self.u8(PROFILED_RANGE);
+                        self.u8(encode_range_profile(profile));
+                    }
+                    ref typ => self.u8(encode_type(typ)),
+                }
+                self.nodes(args);
+            }
+            Node::Weighted(replacement, ref entries) => {
+                self.u8(9);
+                self.u8(encode_replacement(replacement));
+                self.nodes(entries);
+            }
+            Node::WeightedSample(weight, ref a) => {
+                self.u8(10);
+                self.u32(weight);
+                self.node(a);
+            }
+            Node::PercentWeightedSample(weight, ref a) => {
+                self.u8(11);
+                self.u32(weight);
+                self.node(a);
+            }
+            Node::ProbabilityWeightedSample(numerator, denominator, ref a) => {
+                self.u8(12);
+                self.u32(numerator);
+                self.u32(denominator);
+                self.node(a);
+            }
+            Node::RIdentifier(ref name, ref method, span) => {
+                self.u8(13);
+                self.str(name);
+                self.u8(encode_method(method));
+                self.span(span);
+            }
+            Node::MethodChain(ref name, ref methods, span) => {
+                self.u8(14);
+                self.str(name);
+                self.len(methods.len());
+                for method in methods {
+                    self.u8(encode_method(method));
+                }
+                self.span(span);
+            }
+            Node::Except(ref a, ref excluded) => {
+                self.u8(15);
+                self.node(a);
+                self.nodes(excluded);
+            }
+            Node::In(ref a, ref members) => {
+                self.u8(16);
+                self.node(a);
+                self.nodes(members);
+            }
+            Node::Distinct(ref names, span) => {
+                self.u8(17);
+                self.strs(names);
+                self.span(span);
+            }
+            Node::Pair(ref a, ref b, ref rho, span) => {
+                self.u8(18);
+                self.str(a);
+                self.str(b);
+                self.node(rho);
+                self.span(span);
+            }
+            Node::Gate(ref a, ref b, span) => {
+                self.u8(23);
+                self.str(a);
+                self.str(b);
+                self.span(span);
+            }
+            Node::Group(ref name, ref items, span) => {
+                self.u8(19);
+                self.str(name);
+                self.nodes(items);
+                self.span(span);
+            }
+            Node::Let(ref name, ref binding, ref body) => {
+                self.u8(20);
+                self.str(name);
+                self.node(binding);
+                self.node(body);
+            }
+            Node::PatternFile(ref path) => {
+                self.u8(21);
+                self.str(&path.to_string_lossy());
+            }
+            Node::Complement(ref excluded, ref l, ref r) => {
+                self.u8(22);
+                self.nodes(excluded);
+                self.node(l);
+                self.node(r);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(format!(
+            "invalid serialized AST at byte {}: {}",
+            self.position, message
+        ))
+    }
+
+    fn take(&mut self, len: usize) -> ParseResult<&'a [u8]> {
+        if self.bytes.len() - self.position < len {
+            return Err(self.error("unexpected end of input"));
+        }
+
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> ParseResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> ParseResult<u32> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn len(&mut self) -> ParseResult<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    fn span(&mut self) -> ParseResult<Span> {
+        Ok(Span {
+            line: self.len()?,
+            column: self.len()?,
+        })
+    }
+
+    fn strings(&mut self) -> ParseResult<Vec<String>> {
+        (0..self.len()?).map(|_| self.string()).collect()
+    }
+
+    fn nodes(&mut self) -> ParseResult<Vec<Box<Node>>> {
+        (0..self.len()?).map(|_| self.node()).collect()
+    }
+
+    /// Decodes a code with `decode` and reports an error naming `what` if the code is unknown
+    fn code<T>(&mut self, what: &str, decode: fn(u8) -> Option<T>) -> ParseResult<T> {
+        let code = self.u8()?;
+
+        decode(code).ok_or_else(|| self.error(&format!("unknown {} {}", what, code)))
+    }
+
+    fn node(&mut self) -> ParseResult<Box<Node>> {
+        let node = match self.u8()? {
+            0 => Node::Number(self.u32()?),
+            1 => Node::DontCare(self.u32()?, self.u32()?),
+            2 => Node::UnaryOperation(self.code("unary opcode", decode_unary_opcode)?, self.node()?),
+            3 => Node::BinaryOperation(
+                self.node()?,
+                self.code("binary opcode", decode_binary_opcode)?,
+                self.node()?,
+            ),
+            4 => {
+                let name = self.string()?;
+                let a = self.node()?;
+                let span = self.span()?;
+                let attributes = (0..self.len()?)
+                    .map(|_| {
+                        Ok(Attribute {
+                            key: self.string()?,
+                            value: self.string()?,
+                        })
+                    })
+                    .collect::<ParseResult<_>>()?;
+
+                Node::Variable(name, a, span, attributes)
+            }
+            5 => Node::Constant(self.string()?, self.node()?, self.span()?),
+            6 => Node::Enum(self.string()?, self.nodes()?, self.span()?),
+            7 => {
+                let name = self.string()?;
+                let value = match self.u8()? {
+                    0 => None,
+                    1 => Some(self.node()?),
+                    _ => return Err(self.error("invalid enum member")),
+                };
+
+                Node::EnumMember(name, value)
+            }
+            8 => {
+                let typ = match self.u8()? {
+                    PROFILED_RANGE => {
+                        Type::ProfiledRange(self.code("range profile", decode_range_profile)?)
+                    }
+                    code => decode_type(code)
+                        .ok_or_else(|| self.error(&format!("unknown type {}", code)))?,
+                };
+
+                Node::Type(typ, self.nodes()?)
+            }
+            9 => Node::Weighted(self.code("replacement", decode_replacement)?, self.nodes()?),
+            10 => Node::WeightedSample(self.u32()?, self.node()?),
+            11 => Node::PercentWeightedSample(self.u32()?, self.node()?),
+            12 => Node::ProbabilityWeightedSample(self.u32()?, self.u32()?, self.node()?),
+            13 => Node::RIdentifier(
+                self.string()?,
+                self.code("method", decode_method)?,
+                self.span()?,
+            ),
+            14 => {
+                let name = self.string()?;
+                let methods = (0..self.len()?)
+                    .map(|_| self.code("method", decode_method))
+                    .collect::<ParseResult<_>>()?;
+
+                Node::MethodChain(name, methods, self.span()?)
+            }
+            15 => Node::Except(self.node()?, self.nodes()?),
+            16 => Node::In(self.node()?, self.nodes()?),
+            17 => Node::Distinct(self.strings()?, self.span()?),
+            18 => Node::Pair(self.string()?, self.string()?, self.node()?, self.span()?),
+            19 => Node::Group(self.string()?, self.nodes()?, self.span()?),
+            20 => Node::Let(self.string()?, self.node()?, self.node()?),
+            21 => Node::PatternFile(PathBuf::from(self.string()?)),
+            22 => Node::Complement(self.nodes()?, self.node()?, self.node()?),
+            23 => Node::Gate(self.string()?, self.string()?, self.span()?),
+            tag => return Err(self.error(&format!("unknown node {}", tag))),
+        };
+
+        Ok(Box::new(node))
+    }
+}
+
+/// The code of `Type::ProfiledRange`.  Followed by the code of the profile.
+const PROFILED_RANGE: u8 = 255;
+
+fn encode_type(typ: &Type) -> u8 {
+    match *typ {
+        Type::Pattern => 0,
+        Type::Sequence => 1,
+        Type::Range => 2,
+        Type::Expand => 3,
+        Type::Done => 4,
+        Type::Once => 5,
+        Type::Bits => 6,
+        Type::Shuffle => 7,
+        Type::MulMod => 8,
+        Type::Accumulate => 9,
+        Type::Hash => 10,
+        Type::Timestamp => 11,
+        Type::AddCarry => 12,
+        Type::ByteSwap => 13,
+        Type::BitReverse => 14,
+        Type::GrayCode => 15,
+        Type::ModCounter => 16,
+        Type::Maybe => 17,
+        Type::Sum => 18,
+        Type::Mean => 19,
+        Type::OneHot => 20,
+        Type::Decode => 21,
+        Type::NoRepeat => 22,
+        Type::Repeat => 23,
+        Type::AvoidRecent => 24,
+        Type::Zipf => 25,
+        Type::TruncNormal => 26,
+        Type::Poisson => 27,
+        Type::Walk => 28,
+        Type::Ramp => 29,
+        Type::HoldFor => 30,
+        Type::Cdf => 31,
+        Type::PopCount => 32,
+        Type::WindowMin => 33,
+        Type::WindowMax => 34,
+        Type::ProfiledRange(_) => PROFILED_RANGE,
+    }
+}
+
+fn decode_type(code: u8) -> Option<Type> {
+    Some(match code {
+        0 => Type::Pattern,
+        1 => Type::Sequence,
+        2 => Type::Range,
+        3 => Type::Expand,
+        4 => Type::Done,
+        5 => Type::Once,
+        6 => Type::Bits,
+        7 => Type::Shuffle,
+        8 => Type::MulMod,
+        9 => Type::Accumulate,
+        10 => Type::Hash,
+        11 => Type::Timestamp,
+        12 => Type::AddCarry,
+        13 => Type::ByteSwap,
+        14 => Type::BitReverse,
+        15 => Type::GrayCode,
+        16 => Type::ModCounter,
+        17 => Type::Maybe,
+        18 => Type::Sum,
+        19 => Type::Mean,
+        20 => Type::OneHot,
+        21 => Type::Decode,
+        22 => Type::NoRepeat,
+        23 => Type::Repeat,
+        24 => Type::AvoidRecent,
+        25 => Type::Zipf,
+        26 => Type::TruncNormal,
+        27 => Type::Poisson,
+        28 => Type::Walk,
+        29 => Type::Ramp,
+        30 => Type::HoldFor,
+        31 => Type::Cdf,
+        32 => Type::PopCount,
+        33 => Type::WindowMin,
+        34 => Type::WindowMax,
+        _ => return None,
+    })
+}
+
+/// Returns the code of a range profile in the binary format
+pub fn encode_range_profile(profile: RangeProfile) -> u8 {
+    match profile {
+        RangeProfile::Uniform => 0,
+        RangeProfile::LinearUp => 1,
+        RangeProfile::LinearDown => 2,
+        RangeProfile::Normal => 3,
+        RangeProfile::Triangular => 4,
+    }
+}
+
+/// Returns the range profile of a code returned by `encode_range_profile`
+pub fn decode_range_profile(code: u8) -> Option<RangeProfile> {
+    Some(match code {
+        0 => RangeProfile::Uniform,
+        1 => RangeProfile::LinearUp,
+        2 => RangeProfile::LinearDown,
+        3 => RangeProfile::Normal,
+        4 => RangeProfile::Triangular,
+        _ => return None,
+    })
+}
+
+fn encode_replacement(replacement: Replacement) -> u8 {
+    match replacement {
+        Replacement::With => 0,
+        Replacement::Without => 1,
+        Replacement::WithoutInOrder => 2,
+        Replacement::WithoutDistinctValues => 3,
+        Replacement::WithoutFixedOrder => 4,
+    }
+}
+
+fn decode_replacement(code: u8) -> Option<Replacement> {
+    Some(match code {
+        0 => Replacement::With,
+        1 => Replacement::Without,
+        2 => Replacement::WithoutInOrder,
+        3 => Replacement::WithoutDistinctValues,
+        4 => Replacement::WithoutFixedOrder,
+        _ => return None,
+    })
+}
+
+fn encode_method(method: &VariableMethod) -> u8 {
+    match *method {
+        VariableMethod::Next => 0,
+        VariableMethod::Prev => 1,
+        VariableMethod::Copy => 2,
+        VariableMethod::Done => 3,
+    }
+}
+
+fn decode_method(code: u8) -> Option<VariableMethod> {
+    Some(match code {
+        0 => VariableMethod::Next,
+        1 => VariableMethod::Prev,
+        2 => VariableMethod::Copy,
+        3 => VariableMethod::Done,
+        _ => return None,
+    })
+}
+
+/// Returns the code of a unary opcode in the binary format
+pub fn encode_unary_opcode(op: &UnaryOpcode) -> u8 {
+    match *op {
+        UnaryOpcode::Inv => 0,
+        UnaryOpcode::Neg => 1,
+    }
+}
+
+/// Returns the unary opcode of a code returned by `encode_unary_opcode`
+pub fn decode_unary_opcode(code: u8) -> Option<UnaryOpcode> {
+    Some(match code {
+        0 => UnaryOpcode::Inv,
+        1 => UnaryOpcode::Neg,
+        _ => return None,
+    })
+}
+
+/// Returns the code of a binary opcode in the binary format
+pub fn encode_binary_opcode(op: &BinaryOpcode) -> u8 {
+    match *op {
+        BinaryOpcode::Or => 0,
+        BinaryOpcode::Xor => 1,
+        BinaryOpcode::And => 2,
+        BinaryOpcode::Shl => 3,
+        BinaryOpcode::Shr => 4,
+        BinaryOpcode::Add => 5,
+        BinaryOpcode::Sub => 6,
+        BinaryOpcode::AbsDiff => 7,
+        BinaryOpcode::Mul => 8,
+        BinaryOpcode::Div => 9,
+        BinaryOpcode::Mod => 10,
+        BinaryOpcode::Lt => 11,
+        BinaryOpcode::Gt => 12,
+    }
+}
+
+/// Returns the binary opcode of a code returned by `encode_binary_opcode`
+pub fn decode_binary_opcode(code: u8) -> Option<BinaryOpcode> {
+    Some(match code {
+        0 => BinaryOpcode::Or,
+        1 => BinaryOpcode::Xor,
+        2 => BinaryOpcode::And,
+        3 => BinaryOpcode::Shl,
+        4 => BinaryOpcode::Shr,
+        5 => BinaryOpcode::Add,
+        6 => BinaryOpcode::Sub,
+        7 => BinaryOpcode::AbsDiff,
+        8 => BinaryOpcode::Mul,
+        9 => BinaryOpcode::Div,
+        10 => BinaryOpcode::Mod,
+        11 => BinaryOpcode::Lt,
+        12 => BinaryOpcode::Gt,
+        _ => return None,
+    })
+}
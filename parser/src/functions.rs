@@ -0,0 +1,110 @@
+/// The names of all functions in the grammar
+pub const FUNCTIONS: &[&str] = &[
+    "Accumulate",
+    "AddCarry",
+    "AvoidRecent",
+    "BitReverse",
+    "Bits",
+    "ByteSwap",
+    "CDF",
+    "Choose",
+    "Decode",
+    "Done",
+    "Expand",
+    "GrayCode",
+    "Hash",
+    "HoldFor",
+    "Maybe",
+    "Mean",
+    "ModCounter",
+    "MulMod",
+    "NoRepeat",
+    "Once",
+    "OneHot",
+    "Pattern",
+    "PatternFile",
+    "Poisson",
+    "PopCount",
+    "Ramp",
+    "Repeat",
+    "Sequence",
+    "Shuffle",
+    "Sum",
+    "Timestamp",
+    "TruncNormal",
+    "Walk",
+    "WeightedSample",
+    "WindowMax",
+    "WindowMin",
+    "Zipf",
+];
+
+/// Returns the message for a call of the unknown function `name`
+///
+/// Suggests the nearest function by edit distance if it is near enough to be a likely typo.
+/// Otherwise lists all functions.
+pub fn unknown_function_message(name: &str) -> String {
+    match nearest(name) {
+        Some(function) => format!("unknown function '{}', did you mean '{}'?", name, function),
+        None => format!(
+            "unknown function '{}', expected one of {}",
+            name,
+            FUNCTIONS.join(", ")
+        ),
+    }
+}
+
+/// Returns the function nearest to `name` ignoring case if within a third of the length of
+/// `name` (rounded up)
+fn nearest(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let max_distance = name.chars().count().div_ceil(3);
+
+    FUNCTIONS
+        .iter()
+        .map(|function| (edit_distance(&name, &function.to_lowercase()), *function))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, function)| function)
+}
+
+/// Returns the Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + if a == b { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("smple", "sample"), 1);
+        assert_eq!(edit_distance("ab", "ba"), 2);
+    }
+
+    #[test]
+    fn suggestion() {
+        assert_eq!(nearest("WeightedSmple"), Some("WeightedSample"));
+        assert_eq!(nearest("pattern"), Some("Pattern"));
+        assert_eq!(nearest("Shufle"), Some("Shuffle"));
+        assert_eq!(nearest("Frobnicate"), None);
+    }
+}
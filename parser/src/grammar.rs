@@ -1,10 +1,10 @@
 use std::str::FromStr;
 use std::char;
-use std::path::{Path, MAIN_SEPARATOR};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
 use std::fs::File;
 use std::io::prelude::*;
 
-use crate::sourcepaths::SourcePaths;
+use crate::sourcepaths::{expand_env_vars, SourcePaths};
 
 use crate::ast::{
     VariableMethod,
@@ -14,44 +14,155 @@ use crate::ast::{
     Node,
     Item,
     Replacement,
+    RangeProfile,
+    Span,
+    Attribute,
+    Comment,
 };
 
 pub use grammar::*;
 
-peg::parser!{grammar grammar() for str {
-    rule import_path() -> &'input str
+/// Returns the `///` doc comment immediately preceding the statement at `offset` as a `doc`
+/// attribute
+///
+/// The statement must start its line.  Consecutive doc comment lines are joined with newlines.
+/// `////` starts a regular comment.
+fn doc_comment(source: &str, offset: usize) -> Option<Attribute> {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    if !before[line_start..].trim().is_empty() {
+        return None;
+    }
+
+    let mut lines: Vec<&str> = before[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with("///") && !line.starts_with("////"))
+        .map(|line| line[3..].strip_prefix(' ').unwrap_or(&line[3..]))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+
+    Some(Attribute { key: "doc".into(), value: lines.join("\n") })
+}
+
+/// Returns all `//` comments in `source` in source order
+///
+/// `//` inside string literals does not start a comment.  `source` is expected to parse
+/// successfully.
+pub fn comments(source: &str) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    let mut line_start = 0;
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                while let Some((_, inner)) = chars.next() {
+                    match inner {
+                        '\\' => { chars.next(); }
+                        _ if inner == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            '\n' => line_start = offset + 1,
+            '/' if source[offset + 1..].starts_with('/') => {
+                let end = source[offset..]
+                    .find(['\n', '\r', '\u{2028}', '\u{2029}'])
+                    .map_or(source.len(), |index| offset + index);
+                comments.push(Comment {
+                    text: source[offset..end].into(),
+                    span: Span::from_offset(source, offset),
+                    trailing: !source[line_start..offset].trim().is_empty(),
+                });
+                while chars.peek().is_some_and(|&(index, _)| index < end) {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    comments
+}
+
+// `source` must be the same string as the input.  It is used to convert byte offsets to lines and
+// columns.
+peg::parser!{grammar grammar(source: &str) for str {
+    rule bare_import_path() -> &'input str
         = quiet!{$([':' | 'a'..='z' | 'A'..='Z' | '_']+)} / expected!("import path")
 
+    // A backslash escapes the next character E.g. `\'` or `\\`
+    rule escaped_char() -> char
+        = "\\" !['\n'] c:$([_]) { c.chars().next().unwrap() }
+
+    rule single_quoted_path() -> String
+        = "'" s:(escaped_char() / !['\'' | '\\' | '\n'] c:$([_]) { c.chars().next().unwrap() })* "'" {
+            s.into_iter().collect()
+        }
+
+    rule double_quoted_path() -> String
+        = "\"" s:(escaped_char() / !['"' | '\\' | '\n'] c:$([_]) { c.chars().next().unwrap() })* "\"" {
+            s.into_iter().collect()
+        }
+
+    // Quoted paths are used as is after expanding environment variables E.g. `$RVS_LIB`.  Bare
+    // paths use `::` as a separator and get an `.rvs` extension.
+    //
+    // Fails with the unexpanded path and the name of the variable if a variable is unset.
+    rule import_path() -> Result<PathBuf, (String, String)>
+        = s:(single_quoted_path() / double_quoted_path()) {
+            expand_env_vars(&s).map_err(|name| (s, name))
+        }
+        / s:bare_import_path() {
+            Ok(Path::new(&s.replace("::", MAIN_SEPARATOR_STR)).with_extension("rvs"))
+        }
+
     rule import(import_paths: &mut SourcePaths) -> Item
-        = "import" _ s:import_path() _ ";" {
-            let path = Path::new(&s.replace("::", &MAIN_SEPARATOR.to_string())).with_extension("rvs");
-            match import_paths.find(&path) {
-                Err(e) => {
-                    Item::ImportError(path.to_path_buf(), e)
-                }
+        = "import" _ path:import_path() _ ";" {
+            match path {
+                Err((path, name)) => Item::ImportUnsetVariable(path, name),
                 Ok(path) => {
-                    if import_paths.enter_import(&path) {
-                        match File::open(&path) {
-                            Err(e) => {
-                                Item::ImportError(path.to_path_buf(), e)
-                            }
-                            Ok(mut file) => {
-                                let mut contents = String::new();
-                                match file.read_to_string(&mut contents) {
+                    match import_paths.find(&path) {
+                        Err(e) => {
+                            Item::ImportError(path.to_path_buf(), e)
+                        }
+                        Ok(path) => {
+                            if import_paths.is_importing(&path) {
+                                Item::ImportCycle(path)
+                            } else if import_paths.at_max_depth() {
+                                Item::ImportDepthExceeded(path, import_paths.max_depth())
+                            } else if import_paths.enter_import(&path) {
+                                match File::open(&path) {
                                     Err(e) => {
                                         Item::ImportError(path.to_path_buf(), e)
                                     }
-                                    Ok(_) => {
-                                        let result = Item::Multiple(items(&contents, import_paths).unwrap());
-                                        import_paths.leave_import();
-
-                                        result
+                                    Ok(mut file) => {
+                                        let mut contents = String::new();
+                                        match file.read_to_string(&mut contents) {
+                                            Err(e) => {
+                                                Item::ImportError(path.to_path_buf(), e)
+                                            }
+                                            Ok(_) => {
+                                                let result = match items(&contents, &contents, import_paths) {
+                                                    Ok(items) => Item::Multiple(items),
+                                                    Err(e) => Item::ImportParseError(path.to_path_buf(), e.to_string()),
+                                                };
+                                                import_paths.leave_import();
+
+                                                result
+                                            }
+                                        }
                                     }
                                 }
+                            } else {
+                                Item::Multiple(Vec::new())
                             }
                         }
-                    } else {
-                        Item::Multiple(Vec::new())
                     }
                 }
             }
@@ -60,73 +171,162 @@ peg::parser!{grammar grammar() for str {
     rule identifier() -> &'input str
         = quiet!{$(['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':']*)} / expected!("variable name")
 
+    // An identifier optionally qualified by group names E.g. `net.a`
+    rule qualified_identifier() -> &'input str
+        = $(identifier() ("." !(variable_method() identifier_end()) identifier())*)
+
     rule type_name() -> &'input str
         = quiet!{$(['A'..='Z'] ['a'..='z' | 'A'..='Z' | '0'..='9']*)} / expected!("type name")
 
     rule dec_digit() = ['0'..='9']
     rule dec_number() -> u32
-        = s:$(dec_digit() (dec_digit() / "_")*) {
+        = s:$(dec_digit() (dec_digit() / "_")*) {?
             let stripped = &str::replace(s, "_", "");
-            u32::from_str(stripped).unwrap()
+            u32::from_str(stripped).or(Err("integer literal within 32 bits"))
+        }
+
+    // Binary (K, M, G) and decimal (k, m, g) multipliers for sizes E.g. 4K == 4096
+    rule size_suffix() -> u32
+        = "K" { 1 << 10 }
+        / "M" { 1 << 20 }
+        / "G" { 1 << 30 }
+        / "k" { 1_000 }
+        / "m" { 1_000_000 }
+        / "g" { 1_000_000_000 }
+
+    rule suffixed_dec_number() -> u32
+        = a:dec_number() b:size_suffix() {?
+            a.checked_mul(b).ok_or("suffixed integer literal within 32 bits")
         }
 
     rule hex_digit() = ['0'..='9' | 'a'..='f' | 'A'..='F']
     rule hex_number() -> u32
-        = "0" ['x' | 'X'] s:$(hex_digit() (hex_digit() / "_")*) {
+        = "0" ['x' | 'X'] s:$(hex_digit() (hex_digit() / "_")*) {?
             let stripped = &str::replace(s, "_", "");
-            u32::from_str_radix(stripped, 16).unwrap()
+            u32::from_str_radix(stripped, 16).or(Err("integer literal within 32 bits"))
+        }
+
+    rule bin_number() -> u32
+        = "0" ['b' | 'B'] s:$(['0' | '1'] ['0' | '1' | '_']*) !['x' | 'X'] {?
+            let stripped = &str::replace(s, "_", "");
+            u32::from_str_radix(stripped, 2).or(Err("integer literal within 32 bits"))
+        }
+
+    // Binary digits of `x` and hexadecimal digits of `?` are don't-care bits E.g. `0b10xx`
+    rule dont_care_number() -> Box<Node>
+        = "0" ['b' | 'B'] s:$(['0' | '1' | 'x' | 'X'] ['0' | '1' | 'x' | 'X' | '_']*) {?
+            dont_care(s, 2, &['x', 'X'])
+        }
+        / "0" ['x' | 'X'] s:$((hex_digit() / "?") (hex_digit() / "?" / "_")*) {?
+            dont_care(s, 16, &['?'])
         }
 
     rule number() -> Box<Node>
         = u:hex_number() { Box::new(Node::Number(u)) }
+        / u:bin_number() { Box::new(Node::Number(u)) }
+        / u:suffixed_dec_number() { Box::new(Node::Number(u)) }
         / u:dec_number() { Box::new(Node::Number(u)) }
 
-    rule r_identifier() -> Box<Node>
-        = a:identifier() b:variable_method_call()? {
-            let method = if let Some(method) = b {
-                method
-            } else {
-                VariableMethod::Next
-            };
+    rule identifier_end() = !['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':']
+
+    rule let_binding() -> Box<Node>
+        = "let" identifier_end() _ name:identifier() _ "=" _ binding:expr() _ "in" identifier_end() _ body:expr() {
+            Box::new(Node::Let(name.into(), binding, body))
+        }
 
-            Box::new(Node::RIdentifier(a.to_owned(), method))
+    rule r_identifier() -> Box<Node>
+        = start:position!() a:qualified_identifier() b:variable_method_call()* {
+            let span = Span::from_offset(source, start);
+            match b.len() {
+                0 => Box::new(Node::RIdentifier(a.to_owned(), VariableMethod::Next, span)),
+                1 => Box::new(Node::RIdentifier(a.to_owned(), b[0].clone(), span)),
+                _ => Box::new(Node::MethodChain(a.to_owned(), b, span)),
+            }
         }
 
     rule variable_method_call() -> VariableMethod
         = "." a:variable_method() { a }
 
     rule variable_method() -> VariableMethod
-        = "prev" { VariableMethod::Prev }
+        = "next" { VariableMethod::Next }
+        / "prev" { VariableMethod::Prev }
         / "copy" { VariableMethod::Copy }
+        / "done" { VariableMethod::Done }
 
     rule typ() -> Box<Node>
-        = pattern()
+        = pattern_file()
+        / pattern()
         / range()
         / weighted()
+        / choose()
+        / weighted_sample_function()
+        / maybe()
         / sequence()
         / done()
         / once()
+        / bits()
+        / shuffle()
+        / mulmod()
+        / accumulate()
+        / hash()
+        / timestamp()
+        / addcarry()
+        / byteswap()
+        / bitreverse()
+        / popcount()
+        / onehot()
+        / decode()
+        / norepeat()
+        / avoidrecent()
+        / repeat()
+        / holdfor()
+        / zipf()
+        / trunc_normal()
+        / poisson()
+        / walk()
+        / graycode()
+        / modcounter()
+        / ramp()
+        / cdf()
+        / sum()
+        / mean()
+        / window_min()
+        / window_max()
+
+    rule value_set() -> Vec<Box<Node>>
+        = "{" _ a:expr() ** ("," _) optional_trailing_comma() _ "}" { a }
 
     rule expr() -> Box<Node> = precedence!{
+        x:(@) _ "except" _ y:value_set() { Box::new(Node::Except(x, y)) }
+        x:(@) _ "in" _ y:value_set() { Box::new(Node::In(x, y)) }
+        --
         x:(@) _ "|" _  y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Or, y)) }
         --
         x:(@) _ "^" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Xor, y)) }
         --
         x:(@) _ "&" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::And, y)) }
         --
+        x:(@) _ "<" !("<" / "->") _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Lt, y)) }
+        x:(@) _ ">" !['>'] _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Gt, y)) }
+        --
         x:(@) _ "<<" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Shl, y)) }
         x:(@) _ ">>" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Shr, y)) }
         --
         x:(@) _ "+" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Add, y)) }
         x:(@) _ "-" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Sub, y)) }
+        x:(@) _ "<->" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::AbsDiff, y)) }
         --
         x:(@) _ "*" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Mul, y)) }
         x:(@) _ "/" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Div, y)) }
         x:(@) _ "%" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Mod, y)) }
         --
+        v:weighted_choice() { v }
         "(" _ v:expr() _ ")" { v }
+        v:let_binding() { v }
+        v:complement() { v }
         "~" _ v:@ { Box::new(Node::UnaryOperation(UnaryOpcode::Inv, v)) }
         "-" _ v:@ { Box::new(Node::UnaryOperation(UnaryOpcode::Neg, v)) }
+        v:dont_care_number() { v }
         v:number() { v }
         v:typ() { v }
         v:r_identifier() { v }
@@ -134,16 +334,85 @@ peg::parser!{grammar grammar() for str {
 
     rule item(import_paths: &mut SourcePaths) -> Item
         = enum()
+        / constant()
+        / distinct()
+        / pair()
+        / gate()
+        / group()
         / variable()
         / import(import_paths)
 
+    rule group() -> Item
+        = start:position!() "group" identifier_end() _ name:identifier() _ "{" _ items:group_item() ** _ _ "}" {
+            Item::Single(
+                Box::new(Node::Group(name.into(), items, Span::from_offset(source, start)))
+            )
+        }
+
+    // Same as `item` but without `import`
+    rule group_item() -> Box<Node>
+        = item:(enum() / constant() / distinct() / pair() / gate() / group() / variable()) {
+            match item {
+                Item::Single(node) => node,
+                _ => unreachable!("group items are always single nodes"),
+            }
+        }
+
+    rule distinct() -> Item
+        = start:position!() "distinct" _ "(" _ a:qualified_identifier() ++ ("," _) optional_trailing_comma() _ ")" _ ";" {
+            Item::Single(
+                Box::new(Node::Distinct(
+                    a.into_iter().map(String::from).collect(),
+                    Span::from_offset(source, start),
+                ))
+            )
+        }
+
+    rule pair() -> Item
+        = start:position!() "pair" _ "(" _ a:qualified_identifier() _ "," _ b:qualified_identifier() _ "," _ rho:expr() optional_trailing_comma() _ ")" _ ";" {
+            Item::Single(
+                Box::new(Node::Pair(a.into(), b.into(), rho, Span::from_offset(source, start)))
+            )
+        }
+
+    rule gate() -> Item
+        = start:position!() "gate" identifier_end() _ a:qualified_identifier() _ "by" identifier_end() _ b:qualified_identifier() _ ";" {
+            Item::Single(
+                Box::new(Node::Gate(a.into(), b.into(), Span::from_offset(source, start)))
+            )
+        }
+
+    rule constant() -> Item
+        = start:position!() "const" identifier_end() _ lhs:qualified_identifier() _ "=" _ rhs:expr() _ ";" {
+            Item::Single(
+                Box::new(Node::Constant(lhs.into(), rhs, Span::from_offset(source, start)))
+            )
+        }
+
+    rule string_literal() -> &'input str
+        = "\"" s:$((!['"' | '\n'] [_])*) "\"" { s }
+
+    rule attribute_value() -> String
+        = s:string_literal() { s.into() }
+        / u:hex_number() { u.to_string() }
+        / u:suffixed_dec_number() { u.to_string() }
+        / u:dec_number() { u.to_string() }
+
+    rule attribute() -> Attribute
+        = "@" key:identifier() value:(_ "(" _ v:attribute_value() _ ")" { v })? {
+            Attribute { key: key.into(), value: value.unwrap_or_default() }
+        }
+
     rule variable() -> Item
-        = lhs:identifier() _ "=" _ rhs:expr() _ ";" {
+        = start:position!() attributes:(attribute() ** _) _ lhs:qualified_identifier() _ "=" _ rhs:expr() _ ";" {
+            let attributes = doc_comment(source, start).into_iter().chain(attributes).collect();
+
             Item::Single(
-                Box::new(Node::Variable(lhs.into(), rhs))
+                Box::new(Node::Variable(lhs.into(), rhs, Span::from_offset(source, start), attributes))
             )
         }
 
+    // Parses all items in `source`
     pub rule items(import_paths: &mut SourcePaths) -> Vec<Item>
         = _ a:item(import_paths) ** _ _ { a }
 
@@ -151,9 +420,9 @@ peg::parser!{grammar grammar() for str {
         = (_ "," _)?
 
     rule enum() -> Item
-        = "enum" _ id:type_name() _ "{" _ enum_members:enum_member() ** ("," _) optional_trailing_comma() _ "}" {
+        = start:position!() "enum" _ id:type_name() _ "{" _ enum_members:enum_member() ** ("," _) optional_trailing_comma() _ "}" {
             Item::Single(
-                Box::new(Node::Enum(id.into(), enum_members))
+                Box::new(Node::Enum(id.into(), enum_members, Span::from_offset(source, start)))
             )
         }
 
@@ -170,6 +439,11 @@ peg::parser!{grammar grammar() for str {
             Box::new(Node::Type(Type::Pattern, a))
         }
 
+    rule pattern_file() -> Box<Node>
+        = "PatternFile" _ "(" _ path:(single_quoted_path() / double_quoted_path()) _ ")" {
+            Box::new(Node::PatternFile(PathBuf::from(path)))
+        }
+
     rule sequence() -> Box<Node>
         = "Sequence" _ "(" _ a:expr() **<1, 3> ("," _) optional_trailing_comma() _ ")" {
             Box::new(Node::Type(Type::Sequence, a))
@@ -181,31 +455,130 @@ peg::parser!{grammar grammar() for str {
         }
 
     rule range() -> Box<Node>
-        = "[" _ a:expr() **<2> ("," _) _ "]" {
-            Box::new(Node::Type(Type::Range, a))
+        = "[" _ a:expr() **<2> ("," _) _ "]" p:(_ ":" _ p:range_profile() { p } / _ "~" _ p:range_profile() { p })? {
+            match p {
+                None | Some(RangeProfile::Uniform) => Box::new(Node::Type(Type::Range, a)),
+                Some(p) => Box::new(Node::Type(Type::ProfiledRange(p), a)),
+            }
+        }
+
+    rule complement() -> Box<Node>
+        = "~" _ a:value_set() _ "within" identifier_end() _ "[" _ l:expr() _ "," _ r:expr() _ "]" {
+            Box::new(Node::Complement(a, l, r))
         }
 
-    rule weighted_sample() -> Box<Node>
-        = a:weight()? b:expr() {
-            let weight = match a {
-                Some(weight) => weight,
-                None => 1,
-            };
+    rule range_profile() -> RangeProfile
+        = "uniform" identifier_end() { RangeProfile::Uniform }
+        / "linear_up" identifier_end() { RangeProfile::LinearUp }
+        / "linear_down" identifier_end() { RangeProfile::LinearDown }
+        / "linear" identifier_end() { RangeProfile::LinearUp }
+        / "normal" identifier_end() { RangeProfile::Normal }
+        / "triangular" identifier_end() { RangeProfile::Triangular }
+
+    rule weighted_sample(replacement: Replacement) -> Box<Node>
+        = a:percent_weight() b:(weighted_group(replacement) / expr()) {
+            Box::new(Node::PercentWeightedSample(a, b))
+        }
+        / a:probability_weight() b:(weighted_group(replacement) / expr()) {
+            Box::new(Node::ProbabilityWeightedSample(a.0, a.1, b))
+        }
+        / a:weight()? b:(weighted_group(replacement) / expr()) {
+            let weight = a.unwrap_or(1);
             Box::new(Node::WeightedSample(weight, b))
         }
 
+    rule weighted_entry(replacement: Replacement) -> Box<Node>
+        = weighted_sample(replacement) / expand()
+
+    // A nested weighted sample with the same replacement as the enclosing sample
+    //
+    // E.g. `r{3: (1: 0, 1: 5), 1: 9}` is equivalent to `r{3: r{1: 0, 1: 5}, 1: 9}`.  A single
+    // entry requires a weight or a trailing comma to distinguish it from a parenthesized
+    // expression.
+    rule weighted_group(replacement: Replacement) -> Box<Node>
+        = "(" _ a:weighted_entry(replacement) _ "," _ b:weighted_entry(replacement) ** ("," _) optional_trailing_comma() _ ")" {
+            let mut entries = vec![a];
+            entries.extend(b);
+            Box::new(Node::Weighted(replacement, entries))
+        }
+        / "(" _ a:weight() b:(weighted_group(replacement) / expr()) _ ")" {
+            Box::new(Node::Weighted(replacement, vec![Box::new(Node::WeightedSample(a, b))]))
+        }
+
     rule weight() -> u32
         = a:dec_number() _ ":" _ { a }
 
+    rule percent_weight() -> u32
+        = a:dec_number() "%" _ ":" _ { a }
+
+    // A probability in [0, 1] with at most 9 decimal places E.g. `0.25:`
+    //
+    // Returns the probability as a numerator and a power of 10 denominator.
+    rule probability_weight() -> (u32, u32)
+        = whole:$(['0' | '1']) "." fraction:$(dec_digit()+) _ ":" _ {?
+            if fraction.len() > 9 {
+                Err("probability weight with at most 9 decimal places")
+            } else {
+                let denominator = 10u32.pow(fraction.len() as u32);
+                let numerator = u32::from_str(whole).unwrap() * denominator + u32::from_str(fraction).unwrap();
+                if numerator > denominator {
+                    Err("probability weight of at most 1")
+                } else {
+                    Ok((numerator, denominator))
+                }
+            }
+        }
+
+    // Weighted selection with replacement E.g. `(0.25: [0, 10], 0.75: 100)`
+    //
+    // Shorthand for `r{...}`.  Every entry requires a weight to distinguish it from a
+    // parenthesized expression.
+    rule weighted_choice() -> Box<Node>
+        = "(" _ entries:weighted_choice_entry() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Weighted(Replacement::With, entries))
+        }
+
+    rule weighted_choice_entry() -> Box<Node>
+        = &(percent_weight() / probability_weight() / weight()) a:weighted_sample((Replacement::With)) { a }
+
     rule weighted() -> Box<Node>
-        = replacement:"r"?"{" _ entries:(weighted_sample() / expand()) ++ ("," _) optional_trailing_comma() _ "}" {
-            let replacement = match replacement {
-                Some(_) => Replacement::With,
-                None => Replacement::Without,
-            };
+        = replacement:replacement() "{" _ entries:weighted_entry(replacement) ++ ("," _) optional_trailing_comma() _ "}" {
             Box::new(Node::Weighted(replacement, entries))
         }
 
+    // Uniform selection with replacement
+    //
+    // Shorthand for `r{<expr>, ...}`.  Unlike `{<expr>, ...}`, a value may be returned again
+    // immediately.
+    rule choose() -> Box<Node>
+        = "Choose" _ "(" _ a:expr() ++ ("," _) optional_trailing_comma() _ ")" {
+            let entries = a.into_iter().map(|a| Box::new(Node::WeightedSample(1, a))).collect();
+            Box::new(Node::Weighted(Replacement::With, entries))
+        }
+
+    // Weighted selection with replacement E.g. `WeightedSample(10: [0, 9], 90: [10, 99])`
+    //
+    // Shorthand for `r{...}`.  Entries are as for `r{...}`.
+    rule weighted_sample_function() -> Box<Node>
+        = "WeightedSample" _ "(" _ entries:weighted_entry((Replacement::With)) ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Weighted(Replacement::With, entries))
+        }
+
+    // Selection between two expressions with replacement E.g. `Maybe(80, [0, 9], 0xff)`
+    //
+    // The first argument is the percent chance of the second argument.
+    rule maybe() -> Box<Node>
+        = "Maybe" _ "(" _ a:expr() **<3> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Maybe, a))
+        }
+
+    rule replacement() -> Replacement
+        = "r" { Replacement::With }
+        / "o" { Replacement::WithoutInOrder }
+        / "u" { Replacement::WithoutDistinctValues }
+        / "s" { Replacement::WithoutFixedOrder }
+        / "" { Replacement::Without }
+
     rule done() -> Box<Node>
         = "Done" _ "(" _ a:expr() _ ")" {
             Box::new(Node::Type(Type::Done, vec![a]))
@@ -216,6 +589,149 @@ peg::parser!{grammar grammar() for str {
             Box::new(Node::Type(Type::Once, vec![a]))
         }
 
+    rule bits() -> Box<Node>
+        = "Bits" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::Bits, vec![a]))
+        }
+
+    rule graycode() -> Box<Node>
+        = "GrayCode" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::GrayCode, vec![a]))
+        }
+
+    rule modcounter() -> Box<Node>
+        = "ModCounter" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::ModCounter, a))
+        }
+
+    rule ramp() -> Box<Node>
+        = "Ramp" _ "(" _ a:expr() **<3> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Ramp, a))
+        }
+
+    rule cdf() -> Box<Node>
+        = "CDF" _ "(" _ "{" _ a:cdf_entry() ++ ("," _) optional_trailing_comma() _ "}" _ ")" {
+            Box::new(Node::Type(Type::Cdf, a.into_iter().flatten().collect()))
+        }
+
+    rule cdf_entry() -> Vec<Box<Node>>
+        = c:dec_number() _ ":" _ v:expr() { vec![Box::new(Node::Number(c)), v] }
+
+    rule sum() -> Box<Node>
+        = "Sum" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Sum, a))
+        }
+
+    rule window_min() -> Box<Node>
+        = "WindowMin" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::WindowMin, a))
+        }
+
+    rule window_max() -> Box<Node>
+        = "WindowMax" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::WindowMax, a))
+        }
+
+    rule mean() -> Box<Node>
+        = "Mean" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Mean, a))
+        }
+
+    rule shuffle() -> Box<Node>
+        = "Shuffle" _ "(" _ a:expr() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Shuffle, a))
+        }
+
+    rule mulmod() -> Box<Node>
+        = "MulMod" _ "(" _ a:expr() **<3> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::MulMod, a))
+        }
+
+    rule accumulate() -> Box<Node>
+        = "Accumulate" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::Accumulate, vec![a]))
+        }
+
+    rule hash() -> Box<Node>
+        = "Hash" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::Hash, vec![a]))
+        }
+
+    rule timestamp() -> Box<Node>
+        = "Timestamp" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Timestamp, a))
+        }
+
+    rule addcarry() -> Box<Node>
+        = "AddCarry" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::AddCarry, a))
+        }
+
+    rule byteswap() -> Box<Node>
+        = "ByteSwap" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::ByteSwap, vec![a]))
+        }
+
+    rule bitreverse() -> Box<Node>
+        = "BitReverse" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::BitReverse, vec![a]))
+        }
+
+    rule popcount() -> Box<Node>
+        = "PopCount" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::PopCount, vec![a]))
+        }
+
+    rule onehot() -> Box<Node>
+        = "OneHot" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::OneHot, vec![a]))
+        }
+
+    rule norepeat() -> Box<Node>
+        = "NoRepeat" _ "(" _ a:expr() _ ")" {
+            Box::new(Node::Type(Type::NoRepeat, vec![a]))
+        }
+
+    rule avoidrecent() -> Box<Node>
+        = "AvoidRecent" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::AvoidRecent, a))
+        }
+
+    rule repeat() -> Box<Node>
+        = "Repeat" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Repeat, a))
+        }
+
+    rule holdfor() -> Box<Node>
+        = "HoldFor" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::HoldFor, a))
+        }
+
+    rule zipf() -> Box<Node>
+        = "Zipf" _ "(" _ a:expr() **<2,3> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Zipf, a))
+        }
+
+    rule trunc_normal() -> Box<Node>
+        = "TruncNormal" _ "(" _ a:expr() **<4> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::TruncNormal, a))
+        }
+
+    rule poisson() -> Box<Node>
+        = "Poisson" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Poisson, a))
+        }
+
+    rule walk() -> Box<Node>
+        = "Walk" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Walk, a))
+        }
+
+    rule decode() -> Box<Node>
+        = "Decode" _ "(" _ a:expr() **<2> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Decode, a))
+        }
+
     // From: https://github.com/kevinmehall/rust-peg/blob/cc6a3cdebfafc670a9dffb0422709ff6d85d1207/src/grammar.rustpeg
     rule _() = quiet!{(whitespace() / eol() / comment())*}
 
@@ -305,3 +821,31 @@ peg::parser!{grammar grammar() for str {
     rule hexDigit()
         = ['0'..='9' | 'a'..='f' | 'A'..='F']
 }}
+
+/// Converts the digits of a literal with don't-care digits into a `Node::DontCare`
+///
+/// Fails if there are no don't-care digits so the literal is parsed as a plain number instead.
+fn dont_care(digits: &str, radix: u32, wildcards: &[char]) -> Result<Box<Node>, &'static str> {
+    let bits = radix.trailing_zeros();
+    let mut value: u64 = 0;
+    let mut mask: u64 = 0;
+    for digit in digits.chars().filter(|c| *c != '_') {
+        value <<= bits;
+        mask <<= bits;
+        if wildcards.contains(&digit) {
+            mask |= u64::from(radix - 1);
+        } else {
+            value |= u64::from(digit.to_digit(radix).unwrap());
+        }
+
+        if (value | mask) > u64::from(u32::MAX) {
+            return Err("integer literal within 32 bits");
+        }
+    }
+
+    if mask == 0 {
+        return Err("don't-care digit");
+    }
+
+    Ok(Box::new(Node::DontCare(value as u32, mask as u32)))
+}
@@ -1,12 +1,17 @@
+#![allow(clippy::vec_box)]
+
 mod searchpath;
 mod sourcepaths;
 mod parser;
 mod grammar;
+mod functions;
 
 pub mod ast;
+pub mod binary;
 pub mod error;
 
 pub use searchpath::SearchPath;
-pub use parser::Parser;
+pub use sourcepaths::SourcePaths;
+pub use parser::{Parser, DEFAULT_MAX_IMPORT_DEPTH};
 pub use error::Error;
 pub use error::ParseError;
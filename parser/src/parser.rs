@@ -3,20 +3,72 @@ use crate::grammar;
 use crate::searchpath::SearchPath;
 use crate::sourcepaths::SourcePaths;
 use crate::error::{Error, ParseError, Result};
+use crate::functions::{unknown_function_message, FUNCTIONS};
+
+/// Default maximum number of nested imports
+pub const DEFAULT_MAX_IMPORT_DEPTH: usize = 32;
 
 pub struct Parser {
     searchpath: SearchPath,
+    max_import_depth: usize,
+    unknown_function_errors: bool,
 }
 
 impl Parser {
     pub fn new(searchpath: SearchPath) -> Parser {
-        Parser { searchpath }
+        Parser {
+            searchpath,
+            max_import_depth: DEFAULT_MAX_IMPORT_DEPTH,
+            unknown_function_errors: false,
+        }
+    }
+
+    /// Sets the maximum number of nested imports
+    ///
+    /// Exceeding the maximum is a parse error.  Defaults to `DEFAULT_MAX_IMPORT_DEPTH`.
+    pub fn set_max_import_depth(&mut self, max_import_depth: usize) {
+        self.max_import_depth = max_import_depth;
+    }
+
+    /// Enables or disables specific errors for calls of unknown functions
+    ///
+    /// When enabled, a syntax error at the `(` of a call of an unknown function (e.g. `Smple(1)`)
+    /// is reported as an unknown function along with the nearest known function or, if none is
+    /// near, a list of all functions.  Applies to the source passed to `parse` but not to imported
+    /// files.  Disabled by default.
+    pub fn set_unknown_function_errors(&mut self, enabled: bool) {
+        self.unknown_function_errors = enabled;
     }
 
     pub fn parse(&self, s: &str) -> Result<Vec<Box<ast::Node>>> {
-        match grammar::items(s, &mut SourcePaths::new(self.searchpath.clone())) {
-            Ok(items) => self.flatten(items),
-            Err(error) => {
+        self.parse_at(s, 1, &mut self.source_paths())
+    }
+
+    /// Returns a new record of imported files for use with `parse_at`
+    pub fn source_paths(&self) -> SourcePaths {
+        SourcePaths::new(self.searchpath.clone(), self.max_import_depth)
+    }
+
+    /// Parses a fragment of a larger source
+    ///
+    /// `line` is the line number of the first line of `s` within the larger source.  It is used
+    /// to report lines relative to the larger source in errors and spans.  `s` is expected to
+    /// start at the beginning of a line.
+    ///
+    /// `import_paths` records the files imported so far.  Pass the same `SourcePaths` for every
+    /// fragment of a source so that a file imported by several fragments is only imported once.
+    pub fn parse_at(
+        &self,
+        s: &str,
+        line: usize,
+        import_paths: &mut SourcePaths,
+    ) -> Result<Vec<Box<ast::Node>>> {
+        match grammar::items(s, s, import_paths) {
+            Ok(mut items) => {
+                offset_lines(&mut items, line - 1);
+                self.flatten(items)
+            }
+            Err(mut error) => {
                 // FIXME: Improve formatting source code in errors
                 //
                 // Current format:
@@ -38,18 +90,51 @@ impl Parser {
                 // * Source file path
                 // * Single space above and below source line
                 // * Source line prefixed with line number and '|' separator
-                let mut indent = String::with_capacity(error.location.column);
-                for _ in 0..error.location.column - 1 {
-                    indent.push_str(" ");
+                let source_line = s.lines().nth(error.location.line - 1).unwrap_or("");
+                let unknown_function = if self.unknown_function_errors {
+                    unknown_function(s, error.location.offset)
+                } else {
+                    None
+                };
+                error.location.line += line - 1;
+                let (message, column) = match unknown_function {
+                    Some((name, start)) => {
+                        let column = error.location.column
+                            - s[start..error.location.offset].chars().count();
+                        (
+                            format!(
+                                "error at {}:{}: {}",
+                                error.location.line,
+                                column,
+                                unknown_function_message(name)
+                            ),
+                            column,
+                        )
+                    }
+                    None => (error.to_string(), error.location.column),
+                };
+                let mut indent = String::with_capacity(column);
+                for _ in 0..column - 1 {
+                    indent.push(' ');
                 }
-                let line = s.lines().nth(error.location.line - 1).unwrap();
-                let description = format!("{}\n{}\n{}^", error, line, indent,);
+                let description = format!("{}\n{}\n{}^", message, source_line, indent,);
 
                 Err(Error::Parse(ParseError::new(description)))
             }
         }
     }
 
+    /// Parses a source string and returns its `//` comments alongside the AST
+    ///
+    /// Intended for formatters.  Only comments in `s` are returned, not comments in imported
+    /// files.  Doc comments (`///`) are returned as comments in addition to their `doc`
+    /// attributes.
+    pub fn parse_with_comments(&self, s: &str) -> Result<(Vec<Box<ast::Node>>, Vec<ast::Comment>)> {
+        let nodes = self.parse(s)?;
+
+        Ok((nodes, grammar::comments(s)))
+    }
+
     fn flatten_recursive(
         &self,
         mut items: Vec<ast::Item>,
@@ -59,9 +144,35 @@ impl Parser {
             match item {
                 ast::Item::Single(node) => nodes.push(node),
                 ast::Item::Multiple(items) => self.flatten_recursive(items, nodes)?,
-                ast::Item::ImportError(path, err) => {
+                ast::Item::ImportError(_path, err) => {
                     return Err(Error::Io(err));
                 }
+                ast::Item::ImportParseError(path, err) => {
+                    return Err(Error::Parse(ParseError::new(format!(
+                        "import of '{}' failed to parse: {}",
+                        path.display(),
+                        err
+                    ))));
+                }
+                ast::Item::ImportCycle(path) => {
+                    return Err(Error::Parse(ParseError::new(format!(
+                        "import of '{}' creates an import cycle",
+                        path.display()
+                    ))));
+                }
+                ast::Item::ImportDepthExceeded(path, max_depth) => {
+                    return Err(Error::Parse(ParseError::new(format!(
+                        "import of '{}' exceeds the maximum import depth of {}",
+                        path.display(),
+                        max_depth
+                    ))));
+                }
+                ast::Item::ImportUnsetVariable(path, name) => {
+                    return Err(Error::Parse(ParseError::new(format!(
+                        "import of '{}' references the unset environment variable `{}`",
+                        path, name
+                    ))));
+                }
             }
         }
 
@@ -81,3 +192,93 @@ impl Parser {
         Ok(nodes)
     }
 }
+
+/// Offsets the spans of all items, and of the references within them, that originate from the
+/// current source
+///
+/// Items from `import`s are relative to their own source and are left as is.
+fn offset_lines(items: &mut [ast::Item], lines: usize) {
+    for item in items {
+        if let ast::Item::Single(ref mut node) = *item {
+            offset_node_lines(node, lines);
+        }
+    }
+}
+
+fn offset_node_lines(node: &mut ast::Node, lines: usize) {
+    match *node {
+        ast::Node::Variable(_, ref mut a, ref mut span, _)
+        | ast::Node::Constant(_, ref mut a, ref mut span)
+        | ast::Node::Pair(_, _, ref mut a, ref mut span) => {
+            span.line += lines;
+            offset_node_lines(a, lines);
+        }
+        ast::Node::Enum(_, _, ref mut span)
+        | ast::Node::Distinct(_, ref mut span)
+        | ast::Node::Gate(_, _, ref mut span)
+        | ast::Node::RIdentifier(_, _, ref mut span)
+        | ast::Node::MethodChain(_, _, ref mut span) => {
+            span.line += lines;
+        }
+        ast::Node::Group(_, ref mut nodes, ref mut span) => {
+            span.line += lines;
+
+            for node in nodes {
+                offset_node_lines(node, lines);
+            }
+        }
+        ast::Node::UnaryOperation(_, ref mut a)
+        | ast::Node::WeightedSample(_, ref mut a)
+        | ast::Node::PercentWeightedSample(_, ref mut a)
+        | ast::Node::ProbabilityWeightedSample(_, _, ref mut a) => offset_node_lines(a, lines),
+        ast::Node::BinaryOperation(ref mut a, _, ref mut b) | ast::Node::Let(_, ref mut a, ref mut b) => {
+            offset_node_lines(a, lines);
+            offset_node_lines(b, lines);
+        }
+        ast::Node::Except(ref mut a, ref mut nodes) | ast::Node::In(ref mut a, ref mut nodes) => {
+            offset_node_lines(a, lines);
+
+            for node in nodes {
+                offset_node_lines(node, lines);
+            }
+        }
+        ast::Node::Complement(ref mut nodes, ref mut l, ref mut r) => {
+            for node in nodes {
+                offset_node_lines(node, lines);
+            }
+
+            offset_node_lines(l, lines);
+            offset_node_lines(r, lines);
+        }
+        ast::Node::Type(_, ref mut nodes) | ast::Node::Weighted(_, ref mut nodes) => {
+            for node in nodes {
+                offset_node_lines(node, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the name and offset of the unknown function called at the syntax error at `offset`
+///
+/// A call is an identifier followed by optional spaces and a `(` at `offset`.  Identifiers are
+/// never followed by `(` so this is a syntax error unless the identifier is a function.
+fn unknown_function(s: &str, offset: usize) -> Option<(&str, usize)> {
+    if !s[offset..].starts_with('(') {
+        return None;
+    }
+
+    let before = s[..offset].trim_end_matches([' ', '\t']);
+    let start = before
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    let name = &before[start..];
+
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+    let is_method = before[..start].ends_with('.');
+    if !is_identifier || is_method || FUNCTIONS.contains(&name) {
+        return None;
+    }
+
+    Some((name, start))
+}
@@ -13,6 +13,11 @@ impl SearchPath {
         SearchPath { paths }
     }
 
+    /// Returns the paths in search order
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
     /// Sets the search path used for `import`
     ///
     /// The string must be a colon separated list of paths.
@@ -31,7 +36,6 @@ impl SearchPath {
 
         let paths: Vec<PathBuf> = s
             .split(separator)
-            .into_iter()
             .filter(|s| !s.is_empty())
             .map(|s| Path::new(s).to_path_buf())
             .collect();
@@ -39,7 +43,7 @@ impl SearchPath {
         let error_paths: Vec<PathBuf> = paths
             .iter()
             .filter(|path| !path.exists())
-            .map(|path| path.clone())
+            .cloned()
             .collect();
 
         if !error_paths.is_empty() {
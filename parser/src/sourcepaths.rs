@@ -1,5 +1,10 @@
 use crate::searchpath::SearchPath;
 
+use log::debug;
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
@@ -18,24 +23,48 @@ pub struct SourcePaths {
     stack: Vec<PathBuf>,
 
     searchpath: SearchPath,
+
+    /// Maximum number of nested imports
+    max_depth: usize,
 }
 
 impl SourcePaths {
-    pub fn new(searchpath: SearchPath) -> SourcePaths {
+    pub fn new(searchpath: SearchPath, max_depth: usize) -> SourcePaths {
         SourcePaths {
             paths: HashSet::new(),
             stack: Vec::new(),
             searchpath,
+            max_depth,
         }
     }
 
+    /// Returns true if `path` is currently being imported
+    ///
+    /// Importing such a path would create an import cycle.
+    pub fn is_importing(&self, path: &Path) -> bool {
+        self.stack.iter().any(|p| p == path)
+    }
+
+    /// Returns true if entering another import would exceed the maximum import depth
+    pub fn at_max_depth(&self) -> bool {
+        self.stack.len() >= self.max_depth
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
     /// Returns true on first call for a given path, false otherwise.
     ///
     /// All enter_import calls that return true must be paired with a leave_import call.
     pub fn enter_import(&mut self, path: &Path) -> bool {
         if self.paths.contains(path) {
+            debug!("skipping import of '{}': already imported", path.display());
+
             false
         } else {
+            debug!("importing '{}'", path.display());
+
             self.paths.insert(path.to_path_buf());
             self.stack.push(path.to_path_buf());
 
@@ -48,26 +77,83 @@ impl SourcePaths {
     }
 
     /// Returns a path if file found in search path.  Returns an std::io::Error otherwise.
+    ///
+    /// The returned path is canonicalized so that the same file reached via different paths is
+    /// only imported once.
     pub fn find(&self, path: &Path) -> io::Result<PathBuf> {
         // Relative to current source file
         if let Some(current) = self.stack.last() {
             let parent = current.parent().unwrap().join(path);
             if parent.exists() {
-                return Ok(parent);
+                return fs::canonicalize(parent);
             }
         }
 
         // Relative to search path
-        self.searchpath.find(path)
+        fs::canonicalize(self.searchpath.find(path)?)
+    }
+}
+
+/// Expands references to environment variables in an import path
+///
+/// Variables are referenced as `$NAME` or `${NAME}` where `NAME` is a letter or underscore
+/// followed by letters, digits, or underscores.  `$$` is a literal `$`.  A `$` not followed by a
+/// name or a braced name is kept as is.
+///
+/// Returns the name of the first unset variable on failure.
+pub fn expand_env_vars(path: &str) -> Result<PathBuf, String> {
+    let is_name_start = |c: char| c.is_ascii_alphabetic() || c == '_';
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    // Returns the length of the name if `s` starts with a name followed by `}`
+    let braced_name_len = |s: &str| {
+        let end = s.find('}')?;
+        let name = &s[..end];
+
+        if name.starts_with(is_name_start) && name.chars().all(is_name) {
+            Some(end)
+        } else {
+            None
+        }
+    };
+
+    let mut expanded = OsString::new();
+    let mut rest = path;
+
+    while let Some(index) = rest.find('$') {
+        expanded.push(&rest[..index]);
+        let after = &rest[index + 1..];
+
+        let (name, remaining) = if let Some(after) = after.strip_prefix('$') {
+            expanded.push("$");
+            rest = after;
+            continue;
+        } else if let Some(len) = after.strip_prefix('{').and_then(braced_name_len) {
+            (&after[1..len + 1], &after[len + 2..])
+        } else if after.starts_with(is_name_start) {
+            let end = after.find(|c| !is_name(c)).unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        } else {
+            expanded.push("$");
+            rest = after;
+            continue;
+        };
+
+        match env::var_os(name) {
+            Some(value) => expanded.push(value),
+            None => return Err(name.to_owned()),
+        }
+        rest = remaining;
     }
+    expanded.push(rest);
+
+    Ok(PathBuf::from(expanded))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::fs;
-
     #[test]
     fn basic() {
         let path_str = "../examples";
@@ -75,15 +161,32 @@ mod tests {
         let path_file = path_dir.join("readme.rvs");
 
         let search_path = SearchPath::new(vec![path_dir]);
-        let mut sourcepaths = SourcePaths::new(search_path);
+        let mut sourcepaths = SourcePaths::new(search_path, 32);
 
         assert_eq!(
-            sourcepaths.find(&Path::new("readme.rvs")).unwrap(),
+            sourcepaths.find(Path::new("readme.rvs")).unwrap(),
             path_file
         );
         assert!(sourcepaths.enter_import(&path_file));
+        assert!(sourcepaths.is_importing(&path_file));
         assert!(!sourcepaths.enter_import(&path_file));
         sourcepaths.leave_import();
+        assert!(!sourcepaths.is_importing(&path_file));
         assert!(!sourcepaths.enter_import(&path_file));
     }
+
+    #[test]
+    fn expand() {
+        env::set_var("RVS_TEST_EXPAND", "/lib");
+        env::remove_var("RVS_TEST_EXPAND_UNSET");
+
+        let expand = |path| expand_env_vars(path).map(|path| path.to_str().unwrap().to_owned());
+
+        assert_eq!(expand("$RVS_TEST_EXPAND/a.rvs"), Ok("/lib/a.rvs".into()));
+        assert_eq!(expand("${RVS_TEST_EXPAND}_a.rvs"), Ok("/lib_a.rvs".into()));
+        assert_eq!(expand("a$$b/$/${/$1.rvs"), Ok("a$b/$/${/$1.rvs".into()));
+        assert_eq!(expand("${}/${1}/${a b}"), Ok("${}/${1}/${a b}".into()));
+        assert_eq!(expand("a/$RVS_TEST_EXPAND_UNSET"), Err("RVS_TEST_EXPAND_UNSET".into()));
+        assert_eq!(expand("${RVS_TEST_EXPAND_UNSET}"), Err("RVS_TEST_EXPAND_UNSET".into()));
+    }
 }
@@ -0,0 +1,35 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("@field(\"addr\") @width(32) a=5;"),
+        "[Variable(\"a\", Number(5), Span { line: 1, column: 1 }, \
+         [Attribute { key: \"field\", value: \"addr\" }, Attribute { key: \"width\", value: \"32\" }])]"
+    );
+}
+
+#[test]
+fn multiline() {
+    assert!(parse_result("@field(\"addr\")\n@width(32)\na=5;").is_ok());
+}
+
+#[test]
+fn missing_value() {
+    assert!(parse_result("@width() a=5;").is_err());
+}
+
+#[test]
+fn without_variable() {
+    assert!(parse_result("@width(32);").is_err());
+}
+
+#[test]
+fn doc_comment() {
+    assert_eq!(
+        parse("/// The address\n///Aligned\n@width(32) a=5;"),
+        "[Variable(\"a\", Number(5), Span { line: 3, column: 1 }, \
+         [Attribute { key: \"doc\", value: \"The address\\nAligned\" }, Attribute { key: \"width\", value: \"32\" }])]"
+    );
+}
@@ -0,0 +1,16 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = CDF({10: 0, 100: 1});"),
+        "[Variable(\"a\", Type(Cdf, [Number(10), Number(0), Number(100), Number(1)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn requires_entries() {
+    assert!(parse_result("a = CDF({});").is_err());
+    assert!(parse_result("a = CDF({10, 100: 1});").is_err());
+}
@@ -0,0 +1,77 @@
+use rvs_parser::ast::{Comment, Span};
+use rvs_parser::Parser;
+
+fn comments(s: &str) -> Vec<Comment> {
+    let parser = Parser::new(Default::default());
+    parser.parse_with_comments(s).unwrap().1
+}
+
+fn comment(text: &str, line: usize, column: usize, trailing: bool) -> Comment {
+    Comment { text: text.into(), span: Span { line, column }, trailing }
+}
+
+#[test]
+fn between_statements() {
+    assert_eq!(
+        comments("a = 1;\n// between\nb = 2;\n"),
+        vec![comment("// between", 2, 1, false)]
+    );
+}
+
+#[test]
+fn trailing() {
+    assert_eq!(
+        comments("a = 1; // first\nb = 2;  // second"),
+        vec![comment("// first", 1, 8, true), comment("// second", 2, 9, true)]
+    );
+}
+
+#[test]
+fn inside_argument_list() {
+    assert_eq!(
+        comments("a = Pattern(\n    1, // one\n    // two\n    2,\n);"),
+        vec![comment("// one", 2, 8, true), comment("// two", 3, 5, false)]
+    );
+}
+
+#[test]
+fn doc_comment() {
+    assert_eq!(
+        comments("/// doc\na = 1;"),
+        vec![comment("/// doc", 1, 1, false)]
+    );
+}
+
+#[test]
+fn string_literals() {
+    assert_eq!(
+        comments("@field(\"a//b\") a = 1;\nb = PatternFile('c\\'//d'); // e"),
+        vec![comment("// e", 2, 28, true)]
+    );
+}
+
+#[test]
+fn crlf() {
+    assert_eq!(
+        comments("a = 1; // first\r\nb = 2;"),
+        vec![comment("// first", 1, 8, true)]
+    );
+}
+
+#[test]
+fn ast() {
+    let parser = Parser::new(Default::default());
+    let (nodes, _) = parser.parse_with_comments("// comment\na = 1;").unwrap();
+
+    assert_eq!(
+        format!("{:?}", nodes),
+        format!("{:?}", parser.parse("a = 1;").unwrap())
+            .replace("line: 1", "line: 2")
+    );
+}
+
+#[test]
+fn parse_error() {
+    let parser = Parser::new(Default::default());
+    assert!(parser.parse_with_comments("// comment\na = ;").is_err());
+}
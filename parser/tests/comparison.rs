@@ -0,0 +1,50 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn less_than() {
+    assert_eq!(
+        parse("a = 1 < 2;"),
+        "[Variable(\"a\", BinaryOperation(Number(1), Lt, Number(2)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn less_than_negative() {
+    assert_eq!(
+        parse("a = 1<-1;"),
+        "[Variable(\"a\", BinaryOperation(Number(1), Lt, UnaryOperation(Neg, Number(1))), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn greater_than() {
+    assert_eq!(
+        parse("a = 1 > 2;"),
+        "[Variable(\"a\", BinaryOperation(Number(1), Gt, Number(2)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(
+        parse("a = 1 & 2 < 3 << 4;"),
+        "[Variable(\"a\", BinaryOperation(Number(1), And, BinaryOperation(Number(2), Lt, BinaryOperation(Number(3), Shl, Number(4)))), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn shifts_and_abs_diff() {
+    assert_eq!(
+        parse("a = 1 >> 2 << 3 <-> 4;"),
+        "[Variable(\"a\", BinaryOperation(BinaryOperation(Number(1), Shr, Number(2)), Shl, BinaryOperation(Number(3), AbsDiff, Number(4))), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn valueless_attribute() {
+    assert_eq!(
+        parse("@signed a = 1;"),
+        "[Variable(\"a\", Number(1), Span { line: 1, column: 1 }, [Attribute { key: \"signed\", value: \"\" }])]"
+    );
+}
@@ -0,0 +1,23 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = ~{1, 2, 3} within [0, 7];"),
+        "[Variable(\"a\", Complement([Number(1), Number(2), Number(3)], Number(0), Number(7)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn inverse() {
+    assert_eq!(
+        parse("a = ~{1: 2};"),
+        "[Variable(\"a\", UnaryOperation(Inv, Weighted(Without, [WeightedSample(1, Number(2))])), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn within_is_a_keyword() {
+    assert!(parse_result("a = ~{1} withinx [0, 7];").is_err());
+}
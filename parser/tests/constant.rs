@@ -0,0 +1,18 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("const X = 5;"),
+        "[Constant(\"X\", Number(5), Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn identifier_starting_with_const() {
+    assert_eq!(
+        parse("constant = 5;"),
+        "[Variable(\"constant\", Number(5), Span { line: 1, column: 1 }, [])]"
+    );
+}
@@ -0,0 +1,28 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("distinct(a, b, c);"),
+        "[Distinct([\"a\", \"b\", \"c\"], Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn trailing_comma() {
+    assert!(parse_result("distinct(a, b,);").is_ok());
+}
+
+#[test]
+fn empty() {
+    assert!(parse_result("distinct();").is_err());
+}
+
+#[test]
+fn variable_named_distinct() {
+    assert_eq!(
+        parse("distinctive = 1;"),
+        "[Variable(\"distinctive\", Number(1), Span { line: 1, column: 1 }, [])]"
+    );
+}
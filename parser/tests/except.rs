@@ -0,0 +1,23 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = [0, 100] except {13, 37};"),
+        "[Variable(\"a\", Except(Type(Range, [Number(0), Number(100)]), [Number(13), Number(37)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn lowest_precedence() {
+    assert_eq!(
+        parse("a = 1 + 2 except {3};"),
+        "[Variable(\"a\", Except(BinaryOperation(Number(1), Add, Number(2)), [Number(3)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn empty() {
+    assert!(parse_result("a = [0, 1] except {};").is_ok());
+}
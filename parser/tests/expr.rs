@@ -3,10 +3,10 @@ use utils::*;
 
 #[test]
 fn good() {
-    assert_eq!(parse("a = (5);"), "[Variable(\"a\", Number(5))]");
-    assert_eq!(parse("a = 5;"), "[Variable(\"a\", Number(5))]");
-    assert_eq!(parse("a = 0xa;"), "[Variable(\"a\", Number(10))]");
-    assert_eq!(parse("a = 0xaf;"), "[Variable(\"a\", Number(175))]");
+    assert_eq!(parse("a = (5);"), "[Variable(\"a\", Number(5), Span { line: 1, column: 1 }, [])]");
+    assert_eq!(parse("a = 5;"), "[Variable(\"a\", Number(5), Span { line: 1, column: 1 }, [])]");
+    assert_eq!(parse("a = 0xa;"), "[Variable(\"a\", Number(10), Span { line: 1, column: 1 }, [])]");
+    assert_eq!(parse("a = 0xaf;"), "[Variable(\"a\", Number(175), Span { line: 1, column: 1 }, [])]");
 }
 
 #[test]
@@ -19,9 +19,56 @@ fn bad() {
 fn operations() {
     assert_eq!(
         parse("a = 1+2;"),
-        "[Variable(\"a\", BinaryOperation(Number(1), Add, Number(2)))]"
+        "[Variable(\"a\", BinaryOperation(Number(1), Add, Number(2)), Span { line: 1, column: 1 }, [])]"
     );
     assert_eq!(
         parse("a = 1+2*3;"),
-        "[Variable(\"a\", BinaryOperation(Number(1), Add, BinaryOperation(Number(2), Mul, Number(3))))]");
+        "[Variable(\"a\", BinaryOperation(Number(1), Add, BinaryOperation(Number(2), Mul, Number(3))), Span { line: 1, column: 1 }, [])]");
+}
+
+#[test]
+fn distinct_values_without_replacement() {
+    assert_eq!(
+        parse("a = u{1, 2};"),
+        "[Variable(\"a\", Weighted(WithoutDistinctValues, [WeightedSample(1, Number(1)), WeightedSample(1, Number(2))]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn choose() {
+    assert_eq!(
+        parse("a = Choose(1, 2);"),
+        "[Variable(\"a\", Weighted(With, [WeightedSample(1, Number(1)), WeightedSample(1, Number(2))]), Span { line: 1, column: 1 }, [])]"
+    );
+    assert_eq!(parse("a = Choose(1, 2);"), parse("a = r{1, 2};"));
+    assert_eq!(
+        parse("a = {1, 2};"),
+        "[Variable(\"a\", Weighted(Without, [WeightedSample(1, Number(1)), WeightedSample(1, Number(2))]), Span { line: 1, column: 1 }, [])]"
+    );
+    assert!(parse_result("a = Choose();").is_err());
+    assert!(parse_result("a = Choose(1: 2);").is_err());
+}
+
+#[test]
+fn weighted_sample_function() {
+    assert_eq!(
+        parse("a = WeightedSample(10: [0, 9], 90: [10, 99]);"),
+        parse("a = r{10: [0, 9], 90: [10, 99]};")
+    );
+    assert_eq!(parse("a = WeightedSample(1, 2: 3,);"), parse("a = r{1, 2: 3};"));
+    assert_eq!(parse("a = WeightedSample(25%: 1, 75%: 2);"), parse("a = r{25%: 1, 75%: 2};"));
+    assert!(parse_result("a = WeightedSample();").is_err());
+}
+
+#[test]
+fn pattern_file() {
+    assert_eq!(
+        parse("a = PatternFile('values.txt');"),
+        "[Variable(\"a\", PatternFile(\"values.txt\"), Span { line: 1, column: 1 }, [])]"
+    );
+    assert_eq!(
+        parse("a = PatternFile(\"dir/values.txt\");"),
+        "[Variable(\"a\", PatternFile(\"dir/values.txt\"), Span { line: 1, column: 1 }, [])]"
+    );
+    assert!(parse_result("a = PatternFile(values.txt);").is_err());
 }
@@ -0,0 +1,29 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("gate a by b;"),
+        "[Gate(\"a\", \"b\", Span { line: 1, column: 1 })]"
+    );
+
+    assert_eq!(
+        parse("gate net.a by net.b;"),
+        "[Gate(\"net.a\", \"net.b\", Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn malformed() {
+    assert!(parse_result("gate a;").is_err());
+    assert!(parse_result("gate a by;").is_err());
+    assert!(parse_result("gate a by b").is_err());
+    assert!(parse_result("gate a byb;").is_err());
+}
+
+#[test]
+fn gate_as_variable_name() {
+    assert!(parse_result("gate = 1;").is_ok());
+    assert!(parse_result("gate a by gate;").is_ok());
+}
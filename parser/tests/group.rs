@@ -0,0 +1,47 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("group net {\n    a = 1;\n}"),
+        "[Group(\"net\", [Variable(\"a\", Number(1), Span { line: 2, column: 5 }, [])], Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn nested() {
+    assert_eq!(
+        parse("group a { group b { c = 1; } }"),
+        "[Group(\"a\", [Group(\"b\", [Variable(\"c\", Number(1), Span { line: 1, column: 21 }, [])], Span { line: 1, column: 11 })], Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn empty() {
+    assert_eq!(
+        parse("group net {}"),
+        "[Group(\"net\", [], Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn qualified_reference() {
+    assert_eq!(
+        parse("b = net.a + net.a.prev;"),
+        "[Variable(\"b\", BinaryOperation(RIdentifier(\"net.a\", Next, Span { line: 1, column: 5 }), Add, RIdentifier(\"net.a\", Prev, Span { line: 1, column: 13 })), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn import_not_allowed() {
+    assert!(parse_result("group net { import a; }").is_err());
+}
+
+#[test]
+fn variable_named_group() {
+    assert_eq!(
+        parse("group = 1;"),
+        "[Variable(\"group\", Number(1), Span { line: 1, column: 1 }, [])]"
+    );
+}
@@ -11,7 +11,7 @@ fn same_filename_different_directory() {
     let search_path = SearchPath::new(vec![fixtures.join("a"), fixtures.join("b")]);
     let parser = Parser::new(search_path);
     let items = parser.parse("import a;").unwrap();
-    assert_eq!(format!("{:?}", items), "[Variable(\"a\", Number(0))]");
+    assert_eq!(format!("{:?}", items), "[Variable(\"a\", Number(0), Span { line: 1, column: 1 }, [])]");
 }
 
 #[test]
@@ -22,7 +22,7 @@ fn source_relative() {
     let items = parser.parse("import a;").unwrap();
     assert_eq!(
         format!("{:?}", items),
-        "[Variable(\"c\", Number(0)), Variable(\"b\", Number(0)), Variable(\"a\", Number(0))]"
+        "[Variable(\"c\", Number(0), Span { line: 1, column: 1 }, []), Variable(\"b\", Number(0), Span { line: 5, column: 1 }, []), Variable(\"a\", Number(0), Span { line: 5, column: 1 }, [])]"
     );
 }
 
@@ -36,13 +36,153 @@ fn import_is_idempotent() {
     let items = parser.parse("import a;").unwrap();
     assert_eq!(
         format!("{:?}", items),
-        "[Variable(\"a\", Number(1)), Variable(\"a\", Number(2))]"
+        "[Variable(\"a\", Number(1), Span { line: 1, column: 1 }, []), Variable(\"a\", Number(2), Span { line: 3, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn import_is_idempotent_across_different_paths() {
+    let fixtures = current_dir()
+        .unwrap()
+        .join("tests/import/canonical");
+    let search_path = SearchPath::new(vec![fixtures]);
+    let parser = Parser::new(search_path);
+    let items = parser
+        .parse("import a; import 'sub/../a.rvs'; import sub::b;")
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", items),
+        "[Variable(\"a\", Number(1), Span { line: 1, column: 1 }, []), Variable(\"b\", Number(2), Span { line: 2, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn within_max_depth() {
+    let fixtures = current_dir().unwrap().join("tests/import/depth");
+    let search_path = SearchPath::new(vec![fixtures]);
+    let mut parser = Parser::new(search_path);
+    parser.set_max_import_depth(5);
+    assert_eq!(parser.parse("import a;").unwrap().len(), 5);
+}
+
+fn parse_quoted(s: &str) -> String {
+    let fixtures = current_dir().unwrap().join("tests/import/quoted");
+    let search_path = SearchPath::new(vec![fixtures]);
+    let parser = Parser::new(search_path);
+    format!("{:?}", parser.parse(s).unwrap())
+}
+
+#[test]
+fn single_quoted() {
+    assert_eq!(
+        parse_quoted("import 'a.rvs';"),
+        "[Variable(\"a\", Number(0), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn double_quoted() {
+    assert_eq!(
+        parse_quoted("import \"a.rvs\";"),
+        "[Variable(\"a\", Number(0), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn quoted_with_space() {
+    let expected = "[Variable(\"b\", Number(1), Span { line: 1, column: 1 }, [])]";
+    assert_eq!(parse_quoted("import 'dir with space/b.rvs';"), expected);
+    assert_eq!(parse_quoted("import \"dir with space/b.rvs\";"), expected);
+    assert_eq!(parse_quoted("import 'dir\\ with\\ space/b.rvs';"), expected);
+}
+
+#[test]
+fn escaped_character() {
+    let expected = "[Variable(\"a\", Number(0), Span { line: 1, column: 1 }, [])]";
+    assert_eq!(parse_quoted("import 'a.rvs'; import \"\\a.rvs\";"), expected);
+}
+
+#[test]
+fn environment_variable() {
+    let fixtures = current_dir().unwrap().join("tests/import/quoted");
+    std::env::set_var("RVS_TEST_IMPORT_DIR", &fixtures);
+
+    let parser = Parser::new(SearchPath::default());
+    let items = parser.parse("import '$RVS_TEST_IMPORT_DIR/a.rvs';").unwrap();
+    assert_eq!(format!("{:?}", items), "[Variable(\"a\", Number(0), Span { line: 1, column: 1 }, [])]");
+}
+
+#[test]
+fn environment_variable_relative_to_search_path() {
+    std::env::set_var("RVS_TEST_IMPORT_SUBDIR", "dir with space");
+
+    assert_eq!(
+        parse_quoted("import \"${RVS_TEST_IMPORT_SUBDIR}/b.rvs\";"),
+        "[Variable(\"b\", Number(1), Span { line: 1, column: 1 }, [])]"
     );
 }
 
 mod error {
     use super::*;
 
+    #[test]
+    fn unset_environment_variable() {
+        std::env::remove_var("RVS_TEST_IMPORT_UNSET");
+
+        let parser = Parser::new(SearchPath::default());
+        let error = parser.parse("import '${RVS_TEST_IMPORT_UNSET}/a.rvs';").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "import of '${RVS_TEST_IMPORT_UNSET}/a.rvs' references the unset environment variable `RVS_TEST_IMPORT_UNSET`"
+        );
+    }
+
+    #[test]
+    fn unterminated_quoted_path() {
+        let parser = Parser::new(SearchPath::default());
+        let error = parser.parse("import 'a.rvs;\na = 1;").unwrap_err();
+        assert!(error.to_string().starts_with("error at 1:15: expected"), "{}", error);
+    }
+
+    #[test]
+    fn escaped_quote_in_path() {
+        let fixtures = current_dir().unwrap().join("tests/import/quoted");
+        let parser = Parser::new(SearchPath::new(vec![fixtures]));
+        let error = parser.parse("import 'it\\'s.rvs';").unwrap_err();
+        assert!(error.to_string().contains("it's.rvs"), "{}", error);
+    }
+
+    #[test]
+    fn exceeds_max_depth() {
+        let fixtures = current_dir().unwrap().join("tests/import/depth");
+        let search_path = SearchPath::new(vec![fixtures]);
+        let mut parser = Parser::new(search_path);
+        parser.set_max_import_depth(4);
+        let error = parser.parse("import a;").unwrap_err();
+        let error = error.to_string();
+        assert!(error.starts_with("import of '"), "{}", error);
+        assert!(error.ends_with("e.rvs' exceeds the maximum import depth of 4"), "{}", error);
+    }
+
+    #[test]
+    fn cycle() {
+        let fixtures = current_dir().unwrap().join("tests/import/cycle");
+        let search_path = SearchPath::new(vec![fixtures]);
+        let parser = Parser::new(search_path);
+        let error = parser.parse("import a;").unwrap_err();
+        let error = error.to_string();
+        assert!(error.ends_with("a.rvs' creates an import cycle"), "{}", error);
+    }
+
+    #[test]
+    fn malformed() {
+        let fixtures = current_dir().unwrap().join("tests/import/malformed");
+        let search_path = SearchPath::new(vec![fixtures]);
+        let parser = Parser::new(search_path);
+        let error = parser.parse("import a;").unwrap_err().to_string();
+        assert!(error.contains("a.rvs' failed to parse: error at 2:5"), "{}", error);
+    }
+
     #[test]
     fn not_in_search_path() {
         let fixtures = current_dir().unwrap().join("tests/import");
@@ -15,6 +15,6 @@ fn expr_whitespace() {
 fn ast() {
     assert_eq!(
         parse(" a  = // comment0\n5 ; // comment1\nb=6;"),
-        "[Variable(\"a\", Number(5)), Variable(\"b\", Number(6))]"
+        "[Variable(\"a\", Number(5), Span { line: 1, column: 2 }, []), Variable(\"b\", Number(6), Span { line: 3, column: 1 }, [])]"
     );
 }
@@ -0,0 +1,25 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = let b = [0, 1] in b | 2;"),
+        "[Variable(\"a\", Let(\"b\", Type(Range, [Number(0), Number(1)]), BinaryOperation(RIdentifier(\"b\", Next, Span { line: 1, column: 23 }), Or, Number(2))), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn binding_with_in_operator() {
+    assert_eq!(
+        parse("a = let b = 1 in {1} in b;"),
+        "[Variable(\"a\", Let(\"b\", In(Number(1), [Number(1)]), RIdentifier(\"b\", Next, Span { line: 1, column: 25 })), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn keyword_boundaries() {
+    assert!(parse_result("letter = 1; a = letter;").is_ok());
+    assert!(parse_result("a = let b = 1 inb;").is_err());
+    assert!(parse_result("a = letb = 1 in b;").is_err());
+}
@@ -0,0 +1,36 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = [0, 100] in {1, [5, 8]};"),
+        "[Variable(\"a\", In(Type(Range, [Number(0), Number(100)]), [Number(1), Type(Range, [Number(5), Number(8)])]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn lowest_precedence() {
+    assert_eq!(
+        parse("a = 1 + 2 in {3};"),
+        "[Variable(\"a\", In(BinaryOperation(Number(1), Add, Number(2)), [Number(3)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn parenthesized() {
+    assert_eq!(
+        parse("a = (1 in {1}) + 1;"),
+        "[Variable(\"a\", BinaryOperation(In(Number(1), [Number(1)]), Add, Number(1)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn empty() {
+    assert!(parse_result("a = [0, 1] in {};").is_ok());
+}
+
+#[test]
+fn identifier_starting_with_in() {
+    assert!(parse_result("index = 1; a = index in {1};").is_ok());
+}
@@ -14,11 +14,54 @@ mod number {
 
     #[test]
     fn bad() {
-        assert!(parse_result("a = 0b0;").is_err());
+        assert!(parse_result("a = 0c0;").is_err());
         assert!(parse_result("a = 1z;").is_err());
     }
 }
 
+mod bin_number {
+    use super::*;
+
+    #[test]
+    fn good() {
+        assert_eq!(parse("a = 0b1010;"), "[Variable(\"a\", Number(10), Span { line: 1, column: 1 }, [])]");
+        assert_eq!(parse("a = 0B1_0;"), "[Variable(\"a\", Number(2), Span { line: 1, column: 1 }, [])]");
+        assert!(parse_result("a = 0b1111_1111_1111_1111_1111_1111_1111_1111;").is_ok());
+    }
+
+    #[test]
+    fn bad() {
+        assert!(parse_result("a = 0b2;").is_err());
+        assert!(parse_result("a = 0b_1;").is_err());
+        assert!(parse_result("a = 0b1_1111_1111_1111_1111_1111_1111_1111_1111;").is_err());
+    }
+}
+
+mod dont_care {
+    use super::*;
+
+    #[test]
+    fn binary() {
+        assert_eq!(parse("a = 0b10xx;"), "[Variable(\"a\", DontCare(8, 3), Span { line: 1, column: 1 }, [])]");
+        assert_eq!(parse("a = 0bX0x1;"), "[Variable(\"a\", DontCare(1, 10), Span { line: 1, column: 1 }, [])]");
+        assert_eq!(parse("a = 0bxxxx;"), "[Variable(\"a\", DontCare(0, 15), Span { line: 1, column: 1 }, [])]");
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse("a = 0x1?;"), "[Variable(\"a\", DontCare(16, 15), Span { line: 1, column: 1 }, [])]");
+        assert_eq!(parse("a = 0x??_?a;"), "[Variable(\"a\", DontCare(10, 65520), Span { line: 1, column: 1 }, [])]");
+    }
+
+    #[test]
+    fn bad() {
+        assert!(parse_result("a = 0x1x;").is_err());
+        assert!(parse_result("a = 0b1?;").is_err());
+        assert!(parse_result("a = 0x1_0000_000?;").is_err());
+        assert!(parse_result("enum E { A = 0b1x, }").is_err());
+    }
+}
+
 mod hex_number {
     use super::*;
 
@@ -37,3 +80,82 @@ mod hex_number {
         assert!(parse_result("a = 0X_A5;").is_err());
     }
 }
+
+mod suffixed_number {
+    use super::*;
+
+    #[test]
+    fn binary_multipliers() {
+        assert_eq!(parse("a = 4K;"), parse("a = 4096;"));
+        assert_eq!(parse("a = 4M;"), parse("a = 0x40_0000;"));
+        assert_eq!(parse("a = 2G;"), parse("a = 0x8000_0000;"));
+    }
+
+    #[test]
+    fn decimal_multipliers() {
+        assert_eq!(parse("a = 1k;"), parse("a = 1000;"));
+        assert_eq!(parse("a = 1m;"), parse("a = 1_000_000;"));
+        assert_eq!(parse("a = 4g;"), parse("a = 4_000_000_000;"));
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(parse_result("a = 3G;").is_ok());
+        assert!(parse_result("a = 4G;").is_err());
+        assert!(parse_result("a = 8G;").is_err());
+        assert!(parse_result("a = 5g;").is_err());
+    }
+
+    #[test]
+    fn overflow_message() {
+        let error = parse_result("a = 8G;").unwrap_err();
+        assert!(error.to_string().contains("suffixed integer literal within 32 bits"));
+    }
+
+    #[test]
+    fn bad() {
+        assert!(parse_result("a = 0x4K;").is_err());
+        assert!(parse_result("a = 4KB;").is_err());
+        assert!(parse_result("a = 4T;").is_err());
+    }
+}
+
+mod overflow {
+    use super::*;
+
+    fn error(s: &str) -> String {
+        parse_result(s).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn max() {
+        assert_eq!(parse("a = 0xffff_ffff;"), parse("a = 4294967295;"));
+    }
+
+    #[test]
+    fn hex() {
+        let error = error("a = 0x100000000;");
+        assert!(error.starts_with("error at 1:16: "), "{}", error);
+        assert!(error.contains("integer literal within 32 bits"), "{}", error);
+    }
+
+    #[test]
+    fn dec() {
+        let error = error("a = 4294967296;");
+        assert!(error.starts_with("error at 1:15: "), "{}", error);
+        assert!(error.contains("integer literal within 32 bits"), "{}", error);
+    }
+
+    #[test]
+    fn suffixed() {
+        let error = error("a = 4294967296K;");
+        assert!(error.contains("integer literal within 32 bits"), "{}", error);
+    }
+
+    // Binary literals are not supported.  Verifies a long binary literal errors rather than
+    // panics.
+    #[test]
+    fn binary() {
+        assert!(parse_result("a = 0b1_0000_0000_0000_0000_0000_0000_0000_0000;").is_err());
+    }
+}
@@ -0,0 +1,20 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("pair(a, b, 50);"),
+        "[Pair(\"a\", \"b\", Number(50), Span { line: 1, column: 1 })]"
+    );
+}
+
+#[test]
+fn missing_rho() {
+    assert!(parse_result("pair(a, b);").is_err());
+}
+
+#[test]
+fn pair_as_variable_name() {
+    assert!(parse_result("pair = 1;").is_ok());
+}
@@ -0,0 +1,15 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = r{70%: 0, 30%: 1};"),
+        "[Variable(\"a\", Weighted(With, [PercentWeightedSample(70, Number(0)), PercentWeightedSample(30, Number(1))]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn no_space_before_percent() {
+    assert!(parse_result("a = r{70 %: 0, 30%: 1};").is_err());
+}
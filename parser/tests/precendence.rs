@@ -5,7 +5,7 @@ use utils::*;
 fn unary_inv() {
     assert_eq!(
         parse("a = ~0 + 1;"),
-        "[Variable(\"a\", BinaryOperation(UnaryOperation(Inv, Number(0)), Add, Number(1)))]"
+        "[Variable(\"a\", BinaryOperation(UnaryOperation(Inv, Number(0)), Add, Number(1)), Span { line: 1, column: 1 }, [])]"
     );
 }
 
@@ -13,16 +13,29 @@ fn unary_inv() {
 fn unary_neg() {
     assert_eq!(
         parse("a = 4-1;"),
-        "[Variable(\"a\", BinaryOperation(Number(4), Sub, Number(1)))]"
+        "[Variable(\"a\", BinaryOperation(Number(4), Sub, Number(1)), Span { line: 1, column: 1 }, [])]"
     );
 
     assert_eq!(
         parse("a = 4+-1;"),
-        "[Variable(\"a\", BinaryOperation(Number(4), Add, UnaryOperation(Neg, Number(1))))]"
+        "[Variable(\"a\", BinaryOperation(Number(4), Add, UnaryOperation(Neg, Number(1))), Span { line: 1, column: 1 }, [])]"
     );
 
     assert_eq!(
         parse("a = -1;"),
-        "[Variable(\"a\", UnaryOperation(Neg, Number(1)))]"
+        "[Variable(\"a\", UnaryOperation(Neg, Number(1)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn abs_diff() {
+    assert_eq!(
+        parse("a = 1 <-> 2 * 3 + 4;"),
+        "[Variable(\"a\", BinaryOperation(BinaryOperation(Number(1), AbsDiff, BinaryOperation(Number(2), Mul, Number(3))), Add, Number(4)), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = 1 << 2 <-> 3;"),
+        "[Variable(\"a\", BinaryOperation(Number(1), Shl, BinaryOperation(Number(2), AbsDiff, Number(3))), Span { line: 1, column: 1 }, [])]"
     );
 }
@@ -0,0 +1,34 @@
+mod utils;
+use utils::*;
+
+#[test]
+fn ast() {
+    assert_eq!(
+        parse("a = r{0.25: 0, 0.750: 1};"),
+        "[Variable(\"a\", Weighted(With, [ProbabilityWeightedSample(25, 100, Number(0)), ProbabilityWeightedSample(750, 1000, Number(1))]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn weighted_choice() {
+    assert_eq!(
+        parse("a = (0.5: [0, 1], 1: 2, 50%: 3);"),
+        "[Variable(\"a\", Weighted(With, [ProbabilityWeightedSample(5, 10, Type(Range, [Number(0), Number(1)])), WeightedSample(1, Number(2)), PercentWeightedSample(50, Number(3))]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn weighted_choice_requires_weights() {
+    assert!(parse_result("a = (0.5: 0, 1);").is_err());
+    assert!(parse_result("a = (0, 1);").is_err());
+    assert!(parse_result("a = (1);").is_ok());
+}
+
+#[test]
+fn limits() {
+    assert!(parse_result("a = r{1.0: 0};").is_ok());
+    assert!(parse_result("a = r{1.5: 0};").is_err());
+    assert!(parse_result("a = r{2.0: 0};").is_err());
+    assert!(parse_result("a = r{0.123456789: 0};").is_ok());
+    assert!(parse_result("a = r{0.1234567890: 0};").is_err());
+}
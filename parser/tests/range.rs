@@ -5,6 +5,90 @@ use utils::*;
 fn ast() {
     assert_eq!(
         parse("a = [1,2];"),
-        "[Variable(\"a\", Type(Range, [Number(1), Number(2)]))]"
+        "[Variable(\"a\", Type(Range, [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
     );
 }
+
+#[test]
+fn profile() {
+    assert_eq!(
+        parse("a = [1, 2]: linear_up;"),
+        "[Variable(\"a\", Type(ProfiledRange(LinearUp), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2]: linear;"),
+        "[Variable(\"a\", Type(ProfiledRange(LinearUp), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2] : linear_down;"),
+        "[Variable(\"a\", Type(ProfiledRange(LinearDown), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2]: uniform;"),
+        "[Variable(\"a\", Type(Range, [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn unknown_profile() {
+    assert!(parse_result("a = [1, 2]: linearly;").is_err());
+}
+
+#[test]
+fn profile_suffix() {
+    assert_eq!(
+        parse("a = [1, 2]~normal;"),
+        "[Variable(\"a\", Type(ProfiledRange(Normal), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2] ~ triangular;"),
+        "[Variable(\"a\", Type(ProfiledRange(Triangular), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2]~linear;"),
+        "[Variable(\"a\", Type(ProfiledRange(LinearUp), [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+
+    assert_eq!(
+        parse("a = [1, 2]~uniform;"),
+        "[Variable(\"a\", Type(Range, [Number(1), Number(2)]), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn profile_suffix_in_expression() {
+    assert_eq!(
+        parse("a = [0, 10]~normal + 5;"),
+        "[Variable(\"a\", BinaryOperation(Type(ProfiledRange(Normal), [Number(0), Number(10)]), Add, Number(5)), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn profile_suffix_errors() {
+    // Only range literals take a profile
+    assert!(parse_result("a = 5~normal;").is_err());
+    assert!(parse_result("a = Pattern(1, 2)~normal;").is_err());
+
+    assert!(parse_result("a = [1, 2]~gaussian;").is_err());
+    assert!(parse_result("a = [1, 2]~normal: linear;").is_err());
+    assert!(parse_result("a = [1, 2]: linear~normal;").is_err());
+
+    // Ranges have no step so a profile does not make a 3rd bound valid
+    assert!(parse_result("a = [0, 10, 2]~normal;").is_err());
+    assert!(parse_result("a = [0, 10, 2]: normal;").is_err());
+}
+
+#[test]
+fn profile_suffix_alias() {
+    for profile in &["uniform", "linear_up", "linear", "linear_down", "normal", "triangular"] {
+        assert_eq!(
+            parse(&format!("a = [1, 2]~{};", profile)),
+            parse(&format!("a = [1, 2]: {};", profile))
+        );
+    }
+}
@@ -8,7 +8,7 @@ fn good() {
 
 #[test]
 fn ast() {
-    assert_eq!(parse("a=5;"), "[Variable(\"a\", Number(5))]");
+    assert_eq!(parse("a=5;"), "[Variable(\"a\", Number(5), Span { line: 1, column: 1 }, [])]");
 }
 
 #[test]
@@ -20,3 +20,23 @@ fn bad() {
 fn with_enum() {
     assert!(parse_result("a = Enum::Value;").is_ok());
 }
+
+#[test]
+fn method_chain() {
+    assert_eq!(
+        parse("a = b.copy.next;"),
+        "[Variable(\"a\", MethodChain(\"b\", [Copy, Next], Span { line: 1, column: 5 }), Span { line: 1, column: 1 }, [])]"
+    );
+    assert_eq!(
+        parse("a = b.next;"),
+        "[Variable(\"a\", RIdentifier(\"b\", Next, Span { line: 1, column: 5 }), Span { line: 1, column: 1 }, [])]"
+    );
+}
+
+#[test]
+fn done_method() {
+    assert_eq!(
+        parse("a = b.done;"),
+        "[Variable(\"a\", RIdentifier(\"b\", Done, Span { line: 1, column: 5 }), Span { line: 1, column: 1 }, [])]"
+    );
+}
@@ -36,7 +36,7 @@ fn eval(
     let rv = model.get_most_recently_added().unwrap();
     let mut rv = rv.borrow_mut();
 
-    let values: Vec<String> = vec![(0, false); 15]
+    let values: Vec<String> = [(0, false); 15]
         .iter()
         .map(|_| (rv.next(), rv.done()))
         .map(|(next, done)| {
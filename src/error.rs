@@ -2,13 +2,16 @@ use std::fmt;
 use std::io;
 use std::error;
 
-use rvs_parser;
 use rvs_parser::ParseError;
+use rvs_parser::ast::Span;
 
 #[derive(Debug)]
 pub enum Error {
     Parse(ParseError),
     Transform(TransformError),
+    Constraint(ConstraintError),
+    Budget(BudgetError),
+    Lookup(LookupError),
     Io(io::Error),
 }
 
@@ -17,6 +20,8 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct TransformError {
     pub description: String,
+    /// Location of the statement that caused the error, if known
+    pub span: Option<Span>,
 }
 
 impl error::Error for Error {
@@ -24,6 +29,9 @@ impl error::Error for Error {
         match *self {
             Error::Parse(ref err) => Some(err),
             Error::Transform(ref err) => Some(err),
+            Error::Constraint(ref err) => Some(err),
+            Error::Budget(ref err) => Some(err),
+            Error::Lookup(ref err) => Some(err),
             Error::Io(ref err) => Some(err),
         }
     }
@@ -34,6 +42,9 @@ impl fmt::Display for Error {
         match *self {
             Error::Parse(ref err) => err.fmt(f),
             Error::Transform(ref err) => err.fmt(f),
+            Error::Constraint(ref err) => err.fmt(f),
+            Error::Budget(ref err) => err.fmt(f),
+            Error::Lookup(ref err) => err.fmt(f),
             Error::Io(ref err) => err.fmt(f),
         }
     }
@@ -48,6 +59,12 @@ impl From<rvs_parser::Error> for Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
 impl From<TransformError> for Error {
     fn from(err: TransformError) -> Error {
         Error::Transform(err)
@@ -58,7 +75,21 @@ pub type TransformResult<T> = ::std::result::Result<T, TransformError>;
 
 impl TransformError {
     pub fn new(description: String) -> TransformError {
-        TransformError { description }
+        TransformError {
+            description,
+            span: None,
+        }
+    }
+
+    /// Attaches a source location to the error
+    ///
+    /// An existing location is kept since it is the most specific.
+    pub fn at(mut self, span: Span) -> TransformError {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+
+        self
     }
 }
 
@@ -73,7 +104,141 @@ impl error::Error for TransformError {
 }
 
 impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "error at {}: {}", span, self.description),
+            None => write!(f, "{}", self.description),
+        }
+    }
+}
+
+/// A non-fatal problem found during transform
+///
+/// Warnings do not prevent the transform from succeeding.  See `Transform::warnings`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformWarning {
+    pub description: String,
+    /// Location of the statement that caused the warning, if known
+    pub span: Option<Span>,
+}
+
+impl TransformWarning {
+    pub fn new(description: String) -> TransformWarning {
+        TransformWarning {
+            description,
+            span: None,
+        }
+    }
+
+    /// Attaches a source location to the warning
+    ///
+    /// An existing location is kept since it is the most specific.
+    pub fn at(mut self, span: Span) -> TransformWarning {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for TransformWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "warning at {}: {}", span, self.description),
+            None => write!(f, "warning: {}", self.description),
+        }
+    }
+}
+
+/// A failure to satisfy a constraint during evaluation
+///
+/// See `Model::next_group`.
+#[derive(Debug)]
+pub struct ConstraintError {
+    pub description: String,
+}
+
+impl ConstraintError {
+    pub fn new(description: String) -> ConstraintError {
+        ConstraintError { description }
+    }
+}
+
+impl error::Error for ConstraintError {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl fmt::Display for ConstraintError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.description)
     }
 }
+
+impl From<ConstraintError> for Error {
+    fn from(err: ConstraintError) -> Error {
+        Error::Constraint(err)
+    }
+}
+
+/// An evaluation that needed more re-samples than its budget
+///
+/// See `Variable::next_with_budget`.
+#[derive(Debug)]
+pub struct BudgetError {
+    pub max_iterations: u32,
+}
+
+impl BudgetError {
+    pub fn new(max_iterations: u32) -> BudgetError {
+        BudgetError { max_iterations }
+    }
+}
+
+impl error::Error for BudgetError {}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "evaluation budget of {} re-samples exceeded", self.max_iterations)
+    }
+}
+
+impl From<BudgetError> for Error {
+    fn from(err: BudgetError) -> Error {
+        Error::Budget(err)
+    }
+}
+
+/// A failure to find a variable by name
+///
+/// See `Model::get_result`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupError {
+    /// No variable of the name is defined
+    Undefined(String),
+    /// The model has not been populated by `Transform::transform`
+    NotTransformed(String),
+}
+
+impl error::Error for LookupError {}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LookupError::Undefined(ref name) => write!(f, "variable '{}' is not defined", name),
+            LookupError::NotTransformed(ref name) => write!(
+                f,
+                "cannot get variable '{}': the model has not been transformed",
+                name
+            ),
+        }
+    }
+}
+
+impl From<LookupError> for Error {
+    fn from(err: LookupError) -> Error {
+        Error::Lookup(err)
+    }
+}
@@ -1,22 +1,198 @@
+#![allow(clippy::vec_box)]
+
 mod error;
 mod parser;
 mod transform;
 mod model;
 mod types;
+mod selftest;
+mod producer;
+pub mod testing;
 
-pub use rvs_parser::SearchPath;
+pub use rvs_parser::{SearchPath, DEFAULT_MAX_IMPORT_DEPTH};
 pub use crate::parser::Parser;
-pub use crate::transform::{Seed, Transform};
-pub use crate::model::{Model, Variable};
+pub use crate::transform::{Seed, Transform, DEFAULT_MAX_RESAMPLE_ATTEMPTS};
+pub use crate::model::{JsonFormat, Model, ReplayLog, Stats, ValueTransform, Variable, VariableState, VariableSummary};
+pub use crate::model::{MAX_PAIR_RHO, MODEL_FORMAT_VERSION};
+pub use crate::types::{BinaryDoneMode, ExhaustedMode, MAX_TRUNC_NORMAL_STDDEVS, MAX_ZIPF_N};
+pub use crate::selftest::self_test;
+pub use crate::producer::{spawn_producer, spawn_producer_with, ProducerHandle};
+
+pub use crate::error::{BudgetError, ConstraintError, Error, LookupError, Result, TransformError, TransformWarning};
 
-pub use crate::error::{Error, Result};
+use rvs_parser::{ast, ParseError};
+
+use std::io::{BufReader, Read};
 
 pub fn parse(search_path: &SearchPath, s: &str) -> Result<Model> {
     let mut parser = Parser::new(search_path);
     parser.parse(s)?;
 
+    let mut transform = Transform::new(Default::default());
+    transform.set_search_path(search_path.clone());
+    let mut model = Model::new();
+    transform.transform(&mut model, parser.ast())?;
+    Ok(model)
+}
+
+/// Parses and transforms statements while collecting as many errors as possible
+///
+/// Parsing recovers from errors one statement at a time.  See `Parser::parse_recover`.  Returns
+/// all parse errors if any statement fails to parse.  The transform is skipped in that case to
+/// avoid cascading errors (e.g. references to a variable of a failed statement).  Otherwise,
+/// returns the transform error, if any.
+pub fn parse_all_errors(
+    search_path: &SearchPath,
+    s: &str,
+) -> ::std::result::Result<Model, Vec<Error>> {
+    let mut parser = Parser::new(search_path);
+    let errors = parser.parse_recover(s);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut transform = Transform::new(Default::default());
+    transform.set_search_path(search_path.clone());
+    let mut model = Model::new();
+    transform.transform(&mut model, parser.ast()).map_err(|e| vec![e.into()])?;
+    Ok(model)
+}
+
+/// Parses and transforms statements from a reader
+///
+/// Equivalent to `parse` but without reading the whole source into memory.  See
+/// `Parser::parse_reader`.
+pub fn parse_reader<R: Read>(search_path: &SearchPath, reader: R) -> Result<Model> {
+    let mut parser = Parser::new(search_path);
+    parser.parse_reader(BufReader::new(reader))?;
+
+    let mut transform = Transform::new(Default::default());
+    transform.set_search_path(search_path.clone());
+    let mut model = Model::new();
+    transform.transform(&mut model, parser.ast())?;
+    Ok(model)
+}
+
+/// Returns how many of the first `n` values of an expression differ between two seeds
+///
+/// Intended for picking well-separated seeds.  The expression is parsed once and transformed
+/// under each seed.  A constant expression, or two identical seeds, yields 0.
+pub fn seed_divergence(expr: &str, seed_a: Seed, seed_b: Seed, n: usize) -> Result<usize> {
+    let parser = parse_expression(expr)?;
+
+    let a = expression_values(&parser, seed_a, n)?;
+    let b = expression_values(&parser, seed_b, n)?;
+
+    Ok(a.iter().zip(b.iter()).filter(|(a, b)| a != b).count())
+}
+
+/// The name of the variable defined by `parse_expression`
+const EXPRESSION_VARIABLE: &str = "variable";
+
+/// Parses a single expression as the only statement of a parser
+///
+/// Returns an error if `expr` is not exactly one expression E.g. `1; b = 2`.
+pub(crate) fn parse_expression(expr: &str) -> Result<Parser> {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.parse(&format!("{} = {};", EXPRESSION_VARIABLE, expr))?;
+
+    let is_expression = |node: &ast::Node| {
+        matches!(*node, ast::Node::Variable(ref name, ..) if name == EXPRESSION_VARIABLE)
+    };
+
+    match parser.ast() {
+        [node] if is_expression(node) => Ok(parser),
+        _ => Err(Error::Parse(ParseError::new(format!(
+            "expected a single expression but found `{}`",
+            expr
+        )))),
+    }
+}
+
+/// Returns the first `n` values of the expression parsed by `parse_expression` transformed with
+/// `seed`
+pub(crate) fn expression_values(parser: &Parser, seed: Seed, n: usize) -> Result<Vec<u32>> {
+    let mut transform = Transform::new(seed);
+    let mut model = Model::new();
+    transform.transform(&mut model, parser.ast())?;
+
+    let mut variable = model.get_result(EXPRESSION_VARIABLE)?.borrow_mut();
+
+    Ok((0..n).map(|_| variable.next()).collect())
+}
+
+/// Maximum bracket nesting accepted by `parse_fuzz`
+pub const MAX_FUZZ_NESTING: usize = 128;
+
+/// Maximum number of operators and brackets in a single statement accepted by `parse_fuzz`
+pub const MAX_FUZZ_OPERATORS: usize = 256;
+
+/// Parses and transforms arbitrary bytes without panicking
+///
+/// Intended as the entry point for fuzzing.  Differs from `parse` in the following ways:
+///
+/// * The bytes are converted to UTF-8 with invalid sequences replaced.
+/// * `import` and `PatternFile` are rejected so the result does not depend on the file system.
+/// * Inputs nested deeper than `MAX_FUZZ_NESTING` or with statements containing more than
+///   `MAX_FUZZ_OPERATORS` operators are rejected before parsing to bound recursion.
+///
+/// Resource exhaustion (e.g. a sample with a weight of `0xffffffff`) is not bounded.
+pub fn parse_fuzz(bytes: &[u8]) -> Result<Model> {
+    let s = String::from_utf8_lossy(bytes);
+    check_fuzz_complexity(&s)?;
+
+    let search_path = SearchPath::default();
+    let mut parser = Parser::new(&search_path);
+    parser.set_max_import_depth(0);
+    parser.parse(&s)?;
+
+    if parser.ast().iter().any(|node| contains_pattern_file(node)) {
+        return Err(Error::Parse(ParseError::new("PatternFile is not supported".to_owned())));
+    }
+
     let mut transform = Transform::new(Default::default());
     let mut model = Model::new();
     transform.transform(&mut model, parser.ast())?;
+
     Ok(model)
 }
+
+/// Returns true if `node` or any of its descendants is a `PatternFile`
+fn contains_pattern_file(node: &ast::Node) -> bool {
+    matches!(*node, ast::Node::PatternFile(_))
+        || crate::parser::children(node).into_iter().any(contains_pattern_file)
+}
+
+fn check_fuzz_complexity(s: &str) -> Result<()> {
+    let mut nesting: usize = 0;
+    let mut operators = 0;
+
+    for c in s.chars() {
+        match c {
+            '(' | '[' | '{' => nesting += 1,
+            ')' | ']' | '}' => nesting = nesting.saturating_sub(1),
+            ';' => operators = 0,
+            _ => {}
+        }
+
+        if "([{~-+*/%&|^<>.=".contains(c) {
+            operators += 1;
+        }
+
+        if nesting > MAX_FUZZ_NESTING {
+            return Err(Error::Parse(ParseError::new(format!(
+                "nesting exceeds the maximum of {}",
+                MAX_FUZZ_NESTING
+            ))));
+        }
+
+        if operators > MAX_FUZZ_OPERATORS {
+            return Err(Error::Parse(ParseError::new(format!(
+                "statement exceeds the maximum of {} operators",
+                MAX_FUZZ_OPERATORS
+            ))));
+        }
+    }
+
+    Ok(())
+}
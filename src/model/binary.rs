@@ -0,0 +1,472 @@
+//! A compact binary encoding of transformed models
+//!
+//! Allows hosts to cache transformed models.  See `Model::serialize`.  The encoding starts with a
+//! magic number and a format version and ends with a checksum of everything before it.  The
+//! version is incremented whenever the encoding changes (e.g. a new expression type or a new
+//! field) so stale caches are rejected instead of misread.  Integers are little-endian.  Strings
+//! and lists are prefixed with their length as a `u32`.
+//!
+//! Expressions are encoded by `Expr::encode` as a tag followed by their fields and decoded by
+//! `Reader::expr`.
+
+use super::{Expr, ExprData, Model};
+use crate::error::{Result, TransformError, TransformResult};
+use crate::transform::CrateRng;
+use crate::types::*;
+
+use rvs_parser::ast::{BinaryOpcode, UnaryOpcode};
+use rvs_parser::binary::{decode_binary_opcode, decode_unary_opcode, encode_binary_opcode, encode_unary_opcode};
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+const MAGIC: &[u8; 4] = b"RVSM";
+
+/// The version of the encoding
+pub const MODEL_FORMAT_VERSION: u32 = 1;
+
+/// The tags that identify the type of an encoded expression
+pub mod tag {
+    pub const VALUE: u8 = 0;
+    pub const BINARY: u8 = 1;
+    pub const UNARY: u8 = 2;
+    pub const PATTERN: u8 = 3;
+    pub const SEQUENCE: u8 = 4;
+    pub const RANGE: u8 = 5;
+    pub const WEIGHTED_WITH_REPLACEMENT: u8 = 6;
+    pub const WEIGHTED_WITHOUT_REPLACEMENT: u8 = 7;
+    pub const NEXT: u8 = 8;
+    pub const PREV: u8 = 9;
+    pub const DONE_FLAG: u8 = 10;
+    pub const DONE: u8 = 11;
+    pub const ONCE: u8 = 12;
+    pub const EXCEPT: u8 = 13;
+    pub const COMPLEMENT: u8 = 14;
+    pub const SHUFFLE: u8 = 15;
+    pub const MUL_MOD: u8 = 16;
+    pub const ACCUMULATE: u8 = 17;
+    pub const HASH: u8 = 18;
+    pub const REPLAY: u8 = 19;
+    pub const ADD_CARRY: u8 = 20;
+    pub const BYTE_SWAP: u8 = 21;
+    pub const BIT_REVERSE: u8 = 22;
+    pub const POP_COUNT: u8 = 23;
+    pub const IN: u8 = 24;
+    pub const GRAY_CODE: u8 = 25;
+    pub const MOD_COUNTER: u8 = 26;
+    pub const RAMP: u8 = 27;
+    pub const CDF: u8 = 28;
+    pub const SUM: u8 = 29;
+    pub const MEAN: u8 = 30;
+    pub const WINDOW_MIN: u8 = 31;
+    pub const WINDOW_MAX: u8 = 32;
+    pub const LET: u8 = 33;
+    pub const BINDING: u8 = 34;
+    pub const ONE_HOT: u8 = 35;
+    pub const DECODE: u8 = 36;
+    pub const NO_REPEAT: u8 = 37;
+    pub const REPEAT: u8 = 38;
+    pub const HOLD_FOR: u8 = 39;
+    pub const ZIPF: u8 = 40;
+    pub const MEMO: u8 = 41;
+    pub const DONT_CARE: u8 = 42;
+    pub const AVOID_RECENT: u8 = 43;
+    pub const TIMESTAMP: u8 = 44;
+    pub const FLAT_OPERATION: u8 = 45;
+    pub const REDUCE: u8 = 46;
+    pub const PATTERN_FILE: u8 = 47;
+    pub const TRUNC_NORMAL: u8 = 48;
+    pub const POISSON: u8 = 49;
+    pub const WALK: u8 = 50;
+}
+
+/// Encodes a model in the binary format
+pub(crate) fn encode(model: &Model) -> Vec<u8> {
+    let mut writer = Writer(Vec::new());
+
+    writer.0.extend_from_slice(MAGIC);
+    writer.u32(MODEL_FORMAT_VERSION);
+    model.encode(&mut writer);
+
+    let checksum = checksum(&writer.0);
+    writer.u32(checksum);
+
+    writer.0
+}
+
+/// Decodes a model encoded by `encode` into `model`
+///
+/// Nothing is added to `model` if the bytes fail to decode.
+pub(crate) fn decode(model: &mut Model, bytes: &[u8]) -> Result<()> {
+    if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(TransformError::new("not a serialized model".into()).into());
+    }
+
+    let mut reader = Reader::new(&bytes[MAGIC.len()..]);
+    let version = reader.u32()?;
+    if version != MODEL_FORMAT_VERSION {
+        return Err(TransformError::new(format!(
+            "serialized model format version {} is not supported (expected {})",
+            version, MODEL_FORMAT_VERSION
+        ))
+        .into());
+    }
+
+    if bytes.len() < MAGIC.len() + 8 {
+        return Err(reader.error("unexpected end of input").into());
+    }
+    let (payload, expected) = bytes.split_at(bytes.len() - 4);
+    let expected = u32::from_le_bytes([expected[0], expected[1], expected[2], expected[3]]);
+    if checksum(payload) != expected {
+        return Err(TransformError::new("serialized model is corrupt (checksum mismatch)".into()).into());
+    }
+
+    let mut reader = Reader::new(&payload[MAGIC.len() + 4..]);
+    let decoded = Model::decode(&mut reader)?;
+    if reader.position != reader.bytes.len() {
+        return Err(reader.error("trailing bytes").into());
+    }
+
+    model.extend(decoded);
+
+    Ok(())
+}
+
+/// The FNV-1a hash of `bytes`
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0x811c_9dc5, |hash: u32, &byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+}
+
+/// Writes the fields of models, variables, and expressions
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    pub fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a length or an index
+    pub fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("length exceeds u32"));
+    }
+
+    pub fn str(&mut self, s: &str) {
+        self.len(s.len());
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    pub fn u32s(&mut self, values: &[u32]) {
+        self.len(values.len());
+        for &value in values {
+            self.u32(value);
+        }
+    }
+
+    pub fn lens(&mut self, values: &[usize]) {
+        self.len(values.len());
+        for &value in values {
+            self.len(value);
+        }
+    }
+
+    pub fn option_u32(&mut self, value: Option<u32>) {
+        self.bool(value.is_some());
+        if let Some(value) = value {
+            self.u32(value);
+        }
+    }
+
+    pub fn option_len(&mut self, value: Option<usize>) {
+        self.bool(value.is_some());
+        if let Some(value) = value {
+            self.len(value);
+        }
+    }
+
+    pub fn unary_opcode(&mut self, op: &UnaryOpcode) {
+        self.u8(encode_unary_opcode(op));
+    }
+
+    pub fn binary_opcode(&mut self, op: &BinaryOpcode) {
+        self.u8(encode_binary_opcode(op));
+    }
+
+    pub fn done_mode(&mut self, done_mode: BinaryDoneMode) {
+        self.u8(match done_mode {
+            BinaryDoneMode::Latched => 0,
+            BinaryDoneMode::Aligned => 1,
+        });
+    }
+
+    pub fn data(&mut self, data: &ExprData) {
+        self.u32(data.prev);
+        self.bool(data.done);
+    }
+
+    pub fn expr(&mut self, expr: &dyn Expr) {
+        expr.encode(self);
+    }
+
+    pub fn exprs(&mut self, exprs: &[Box<dyn Expr>]) {
+        self.len(exprs.len());
+        for expr in exprs {
+            self.expr(&**expr);
+        }
+    }
+
+    pub fn rng(&mut self, rng: &CrateRng) {
+        rng.encode(self);
+    }
+
+    pub fn option_rng(&mut self, rng: Option<&CrateRng>) {
+        self.bool(rng.is_some());
+        if let Some(rng) = rng {
+            self.rng(rng);
+        }
+    }
+}
+
+/// Reads the fields written by `Writer`
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// The values of the enclosing `let` bindings, innermost last.  Shared with the `Binding`s of
+    /// the same name.
+    bindings: Vec<(String, Rc<Cell<u32>>)>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader {
+            bytes,
+            position: 0,
+            bindings: Vec::new(),
+        }
+    }
+
+    pub fn error(&self, message: &str) -> TransformError {
+        TransformError::new(format!(
+            "invalid serialized model at byte {}: {}",
+            MAGIC.len() + 4 + self.position,
+            message
+        ))
+    }
+
+    /// Returns an error with `message` unless `condition` holds
+    pub fn check(&self, condition: bool, message: &str) -> TransformResult<()> {
+        if condition {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn take(&mut self, len: usize) -> TransformResult<&'a [u8]> {
+        if self.bytes.len() - self.position < len {
+            return Err(self.error("unexpected end of input"));
+        }
+
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> TransformResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> TransformResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(self.error(&format!("invalid bool {}", value))),
+        }
+    }
+
+    pub fn u32(&mut self) -> TransformResult<u32> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn u64(&mut self) -> TransformResult<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn len(&mut self) -> TransformResult<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    /// Reads a list length
+    ///
+    /// Each element takes at least one byte so a length greater than the remaining bytes is
+    /// rejected before anything is allocated for it.
+    fn list_len(&mut self) -> TransformResult<usize> {
+        let len = self.len()?;
+        self.check(len <= self.bytes.len() - self.position, "unexpected end of input")?;
+
+        Ok(len)
+    }
+
+    pub fn string(&mut self) -> TransformResult<String> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    pub fn u32s(&mut self) -> TransformResult<Vec<u32>> {
+        (0..self.list_len()?).map(|_| self.u32()).collect()
+    }
+
+    pub fn lens(&mut self) -> TransformResult<Vec<usize>> {
+        (0..self.list_len()?).map(|_| self.len()).collect()
+    }
+
+    pub fn option_u32(&mut self) -> TransformResult<Option<u32>> {
+        Ok(if self.bool()? { Some(self.u32()?) } else { None })
+    }
+
+    pub fn option_len(&mut self) -> TransformResult<Option<usize>> {
+        Ok(if self.bool()? { Some(self.len()?) } else { None })
+    }
+
+    pub fn unary_opcode(&mut self) -> TransformResult<UnaryOpcode> {
+        let code = self.u8()?;
+
+        decode_unary_opcode(code).ok_or_else(|| self.error(&format!("unknown unary opcode {}", code)))
+    }
+
+    pub fn binary_opcode(&mut self) -> TransformResult<BinaryOpcode> {
+        let code = self.u8()?;
+
+        decode_binary_opcode(code).ok_or_else(|| self.error(&format!("unknown binary opcode {}", code)))
+    }
+
+    pub fn done_mode(&mut self) -> TransformResult<BinaryDoneMode> {
+        match self.u8()? {
+            0 => Ok(BinaryDoneMode::Latched),
+            1 => Ok(BinaryDoneMode::Aligned),
+            code => Err(self.error(&format!("unknown done mode {}", code))),
+        }
+    }
+
+    pub fn data(&mut self) -> TransformResult<ExprData> {
+        Ok(ExprData {
+            prev: self.u32()?,
+            done: self.bool()?,
+        })
+    }
+
+    pub fn rng(&mut self) -> TransformResult<CrateRng> {
+        CrateRng::decode(self)
+    }
+
+    pub fn option_rng(&mut self) -> TransformResult<Option<CrateRng>> {
+        Ok(if self.bool()? { Some(self.rng()?) } else { None })
+    }
+
+    pub fn exprs(&mut self) -> TransformResult<Vec<Box<dyn Expr>>> {
+        (0..self.list_len()?).map(|_| self.expr()).collect()
+    }
+
+    /// Decodes the value of a `let` binding and the body it is visible in
+    pub fn scoped<T, F>(&mut self, name: &str, value: Rc<Cell<u32>>, decode: F) -> TransformResult<T>
+    where
+        F: FnOnce(&mut Reader<'a>) -> TransformResult<T>,
+    {
+        self.bindings.push((name.into(), value));
+        let result = decode(self);
+        self.bindings.pop();
+
+        result
+    }
+
+    /// Returns the value of the innermost enclosing `let` binding named `name`
+    pub fn binding(&self, name: &str) -> TransformResult<Rc<Cell<u32>>> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|binding| binding.0 == name)
+            .map(|binding| Rc::clone(&binding.1))
+            .ok_or_else(|| self.error(&format!("binding `{}` outside of its let", name)))
+    }
+
+    pub fn expr(&mut self) -> TransformResult<Box<dyn Expr>> {
+        let expr: Box<dyn Expr> = match self.u8()? {
+            tag::VALUE => Box::new(Value::decode(self)?),
+            tag::BINARY => Box::new(Binary::decode(self)?),
+            tag::UNARY => Box::new(Unary::decode(self)?),
+            tag::PATTERN => Box::new(Pattern::decode(self)?),
+            tag::SEQUENCE => Box::new(Sequence::decode(self)?),
+            tag::RANGE => Box::new(Range::decode(self)?),
+            tag::WEIGHTED_WITH_REPLACEMENT => Box::new(WeightedWithReplacement::decode(self)?),
+            tag::WEIGHTED_WITHOUT_REPLACEMENT => Box::new(WeightedWithoutReplacement::decode(self)?),
+            tag::NEXT => Box::new(Next::decode(self)?),
+            tag::PREV => Box::new(Prev::decode(self)?),
+            tag::DONE_FLAG => Box::new(DoneFlag::decode(self)?),
+            tag::DONE => Box::new(Done::decode(self)?),
+            tag::ONCE => Box::new(Once::decode(self)?),
+            tag::EXCEPT => Box::new(Except::decode(self)?),
+            tag::COMPLEMENT => Box::new(Complement::decode(self)?),
+            tag::SHUFFLE => Box::new(Shuffle::decode(self)?),
+            tag::MUL_MOD => Box::new(MulMod::decode(self)?),
+            tag::ACCUMULATE => Box::new(Accumulate::decode(self)?),
+            tag::HASH => Box::new(Hash::decode(self)?),
+            tag::REPLAY => Box::new(Replay::decode(self)?),
+            tag::ADD_CARRY => Box::new(AddCarry::decode(self)?),
+            tag::BYTE_SWAP => Box::new(ByteSwap::decode(self)?),
+            tag::BIT_REVERSE => Box::new(BitReverse::decode(self)?),
+            tag::POP_COUNT => Box::new(PopCount::decode(self)?),
+            tag::IN => Box::new(In::decode(self)?),
+            tag::GRAY_CODE => Box::new(GrayCode::decode(self)?),
+            tag::MOD_COUNTER => Box::new(ModCounter::decode(self)?),
+            tag::RAMP => Box::new(Ramp::decode(self)?),
+            tag::CDF => Box::new(Cdf::decode(self)?),
+            tag::SUM => Box::new(Sum::decode(self)?),
+            tag::MEAN => Box::new(Mean::decode(self)?),
+            tag::WINDOW_MIN => Box::new(WindowMin::decode(self)?),
+            tag::WINDOW_MAX => Box::new(WindowMax::decode(self)?),
+            tag::LET => Box::new(Let::decode(self)?),
+            tag::BINDING => Box::new(Binding::decode(self)?),
+            tag::ONE_HOT => Box::new(OneHot::decode(self)?),
+            tag::DECODE => Box::new(Decode::decode(self)?),
+            tag::NO_REPEAT => Box::new(NoRepeat::decode(self)?),
+            tag::REPEAT => Box::new(Repeat::decode(self)?),
+            tag::HOLD_FOR => Box::new(HoldFor::decode(self)?),
+            tag::ZIPF => Box::new(Zipf::decode(self)?),
+            tag::MEMO => Box::new(Memo::decode(self)?),
+            tag::DONT_CARE => Box::new(DontCare::decode(self)?),
+            tag::AVOID_RECENT => Box::new(AvoidRecent::decode(self)?),
+            tag::TIMESTAMP => Box::new(Timestamp::decode(self)?),
+            tag::FLAT_OPERATION => Box::new(FlatOperation::decode(self)?),
+            tag::REDUCE => Box::new(Reduce::decode(self)?),
+            tag::PATTERN_FILE => Box::new(PatternFile::decode(self)?),
+            tag::TRUNC_NORMAL => Box::new(TruncNormal::decode(self)?),
+            tag::POISSON => Box::new(Poisson::decode(self)?),
+            tag::WALK => Box::new(Walk::decode(self)?),
+            code => return Err(self.error(&format!("unknown expression tag {}", code))),
+        };
+
+        Ok(expr)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::ConstraintError;
+
+use log::warn;
+
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    /// The first resample limit exceeded by the checked evaluation in progress, if checked
+    static LIMIT_EXCEEDED: RefCell<Option<Option<ConstraintError>>> = const { RefCell::new(None) };
+    /// The number of re-samples remaining for the evaluation in progress, if budgeted
+    static REMAINING: Cell<Option<u32>> = const { Cell::new(None) };
+    /// Whether a re-sample of the evaluation in progress was refused
+    static EXCEEDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Accounts for one re-sample of a sub-expression
+///
+/// Called by every re-sampling loop before re-sampling.  Returns false if the budget is exhausted.
+/// The loop must then stop re-sampling and keep the value it has.  The evaluation runs to
+/// completion and its result is discarded by `with_budget`.  Always returns true outside of
+/// `with_budget`.
+#[must_use]
+pub(crate) fn consume() -> bool {
+    REMAINING.with(|remaining| match remaining.get() {
+        Some(0) => {
+            EXCEEDED.with(|exceeded| exceeded.set(true));
+            false
+        }
+        Some(n) => {
+            remaining.set(Some(n - 1));
+            true
+        }
+        None => true,
+    })
+}
+
+/// Runs `f` with a budget of `max_iterations` re-samples
+///
+/// Returns `None` if `f` exceeded the budget.  Budgets do not nest.  The budget of an inner call
+/// replaces the budget of the outer call for the duration of the inner call.
+pub(crate) fn with_budget<T, F: FnOnce() -> T>(max_iterations: u32, f: F) -> Option<T> {
+    let outer_remaining = REMAINING.with(|remaining| remaining.replace(Some(max_iterations)));
+    let outer_exceeded = EXCEEDED.with(|exceeded| exceeded.replace(false));
+
+    let value = f();
+
+    REMAINING.with(|remaining| remaining.set(outer_remaining));
+    let exceeded = EXCEEDED.with(|exceeded| exceeded.replace(outer_exceeded));
+
+    if exceeded {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Reports that a re-sampling loop exceeded its resample limit
+///
+/// The loop must then stop re-sampling and keep the value it has, as for an exhausted budget.
+/// Within `check_limits`, the first report is returned as an error.  Otherwise the report is
+/// logged as a warning.
+pub(crate) fn limit_exceeded(max_attempts: u32, failure: String) {
+    let err = ConstraintError::new(format!(
+        "resample limit of {} exceeded: {}",
+        max_attempts, failure
+    ));
+
+    LIMIT_EXCEEDED.with(|exceeded| match *exceeded.borrow_mut() {
+        Some(ref mut first @ None) => *first = Some(err),
+        Some(Some(_)) => {}
+        None => warn!("{}", err),
+    });
+}
+
+/// Runs `f` and returns an error if it exceeded a resample limit
+///
+/// The evaluation runs to completion.  Calls nest.  An inner call reports to its own caller only.
+pub(crate) fn check_limits<T, F: FnOnce() -> T>(f: F) -> Result<T, ConstraintError> {
+    let outer = LIMIT_EXCEEDED.with(|exceeded| exceeded.replace(Some(None)));
+    let result = f();
+    let exceeded = LIMIT_EXCEEDED.with(|exceeded| exceeded.replace(outer));
+
+    match exceeded {
+        Some(Some(err)) => Err(err),
+        _ => Ok(result),
+    }
+}
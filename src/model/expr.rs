@@ -1,25 +1,26 @@
 use crate::transform::CrateRng;
+use crate::types::Range;
+use super::{Model, Writer};
 
 use std::fmt;
 
-#[derive(Clone)]
+/// The maximum number of values `Expr::possible_values` enumerates
+///
+/// Larger sets (e.g. of a wide range or of an operation on many values) are not enumerated.
+pub const MAX_POSSIBLE_VALUES: usize = 1 << 12;
+
+#[derive(Clone, Default)]
 pub struct ExprData {
     pub prev: u32,
     pub done: bool,
 }
 
-impl Default for ExprData {
-    fn default() -> ExprData {
-        ExprData {
-            prev: 0,
-            done: false,
-        }
-    }
-}
-
 pub trait Expr: fmt::Display + ExprClone {
     fn next(&mut self, rng: &mut CrateRng) -> u32;
 
+    /// Returns the value returned by the most recent call to `next`
+    ///
+    /// Returns 0 if `next` has not been called.
     fn prev(&self) -> u32 {
         self.data().prev
     }
@@ -29,6 +30,173 @@ pub trait Expr: fmt::Display + ExprClone {
     }
 
     fn data(&self) -> &ExprData;
+
+    /// Writes the tag of the expression followed by its state
+    ///
+    /// The state includes everything `next` depends on, e.g. the position within a cycle and any
+    /// RNG owned by the expression, so the decoded expression continues where this one left off.
+    /// See `Reader::expr`.
+    fn encode(&self, writer: &mut Writer);
+
+    /// Returns the index of the sub-expression selected by the most recent call to `next`
+    ///
+    /// Only sampling expressions (e.g. `r{...}` and `{...}`) select sub-expressions.  Returns
+    /// `None` for all other expressions and if `next` has not been called.
+    fn last_selected(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the position within the current cycle of the value returned by the most recent
+    /// call to `next`
+    ///
+    /// Only cyclic expressions (e.g. `Pattern` and `Sequence`) have a position.  Returns `None`
+    /// for all other expressions and if `next` has not been called.
+    fn cycle_position(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the minimum and maximum values the expression can return if statically determinable
+    ///
+    /// Bounds are conservative.  Every value returned is within the bounds but not every value
+    /// within the bounds is necessarily returned.  Returns `None` if the bounds can't be
+    /// determined.  E.g. for an operation that may wrap.
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Returns every value the expression can return if statically determinable
+    ///
+    /// Like `value_bounds`, the values are conservative.  Every value returned is in the list but
+    /// not every value in the list is necessarily returned.  Returns `None` if the values can't be
+    /// enumerated or there are more than `MAX_POSSIBLE_VALUES`.  E.g. for a wide range.  Use
+    /// `value_bounds` instead in that case.
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        None
+    }
+
+    /// Returns true if the expression never produces a value of its own
+    ///
+    /// E.g. a sample with all weights 0 always returns 0 and is always done.  Operations reject
+    /// such operands at transform time.  The default is true if the expression has
+    /// sub-expressions and all of them are empty E.g. `Pattern({0: 1}, {0: 2})`.
+    fn is_empty(&self) -> bool {
+        let children = self.children();
+
+        !children.is_empty() && children.iter().all(|child| child.is_empty())
+    }
+
+    /// Returns a short description of the expression excluding its sub-expressions
+    ///
+    /// Used to label the nodes of `Variable::to_dot`.  The default is the name of the expression
+    /// for expressions displayed as `Name(...)` and the full display otherwise.
+    fn label(&self) -> String {
+        let display = self.to_string();
+
+        match display.find('(') {
+            Some(index)
+                if index > 0 && display[..index].chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                display[..index].to_owned()
+            }
+            _ => display,
+        }
+    }
+
+    /// Returns the expression as a `Range` if it is one
+    fn as_range(&self) -> Option<&Range> {
+        None
+    }
+
+    /// Returns the expression as a mutable `Range` if it is one
+    fn as_range_mut(&mut self) -> Option<&mut Range> {
+        None
+    }
+
+    /// Returns the weights of the sub-expressions in order if the expression is a weighted sample
+    ///
+    /// The weights are those used for selection and are not normalized.  Plain and percentage
+    /// weights are as written.  Probability weights are scaled to integers over their largest
+    /// denominator then reduced by their greatest common divisor.  E.g.
+    /// `r{0.5: 0, 0.25: 1, 0.25: 2}` has weights 2, 1, and 1.  Unweighted sub-expressions
+    /// (including those of `Expand`) have a weight of 1.  `CDF` weights are the differences
+    /// between cumulative percentages.
+    fn weights(&self) -> Option<&[u32]> {
+        None
+    }
+
+    /// Replaces any RNGs owned by the expression with RNGs derived from `rng`
+    ///
+    /// Used by `Variable::fork` so that copies don't repeat the streams of the original.  Most
+    /// expressions use the RNG passed to `next` and own no RNGs.  The default recurses into all
+    /// sub-expressions.
+    fn fork_rngs(&mut self, rng: &mut CrateRng) {
+        for child in self.children_mut() {
+            child.fork_rngs(rng);
+        }
+    }
+
+    /// Returns true if the expression returns the same values regardless of seed
+    ///
+    /// Computed structurally.  Expressions that use the RNG (e.g. ranges and samples) are not
+    /// deterministic even if every value they can return is the same.  The default is true if all
+    /// sub-expressions are deterministic.
+    fn is_deterministic(&self) -> bool {
+        self.children().iter().all(|child| child.is_deterministic())
+    }
+
+    /// Returns references to all sub-expressions
+    fn children(&self) -> Vec<&dyn Expr> {
+        Vec::new()
+    }
+
+    /// Returns mutable references to all sub-expressions
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        Vec::new()
+    }
+
+    /// Points all variable references at the variables of the same name in `model`
+    fn relink(&mut self, model: &Model) {
+        for child in self.children_mut() {
+            child.relink(model);
+        }
+    }
+}
+
+/// Returns the union of the bounds of `exprs`
+///
+/// Returns `None` if there are no expressions or if the bounds of any expression are unknown.
+pub fn union_bounds<'a, I>(exprs: I) -> Option<(u32, u32)>
+where
+    I: IntoIterator<Item = &'a Box<dyn Expr>>,
+{
+    let mut bounds: Option<(u32, u32)> = None;
+    for expr in exprs {
+        let (min, max) = expr.value_bounds()?;
+        bounds = Some(match bounds {
+            Some(bounds) => (bounds.0.min(min), bounds.1.max(max)),
+            None => (min, max),
+        });
+    }
+
+    bounds
+}
+
+/// Returns the union of the possible values of `exprs`
+///
+/// Returns `None` if the possible values of any expression are unknown.
+pub fn union_values<'a, I>(exprs: I) -> Option<Vec<u32>>
+where
+    I: IntoIterator<Item = &'a Box<dyn Expr>>,
+{
+    let mut values = Vec::new();
+    for expr in exprs {
+        values.extend(expr.possible_values()?);
+    }
+
+    values.sort_unstable();
+    values.dedup();
+
+    Some(values)
 }
 
 /// Used to implement clone for all implementors of Expr trait.
@@ -1,8 +1,23 @@
 mod expr;
 mod variable;
-#[cfg_attr(feature = "cargo-clippy", allow(module_inception))]
+mod stats;
+mod state;
+mod replay;
+mod summary;
+mod binary;
+pub(crate) mod budget;
+pub(crate) mod substitution;
+#[allow(clippy::module_inception)]
 mod model;
 
-pub use self::model::Model;
-pub use self::variable::{Variable, VariableRef, VariableWeak};
-pub use self::expr::{Expr, ExprData};
+pub use self::model::{JsonFormat, Model, MAX_PAIR_RHO};
+pub(crate) use self::model::{format_number, DeferredVariable};
+pub use self::variable::{ValueTransform, Variable, VariableRef, VariableWeak};
+pub(crate) use self::variable::VariableCheckpoint;
+pub use self::expr::{union_bounds, union_values, Expr, ExprData, MAX_POSSIBLE_VALUES};
+pub use self::stats::Stats;
+pub use self::state::VariableState;
+pub use self::summary::VariableSummary;
+use self::summary::SummaryTable;
+pub use self::binary::{tag, Reader, Writer, MODEL_FORMAT_VERSION};
+pub use self::replay::{Recorder, ReplayLog, VariableRecorder};
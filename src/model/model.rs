@@ -1,13 +1,90 @@
-use super::VariableRef;
+use super::{budget, Variable, VariableCheckpoint, VariableRef};
+use super::{Recorder, ReplayLog, VariableRecorder};
+use super::{SummaryTable, VariableSummary};
+use super::{binary, Reader, Writer};
+use crate::error::{ConstraintError, LookupError, Result, TransformError, TransformResult, TransformWarning};
+use crate::transform::CrateRng;
+use crate::types::Replay;
+
+use rvs_parser::ast::Span;
 
 use indexmap::{map::Entry, IndexMap};
+use log::warn;
+use std::collections::HashMap;
 use std::fmt;
+use std::rc::Rc;
+use std::cell::RefCell;
 
 #[derive(Default)]
 pub struct Model {
     variables: Vec<VariableRef>,
     variable_indexes: IndexMap<String, usize>,
     most_recent: usize,
+    recorder: Option<Rc<RefCell<Recorder>>>,
+    enums: IndexMap<String, IndexMap<String, u32>>,
+    distincts: Vec<Distinct>,
+    pairs: Vec<Pair>,
+    gates: Vec<Gate>,
+    transformed: bool,
+    warnings: Vec<TransformWarning>,
+    tag: u32,
+    /// The maximum number of re-samples per evaluation.  See `set_eval_budget`.
+    eval_budget: Option<u32>,
+    /// Variables whose expressions have not been built yet.  See `Transform::set_lazy`.
+    deferred: RefCell<HashMap<usize, Rc<dyn DeferredVariable>>>,
+    /// Variable states saved by `checkpoint` by name
+    checkpoints: HashMap<String, Vec<VariableCheckpoint>>,
+}
+
+/// A `pair(a, b, rho);` correlation
+///
+/// Only applied by `Model::next_group`.
+#[derive(Clone)]
+struct Pair {
+    a: String,
+    b: String,
+    rho: u32,
+    /// Draws the shared values so that neither variable is advanced by the blend
+    rng: RefCell<CrateRng>,
+}
+
+/// A `gate a by b;` coordination
+///
+/// Only applied by `Model::next_group`.
+#[derive(Clone)]
+struct Gate {
+    a: String,
+    b: String,
+}
+
+/// How `Model::sample_to_json` writes values
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum JsonFormat {
+    /// JSON numbers in decimal E.g. `255`
+    #[default]
+    Number,
+    /// JSON strings in hexadecimal E.g. `"0xff"`
+    HexString,
+}
+
+/// The `rho` of a `pair` that makes both variables equal
+pub const MAX_PAIR_RHO: u32 = 100;
+
+/// A variable whose expression is built on first access
+pub(crate) trait DeferredVariable {
+    /// Builds the variable
+    ///
+    /// Variables referenced by the variable are built via `model` as needed.
+    fn build(&self, model: &Model) -> TransformResult<Variable>;
+}
+
+/// A `distinct(a, b, ...);` constraint
+///
+/// Only enforced by `Model::next_group`.
+#[derive(Clone)]
+struct Distinct {
+    names: Vec<String>,
+    max_attempts: u32,
 }
 
 impl Model {
@@ -28,6 +105,7 @@ impl Model {
         match self.variable_indexes.entry(name.into()) {
             Entry::Occupied(entry) => {
                 *most_recent = *entry.get();
+                self.deferred.get_mut().remove(most_recent);
                 variables[*most_recent] = variable;
 
                 *most_recent
@@ -42,46 +120,993 @@ impl Model {
         }
     }
 
+    /// Adds an enum definition to the model
+    ///
+    /// Enum definitions are for introspection only.  Enum members are replaced with their values
+    /// during transform.
+    pub(crate) fn add_enum(&mut self, name: &str, members: IndexMap<String, u32>) {
+        self.enums.insert(name.into(), members);
+    }
+
+    /// Adds a `distinct` constraint to the model
+    ///
+    /// `max_attempts` limits the number of re-samples `next_group` performs to satisfy the
+    /// constraint.
+    pub(crate) fn add_distinct(&mut self, names: Vec<String>, max_attempts: u32) {
+        self.distincts.push(Distinct { names, max_attempts });
+    }
+
+    /// Defers building the variable at `index` until it is first accessed
+    ///
+    /// The variable at `index` is a placeholder until then.
+    pub(crate) fn defer(&mut self, index: usize, variable: Rc<dyn DeferredVariable>) {
+        self.deferred.get_mut().insert(index, variable);
+    }
+
+    /// Builds the variable at `index` if it was deferred by a lazy transform
+    ///
+    /// Does nothing if the variable has already been built.  Variables referenced by the variable
+    /// are built first.  Returns an error if the expression of the variable fails to transform.
+    /// A failed build is retried (and fails again) on the next call.  See `Transform::set_lazy`.
+    pub fn build(&self, index: usize) -> Result<()> {
+        Ok(self.build_deferred(index)?)
+    }
+
+    pub(crate) fn build_deferred(&self, index: usize) -> TransformResult<()> {
+        let deferred = self.deferred.borrow_mut().remove(&index);
+
+        if let Some(deferred) = deferred {
+            match deferred.build(self) {
+                Ok(variable) => **self.variables[index].borrow_mut() = variable,
+                Err(e) => {
+                    self.deferred.borrow_mut().insert(index, deferred);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds all variables deferred by a lazy transform
+    ///
+    /// Returns the first error.  See `build`.
+    pub fn build_all(&self) -> Result<()> {
+        for index in 0..self.variables.len() {
+            self.build(index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the variable at `index` has been built
+    ///
+    /// Variables are always built unless deferred by a lazy transform.
+    pub fn is_built(&self, index: usize) -> bool {
+        !self.deferred.borrow().contains_key(&index)
+    }
+
+    /// Adds a `pair` correlation to the model
+    ///
+    /// `rho` must be at most `MAX_PAIR_RHO`.
+    pub(crate) fn add_pair(&mut self, a: String, b: String, rho: u32, rng: CrateRng) {
+        self.pairs.push(Pair {
+            a,
+            b,
+            rho,
+            rng: RefCell::new(rng),
+        });
+    }
+
+    /// Adds a `gate` coordination to the model
+    ///
+    /// `a` must not already be gated and the gate must not form a cycle.
+    pub(crate) fn add_gate(&mut self, a: String, b: String) {
+        self.gates.push(Gate { a, b });
+    }
+
+    /// Returns the name of the variable that gates the variable `name`
+    ///
+    /// Returns `None` if the variable is not gated.
+    pub(crate) fn gate_of(&self, name: &str) -> Option<&str> {
+        self.gates
+            .iter()
+            .find(|gate| gate.a == name)
+            .map(|gate| gate.b.as_str())
+    }
+
+    /// Returns the names of all enums in the order they were defined
+    pub fn enum_names(&self) -> impl Iterator<Item = &str> {
+        self.enums.keys().map(String::as_str)
+    }
+
+    /// Returns the `(member name, value)` pairs of an enum in the order they were defined
+    ///
+    /// Returns `None` if the enum does not exist.
+    pub fn enum_members(&self, name: &str) -> Option<impl Iterator<Item = (&str, u32)>> {
+        let members = self.enums.get(name)?;
+
+        Some(members.iter().map(|(name, value)| (name.as_str(), *value)))
+    }
+
+    /// Appends warnings found while transforming the model
+    pub(crate) fn add_warnings(&mut self, warnings: &[TransformWarning]) {
+        self.warnings.extend_from_slice(warnings);
+    }
+
+    /// Returns the warnings found by the transforms that populated the model
+    ///
+    /// Warnings are kept until cleared by `clear_warnings`.  Warnings of variables built lazily
+    /// are logged but not collected.  See `Transform::warnings`.
+    pub fn warnings(&self) -> &[TransformWarning] {
+        &self.warnings
+    }
+
+    /// Discards all collected warnings
+    pub fn clear_warnings(&mut self) {
+        self.warnings.clear();
+    }
+
+    /// Sets a value that identifies the origin of the model to users of variable handles
+    ///
+    /// Not used by the model itself.  E.g. the C API rejects variable handles with a different
+    /// tag.  Forks share the tag.  Defaults to 0.
+    pub fn set_tag(&mut self, tag: u32) {
+        self.tag = tag;
+    }
+
+    /// Returns the value set by `set_tag`
+    pub fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Sets the maximum number of re-samples per evaluation of a variable
+    ///
+    /// Not used by the model itself.  Users of the model pass the budget to
+    /// `Variable::next_with_budget`.  E.g. the C API applies the budget in `rvs_next_checked`.
+    /// Forks share the budget.  Defaults to `None` (unlimited).
+    pub fn set_eval_budget(&mut self, max_iterations: Option<u32>) {
+        self.eval_budget = max_iterations;
+    }
+
+    /// Returns the value set by `set_eval_budget`
+    pub fn eval_budget(&self) -> Option<u32> {
+        self.eval_budget
+    }
+
+    /// Marks the model as populated by `Transform::transform`
+    pub(crate) fn set_transformed(&mut self) {
+        self.transformed = true;
+    }
+
+    /// Returns a variable by name
+    ///
+    /// Unlike `get_variable_by_name`, returns an error if the model has not been populated by
+    /// `Transform::transform` (e.g. `Model::new` was used without a transform step).  Returns
+    /// `Ok(None)` if the variable does not exist.
+    ///
+    /// Builds the variable if it was deferred by a lazy transform.  Returns an error if the build
+    /// fails.  See `build`.
+    pub fn get(&self, name: &str) -> Result<Option<&VariableRef>> {
+        if !self.transformed {
+            return Err(TransformError::new(format!(
+                "cannot get variable '{}': the model has not been transformed",
+                name
+            ))
+            .into());
+        }
+
+        match self.get_variable_index(name) {
+            Some(index) => {
+                self.build(index)?;
+                Ok(self.get_variable_by_index(index))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a variable by name
+    ///
+    /// Like `get` but returns a `LookupError` instead of `None` so callers can tell why the
+    /// variable is unavailable.  Returns an `Error::Lookup` if the model has not been transformed
+    /// or the variable is not defined.  Returns an `Error::Transform` if the variable was deferred
+    /// by a lazy transform and fails to build.
+    pub fn get_result(&self, name: &str) -> Result<&VariableRef> {
+        if !self.transformed {
+            return Err(LookupError::NotTransformed(name.into()).into());
+        }
+
+        let index = self
+            .get_variable_index(name)
+            .ok_or_else(|| LookupError::Undefined(name.into()))?;
+        self.build(index)?;
+
+        Ok(&self.variables[index])
+    }
+
     pub fn get_variable_index(&self, name: &str) -> Option<usize> {
         let index = self.variable_indexes.get(name)?;
         Some(*index)
     }
 
+    /// Returns the variable at `index`
+    ///
+    /// Builds the variable if it was deferred by a lazy transform.  Returns `None` if the build
+    /// fails.  Use `build` or `get` for the error.
     pub fn get_variable_by_index(&self, index: usize) -> Option<&VariableRef> {
         let variable = self.variables.get(index)?;
+        self.build_deferred(index).ok()?;
         Some(variable)
     }
 
+    /// Returns the variable named `name`
+    ///
+    /// Builds the variable if it was deferred by a lazy transform.  Returns `None` if the build
+    /// fails.  Use `build` or `get` for the error.
     pub fn get_variable_by_name(&self, name: &str) -> Option<&VariableRef> {
         let index = self.variable_indexes.get(name)?;
-        let variable = self.variables.get(*index)?;
-        Some(variable)
+        self.get_variable_by_index(*index)
+    }
+
+    /// Returns the variable named `name` without building it
+    ///
+    /// Used to link variable references while other variables are borrowed.
+    pub(crate) fn get_unbuilt_variable_by_name(&self, name: &str) -> Option<&VariableRef> {
+        let index = self.variable_indexes.get(name)?;
+        self.variables.get(*index)
+    }
+
+    /// Returns the indexes of all variables whose names start with `prefix`
+    ///
+    /// Indexes are returned in the order the variables were defined.
+    pub fn get_variable_indexes_by_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.variable_indexes
+            .iter()
+            .filter(move |(name, _)| name.starts_with(prefix))
+            .map(|(_, index)| *index)
     }
 
     pub fn get_most_recently_added(&self) -> Option<&VariableRef> {
         self.variables.get(self.most_recent)
     }
 
-    pub fn variables_iter(&self) -> VariablesIter {
+    pub fn variables_iter(&self) -> VariablesIter<'_> {
         VariablesIter {
             iter: self.variable_indexes.iter(),
             variables: &self.variables,
         }
     }
+
+    /// Advances each variable once and yields its name and value
+    ///
+    /// Variables are advanced lazily in the order they were defined.  Each variable is borrowed
+    /// only while it is advanced so variables that reference other variables can be advanced.
+    /// Referenced variables are advanced by both the reference and their own turn.
+    ///
+    /// Builds all variables first (see `build_all`).  Returns an error if any of them fails to
+    /// build.
+    pub fn iter_values(&mut self) -> Result<impl Iterator<Item = (String, u32)> + '_> {
+        self.build_all()?;
+
+        Ok(self
+            .variables_iter()
+            .map(|(name, variable)| (name.to_owned(), variable.borrow_mut().next())))
+    }
+
+    /// Advances each variable `n` times and returns the values as JSON
+    ///
+    /// Builds all variables first (see `build_all`).  Variables (including constants) are
+    /// evaluated in the order they were defined.  The JSON is an object with one member per
+    /// variable E.g.
+    ///
+    /// ```text
+    /// {"a": {"values": [1, 2, 1], "first_done": 1}}
+    /// ```
+    ///
+    /// `first_done` is the index of the first value for which the variable indicated done or
+    /// `null` if it never did.  Variables keep returning values after done.  Values are written as
+    /// specified by `format`.  Values of signed variables (see `Variable::set_signed`) are written
+    /// as `i32` E.g. `-1` or `"-0x1"` for `0xffffffff`.
+    pub fn sample_to_json(&self, n: usize, format: JsonFormat) -> Result<String> {
+        self.build_all()?;
+
+        let members: Vec<String> = self
+            .variables_iter()
+            .map(|(name, variable)| {
+                let mut variable = variable.borrow_mut();
+                let mut values = Vec::with_capacity(n);
+                let mut first_done = None;
+                for i in 0..n {
+                    let value = variable.next();
+                    let signed = variable.is_signed();
+                    values.push(match format {
+                        JsonFormat::Number => format_number(value, signed),
+                        JsonFormat::HexString => format!("\"{}\"", format_hex(value, signed)),
+                    });
+                    if first_done.is_none() && variable.done() {
+                        first_done = Some(i);
+                    }
+                }
+
+                // Variable names are identifiers so they need no escaping
+                format!(
+                    "\"{}\": {{\"values\": [{}], \"first_done\": {}}}",
+                    name,
+                    values.join(", "),
+                    first_done.map_or("null".to_owned(), |i| i.to_string())
+                )
+            })
+            .collect();
+
+        Ok(format!("{{{}}}", members.join(", ")))
+    }
+
+    /// Draws `n` values from each variable and returns statistics of the values
+    ///
+    /// Builds all variables first (see `build_all`).  Variables (including constants) are
+    /// evaluated in the order they were defined.  Variables keep returning values after done.
+    pub fn summarize(&self, n: usize) -> Result<Vec<VariableSummary>> {
+        self.build_all()?;
+
+        Ok(self
+            .variables_iter()
+            .map(|(name, variable)| VariableSummary::new(name, &mut variable.borrow_mut(), n))
+            .collect())
+    }
+
+    /// Draws `n` values from each variable and returns statistics of the values as a text table
+    ///
+    /// One row per variable with the min, max, and mean in hexadecimal, the number of distinct
+    /// values, and the index of the first value for which the variable indicated done E.g.
+    ///
+    /// ```text
+    /// name  min  max  mean  distinct  first_done
+    /// a     0x0  0x9  0x5   10        -
+    /// b     0x1  0x3  0x2   3         2
+    /// ```
+    ///
+    /// `-` marks values that don't exist.  See `summarize`.
+    pub fn summary(&self, n: usize) -> Result<String> {
+        Ok(SummaryTable(&self.summarize(n)?).to_string())
+    }
+
+    /// Draws `n` values from each variable and returns statistics of the values as JSON
+    ///
+    /// The JSON is an object with one member per variable E.g.
+    ///
+    /// ```text
+    /// {"a": {"min": 0, "max": 9, "mean": 5, "distinct": 10, "first_done": null}}
+    /// ```
+    ///
+    /// `null` marks values that don't exist.  See `summarize`.
+    pub fn summary_to_json(&self, n: usize) -> Result<String> {
+        let members: Vec<String> = self.summarize(n)?.iter().map(VariableSummary::to_json).collect();
+
+        Ok(format!("{{{}}}", members.join(", ")))
+    }
+
+    /// Evaluates a group of variables together subject to `gate` coordinations, `pair`
+    /// correlations, and `distinct` constraints
+    ///
+    /// Each variable is advanced once except for variables gated by a member of the group.  A gated
+    /// variable is advanced only if the value of its gate is non-zero and otherwise holds (i.e.
+    /// returns its previous value).  Gates are evaluated before the variables they gate.  A gated
+    /// variable is always advanced on its first evaluation since it has no previous value.  Gates
+    /// outside of the group are ignored.  Then, for every `pair` with both members in the group
+    /// and neither member held, a shared value is drawn from a copy of the expression of the first
+    /// member with an RNG of the pair and both values are blended toward it by
+    /// `rho / MAX_PAIR_RHO`.  Neither member is advanced by the draw (see `Variable::sample`).  A
+    /// `rho` of 0 leaves the values untouched.  A `rho` of `MAX_PAIR_RHO` makes both values equal.
+    /// Blended values are only returned.  `prev` of a member is its value before blending.  Then,
+    /// for every `distinct` constraint, members of the group that repeat the value of an earlier
+    /// member are advanced again.  If the later member is held, the earlier member is advanced
+    /// instead.  Held members are never advanced so two held members may repeat a value.  All
+    /// constraints are checked again after any re-sample until every constraint holds.  Constraint
+    /// members outside of the group are ignored.  Returns the final values in the order of
+    /// `names`.
+    ///
+    /// Returns an error if a variable does not exist or if the constraints can't be satisfied
+    /// together.  A `distinct` constraint whose members can't return distinct values given their
+    /// possible values (see `Variable::possible_values`) is reported before any variable is
+    /// advanced.  Otherwise the re-samples of all constraints count against the resample limit of
+    /// the constraint being satisfied.  Also returns an error if an expression of a variable
+    /// exceeds its resample limit (see `Variable::next_checked`).
+    pub fn next_group(&self, names: &[&str]) -> Result<Vec<u32>> {
+        budget::check_limits(|| self.evaluate_group(names))?
+    }
+
+    /// Evaluates a group of variables.  See `next_group`.
+    fn evaluate_group(&self, names: &[&str]) -> Result<Vec<u32>> {
+        let mut variables = Vec::with_capacity(names.len());
+        for name in names {
+            match self.get_variable_index(name) {
+                Some(index) => {
+                    self.build(index)?;
+                    variables.push(&self.variables[index]);
+                }
+                None => {
+                    return Err(ConstraintError::new(format!(
+                        "Unknown variable '{}' in group",
+                        name
+                    ))
+                    .into())
+                }
+            }
+        }
+
+        let distincts: Vec<(&Distinct, Vec<usize>)> = self
+            .distincts
+            .iter()
+            .map(|distinct| {
+                let positions = distinct
+                    .names
+                    .iter()
+                    .filter_map(|name| names.iter().position(|n| n == name))
+                    .collect();
+
+                (distinct, positions)
+            })
+            .collect();
+
+        for (distinct, positions) in &distincts {
+            if !can_be_distinct(&variables, positions) {
+                let err = ConstraintError::new(format!(
+                    "could not satisfy {}: the variables can't return distinct values",
+                    distinct
+                ));
+                warn!("{}", err);
+
+                return Err(err.into());
+            }
+        }
+
+        let mut gated = vec![None; names.len()];
+        let mut held = vec![false; names.len()];
+        for index in 0..names.len() {
+            self.next_gated(names, &variables, &mut gated, &mut held, index);
+        }
+        let mut values: Vec<u32> = gated.into_iter().map(Option::unwrap).collect();
+
+        for pair in &self.pairs {
+            if pair.rho == 0 {
+                continue;
+            }
+
+            let a = names.iter().position(|name| *name == pair.a);
+            let b = names.iter().position(|name| *name == pair.b);
+
+            if let (Some(a), Some(b)) = (a, b) {
+                if held[a] || held[b] {
+                    continue;
+                }
+
+                let shared = variables[a].borrow().sample(&mut pair.rng.borrow_mut());
+
+                values[a] = blend(values[a], shared, pair.rho);
+                values[b] = blend(values[b], shared, pair.rho);
+            }
+        }
+
+        // Re-sampling a member of one constraint may break an earlier constraint that shares the
+        // member so all constraints are checked again until none needs a re-sample.
+        let mut attempts = 0;
+        loop {
+            let mut resampled = false;
+
+            for (distinct, positions) in &distincts {
+                for (i, &a) in positions.iter().enumerate() {
+                    for &b in &positions[i + 1..] {
+                        if values[a] != values[b] {
+                            continue;
+                        }
+
+                        let resample = if !held[b] {
+                            b
+                        } else if !held[a] {
+                            a
+                        } else {
+                            continue;
+                        };
+
+                        if attempts >= distinct.max_attempts {
+                            let err = ConstraintError::new(format!(
+                                "resample limit of {} exceeded: could not satisfy {}",
+                                distinct.max_attempts, distinct
+                            ));
+                            warn!("{}", err);
+
+                            return Err(err.into());
+                        }
+
+                        if !budget::consume() {
+                            return Ok(values);
+                        }
+                        values[resample] = variables[resample].borrow_mut().next();
+                        attempts += 1;
+                        resampled = true;
+                    }
+                }
+            }
+
+            if !resampled {
+                break;
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Evaluates the member `index` of a group after evaluating its gate
+    ///
+    /// `values` holds the values of the members evaluated so far.  `held` is set for members that
+    /// returned their previous value.  Transform rejects cyclic gates so the recursion terminates.
+    fn next_gated(
+        &self,
+        names: &[&str],
+        variables: &[&VariableRef],
+        values: &mut [Option<u32>],
+        held: &mut [bool],
+        index: usize,
+    ) -> u32 {
+        if let Some(value) = values[index] {
+            return value;
+        }
+
+        let gate = self
+            .gate_of(names[index])
+            .and_then(|gate| names.iter().position(|name| *name == gate));
+        let advance = match gate {
+            Some(gate) => {
+                self.next_gated(names, variables, values, held, gate) != 0
+                    || variables[index].borrow().current().is_none()
+            }
+            None => true,
+        };
+
+        let value = if advance {
+            variables[index].borrow_mut().next()
+        } else {
+            variables[index].borrow().prev()
+        };
+        values[index] = Some(value);
+        held[index] = !advance;
+
+        value
+    }
+
+    /// Resets all variables
+    ///
+    /// See `Variable::reset`.  Resetting a variable does not evaluate any other variable so the
+    /// result does not depend on the order variables reference each other.
+    pub fn reset_all(&mut self) {
+        for variable in &self.variables {
+            variable.borrow_mut().reset();
+        }
+    }
+
+    /// Saves the evaluation state of all variables under a name
+    ///
+    /// The state includes the expression and RNG of each variable so `restore` replays the same
+    /// values.  An existing checkpoint of the same name is replaced.  Variables deferred by a lazy
+    /// transform are built first.  Returns an error if any of them fails to build.
+    pub fn checkpoint(&mut self, name: &str) -> Result<()> {
+        self.build_all()?;
+
+        let states = self
+            .variables
+            .iter()
+            .map(|variable| variable.borrow().checkpoint())
+            .collect();
+        self.checkpoints.insert(name.into(), states);
+
+        Ok(())
+    }
+
+    /// Restores the evaluation state of all variables saved by `checkpoint`
+    ///
+    /// The checkpoint is kept so it can be restored again.  Variables added after the checkpoint
+    /// are not affected.  Statistics and recordings are not affected.  Returns false if there is
+    /// no checkpoint named `name`.
+    pub fn restore(&mut self, name: &str) -> bool {
+        let states = match self.checkpoints.get(name) {
+            Some(states) => states,
+            None => return false,
+        };
+
+        for (variable, state) in self.variables.iter().zip(states) {
+            variable.borrow_mut().restore_checkpoint(state);
+        }
+
+        true
+    }
+
+    /// Returns a deep copy of the model with independent variable state and RNG streams
+    ///
+    /// Variable indexes are preserved.  Variable references within the copy refer to the
+    /// variables of the copy.  See `Variable::fork` for how RNG streams are derived.  Checkpoints
+    /// are not copied.
+    pub fn fork(&self) -> Model {
+        let model = Model {
+            variables: self
+                .variables
+                .iter()
+                .map(|variable| Rc::new(RefCell::new(Box::new(variable.borrow().fork()))))
+                .collect(),
+            variable_indexes: self.variable_indexes.clone(),
+            most_recent: self.most_recent,
+            recorder: None,
+            enums: self.enums.clone(),
+            distincts: self.distincts.clone(),
+            pairs: self.pairs.clone(),
+            gates: self.gates.clone(),
+            transformed: self.transformed,
+            warnings: self.warnings.clone(),
+            tag: self.tag,
+            eval_budget: self.eval_budget,
+            deferred: self.deferred.clone(),
+            checkpoints: HashMap::new(),
+        };
+
+        for variable in &model.variables {
+            variable.borrow_mut().relink(&model);
+        }
+
+        model
+    }
+
+    /// Returns the variables of the model and their evaluation state in a compact binary format
+    ///
+    /// `deserialize` restores the model without parsing or transforming the source again.  E.g.
+    /// to cache transformed models.  Variables continue from their current state and RNG streams.
+    /// Enums, constraints, and warnings are included.  Value transforms, recordings, statistics,
+    /// and checkpoints are not.  The format is versioned.  See `MODEL_FORMAT_VERSION`.  Variables
+    /// deferred by a lazy transform are built first.  Returns an error if any of them fails to
+    /// build.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        self.build_all()?;
+
+        Ok(binary::encode(self))
+    }
+
+    /// Adds the variables returned by `serialize` to the model
+    ///
+    /// Like a transform, existing variables of the same name are replaced.  Returns an error if
+    /// the bytes were not returned by `serialize`, are corrupt, or are of a different format
+    /// version.  The model is not modified on error.
+    pub fn deserialize(&mut self, bytes: &[u8]) -> Result<()> {
+        binary::decode(self, bytes)
+    }
+
+    pub(crate) fn encode(&self, writer: &mut Writer) {
+        writer.len(self.variables.len());
+        for (name, variable) in self.variables_iter() {
+            writer.str(name);
+            variable.borrow().encode(writer);
+        }
+
+        writer.len(self.enums.len());
+        for (name, members) in &self.enums {
+            writer.str(name);
+            writer.len(members.len());
+            for (member, &value) in members {
+                writer.str(member);
+                writer.u32(value);
+            }
+        }
+
+        writer.len(self.distincts.len());
+        for distinct in &self.distincts {
+            writer.len(distinct.names.len());
+            for name in &distinct.names {
+                writer.str(name);
+            }
+            writer.u32(distinct.max_attempts);
+        }
+
+        writer.len(self.pairs.len());
+        for pair in &self.pairs {
+            writer.str(&pair.a);
+            writer.str(&pair.b);
+            writer.u32(pair.rho);
+            writer.rng(&pair.rng.borrow());
+        }
+
+        writer.len(self.gates.len());
+        for gate in &self.gates {
+            writer.str(&gate.a);
+            writer.str(&gate.b);
+        }
+
+        writer.len(self.warnings.len());
+        for warning in &self.warnings {
+            writer.str(&warning.description);
+            writer.bool(warning.span.is_some());
+            if let Some(ref span) = warning.span {
+                writer.len(span.line);
+                writer.len(span.column);
+            }
+        }
+    }
+
+    /// Reads a model written by `encode`
+    ///
+    /// Variable references are not linked.  See `extend`.
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Model> {
+        let mut model = Model::new();
+
+        for _ in 0..reader.len()? {
+            let name = reader.string()?;
+            let variable = Variable::decode(reader)?;
+            model.add_variable(&name, Rc::new(RefCell::new(Box::new(variable))));
+        }
+
+        for _ in 0..reader.len()? {
+            let name = reader.string()?;
+            let mut members = IndexMap::new();
+            for _ in 0..reader.len()? {
+                members.insert(reader.string()?, reader.u32()?);
+            }
+            model.add_enum(&name, members);
+        }
+
+        for _ in 0..reader.len()? {
+            let names = (0..reader.len()?)
+                .map(|_| reader.string())
+                .collect::<TransformResult<_>>()?;
+            model.add_distinct(names, reader.u32()?);
+        }
+
+        for _ in 0..reader.len()? {
+            let (a, b) = (reader.string()?, reader.string()?);
+            let rho = reader.u32()?;
+            reader.check(rho <= MAX_PAIR_RHO, "pair correlation out of range")?;
+            model.add_pair(a, b, rho, reader.rng()?);
+        }
+
+        for _ in 0..reader.len()? {
+            let (a, b) = (reader.string()?, reader.string()?);
+            model.add_gate(a, b);
+        }
+
+        for _ in 0..reader.len()? {
+            let mut warning = TransformWarning::new(reader.string()?);
+            if reader.bool()? {
+                warning = warning.at(Span {
+                    line: reader.len()?,
+                    column: reader.len()?,
+                });
+            }
+            model.warnings.push(warning);
+        }
+
+        Ok(model)
+    }
+
+    /// Adds the variables, enums, constraints, and warnings of `other` to the model
+    ///
+    /// Existing variables of the same name are replaced.  Variable references are relinked.
+    pub(crate) fn extend(&mut self, other: Model) {
+        for (name, index) in other.variable_indexes {
+            self.add_variable(&name, Rc::clone(&other.variables[index]));
+        }
+        for (name, members) in other.enums {
+            self.add_enum(&name, members);
+        }
+        self.distincts.extend(other.distincts);
+        self.pairs.extend(other.pairs);
+        self.gates.extend(other.gates);
+        self.warnings.extend(other.warnings);
+        self.set_transformed();
+
+        for variable in &self.variables {
+            variable.borrow_mut().relink(self);
+        }
+    }
+
+    /// Starts recording the values returned by all variables currently in the model
+    ///
+    /// A recording already in progress is discarded.  See `ReplayLog` for what is recorded.
+    pub fn start_recording(&mut self) {
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+
+        for (name, variable) in self.variables_iter() {
+            let variable_recorder = VariableRecorder::new(Rc::clone(&recorder), name);
+            variable.borrow_mut().set_recorder(Some(variable_recorder));
+        }
+
+        self.recorder = Some(recorder);
+    }
+
+    /// Stops recording and returns the values recorded since `start_recording`
+    ///
+    /// Returns an empty log if not recording.
+    pub fn stop_recording(&mut self) -> ReplayLog {
+        for variable in &self.variables {
+            variable.borrow_mut().set_recorder(None);
+        }
+
+        match self.recorder.take() {
+            Some(recorder) => match Rc::try_unwrap(recorder) {
+                Ok(recorder) => recorder.into_inner().into_log(),
+                Err(recorder) => recorder.borrow().log().clone(),
+            },
+            None => ReplayLog::new(),
+        }
+    }
+
+    /// Replaces the expression of every variable with the values recorded for it in `log`
+    ///
+    /// Each variable returns its recorded values in order without using its RNG.  Once its recorded
+    /// values are exhausted, a variable holds its last recorded value and is done on every call.
+    pub fn replay(&mut self, log: &ReplayLog) {
+        for (name, variable) in self.variables_iter() {
+            variable
+                .borrow_mut()
+                .set_expr(Box::new(Replay::new(name, log.values(name))));
+        }
+    }
+
+    /// Returns true if both models define the same variables with the same expressions
+    ///
+    /// Expressions are compared via their `Display` representation.  Evaluation state (e.g. RNG
+    /// state, previous values) is ignored.
+    pub fn structurally_eq(&self, other: &Model) -> bool {
+        self.diff(other).is_empty()
+    }
+
+    /// Lists the structural differences between two models
+    ///
+    /// Returns one entry for each variable that is missing from either model or whose expression
+    /// differs.  Variables only in `other` are listed last.
+    pub fn diff(&self, other: &Model) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        for (name, variable) in self.variables_iter() {
+            let expr = variable.borrow().to_string();
+
+            match other.get_variable_by_name(name) {
+                Some(other_variable) => {
+                    let other_expr = other_variable.borrow().to_string();
+                    if expr != other_expr {
+                        differences.push(format!("{}: {} != {}", name, expr, other_expr));
+                    }
+                }
+                None => differences.push(format!("{}: only in left", name)),
+            }
+        }
+
+        for (name, _) in other.variables_iter() {
+            if self.get_variable_index(name).is_none() {
+                differences.push(format!("{}: only in right", name));
+            }
+        }
+
+        differences
+    }
 }
 
 impl fmt::Display for Model {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (name, variable) in self.variables_iter() {
+            if let Some(doc) = variable.borrow().doc() {
+                for line in doc.lines() {
+                    writeln!(f, "/// {}", line)?;
+                }
+            }
+            for (key, value) in variable.borrow().attributes() {
+                if key == "doc" {
+                    continue;
+                }
+
+                if value.is_empty() {
+                    write!(f, "@{} ", key)?;
+                } else {
+                    write!(f, "@{}(\"{}\") ", key, value)?;
+                }
+            }
+            if variable.borrow().is_constant() {
+                write!(f, "const ")?;
+            }
             write!(f, "{} = ", name)?;
             variable.borrow().fmt(f)?;
             writeln!(f, ";")?;
         }
 
+        for distinct in &self.distincts {
+            writeln!(f, "{};", distinct)?;
+        }
+
+        for pair in &self.pairs {
+            writeln!(f, "pair({}, {}, {});", pair.a, pair.b, pair.rho)?;
+        }
+
+        for gate in &self.gates {
+            writeln!(f, "gate {} by {};", gate.a, gate.b)?;
+        }
+
         Ok(())
     }
 }
 
+/// Moves `value` toward `target` by `rho / MAX_PAIR_RHO` of the distance between them
+fn blend(value: u32, target: u32, rho: u32) -> u32 {
+    let value = u64::from(value);
+    let target = u64::from(target);
+    let rho = u64::from(rho);
+    let max = u64::from(MAX_PAIR_RHO);
+
+    ((value * (max - rho) + target * rho) / max) as u32
+}
+
+/// Returns false if the variables at `positions` can never return distinct values
+///
+/// Conservative.  A variable with at least as many possible values as there are variables can
+/// always be given a distinct value so only variables with fewer values are considered.  These
+/// must have a distinct value each which is checked by bipartite matching.  Variables with
+/// unknown values are assumed to be satisfiable.
+fn can_be_distinct(variables: &[&VariableRef], positions: &[usize]) -> bool {
+    let count = positions.len();
+
+    let mut candidates: Vec<Vec<u32>> = Vec::new();
+    for &position in positions {
+        let variable = variables[position].borrow();
+        let values = match variable.possible_values() {
+            Some(values) => values,
+            None => match variable.value_bounds() {
+                Some((min, max)) if ((max - min) as usize) < count => (min..=max).collect(),
+                _ => continue,
+            },
+        };
+
+        if values.len() < count {
+            candidates.push(values);
+        }
+    }
+
+    // Maps each value to the index of the candidate it is matched with
+    let mut matches: HashMap<u32, usize> = HashMap::new();
+    (0..candidates.len()).all(|candidate| {
+        let mut visited = Vec::new();
+        augment(&candidates, candidate, &mut matches, &mut visited)
+    })
+}
+
+/// Finds an augmenting path from `candidate` to a free value.  Returns true if one was found.
+fn augment(
+    candidates: &[Vec<u32>],
+    candidate: usize,
+    matches: &mut HashMap<u32, usize>,
+    visited: &mut Vec<u32>,
+) -> bool {
+    for &value in &candidates[candidate] {
+        if visited.contains(&value) {
+            continue;
+        }
+        visited.push(value);
+
+        let free = match matches.get(&value) {
+            Some(&other) => augment(candidates, other, matches, visited),
+            None => true,
+        };
+        if free {
+            matches.insert(value, candidate);
+
+            return true;
+        }
+    }
+
+    false
+}
+
+impl fmt::Display for Distinct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "distinct({})", self.names.join(", "))
+    }
+}
+
 pub struct VariablesIter<'a> {
     iter: ::indexmap::map::Iter<'a, String, usize>,
     variables: &'a Vec<VariableRef>,
@@ -96,3 +1121,21 @@ impl<'a> Iterator for VariablesIter<'a> {
         Some((next.0, &self.variables[*next.1]))
     }
 }
+
+/// Returns a value in decimal, as `i32` if `signed`
+pub(crate) fn format_number(value: u32, signed: bool) -> String {
+    if signed {
+        (value as i32).to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns a value in hexadecimal, as `i32` if `signed` E.g. `-0x1` for `0xffffffff`
+pub(crate) fn format_hex(value: u32, signed: bool) -> String {
+    if signed && (value as i32) < 0 {
+        format!("-0x{:x}", value.wrapping_neg())
+    } else {
+        format!("0x{:x}", value)
+    }
+}
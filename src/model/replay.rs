@@ -0,0 +1,121 @@
+use std::io;
+use std::io::prelude::*;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+/// The values returned by variables in the order they were returned
+///
+/// Created by `Model::stop_recording` and consumed by `Model::replay`.  Only values returned
+/// directly by a variable are logged.  Values a variable returns while being evaluated as a
+/// reference from another variable are not.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayLog {
+    entries: Vec<(String, u32)>,
+}
+
+impl ReplayLog {
+    pub fn new() -> ReplayLog {
+        Default::default()
+    }
+
+    pub fn push(&mut self, name: &str, value: u32) {
+        self.entries.push((name.into(), value));
+    }
+
+    /// Returns all `(variable name, value)` entries in the order they were logged
+    pub fn entries(&self) -> &[(String, u32)] {
+        &self.entries
+    }
+
+    /// Returns the values logged for a variable in the order they were logged
+    pub fn values(&self, name: &str) -> Vec<u32> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.0 == name)
+            .map(|entry| entry.1)
+            .collect()
+    }
+
+    /// Writes the log as one `<name> <value>` entry per line
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (name, value) in &self.entries {
+            writeln!(writer, "{} {}", name, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a log previously written by `write`
+    pub fn read<R: BufRead>(reader: R) -> io::Result<ReplayLog> {
+        let mut log = ReplayLog::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+
+            match (fields.next(), fields.next().map(str::parse::<u32>), fields.next()) {
+                (Some(name), Some(Ok(value)), None) => log.push(name, value),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid replay log entry on line {}: '{}'", index + 1, line),
+                    ))
+                }
+            }
+        }
+
+        Ok(log)
+    }
+}
+
+/// Shared state for recording a model
+#[derive(Default)]
+pub struct Recorder {
+    log: ReplayLog,
+
+    /// Number of variables currently being evaluated
+    ///
+    /// Only values returned at depth 0 are logged.
+    depth: usize,
+}
+
+impl Recorder {
+    pub fn log(&self) -> &ReplayLog {
+        &self.log
+    }
+
+    pub fn into_log(self) -> ReplayLog {
+        self.log
+    }
+}
+
+/// Records the values of a single variable
+#[derive(Clone)]
+pub struct VariableRecorder {
+    recorder: Rc<RefCell<Recorder>>,
+    name: String,
+}
+
+impl VariableRecorder {
+    pub fn new(recorder: Rc<RefCell<Recorder>>, name: &str) -> VariableRecorder {
+        VariableRecorder {
+            recorder,
+            name: name.into(),
+        }
+    }
+
+    /// Called before evaluating the variable
+    pub fn enter(&self) {
+        self.recorder.borrow_mut().depth += 1;
+    }
+
+    /// Called after evaluating the variable
+    pub fn leave(&self, value: u32) {
+        let mut recorder = self.recorder.borrow_mut();
+
+        recorder.depth -= 1;
+        if recorder.depth == 0 {
+            recorder.log.push(&self.name, value);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+/// A snapshot of the evaluation state of a variable for debugging
+///
+/// Returned by `Variable::state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VariableState {
+    /// See `Variable::prev`
+    pub prev: u32,
+    /// See `Variable::done`
+    pub done: bool,
+    /// See `Variable::count`
+    pub count: u64,
+    /// See `Variable::cycle_count`
+    pub cycle_count: u64,
+    /// See `Variable::cycle_position`
+    pub cycle_position: Option<usize>,
+}
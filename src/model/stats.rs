@@ -0,0 +1,92 @@
+/// Running statistics of the values returned by a variable
+///
+/// Signed statistics (see `Variable::set_signed`) order values as `i32`.  `min`, `max`, and `mean`
+/// are two's complement and `sum` is the `i64` sum stored as two's complement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: u32,
+    pub max: u32,
+    pub sum: u64,
+    pub count: u64,
+    pub signed: bool,
+}
+
+impl Stats {
+    pub fn new(value: u32) -> Stats {
+        Stats::with_signedness(value, false)
+    }
+
+    pub fn with_signedness(value: u32, signed: bool) -> Stats {
+        let mut stats = Stats {
+            min: value,
+            max: value,
+            sum: 0,
+            count: 0,
+            signed,
+        };
+        stats.add(value);
+
+        stats
+    }
+
+    pub fn add(&mut self, value: u32) {
+        if self.signed {
+            self.min = (self.min as i32).min(value as i32) as u32;
+            self.max = (self.max as i32).max(value as i32) as u32;
+            self.sum = (self.sum as i64 + i64::from(value as i32)) as u64;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+            self.sum += u64::from(value);
+        }
+        self.count += 1;
+    }
+
+    /// Returns the mean rounded to the nearest integer
+    ///
+    /// Halves are rounded up.
+    pub fn mean(&self) -> u32 {
+        if self.signed {
+            let count = self.count as i64;
+            (self.sum as i64 + count / 2).div_euclid(count) as i32 as u32
+        } else {
+            ((self.sum + self.count / 2) / self.count) as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_rounds_to_nearest() {
+        let mut stats = Stats::new(1);
+        stats.add(2);
+        assert_eq!(stats.mean(), 2);
+
+        stats.add(2);
+        assert_eq!(stats.mean(), 2);
+
+        let mut stats = Stats::new(0);
+        stats.add(0);
+        stats.add(1);
+        assert_eq!(stats.mean(), 0);
+
+        let mut stats = Stats::with_signedness(-3i32 as u32, true);
+        stats.add(-2i32 as u32);
+        assert_eq!(stats.mean() as i32, -2);
+    }
+
+    #[test]
+    fn signed() {
+        let mut stats = Stats::with_signedness(5, true);
+        stats.add(-1i32 as u32);
+        stats.add(3);
+
+        assert_eq!(stats.min as i32, -1);
+        assert_eq!(stats.max, 5);
+        assert_eq!(stats.sum as i64, 7);
+        assert_eq!(stats.mean(), 2);
+    }
+}
@@ -0,0 +1,38 @@
+use std::cell::RefCell;
+
+thread_local! {
+    /// The value substituted for references to a variable for the evaluation in progress, if any
+    static OVERRIDE: RefCell<Option<(String, u32)>> = const { RefCell::new(None) };
+}
+
+/// Restores the outer override when dropped so an unwinding evaluation does not leak the override
+struct Restore(Option<(String, u32)>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        OVERRIDE.with(|current| *current.borrow_mut() = outer);
+    }
+}
+
+/// Returns the value substituted for references to the variable `name`, if any
+///
+/// Returns `None` outside of `with_override`.  Called by variable references before evaluating
+/// the variable.
+pub(crate) fn lookup(name: &str) -> Option<u32> {
+    OVERRIDE.with(|current| match *current.borrow() {
+        Some((ref override_name, value)) if override_name == name => Some(value),
+        _ => None,
+    })
+}
+
+/// Runs `f` with references to the variable `name` returning `value`
+///
+/// Overrides do not nest.  The override of an inner call replaces the override of the outer call
+/// for the duration of the inner call.
+pub(crate) fn with_override<T, F: FnOnce() -> T>(name: &str, value: u32, f: F) -> T {
+    let outer = OVERRIDE.with(|current| current.borrow_mut().replace((name.into(), value)));
+    let _restore = Restore(outer);
+
+    f()
+}
@@ -0,0 +1,112 @@
+use super::{format_number, Stats, Variable};
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// Statistics of the values drawn from a variable by `Model::summarize`
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableSummary {
+    pub name: String,
+    /// `None` if no values were drawn
+    pub stats: Option<Stats>,
+    /// The number of distinct values drawn
+    pub distinct: usize,
+    /// The index of the first value for which the variable indicated done, if any
+    pub first_done: Option<usize>,
+}
+
+impl VariableSummary {
+    /// Draws `n` values from `variable`
+    pub(crate) fn new(name: &str, variable: &mut Variable, n: usize) -> VariableSummary {
+        let mut stats: Option<Stats> = None;
+        let mut values = HashSet::new();
+        let mut first_done = None;
+
+        for i in 0..n {
+            let value = variable.next();
+
+            match stats {
+                Some(ref mut stats) => stats.add(value),
+                None => stats = Some(Stats::with_signedness(value, variable.is_signed())),
+            }
+            values.insert(value);
+            if first_done.is_none() && variable.done() {
+                first_done = Some(i);
+            }
+        }
+
+        VariableSummary {
+            name: name.into(),
+            stats,
+            distinct: values.len(),
+            first_done,
+        }
+    }
+
+    /// Returns the summary as a JSON object member E.g.
+    ///
+    /// ```text
+    /// "a": {"min": 0, "max": 9, "mean": 5, "distinct": 10, "first_done": null}
+    /// ```
+    pub(crate) fn to_json(&self) -> String {
+        let signed = self.stats.is_some_and(|stats| stats.signed);
+        let number = |value: Option<u32>| {
+            value.map_or("null".to_owned(), |value| format_number(value, signed))
+        };
+
+        // Variable names are identifiers so they need no escaping
+        format!(
+            "\"{}\": {{\"min\": {}, \"max\": {}, \"mean\": {}, \"distinct\": {}, \"first_done\": {}}}",
+            self.name,
+            number(self.stats.map(|stats| stats.min)),
+            number(self.stats.map(|stats| stats.max)),
+            number(self.stats.map(|stats| stats.mean())),
+            self.distinct,
+            self.first_done.map_or("null".to_owned(), |i| i.to_string())
+        )
+    }
+
+    /// Returns the columns of the summary as text in the order of `HEADINGS`
+    fn columns(&self) -> [String; 6] {
+        let hex = |value: Option<u32>| value.map_or("-".to_owned(), |value| format!("0x{:x}", value));
+
+        [
+            self.name.clone(),
+            hex(self.stats.map(|stats| stats.min)),
+            hex(self.stats.map(|stats| stats.max)),
+            hex(self.stats.map(|stats| stats.mean())),
+            self.distinct.to_string(),
+            self.first_done.map_or("-".to_owned(), |i| i.to_string()),
+        ]
+    }
+}
+
+const HEADINGS: [&str; 6] = ["name", "min", "max", "mean", "distinct", "first_done"];
+
+/// A text table of summaries with one row per variable
+pub(crate) struct SummaryTable<'a>(pub &'a [VariableSummary]);
+
+impl<'a> fmt::Display for SummaryTable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<[String; 6]> = self.0.iter().map(VariableSummary::columns).collect();
+
+        let mut widths = HEADINGS.map(str::len);
+        for row in &rows {
+            for (width, column) in widths.iter_mut().zip(row) {
+                *width = (*width).max(column.len());
+            }
+        }
+
+        let headings = HEADINGS.map(str::to_owned);
+        for row in std::iter::once(&headings).chain(&rows) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(column, &width)| format!("{:width$}", column, width = width))
+                .collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
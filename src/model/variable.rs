@@ -1,39 +1,838 @@
 use super::expr::Expr;
-use crate::transform::CrateRng;
+use super::Model;
+use super::budget;
+use super::substitution;
+use super::Stats;
+use super::VariableState;
+use super::VariableRecorder;
+use super::{Reader, Writer};
+use crate::transform::{CrateRng, Seed};
+use crate::types::{flag_exhausted, Range};
+use crate::error::{BudgetError, Result, TransformResult};
+
+use indexmap::IndexMap;
 
 use std::fmt;
+use std::mem;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 
 pub struct Variable {
+    expr: Box<dyn Expr>,
+    /// The expression as transformed.  Used by `reset`.
+    initial_expr: Box<dyn Expr>,
+    rng: CrateRng,
+    /// The RNG as passed to `new`.  Used by `reset_rng`.
+    initial_rng: CrateRng,
+    stats_enabled: bool,
+    stats: Option<Stats>,
+    attributes: IndexMap<String, String>,
+    value_transform: Option<ValueTransform>,
+    /// The value returned by the most recent call to `next`.  `None` before the first call.
+    current: Option<u32>,
+    /// The done of the expression after the most recent call to `next`
+    prev_done: bool,
+    done_edge: bool,
+    /// The number of values returned since created or reset.  See `count`.
+    count: u64,
+    /// The number of cycles completed.  See `cycle_count`.
+    cycle_count: u64,
+    constant: bool,
+    recorder: Option<VariableRecorder>,
+    output_mask: u32,
+    enabled: bool,
+    /// The value returned by `next` while disabled.  `None` returns `prev`.
+    disabled_value: Option<u32>,
+    frozen: bool,
+    /// Values are two's complement.  See `set_signed`.
+    signed: bool,
+    /// `done` remains true once set.  See `set_sticky_done`.
+    sticky_done: bool,
+    /// The expression has been done since created or reset
+    done_once: bool,
+    /// The most recent call to `next` evaluated an exhausted expression.  See `exhausted`.
+    exhausted: bool,
+}
+
+/// The evaluation state of a variable saved by `Model::checkpoint`
+#[derive(Clone)]
+pub(crate) struct VariableCheckpoint {
     expr: Box<dyn Expr>,
     rng: CrateRng,
+    current: Option<u32>,
+    prev_done: bool,
+    done_edge: bool,
+    done_once: bool,
+    count: u64,
+    cycle_count: u64,
 }
 
+/// A function applied to each value returned by `Variable::next`
+pub type ValueTransform = Rc<dyn Fn(u32) -> u32>;
+
 pub type VariableRef = Rc<RefCell<Box<Variable>>>;
 pub type VariableWeak = Weak<RefCell<Box<Variable>>>;
 
 impl Variable {
     pub fn new(expr: Box<dyn Expr>, rng: CrateRng) -> Variable {
-        Variable { expr, rng }
+        Variable {
+            initial_expr: expr.clone(),
+            expr,
+            initial_rng: rng.clone(),
+            rng,
+            stats_enabled: false,
+            stats: None,
+            attributes: IndexMap::new(),
+            value_transform: None,
+            current: None,
+            prev_done: false,
+            done_edge: false,
+            count: 0,
+            cycle_count: 0,
+            constant: false,
+            recorder: None,
+            output_mask: u32::MAX,
+            enabled: true,
+            disabled_value: None,
+            frozen: false,
+            signed: false,
+            sticky_done: false,
+            done_once: false,
+            exhausted: false,
+        }
     }
 
     pub fn clone_expr(&self) -> Box<dyn Expr> {
         self.expr.clone()
     }
 
-    #[cfg_attr(feature = "cargo-clippy", allow(should_implement_trait))]
+    /// Returns a copy of the variable with an independent RNG stream
+    ///
+    /// The evaluation state of the expression is copied as is.  The RNG of the copy is derived
+    /// from the current RNG state so the copy diverges from the original.  The original is not
+    /// modified.  The copy is not recorded.  RNGs owned by the expression (see
+    /// `Transform::set_independent_selection`) are derived the same way.
+    pub fn fork(&self) -> Variable {
+        let mut rng = self.rng.clone();
+
+        let mut fork_rng = rng.derive();
+        fork_rng.set_draw_counting(self.rng.draws().is_some());
+
+        let mut expr = self.expr.clone();
+        expr.fork_rngs(&mut rng);
+        let mut initial_expr = self.initial_expr.clone();
+        initial_expr.fork_rngs(&mut rng);
+
+        Variable {
+            expr,
+            initial_expr,
+            initial_rng: fork_rng.clone(),
+            rng: fork_rng,
+            stats_enabled: self.stats_enabled,
+            stats: self.stats,
+            attributes: self.attributes.clone(),
+            value_transform: self.value_transform.clone(),
+            current: self.current,
+            prev_done: self.prev_done,
+            done_edge: self.done_edge,
+            count: self.count,
+            cycle_count: self.cycle_count,
+            constant: self.constant,
+            recorder: None,
+            output_mask: self.output_mask,
+            enabled: self.enabled,
+            disabled_value: self.disabled_value,
+            frozen: self.frozen,
+            signed: self.signed,
+            sticky_done: self.sticky_done,
+            done_once: self.done_once,
+            exhausted: self.exhausted,
+        }
+    }
+
+    /// Returns a copy of the evaluation state of the variable
+    pub(crate) fn checkpoint(&self) -> VariableCheckpoint {
+        VariableCheckpoint {
+            expr: self.expr.clone(),
+            rng: self.rng.clone(),
+            current: self.current,
+            prev_done: self.prev_done,
+            done_edge: self.done_edge,
+            done_once: self.done_once,
+            count: self.count,
+            cycle_count: self.cycle_count,
+        }
+    }
+
+    /// Replaces the evaluation state of the variable with one returned by `checkpoint`
+    pub(crate) fn restore_checkpoint(&mut self, checkpoint: &VariableCheckpoint) {
+        self.expr = checkpoint.expr.clone();
+        self.rng = checkpoint.rng.clone();
+        self.current = checkpoint.current;
+        self.prev_done = checkpoint.prev_done;
+        self.done_edge = checkpoint.done_edge;
+        self.done_once = checkpoint.done_once;
+        self.count = checkpoint.count;
+        self.cycle_count = checkpoint.cycle_count;
+    }
+
+    /// Returns a snapshot of the evaluation state of the variable for debugging
+    ///
+    /// Equivalent to calling `prev`, `done`, `count`, `cycle_count`, and `cycle_position`.
+    pub fn state(&self) -> VariableState {
+        VariableState {
+            prev: self.prev(),
+            done: self.done(),
+            count: self.count,
+            cycle_count: self.cycle_count,
+            cycle_position: self.cycle_position(),
+        }
+    }
+
+    /// Writes the expression, RNG, configuration, and evaluation state of the variable
+    ///
+    /// The value transform, recorder, and statistics are not written.  See `Model::serialize`.
+    pub(crate) fn encode(&self, writer: &mut Writer) {
+        writer.expr(&*self.expr);
+        writer.expr(&*self.initial_expr);
+        writer.rng(&self.rng);
+        writer.rng(&self.initial_rng);
+
+        writer.len(self.attributes.len());
+        for (key, value) in &self.attributes {
+            writer.str(key);
+            writer.str(value);
+        }
+
+        writer.bool(self.stats_enabled);
+        writer.bool(self.constant);
+        writer.u32(self.output_mask);
+        writer.bool(self.enabled);
+        writer.option_u32(self.disabled_value);
+        writer.bool(self.frozen);
+        writer.bool(self.signed);
+        writer.bool(self.sticky_done);
+
+        writer.option_u32(self.current);
+        writer.bool(self.prev_done);
+        writer.bool(self.done_edge);
+        writer.bool(self.done_once);
+        writer.bool(self.exhausted);
+        writer.u64(self.count);
+        writer.u64(self.cycle_count);
+    }
+
+    /// Reads a variable written by `encode`
+    ///
+    /// Variable references are not linked.  See `relink`.
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Variable> {
+        let expr = reader.expr()?;
+        let initial_expr = reader.expr()?;
+        let rng = reader.rng()?;
+        let initial_rng = reader.rng()?;
+
+        let mut attributes = IndexMap::new();
+        for _ in 0..reader.len()? {
+            attributes.insert(reader.string()?, reader.string()?);
+        }
+
+        Ok(Variable {
+            expr,
+            initial_expr,
+            rng,
+            initial_rng,
+            attributes,
+            stats_enabled: reader.bool()?,
+            stats: None,
+            value_transform: None,
+            recorder: None,
+            constant: reader.bool()?,
+            output_mask: reader.u32()?,
+            enabled: reader.bool()?,
+            disabled_value: reader.option_u32()?,
+            frozen: reader.bool()?,
+            signed: reader.bool()?,
+            sticky_done: reader.bool()?,
+            current: reader.option_u32()?,
+            prev_done: reader.bool()?,
+            done_edge: reader.bool()?,
+            done_once: reader.bool()?,
+            exhausted: reader.bool()?,
+            count: reader.u64()?,
+            cycle_count: reader.u64()?,
+        })
+    }
+
+    /// Points all variable references at the variables of the same name in `model`
+    pub fn relink(&mut self, model: &Model) {
+        self.expr.relink(model);
+        self.initial_expr.relink(model);
+    }
+
+    /// Sets or removes the recorder used by `Model::start_recording`
+    pub fn set_recorder(&mut self, recorder: Option<VariableRecorder>) {
+        self.recorder = recorder;
+    }
+
+    /// Replaces the expression of the variable
+    ///
+    /// `reset` restores `expr`.
+    pub fn set_expr(&mut self, expr: Box<dyn Expr>) {
+        self.initial_expr = expr.clone();
+        self.expr = expr;
+    }
+
+    /// Restores the expression of the variable to its state before the first call to `next`
+    ///
+    /// Subsequent calls to `next` replay from the start of the expression.  `prev` returns 0 and
+    /// `current` returns `None` until the next call to `next`.  The RNG of the variable is not
+    /// reset so random sub-expressions do not repeat their values (see `reset_rng`).  RNGs owned by
+    /// the expression (see `Transform::set_independent_selection`) are part of its state and are
+    /// restored so selections repeat.  Statistics are not cleared.  The count and cycle count are
+    /// cleared.
+    pub fn reset(&mut self) {
+        self.expr = self.initial_expr.clone();
+        self.current = None;
+        self.prev_done = false;
+        self.done_edge = false;
+        self.done_once = false;
+        self.exhausted = false;
+        self.count = 0;
+        self.cycle_count = 0;
+    }
+
+    /// Restores the RNG of the variable to its state before the first call to `next`
+    ///
+    /// Subsequent calls to `next` draw the same random numbers as a newly transformed variable.
+    /// The evaluation state of the expression (e.g. the position in a `Pattern`), `prev`, and
+    /// `done` are not reset.  RNGs owned by the expression (see
+    /// `Transform::set_independent_selection`) are not reset.  The draw count is preserved.  Does
+    /// nothing for variables that do not use the RNG.
+    pub fn reset_rng(&mut self) {
+        let draws = self.rng.draws();
+
+        self.rng = self.initial_rng.clone();
+        self.rng.set_draw_counting(draws.is_some());
+        self.rng.add_draws(draws.unwrap_or(0));
+    }
+
+    /// Sets a mask applied to each value returned by `next`
+    ///
+    /// The mask is applied last (i.e. after the value transform).  Defaults to `u32::MAX` (no
+    /// masking).
+    pub fn set_output_mask(&mut self, output_mask: u32) {
+        self.output_mask = output_mask;
+    }
+
+    /// Enables or disables sticky done
+    ///
+    /// When enabled, `done` remains true from the end of the first cycle until `reset` instead of
+    /// returning to false at the start of the next cycle.  E.g. `Pattern(1, 2)` is done on every
+    /// call after the second.  Has no effect on expressions that are done on every call (e.g.
+    /// `Once` and constants).  `done_edge` and the cycle count are unaffected.  References to the
+    /// variable from other variables see the sticky done too.  Disabled by default.
+    pub fn set_sticky_done(&mut self, enabled: bool) {
+        self.sticky_done = enabled;
+    }
+
+    /// Returns true if sticky done is enabled.  See `set_sticky_done`.
+    pub fn is_sticky_done(&self) -> bool {
+        self.sticky_done
+    }
+
+    /// Enables or disables the variable
+    ///
+    /// While disabled, `next` and `prev` return the disabled value (see `set_disabled_value`)
+    /// without evaluating the expression.  The expression, RNG, `current`, `done`, and statistics
+    /// are left as is.  References to the variable from other variables (including `.prev`) see
+    /// the disabled value too.  Variables are enabled by default.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the value returned by `next` and `prev` while the variable is disabled
+    ///
+    /// `None` (the default) holds the most recent value.
+    pub fn set_disabled_value(&mut self, disabled_value: Option<u32>) {
+        self.disabled_value = disabled_value;
+    }
+
+    /// Freezes the variable until `unfreeze`
+    ///
+    /// While frozen, `next` returns `prev` (i.e. the most recent value, or 0 if `next` has not been
+    /// called) without evaluating the expression.  References to the variable from other variables
+    /// see the frozen value too.  Unlike disabling, the disabled value is not used.  Takes
+    /// precedence over disabling.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Resumes evaluation of a frozen variable
+    ///
+    /// The next call to `next` returns the value that would have followed the frozen value.
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Marks the values of the variable as two's complement (i.e. `i32`)
+    ///
+    /// Values are still returned as `u32`.  Affects statistics (see `Stats`) and
+    /// `Model::sample_to_json`.  Set for variables with the `@signed` attribute.
+    pub fn set_signed(&mut self, signed: bool) {
+        self.signed = signed;
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Marks the variable as defined with `const`
+    pub fn set_constant(&mut self, constant: bool) {
+        self.constant = constant;
+    }
+
+    /// Returns true if the variable was defined with `const`
+    ///
+    /// References to constants are replaced with the value of the constant.
+    pub fn is_constant(&self) -> bool {
+        self.constant
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> u32 {
-        self.expr.next(&mut self.rng)
+        if self.frozen {
+            return self.prev();
+        }
+
+        if !self.enabled {
+            return self.disabled_value.unwrap_or_else(|| self.prev());
+        }
+
+        let value = self.evaluate();
+        let value = self.advance(value);
+
+        if self.stats_enabled {
+            match self.stats {
+                Some(ref mut stats) => stats.add(value),
+                None => self.stats = Some(Stats::with_signedness(value, self.signed)),
+            }
+        }
+
+        value
     }
 
+    /// Returns the next value of the expression and passes it to the recorder
+    fn evaluate(&mut self) -> u32 {
+        if let Some(ref recorder) = self.recorder {
+            recorder.enter();
+        }
+        let (value, exhausted) = flag_exhausted(|| self.expr.next(&mut self.rng));
+        self.exhausted = exhausted;
+        if let Some(ref recorder) = self.recorder {
+            recorder.leave(value);
+        }
+
+        value
+    }
+
+    /// Applies the value transform and output mask to a value of the expression and updates
+    /// `current`, `done_edge`, the count, and the cycle count
+    fn advance(&mut self, value: u32) -> u32 {
+        let value = self.output(value);
+        let first = self.current.is_none();
+        self.current = Some(value);
+        self.count += 1;
+
+        let done = self.expr.done();
+        self.done_edge = done && !self.prev_done;
+        self.prev_done = done;
+        self.done_once |= done;
+        if self.done_edge && !first {
+            self.cycle_count += 1;
+        }
+
+        value
+    }
+
+    /// Applies the value transform and output mask to a value of the expression
+    fn output(&self, value: u32) -> u32 {
+        let value = match self.value_transform {
+            Some(ref value_transform) => value_transform(value),
+            None => value,
+        };
+
+        value & self.output_mask
+    }
+
+    /// Returns a value drawn from a copy of the expression with `rng`
+    ///
+    /// Neither the variable nor its RNG is advanced.  Variables referenced by the expression are
+    /// advanced.  The value transform and output mask are applied.  Returns `prev` while the
+    /// variable is frozen or disabled.  Used for the shared value of a `pair`.
+    pub(crate) fn sample(&self, rng: &mut CrateRng) -> u32 {
+        if self.frozen || !self.enabled {
+            return self.prev();
+        }
+
+        self.output(self.expr.clone().next(rng))
+    }
+
+    /// Advances the variable by `n` values without returning them
+    ///
+    /// Equivalent to `n` calls to `next` except the skipped values are not added to the statistics.
+    /// The skipped values are recorded (see `Model::start_recording`) so a replay skips the same
+    /// values.  `prev`, `current`, `done`, `done_edge`, `count`, and `cycle_count` reflect the last
+    /// skipped value.  The RNG is advanced draw by draw rather than jumped because the number of
+    /// draws per value varies (e.g. re-samples) so the values that follow match those that follow
+    /// `n` calls to `next`.  Does nothing while the variable is disabled or frozen.
+    pub fn skip(&mut self, n: usize) {
+        if !self.enabled || self.frozen {
+            return;
+        }
+
+        for _ in 0..n {
+            let value = self.evaluate();
+            self.advance(value);
+        }
+    }
+
+    /// Returns the next value unless a re-sampling expression exceeds its resample limit
+    ///
+    /// `next` returns the fallback value of the expression (e.g. the previous value for `except`)
+    /// and logs a warning instead.  See `Transform::set_max_resample_attempts`.
+    ///
+    /// Returns an error if a resample limit is exceeded by this call, including by referenced
+    /// variables.  The evaluation completes with the fallback values.  The value is not returned
+    /// but the state of the variable (e.g. `prev`) and of any referenced variables reflects it.
+    /// The variables remain usable.
+    pub fn next_checked(&mut self) -> Result<u32> {
+        budget::check_limits(|| self.next()).map_err(From::from)
+    }
+
+    /// Returns the next value unless more than `max_iterations` re-samples are needed
+    ///
+    /// Re-samples are counted across all re-sampling expressions (e.g. `except`) evaluated by this
+    /// call, including those of referenced variables.  The budget is checked in addition to the
+    /// resample limit of each expression.
+    ///
+    /// Returns an error if the budget or a resample limit is exceeded.  Re-sampling stops once the
+    /// budget is exhausted and the evaluation completes with values that may not satisfy the
+    /// expressions (e.g. an excluded value).  The value is not returned but the state of the
+    /// variable (e.g. `prev`) and of any referenced variables reflects it.  The variables remain
+    /// usable.  See `next_checked`.
+    pub fn next_with_budget(&mut self, max_iterations: u32) -> Result<u32> {
+        budget::with_budget(max_iterations, || self.next_checked())
+            .unwrap_or_else(|| Err(BudgetError::new(max_iterations).into()))
+    }
+
+    /// Returns the next value using `entropy` as the randomness source for this call only
+    ///
+    /// The RNG of the variable is not advanced.  All other state (e.g. the evaluation state of
+    /// the expression) is updated as for `next`.  The same entropy and the same state yield the
+    /// same value.  See `Seed::from_bytes` for how `entropy` is used.
+    pub fn next_with_entropy(&mut self, entropy: &[u8]) -> u32 {
+        let mut entropy_rng = Seed::from_bytes(entropy).to_rng();
+        entropy_rng.set_draw_counting(self.rng.draws().is_some());
+
+        let rng = mem::replace(&mut self.rng, entropy_rng);
+        let value = self.next();
+        let entropy_rng = mem::replace(&mut self.rng, rng);
+        self.rng.add_draws(entropy_rng.draws().unwrap_or(0));
+
+        value
+    }
+
+    /// Returns the next value with references to the variable `name` returning `value`
+    ///
+    /// Applies to this call only and to references at any depth (e.g. references within
+    /// referenced variables).  Both `<name>` and `<name>.prev` return `value` and the overridden
+    /// variable is not advanced.  References copied with `.copy` and constants are resolved
+    /// during transform and are not overridden.  The variable itself is not overridden.  Has no
+    /// effect if the expression does not reference `name`.
+    pub fn next_with_override(&mut self, name: &str, value: u32) -> u32 {
+        substitution::with_override(name, value, || self.next())
+    }
+
+    /// Advances the variable until it returns `target`
+    ///
+    /// Returns the number of calls to `next`, including the one that returned `target`.  Returns
+    /// `None` if `target` was not returned within `cap` calls.
+    pub fn next_until(&mut self, target: u32, cap: usize) -> Option<usize> {
+        (1..=cap).find(|_| self.next() == target)
+    }
+
+    /// Returns the value returned by the most recent call to `next` or 0 if `next` has not been
+    /// called
+    ///
+    /// See `current` to distinguish the two.  Returns the disabled value while the variable is
+    /// disabled (see `set_enabled`) and not frozen.
     pub fn prev(&self) -> u32 {
-        self.expr.prev()
+        match self.disabled_value {
+            Some(disabled_value) if !self.enabled && !self.frozen => disabled_value,
+            _ => self.current.unwrap_or(0),
+        }
     }
 
+    /// Returns the value returned by the most recent call to `next`
+    ///
+    /// The value is as returned by `next` (i.e. after the value transform and output mask), not
+    /// the value of any sub-expression.  Returns `None` if `next` has not been called since the
+    /// variable was created or `reset`.
+    pub fn current(&self) -> Option<u32> {
+        self.current
+    }
+
+    /// Returns true if the expression indicated done on the most recent call to `next`
+    ///
+    /// With sticky done (see `set_sticky_done`), returns true if the expression has indicated
+    /// done on any call to `next` since the variable was created or reset.
     pub fn done(&self) -> bool {
-        self.expr.done()
+        self.expr.done() || (self.sticky_done && self.done_once)
     }
+
+    /// Returns true if the most recent call to `next` evaluated an exhausted `Once`
+    ///
+    /// Only set with `ExhaustedMode::Flag` (see `Transform::set_exhausted_mode`).  Includes
+    /// exhausted expressions of referenced variables.
+    pub fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Returns true if the most recent call to `next` changed `done` from false to true
+    ///
+    /// I.e. returns true once per cycle on the call that completes the cycle.  `done` is
+    /// considered false before the first call to `next` so an expression that is done after
+    /// every call (e.g. a constant) returns true on the first call only.  Returns false if `next`
+    /// has not been called.
+    pub fn done_edge(&self) -> bool {
+        self.done_edge
+    }
+
+    /// Returns the number of values returned by `next` (or skipped by `skip`) since the variable
+    /// was created or `reset`
+    ///
+    /// Calls to `next` while the variable is disabled are not counted.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the number of cycles completed since the variable was created or `reset`
+    ///
+    /// A cycle completes on each call to `next` that changes `done` from false to true (see
+    /// `done_edge`) except the first call.  Expressions that are done after every call (e.g.
+    /// constants and ranges) therefore never complete a cycle and return 0.  E.g. 10 calls of
+    /// `Pattern(1, 2, 3, 4)` complete 2 cycles.
+    pub fn cycle_count(&self) -> u64 {
+        self.cycle_count
+    }
+
+    /// Returns true if the variable returns the same values regardless of seed
+    ///
+    /// Computed structurally.  See `Expr::is_deterministic`.
+    pub fn is_deterministic(&self) -> bool {
+        self.expr.is_deterministic()
+    }
+
+    /// Returns the minimum and maximum values the variable can return if statically determinable
+    ///
+    /// Bounds are conservative.  The output mask is accounted for.  Returns `None` if the variable
+    /// has a value transform.  See `Expr::value_bounds`.
+    pub fn value_bounds(&self) -> Option<(u32, u32)> {
+        if self.value_transform.is_some() {
+            return None;
+        }
+
+        let (min, max) = self.expr.value_bounds()?;
+        let low_mask = self.output_mask.checked_add(1).is_none_or(u32::is_power_of_two);
+        if low_mask && max <= self.output_mask {
+            Some((min, max))
+        } else {
+            Some((0, max.min(self.output_mask)))
+        }
+    }
+
+    /// Returns every value `next` can return if statically determinable
+    ///
+    /// Accounts for the output mask and for the variable being frozen or disabled.  Returns `None`
+    /// if the variable has a value transform.  See `Expr::possible_values`.
+    pub fn possible_values(&self) -> Option<Vec<u32>> {
+        if self.frozen {
+            return Some(vec![self.prev()]);
+        }
+
+        if !self.enabled {
+            return Some(vec![self.disabled_value.unwrap_or_else(|| self.prev())]);
+        }
+
+        if self.value_transform.is_some() {
+            return None;
+        }
+
+        let mut values: Vec<u32> = self
+            .expr
+            .possible_values()?
+            .into_iter()
+            .map(|value| value & self.output_mask)
+            .collect();
+        values.sort_unstable();
+        values.dedup();
+
+        Some(values)
+    }
+
+    /// Returns the bounds of the expression of the variable if it is a range E.g. `[0, 10]`
+    ///
+    /// Returns `None` for all other expressions including ranges nested in other expressions.
+    pub fn range_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.as_range().map(Range::bounds)
+    }
+
+    /// Returns the weights of the expression of the variable if it is a weighted sample E.g.
+    /// `{3: 0, 1: 1}`
+    ///
+    /// Returns `None` for all other expressions including weighted samples nested in other
+    /// expressions.  See `Expr::weights`.
+    pub fn weights(&self) -> Option<&[u32]> {
+        self.expr.weights()
+    }
+
+    /// Replaces the bounds of the expression of the variable if it is a range
+    ///
+    /// The new bounds are kept by `reset`.  Returns false and leaves the variable unmodified if the
+    /// expression is not a range.  See `range_bounds`.
+    pub fn set_range_bounds(&mut self, l: u32, r: u32) -> bool {
+        match self.expr.as_range_mut() {
+            Some(range) => range.set_bounds(l, r),
+            None => return false,
+        }
+
+        if let Some(range) = self.initial_expr.as_range_mut() {
+            range.set_bounds(l, r);
+        }
+
+        true
+    }
+
+    /// Enables or disables counting of the raw 32-bit words drawn from the RNG of the variable
+    ///
+    /// Enabling restarts the count at 0.  See `rng_draws`.
+    pub fn set_rng_draw_counting(&mut self, enabled: bool) {
+        self.rng.set_draw_counting(enabled);
+    }
+
+    /// Returns the cumulative number of raw 32-bit words drawn from the RNG of the variable
+    ///
+    /// Only draws from the RNG of this variable are counted.  Referenced variables draw from their
+    /// own RNGs.  Draws made by `next_with_entropy` are included.  Returns `None` if counting is
+    /// disabled.  See `set_rng_draw_counting`.
+    pub fn rng_draws(&self) -> Option<u64> {
+        self.rng.draws()
+    }
+
+    /// Returns the index of the sub-expression selected by the most recent call to `next`
+    ///
+    /// Returns `None` if the expression of the variable is not a sample (e.g. `r{...}` or
+    /// `{...}`) or if `next` has not been called.
+    pub fn last_selected(&self) -> Option<usize> {
+        self.expr.last_selected()
+    }
+
+    /// Returns the position within the current cycle of the value returned by the most recent
+    /// call to `next`
+    ///
+    /// Returns `None` if the expression of the variable is not cyclic (e.g. `Pattern(...)` or
+    /// `Sequence(...)`) or if `next` has not been called.
+    pub fn cycle_position(&self) -> Option<usize> {
+        self.expr.cycle_position()
+    }
+
+    /// Enables or disables collection of statistics on values returned by `next`
+    ///
+    /// Statistics collected so far are kept when disabled.
+    pub fn set_stats_enabled(&mut self, enabled: bool) {
+        self.stats_enabled = enabled;
+    }
+
+    /// Returns the statistics collected so far
+    ///
+    /// Returns `None` if no values have been collected.
+    pub fn stats(&self) -> Option<Stats> {
+        self.stats
+    }
+
+    /// Sets or removes a function applied to each value returned by `next`
+    ///
+    /// The transformed value is also returned by `prev`, seen by references to this variable, and
+    /// collected by statistics.  Forks share the function.
+    pub fn set_value_transform(&mut self, value_transform: Option<ValueTransform>) {
+        self.value_transform = value_transform;
+    }
+
+    /// Attaches metadata to the variable
+    ///
+    /// Attributes do not affect evaluation.  An existing value for `key` is replaced.
+    pub fn set_attribute(&mut self, key: &str, value: &str) {
+        self.attributes.insert(key.into(), value.into());
+    }
+
+    /// Returns the value of an attribute or `None` if the attribute does not exist
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    /// Returns the doc comment of the variable or `None` if the variable has no doc comment
+    ///
+    /// Doc comments are `///` comments immediately preceding a variable definition.  Consecutive
+    /// lines are joined with newlines.  Stored as the `doc` attribute.
+    pub fn doc(&self) -> Option<&str> {
+        self.attribute("doc")
+    }
+
+    /// Returns all attributes in the order they were defined
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns the expression tree in Graphviz DOT format
+    ///
+    /// Each expression is a node labeled by `Expr::label` with an edge to each sub-expression.
+    /// Nodes are named `n<index>` in depth-first order starting with `n0` for the root.  The value
+    /// transform and output mask are not included.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        write_dot_node(&*self.expr, &mut dot, &mut 0);
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+/// Writes the DOT node for `expr` and recurses into its sub-expressions
+///
+/// Returns the name of the node.
+fn write_dot_node(expr: &dyn Expr, dot: &mut String, count: &mut usize) -> String {
+    let name = format!("n{}", count);
+    *count += 1;
+
+    let label = expr.label().replace('\\', "\\\\").replace('"', "\\\"");
+    dot.push_str(&format!("    {} [label=\"{}\"];\n", name, label));
+
+    for child in expr.children() {
+        let child = write_dot_node(child, dot, count);
+        dot.push_str(&format!("    {} -> {};\n", name, child));
+    }
+
+    name
 }
 
 impl fmt::Display for Variable {
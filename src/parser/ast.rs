@@ -24,7 +24,7 @@ impl Ast {
 
     /// Adds AST nodes to the AST.
     ///
-    /// * If Node is a variable definition
+    /// * If Node is a variable or constant definition
     ///
     ///   * If variable has been previously defined
     ///
@@ -40,19 +40,14 @@ impl Ast {
     }
 
     fn add_node(&mut self, node: Box<ast::Node>) {
-        let is_variable = if let ast::Node::Variable(_, _) = *node {
-            true
-        } else {
-            false
+        let name = match *node {
+            ast::Node::Variable(ref name, ..) | ast::Node::Constant(ref name, ..) => {
+                Some(name.to_owned())
+            }
+            _ => None,
         };
 
-        if is_variable {
-            let name = if let ast::Node::Variable(ref name, _) = *node {
-                name.to_owned()
-            } else {
-                "".to_owned()
-            };
-
+        if let Some(name) = name {
             let nodes = &mut self.nodes;
             match self.variable_indexes.entry(name) {
                 Entry::Occupied(entry) => {
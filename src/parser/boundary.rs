@@ -0,0 +1,158 @@
+/// Tracks whether a stream of source lines ends on a statement boundary
+///
+/// Used to split a source into chunks of whole statements without parsing it.  A statement ends
+/// with a `;` outside of any brackets.  An `enum` or `group` statement ends with its closing `}`.
+/// Comments and quoted strings (e.g. quoted `import` paths) are ignored.  Quoted strings end at the
+/// end of the line as in the grammar.
+#[derive(Default)]
+pub struct StatementBoundary {
+    depth: usize,
+    in_statement: bool,
+    /// The current statement ends with a `}` instead of a `;`
+    ends_with_brace: bool,
+}
+
+impl StatementBoundary {
+    pub fn new() -> StatementBoundary {
+        Default::default()
+    }
+
+    /// Advances over a single line
+    ///
+    /// Returns true if the line ends outside of any statement.
+    pub fn advance(&mut self, line: &str) -> bool {
+        self.statement_ends(line);
+
+        !self.in_statement
+    }
+
+    /// Returns true if the current statement has unclosed brackets
+    pub fn in_brackets(&self) -> bool {
+        self.depth > 0
+    }
+
+    /// Advances over a single line and returns the byte offset just past the end of each statement
+    /// that ends within it
+    pub fn statement_ends(&mut self, line: &str) -> Vec<usize> {
+        let mut ends = Vec::new();
+        let mut chars = line.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            if c == '/' && chars.peek().map(|&(_, c)| c) == Some('/') {
+                break;
+            }
+
+            if c.is_whitespace() {
+                continue;
+            }
+
+            if !self.in_statement {
+                self.in_statement = true;
+                self.ends_with_brace = starts_with_keyword(&line[index..], "enum")
+                    || starts_with_keyword(&line[index..], "group");
+            }
+
+            match c {
+                '\'' | '"' => skip_quoted(&mut chars, c),
+                '(' | '[' | '{' => self.depth += 1,
+                ')' | ']' | '}' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if c == '}' && self.depth == 0 && self.ends_with_brace {
+                        self.in_statement = false;
+                        ends.push(index + 1);
+                    }
+                }
+                ';' if self.depth == 0 => {
+                    self.in_statement = false;
+                    ends.push(index + 1);
+                }
+                _ => {}
+            }
+        }
+
+        ends
+    }
+}
+
+/// Returns true if `s` starts with `keyword` as a whole word
+///
+/// E.g. `groupsize = 1;` does not start with the keyword `group`.
+fn starts_with_keyword(s: &str, keyword: &str) -> bool {
+    match s.strip_prefix(keyword) {
+        Some(rest) => !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'),
+        None => false,
+    }
+}
+
+/// Advances `chars` past the closing `quote` of a quoted string
+///
+/// A backslash escapes the next character.
+fn skip_quoted<I>(chars: &mut I, quote: char)
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    while let Some((_, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semicolon() {
+        let mut boundary = StatementBoundary::new();
+        assert!(!boundary.advance("a = Pattern(1,"));
+        assert!(!boundary.advance("  2); b = 5"));
+        assert!(boundary.advance(";"));
+    }
+
+    #[test]
+    fn braces() {
+        let mut boundary = StatementBoundary::new();
+        assert!(!boundary.advance("a = {1, 2}"));
+        assert!(boundary.advance(";"));
+        assert!(!boundary.advance("enum E {"));
+        assert!(boundary.advance("}"));
+    }
+
+    #[test]
+    fn keyword_prefixed_names() {
+        let mut boundary = StatementBoundary::new();
+        assert!(!boundary.advance("groupsize = {1,"));
+        assert!(!boundary.advance(" 2}"));
+        assert!(boundary.advance(";"));
+        assert!(!boundary.advance("enum_count = {1}"));
+        assert!(boundary.advance(";"));
+        assert!(!boundary.advance("group g {"));
+        assert!(boundary.advance("}"));
+    }
+
+    #[test]
+    fn statement_ends() {
+        let mut boundary = StatementBoundary::new();
+        assert_eq!(boundary.statement_ends("a = 1; b = (2;"), [6]);
+        assert_eq!(boundary.statement_ends("); c = 3; // d = 4;"), [2, 9]);
+    }
+
+    #[test]
+    fn comments() {
+        let mut boundary = StatementBoundary::new();
+        assert!(boundary.advance("// a = 5"));
+        assert!(!boundary.advance("a = 5 // ;"));
+        assert!(boundary.advance(";"));
+    }
+
+    #[test]
+    fn quotes() {
+        let mut boundary = StatementBoundary::new();
+        assert_eq!(boundary.statement_ends("import 'a;b.rvs'; import \"(c//d.rvs\";"), [17, 37]);
+        assert_eq!(boundary.statement_ends(r"import 'a\';b.rvs'; a = 1;"), [19, 26]);
+        assert!(!boundary.in_brackets());
+    }
+}
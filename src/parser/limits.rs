@@ -0,0 +1,165 @@
+use crate::error::{Error, Result};
+
+use rvs_parser::ast::{Node, Span};
+use rvs_parser::ParseError;
+
+/// Limits on the size of the parsed sources
+///
+/// Guards against resource exhaustion by untrusted sources.  Sizes accumulate over all sources
+/// parsed by a `Parser`.  All limits default to unlimited.
+///
+/// The limits are checked after a source (or a statement of `Parser::parse_reader`) is parsed and
+/// before it is added to the AST.  They bound the AST that is kept and transformed but not the
+/// memory or time spent parsing the source itself.
+pub struct Limits {
+    pub max_variables: usize,
+    pub max_children: usize,
+    pub max_nodes: usize,
+    variables: usize,
+    nodes: usize,
+}
+
+impl Limits {
+    pub fn new() -> Limits {
+        Limits {
+            max_variables: usize::MAX,
+            max_children: usize::MAX,
+            max_nodes: usize::MAX,
+            variables: 0,
+            nodes: 0,
+        }
+    }
+
+    /// Adds the sizes of `nodes` to the running totals
+    ///
+    /// Returns an error and leaves the totals untouched if a limit is exceeded.
+    pub fn check(&mut self, nodes: &[Box<Node>]) -> Result<()> {
+        let mut sizes = Sizes {
+            limits: self,
+            variables: self.variables,
+            nodes: self.nodes,
+        };
+
+        for node in nodes {
+            sizes.add(node, statement_span(node)).map_err(|description| {
+                Error::Parse(ParseError::new(description))
+            })?;
+        }
+
+        let (variables, nodes) = (sizes.variables, sizes.nodes);
+        self.variables = variables;
+        self.nodes = nodes;
+
+        Ok(())
+    }
+
+    /// Returns the number of variable and constant definitions checked so far
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+}
+
+/// Running totals of a single call to `Limits::check`
+struct Sizes<'a> {
+    limits: &'a Limits,
+    variables: usize,
+    nodes: usize,
+}
+
+impl<'a> Sizes<'a> {
+    fn add(&mut self, node: &Node, span: Option<Span>) -> ::std::result::Result<(), String> {
+        let error = |message: String| match span {
+            Some(span) => format!("error at {}: {}", span, message),
+            None => message,
+        };
+
+        self.nodes += 1;
+        if self.nodes > self.limits.max_nodes {
+            return Err(error(format!(
+                "the number of AST nodes exceeds the limit of {}",
+                self.limits.max_nodes
+            )));
+        }
+
+        match *node {
+            Node::Variable(..) | Node::Constant(..) => {
+                self.variables += 1;
+                if self.variables > self.limits.max_variables {
+                    return Err(error(format!(
+                        "the number of variables exceeds the limit of {}",
+                        self.limits.max_variables
+                    )));
+                }
+            }
+            Node::Type(ref typ, ref children) if children.len() > self.limits.max_children => {
+                return Err(error(format!(
+                    "`{:?}` has {} arguments which exceeds the limit of {}",
+                    typ,
+                    children.len(),
+                    self.limits.max_children
+                )));
+            }
+            Node::Weighted(_, ref children) if children.len() > self.limits.max_children => {
+                return Err(error(format!(
+                    "weighted sample has {} entries which exceeds the limit of {}",
+                    children.len(),
+                    self.limits.max_children
+                )));
+            }
+            _ => {}
+        }
+
+        for child in children(node) {
+            self.add(child, statement_span(child).or(span))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the location of a statement
+fn statement_span(node: &Node) -> Option<Span> {
+    match *node {
+        Node::Variable(_, _, span, _)
+        | Node::Constant(_, _, span)
+        | Node::Enum(_, _, span)
+        | Node::Distinct(_, span)
+        | Node::Pair(_, _, _, span)
+        | Node::Gate(_, _, span)
+        | Node::Group(_, _, span) => Some(span),
+        _ => None,
+    }
+}
+
+/// Returns the direct children of a node
+pub(crate) fn children(node: &Node) -> Vec<&Node> {
+    match *node {
+        Node::UnaryOperation(_, ref a)
+        | Node::Variable(_, ref a, ..)
+        | Node::Constant(_, ref a, _)
+        | Node::WeightedSample(_, ref a)
+        | Node::PercentWeightedSample(_, ref a)
+        | Node::ProbabilityWeightedSample(_, _, ref a)
+        | Node::Pair(_, _, ref a, _) => vec![a],
+        Node::EnumMember(_, Some(ref a)) => vec![a],
+        Node::BinaryOperation(ref a, _, ref b) | Node::Let(_, ref a, ref b) => vec![a, b],
+        Node::Except(ref a, ref nodes) | Node::In(ref a, ref nodes) => {
+            ::std::iter::once(&**a).chain(nodes.iter().map(|n| &**n)).collect()
+        }
+        Node::Complement(ref nodes, ref l, ref r) => {
+            nodes.iter().map(|n| &**n).chain(vec![&**l, &**r]).collect()
+        }
+        Node::Type(_, ref nodes)
+        | Node::Weighted(_, ref nodes)
+        | Node::Enum(_, ref nodes, _)
+        | Node::Group(_, ref nodes, _) => nodes.iter().map(|n| &**n).collect(),
+        Node::Number(_)
+        | Node::DontCare(..)
+        | Node::EnumMember(_, None)
+        | Node::RIdentifier(..)
+        | Node::MethodChain(..)
+        | Node::Distinct(..)
+        | Node::Gate(..)
+        | Node::PatternFile(_) => Vec::new(),
+    }
+}
@@ -1,5 +1,8 @@
 mod ast;
-#[cfg_attr(feature = "cargo-clippy", allow(module_inception))]
+mod boundary;
+mod limits;
+#[allow(clippy::module_inception)]
 mod parser;
 
 pub use self::parser::Parser;
+pub(crate) use self::limits::children;
@@ -1,12 +1,18 @@
 use super::ast::Ast;
+use super::boundary::StatementBoundary;
+use super::limits::Limits;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
-use rvs_parser;
 use rvs_parser::ast;
 
+use std::io::BufRead;
+
 pub struct Parser {
     search_path: rvs_parser::SearchPath,
+    max_import_depth: usize,
+    unknown_function_errors: bool,
+    limits: Limits,
     ast: Ast,
 }
 
@@ -15,20 +21,218 @@ impl Parser {
         Parser {
             // FIXME: Remove clone
             search_path: search_path.clone(),
+            max_import_depth: rvs_parser::DEFAULT_MAX_IMPORT_DEPTH,
+            unknown_function_errors: false,
+            limits: Limits::new(),
             ast: Ast::new(),
         }
     }
 
-    pub fn parse(&mut self, s: &str) -> Result<()> {
+    /// Sets the maximum number of nested imports
+    ///
+    /// Exceeding the maximum is a parse error.
+    pub fn set_max_import_depth(&mut self, max_import_depth: usize) {
+        self.max_import_depth = max_import_depth;
+    }
+
+    /// Enables or disables specific errors for calls of unknown functions
+    ///
+    /// E.g. `Shufle(1, 2)` is reported as "unknown function 'Shufle', did you mean 'Shuffle'?"
+    /// instead of a generic syntax error.  Disabled by default.
+    pub fn set_unknown_function_errors(&mut self, enabled: bool) {
+        self.unknown_function_errors = enabled;
+    }
+
+    /// Sets the maximum number of variable and constant definitions
+    ///
+    /// Redefinitions count.  Exceeding the maximum is a parse error.  Defaults to unlimited.
+    pub fn set_max_variables(&mut self, max_variables: usize) {
+        self.limits.max_variables = max_variables;
+    }
+
+    /// Sets the maximum number of arguments of a type (e.g. `Pattern`) or entries of a weighted
+    /// sample
+    ///
+    /// Exceeding the maximum is a parse error.  Defaults to unlimited.  Values expanded when
+    /// transforming (e.g. by `Expand()`) are limited by `Transform::set_max_children`.
+    pub fn set_max_children(&mut self, max_children: usize) {
+        self.limits.max_children = max_children;
+    }
+
+    /// Sets the maximum total number of AST nodes
+    ///
+    /// Exceeding the maximum is a parse error.  Defaults to unlimited.  Like the other limits, the
+    /// maximum is checked once a source has been parsed so a single source may exceed it while
+    /// being parsed.  `parse_reader` checks statement by statement.
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.limits.max_nodes = max_nodes;
+    }
+
+    fn parser(&self) -> rvs_parser::Parser {
         // FIXME: Remove clone
-        let parser = rvs_parser::Parser::new(self.search_path.clone());
-        let nodes = parser.parse(s)?;
+        let mut parser = rvs_parser::Parser::new(self.search_path.clone());
+        parser.set_max_import_depth(self.max_import_depth);
+        parser.set_unknown_function_errors(self.unknown_function_errors);
+
+        parser
+    }
+
+    pub fn parse(&mut self, s: &str) -> Result<()> {
+        let nodes = self.parser().parse(s)?;
+
+        self.add_nodes(nodes)
+    }
+
+    /// Adds nodes to the AST after checking them against the limits
+    fn add_nodes(&mut self, nodes: Vec<Box<ast::Node>>) -> Result<()> {
+        self.limits.check(&nodes)?;
         self.ast.add_nodes(nodes);
 
         Ok(())
     }
 
+    /// Parses statements from a reader
+    ///
+    /// Only buffers enough lines to hold whole statements.  Memory use scales with the largest
+    /// statement instead of the whole source.
+    pub fn parse_reader<R: BufRead>(&mut self, reader: R) -> Result<()> {
+        let parser = self.parser();
+        let mut import_paths = parser.source_paths();
+        let mut boundary = StatementBoundary::new();
+        let mut buffer = String::new();
+        let mut buffer_line = 1;
+        let mut line_number = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            line_number += 1;
+
+            buffer.push_str(&line);
+            buffer.push('\n');
+
+            if boundary.advance(&line) {
+                let nodes = parser.parse_at(&buffer, buffer_line, &mut import_paths)?;
+                self.add_nodes(nodes)?;
+
+                buffer.clear();
+                buffer_line = line_number + 1;
+            }
+        }
+
+        if !buffer.is_empty() {
+            let nodes = parser.parse_at(&buffer, buffer_line, &mut import_paths)?;
+            self.add_nodes(nodes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parses statements while recovering from parse errors
+    ///
+    /// On error, the source is split into statements and each statement is parsed on its own.  A
+    /// statement that fails to parse is skipped and parsing resumes at the next statement so a
+    /// mistake in one statement does not cause errors in others.  Statements end as described by
+    /// `StatementBoundary` so an unbalanced bracket extends the failed statement to the end of the
+    /// source.  Statements parsed without error are added to the AST.
+    ///
+    /// Returns the errors of all failed statements in source order.
+    pub fn parse_recover(&mut self, s: &str) -> Vec<Error> {
+        let parser = self.parser();
+        if let Ok(nodes) = parser.parse(s) {
+            return self.add_nodes(nodes).err().into_iter().collect();
+        }
+
+        let mut import_paths = parser.source_paths();
+        let mut errors = Vec::new();
+        for (start, end) in statements(s) {
+            // Pad the statement to its column so errors and spans match the whole source
+            let line_start = s[..start].rfind('\n').map_or(0, |index| index + 1);
+            let line = s[..start].matches('\n').count() + 1;
+            let statement = " ".repeat(s[line_start..start].chars().count()) + &s[start..end];
+
+            match parser.parse_at(&statement, line, &mut import_paths) {
+                Ok(nodes) => {
+                    if let Err(e) = self.add_nodes(nodes) {
+                        errors.push(e);
+                    }
+                }
+                Err(e) => errors.push(From::from(e)),
+            }
+        }
+
+        errors
+    }
+
+    /// Returns the AST in a compact binary format
+    ///
+    /// Allows hosts to cache parse results.  See `deserialize_ast`.  Only the AST is cached.  The
+    /// model is not, so a deserialized AST must still be transformed.  See `Model::serialize` for
+    /// caching transformed models.
+    pub fn serialize_ast(&self) -> Vec<u8> {
+        rvs_parser::binary::encode(self.ast.get())
+    }
+
+    /// Creates a parser with the AST returned by `serialize_ast`
+    ///
+    /// Transforming the AST with the same seed yields the same values as transforming the AST of
+    /// the original parser.  Returns an error if `bytes` were serialized by a different version of
+    /// the format.  Limits are not applied to the AST.
+    pub fn deserialize_ast(search_path: &rvs_parser::SearchPath, bytes: &[u8]) -> Result<Parser> {
+        let nodes = rvs_parser::binary::decode(bytes).map_err(Error::Parse)?;
+
+        let mut parser = Parser::new(search_path);
+        parser.ast.add_nodes(nodes);
+
+        Ok(parser)
+    }
+
     pub fn ast(&self) -> &[Box<ast::Node>] {
         self.ast.get()
     }
+
+    /// Returns the number of variable and constant definitions parsed so far
+    ///
+    /// Includes definitions in imported files and groups.  Redefinitions count.  Sources that
+    /// fail to parse do not count.
+    pub fn definition_count(&self) -> usize {
+        self.limits.variables()
+    }
+
+    pub fn search_path(&self) -> &rvs_parser::SearchPath {
+        &self.search_path
+    }
+}
+
+/// Returns the byte ranges of the statements of `s`
+///
+/// Text after the last statement (e.g. an unterminated statement) is returned as a statement of
+/// its own.  If the text has unclosed brackets, it is split at every `;` instead so that the
+/// statements following an unclosed bracket are recovered.
+fn statements(s: &str) -> Vec<(usize, usize)> {
+    let mut boundary = StatementBoundary::new();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut line_start = 0;
+
+    for line in s.split_inclusive('\n') {
+        for end in boundary.statement_ends(line) {
+            statements.push((start, line_start + end));
+            start = line_start + end;
+        }
+        line_start += line.len();
+    }
+
+    if boundary.in_brackets() {
+        let rest = start;
+        for (index, _) in s[rest..].match_indices(';') {
+            statements.push((start, rest + index + 1));
+            start = rest + index + 1;
+        }
+    }
+
+    if !s[start..].trim().is_empty() {
+        statements.push((start, s.len()));
+    }
+
+    statements
 }
@@ -0,0 +1,164 @@
+use crate::error::Result;
+use crate::model::{Model, Variable};
+use crate::parser::Parser;
+use crate::transform::{Seed, Transform};
+
+use std::panic;
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+/// Evaluates a variable on a background thread
+///
+/// Values are passed to the consumer through a bounded single-producer single-consumer buffer.
+/// The producer blocks while the buffer is full.  See `spawn_producer`.
+pub struct ProducerHandle {
+    receiver: Option<Receiver<u32>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Starts evaluating the variable `name` on a background thread
+///
+/// The model is built on the background thread by transforming the AST of `parser` with `seed`
+/// so no variables are shared between threads.  The transform uses the default settings and the
+/// search path of `parser`.  The values are the same as those of the variable of a model
+/// transformed on the calling thread with the same AST and seed.  Up to `capacity` values are
+/// buffered ahead of the consumer.  See `spawn_producer_with` for other transform settings.
+///
+/// Returns an error if the transform fails or the variable is not defined.
+pub fn spawn_producer(
+    parser: &Parser,
+    seed: Seed,
+    name: &str,
+    capacity: usize,
+) -> Result<ProducerHandle> {
+    spawn_producer_with(parser, seed, name, capacity, |_| {})
+}
+
+/// Starts evaluating the variable `name` on a background thread with a configured transform
+///
+/// As for `spawn_producer` except `configure` is called on the background thread to configure
+/// the transform (e.g. `Transform::set_output_mask`) before transforming.  A `Transform` cannot
+/// be passed between threads so it is configured in place.
+pub fn spawn_producer_with<F>(
+    parser: &Parser,
+    seed: Seed,
+    name: &str,
+    capacity: usize,
+    configure: F,
+) -> Result<ProducerHandle>
+where
+    F: FnOnce(&mut Transform) + Send + 'static,
+{
+    let nodes = parser.ast().to_vec();
+    let search_path = parser.search_path().clone();
+    let name = name.to_owned();
+    let (status_sender, status_receiver) = mpsc::sync_channel(1);
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+
+    let thread = thread::spawn(move || {
+        let mut transform = Transform::new(seed);
+        transform.set_search_path(search_path);
+        configure(&mut transform);
+        let mut model = Model::new();
+        let variable = transform
+            .transform(&mut model, &nodes)
+            .map_err(From::from)
+            .and_then(|_| model.get_result(&name).cloned());
+
+        match variable {
+            Ok(variable) => {
+                let _ = status_sender.send(Ok(()));
+                produce(&mut variable.borrow_mut(), &sender);
+            }
+            Err(e) => {
+                let _ = status_sender.send(Err(e));
+            }
+        }
+    });
+
+    let mut producer = ProducerHandle {
+        receiver: Some(receiver),
+        thread: Some(thread),
+    };
+
+    match status_receiver.recv() {
+        Ok(Ok(())) => Ok(producer),
+        Ok(Err(e)) => {
+            let _ = producer.join();
+            Err(e)
+        }
+        Err(_) => producer.disconnected(),
+    }
+}
+
+/// Sends values until the receiver is dropped
+fn produce(variable: &mut Variable, sender: &SyncSender<u32>) {
+    while sender.send(variable.next()).is_ok() {}
+}
+
+impl ProducerHandle {
+    /// Returns the next value, blocking until one is available
+    ///
+    /// # Panics
+    ///
+    /// * If evaluating the variable panicked on the background thread
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u32 {
+        match self.receiver().recv() {
+            Ok(value) => value,
+            Err(_) => self.disconnected(),
+        }
+    }
+
+    /// Returns the next value if one is available without blocking
+    ///
+    /// # Panics
+    ///
+    /// * If evaluating the variable panicked on the background thread
+    pub fn try_next(&mut self) -> Option<u32> {
+        match self.receiver().try_recv() {
+            Ok(value) => Some(value),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => self.disconnected(),
+        }
+    }
+
+    /// Stops the producer and waits for the background thread to exit
+    ///
+    /// Buffered values are discarded.  Equivalent to dropping the handle.
+    pub fn stop(mut self) {
+        let _ = self.join();
+    }
+
+    /// Propagates the panic of the background thread
+    ///
+    /// The background thread only exits on its own by panicking.
+    fn disconnected(&mut self) -> ! {
+        if let Err(payload) = self.join() {
+            panic::resume_unwind(payload);
+        }
+
+        unreachable!("the producer thread exited without panicking");
+    }
+
+    fn receiver(&self) -> &Receiver<u32> {
+        self.receiver.as_ref().expect("receiver is only taken by join")
+    }
+
+    /// Disconnects the buffer so a blocked producer wakes up and exits, then joins the thread
+    ///
+    /// Returns the panic of the background thread, if any.
+    fn join(&mut self) -> thread::Result<()> {
+        self.receiver.take();
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ProducerHandle {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
@@ -0,0 +1,94 @@
+use crate::model::Model;
+use crate::parser::Parser;
+use crate::transform::{Seed, Transform};
+
+const SOURCE: &str = "
+    enum Kind { A, B, C }
+    a = [0, 0xffff_ffff];
+    b = r{[0, 9], [10, 19], 2: Pattern(100, 200)};
+    c = {Kind::A, Kind::B, Kind::C, 7};
+    d = Shuffle(1, 2, 3, 4);
+    e = Sequence(0, 20, 5) + [0, 1];
+    f = [0, 15] except {3, 5, 7};
+";
+
+const SEED: u32 = 0x5eed;
+
+/// The first values returned by each variable in `SOURCE`
+///
+/// Selections draw from fixed-width integers (e.g. `Uniform<u64>` for samples with replacement)
+/// so the values are the same on all targets.
+const GOLDEN: &[(&str, &[u32])] = &[
+    ("a", &[0xe4ae_822a, 0x9c7c_9c2e, 0x0a8e_c6ca, 0x7b8e_27b1, 0xa571_fea6, 0x90d6_8ad6, 0x5f84_6e2a, 0x710c_68ef]),
+    ("b", &[0x64, 0xc8, 0x10, 0xe, 0x8, 0xd, 0x64, 0xc8]),
+    ("c", &[0x7, 0x1, 0x0, 0x2, 0x2, 0x7, 0x1, 0x0]),
+    ("d", &[0x4, 0x2, 0x1, 0x3, 0x3, 0x4, 0x2, 0x1]),
+    ("e", &[0x1, 0x6, 0xa, 0xf, 0x15, 0x1, 0x5, 0xa]),
+    ("f", &[0xe, 0x9, 0x0, 0xa, 0x9, 0x4, 0x2, 0xd]),
+];
+
+/// Checks that evaluation matches golden values recorded with a fixed source and seed
+///
+/// Returns false if the values have drifted E.g. due to a change in the RNG or in the
+/// implementation of an expression by a dependency update.  Covers ranges, samples with and
+/// without replacement, shuffles, sequences, operations, and `except`.
+pub fn self_test() -> bool {
+    check(SOURCE, SEED, GOLDEN)
+}
+
+fn check(source: &str, seed: u32, golden: &[(&str, &[u32])]) -> bool {
+    let mut parser = Parser::new(&Default::default());
+    if parser.parse(source).is_err() {
+        return false;
+    }
+
+    let mut model = Model::new();
+    let mut transform = Transform::new(Seed::from_u32(seed));
+    if transform.transform(&mut model, parser.ast()).is_err() {
+        return false;
+    }
+
+    golden.iter().all(|(name, values)| match model.get_variable_by_name(name) {
+        Some(variable) => {
+            let mut variable = variable.borrow_mut();
+
+            values.iter().all(|value| variable.next() == *value)
+        }
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes() {
+        assert!(self_test());
+    }
+
+    #[test]
+    fn corrupted_golden_value() {
+        let mut golden: Vec<(&str, Vec<u32>)> = GOLDEN
+            .iter()
+            .map(|(name, values)| (*name, values.to_vec()))
+            .collect();
+        golden[2].1[3] ^= 1;
+        let golden: Vec<(&str, &[u32])> = golden
+            .iter()
+            .map(|(name, values)| (*name, values.as_slice()))
+            .collect();
+
+        assert!(!check(SOURCE, SEED, &golden));
+    }
+
+    #[test]
+    fn different_seed() {
+        assert!(!check(SOURCE, SEED + 1, GOLDEN));
+    }
+
+    #[test]
+    fn missing_variable() {
+        assert!(!check(SOURCE, SEED, &[("z", &[0])]));
+    }
+}
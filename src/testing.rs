@@ -0,0 +1,64 @@
+//! Helpers for golden value tests
+//!
+//! Golden value tests pin the values an expression returns for a seed so that changes in the
+//! values (e.g. due to a change in the RNG or in the implementation of an expression) are caught.
+
+use crate::error::Result;
+use crate::transform::Seed;
+use crate::{expression_values, parse_expression};
+
+use std::fmt::Write;
+
+/// Returns the first `n` values of an expression transformed with `seed`
+///
+/// Returns an error if the expression fails to parse or transform.
+pub fn stream(expr: &str, seed: u32, n: usize) -> Result<Vec<u32>> {
+    let parser = parse_expression(expr)?;
+
+    expression_values(&parser, Seed::from_u32(seed), n)
+}
+
+/// Asserts that the first values of an expression transformed with `seed` are `expected`
+///
+/// # Panics
+///
+/// * If the expression fails to parse or transform
+/// * If the values differ.  The message identifies the first value that differs and lists all
+///   expected and actual values with the differing values marked.  E.g.
+///
+/// ```text
+/// the stream of `Pattern(1, 2, 3)` with seed 0x0 differs from the expected stream at index 2
+///   index  expected    actual
+///       0       0x1       0x1
+///       1       0x2       0x2
+///       2       0x4       0x3  <--
+/// ```
+pub fn assert_stream(expr: &str, seed: u32, expected: &[u32]) {
+    let actual = match stream(expr, seed, expected.len()) {
+        Ok(actual) => actual,
+        Err(e) => panic!("failed to evaluate `{}`: {}", expr, e),
+    };
+
+    if let Some(message) = diff(expr, seed, expected, &actual) {
+        panic!("{}", message);
+    }
+}
+
+/// Returns a description of the differences between two streams or `None` if they are equal
+fn diff(expr: &str, seed: u32, expected: &[u32], actual: &[u32]) -> Option<String> {
+    let first = expected.iter().zip(actual).position(|(expected, actual)| expected != actual)?;
+
+    let mut message = format!(
+        "the stream of `{}` with seed 0x{:x} differs from the expected stream at index {}\n",
+        expr, seed, first
+    );
+    writeln!(message, "  {:>5}  {:>10}  {:>10}", "index", "expected", "actual").unwrap();
+    for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        let marker = if expected != actual { "  <--" } else { "" };
+        let expected = format!("0x{:x}", expected);
+        let actual = format!("0x{:x}", actual);
+        writeln!(message, "  {:>5}  {:>10}  {:>10}{}", index, expected, actual, marker).unwrap();
+    }
+
+    Some(message)
+}
@@ -1,7 +1,8 @@
 use indexmap::IndexMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Enum {
+    #[allow(dead_code)]
     pub items: IndexMap<String, u32>,
 }
 
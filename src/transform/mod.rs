@@ -1,9 +1,9 @@
 mod rand;
 mod enumeration;
 mod symbols;
-#[cfg_attr(feature = "cargo-clippy", allow(module_inception))]
+#[allow(clippy::module_inception)]
 mod transform;
 
 pub use self::rand::Seed;
 pub use self::rand::CrateRng;
-pub use self::transform::Transform;
+pub use self::transform::{Transform, DEFAULT_MAX_RESAMPLE_ATTEMPTS};
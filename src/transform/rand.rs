@@ -1,9 +1,217 @@
-use rand::{Rng, SeedableRng};
+use rand::{Error, Rng, RngCore, SeedableRng};
 
-/// The RNG type used by this crate.
+use crate::error::TransformResult;
+use crate::model::{Reader, Writer};
+
+/// The RNG used by this crate
+///
+/// Wraps the underlying RNG to optionally count the number of raw 32-bit words drawn.  Counting
+/// is disabled by default.
+#[derive(Clone)]
+pub struct CrateRng {
+    source: Source,
+    draws: Option<u64>,
+}
+
+/// The source of the random numbers of a `CrateRng`
+#[derive(Clone)]
+enum Source {
+    Pcg(Pcg32),
+    /// The n-th 32-bit word is a mix of the key and n.  See `Seed::to_counter_rng`.
+    Counter { key: u64, counter: u64 },
+}
+
+/// The PCG32 generator (XSH RR output of a 64-bit LCG)
+///
+/// Produces the same values as `rand_pcg::Pcg32` but exposes its state so that it can be
+/// serialized.  See `CrateRng::encode`.
+#[derive(Clone)]
+struct Pcg32 {
+    state: u64,
+    /// Selects the stream.  Always odd.
+    increment: u64,
+}
+
+/// The multiplier of the PCG32 LCG
+const PCG_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+impl Pcg32 {
+    /// Seeds the generator as `rand_pcg::Pcg32::from_seed` does
+    ///
+    /// The low 8 bytes (little endian) are the initial state and the high 8 bytes select the
+    /// stream.  The lowest bit of the stream is ignored.
+    fn from_seed(seed: [u8; 16]) -> Pcg32 {
+        let mut words = [0u64; 2];
+        for (i, byte) in seed.iter().enumerate() {
+            words[i / 8] |= u64::from(*byte) << (8 * (i % 8));
+        }
+
+        let mut pcg = Pcg32 {
+            state: words[0],
+            increment: words[1] | 1,
+        };
+        pcg.state = pcg.state.wrapping_add(pcg.increment);
+        pcg.step();
+
+        pcg
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(PCG_MULTIPLIER).wrapping_add(self.increment);
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+
+        let rotate = (state >> 59) as u32;
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+
+        xorshifted.rotate_right(rotate)
+    }
+}
+
+/// The SplitMix64 increment
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The SplitMix64 finalizer
+fn finalize(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    z ^ (z >> 31)
+}
+
+/// Mixes a key and a counter into a 32-bit word
 ///
-/// Exists as a type alias to make changing RNG implementation easier.
-pub type CrateRng = rand_pcg::Pcg32;
+/// The SplitMix64 finalizer applied to the key offset by the counter.
+fn mix(key: u64, counter: u64) -> u32 {
+    (finalize(key.wrapping_add(counter.wrapping_mul(GOLDEN_GAMMA))) >> 32) as u32
+}
+
+impl CrateRng {
+    /// Returns a new RNG seeded from this one
+    ///
+    /// The new RNG uses the same source as this one (i.e. a counter-based RNG derives a
+    /// counter-based RNG).  Draws are not counted by the new RNG.
+    pub fn derive(&mut self) -> CrateRng {
+        match self.source {
+            Source::Pcg(_) => CrateRng::from_rng(self).unwrap(),
+            Source::Counter { .. } => CrateRng {
+                source: Source::Counter { key: self.next_u64(), counter: 0 },
+                draws: None,
+            },
+        }
+    }
+
+    /// Enables or disables counting of raw draws
+    ///
+    /// Enabling restarts the count at 0.
+    pub fn set_draw_counting(&mut self, enabled: bool) {
+        self.draws = if enabled { Some(0) } else { None };
+    }
+
+    /// Returns the number of raw 32-bit words drawn since counting was enabled
+    ///
+    /// Returns `None` if counting is disabled.
+    pub fn draws(&self) -> Option<u64> {
+        self.draws
+    }
+
+    /// Adds to the draw count if counting is enabled
+    pub fn add_draws(&mut self, count: u64) {
+        if let Some(ref mut draws) = self.draws {
+            *draws += count;
+        }
+    }
+
+    /// Writes the state of the RNG including the draw count
+    pub fn encode(&self, writer: &mut Writer) {
+        match self.source {
+            Source::Pcg(ref rng) => {
+                writer.u8(0);
+                writer.u64(rng.state);
+                writer.u64(rng.increment);
+            }
+            Source::Counter { key, counter } => {
+                writer.u8(1);
+                writer.u64(key);
+                writer.u64(counter);
+            }
+        }
+
+        writer.bool(self.draws.is_some());
+        if let Some(draws) = self.draws {
+            writer.u64(draws);
+        }
+    }
+
+    /// Reads an RNG written by `encode`
+    ///
+    /// The RNG continues where the encoded RNG left off.
+    pub fn decode(reader: &mut Reader) -> TransformResult<CrateRng> {
+        let source = match reader.u8()? {
+            0 => {
+                let state = reader.u64()?;
+                let increment = reader.u64()?;
+                reader.check(increment % 2 == 1, "even PCG increment")?;
+
+                Source::Pcg(Pcg32 { state, increment })
+            }
+            1 => Source::Counter {
+                key: reader.u64()?,
+                counter: reader.u64()?,
+            },
+            code => return Err(reader.error(&format!("unknown RNG source {}", code))),
+        };
+
+        let draws = if reader.bool()? { Some(reader.u64()?) } else { None };
+
+        Ok(CrateRng { source, draws })
+    }
+}
+
+impl RngCore for CrateRng {
+    fn next_u32(&mut self) -> u32 {
+        self.add_draws(1);
+        match self.source {
+            Source::Pcg(ref mut rng) => rng.next_u32(),
+            Source::Counter { key, ref mut counter } => {
+                *counter += 1;
+                mix(key, *counter)
+            }
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = u64::from(self.next_u32());
+        let high = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for CrateRng {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> CrateRng {
+        CrateRng {
+            source: Source::Pcg(Pcg32::from_seed(seed)),
+            draws: None,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Seed([u8; 16]);
@@ -33,30 +241,79 @@ impl Seed {
         Seed::from_u32_array([rng.gen(), rng.gen(), rng.gen(), rng.gen()])
     }
 
+    /// Generates a 128-bit seed from an arbitrary number of bytes of entropy
+    ///
+    /// The bytes are absorbed 8 at a time into a 64-bit state via the SplitMix64 finalizer so that
+    /// every byte affects every bit of the seed.  The seed is then drawn from the state as in
+    /// `to_counter_rng`.  Any number of bytes is accepted.  Fewer than 16 bytes are zero extended.
+    pub fn from_bytes(bytes: &[u8]) -> Seed {
+        let mut words: Vec<u64> = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+
+                u64::from_le_bytes(word)
+            })
+            .collect();
+        if words.len() < 2 {
+            words.resize(2, 0);
+        }
+
+        let state = words
+            .iter()
+            .fold(0, |state: u64, word| finalize(state.wrapping_add(GOLDEN_GAMMA) ^ word));
+
+        Seed::from_u32_array([mix(state, 0), mix(state, 1), mix(state, 2), mix(state, 3)])
+    }
+
     pub fn from_u32_array(x: [u32; 4]) -> Seed {
         Seed([
-             (x[0] >>  0) as u8,
+             x[0] as u8,
              (x[0] >>  8) as u8,
              (x[0] >> 16) as u8,
              (x[0] >> 24) as u8,
-             (x[1] >>  0) as u8,
+             x[1] as u8,
              (x[1] >>  8) as u8,
              (x[1] >> 16) as u8,
              (x[1] >> 24) as u8,
-             (x[2] >>  0) as u8,
+             x[2] as u8,
              (x[2] >>  8) as u8,
              (x[2] >> 16) as u8,
              (x[2] >> 24) as u8,
-             (x[3] >>  0) as u8,
+             x[3] as u8,
              (x[3] >>  8) as u8,
              (x[3] >> 16) as u8,
              (x[3] >> 24) as u8,
         ])
     }
 
+    /// Returns the 128-bit seed
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
     pub fn to_rng(&self) -> CrateRng {
         CrateRng::from_seed(self.0)
     }
+
+    /// Returns a counter-based RNG
+    ///
+    /// The n-th 32-bit word drawn (counting from 1) is the upper half of the SplitMix64 finalizer
+    /// applied to `key + n * 0x9e37_79b9_7f4a_7c15` where `key` is the low 64 bits of the seed
+    /// XOR the high 64 bits rotated by 32 (both little endian).  Each draw is therefore
+    /// reproducible from the seed and the draw index alone.  Not suitable for cryptography.
+    pub fn to_counter_rng(&self) -> CrateRng {
+        let mut words = [0u64; 2];
+        for (i, byte) in self.0.iter().enumerate() {
+            words[i / 8] |= u64::from(*byte) << (8 * (i % 8));
+        }
+
+        CrateRng {
+            source: Source::Counter { key: words[0] ^ words[1].rotate_left(32), counter: 0 },
+            draws: None,
+        }
+    }
 }
 
 impl Default for Seed {
@@ -2,36 +2,58 @@ use super::enumeration::Enum;
 
 use std::collections::HashMap;
 
+#[derive(Clone)]
 pub enum Symbol {
     Variable(usize),
-    Enum(Enum),
+    Enum(#[allow(dead_code)] Enum),
     EnumMember(u32),
+    Constant(u32),
 }
 
+/// Symbol table
+///
+/// Keeps every definition of a name along with the generation (i.e. insertion count) it was
+/// defined at so that lazily built variables see the symbols as they were when the variable was
+/// defined.
+#[derive(Clone)]
 pub struct Symbols {
-    symbols: HashMap<String, Symbol>,
+    symbols: HashMap<String, Vec<(usize, Symbol)>>,
+    generation: usize,
 }
 
 impl Symbols {
     pub fn new() -> Symbols {
         Symbols {
             symbols: HashMap::new(),
+            generation: 0,
         }
     }
 
+    fn insert(&mut self, name: String, symbol: Symbol) {
+        self.symbols
+            .entry(name)
+            .or_default()
+            .push((self.generation, symbol));
+        self.generation += 1;
+    }
+
     pub fn insert_enum(&mut self, name: &str, enumeration: Enum) {
-        self.symbols.insert(name.into(), Symbol::Enum(enumeration));
+        self.insert(name.into(), Symbol::Enum(enumeration));
     }
 
     pub fn insert_enum_member<S>(&mut self, name: S, value: u32)
     where
         S: Into<String>,
     {
-        self.symbols.insert(name.into(), Symbol::EnumMember(value));
+        self.insert(name.into(), Symbol::EnumMember(value));
+    }
+
+    pub fn insert_constant(&mut self, name: &str, value: u32) {
+        self.insert(name.into(), Symbol::Constant(value));
     }
 
     pub fn insert_variable(&mut self, name: &str, index: usize) {
-        self.symbols.insert(name.into(), Symbol::Variable(index));
+        self.insert(name.into(), Symbol::Variable(index));
     }
 
     pub fn contains(&self, name: &str) -> bool {
@@ -39,6 +61,21 @@ impl Symbols {
     }
 
     pub fn get(&self, name: &str) -> Option<&Symbol> {
-        self.symbols.get(name)
+        self.symbols.get(name)?.last().map(|(_, symbol)| symbol)
+    }
+
+    /// Returns the number of symbols inserted so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns the most recent definition of `name` inserted before `generation`
+    pub fn get_before(&self, name: &str, generation: usize) -> Option<&Symbol> {
+        self.symbols
+            .get(name)?
+            .iter()
+            .rev()
+            .find(|(symbol_generation, _)| *symbol_generation < generation)
+            .map(|(_, symbol)| symbol)
     }
 }
@@ -2,20 +2,83 @@ use super::rand::{CrateRng, Seed};
 use super::enumeration::Enum;
 use super::symbols::{Symbol, Symbols};
 
-use crate::model::{Expr, Model, Variable, VariableRef};
-use crate::types::{Binary, Done, Next, Once, Pattern, Prev, Range, Sequence, Unary, Value,
+use crate::model::{union_bounds, union_values, DeferredVariable, Expr, Model, Variable, VariableRef, MAX_PAIR_RHO};
+use crate::types::{checked, Accumulate, Hash, AddCarry, Binary, BinaryDoneMode, Binding, BitReverse, ByteSwap, Cdf, PopCount, Complement, MAX_COMPLEMENT_SIZE, Decode, Done, DoneFlag, Except, ExhaustedMode, GrayCode, In, Let, Mean, MulMod, Next, NoRepeat, OneHot, Once, Pattern, Prev, Range, Repeat, HoldFor, Sequence, Shuffle, Sum, Unary, WindowMax, WindowMin, Value, Zipf, MAX_ZIPF_N, Memo, DontCare, AvoidRecent, Timestamp, FlatOperation, Reduce, PatternFile, TruncNormal, MAX_TRUNC_NORMAL_STDDEVS, Poisson, Walk, ModCounter, Ramp,
             WeightedWithReplacement, WeightedWithoutReplacement};
-use crate::error::{TransformError, TransformResult};
+use crate::error::{TransformError, TransformResult, TransformWarning};
 
-use rvs_parser::ast;
+use rvs_parser::{ast, SearchPath};
 
 use indexmap::IndexMap;
+use log::{debug, warn};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
 use std::rc::Rc;
+use std::cell::Cell;
 use std::cell::RefCell;
 
+/// Default maximum number of times an expression re-samples a sub-expression
+pub const DEFAULT_MAX_RESAMPLE_ATTEMPTS: u32 = 10_000;
+
 pub struct Transform {
     seed: Seed,
     symbols: Symbols,
+    max_resample_attempts: u32,
+    /// Limit on the number of values expanded from a single expression.  See
+    /// `set_max_children`.
+    max_children: usize,
+    binary_done_mode: BinaryDoneMode,
+    exhausted_mode: ExhaustedMode,
+    output_mask: u32,
+    count_rng_draws: bool,
+    independent_selection: bool,
+    warnings: RefCell<Vec<TransformWarning>>,
+    /// Stack of `let` bindings in scope.  Innermost last.
+    bindings: RefCell<Vec<(String, Rc<Cell<u32>>)>>,
+    /// Stack of the names of the enclosing `group`s.  Innermost last.
+    groups: RefCell<Vec<String>>,
+    lazy: bool,
+    /// Variables deferred by the current call to `transform`
+    deferred: Vec<(usize, Deferred)>,
+    /// Limits symbol lookup to the symbols defined before a lazily built variable
+    visible_generation: Cell<Option<usize>>,
+    memoize: bool,
+    /// True while transforming a memoized sub-expression.  Prevents nested memoization.
+    memoizing: Cell<bool>,
+    /// True while transforming the expression of a `@signed` variable
+    signed_comparisons: Cell<bool>,
+    /// True while transforming the step of a `Walk`.  Range bounds are ordered as `i32`.
+    signed_ranges: Cell<bool>,
+    flatten_operations: bool,
+    reduce_operations: bool,
+    counter_rng: bool,
+    sticky_done: bool,
+    /// Search path for `PatternFile`
+    search_path: SearchPath,
+}
+
+/// The parts of a variable definition needed to build it later.  See `Transform::set_lazy`.
+struct Deferred {
+    expr: ast::Node,
+    attributes: Vec<ast::Attribute>,
+    span: ast::Span,
+    groups: Vec<String>,
+    generation: usize,
+}
+
+/// A deferred variable along with the transform that builds it
+struct LazyVariable {
+    transform: Rc<Transform>,
+    deferred: Deferred,
+}
+
+impl DeferredVariable for LazyVariable {
+    fn build(&self, model: &Model) -> TransformResult<Variable> {
+        self.transform.build_deferred(model, &self.deferred)
+    }
 }
 
 impl Transform {
@@ -23,6 +86,235 @@ impl Transform {
         Transform {
             seed,
             symbols: Symbols::new(),
+            max_resample_attempts: DEFAULT_MAX_RESAMPLE_ATTEMPTS,
+            max_children: usize::MAX,
+            binary_done_mode: Default::default(),
+            exhausted_mode: Default::default(),
+            output_mask: u32::MAX,
+            count_rng_draws: false,
+            independent_selection: false,
+            warnings: RefCell::new(Vec::new()),
+            bindings: RefCell::new(Vec::new()),
+            groups: RefCell::new(Vec::new()),
+            lazy: false,
+            deferred: Vec::new(),
+            visible_generation: Cell::new(None),
+            memoize: true,
+            memoizing: Cell::new(false),
+            signed_comparisons: Cell::new(false),
+            signed_ranges: Cell::new(false),
+            flatten_operations: false,
+            reduce_operations: false,
+            counter_rng: false,
+            sticky_done: false,
+            search_path: Default::default(),
+        }
+    }
+
+    /// Returns a copy of the settings and symbols without any transform state
+    fn snapshot(&self) -> Transform {
+        Transform {
+            seed: self.seed.clone(),
+            symbols: self.symbols.clone(),
+            max_resample_attempts: self.max_resample_attempts,
+            max_children: self.max_children,
+            binary_done_mode: self.binary_done_mode,
+            exhausted_mode: self.exhausted_mode,
+            output_mask: self.output_mask,
+            count_rng_draws: self.count_rng_draws,
+            independent_selection: self.independent_selection,
+            warnings: RefCell::new(Vec::new()),
+            bindings: RefCell::new(Vec::new()),
+            groups: RefCell::new(Vec::new()),
+            lazy: false,
+            deferred: Vec::new(),
+            visible_generation: Cell::new(None),
+            memoize: self.memoize,
+            memoizing: Cell::new(false),
+            signed_comparisons: Cell::new(false),
+            signed_ranges: Cell::new(false),
+            flatten_operations: self.flatten_operations,
+            reduce_operations: self.reduce_operations,
+            counter_rng: self.counter_rng,
+            sticky_done: self.sticky_done,
+            search_path: self.search_path.clone(),
+        }
+    }
+
+    /// Sets the maximum number of times an expression re-samples a sub-expression
+    ///
+    /// Applies to all re-sampling expressions (e.g. `except`) transformed afterwards.  An
+    /// expression that exceeds the maximum during evaluation stops re-sampling and returns a
+    /// fallback value.  The failure is reported by `Variable::next_checked` and logged otherwise.
+    ///
+    /// # Panics
+    ///
+    /// * If `max_resample_attempts` is 0
+    pub fn set_max_resample_attempts(&mut self, max_resample_attempts: u32) {
+        assert!(max_resample_attempts > 0, "the maximum number of resample attempts must not be 0");
+
+        self.max_resample_attempts = max_resample_attempts;
+    }
+
+    /// Sets how binary operations combine the done of their operands
+    ///
+    /// Applies to all binary operations transformed afterwards.  Defaults to
+    /// `BinaryDoneMode::Latched`.
+    pub fn set_binary_done_mode(&mut self, binary_done_mode: BinaryDoneMode) {
+        self.binary_done_mode = binary_done_mode;
+    }
+
+    /// Sets what `Once` returns after it is exhausted
+    ///
+    /// Applies to all `Once` expressions transformed afterwards.  Other expressions are not
+    /// finite and are not affected.  Defaults to `ExhaustedMode::Hold`.
+    pub fn set_exhausted_mode(&mut self, exhausted_mode: ExhaustedMode) {
+        self.exhausted_mode = exhausted_mode;
+    }
+
+    /// Sets a mask applied to the values of all variables
+    ///
+    /// Applies to all variables transformed afterwards.  E.g. a mask of `0xfff` limits all values
+    /// to 12 bits.  Defaults to `u32::MAX` (no masking).  See `Variable::set_output_mask`.
+    pub fn set_output_mask(&mut self, output_mask: u32) {
+        self.output_mask = output_mask;
+    }
+
+    /// Enables or disables counting of raw RNG draws
+    ///
+    /// Applies to all variables transformed afterwards.  Disabled by default.  See
+    /// `Variable::rng_draws`.
+    pub fn set_rng_draw_counting(&mut self, enabled: bool) {
+        self.count_rng_draws = enabled;
+    }
+
+    /// Enables or disables selection streams independent of sub-expression streams for samples
+    ///
+    /// When enabled, each sample (e.g. `r{...}` and `{...}`) selects its sub-expressions with an
+    /// RNG of its own derived before its sub-expressions are transformed.  Changing the
+    /// randomness of a sub-expression then does not change which sub-expressions are selected.
+    /// Applies to all samples transformed afterwards.  Disabled by default.
+    pub fn set_independent_selection(&mut self, enabled: bool) {
+        self.independent_selection = enabled;
+    }
+
+    /// Enables or disables lazy building of variables
+    ///
+    /// When enabled, `transform` defers building the expression of each variable until the
+    /// variable is first accessed via `Model::get`, `Model::build`, or a reference from another
+    /// variable being built.  Variables never accessed are never built so errors in their
+    /// expressions are never reported.  Variables are built with the symbols defined before them
+    /// so the values are the same as without lazy building.  Constants and enums are not deferred.
+    ///
+    /// Accessors of a single variable (e.g. `Model::get_variable_by_name`) and
+    /// `Model::iter_values` build on access.  Deferred variables are placeholders until built.
+    /// Use `Model::build_all` before operating on all variables of a model (e.g.
+    /// `Model::start_recording`).  Warnings of lazily built variables are logged but not returned
+    /// by `warnings`.  Disabled by default.
+    pub fn set_lazy(&mut self, enabled: bool) {
+        self.lazy = enabled;
+    }
+
+    /// Enables or disables memoization of pure sub-expressions
+    ///
+    /// A pure sub-expression (e.g. `(1 << 4) * 3`) uses no RNG, references no variables, and has
+    /// no state.  When enabled, each pure sub-expression is evaluated once and its value is cached.
+    /// Values are the same either way.  Applies to all expressions transformed afterwards.  Enabled
+    /// by default.
+    pub fn set_memoize(&mut self, enabled: bool) {
+        self.memoize = enabled;
+    }
+
+    /// Enables or disables flattening of trees of unary and binary operations
+    ///
+    /// When enabled, each tree of operations (e.g. `(a + 1) * (b - 2)`) is transformed into a
+    /// single `FlatOperation` evaluated iteratively instead of an `Expr` per operation.  Values,
+    /// done, and RNG draws are the same either way.  Applies to all expressions transformed
+    /// afterwards.  Disabled by default.
+    pub fn set_flatten_operations(&mut self, enabled: bool) {
+        self.flatten_operations = enabled;
+    }
+
+    /// Enables or disables collapsing chains of the same associative operation
+    ///
+    /// When enabled, each chain of `&`, `|`, `^`, `+`, or `*` (e.g. `a & b & c & d`) is
+    /// transformed into a single `Reduce` on all operands instead of an `Expr` per operation.
+    /// Values, done, and RNG draws are the same either way but the display lists the operands
+    /// without nesting (e.g. `(a & b & c & d)`).  Applies to all expressions transformed
+    /// afterwards.  Takes precedence over `set_flatten_operations` for chains.  Disabled by default.
+    pub fn set_reduce_operations(&mut self, enabled: bool) {
+        self.reduce_operations = enabled;
+    }
+
+    /// Enables or disables counter-based RNGs
+    ///
+    /// When enabled, each variable uses an RNG whose n-th draw is a pure function of the seed and
+    /// n instead of a PCG stream.  Each variable has its own RNG in either mode so draws are
+    /// reproducible across evaluation orders either way.  Counter mode additionally allows any
+    /// draw to be computed from the seed and its index alone without making the preceding draws
+    /// (e.g. by another tool reproducing a single value).  See `Seed::to_counter_rng` for the
+    /// mixing function.  Applies to all variables transformed afterwards.  Disabled by default.
+    pub fn set_counter_rng(&mut self, enabled: bool) {
+        self.counter_rng = enabled;
+    }
+
+    /// Enables or disables sticky done
+    ///
+    /// When enabled, `done` of each variable remains true from the end of its first cycle until
+    /// it is reset.  Applies to all variables transformed afterwards.  Disabled by default.  See
+    /// `Variable::set_sticky_done`.
+    pub fn set_sticky_done(&mut self, enabled: bool) {
+        self.sticky_done = enabled;
+    }
+
+    /// Returns a new RNG for a variable or constant
+    fn new_rng(&self) -> CrateRng {
+        if self.counter_rng {
+            self.seed.to_counter_rng()
+        } else {
+            self.seed.to_rng()
+        }
+    }
+
+    /// Sets the maximum number of values expanded from a single expression
+    ///
+    /// Applies to the entries of a weighted sample including those added by `Expand()`, the pool
+    /// of a sample without replacement (i.e. the sum of its weights), and the values of a
+    /// `PatternFile`.  Exceeding the maximum is a transform error.  Defaults to unlimited.  See
+    /// `Parser::set_max_children` for the limit on the parsed sources.
+    pub fn set_max_children(&mut self, max_children: usize) {
+        self.max_children = max_children;
+    }
+
+    /// Sets the search path used to find the files of `PatternFile`
+    ///
+    /// Applies to all expressions transformed afterwards.  Defaults to an empty search path (i.e.
+    /// only absolute paths are found).
+    pub fn set_search_path(&mut self, search_path: SearchPath) {
+        self.search_path = search_path;
+    }
+
+    /// Returns the warnings found by all calls to `transform`
+    ///
+    /// Warnings identify likely mistakes that do not prevent the transform from succeeding.
+    /// E.g. a range with literal bounds in reverse order.  Warnings are also collected by the
+    /// model.  See `Model::warnings`.
+    pub fn warnings(&self) -> Vec<TransformWarning> {
+        self.warnings.borrow().clone()
+    }
+
+    fn warn(&self, description: String) {
+        self.warnings.borrow_mut().push(TransformWarning::new(description));
+    }
+
+    /// Attaches a source location to all warnings found since the `start`th warning
+    fn warnings_at(&self, start: usize, span: ast::Span) {
+        for warning in self.warnings.borrow_mut()[start..].iter_mut() {
+            if warning.span.is_none() {
+                warning.span = Some(span);
+            }
+
+            warn!("{}", warning);
         }
     }
 
@@ -30,16 +322,101 @@ impl Transform {
         &mut self,
         model: &mut Model,
         nodes: &[Box<ast::Node>],
+    ) -> TransformResult<()> {
+        let warnings_start = self.warnings.borrow().len();
+        let result = self.transform_items(model, nodes);
+        model.add_warnings(&self.warnings.borrow()[warnings_start..]);
+
+        if !self.deferred.is_empty() {
+            let transform = Rc::new(self.snapshot());
+
+            for (index, deferred) in self.deferred.drain(..) {
+                let transform = Rc::clone(&transform);
+                model.defer(index, Rc::new(LazyVariable { transform, deferred }));
+            }
+        }
+
+        result?;
+
+        model.set_transformed();
+
+        Ok(())
+    }
+
+    fn transform_items(
+        &mut self,
+        model: &mut Model,
+        nodes: &[Box<ast::Node>],
     ) -> TransformResult<()> {
         for node in nodes {
+            let warnings_start = self.warnings.borrow().len();
+
             match **node {
-                ast::Node::Variable(ref name, ref expr) => {
-                    let variable = self.transform_variable(model, expr)?;
+                ast::Node::Variable(ref name, ref expr, span, ref attributes) if self.lazy => {
+                    let generation = self.symbols.generation();
+                    let placeholder = Variable::new(Box::new(Value::new(0)), self.new_rng());
+                    let name = &self.qualify(name);
+                    let variable_index =
+                        model.add_variable(name, Rc::new(RefCell::new(Box::new(placeholder))));
+                    self.symbols.insert_variable(name, variable_index);
+                    self.deferred.push((
+                        variable_index,
+                        Deferred {
+                            expr: (**expr).clone(),
+                            attributes: attributes.clone(),
+                            span,
+                            groups: self.groups.borrow().clone(),
+                            generation,
+                        },
+                    ));
+                    debug!("deferred variable '{}'", name);
+                }
+                ast::Node::Variable(ref name, ref expr, span, ref attributes) => {
+                    let variable = self
+                        .transform_variable(model, expr, attributes)
+                        .map_err(|e| e.at(span))?;
+                    let name = &self.qualify(name);
                     let variable_index = model.add_variable(name, variable);
                     self.symbols.insert_variable(name, variable_index);
+                    debug!("defined variable '{}'", name);
+                    self.warnings_at(warnings_start, span);
+                }
+                ast::Node::Constant(ref name, ref expr, span) => {
+                    let value = self.transform_constant(model, expr).map_err(|e| e.at(span))?;
+                    let mut variable = Variable::new(Box::new(Value::new(value)), self.new_rng());
+                    variable.set_constant(true);
+                    variable.set_output_mask(self.output_mask);
+
+                    let variable = Rc::new(RefCell::new(Box::new(variable)));
+                    let name = &self.qualify(name);
+                    model.add_variable(name, variable);
+                    self.symbols.insert_constant(name, value);
+                    debug!("defined constant '{}' = {}", name, value);
+                    self.warnings_at(warnings_start, span);
                 }
-                ast::Node::Enum(ref name, ref items) => {
-                    self.transform_enum(name, items)?;
+                ast::Node::Enum(ref name, ref items, span) => {
+                    let name = &self.qualify(name);
+                    self.transform_enum(model, name, items).map_err(|e| e.at(span))?;
+                    debug!("defined enum '{}'", name);
+                    self.warnings_at(warnings_start, span);
+                }
+                ast::Node::Distinct(ref names, span) => {
+                    self.transform_distinct(model, names).map_err(|e| e.at(span))?;
+                    debug!("defined distinct({})", names.join(", "));
+                }
+                ast::Node::Pair(ref a, ref b, ref rho, span) => {
+                    self.transform_pair(model, a, b, rho).map_err(|e| e.at(span))?;
+                    debug!("defined pair({}, {})", a, b);
+                }
+                ast::Node::Gate(ref a, ref b, span) => {
+                    self.transform_gate(model, a, b).map_err(|e| e.at(span))?;
+                    debug!("defined gate {} by {}", a, b);
+                }
+                ast::Node::Group(ref name, ref nodes, _) => {
+                    self.groups.borrow_mut().push(name.clone());
+                    let result = self.transform_items(model, nodes);
+                    self.groups.borrow_mut().pop();
+                    result?;
                 }
                 _ => {
                     return Err(TransformError::new(format!(
@@ -53,15 +430,249 @@ impl Transform {
         Ok(())
     }
 
-    fn transform_variable(&self, model: &Model, expr: &ast::Node) -> TransformResult<VariableRef> {
-        let mut rng = self.seed.to_rng();
-        let expr = self.transform_expr(model, &mut rng, expr)?;
-        let variable = Rc::new(RefCell::new(Box::new(Variable::new(expr, rng))));
+    /// Builds a variable deferred by a lazy transform
+    ///
+    /// The variable is built in the scope it was defined in.
+    fn build_deferred(&self, model: &Model, deferred: &Deferred) -> TransformResult<Variable> {
+        let groups = self.groups.replace(deferred.groups.clone());
+        let generation = self.visible_generation.replace(Some(deferred.generation));
+        let bindings = self.bindings.replace(Vec::new());
+        let warnings_start = self.warnings.borrow().len();
+
+        let result = self
+            .build_variable(model, &deferred.expr, &deferred.attributes)
+            .map_err(|e| e.at(deferred.span));
+
+        // Logged here since this transform's warnings are not returned by `warnings`
+        self.warnings_at(warnings_start, deferred.span);
+        self.warnings.borrow_mut().truncate(warnings_start);
+
+        self.groups.replace(groups);
+        self.visible_generation.set(generation);
+        self.bindings.replace(bindings);
+
+        debug!("built deferred variable at {}", deferred.span);
+
+        result
+    }
+
+    fn transform_distinct(&self, model: &mut Model, names: &[String]) -> TransformResult<()> {
+        let mut qualified_names = Vec::with_capacity(names.len());
+
+        for name in names {
+            match self.resolve(name) {
+                Some((qualified_name, Symbol::Variable(_))) => qualified_names.push(qualified_name),
+                _ => {
+                    return Err(TransformError::new(format!(
+                        "distinct references unknown variable '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+
+        model.add_distinct(qualified_names, self.max_resample_attempts);
+
+        Ok(())
+    }
+
+    fn transform_pair(
+        &self,
+        model: &mut Model,
+        a: &str,
+        b: &str,
+        rho: &ast::Node,
+    ) -> TransformResult<()> {
+        let mut qualified_names = Vec::with_capacity(2);
+
+        for name in &[a, b] {
+            match self.resolve(name) {
+                Some((qualified_name, Symbol::Variable(_))) => qualified_names.push(qualified_name),
+                _ => {
+                    return Err(TransformError::new(format!(
+                        "pair references unknown variable '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+
+        let rho = self.transform_constant(model, rho)?;
+        if rho > MAX_PAIR_RHO {
+            return Err(TransformError::new(format!(
+                "pair rho must be in the range [0, {}] but found {}",
+                MAX_PAIR_RHO, rho
+            )));
+        }
+
+        let b = qualified_names.pop().unwrap();
+        let a = qualified_names.pop().unwrap();
+        // Derived so that the shared values don't follow the values of `a` which starts from the
+        // same seed
+        model.add_pair(a, b, rho, self.new_rng().derive());
+
+        Ok(())
+    }
+
+    fn transform_gate(&self, model: &mut Model, a: &str, b: &str) -> TransformResult<()> {
+        let mut qualified_names = Vec::with_capacity(2);
+
+        for name in &[a, b] {
+            match self.resolve(name) {
+                Some((qualified_name, Symbol::Variable(_))) => qualified_names.push(qualified_name),
+                _ => {
+                    return Err(TransformError::new(format!(
+                        "gate references unknown variable '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+
+        let b = qualified_names.pop().unwrap();
+        let a = qualified_names.pop().unwrap();
+
+        if let Some(gate) = model.gate_of(&a) {
+            return Err(TransformError::new(format!(
+                "variable '{}' is already gated by '{}'",
+                a, gate
+            )));
+        }
+
+        // Gates are evaluated before the variables they gate so they must not form a cycle
+        let mut gate = Some(b.as_str());
+        while let Some(name) = gate {
+            if name == a {
+                return Err(TransformError::new(format!(
+                    "gating '{}' by '{}' forms a cycle",
+                    a, b
+                )));
+            }
+            gate = model.gate_of(name);
+        }
+
+        model.add_gate(a, b);
+
+        Ok(())
+    }
+
+    /// Prefixes `name` with the names of the enclosing `group`s E.g. `net.a`
+    fn qualify(&self, name: &str) -> String {
+        self.qualify_at(self.groups.borrow().len(), name)
+    }
+
+    fn qualify_at(&self, depth: usize, name: &str) -> String {
+        if depth == 0 {
+            name.into()
+        } else {
+            format!("{}.{}", self.groups.borrow()[..depth].join("."), name)
+        }
+    }
+
+    /// Looks up a symbol from the innermost enclosing `group` outwards
+    ///
+    /// Returns the fully qualified name along with the symbol.  Allows references within a group
+    /// to use short names.
+    fn resolve(&self, name: &str) -> Option<(String, &Symbol)> {
+        let depth = self.groups.borrow().len();
+
+        (0..=depth).rev().find_map(|depth| {
+            let qualified_name = self.qualify_at(depth, name);
+            let symbol = match self.visible_generation.get() {
+                Some(generation) => self.symbols.get_before(&qualified_name, generation)?,
+                None => self.symbols.get(&qualified_name)?,
+            };
+
+            Some((qualified_name, symbol))
+        })
+    }
+
+    fn transform_variable(
+        &self,
+        model: &Model,
+        expr: &ast::Node,
+        attributes: &[ast::Attribute],
+    ) -> TransformResult<VariableRef> {
+        let variable = self.build_variable(model, expr, attributes)?;
+
+        Ok(Rc::new(RefCell::new(Box::new(variable))))
+    }
+
+    fn build_variable(
+        &self,
+        model: &Model,
+        expr: &ast::Node,
+        attributes: &[ast::Attribute],
+    ) -> TransformResult<Variable> {
+        let signed = attributes.iter().any(|attribute| attribute.key == "signed");
+
+        let mut rng = self.new_rng();
+        let outer = self.signed_comparisons.replace(signed);
+        let expr = self.transform_expr(model, &mut rng, expr);
+        self.signed_comparisons.set(outer);
+        let mut variable = Variable::new(expr?, rng);
+        variable.set_output_mask(self.output_mask);
+        variable.set_rng_draw_counting(self.count_rng_draws);
+        variable.set_signed(signed);
+        variable.set_sticky_done(self.sticky_done);
+
+        for attribute in attributes {
+            if variable.attribute(&attribute.key).is_some() {
+                return Err(TransformError::new(format!(
+                    "Duplicate attribute '{}'",
+                    attribute.key
+                )));
+            }
+
+            variable.set_attribute(&attribute.key, &attribute.value);
+        }
 
         Ok(variable)
     }
 
-    fn transform_enum(&mut self, name: &str, items: &[Box<ast::Node>]) -> TransformResult<()> {
+    /// Evaluates the expression of a `const` once
+    ///
+    /// The expression is evaluated with its own RNG so other variables are not affected.
+    /// Variables can't be referenced because evaluating them would advance them.
+    fn transform_constant(&self, model: &Model, expr: &ast::Node) -> TransformResult<u32> {
+        if let Some(name) = self.find_variable_reference(expr) {
+            return Err(TransformError::new(format!(
+                "const expressions can't reference variable '{}'",
+                name
+            )));
+        }
+
+        let mut rng = self.new_rng();
+        let mut expr = self.transform_expr(model, &mut rng, expr)?;
+
+        evaluate(&mut expr, &mut rng)
+    }
+
+    /// Returns true if `node` is a literal or a reference to a constant or enum member
+    fn is_constant(&self, node: &ast::Node) -> bool {
+        match *node {
+            ast::Node::Number(_) => true,
+            ast::Node::RIdentifier(ref name, ..) => matches!(
+                self.resolve(name),
+                Some((_, Symbol::Constant(_))) | Some((_, Symbol::EnumMember(_)))
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns the name of the first variable referenced by `node`, if any
+    fn find_variable_reference<'a>(&self, node: &'a ast::Node) -> Option<&'a str> {
+        find_reference(node, &|name| {
+            matches!(self.resolve(name), Some((_, Symbol::Variable(_))))
+        })
+    }
+
+    fn transform_enum(
+        &mut self,
+        model: &mut Model,
+        name: &str,
+        items: &[Box<ast::Node>],
+    ) -> TransformResult<()> {
         if self.symbols.contains(name) {
             return Err(TransformError::new(format!(
                 "Symbol '{}' already exists",
@@ -101,6 +712,7 @@ impl Transform {
                 )));
             }
         }
+        model.add_enum(name, enum_members_map.clone());
         self.symbols.insert_enum(name, Enum::new(enum_members_map));
 
         Ok(())
@@ -111,47 +723,106 @@ impl Transform {
         model: &Model,
         rng: &mut CrateRng,
         node: &ast::Node,
+    ) -> TransformResult<Box<dyn Expr>> {
+        let memoize = self.memoize
+            && !self.memoizing.get()
+            && !matches!(*node, ast::Node::Number(_) | ast::Node::RIdentifier(..))
+            && self.is_pure(node);
+        if !memoize {
+            return self.transform_node(model, rng, node);
+        }
+
+        self.memoizing.set(true);
+        let expr = self.transform_node(model, rng, node);
+        self.memoizing.set(false);
+
+        Ok(Box::new(Memo::new(expr?)))
+    }
+
+    /// Returns true if `node` uses no RNG, references no variables, and has no state
+    ///
+    /// Conservative.  Only literals, constants, operations, and stateless functions of pure
+    /// arguments are pure.
+    fn is_pure(&self, node: &ast::Node) -> bool {
+        match *node {
+            ast::Node::Number(_) => true,
+            ast::Node::RIdentifier(ref name, ..) => {
+                self.find_binding(name).is_none() && self.is_constant(node)
+            }
+            ast::Node::UnaryOperation(_, ref a) => self.is_pure(a),
+            ast::Node::BinaryOperation(ref a, _, ref b) => self.is_pure(a) && self.is_pure(b),
+            ast::Node::Type(ast::Type::MulMod, ref args)
+            | ast::Node::Type(ast::Type::ByteSwap, ref args)
+            | ast::Node::Type(ast::Type::BitReverse, ref args)
+            | ast::Node::Type(ast::Type::PopCount, ref args)
+            | ast::Node::Type(ast::Type::OneHot, ref args)
+            | ast::Node::Type(ast::Type::Decode, ref args) => {
+                args.iter().all(|arg| self.is_pure(arg))
+            }
+            _ => false,
+        }
+    }
+
+    fn transform_node(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
     ) -> TransformResult<Box<dyn Expr>> {
         match *node {
             ast::Node::Type(ref typ, ref args) => self.transform_type(model, rng, typ, args),
             ast::Node::Weighted(ref replacement, ref args) =>
                 self.transform_weighted(model, rng, replacement, args),
             ast::Node::Number(x) => Ok(Box::new(Value::new(x))),
+            ast::Node::DontCare(value, mask) => Ok(Box::new(DontCare::new(value, mask))),
+            ast::Node::BinaryOperation(ref bx, ref op, ref by)
+                if self.reduce_operations && Reduce::is_reducible(op) =>
+            {
+                let mut operands = Vec::new();
+                self.collect_reduce_operands(model, rng, bx, op, &mut operands)?;
+                self.collect_reduce_operands(model, rng, by, op, &mut operands)?;
+
+                Ok(Box::new(Reduce::new(op.clone(), operands).with_done_mode(self.binary_done_mode)))
+            }
+            ast::Node::UnaryOperation(..) | ast::Node::BinaryOperation(..)
+                if self.flatten_operations =>
+            {
+                let mut flat = FlatOperation::new(self.binary_done_mode)
+                    .with_signed_comparisons(self.signed_comparisons.get());
+                self.flatten(model, rng, node, &mut flat)?;
+
+                Ok(Box::new(flat))
+            }
             ast::Node::UnaryOperation(ref op, ref a) => Ok(Box::new(Unary::new(
                 op.clone(),
-                self.transform_expr(model, rng, a)?,
+                check_operand(self.transform_expr(model, rng, a)?, op)?,
             ))),
-            ast::Node::BinaryOperation(ref bx, ref op, ref by) => Ok(Box::new(Binary::new(
-                self.transform_expr(model, rng, bx)?,
-                op.clone(),
-                self.transform_expr(model, rng, by)?,
-            ))),
-            ast::Node::RIdentifier(ref name, ref method) => {
-                match self.symbols.get(name) {
-                    Some(symbol) => {
-                        match *symbol {
-                            Symbol::EnumMember(ref value) => {
-                                Ok(Box::new(Value::new(*value)))
-                            }
-                            Symbol::Variable(ref index) => {
-                                self.transform_r_variable(model, name, *index, method)
-                            }
-                            Symbol::Enum(_) => {
-                                Err(TransformError::new(format!(
-                                    "Expected a Variable or EnumMember identifier but found Enum identifer '{}'",
-                                    name
-                                )))
-                            }
-                        }
-                    }
-                    None => {
-                        Err(TransformError::new(format!(
-                            "Could not find symbol '{}'",
-                            name
-                        )))
-                    }
-                }
+            ast::Node::BinaryOperation(ref bx, ref op, ref by) => {
+                check_divisor(op, by)?;
+
+                Ok(Box::new(Binary::new(
+                    check_operand(self.transform_expr(model, rng, bx)?, op)?,
+                    op.clone(),
+                    check_operand(self.transform_expr(model, rng, by)?, op)?,
+                )
+                .with_done_mode(self.binary_done_mode)
+                .with_signed_comparisons(self.signed_comparisons.get())))
             }
+            ast::Node::Except(ref expr, ref excluded) =>
+                self.transform_except(model, rng, expr, excluded),
+            ast::Node::In(ref expr, ref members) =>
+                self.transform_in(model, rng, expr, members),
+            ast::Node::Complement(ref excluded, ref l, ref r) =>
+                self.transform_complement(model, rng, excluded, l, r),
+            ast::Node::Let(ref name, ref binding, ref body) =>
+                self.transform_let(model, rng, name, binding, body),
+            ast::Node::PatternFile(ref path) => self.transform_pattern_file(path),
+            ast::Node::RIdentifier(ref name, ref method, span) => self
+                .transform_identifier(model, name, std::slice::from_ref(method))
+                .map_err(|e| e.at(span)),
+            ast::Node::MethodChain(ref name, ref methods, span) => self
+                .transform_identifier(model, name, methods)
+                .map_err(|e| e.at(span)),
             _ => Err(TransformError::new(format!(
                 "Expected (Type|Number|UnaryOperation|BinaryOperation|Identifier) but found {:?}",
                 *node
@@ -159,74 +830,370 @@ impl Transform {
         }
     }
 
-    fn transform_r_variable(
+    /// Appends a tree of operations to `flat` in postfix order
+    ///
+    /// Operands that are not operations, and operations that would be memoized, are transformed
+    /// with `transform_expr`.
+    fn flatten(
         &self,
         model: &Model,
-        variable_name: &str,
-        variable_index: usize,
-        method: &ast::VariableMethod,
-    ) -> TransformResult<Box<dyn Expr>> {
-        match model.get_variable_by_index(variable_index) {
-            Some(variable) => match *method {
-                ast::VariableMethod::Next => {
-                    Ok(Box::new(Next::new(variable_name, Rc::downgrade(variable))))
-                }
-                ast::VariableMethod::Prev => {
-                    Ok(Box::new(Prev::new(variable_name, Rc::downgrade(variable))))
-                }
-                ast::VariableMethod::Copy => Ok(variable.borrow().clone_expr()),
-            },
-            None => Err(TransformError::new(format!(
-                "Could not find variable '{}'",
-                variable_name
-            ))),
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        flat: &mut FlatOperation,
+    ) -> TransformResult<()> {
+        let memoize = self.memoize && !self.memoizing.get() && self.is_pure(node);
+
+        match *node {
+            ast::Node::UnaryOperation(ref op, ref a) if !memoize => {
+                self.flatten_operand(model, rng, a, op, flat)?;
+                flat.unary(op.clone());
+            }
+            ast::Node::BinaryOperation(ref bx, ref op, ref by) if !memoize => {
+                check_divisor(op, by)?;
+
+                self.flatten_operand(model, rng, bx, op, flat)?;
+                self.flatten_operand(model, rng, by, op, flat)?;
+                flat.binary(op.clone());
+            }
+            _ => flat.operand(self.transform_expr(model, rng, node)?),
         }
+
+        Ok(())
     }
 
-    fn transform_args(
+    /// Appends an operand of a chain of the operation `op` to `operands` in evaluation order
+    ///
+    /// Operands that are themselves the operation `op` are collapsed into the chain unless they
+    /// would be memoized.  All other operands are transformed with `transform_expr`.
+    fn collect_reduce_operands(
         &self,
         model: &Model,
         rng: &mut CrateRng,
-        args: &[Box<ast::Node>],
-    ) -> TransformResult<Vec<Box<dyn Expr>>> {
-        let mut arg_exprs: Vec<Box<dyn Expr>> = Vec::new();
-        for arg in args {
-            arg_exprs.push(self.transform_expr(model, rng, arg)?);
+        node: &ast::Node,
+        op: &ast::BinaryOpcode,
+        operands: &mut Vec<Box<dyn Expr>>,
+    ) -> TransformResult<()> {
+        let memoize = self.memoize && !self.memoizing.get() && self.is_pure(node);
+
+        match *node {
+            ast::Node::BinaryOperation(ref bx, ref node_op, ref by) if node_op == op && !memoize => {
+                self.collect_reduce_operands(model, rng, bx, op, operands)?;
+                self.collect_reduce_operands(model, rng, by, op, operands)?;
+            }
+            _ => operands.push(check_operand(self.transform_expr(model, rng, node)?, op)?),
         }
 
-        Ok(arg_exprs)
+        Ok(())
     }
 
-    fn transform_weighted(
+    /// Appends an operand of the operation `op` to `flat`
+    fn flatten_operand(
         &self,
         model: &Model,
         rng: &mut CrateRng,
-        replacement: &ast::Replacement,
-        args: &[Box<ast::Node>],
-    ) -> TransformResult<Box<dyn Expr>> {
-        let mut weights: Vec<u32> = Vec::new();
+        node: &ast::Node,
+        op: &dyn fmt::Display,
+        flat: &mut FlatOperation,
+    ) -> TransformResult<()> {
+        match *node {
+            ast::Node::UnaryOperation(..) | ast::Node::BinaryOperation(..) => {
+                self.flatten(model, rng, node, flat)
+            }
+            _ => {
+                flat.operand(check_operand(self.transform_expr(model, rng, node)?, op)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads the whitespace separated values of a `PatternFile`
+    ///
+    /// Values are decimal or `0x` prefixed hexadecimal.  The file is read a line at a time.
+    fn transform_pattern_file(&self, path: &Path) -> TransformResult<Box<dyn Expr>> {
+        let read_error = |e: io::Error| {
+            TransformError::new(format!("PatternFile() cannot read '{}': {}", path.display(), e))
+        };
+
+        let file = self.search_path.find(path).and_then(File::open).map_err(read_error)?;
+
+        let mut values = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(read_error)?;
+
+            for token in line.split_whitespace() {
+                let value = match token.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => token.parse(),
+                };
+
+                if values.len() == self.max_children {
+                    return Err(TransformError::new(format!(
+                        "PatternFile() '{}' has more than {} values which exceeds the limit",
+                        path.display(),
+                        self.max_children
+                    )));
+                }
+
+                match value {
+                    Ok(value) => values.push(value),
+                    Err(_) => {
+                        return Err(TransformError::new(format!(
+                            "PatternFile() '{}' line {}: expected an integer but found '{}'",
+                            path.display(),
+                            index + 1,
+                            token
+                        )))
+                    }
+                }
+            }
+        }
+
+        if values.is_empty() {
+            return Err(TransformError::new(format!(
+                "PatternFile() '{}' contains no values",
+                path.display()
+            )));
+        }
+
+        Ok(Box::new(PatternFile::new(path.to_path_buf(), values)))
+    }
+
+    /// Transforms an identifier with one or more methods E.g. `a`, `a.prev`, or `a.copy.next`
+    ///
+    /// Methods have no effect on constants, enum members, and `let` bindings except for `done`.
+    /// These are always done so `done` is always 1.
+    fn transform_identifier(
+        &self,
+        model: &Model,
+        name: &str,
+        methods: &[ast::VariableMethod],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let always_done = || -> TransformResult<Option<Box<dyn Expr>>> {
+            if methods.iter().any(|method| matches!(method, ast::VariableMethod::Done)) {
+                if let Some(ast::VariableMethod::Done) = reduce_method_chain(methods)? {
+                    return Ok(Some(Box::new(Value::new(1))));
+                }
+            }
+
+            Ok(None)
+        };
+
+        if let Some(value) = self.find_binding(name) {
+            if let Some(done) = always_done()? {
+                return Ok(done);
+            }
+
+            return Ok(Box::new(Binding::new(name, value)));
+        }
+
+        match self.resolve(name) {
+            Some((qualified_name, symbol)) => {
+                match *symbol {
+                    Symbol::EnumMember(ref value) | Symbol::Constant(ref value) => {
+                        match always_done()? {
+                            Some(done) => Ok(done),
+                            None => Ok(Box::new(Value::new(*value))),
+                        }
+                    }
+                    Symbol::Variable(ref index) => {
+                        match reduce_method_chain(methods)? {
+                            Some(method) => {
+                                self.transform_r_variable(model, &qualified_name, *index, &method)
+                            }
+                            None => {
+                                let chain: Vec<String> =
+                                    methods.iter().map(|method| method.to_string()).collect();
+                                self.warn(format!(
+                                    "'{}.{}' is always 0 because the copy is never advanced",
+                                    name,
+                                    chain.join(".")
+                                ));
+
+                                Ok(Box::new(Value::new(0)))
+                            }
+                        }
+                    }
+                    Symbol::Enum(_) => {
+                        Err(TransformError::new(format!(
+                            "Expected a Variable or EnumMember identifier but found Enum identifer '{}'",
+                            name
+                        )))
+                    }
+                }
+            }
+            None => {
+                Err(TransformError::new(format!(
+                    "Could not find symbol '{}'",
+                    name
+                )))
+            }
+        }
+    }
+
+    /// Returns the value of the innermost `let` binding of `name` in scope
+    fn find_binding(&self, name: &str) -> Option<Rc<Cell<u32>>> {
+        self.bindings
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(binding_name, _)| binding_name == name)
+            .map(|(_, value)| Rc::clone(value))
+    }
+
+    /// Transforms `node` and evaluates it once
+    ///
+    /// Used for arguments that are evaluated at transform time (e.g. range bounds).
+    fn eval(&self, model: &Model, rng: &mut CrateRng, node: &ast::Node) -> TransformResult<u32> {
+        let mut expr = self.transform_expr(model, rng, node)?;
+
+        evaluate(&mut expr, rng)
+    }
+
+    fn transform_let(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        name: &str,
+        binding: &ast::Node,
+        body: &ast::Node,
+    ) -> TransformResult<Box<dyn Expr>> {
+        // The binding is not in scope for its own expression
+        let binding = self.transform_expr(model, rng, binding)?;
+
+        // Sub-expressions of the body that are evaluated at transform time (e.g. range bounds) see
+        // the value of the binding instead of 0.  The value is drawn from a copy so the transform
+        // leaves the binding and the RNG as is.
+        let value = Rc::new(Cell::new(evaluate(&mut binding.clone(), &mut rng.clone())?));
+        self.bindings.borrow_mut().push((name.into(), Rc::clone(&value)));
+        let body = self.transform_expr(model, rng, body);
+        self.bindings.borrow_mut().pop();
+
+        Ok(Box::new(Let::new(name, binding, value, body?)))
+    }
+
+    fn transform_r_variable(
+        &self,
+        model: &Model,
+        variable_name: &str,
+        variable_index: usize,
+        method: &ast::VariableMethod,
+    ) -> TransformResult<Box<dyn Expr>> {
+        model.build_deferred(variable_index)?;
+
+        match model.get_variable_by_index(variable_index) {
+            Some(variable) => match *method {
+                ast::VariableMethod::Next => {
+                    Ok(Box::new(Next::new(variable_name, Rc::downgrade(variable))))
+                }
+                ast::VariableMethod::Prev => {
+                    Ok(Box::new(Prev::new(variable_name, Rc::downgrade(variable))))
+                }
+                ast::VariableMethod::Copy => Ok(variable.borrow().clone_expr()),
+                ast::VariableMethod::Done => {
+                    Ok(Box::new(DoneFlag::new(variable_name, Rc::downgrade(variable))))
+                }
+            },
+            None => Err(TransformError::new(format!(
+                "Could not find variable '{}'",
+                variable_name
+            ))),
+        }
+    }
+
+    fn transform_args(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        args: &[Box<ast::Node>],
+    ) -> TransformResult<Vec<Box<dyn Expr>>> {
+        let mut arg_exprs: Vec<Box<dyn Expr>> = Vec::new();
+        for arg in args {
+            arg_exprs.push(self.transform_expr(model, rng, arg)?);
+        }
+
+        Ok(arg_exprs)
+    }
+
+    fn transform_weighted(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        replacement: &ast::Replacement,
+        args: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        // Derived before the children are transformed so that their transform-time draws don't
+        // affect it
+        let mut selection_rng = if self.independent_selection {
+            Some(rng.derive())
+        } else {
+            None
+        };
+
+        let distinct_values = matches!(*replacement, ast::Replacement::WithoutDistinctValues);
+
+        let mut weights: Vec<u32> = Vec::new();
         let mut children: Vec<Box<dyn Expr>> = Vec::new();
+        // The value of each constant sub-expression.  Only tracked for distinct values.
+        let mut constants: Vec<Option<u32>> = Vec::new();
+        let mut percent_sum: Option<u64> = None;
+        // The index, numerator, and denominator of each probability weight
+        let mut probabilities: Vec<(usize, u32, u32)> = Vec::new();
+        let mut has_plain_weights = false;
         for arg in args {
             match **arg {
                 ast::Node::Type(ast::Type::Expand, ref args) => {
+                    has_plain_weights = true;
                     let mut expr = self.transform_expr(model, rng, &args[0])?;
 
                     if args.len() == 1 {
                         while !expr.done() {
+                            self.check_entries(children.len() + 1)?;
+                            let value = evaluate(&mut expr, rng)?;
                             weights.push(1);
-                            children.push(Box::new(Value::new(expr.next(rng))));
+                            children.push(Box::new(Value::new(value)));
+                            constants.push(Some(value));
                         }
                     } else {
                         let mut count = self.transform_expr(model, rng, &args[1])?;
-                        for _ in 0..count.next(rng) {
+                        for _ in 0..evaluate(&mut count, rng)? {
+                            self.check_entries(children.len() + 1)?;
+                            let value = evaluate(&mut expr, rng)?;
                             weights.push(1);
-                            children.push(Box::new(Value::new(expr.next(rng))));
+                            children.push(Box::new(Value::new(value)));
+                            constants.push(Some(value));
                         }
                     }
                 }
                 ast::Node::WeightedSample(ref weight, ref node) => {
+                    has_plain_weights = true;
                     weights.push(*weight);
                     children.push(self.transform_expr(model, rng, node)?);
+                    if distinct_values && self.is_constant(node) {
+                        constants.push(Some(self.transform_constant(model, node)?));
+                    } else {
+                        constants.push(None);
+                    }
+                }
+                ast::Node::PercentWeightedSample(ref weight, ref node) => {
+                    *percent_sum.get_or_insert(0) += u64::from(*weight);
+                    weights.push(*weight);
+                    children.push(self.transform_expr(model, rng, node)?);
+                    if distinct_values && self.is_constant(node) {
+                        constants.push(Some(self.transform_constant(model, node)?));
+                    } else {
+                        constants.push(None);
+                    }
+                }
+                ast::Node::ProbabilityWeightedSample(numerator, denominator, ref node) => {
+                    probabilities.push((weights.len(), numerator, denominator));
+                    // Scaled to a common denominator once all probabilities are known
+                    weights.push(0);
+                    children.push(self.transform_expr(model, rng, node)?);
+                    if distinct_values && self.is_constant(node) {
+                        constants.push(Some(self.transform_constant(model, node)?));
+                    } else {
+                        constants.push(None);
+                    }
                 }
                 _ => {
                     return Err(TransformError::new(format!(
@@ -235,18 +1202,263 @@ impl Transform {
                     )));
                 }
             }
+
+            self.check_entries(children.len())?;
+        }
+
+        if !probabilities.is_empty() {
+            if has_plain_weights || percent_sum.is_some() {
+                return Err(TransformError::new(
+                    "Probability weights cannot be mixed with other weights".into(),
+                ));
+            }
+
+            // Denominators are powers of 10 so the largest is a multiple of all others
+            let denominator = probabilities.iter().map(|&(_, _, d)| d).max().unwrap();
+            let mut sum = 0u64;
+            for &(index, n, d) in &probabilities {
+                weights[index] = n * (denominator / d);
+                sum += u64::from(weights[index]);
+            }
+
+            if sum != u64::from(denominator) {
+                let digits = denominator.ilog10() as usize;
+                return Err(TransformError::new(format!(
+                    "Probability weights must sum to 1, found {}.{:0digits$}",
+                    sum / u64::from(denominator),
+                    sum % u64::from(denominator),
+                    digits = digits
+                )));
+            }
+
+            // Reduced so that sampling without replacement has the smallest equivalent pool
+            let divisor = probabilities.iter().fold(0, |divisor, &(index, _, _)| gcd(divisor, weights[index]));
+            if divisor > 1 {
+                for &(index, _, _) in &probabilities {
+                    weights[index] /= divisor;
+                }
+            }
+        }
+
+        if let Some(percent_sum) = percent_sum {
+            if has_plain_weights {
+                return Err(TransformError::new(
+                    "Percentage weights cannot be mixed with other weights".into(),
+                ));
+            }
+
+            if percent_sum != 100 {
+                return Err(TransformError::new(format!(
+                    "Percentage weights must sum to 100%, found {}%",
+                    percent_sum
+                )));
+            }
+        }
+
+        if distinct_values {
+            // Each position is selected `weight` times per cycle so a constant sub-expression
+            // must have a weight of 1 and a value no other constant sub-expression has
+            let mut values = HashSet::new();
+            for (weight, value) in weights.iter().zip(&constants) {
+                if let Some(value) = *value {
+                    if *weight > 1 || !values.insert(value) {
+                        return Err(TransformError::new(format!(
+                            "Distinct values are impossible: the value 0x{:x} is returned more than once per cycle",
+                            value
+                        )));
+                    }
+                }
+            }
+
+            // Every position needs a value of its own.  The union of the possible values of the
+            // sub-expressions is exact.  The span of their bounds over-estimates the union.
+            let positions: u64 = weights.iter().map(|&weight| u64::from(weight)).sum();
+            let available = match (union_values(&children), union_bounds(&children)) {
+                (Some(values), _) => Some(values.len() as u64),
+                (None, Some((min, max))) => Some(u64::from(max - min) + 1),
+                (None, None) => None,
+            };
+            if let Some(available) = available {
+                if available < positions {
+                    return Err(TransformError::new(format!(
+                        "Distinct values are impossible: {} position(s) per cycle but only {} possible value(s)",
+                        positions, available
+                    )));
+                }
+            }
+        }
+
+        // Samples without replacement hold each entry `weight` times per cycle
+        if !matches!(*replacement, ast::Replacement::With) {
+            let pool: u64 = weights.iter().map(|&weight| u64::from(weight)).sum();
+            if pool > self.max_children as u64 {
+                return Err(TransformError::new(format!(
+                    "the pool of the sample has {} entries which exceeds the limit of {}",
+                    pool, self.max_children
+                )));
+            }
         }
 
         match *replacement {
-            ast::Replacement::With => Ok(Box::new(WeightedWithReplacement::new(weights, children))),
-            ast::Replacement::Without => Ok(Box::new(WeightedWithoutReplacement::new(
-                weights,
-                children,
-                rng,
-            ))),
+            ast::Replacement::With => {
+                let expr = WeightedWithReplacement::new(weights, children);
+
+                match selection_rng {
+                    Some(selection_rng) => Ok(Box::new(expr.with_selection_rng(selection_rng))),
+                    None => Ok(Box::new(expr)),
+                }
+            }
+            ast::Replacement::Without => match selection_rng {
+                Some(ref mut selection_rng) => {
+                    let expr = WeightedWithoutReplacement::new(weights, children, selection_rng);
+
+                    Ok(Box::new(expr.with_selection_rng(selection_rng.clone())))
+                }
+                None => Ok(Box::new(WeightedWithoutReplacement::new(weights, children, rng))),
+            },
+            ast::Replacement::WithoutInOrder => {
+                let expr = WeightedWithoutReplacement::new_in_order(weights, children);
+
+                match selection_rng {
+                    Some(selection_rng) => Ok(Box::new(expr.with_selection_rng(selection_rng))),
+                    None => Ok(Box::new(expr)),
+                }
+            }
+            // Never shuffles so the selection RNG is unused
+            ast::Replacement::WithoutFixedOrder => {
+                Ok(Box::new(WeightedWithoutReplacement::new_fixed_order(weights, children)))
+            }
+            ast::Replacement::WithoutDistinctValues => {
+                let expr = match selection_rng {
+                    Some(ref mut selection_rng) => {
+                        WeightedWithoutReplacement::new(weights, children, selection_rng)
+                            .with_selection_rng(selection_rng.clone())
+                    }
+                    None => WeightedWithoutReplacement::new(weights, children, rng),
+                };
+
+                Ok(Box::new(expr.with_distinct_values(constants, self.max_resample_attempts)))
+            }
         }
     }
 
+    /// Returns an error if a weighted sample with `entries` entries exceeds `max_children`
+    fn check_entries(&self, entries: usize) -> TransformResult<()> {
+        if entries > self.max_children {
+            return Err(TransformError::new(format!(
+                "weighted sample has more than {} entries which exceeds the limit",
+                self.max_children
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn transform_except(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        excluded: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let mut set = HashSet::new();
+        for value in excluded {
+            set.insert(self.eval(model, rng, value)?);
+        }
+
+        let expr = self.transform_expr(model, rng, node)?;
+
+        // Catch exclusion lists that exclude every value the sub-expression can return.  Prefer the
+        // exact values (e.g. of a pattern) and fall back to the bounds (e.g. of a range).  Other
+        // sub-expressions are checked at evaluation time.
+        if let Some(values) = expr.possible_values() {
+            if values.iter().all(|value| set.contains(value)) {
+                return Err(TransformError::new(format!(
+                    "the exclusion list excludes every value of `{}`",
+                    expr
+                )));
+            }
+        } else if let Some((l, r)) = expr.value_bounds() {
+            let size = u64::from(r) - u64::from(l) + 1;
+            let num_excluded = set.iter().filter(|&&value| value >= l && value <= r).count();
+            if num_excluded as u64 == size {
+                return Err(TransformError::new(format!(
+                    "the exclusion list excludes every value in the range [0x{:x}, 0x{:x}]",
+                    l, r
+                )));
+            }
+        }
+
+        Ok(Box::new(Except::new(expr, set, self.max_resample_attempts)))
+    }
+
+    fn transform_complement(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        excluded: &[Box<ast::Node>],
+        l: &ast::Node,
+        r: &ast::Node,
+    ) -> TransformResult<Box<dyn Expr>> {
+        let l = self.eval(model, rng, l)?;
+        let r = self.eval(model, rng, r)?;
+        let (l, r) = if r > l { (l, r) } else { (r, l) };
+
+        let size = u64::from(r) - u64::from(l) + 1;
+        if size > MAX_COMPLEMENT_SIZE {
+            return Err(TransformError::new(format!(
+                "the range [0x{:x}, 0x{:x}] of a complement has more than {} values",
+                l, r, MAX_COMPLEMENT_SIZE
+            )));
+        }
+
+        let mut set = HashSet::new();
+        for value in excluded {
+            set.insert(self.eval(model, rng, value)?);
+        }
+
+        let num_excluded = set.iter().filter(|&&value| value >= l && value <= r).count();
+        if num_excluded as u64 == size {
+            return Err(TransformError::new(format!(
+                "the complement excludes every value in the range [0x{:x}, 0x{:x}]",
+                l, r
+            )));
+        }
+
+        let set: Vec<u32> = set.into_iter().collect();
+
+        Ok(Box::new(Complement::new(l, r, &set)))
+    }
+
+    fn transform_in(
+        &self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        members: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let mut values = HashSet::new();
+        let mut ranges = Vec::new();
+        for member in members {
+            // Ranges are expanded to all of their values
+            if let ast::Node::Type(ast::Type::Range, ref args)
+            | ast::Node::Type(ast::Type::ProfiledRange(_), ref args) = **member
+            {
+                let l = self.eval(model, rng, &args[0])?;
+                let r = self.eval(model, rng, &args[1])?;
+                let (l, r) = if r > l { (l, r) } else { (r, l) };
+
+                ranges.push((l, r));
+            } else {
+                values.insert(self.eval(model, rng, member)?);
+            }
+        }
+
+        let expr = self.transform_expr(model, rng, node)?;
+
+        Ok(Box::new(In::new(expr, values, ranges)))
+    }
+
     fn transform_type(
         &self,
         model: &Model,
@@ -263,31 +1475,537 @@ impl Transform {
             ast::Type::Sequence => {
                 let args = self.transform_args(model, rng, args)?;
 
-                Ok(Box::new(Sequence::new(args, rng)))
+                match checked(|| Sequence::new(args, rng)) {
+                    Some(Some(sequence)) => Ok(Box::new(sequence)),
+                    Some(None) => {
+                        Err(TransformError::new("Sequence() increment must not be 0".to_owned()))
+                    }
+                    None => Err(TransformError::new(
+                        "division by zero while evaluating the arguments of Sequence()".to_owned(),
+                    )),
+                }
             }
-            ast::Type::Range => {
-                let l = self.transform_expr(model, rng, &args[0])?.next(rng);
-                let r = self.transform_expr(model, rng, &args[1])?.next(rng);
+            ast::Type::Range | ast::Type::ProfiledRange(_) => {
+                let profile = match *typ {
+                    ast::Type::ProfiledRange(profile) => profile,
+                    _ => ast::RangeProfile::Uniform,
+                };
+                let l = self.eval(model, rng, &args[0])?;
+                let r = self.eval(model, rng, &args[1])?;
+                let signed = self.signed_ranges.get();
+
+                if signed && profile != ast::RangeProfile::Uniform {
+                    return Err(TransformError::new(
+                        "profiled ranges are not supported in the step of Walk()".to_owned(),
+                    ));
+                }
+
+                // Reversed ranges are allowed but are likely a mistake when the bounds are
+                // constant
+                let reversed = if signed { (l as i32) > (r as i32) } else { l > r };
+                if reversed && self.is_constant(&args[0]) && self.is_constant(&args[1]) {
+                    self.warn(format!(
+                        "the range [0x{:x}, 0x{:x}] has a low bound greater than its high bound and is treated as [0x{:x}, 0x{:x}]",
+                        l, r, r, l
+                    ));
+                }
 
                 // Elide the range for case when limits are equal
                 //
                 // The underlying rand::distributions::Range treats this case as an error.  We
                 // don't want an error so catch and handle gracefully.
                 if l == r {
-                    Ok(Box::new(Value::new(1)))
+                    Ok(Box::new(Value::new(l)))
+                } else if signed {
+                    Ok(Box::new(Range::signed(l, r)))
                 } else {
-                    Ok(Box::new(Range::new(l, r)))
+                    Ok(Box::new(Range::with_profile(l, r, profile)))
                 }
             }
             ast::Type::Expand => Err(TransformError::new("Expand() must be inside {}".to_owned())),
             ast::Type::Done => {
-                let expr = self.transform_expr(model, rng, &*args[0])?;
+                let expr = self.transform_expr(model, rng, &args[0])?;
                 Ok(Box::new(Done::new(expr)))
             }
             ast::Type::Once => {
-                let expr = self.transform_expr(model, rng, &*args[0])?;
-                Ok(Box::new(Once::new(expr)))
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(Once::new(expr).with_exhausted_mode(self.exhausted_mode)))
+            }
+            ast::Type::Shuffle => {
+                let args = self.transform_args(model, rng, args)?;
+
+                Ok(Box::new(Shuffle::new(args, rng)))
             }
+            ast::Type::MulMod => {
+                // Constant expressions (e.g. `1 - 1`) are evaluated.  Other moduli are rejected if
+                // their bounds show they are always 0 (e.g. `Pattern(0, 0)`).
+                if self.is_pure(&args[2]) && self.eval(model, rng, &args[2])? == 0 {
+                    return Err(TransformError::new("MulMod() modulus must not be 0".to_owned()));
+                }
+
+                let mut args = self.transform_args(model, rng, args)?.into_iter();
+                let a = args.next().unwrap();
+                let b = args.next().unwrap();
+                let m = args.next().unwrap();
+
+                if m.value_bounds() == Some((0, 0)) {
+                    return Err(TransformError::new("MulMod() modulus is always 0".to_owned()));
+                }
+
+                Ok(Box::new(MulMod::new(a, b, m)))
+            }
+            ast::Type::Accumulate => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(Accumulate::new(expr)))
+            }
+            ast::Type::Hash => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(Hash::new(expr)))
+            }
+            ast::Type::Timestamp => {
+                let mut args = self.transform_args(model, rng, args)?.into_iter();
+                let step = args.next().unwrap();
+                let jitter = args.next().unwrap();
+
+                Ok(Box::new(Timestamp::new(step, jitter)))
+            }
+            ast::Type::AddCarry => {
+                let mut args = self.transform_args(model, rng, args)?.into_iter();
+                let a = args.next().unwrap();
+                let b = args.next().unwrap();
+
+                Ok(Box::new(AddCarry::new(a, b)))
+            }
+            ast::Type::ByteSwap => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(ByteSwap::new(expr)))
+            }
+            ast::Type::BitReverse => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(BitReverse::new(expr)))
+            }
+            ast::Type::PopCount => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(PopCount::new(expr)))
+            }
+            ast::Type::OneHot => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                Ok(Box::new(OneHot::new(expr)))
+            }
+            ast::Type::Decode => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let width = self.eval(model, rng, &args[1])?;
+
+                Ok(Box::new(Decode::new(expr, width)))
+            }
+            ast::Type::NoRepeat => {
+                if self.is_constant(&args[0]) {
+                    return Err(TransformError::new(
+                        "NoRepeat() of a constant can never return a different value".into(),
+                    ));
+                }
+
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                if let Some(value) = single_value(&*expr) {
+                    return Err(TransformError::new(format!(
+                        "NoRepeat() of `{}` can never return a value other than 0x{:x}",
+                        expr, value
+                    )));
+                }
+
+                Ok(Box::new(NoRepeat::new(expr, self.max_resample_attempts)))
+            }
+            ast::Type::AvoidRecent => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let window = self.eval(model, rng, &args[1])?;
+
+                let values = match (expr.possible_values(), expr.value_bounds()) {
+                    (Some(values), _) => Some(values.len() as u64),
+                    (None, Some((min, max))) => Some(u64::from(max - min) + 1),
+                    (None, None) => None,
+                };
+                if let Some(values) = values {
+                    if u64::from(window) >= values {
+                        return Err(TransformError::new(format!(
+                            "AvoidRecent() window of {} must be less than the {} value(s) of `{}`",
+                            window, values, expr
+                        )));
+                    }
+                }
+
+                Ok(Box::new(AvoidRecent::new(expr, window as usize, self.max_resample_attempts)))
+            }
+            ast::Type::Repeat => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let count = self.transform_expr(model, rng, &args[1])?;
+
+                if is_always_zero(&*count)
+                    || (self.is_constant(&args[1]) && count.clone().next(rng) == 0)
+                {
+                    return Err(TransformError::new(format!(
+                        "Repeat() count `{}` is always 0 and can never return a value",
+                        count
+                    )));
+                }
+
+                Ok(Box::new(Repeat::new(expr, count, self.max_resample_attempts)))
+            }
+            ast::Type::HoldFor => {
+                // Derived before the children are transformed so that their transform-time draws
+                // don't affect it
+                let count_rng = rng.derive();
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let count = self.transform_expr(model, rng, &args[1])?;
+
+                if is_always_zero(&*count)
+                    || (self.is_constant(&args[1]) && count.clone().next(rng) == 0)
+                {
+                    return Err(TransformError::new(format!(
+                        "HoldFor() count `{}` is always 0 and can never return a value",
+                        count
+                    )));
+                }
+
+                Ok(Box::new(HoldFor::new(expr, count, count_rng, self.max_resample_attempts)))
+            }
+            ast::Type::Bits => {
+                let width = self.eval(model, rng, &args[0])?;
+
+                match width {
+                    0 => Ok(Box::new(Value::new(0))),
+                    1..=32 => Ok(Box::new(Range::new(0, u32::MAX >> (32 - width)))),
+                    _ => Err(TransformError::new(format!(
+                        "Bits() width must be in the range [0, 32] but found {}",
+                        width
+                    ))),
+                }
+            }
+            ast::Type::Sum => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let count = self.eval(model, rng, &args[1])?;
+
+                Ok(Box::new(Sum::new(expr, count)))
+            }
+            ast::Type::Mean => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let count = self.eval(model, rng, &args[1])?;
+
+                Ok(Box::new(Mean::new(expr, count)))
+            }
+            ast::Type::WindowMin | ast::Type::WindowMax => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let size = self.eval(model, rng, &args[1])?;
+
+                if size == 0 {
+                    let function = match *typ {
+                        ast::Type::WindowMin => "WindowMin",
+                        _ => "WindowMax",
+                    };
+                    return Err(TransformError::new(format!(
+                        "{}() window size must be at least 1",
+                        function
+                    )));
+                }
+
+                match *typ {
+                    ast::Type::WindowMin => Ok(Box::new(WindowMin::new(expr, size))),
+                    _ => Ok(Box::new(WindowMax::new(expr, size))),
+                }
+            }
+            ast::Type::Zipf => {
+                let n = self.eval(model, rng, &args[0])?;
+                let s_num = self.eval(model, rng, &args[1])?;
+                let s_den = match args.get(2) {
+                    Some(arg) => self.eval(model, rng, arg)?,
+                    None => 1,
+                };
+
+                if !(1..=MAX_ZIPF_N).contains(&n) {
+                    return Err(TransformError::new(format!(
+                        "Zipf() n must be in the range [1, {}] but found {}",
+                        MAX_ZIPF_N, n
+                    )));
+                }
+
+                if s_den == 0 {
+                    return Err(TransformError::new(
+                        "Zipf() exponent denominator must be non-zero".into(),
+                    ));
+                }
+
+                Ok(Box::new(Zipf::new(n, s_num, s_den)))
+            }
+            ast::Type::TruncNormal => {
+                let mean = self.eval(model, rng, &args[0])?;
+                let stddev = self.eval(model, rng, &args[1])?;
+                let min = self.eval(model, rng, &args[2])?;
+                let max = self.eval(model, rng, &args[3])?;
+
+                if min > max {
+                    return Err(TransformError::new(format!(
+                        "TruncNormal() min must not be greater than max but found min {} and max {}",
+                        min, max
+                    )));
+                }
+
+                // The distance from the mean to the nearest value of the range
+                let distance = if mean < min {
+                    min - mean
+                } else {
+                    mean.saturating_sub(max)
+                };
+                if u64::from(distance) > u64::from(stddev) * u64::from(MAX_TRUNC_NORMAL_STDDEVS) {
+                    return Err(TransformError::new(format!(
+                        "TruncNormal() range [{}, {}] must be within {} standard deviations of {} from the mean {}",
+                        min, max, MAX_TRUNC_NORMAL_STDDEVS, stddev, mean
+                    )));
+                }
+
+                Ok(Box::new(TruncNormal::new(mean, stddev, min, max, self.max_resample_attempts)))
+            }
+            ast::Type::Poisson => {
+                let lambda_num = self.eval(model, rng, &args[0])?;
+                let lambda_den = self.eval(model, rng, &args[1])?;
+
+                if lambda_den == 0 {
+                    return Err(TransformError::new(
+                        "Poisson() lambda denominator must not be 0".into(),
+                    ));
+                }
+
+                Ok(Box::new(Poisson::new(lambda_num, lambda_den)))
+            }
+            ast::Type::Walk => {
+                let start = self.eval(model, rng, &args[0])?;
+
+                // Steps are signed so ranges anywhere in the step are ordered as i32 E.g.
+                // `[-5, 5] + 1`
+                let outer = self.signed_ranges.replace(true);
+                let step = self.transform_expr(model, rng, &args[1]);
+                self.signed_ranges.set(outer);
+                let step = step?;
+
+                Ok(Box::new(Walk::new(start, step)))
+            }
+            ast::Type::GrayCode => {
+                let width = self.eval(model, rng, &args[0])?;
+
+                if width > 32 {
+                    return Err(TransformError::new(format!(
+                        "GrayCode() width must be in the range [0, 32] but found {}",
+                        width
+                    )));
+                }
+
+                Ok(Box::new(GrayCode::new(width)))
+            }
+            ast::Type::Maybe => {
+                let percent = self.eval(model, rng, &args[0])?;
+
+                match percent {
+                    0 => self.transform_expr(model, rng, &args[2]),
+                    100 => self.transform_expr(model, rng, &args[1]),
+                    1..=99 => {
+                        let entries = vec![
+                            Box::new(ast::Node::WeightedSample(percent, args[1].clone())),
+                            Box::new(ast::Node::WeightedSample(100 - percent, args[2].clone())),
+                        ];
+
+                        self.transform_weighted(model, rng, &ast::Replacement::With, &entries)
+                    }
+                    _ => Err(TransformError::new(format!(
+                        "Maybe() percent must be in the range [0, 100] but found {}",
+                        percent
+                    ))),
+                }
+            }
+            ast::Type::ModCounter => {
+                let start = self.eval(model, rng, &args[0])?;
+                let modulus = self.eval(model, rng, &args[1])?;
+
+                if modulus == 0 {
+                    return Err(TransformError::new("ModCounter() modulus must not be 0".into()));
+                }
+
+                if start >= modulus {
+                    return Err(TransformError::new(format!(
+                        "ModCounter() start must be less than the modulus but found start {} and modulus {}",
+                        start, modulus
+                    )));
+                }
+
+                Ok(Box::new(ModCounter::new(start, modulus)))
+            }
+            ast::Type::Ramp => {
+                let start = self.eval(model, rng, &args[0])?;
+                let end = self.eval(model, rng, &args[1])?;
+                let steps = self.eval(model, rng, &args[2])?;
+
+                if steps == 0 {
+                    return Err(TransformError::new("Ramp() steps must not be 0".into()));
+                }
+
+                Ok(Box::new(Ramp::new(start, end, steps)))
+            }
+            ast::Type::Cdf => {
+                let mut cumulatives = Vec::new();
+                let mut children = Vec::new();
+                for entry in args.chunks(2) {
+                    let cumulative = self.eval(model, rng, &entry[0])?;
+                    let prev = cumulatives.last().cloned().unwrap_or(0);
+                    if cumulative <= prev {
+                        return Err(TransformError::new(format!(
+                            "CDF() cumulative percentages must be increasing but found {} after {}",
+                            cumulative, prev
+                        )));
+                    }
+
+                    cumulatives.push(cumulative);
+                    children.push(self.transform_expr(model, rng, &entry[1])?);
+                }
+
+                let last = cumulatives.last().cloned().unwrap_or(0);
+                if last != 100 {
+                    return Err(TransformError::new(format!(
+                        "CDF() cumulative percentages must end at 100 but end at {}",
+                        last
+                    )));
+                }
+
+                Ok(Box::new(Cdf::new(cumulatives, children)))
+            }
+        }
+    }
+}
+
+/// Reduces a chain of variable methods to the equivalent single method
+///
+/// Methods apply left to right.  `next` has no effect after `next` or `copy` since both advance.
+/// `copy` has no effect after `copy`.  `prev` reads without advancing so only `prev` may follow
+/// it.  `done` reads without advancing and is not a variable so nothing may follow it.  Returns
+/// `None` for `copy` followed by `prev` or `done` since nothing advances the copy so its previous
+/// value and done are always 0.
+fn reduce_method_chain(
+    methods: &[ast::VariableMethod],
+) -> TransformResult<Option<ast::VariableMethod>> {
+    let mut reduced = Some(ast::VariableMethod::Next);
+    for method in methods {
+        reduced = match (reduced, method) {
+            (Some(ast::VariableMethod::Next), _) => Some(method.clone()),
+            (Some(ast::VariableMethod::Copy), ast::VariableMethod::Prev)
+            | (Some(ast::VariableMethod::Copy), ast::VariableMethod::Done) => None,
+            (Some(ast::VariableMethod::Copy), _) => Some(ast::VariableMethod::Copy),
+            (Some(ast::VariableMethod::Done), method) => {
+                return Err(TransformError::new(format!(
+                    "'.{}' can't follow '.done'",
+                    method
+                )));
+            }
+            (reduced, ast::VariableMethod::Prev) => reduced,
+            (_, method) => {
+                return Err(TransformError::new(format!(
+                    "'.{}' can't follow '.prev'",
+                    method
+                )));
+            }
+        };
+    }
+
+    Ok(reduced)
+}
+
+/// Returns the first identifier referenced by `node` for which `is_match` returns true, if any
+fn find_reference<'a>(node: &'a ast::Node, is_match: &dyn Fn(&str) -> bool) -> Option<&'a str> {
+    match *node {
+        ast::Node::RIdentifier(ref name, ..) | ast::Node::MethodChain(ref name, ..) => {
+            if is_match(name) {
+                Some(name)
+            } else {
+                None
+            }
+        }
+        ast::Node::UnaryOperation(_, ref a)
+        | ast::Node::WeightedSample(_, ref a)
+        | ast::Node::PercentWeightedSample(_, ref a)
+        | ast::Node::ProbabilityWeightedSample(_, _, ref a) => find_reference(a, is_match),
+        ast::Node::BinaryOperation(ref a, _, ref b) | ast::Node::Let(_, ref a, ref b) => {
+            find_reference(a, is_match).or_else(|| find_reference(b, is_match))
+        }
+        ast::Node::Except(ref a, ref nodes) | ast::Node::In(ref a, ref nodes) => {
+            find_reference(a, is_match)
+                .or_else(|| nodes.iter().find_map(|n| find_reference(n, is_match)))
+        }
+        ast::Node::Complement(ref nodes, ref l, ref r) => nodes
+            .iter()
+            .find_map(|n| find_reference(n, is_match))
+            .or_else(|| find_reference(l, is_match))
+            .or_else(|| find_reference(r, is_match)),
+        ast::Node::Type(_, ref nodes) | ast::Node::Weighted(_, ref nodes) => {
+            nodes.iter().find_map(|n| find_reference(n, is_match))
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates `expr` once at transform time
+///
+/// Division by zero is an error instead of a panic.
+fn evaluate(expr: &mut Box<dyn Expr>, rng: &mut CrateRng) -> TransformResult<u32> {
+    checked(|| expr.next(rng)).ok_or_else(|| {
+        TransformError::new(format!("division by zero while evaluating `{}`", expr))
+    })
+}
+
+/// Returns an error if `op` divides by a literal 0
+fn check_divisor(op: &ast::BinaryOpcode, divisor: &ast::Node) -> TransformResult<()> {
+    match (op, divisor) {
+        (ast::BinaryOpcode::Div, ast::Node::Number(0))
+        | (ast::BinaryOpcode::Mod, ast::Node::Number(0)) => {
+            Err(TransformError::new("division by zero".to_owned()))
         }
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if `operand` of the operation `op` never produces a value
+///
+/// The operation would be done on every next and return a value unrelated to the operand.  See
+/// `Expr::is_empty`.
+fn check_operand(operand: Box<dyn Expr>, op: &dyn fmt::Display) -> TransformResult<Box<dyn Expr>> {
+    if operand.is_empty() {
+        return Err(TransformError::new(format!(
+            "the operand `{}` of `{}` never produces a value because all weights of its samples are 0",
+            operand, op
+        )));
+    }
+
+    Ok(operand)
+}
+
+/// Returns the only value `expr` can return, if it can return only one
+///
+/// Uses the possible values and falls back to the bounds.
+fn single_value(expr: &dyn Expr) -> Option<u32> {
+    match expr.possible_values() {
+        Some(values) if values.len() == 1 => Some(values[0]),
+        Some(_) => None,
+        None => match expr.value_bounds() {
+            Some((min, max)) if min == max => Some(min),
+            _ => None,
+        },
+    }
+}
+
+/// Returns true if the bounds of `expr` show it always returns 0
+fn is_always_zero(expr: &dyn Expr) -> bool {
+    expr.value_bounds() == Some((0, 0))
+}
+
+/// Returns the greatest common divisor of `a` and `b`
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
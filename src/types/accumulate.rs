@@ -0,0 +1,72 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns the running sum of the values of its sub-expression
+///
+/// The first value is the first value of the sub-expression.  The sum wraps on u32 overflow.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.  The sum is not cleared when done.
+#[derive(Clone)]
+pub struct Accumulate {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    sum: u32,
+}
+
+impl Accumulate {
+    pub fn new(expr: Box<dyn Expr>) -> Accumulate {
+        Accumulate {
+            data: Default::default(),
+            expr,
+            sum: 0,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Accumulate> {
+        Ok(Accumulate {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            sum: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Accumulate {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.sum = self.sum.wrapping_add(self.expr.next(rng));
+        self.data.done = self.expr.done();
+        self.data.prev = self.sum;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::ACCUMULATE);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u32(self.sum);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Accumulate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Accumulate({})", self.expr)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns `a + b` truncated to 32 bits
+///
+/// # Done
+///
+/// Done indicates the carry out of the addition.  It is true when `a + b` exceeds `u32::MAX`.
+/// The done values of the operands are ignored.
+#[derive(Clone)]
+pub struct AddCarry {
+    data: ExprData,
+    operands: [Box<dyn Expr>; 2],
+}
+
+impl AddCarry {
+    pub fn new(a: Box<dyn Expr>, b: Box<dyn Expr>) -> AddCarry {
+        AddCarry {
+            data: Default::default(),
+            operands: [a, b],
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<AddCarry> {
+        Ok(AddCarry {
+            data: reader.data()?,
+            operands: [reader.expr()?, reader.expr()?],
+        })
+    }
+}
+
+impl Expr for AddCarry {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let a = self.operands[0].next(rng);
+        let b = self.operands[1].next(rng);
+
+        let (sum, carry) = a.overflowing_add(b);
+        self.data.prev = sum;
+        self.data.done = carry;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::ADD_CARRY);
+        writer.data(&self.data);
+        writer.expr(&*self.operands[0]);
+        writer.expr(&*self.operands[1]);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.operands.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.operands.iter_mut().collect()
+    }
+}
+
+impl fmt::Display for AddCarry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AddCarry({}, {})", self.operands[0], self.operands[1])
+    }
+}
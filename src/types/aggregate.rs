@@ -0,0 +1,160 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns the sum of `count` values of a sub-expression
+///
+/// The sum is accumulated in 64 bits then truncated to 32 bits.  Returns 0 if `count` is 0.
+///
+/// # Done
+///
+/// Done is passed through from the last value of the sub-expression.  Always done if `count` is
+/// 0.
+#[derive(Clone)]
+pub struct Sum {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: u32,
+}
+
+/// Returns the mean of `count` values of a sub-expression
+///
+/// The mean is rounded down.  Returns 0 if `count` is 0.
+///
+/// # Done
+///
+/// Done is passed through from the last value of the sub-expression.  Always done if `count` is
+/// 0.
+#[derive(Clone)]
+pub struct Mean {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: u32,
+}
+
+/// Sums `count` values of `expr`
+///
+/// Returns the sum and the done of the last value.
+fn sum(expr: &mut Box<dyn Expr>, count: u32, rng: &mut CrateRng) -> (u64, bool) {
+    let mut sum = 0;
+    let mut done = true;
+    for _ in 0..count {
+        sum += u64::from(expr.next(rng));
+        done = expr.done();
+    }
+
+    (sum, done)
+}
+
+impl Sum {
+    pub fn new(expr: Box<dyn Expr>, count: u32) -> Sum {
+        Sum {
+            data: Default::default(),
+            expr,
+            count,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Sum> {
+        Ok(Sum {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            count: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Sum {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let (sum, done) = sum(&mut self.expr, self.count, rng);
+
+        self.data.prev = sum as u32;
+        self.data.done = done;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::SUM);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u32(self.count);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Sum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Sum({}, {})", self.expr, self.count)
+    }
+}
+
+impl Mean {
+    pub fn new(expr: Box<dyn Expr>, count: u32) -> Mean {
+        Mean {
+            data: Default::default(),
+            expr,
+            count,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Mean> {
+        Ok(Mean {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            count: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Mean {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let (sum, done) = sum(&mut self.expr, self.count, rng);
+
+        self.data.prev = match self.count {
+            0 => 0,
+            count => (sum / u64::from(count)) as u32,
+        };
+        self.data.done = done;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::MEAN);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u32(self.count);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Mean {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mean({}, {})", self.expr, self.count)
+    }
+}
@@ -0,0 +1,120 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Re-samples its sub-expression until it returns a value not among the last `window` values
+///
+/// Until `window` values have been returned, only the values returned so far are avoided.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression on the call that returns the value.
+#[derive(Clone)]
+pub struct AvoidRecent {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    window: usize,
+    recent: VecDeque<u32>,
+    max_attempts: u32,
+}
+
+impl AvoidRecent {
+    /// `max_attempts` is the maximum number of times the sub-expression is re-sampled to find a
+    /// value not among the last `window` values
+    pub fn new(expr: Box<dyn Expr>, window: usize, max_attempts: u32) -> AvoidRecent {
+        AvoidRecent {
+            data: Default::default(),
+            expr,
+            window,
+            recent: VecDeque::with_capacity(window),
+            max_attempts,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<AvoidRecent> {
+        let avoid_recent = AvoidRecent {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            window: reader.len()?,
+            recent: reader.u32s()?.into_iter().collect(),
+            max_attempts: reader.u32()?,
+        };
+        reader.check(avoid_recent.recent.len() <= avoid_recent.window, "window overfull")?;
+
+        Ok(avoid_recent)
+    }
+}
+
+impl Expr for AvoidRecent {
+    /// Returns the last value drawn if the sub-expression does not return a value outside the
+    /// window within `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mut value = self.expr.next(rng);
+
+        let mut attempts = 0;
+        while self.recent.contains(&value) {
+            if attempts == self.max_attempts {
+                budget::limit_exceeded(
+                    self.max_attempts,
+                    format!(
+                        "the expression `{}` failed to return a value other than {:x?}",
+                        self, self.recent
+                    ),
+                );
+                break;
+            }
+
+            if !budget::consume() {
+                break;
+            }
+            value = self.expr.next(rng);
+            attempts += 1;
+        }
+
+        if self.window > 0 {
+            if self.recent.len() == self.window {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(value);
+        }
+
+        self.data.prev = value;
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::AVOID_RECENT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.len(self.window);
+        writer.u32s(&self.recent.iter().cloned().collect::<Vec<_>>());
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for AvoidRecent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AvoidRecent({}, {})", self.expr, self.window)
+    }
+}
@@ -0,0 +1,166 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+/// Binds the value of an expression to a name for use in a body expression
+///
+/// `let <name> = <binding> in <body>`
+///
+/// Each call to `next` evaluates the binding once then evaluates the body.  All `Binding`s of
+/// the name in the body return the same value.  Arguments of the body that are evaluated at
+/// transform time (e.g. range bounds) see a single value of the binding drawn at transform time.
+///
+/// The value is shared with the `Binding`s via an `Rc`.  Clones share the value with the
+/// original.  This is safe since the value is always set immediately before the body is
+/// evaluated.
+///
+/// # Done
+///
+/// Done follows the body.
+#[derive(Clone)]
+pub struct Let {
+    data: ExprData,
+    name: String,
+    binding: Box<dyn Expr>,
+    value: Rc<Cell<u32>>,
+    body: Box<dyn Expr>,
+}
+
+/// A reference to the value bound by a `Let`
+///
+/// # Done
+///
+/// Always done.
+#[derive(Clone)]
+pub struct Binding {
+    data: ExprData,
+    name: String,
+    value: Rc<Cell<u32>>,
+}
+
+impl Let {
+    pub fn new(
+        name: &str,
+        binding: Box<dyn Expr>,
+        value: Rc<Cell<u32>>,
+        body: Box<dyn Expr>,
+    ) -> Let {
+        Let {
+            data: Default::default(),
+            name: name.into(),
+            binding,
+            value,
+            body,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Let> {
+        let data = reader.data()?;
+        let name = reader.string()?;
+        let binding = reader.expr()?;
+        let value = Rc::new(Cell::new(reader.u32()?));
+        let body = reader.scoped(&name, Rc::clone(&value), |reader| reader.expr())?;
+
+        Ok(Let {
+            data,
+            name,
+            binding,
+            value,
+            body,
+        })
+    }
+}
+
+impl Expr for Let {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.value.set(self.binding.next(rng));
+
+        self.data.prev = self.body.next(rng);
+        self.data.done = self.body.done();
+
+        self.data.prev
+    }
+
+    /// Values of the binding are only produced via the body
+    fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    fn label(&self) -> String {
+        format!("let {}", self.name)
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::LET);
+        writer.data(&self.data);
+        writer.str(&self.name);
+        writer.expr(&*self.binding);
+        writer.u32(self.value.get());
+        writer.expr(&*self.body);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.binding, &*self.body]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.binding, &mut self.body]
+    }
+}
+
+impl fmt::Display for Let {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(let {} = {} in {})", self.name, self.binding, self.body)
+    }
+}
+
+impl Binding {
+    pub fn new(name: &str, value: Rc<Cell<u32>>) -> Binding {
+        Binding {
+            data: Default::default(),
+            name: name.into(),
+            value,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Binding> {
+        let data = reader.data()?;
+        let name = reader.string()?;
+        let value = reader.binding(&name)?;
+
+        Ok(Binding { data, name, value })
+    }
+}
+
+impl Expr for Binding {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.value.get();
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::BINDING);
+        writer.data(&self.data);
+        writer.str(&self.name);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
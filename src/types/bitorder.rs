@@ -0,0 +1,125 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns the value of its sub-expression with the byte order reversed
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct ByteSwap {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+}
+
+impl ByteSwap {
+    pub fn new(expr: Box<dyn Expr>) -> ByteSwap {
+        ByteSwap {
+            data: Default::default(),
+            expr,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<ByteSwap> {
+        Ok(ByteSwap {
+            data: reader.data()?,
+            expr: reader.expr()?,
+        })
+    }
+}
+
+impl Expr for ByteSwap {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.expr.next(rng).swap_bytes();
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::BYTE_SWAP);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for ByteSwap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ByteSwap({})", self.expr)
+    }
+}
+
+/// Returns the value of its sub-expression with the bit order reversed
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct BitReverse {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+}
+
+impl BitReverse {
+    pub fn new(expr: Box<dyn Expr>) -> BitReverse {
+        BitReverse {
+            data: Default::default(),
+            expr,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<BitReverse> {
+        Ok(BitReverse {
+            data: reader.data()?,
+            expr: reader.expr()?,
+        })
+    }
+}
+
+impl Expr for BitReverse {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.expr.next(rng).reverse_bits();
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::BIT_REVERSE);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for BitReverse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BitReverse({})", self.expr)
+    }
+}
@@ -0,0 +1,144 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, union_bounds, Expr, ExprData, Reader, Writer};
+
+use rand::distributions::Distribution;
+use rand::distributions::uniform::Uniform;
+use std::fmt;
+
+/// Selects sub-expressions via a table of cumulative percentages
+///
+/// E.g. `CDF({10: 0, 30: 1, 100: 2})` returns 0 10% of the time, 1 20% of the time, and 2 70% of
+/// the time.  A percentile in [0, 100) is drawn and the first sub-expression with a cumulative
+/// percentage greater than the percentile is selected.
+///
+/// # Done
+///
+/// As for `r{}`, the selected sub-expression is held until it indicates done.  Done follows the
+/// selected sub-expression.
+#[derive(Clone)]
+pub struct Cdf {
+    data: ExprData,
+    cumulatives: Vec<u32>,
+    /// The percentage of each sub-expression.  See `Expr::weights`.
+    weights: Vec<u32>,
+    children: Vec<Box<dyn Expr>>,
+    percentile: Uniform<u32>,
+    selected: Option<usize>,
+}
+
+impl Cdf {
+    /// `cumulatives` must be strictly increasing, end at 100, and have one entry per child
+    pub fn new(cumulatives: Vec<u32>, children: Vec<Box<dyn Expr>>) -> Cdf {
+        assert_eq!(cumulatives.len(), children.len());
+        assert_eq!(cumulatives.last(), Some(&100));
+
+        let weights = cumulatives
+            .iter()
+            .scan(0, |prev, &cumulative| {
+                let weight = cumulative - *prev;
+                *prev = cumulative;
+                Some(weight)
+            })
+            .collect();
+
+        Cdf {
+            data: Default::default(),
+            cumulatives,
+            weights,
+            children,
+            percentile: Uniform::new(0, 100),
+            selected: None,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Cdf> {
+        let data = reader.data()?;
+        let cumulatives = reader.u32s()?;
+        let children = reader.exprs()?;
+        reader.check(
+            cumulatives.len() == children.len(),
+            "percentage count differs from child count",
+        )?;
+        reader.check(
+            cumulatives.windows(2).all(|pair| pair[0] <= pair[1])
+                && cumulatives.last() == Some(&100),
+            "percentages are not cumulative to 100",
+        )?;
+
+        let mut cdf = Cdf::new(cumulatives, children);
+        cdf.data = data;
+        cdf.selected = reader.option_len()?;
+        reader.check(
+            cdf.selected.is_none_or(|selected| selected < cdf.children.len()),
+            "selected child out of range",
+        )?;
+
+        Ok(cdf)
+    }
+}
+
+impl Expr for Cdf {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let child_index = match self.selected {
+            Some(child_index) if !self.data.done => child_index,
+            _ => {
+                let percentile = self.percentile.sample(rng);
+                self.cumulatives.iter().position(|&cumulative| percentile < cumulative).unwrap()
+            }
+        };
+        self.selected = Some(child_index);
+
+        self.data.prev = self.children[child_index].next(rng);
+        self.data.done = self.children[child_index].done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        union_bounds(&self.children)
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::CDF);
+        writer.data(&self.data);
+        writer.u32s(&self.cumulatives);
+        writer.exprs(&self.children);
+        writer.option_len(self.selected);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn last_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    fn weights(&self) -> Option<&[u32]> {
+        Some(&self.weights)
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.children.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.children.iter_mut().collect()
+    }
+}
+
+impl fmt::Display for Cdf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CDF({{")?;
+        for (cumulative, child) in self.cumulatives.iter().zip(&self.children) {
+            write!(f, "{}: {}, ", cumulative, child)?;
+        }
+        write!(f, "}})")
+    }
+}
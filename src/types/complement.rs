@@ -0,0 +1,113 @@
+use rand::distributions::Distribution;
+use rand::distributions::uniform::Uniform;
+
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// The maximum number of values in the range of a complement.  Limits the size of the allowed
+/// list.
+pub const MAX_COMPLEMENT_SIZE: u64 = 1 << 20;
+
+/// Returns a random value in the inclusive range [l, r] excluding a set of values
+///
+/// The allowed values are computed once and sampled uniformly.
+///
+/// # Done
+///
+/// Done is indicated on every call.
+#[derive(Clone)]
+pub struct Complement {
+    data: ExprData,
+    l: u32,
+    r: u32,
+    excluded: Vec<u32>,
+    allowed: Vec<u32>,
+    index: Uniform<usize>,
+}
+
+impl Complement {
+    /// `l` must not be greater than `r` and the excluded values must not include every value of
+    /// the range.  Excluded values outside the range are ignored.
+    pub fn new(l: u32, r: u32, excluded: &[u32]) -> Complement {
+        let mut excluded: Vec<u32> =
+            excluded.iter().cloned().filter(|value| (l..=r).contains(value)).collect();
+        excluded.sort();
+        excluded.dedup();
+
+        let allowed: Vec<u32> =
+            (l..=r).filter(|value| excluded.binary_search(value).is_err()).collect();
+        assert!(!allowed.is_empty());
+
+        Complement {
+            data: Default::default(),
+            l,
+            r,
+            index: Uniform::new(0, allowed.len()),
+            excluded,
+            allowed,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Complement> {
+        let data = reader.data()?;
+        let (l, r) = (reader.u32()?, reader.u32()?);
+        let excluded = reader.u32s()?;
+
+        reader.check(l <= r, "complement bounds in reverse order")?;
+        let size = u64::from(r - l) + 1;
+        reader.check(size <= MAX_COMPLEMENT_SIZE, "complement range too large")?;
+        let mut within: Vec<u32> =
+            excluded.iter().cloned().filter(|value| (l..=r).contains(value)).collect();
+        within.sort_unstable();
+        within.dedup();
+        reader.check((within.len() as u64) < size, "complement excludes every value")?;
+
+        let mut complement = Complement::new(l, r, &excluded);
+        complement.data = data;
+
+        Ok(complement)
+    }
+}
+
+impl Expr for Complement {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.allowed[self.index.sample(rng)];
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((self.allowed[0], self.allowed[self.allowed.len() - 1]))
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::COMPLEMENT);
+        writer.data(&self.data);
+        writer.u32(self.l);
+        writer.u32(self.r);
+        writer.u32s(&self.excluded);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Complement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "~{{")?;
+        for value in &self.excluded {
+            write!(f, "0x{:x}, ", value)?;
+        }
+        write!(f, "}} within [0x{:x}, 0x{:x}]", self.l, self.r)
+    }
+}
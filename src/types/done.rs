@@ -1,5 +1,6 @@
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
 
 use std::fmt;
 
@@ -16,6 +17,13 @@ impl Done {
             expr,
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Done> {
+        Ok(Done {
+            data: reader.data()?,
+            expr: reader.expr()?,
+        })
+    }
 }
 
 impl Expr for Done {
@@ -26,9 +34,27 @@ impl Expr for Done {
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::DONE);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
 }
 
 impl fmt::Display for Done {
@@ -0,0 +1,81 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use rand::RngCore;
+use std::fmt;
+
+/// Returns a value with fixed known bits and random don't-care bits
+///
+/// E.g. `0b10xx` returns 8, 9, 10, or 11.  Each don't-care bit is equally likely to be 0 or 1.
+#[derive(Clone)]
+pub struct DontCare {
+    data: ExprData,
+    value: u32,
+    mask: u32,
+}
+
+impl DontCare {
+    /// `mask` selects the don't-care bits.  Bits of `value` selected by `mask` are ignored.
+    pub fn new(value: u32, mask: u32) -> DontCare {
+        DontCare {
+            data: Default::default(),
+            value: value & !mask,
+            mask,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<DontCare> {
+        let data = reader.data()?;
+        let mut dont_care = DontCare::new(reader.u32()?, reader.u32()?);
+        dont_care.data = data;
+
+        Ok(dont_care)
+    }
+}
+
+impl Expr for DontCare {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.value | (rng.next_u32() & self.mask);
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((self.value, self.value | self.mask))
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::DONT_CARE);
+        writer.data(&self.data);
+        writer.u32(self.value);
+        writer.u32(self.mask);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for DontCare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = 32 - (self.value | self.mask).leading_zeros();
+
+        write!(f, "0b")?;
+        for bit in (0..width).rev() {
+            if self.mask & (1 << bit) != 0 {
+                write!(f, "x")?;
+            } else {
+                write!(f, "{}", (self.value >> bit) & 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Clone)]
+pub struct Except {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    excluded: HashSet<u32>,
+    max_attempts: u32,
+}
+
+impl Except {
+    /// `max_attempts` is the maximum number of times the sub-expression is re-sampled to find a
+    /// non-excluded value
+    pub fn new(expr: Box<dyn Expr>, excluded: HashSet<u32>, max_attempts: u32) -> Except {
+        Except {
+            data: Default::default(),
+            expr,
+            excluded,
+            max_attempts,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Except> {
+        Ok(Except {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            excluded: reader.u32s()?.into_iter().collect(),
+            max_attempts: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Except {
+    /// Returns the previous value if the sub-expression does not return a non-excluded value
+    /// within `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        // Done is latched across excluded values so that excluding the value that completes the
+        // sub-expression does not hide the completion.
+        let mut done = false;
+
+        for attempt in 0..self.max_attempts {
+            if attempt > 0 && !budget::consume() {
+                self.data.done = done;
+
+                return self.data.prev;
+            }
+
+            let value = self.expr.next(rng);
+            done |= self.expr.done();
+
+            if !self.excluded.contains(&value) {
+                self.data.prev = value;
+                self.data.done = done;
+
+                return self.data.prev;
+            }
+        }
+
+        budget::limit_exceeded(
+            self.max_attempts,
+            format!("the expression `{}` failed to return a non-excluded value", self),
+        );
+        self.data.done = done;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        let mut values = self.expr.possible_values()?;
+        values.retain(|value| !self.excluded.contains(value));
+
+        Some(values)
+    }
+
+    fn label(&self) -> String {
+        let mut excluded: Vec<&u32> = self.excluded.iter().collect();
+        excluded.sort();
+        let excluded: Vec<String> = excluded.iter().map(|value| format!("0x{:x}", value)).collect();
+
+        format!("except {{{}}}", excluded.join(", "))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::EXCEPT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+
+        let mut excluded: Vec<u32> = self.excluded.iter().cloned().collect();
+        excluded.sort_unstable();
+        writer.u32s(&excluded);
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Except {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut excluded: Vec<&u32> = self.excluded.iter().collect();
+        excluded.sort();
+
+        write!(f, "({} except {{", self.expr)?;
+        for value in excluded {
+            write!(f, "0x{:x}, ", value)?;
+        }
+        write!(f, "}})")
+    }
+}
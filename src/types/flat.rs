@@ -0,0 +1,307 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+use super::operation::{apply_binary, apply_signed_binary, apply_unary, binary_bounds, binary_values, unary_bounds, unary_values, BinaryDoneMode};
+use rvs_parser::ast;
+
+use std::fmt;
+use std::mem;
+
+/// A step of a `FlatOperation`
+#[derive(Clone)]
+enum Instruction {
+    /// Pushes the next value of the operand at the index
+    Operand(usize),
+    /// Replaces the top of the stack with the result of the operation
+    Unary(ast::UnaryOpcode),
+    /// Replaces the top two entries of the stack with the result of the operation
+    ///
+    /// The index is that of the done latches of the operation.
+    Binary(ast::BinaryOpcode, usize),
+}
+
+/// A tree of unary and binary operations evaluated iteratively
+///
+/// Equivalent to the tree of `Unary` and `Binary` expressions it was flattened from but without a
+/// call through `Expr` per operation.  Operations are stored in postfix order and evaluated with
+/// a stack.  Operands (i.e. the sub-expressions that are not operations) are evaluated in the same
+/// order as the tree so values, done, and RNG draws are identical.
+///
+/// Built in postfix order with `operand`, `unary`, and `binary`.
+///
+/// # Done
+///
+/// As for the equivalent tree.  See `Binary` and `Unary`.
+#[derive(Clone)]
+pub struct FlatOperation {
+    data: ExprData,
+    instructions: Vec<Instruction>,
+    operands: Vec<Box<dyn Expr>>,
+    /// The done latches of each binary operation for `BinaryDoneMode::Latched`
+    latches: Vec<(bool, bool)>,
+    done_mode: BinaryDoneMode,
+    /// Compares operands as two's complement.  See `Binary::with_signed_comparisons`.
+    signed_comparisons: bool,
+    /// Reused by `next` to avoid an allocation per call
+    stack: Vec<(u32, bool)>,
+}
+
+impl FlatOperation {
+    pub fn new(done_mode: BinaryDoneMode) -> FlatOperation {
+        FlatOperation {
+            data: Default::default(),
+            instructions: Vec::new(),
+            operands: Vec::new(),
+            latches: Vec::new(),
+            done_mode,
+            signed_comparisons: false,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Interprets the operands of `<` and `>` as two's complement
+    pub fn with_signed_comparisons(mut self, signed_comparisons: bool) -> FlatOperation {
+        self.signed_comparisons = signed_comparisons;
+        self
+    }
+
+    pub fn operand(&mut self, expr: Box<dyn Expr>) {
+        self.instructions.push(Instruction::Operand(self.operands.len()));
+        self.operands.push(expr);
+    }
+
+    pub fn unary(&mut self, operation: ast::UnaryOpcode) {
+        self.instructions.push(Instruction::Unary(operation));
+    }
+
+    pub fn binary(&mut self, operation: ast::BinaryOpcode) {
+        self.instructions.push(Instruction::Binary(operation, self.latches.len()));
+        self.latches.push((false, false));
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<FlatOperation> {
+        let data = reader.data()?;
+
+        let instructions = (0..reader.len()?)
+            .map(|_| match reader.u8()? {
+                0 => Ok(Instruction::Operand(reader.len()?)),
+                1 => Ok(Instruction::Unary(reader.unary_opcode()?)),
+                2 => Ok(Instruction::Binary(reader.binary_opcode()?, reader.len()?)),
+                code => Err(reader.error(&format!("unknown instruction {}", code))),
+            })
+            .collect::<TransformResult<Vec<_>>>()?;
+
+        let operands = reader.exprs()?;
+        let latches = (0..reader.len()?)
+            .map(|_| Ok((reader.bool()?, reader.bool()?)))
+            .collect::<TransformResult<Vec<_>>>()?;
+
+        // Every instruction must find its operands on the stack and leave exactly one value
+        let mut depth = 0usize;
+        for instruction in &instructions {
+            let valid = match *instruction {
+                Instruction::Operand(index) => index < operands.len(),
+                Instruction::Unary(_) => depth >= 1,
+                Instruction::Binary(_, latch) => depth >= 2 && latch < latches.len(),
+            };
+            reader.check(valid, "invalid instruction")?;
+
+            depth = match *instruction {
+                Instruction::Operand(_) => depth + 1,
+                Instruction::Unary(_) => depth,
+                Instruction::Binary(..) => depth - 1,
+            };
+        }
+        reader.check(depth == 1, "unbalanced instructions")?;
+
+        Ok(FlatOperation {
+            data,
+            instructions,
+            operands,
+            latches,
+            done_mode: reader.done_mode()?,
+            signed_comparisons: reader.bool()?,
+            stack: Vec::new(),
+        })
+    }
+}
+
+impl Expr for FlatOperation {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mut stack = mem::take(&mut self.stack);
+        stack.clear();
+
+        for instruction in &self.instructions {
+            match *instruction {
+                Instruction::Operand(index) => {
+                    let operand = &mut self.operands[index];
+                    let value = operand.next(rng);
+                    stack.push((value, operand.done()));
+                }
+                Instruction::Unary(ref operation) => {
+                    let (value, done) = stack.pop().unwrap();
+                    stack.push((apply_unary(operation, value), done));
+                }
+                Instruction::Binary(ref operation, latch) => {
+                    let (r, r_done) = stack.pop().unwrap();
+                    let (l, l_done) = stack.pop().unwrap();
+
+                    let done = match self.done_mode {
+                        BinaryDoneMode::Latched => {
+                            let latch = &mut self.latches[latch];
+                            latch.0 |= l_done;
+                            latch.1 |= r_done;
+                            latch.0 && latch.1
+                        }
+                        BinaryDoneMode::Aligned => l_done && r_done,
+                    };
+
+                    let value = if self.signed_comparisons {
+                        apply_signed_binary(operation, l, r)
+                    } else {
+                        apply_binary(operation, l, r)
+                    };
+                    stack.push((value, done));
+                }
+            }
+        }
+
+        let (value, done) = stack.pop().unwrap();
+        self.data.prev = value;
+        self.data.done = done;
+        self.stack = stack;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let mut stack: Vec<Option<(u32, u32)>> = Vec::new();
+
+        for instruction in &self.instructions {
+            let bounds = match *instruction {
+                Instruction::Operand(index) => self.operands[index].value_bounds(),
+                Instruction::Unary(ref operation) => {
+                    let bounds = stack.pop().unwrap();
+                    bounds.and_then(|bounds| unary_bounds(operation, bounds))
+                }
+                Instruction::Binary(ref operation, _) => {
+                    let r = stack.pop().unwrap();
+                    let l = stack.pop().unwrap();
+                    match (l, r) {
+                        (Some(l), Some(r)) => binary_bounds(operation, l, r),
+                        _ => None,
+                    }
+                }
+            };
+
+            stack.push(bounds);
+        }
+
+        stack.pop().unwrap()
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        let mut stack: Vec<Vec<u32>> = Vec::new();
+
+        for instruction in &self.instructions {
+            let values = match *instruction {
+                Instruction::Operand(index) => self.operands[index].possible_values()?,
+                Instruction::Unary(ref operation) => unary_values(operation, &stack.pop().unwrap()),
+                Instruction::Binary(ref operation, _) => {
+                    let r = stack.pop().unwrap();
+                    let l = stack.pop().unwrap();
+                    binary_values(operation, &l, &r, self.signed_comparisons)?
+                }
+            };
+
+            stack.push(values);
+        }
+
+        stack.pop()
+    }
+
+    /// The operations in postfix order with operands as `$<index>` E.g. `$0 $1 + $2 *`
+    fn label(&self) -> String {
+        let words: Vec<String> = self
+            .instructions
+            .iter()
+            .map(|instruction| match *instruction {
+                Instruction::Operand(index) => format!("${}", index),
+                Instruction::Unary(ref operation) => operation.to_string(),
+                Instruction::Binary(ref operation, _) => operation.to_string(),
+            })
+            .collect();
+
+        words.join(" ")
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::FLAT_OPERATION);
+        writer.data(&self.data);
+
+        writer.len(self.instructions.len());
+        for instruction in &self.instructions {
+            match *instruction {
+                Instruction::Operand(index) => {
+                    writer.u8(0);
+                    writer.len(index);
+                }
+                Instruction::Unary(ref operation) => {
+                    writer.u8(1);
+                    writer.unary_opcode(operation);
+                }
+                Instruction::Binary(ref operation, latch) => {
+                    writer.u8(2);
+                    writer.binary_opcode(operation);
+                    writer.len(latch);
+                }
+            }
+        }
+
+        writer.exprs(&self.operands);
+        writer.len(self.latches.len());
+        for &(l, r) in &self.latches {
+            writer.bool(l);
+            writer.bool(r);
+        }
+        writer.done_mode(self.done_mode);
+        writer.bool(self.signed_comparisons);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.operands.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.operands.iter_mut().collect()
+    }
+}
+
+/// Displays the same as the equivalent tree
+impl fmt::Display for FlatOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut stack: Vec<String> = Vec::new();
+
+        for instruction in &self.instructions {
+            let s = match *instruction {
+                Instruction::Operand(index) => self.operands[index].to_string(),
+                Instruction::Unary(ref operation) => {
+                    format!("{}{}", operation, stack.pop().unwrap())
+                }
+                Instruction::Binary(ref operation, _) => {
+                    let r = stack.pop().unwrap();
+                    let l = stack.pop().unwrap();
+                    format!("({} {} {})", l, operation, r)
+                }
+            };
+
+            stack.push(s);
+        }
+
+        write!(f, "{}", stack.pop().unwrap_or_default())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns the Gray code sequence for a bit width
+///
+/// Cycles through all `2^width` codes E.g. 0, 1, 3, 2, 6, 7, 5, 4 for a width of 3.
+///
+/// # Done
+///
+/// Done on the last code of each cycle.
+#[derive(Clone)]
+pub struct GrayCode {
+    data: ExprData,
+    width: u32,
+    count: u64,
+    n: u64,
+}
+
+impl GrayCode {
+    /// `width` must be in the range [0, 32]
+    pub fn new(width: u32) -> GrayCode {
+        assert!(width <= 32);
+
+        GrayCode {
+            data: Default::default(),
+            width,
+            count: 1 << width,
+            n: 0,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<GrayCode> {
+        let data = reader.data()?;
+        let width = reader.u32()?;
+        reader.check(width <= 32, "gray code width exceeds 32")?;
+
+        let mut gray_code = GrayCode::new(width);
+        gray_code.data = data;
+        gray_code.n = reader.u64()?;
+        reader.check(gray_code.n < gray_code.count, "gray code position out of range")?;
+
+        Ok(gray_code)
+    }
+}
+
+impl Expr for GrayCode {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = (self.n ^ (self.n >> 1)) as u32;
+
+        self.n += 1;
+        self.data.done = self.n == self.count;
+        if self.data.done {
+            self.n = 0;
+        }
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::GRAY_CODE);
+        writer.data(&self.data);
+        writer.u32(self.width);
+        writer.u64(self.n);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for GrayCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GrayCode({})", self.width)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// Returns a rolling hash of the values of its sub-expression
+///
+/// Each value of the sub-expression is folded into a 32-bit FNV-1a hash one byte at a time,
+/// least significant byte first.  Identical sub-expression sequences produce identical hash
+/// sequences.  The hash restarts from the FNV offset basis on reset.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.  The hash is not restarted when done.
+#[derive(Clone)]
+pub struct Hash {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    hash: u32,
+}
+
+impl Hash {
+    pub fn new(expr: Box<dyn Expr>) -> Hash {
+        Hash {
+            data: Default::default(),
+            expr,
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Hash> {
+        Ok(Hash {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            hash: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Hash {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        for byte in self.expr.next(rng).to_le_bytes() {
+            self.hash = (self.hash ^ u32::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+        self.data.done = self.expr.done();
+        self.data.prev = self.hash;
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::HASH);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u32(self.hash);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash({})", self.expr)
+    }
+}
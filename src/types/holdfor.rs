@@ -0,0 +1,141 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns a value of a sub-expression for a random number of calls
+///
+/// Each hold draws a value from the value sub-expression then a count from the count
+/// sub-expression and returns the value `count` times.  A count of 0 discards the value and
+/// redraws both.
+///
+/// Unlike `Repeat`, the count sub-expression is evaluated with its own RNG so the values drawn
+/// by the value sub-expression do not depend on how many random numbers the counts consume.
+///
+/// # Done
+///
+/// Done on the last call of each hold.  The done of the sub-expressions is ignored.
+#[derive(Clone)]
+pub struct HoldFor {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    count_rng: CrateRng,
+    remaining: u32,
+    max_attempts: u32,
+}
+
+impl HoldFor {
+    /// `max_attempts` is the maximum number of times the value and count are redrawn to find a
+    /// non-zero count
+    pub fn new(
+        expr: Box<dyn Expr>,
+        count: Box<dyn Expr>,
+        count_rng: CrateRng,
+        max_attempts: u32,
+    ) -> HoldFor {
+        HoldFor {
+            data: Default::default(),
+            expr,
+            count,
+            count_rng,
+            remaining: 0,
+            max_attempts,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<HoldFor> {
+        Ok(HoldFor {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            count: reader.expr()?,
+            count_rng: reader.rng()?,
+            remaining: reader.u32()?,
+            max_attempts: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for HoldFor {
+    /// Uses a count of 1 if the count sub-expression does not return a non-zero count within
+    /// `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.remaining == 0 {
+            let mut value = self.expr.next(rng);
+            let mut count = self.count.next(&mut self.count_rng);
+
+            let mut attempts = 0;
+            while count == 0 {
+                if attempts == self.max_attempts {
+                    budget::limit_exceeded(
+                        self.max_attempts,
+                        format!("the expression `{}` failed to return a non-zero count", self),
+                    );
+                    break;
+                }
+
+                if !budget::consume() {
+                    break;
+                }
+                value = self.expr.next(rng);
+                count = self.count.next(&mut self.count_rng);
+                attempts += 1;
+            }
+
+            self.data.prev = value;
+            // A count of 0 remains if the budget or the resample limit is exhausted
+            self.remaining = count.max(1);
+        }
+
+        self.remaining -= 1;
+        self.data.done = self.remaining == 0;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    /// The count does not produce values
+    fn is_empty(&self) -> bool {
+        self.expr.is_empty()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::HOLD_FOR);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.expr(&*self.count);
+        writer.rng(&self.count_rng);
+        writer.u32(self.remaining);
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn fork_rngs(&mut self, rng: &mut CrateRng) {
+        self.count_rng = rng.derive();
+
+        for child in self.children_mut() {
+            child.fork_rngs(rng);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr, &*self.count]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr, &mut self.count]
+    }
+}
+
+impl fmt::Display for HoldFor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HoldFor({}, {})", self.expr, self.count)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// Tests the values of its sub-expression for membership in a set
+///
+/// Returns 1 if the value of the sub-expression is a member of the set.  Returns 0 otherwise.  A
+/// value is a member if it is one of the `values` or within one of the inclusive `ranges`.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct In {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    values: HashSet<u32>,
+    ranges: Vec<(u32, u32)>,
+}
+
+impl In {
+    pub fn new(expr: Box<dyn Expr>, values: HashSet<u32>, ranges: Vec<(u32, u32)>) -> In {
+        In {
+            data: Default::default(),
+            expr,
+            values,
+            ranges,
+        }
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.values.contains(&value)
+            || self.ranges.iter().any(|&(l, r)| value >= l && value <= r)
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<In> {
+        Ok(In {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            values: reader.u32s()?.into_iter().collect(),
+            ranges: (0..reader.len()?)
+                .map(|_| Ok((reader.u32()?, reader.u32()?)))
+                .collect::<TransformResult<_>>()?,
+        })
+    }
+}
+
+impl Expr for In {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = self.expr.next(rng);
+
+        self.data.prev = self.contains(value) as u32;
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn label(&self) -> String {
+        let mut values: Vec<&u32> = self.values.iter().collect();
+        values.sort();
+        let values: Vec<String> = values.iter().map(|value| format!("0x{:x}", value)).collect();
+
+        format!("in {{{}}}", values.join(", "))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::IN);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+
+        let mut values: Vec<u32> = self.values.iter().cloned().collect();
+        values.sort_unstable();
+        writer.u32s(&values);
+
+        writer.len(self.ranges.len());
+        for &(l, r) in &self.ranges {
+            writer.u32(l);
+            writer.u32(r);
+        }
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for In {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut values: Vec<&u32> = self.values.iter().collect();
+        values.sort();
+
+        write!(f, "({} in {{", self.expr)?;
+        for value in values {
+            write!(f, "0x{:x}, ", value)?;
+        }
+        for (l, r) in &self.ranges {
+            write!(f, "[0x{:x}, 0x{:x}], ", l, r)?;
+        }
+        write!(f, "}})")
+    }
+}
@@ -0,0 +1,154 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Evaluates a pure sub-expression once and returns the cached value thereafter
+///
+/// A pure sub-expression uses no RNG, references no variables, and has no state so every call to
+/// `next` returns the same value with the same done.  The sub-expression is evaluated on the first
+/// call to `next`.
+#[derive(Clone)]
+pub struct Memo {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    evaluated: bool,
+}
+
+impl Memo {
+    /// `expr` must be pure
+    pub fn new(expr: Box<dyn Expr>) -> Memo {
+        Memo {
+            data: Default::default(),
+            expr,
+            evaluated: false,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Memo> {
+        Ok(Memo {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            evaluated: reader.bool()?,
+        })
+    }
+}
+
+impl Expr for Memo {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if !self.evaluated {
+            self.expr.next(rng);
+            self.data = self.expr.data().clone();
+            self.evaluated = true;
+        }
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        self.expr.possible_values()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::MEMO);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.bool(self.evaluated);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    /// Memoization is an implementation detail so the label is that of the sub-expression
+    fn label(&self) -> String {
+        self.expr.label()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.expr.is_deterministic()
+    }
+
+    /// The sub-expressions of the sub-expression for the same reason as `label`
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.expr.children()
+    }
+
+    /// The sub-expressions of the sub-expression for the same reason as `label`
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.expr.children_mut()
+    }
+}
+
+impl fmt::Display for Memo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.expr.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Seed;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Counts calls to `next`
+    #[derive(Clone)]
+    struct Probe {
+        data: ExprData,
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl Expr for Probe {
+        fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+            self.calls.set(self.calls.get() + 1);
+            self.data.prev = 5;
+            self.data.done = true;
+
+            self.data.prev
+        }
+
+        fn encode(&self, _writer: &mut Writer) {
+            unreachable!()
+        }
+
+        fn data(&self) -> &ExprData {
+            &self.data
+        }
+    }
+
+    impl fmt::Display for Probe {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Probe")
+        }
+    }
+
+    #[test]
+    fn evaluates_once() {
+        let calls = Rc::new(Cell::new(0));
+        let probe = Probe {
+            data: Default::default(),
+            calls: Rc::clone(&calls),
+        };
+        let mut memo = Memo::new(Box::new(probe));
+        let mut rng = Seed::from_u32(0).to_rng();
+
+        assert_eq!(calls.get(), 0);
+        assert_eq!(memo.prev(), 0);
+
+        for _ in 0..10 {
+            assert_eq!(memo.next(&mut rng), 5);
+            assert!(memo.done());
+        }
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(memo.to_string(), "Probe");
+    }
+}
@@ -7,13 +7,81 @@ mod weighted;
 mod variables;
 mod done;
 mod once;
+mod except;
+mod complement;
+mod shuffle;
+mod mulmod;
+mod accumulate;
+mod hash;
+mod replay;
+mod addcarry;
+mod bitorder;
+mod popcount;
+mod membership;
+mod graycode;
+mod modcounter;
+mod ramp;
+mod cdf;
+mod aggregate;
+mod window;
+mod binding;
+mod onehot;
+mod norepeat;
+mod repeat;
+mod holdfor;
+mod zipf;
+mod memo;
+mod dontcare;
+mod avoidrecent;
+mod timestamp;
+mod flat;
+mod reduce;
+mod patternfile;
+mod truncnormal;
+mod poisson;
+mod walk;
 
 pub use self::value::Value;
-pub use self::operation::{Binary, Unary};
+pub use self::operation::{Binary, BinaryDoneMode, Unary};
+pub(crate) use self::operation::checked;
 pub use self::pattern::Pattern;
 pub use self::sequence::Sequence;
 pub use self::range::Range;
 pub use self::weighted::{WeightedWithReplacement, WeightedWithoutReplacement};
-pub use self::variables::{Next, Prev};
+pub use self::variables::{DoneFlag, Next, Prev};
 pub use self::done::Done;
-pub use self::once::Once;
+pub use self::once::{ExhaustedMode, Once};
+pub(crate) use self::once::flag_exhausted;
+pub use self::except::Except;
+pub use self::complement::{Complement, MAX_COMPLEMENT_SIZE};
+pub use self::shuffle::Shuffle;
+pub use self::mulmod::MulMod;
+pub use self::accumulate::Accumulate;
+pub use self::hash::Hash;
+pub use self::replay::Replay;
+pub use self::addcarry::AddCarry;
+pub use self::bitorder::{BitReverse, ByteSwap};
+pub use self::popcount::PopCount;
+pub use self::membership::In;
+pub use self::graycode::GrayCode;
+pub use self::modcounter::ModCounter;
+pub use self::ramp::Ramp;
+pub use self::cdf::Cdf;
+pub use self::aggregate::{Mean, Sum};
+pub use self::window::{WindowMax, WindowMin};
+pub use self::binding::{Binding, Let};
+pub use self::onehot::{Decode, OneHot};
+pub use self::norepeat::NoRepeat;
+pub use self::repeat::Repeat;
+pub use self::holdfor::HoldFor;
+pub use self::zipf::{Zipf, MAX_ZIPF_N};
+pub use self::memo::Memo;
+pub use self::dontcare::DontCare;
+pub use self::avoidrecent::AvoidRecent;
+pub use self::timestamp::Timestamp;
+pub use self::flat::FlatOperation;
+pub use self::reduce::Reduce;
+pub use self::patternfile::PatternFile;
+pub use self::truncnormal::{TruncNormal, MAX_TRUNC_NORMAL_STDDEVS};
+pub use self::poisson::Poisson;
+pub use self::walk::Walk;
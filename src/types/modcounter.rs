@@ -0,0 +1,81 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Counts up from `start` and wraps to 0 at `modulus`
+///
+/// E.g. 2, 3, 0, 1, 2, 3, 0, ... for a start of 2 and a modulus of 4.
+///
+/// # Done
+///
+/// Done on `modulus - 1` (i.e. on the value before each wrap).
+#[derive(Clone)]
+pub struct ModCounter {
+    data: ExprData,
+    start: u32,
+    modulus: u32,
+    count: u32,
+}
+
+impl ModCounter {
+    /// `start` must be less than `modulus`
+    pub fn new(start: u32, modulus: u32) -> ModCounter {
+        assert!(start < modulus);
+
+        ModCounter {
+            data: Default::default(),
+            start,
+            modulus,
+            count: start,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<ModCounter> {
+        let counter = ModCounter {
+            data: reader.data()?,
+            start: reader.u32()?,
+            modulus: reader.u32()?,
+            count: reader.u32()?,
+        };
+        reader.check(
+            counter.start < counter.modulus && counter.count < counter.modulus,
+            "counter out of range",
+        )?;
+
+        Ok(counter)
+    }
+}
+
+impl Expr for ModCounter {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.count;
+        self.data.done = self.count == self.modulus - 1;
+        self.count = if self.data.done { 0 } else { self.count + 1 };
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((0, self.modulus - 1))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::MOD_COUNTER);
+        writer.data(&self.data);
+        writer.u32(self.start);
+        writer.u32(self.modulus);
+        writer.u32(self.count);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for ModCounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ModCounter({}, {})", self.start, self.modulus)
+    }
+}
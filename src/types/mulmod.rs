@@ -0,0 +1,95 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Computes `(a * b) % m` without overflowing the intermediate product
+///
+/// A literal modulus of 0 is rejected at transform time.  A modulus sub-expression that returns 0
+/// is treated as a modulus of 2^32.  I.e. the product wraps as for `a * b`.
+///
+/// # Done
+///
+/// Done latches once all operands have indicated done at least once.  See `Binary`.
+#[derive(Clone)]
+pub struct MulMod {
+    data: ExprData,
+    operands: [Box<dyn Expr>; 3],
+    done: [bool; 3],
+}
+
+impl MulMod {
+    pub fn new(a: Box<dyn Expr>, b: Box<dyn Expr>, m: Box<dyn Expr>) -> MulMod {
+        MulMod {
+            data: Default::default(),
+            operands: [a, b, m],
+            done: [false; 3],
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<MulMod> {
+        let data = reader.data()?;
+        let (a, a_done) = (reader.expr()?, reader.bool()?);
+        let (b, b_done) = (reader.expr()?, reader.bool()?);
+        let (m, m_done) = (reader.expr()?, reader.bool()?);
+
+        Ok(MulMod {
+            data,
+            operands: [a, b, m],
+            done: [a_done, b_done, m_done],
+        })
+    }
+}
+
+impl Expr for MulMod {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let a = self.operands[0].next(rng);
+        let b = self.operands[1].next(rng);
+        let m = self.operands[2].next(rng);
+
+        for (done, operand) in self.done.iter_mut().zip(self.operands.iter()) {
+            *done |= operand.done();
+        }
+        self.data.done = self.done.iter().all(|&done| done);
+
+        self.data.prev = if m == 0 {
+            a.wrapping_mul(b)
+        } else {
+            ((u64::from(a) * u64::from(b)) % u64::from(m)) as u32
+        };
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::MUL_MOD);
+        writer.data(&self.data);
+        for (operand, &done) in self.operands.iter().zip(self.done.iter()) {
+            writer.expr(&**operand);
+            writer.bool(done);
+        }
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.operands.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.operands.iter_mut().collect()
+    }
+}
+
+impl fmt::Display for MulMod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MulMod({}, {}, {})",
+            self.operands[0], self.operands[1], self.operands[2]
+        )
+    }
+}
@@ -0,0 +1,108 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Re-samples its sub-expression until it returns a value different from the previous value
+///
+/// The first value is returned as is.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression on the call that returns the value.
+#[derive(Clone)]
+pub struct NoRepeat {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    started: bool,
+    max_attempts: u32,
+}
+
+impl NoRepeat {
+    /// `max_attempts` is the maximum number of times the sub-expression is re-sampled to find a
+    /// value different from the previous value
+    pub fn new(expr: Box<dyn Expr>, max_attempts: u32) -> NoRepeat {
+        NoRepeat {
+            data: Default::default(),
+            expr,
+            started: false,
+            max_attempts,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<NoRepeat> {
+        Ok(NoRepeat {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            started: reader.bool()?,
+            max_attempts: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for NoRepeat {
+    /// Returns the repeated value if the sub-expression does not return a different value within
+    /// `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mut value = self.expr.next(rng);
+
+        if self.started {
+            let mut attempts = 0;
+            while value == self.data.prev {
+                if attempts == self.max_attempts {
+                    budget::limit_exceeded(
+                        self.max_attempts,
+                        format!(
+                            "the expression `{}` failed to return a value other than 0x{:x}",
+                            self, self.data.prev
+                        ),
+                    );
+                    break;
+                }
+
+                if !budget::consume() {
+                    break;
+                }
+                value = self.expr.next(rng);
+                attempts += 1;
+            }
+        }
+
+        self.started = true;
+        self.data.prev = value;
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::NO_REPEAT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.bool(self.started);
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for NoRepeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NoRepeat({})", self.expr)
+    }
+}
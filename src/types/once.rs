@@ -1,12 +1,66 @@
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
 
+use std::cell::Cell;
 use std::fmt;
 
+thread_local! {
+    /// Whether the evaluation in progress evaluated an exhausted expression in `Flag` mode
+    static EXHAUSTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` and returns whether it evaluated an exhausted expression in `Flag` mode
+///
+/// Calls nest.  An exhausted evaluation is also reported to the outer call.
+pub(crate) fn flag_exhausted<T, F: FnOnce() -> T>(f: F) -> (T, bool) {
+    let outer = EXHAUSTED.with(|exhausted| exhausted.replace(false));
+    let result = f();
+    let exhausted = EXHAUSTED.with(|exhausted| exhausted.replace(outer || exhausted.get()));
+
+    (result, exhausted)
+}
+
+/// What `Once` returns after it is exhausted
+///
+/// Only `Once` is finite.  Other expressions that indicate done (e.g. `Pattern` and `Ramp`) start
+/// over on their own and are not affected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExhaustedMode {
+    /// Returns the last value.  Done remains set.
+    #[default]
+    Hold,
+
+    /// Returns 0.  Done remains set.
+    Zero,
+
+    /// Starts over as if the expression had not been evaluated
+    ///
+    /// Done follows the sub-expression after the first call, i.e. done is cleared until the end
+    /// of each pass.  E.g. `Once(Pattern(1, 2, 3))` returns 1, 2, 3, 1, ... and is done on the
+    /// 1st, 3rd, 6th, ... calls.
+    Wrap,
+
+    /// Returns the last value and flags the evaluation
+    ///
+    /// For catching unintended evaluations of an exhausted expression.  See
+    /// `Variable::exhausted`.  Done remains set.
+    Flag,
+}
+
+/// Returns a single value of the sub-expression
+///
+/// # Done
+///
+/// Done on the first call and after.  What the following calls return depends on the
+/// `ExhaustedMode`.  `Wrap` follows the done of the sub-expression after the first call.
 #[derive(Clone)]
 pub struct Once {
     data: ExprData,
     expr: Box<dyn Expr>,
+    exhausted_mode: ExhaustedMode,
+    /// The sub-expression has been evaluated.  Unlike done, not cleared by `Wrap`.
+    exhausted: bool,
 }
 
 impl Once {
@@ -14,23 +68,81 @@ impl Once {
         Once {
             data: Default::default(),
             expr,
+            exhausted_mode: Default::default(),
+            exhausted: false,
         }
     }
+
+    pub fn with_exhausted_mode(mut self, exhausted_mode: ExhaustedMode) -> Once {
+        self.exhausted_mode = exhausted_mode;
+        self
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Once> {
+        Ok(Once {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            exhausted_mode: match reader.u8()? {
+                0 => ExhaustedMode::Hold,
+                1 => ExhaustedMode::Zero,
+                2 => ExhaustedMode::Wrap,
+                3 => ExhaustedMode::Flag,
+                code => return Err(reader.error(&format!("unknown exhausted mode {}", code))),
+            },
+            exhausted: reader.bool()?,
+        })
+    }
 }
 
 impl Expr for Once {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        if !self.data.done {
+        if !self.exhausted {
             self.data.prev = self.expr.next(rng);
+            self.data.done = true;
+            self.exhausted = true;
+        } else {
+            match self.exhausted_mode {
+                ExhaustedMode::Hold => {}
+                ExhaustedMode::Zero => self.data.prev = 0,
+                ExhaustedMode::Wrap => {
+                    self.data.prev = self.expr.next(rng);
+                    self.data.done = self.expr.done();
+                }
+                ExhaustedMode::Flag => EXHAUSTED.with(|exhausted| exhausted.set(true)),
+            }
         }
-        self.data.done = true;
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let (min, max) = self.expr.value_bounds()?;
+
+        match self.exhausted_mode {
+            ExhaustedMode::Zero => Some((0, max)),
+            _ => Some((min, max)),
+        }
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::ONCE);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u8(self.exhausted_mode as u8);
+        writer.bool(self.exhausted);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
 }
 
 impl fmt::Display for Once {
@@ -0,0 +1,138 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns `1 << index` where `index` is the value of its sub-expression
+///
+/// Only the low 5 bits of the index are used.  Indexes of 32 or more wrap E.g. an index of 33
+/// returns 2.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct OneHot {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+}
+
+impl OneHot {
+    pub fn new(expr: Box<dyn Expr>) -> OneHot {
+        OneHot {
+            data: Default::default(),
+            expr,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<OneHot> {
+        Ok(OneHot {
+            data: reader.data()?,
+            expr: reader.expr()?,
+        })
+    }
+}
+
+impl Expr for OneHot {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = 1u32 << (self.expr.next(rng) & 31);
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::ONE_HOT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for OneHot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OneHot({})", self.expr)
+    }
+}
+
+/// Returns a mask of `width` contiguous set bits starting at bit `index`
+///
+/// `index` is the value of the sub-expression.  As with `OneHot`, only its low 5 bits are used.
+/// Bits that would land above bit 31 are discarded.  A `width` of 1 is equivalent to `OneHot`.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct Decode {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    width: u32,
+}
+
+impl Decode {
+    pub fn new(expr: Box<dyn Expr>, width: u32) -> Decode {
+        Decode {
+            data: Default::default(),
+            expr,
+            width,
+        }
+    }
+
+    /// Named for consistency with the other expressions.  Not the `Decode()` function.
+    #[allow(clippy::self_named_constructors)]
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Decode> {
+        Ok(Decode {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            width: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Decode {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mask = (1u64 << self.width.min(32)) - 1;
+        self.data.prev = (mask << (self.expr.next(rng) & 31)) as u32;
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::DECODE);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.u32(self.width);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for Decode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Decode({}, {})", self.expr, self.width)
+    }
+}
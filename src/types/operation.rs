@@ -1,18 +1,82 @@
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer, MAX_POSSIBLE_VALUES};
 use rvs_parser::ast;
 
+use std::cell::Cell;
 use std::fmt::{self, Write};
 use std::num::Wrapping;
 
+thread_local! {
+    /// Whether the checked evaluation in progress divided by zero, if checked
+    static DIVIDED_BY_ZERO: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with division and modulo by zero evaluating to 0 instead of panicking
+///
+/// Returns `None` if `f` divided by zero.  Used for evaluation at transform time.
+pub(crate) fn checked<T, F: FnOnce() -> T>(f: F) -> Option<T> {
+    let outer = DIVIDED_BY_ZERO.with(|divided| divided.replace(Some(false)));
+    let result = f();
+    let divided = DIVIDED_BY_ZERO.with(|divided| divided.replace(outer));
+
+    match divided {
+        Some(true) => None,
+        _ => Some(result),
+    }
+}
+
+/// Returns the result of a division or modulo by zero
+///
+/// # Panics
+///
+/// * Outside of `checked`
+fn divided_by_zero() -> u32 {
+    DIVIDED_BY_ZERO.with(|divided| match divided.get() {
+        Some(_) => {
+            divided.set(Some(true));
+            0
+        }
+        None => panic!("attempt to divide by zero"),
+    })
+}
+
+/// How a binary operation combines the done of its operands
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BinaryDoneMode {
+    /// Done latches once both operands have indicated done at least once
+    ///
+    /// The operands do not need to indicate done on the same call.  E.g.
+    /// `Pattern(0, 0) + Pattern(0, 0, 0)` is done from the 3rd call onward.
+    #[default]
+    Latched,
+
+    /// Done only on calls where both operands indicate done
+    ///
+    /// Done indicates the operands are aligned at a cycle boundary.  E.g.
+    /// `Pattern(0, 0) + Pattern(0, 0, 0)` is done on every 6th call.
+    Aligned,
+}
+
+/// A binary operation on two sub-expressions
+///
+/// # Done
+///
+/// Depends on the `BinaryDoneMode`.
 #[derive(Clone)]
 pub struct Binary {
     data: ExprData,
     operation: ast::BinaryOpcode,
     operands: (Box<dyn Expr>, Box<dyn Expr>),
     done: (bool, bool),
+    done_mode: BinaryDoneMode,
+    /// Compares operands as two's complement.  See `with_signed_comparisons`.
+    signed_comparisons: bool,
 }
 
+/// A unary operation on a sub-expression
+///
+/// Done follows the operand.
 #[derive(Clone)]
 pub struct Unary {
     data: ExprData,
@@ -27,8 +91,32 @@ impl Binary {
             operation,
             operands: (l, r),
             done: (false, false),
+            done_mode: Default::default(),
+            signed_comparisons: false,
         }
     }
+
+    pub fn with_done_mode(mut self, done_mode: BinaryDoneMode) -> Binary {
+        self.done_mode = done_mode;
+        self
+    }
+
+    /// Interprets the operands of `<` and `>` as two's complement E.g. `0xffffffff < 0` is 1
+    pub fn with_signed_comparisons(mut self, signed_comparisons: bool) -> Binary {
+        self.signed_comparisons = signed_comparisons;
+        self
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Binary> {
+        Ok(Binary {
+            data: reader.data()?,
+            operation: reader.binary_opcode()?,
+            operands: (reader.expr()?, reader.expr()?),
+            done: (reader.bool()?, reader.bool()?),
+            done_mode: reader.done_mode()?,
+            signed_comparisons: reader.bool()?,
+        })
+    }
 }
 
 impl Expr for Binary {
@@ -36,29 +124,211 @@ impl Expr for Binary {
         let l = self.operands.0.next(rng);
         let r = self.operands.1.next(rng);
 
-        self.done.0 |= self.operands.0.done();
-        self.done.1 |= self.operands.1.done();
-        self.data.done = self.done.0 && self.done.1;
-
-        self.data.prev = match self.operation {
-            ast::BinaryOpcode::Or => l | r,
-            ast::BinaryOpcode::Xor => l ^ r,
-            ast::BinaryOpcode::And => l & r,
-            ast::BinaryOpcode::Shl => (Wrapping(l) << (r as usize)).0,
-            ast::BinaryOpcode::Shr => (Wrapping(l) >> (r as usize)).0,
-            ast::BinaryOpcode::Add => (Wrapping(l) + Wrapping(r)).0,
-            ast::BinaryOpcode::Sub => (Wrapping(l) - Wrapping(r)).0,
-            ast::BinaryOpcode::Mul => (Wrapping(l) * Wrapping(r)).0,
-            ast::BinaryOpcode::Div => l / r,
-            ast::BinaryOpcode::Mod => l % r,
+        self.data.done = match self.done_mode {
+            BinaryDoneMode::Latched => {
+                self.done.0 |= self.operands.0.done();
+                self.done.1 |= self.operands.1.done();
+                self.done.0 && self.done.1
+            }
+            BinaryDoneMode::Aligned => self.operands.0.done() && self.operands.1.done(),
+        };
+
+        self.data.prev = if self.signed_comparisons {
+            apply_signed_binary(&self.operation, l, r)
+        } else {
+            apply_binary(&self.operation, l, r)
         };
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        binary_bounds(
+            &self.operation,
+            self.operands.0.value_bounds()?,
+            self.operands.1.value_bounds()?,
+        )
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        binary_values(
+            &self.operation,
+            &self.operands.0.possible_values()?,
+            &self.operands.1.possible_values()?,
+            self.signed_comparisons,
+        )
+    }
+
+    fn label(&self) -> String {
+        self.operation.to_string()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::BINARY);
+        writer.data(&self.data);
+        writer.binary_opcode(&self.operation);
+        writer.expr(&*self.operands.0);
+        writer.expr(&*self.operands.1);
+        writer.bool(self.done.0);
+        writer.bool(self.done.1);
+        writer.done_mode(self.done_mode);
+        writer.bool(self.signed_comparisons);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.operands.0, &*self.operands.1]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.operands.0, &mut self.operands.1]
+    }
+}
+
+/// Returns the result of a binary operation
+///
+/// # Panics
+///
+/// * On division or modulo by zero outside of `checked`
+pub(crate) fn apply_binary(operation: &ast::BinaryOpcode, l: u32, r: u32) -> u32 {
+    match *operation {
+        ast::BinaryOpcode::Or => l | r,
+        ast::BinaryOpcode::Xor => l ^ r,
+        ast::BinaryOpcode::And => l & r,
+        ast::BinaryOpcode::Shl => (Wrapping(l) << (r as usize)).0,
+        ast::BinaryOpcode::Shr => (Wrapping(l) >> (r as usize)).0,
+        ast::BinaryOpcode::Add => (Wrapping(l) + Wrapping(r)).0,
+        ast::BinaryOpcode::Sub => (Wrapping(l) - Wrapping(r)).0,
+        ast::BinaryOpcode::AbsDiff => l.max(r) - l.min(r),
+        ast::BinaryOpcode::Mul => (Wrapping(l) * Wrapping(r)).0,
+        ast::BinaryOpcode::Div => l.checked_div(r).unwrap_or_else(divided_by_zero),
+        ast::BinaryOpcode::Mod => l.checked_rem(r).unwrap_or_else(divided_by_zero),
+        ast::BinaryOpcode::Lt => u32::from(l < r),
+        ast::BinaryOpcode::Gt => u32::from(l > r),
+    }
+}
+
+/// Maps `i32` order to `u32` order and back
+pub(crate) const SIGN_BIAS: u32 = 0x8000_0000;
+
+/// Returns the result of a binary operation with comparisons of two's complement operands
+///
+/// Operations other than comparisons are the same as for `apply_binary`.
+pub(crate) fn apply_signed_binary(operation: &ast::BinaryOpcode, l: u32, r: u32) -> u32 {
+    match *operation {
+        ast::BinaryOpcode::Lt | ast::BinaryOpcode::Gt => {
+            apply_binary(operation, l ^ SIGN_BIAS, r ^ SIGN_BIAS)
+        }
+        _ => apply_binary(operation, l, r),
+    }
+}
+
+/// Returns the bounds of a binary operation given the bounds of its operands
+///
+/// See `Expr::value_bounds`.
+pub(crate) fn binary_bounds(
+    operation: &ast::BinaryOpcode,
+    (l_min, l_max): (u32, u32),
+    (r_min, r_max): (u32, u32),
+) -> Option<(u32, u32)> {
+    match *operation {
+        ast::BinaryOpcode::Or => Some((l_min.max(r_min), fill_bits(l_max | r_max))),
+        ast::BinaryOpcode::Xor => Some((0, fill_bits(l_max | r_max))),
+        ast::BinaryOpcode::And => Some((0, l_max.min(r_max))),
+        ast::BinaryOpcode::Shl if r_max < 32 && l_max.leading_zeros() >= r_max => {
+            Some((l_min << r_min, l_max << r_max))
+        }
+        ast::BinaryOpcode::Shr if r_max < 32 => Some((l_min >> r_max, l_max >> r_min)),
+        ast::BinaryOpcode::Add => Some((l_min.checked_add(r_min)?, l_max.checked_add(r_max)?)),
+        ast::BinaryOpcode::Sub if l_min >= r_max => Some((l_min - r_max, l_max - r_min)),
+        ast::BinaryOpcode::AbsDiff => Some((
+            l_min.saturating_sub(r_max).max(r_min.saturating_sub(l_max)),
+            l_max.max(r_max) - l_min.min(r_min),
+        )),
+        ast::BinaryOpcode::Mul => Some((l_min.checked_mul(r_min)?, l_max.checked_mul(r_max)?)),
+        ast::BinaryOpcode::Div if r_min > 0 => Some((l_min / r_max, l_max / r_min)),
+        ast::BinaryOpcode::Mod if r_min > 0 => Some((0, l_max.min(r_max - 1))),
+        ast::BinaryOpcode::Lt | ast::BinaryOpcode::Gt => Some((0, 1)),
+        _ => None,
+    }
+}
+
+/// Returns the possible values of a binary operation given those of its operands
+///
+/// The result of every combination of operand values.  Returns `None` if there are more than
+/// `MAX_POSSIBLE_VALUES` combinations or for division and modulo by a possible zero.  See
+/// `Expr::possible_values`.
+pub(crate) fn binary_values(
+    operation: &ast::BinaryOpcode,
+    l: &[u32],
+    r: &[u32],
+    signed_comparisons: bool,
+) -> Option<Vec<u32>> {
+    if l.len().checked_mul(r.len())? > MAX_POSSIBLE_VALUES {
+        return None;
+    }
+
+    let divides = matches!(*operation, ast::BinaryOpcode::Div | ast::BinaryOpcode::Mod);
+    if divides && r.contains(&0) {
+        return None;
+    }
+
+    let mut values = Vec::with_capacity(l.len() * r.len());
+    for &l in l {
+        for &r in r {
+            values.push(if signed_comparisons {
+                apply_signed_binary(operation, l, r)
+            } else {
+                apply_binary(operation, l, r)
+            });
+        }
+    }
+    values.sort_unstable();
+    values.dedup();
+
+    Some(values)
+}
+
+/// Returns the result of a unary operation
+pub(crate) fn apply_unary(operation: &ast::UnaryOpcode, operand: u32) -> u32 {
+    match *operation {
+        ast::UnaryOpcode::Inv => !operand,
+        ast::UnaryOpcode::Neg => (Wrapping(!operand) + Wrapping(1)).0,
+    }
+}
+
+/// Returns the bounds of a unary operation given the bounds of its operand
+///
+/// See `Expr::value_bounds`.
+pub(crate) fn unary_bounds(operation: &ast::UnaryOpcode, (min, max): (u32, u32)) -> Option<(u32, u32)> {
+    match *operation {
+        ast::UnaryOpcode::Inv => Some((!max, !min)),
+        ast::UnaryOpcode::Neg if max == 0 => Some((0, 0)),
+        ast::UnaryOpcode::Neg if min > 0 => Some((max.wrapping_neg(), min.wrapping_neg())),
+        ast::UnaryOpcode::Neg => None,
+    }
+}
+
+/// Returns the possible values of a unary operation given those of its operand
+///
+/// See `Expr::possible_values`.
+pub(crate) fn unary_values(operation: &ast::UnaryOpcode, values: &[u32]) -> Vec<u32> {
+    let mut values: Vec<u32> = values.iter().map(|&value| apply_unary(operation, value)).collect();
+    values.sort_unstable();
+    values.dedup();
+
+    values
+}
+
+/// Sets all bits below the highest set bit E.g. 0b1010 becomes 0b1111
+fn fill_bits(value: u32) -> u32 {
+    match value.leading_zeros() {
+        32 => 0,
+        zeros => u32::MAX >> zeros,
+    }
 }
 
 impl fmt::Display for Binary {
@@ -81,6 +351,14 @@ impl Unary {
             operand,
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Unary> {
+        Ok(Unary {
+            data: reader.data()?,
+            operation: reader.unary_opcode()?,
+            operand: reader.expr()?,
+        })
+    }
 }
 
 impl Expr for Unary {
@@ -89,17 +367,41 @@ impl Expr for Unary {
 
         self.data.done = self.operand.done();
 
-        self.data.prev = match self.operation {
-            ast::UnaryOpcode::Inv => !operand,
-            ast::UnaryOpcode::Neg => (Wrapping(!operand) + Wrapping(1)).0,
-        };
+        self.data.prev = apply_unary(&self.operation, operand);
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        unary_bounds(&self.operation, self.operand.value_bounds()?)
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        Some(unary_values(&self.operation, &self.operand.possible_values()?))
+    }
+
+    fn label(&self) -> String {
+        self.operation.to_string()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::UNARY);
+        writer.data(&self.data);
+        writer.unary_opcode(&self.operation);
+        writer.expr(&*self.operand);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.operand]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.operand]
+    }
 }
 
 impl fmt::Display for Unary {
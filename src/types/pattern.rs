@@ -1,13 +1,21 @@
 use std::fmt;
 
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, union_bounds, union_values, Expr, ExprData, Reader, Writer};
 
+/// Returns the values of its sub-expressions in order
+///
+/// # Done
+///
+/// Done is only indicated on the call that completes the last sub-expression.  The following call
+/// starts a new cycle and clears done.
 #[derive(Clone)]
 pub struct Pattern {
     data: ExprData,
     children: Vec<Box<dyn Expr>>,
     current_child: usize,
+    position: Option<usize>,
 }
 
 impl Pattern {
@@ -16,13 +24,27 @@ impl Pattern {
             data: Default::default(),
             children,
             current_child: 0,
+            position: None,
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Pattern> {
+        let pattern = Pattern {
+            data: reader.data()?,
+            children: reader.exprs()?,
+            current_child: reader.len()?,
+            position: reader.option_len()?,
+        };
+        reader.check(pattern.current_child < pattern.children.len(), "pattern index out of range")?;
+
+        Ok(pattern)
+    }
 }
 
 impl Expr for Pattern {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
         self.data.prev = self.children[self.current_child].next(rng);
+        self.position = Some(self.current_child);
 
         if self.children[self.current_child].done() {
             self.current_child = (self.current_child + 1) % self.children.len();
@@ -34,9 +56,38 @@ impl Expr for Pattern {
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        union_bounds(&self.children)
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        union_values(&self.children)
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::PATTERN);
+        writer.data(&self.data);
+        writer.exprs(&self.children);
+        writer.len(self.current_child);
+        writer.option_len(self.position);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    /// Returns the index of the sub-expression that produced the most recent value
+    fn cycle_position(&self) -> Option<usize> {
+        self.position
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.children.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.children.iter_mut().collect()
+    }
 }
 
 impl fmt::Display for Pattern {
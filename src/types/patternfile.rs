@@ -0,0 +1,97 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Returns the values read from a file in order
+///
+/// Equivalent to a `Pattern` of the values but without an expression per value.
+///
+/// # Done
+///
+/// Done is only indicated on the call that returns the last value.  The following call starts a
+/// new cycle and clears done.
+#[derive(Clone)]
+pub struct PatternFile {
+    data: ExprData,
+    /// The path as written.  Only used for display.
+    path: PathBuf,
+    values: Vec<u32>,
+    index: usize,
+    position: Option<usize>,
+}
+
+impl PatternFile {
+    /// # Panics
+    ///
+    /// * If `values` is empty
+    pub fn new(path: PathBuf, values: Vec<u32>) -> PatternFile {
+        assert!(!values.is_empty(), "PatternFile requires at least one value");
+
+        PatternFile {
+            data: Default::default(),
+            path,
+            values,
+            index: 0,
+            position: None,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<PatternFile> {
+        let pattern_file = PatternFile {
+            data: reader.data()?,
+            path: PathBuf::from(reader.string()?),
+            values: reader.u32s()?,
+            index: reader.len()?,
+            position: reader.option_len()?,
+        };
+        reader.check(pattern_file.index < pattern_file.values.len(), "pattern index out of range")?;
+
+        Ok(pattern_file)
+    }
+}
+
+impl Expr for PatternFile {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.values[self.index];
+        self.position = Some(self.index);
+
+        self.index = (self.index + 1) % self.values.len();
+        self.data.done = self.index == 0;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let min = self.values.iter().min()?;
+        let max = self.values.iter().max()?;
+
+        Some((*min, *max))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::PATTERN_FILE);
+        writer.data(&self.data);
+        writer.str(&self.path.to_string_lossy());
+        writer.u32s(&self.values);
+        writer.len(self.index);
+        writer.option_len(self.position);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    /// Returns the index of the value most recently returned
+    fn cycle_position(&self) -> Option<usize> {
+        self.position
+    }
+}
+
+impl fmt::Display for PatternFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PatternFile('{}')", self.path.display())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use rand_distr::{Distribution, Poisson as PoissonDistribution};
+use std::fmt;
+
+/// Returns a Poisson distributed count with a mean of `lambda_num / lambda_den`
+///
+/// Counts greater than `u32::MAX` saturate.  A mean of 0 always returns 0.
+#[derive(Clone)]
+pub struct Poisson {
+    data: ExprData,
+    lambda_num: u32,
+    lambda_den: u32,
+    distribution: Option<PoissonDistribution<f64>>,
+}
+
+impl Poisson {
+    /// `lambda_den` must not be 0
+    pub fn new(lambda_num: u32, lambda_den: u32) -> Poisson {
+        assert!(lambda_den != 0);
+
+        let lambda = f64::from(lambda_num) / f64::from(lambda_den);
+
+        Poisson {
+            data: Default::default(),
+            lambda_num,
+            lambda_den,
+            distribution: if lambda_num == 0 {
+                None
+            } else {
+                // The mean is positive and finite so the distribution is valid
+                Some(PoissonDistribution::new(lambda).unwrap())
+            },
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Poisson> {
+        let data = reader.data()?;
+        let (lambda_num, lambda_den) = (reader.u32()?, reader.u32()?);
+        reader.check(lambda_den != 0, "mean denominator is 0")?;
+
+        let mut poisson = Poisson::new(lambda_num, lambda_den);
+        poisson.data = data;
+
+        Ok(poisson)
+    }
+}
+
+impl Expr for Poisson {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = match self.distribution {
+            Some(ref distribution) => {
+                let count: u64 = distribution.sample(rng);
+                count.min(u64::from(u32::MAX)) as u32
+            }
+            None => 0,
+        };
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    /// Uses the RNG unless the mean is 0
+    fn is_deterministic(&self) -> bool {
+        self.distribution.is_none()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::POISSON);
+        writer.data(&self.data);
+        writer.u32(self.lambda_num);
+        writer.u32(self.lambda_den);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Poisson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Poisson({}, {})", self.lambda_num, self.lambda_den)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns the number of set bits in the value of its sub-expression
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct PopCount {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+}
+
+impl PopCount {
+    pub fn new(expr: Box<dyn Expr>) -> PopCount {
+        PopCount {
+            data: Default::default(),
+            expr,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<PopCount> {
+        Ok(PopCount {
+            data: reader.data()?,
+            expr: reader.expr()?,
+        })
+    }
+}
+
+impl Expr for PopCount {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.expr.next(rng).count_ones();
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((0, 32))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::POP_COUNT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for PopCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PopCount({})", self.expr)
+    }
+}
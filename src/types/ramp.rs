@@ -0,0 +1,102 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::cmp;
+use std::fmt;
+
+/// Returns `steps` evenly spaced values from `start` to `end` inclusive, then repeats
+///
+/// E.g. 0, 25, 50, 75, 100, 0, ... for `Ramp(0, 100, 5)`.  Intermediate values are rounded to the
+/// nearest integer (half away from `start`) so the first value is always `start` and the last is
+/// always `end`.  `start` may be greater than `end` for a descending ramp.
+///
+/// # Done
+///
+/// Done on `end` (i.e. at the end of each ramp).
+#[derive(Clone)]
+pub struct Ramp {
+    data: ExprData,
+    start: u32,
+    end: u32,
+    steps: u32,
+    index: u32,
+}
+
+impl Ramp {
+    /// `steps` must not be 0
+    pub fn new(start: u32, end: u32, steps: u32) -> Ramp {
+        assert!(steps > 0);
+
+        Ramp {
+            data: Default::default(),
+            start,
+            end,
+            steps,
+            index: 0,
+        }
+    }
+
+    fn value(&self, index: u32) -> u32 {
+        if self.steps == 1 {
+            return self.start;
+        }
+
+        let span = (i64::from(self.end) - i64::from(self.start)).unsigned_abs() as u128;
+        let intervals = u128::from(self.steps - 1);
+        let offset = (2 * span * u128::from(index) + intervals) / (2 * intervals);
+        let offset = offset as u32;
+
+        if self.end >= self.start {
+            self.start + offset
+        } else {
+            self.start - offset
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Ramp> {
+        let ramp = Ramp {
+            data: reader.data()?,
+            start: reader.u32()?,
+            end: reader.u32()?,
+            steps: reader.u32()?,
+            index: reader.u32()?,
+        };
+        reader.check(ramp.index < ramp.steps, "ramp index out of range")?;
+
+        Ok(ramp)
+    }
+}
+
+impl Expr for Ramp {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.value(self.index);
+        self.data.done = self.index == self.steps - 1;
+        self.index = if self.data.done { 0 } else { self.index + 1 };
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((cmp::min(self.start, self.end), cmp::max(self.start, self.end)))
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::RAMP);
+        writer.data(&self.data);
+        writer.u32(self.start);
+        writer.u32(self.end);
+        writer.u32(self.steps);
+        writer.u32(self.index);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Ramp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ramp({}, {}, {})", self.start, self.end, self.steps)
+    }
+}
@@ -1,48 +1,265 @@
 use std::fmt;
-use std::u32;
+use rand::Rng;
 use rand::distributions::Distribution;
 use rand::distributions::uniform::Uniform;
+use rand_distr::Normal;
 
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer, MAX_POSSIBLE_VALUES};
+use crate::types::operation::SIGN_BIAS;
 
+use rvs_parser::ast::RangeProfile;
+use rvs_parser::binary::{decode_range_profile, encode_range_profile};
+
+/// Returns a random value in the inclusive range [l, r]
+///
+/// Values are sampled according to a profile.  Linear profiles weight each value by its distance
+/// from the disfavored bound plus one and are sampled via the inverse of their CDF.  Bounds in
+/// reverse order are swapped so `LinearUp` always favors the numerically highest value.
+///
+/// The normal profile is centered on the midpoint with a standard deviation of a sixth of the
+/// number of values.  Draws are rounded to the nearest integer and re-drawn until they fall
+/// within the bounds.  The triangular profile is sampled via the inverse of its continuous CDF.
+///
+/// Signed ranges (see `signed`) order their bounds as `i32` and are always uniform.
 #[derive(Clone)]
 pub struct Range {
     data: ExprData,
     l: u32,
     r: u32,
     range: Uniform<u32>,
+    profile: RangeProfile,
+    signed: bool,
 }
 
 impl Range {
     pub fn new(l: u32, r: u32) -> Range {
-        let limits = if r > l { (l, r) } else { (r, l) };
+        Range::with_profile(l, r, RangeProfile::Uniform)
+    }
+
+    pub fn with_profile(l: u32, r: u32, profile: RangeProfile) -> Range {
+        Range {
+            data: Default::default(),
+            l,
+            r,
+            range: Range::uniform(l, r),
+            profile,
+            signed: false,
+        }
+    }
 
+    /// A uniform range with bounds interpreted as `i32` E.g. `[-5, 5]` returns one of -5..=5 as
+    /// two's complement
+    pub fn signed(l: u32, r: u32) -> Range {
         Range {
             data: Default::default(),
             l,
             r,
-            range: Uniform::new_inclusive(limits.0, limits.1),
+            range: Range::uniform(l ^ SIGN_BIAS, r ^ SIGN_BIAS),
+            profile: RangeProfile::Uniform,
+            signed: true,
         }
     }
+
+    fn bias(&self) -> u32 {
+        if self.signed { SIGN_BIAS } else { 0 }
+    }
+
+    fn uniform(l: u32, r: u32) -> Uniform<u32> {
+        let limits = if r > l { (l, r) } else { (r, l) };
+
+        Uniform::new_inclusive(limits.0, limits.1)
+    }
+
+    /// Returns the bounds as written E.g. `(10, 0)` for `[10, 0]`
+    pub fn bounds(&self) -> (u32, u32) {
+        (self.l, self.r)
+    }
+
+    /// Replaces the bounds
+    ///
+    /// As with `new`, bounds in reverse order are allowed.
+    pub fn set_bounds(&mut self, l: u32, r: u32) {
+        self.l = l;
+        self.r = r;
+        self.range = Range::uniform(l ^ self.bias(), r ^ self.bias());
+    }
+
+    /// Samples the offset from the favored bound of a linear profile
+    fn linear_offset(&self, rng: &mut CrateRng) -> u32 {
+        let (l, r) = if self.r > self.l { (self.l, self.r) } else { (self.r, self.l) };
+        let n = u64::from(r) - u64::from(l) + 1;
+
+        // Value i (counted from the disfavored bound) has weight i + 1 so the cumulative weight
+        // of the values before i is triangle(i).  Find the value whose cumulative weight spans u.
+        let u = Uniform::new(0, triangle(n)).sample(rng);
+        let mut i = ((((8 * u128::from(u) + 1) as f64).sqrt() - 1.0) / 2.0) as u64;
+        while triangle(i) > u {
+            i -= 1;
+        }
+        while triangle(i + 1) <= u {
+            i += 1;
+        }
+
+        (n - 1 - i) as u32
+    }
+
+    /// Samples a value of the normal profile
+    fn normal(&self, l: u32, r: u32, rng: &mut CrateRng) -> u32 {
+        let mean = (f64::from(l) + f64::from(r)) / 2.0;
+        let stddev = (f64::from(r) - f64::from(l) + 1.0) / 6.0;
+        let (l, r) = (f64::from(l), f64::from(r));
+        let normal = Normal::new(mean, stddev).unwrap();
+
+        let mut value = normal.sample(rng).round();
+        while value < l || value > r {
+            if !budget::consume() {
+                break;
+            }
+            value = normal.sample(rng).round();
+        }
+
+        value.clamp(l, r) as u32
+    }
+
+    /// Samples the offset from the low bound of the triangular profile
+    fn triangular_offset(&self, l: u32, r: u32, rng: &mut CrateRng) -> u32 {
+        let n = u64::from(r) - u64::from(l) + 1;
+        let u = rng.gen::<f64>();
+        let x = if u < 0.5 {
+            (u / 2.0).sqrt()
+        } else {
+            1.0 - ((1.0 - u) / 2.0).sqrt()
+        };
+
+        ((x * n as f64) as u64).min(n - 1) as u32
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Range> {
+        let data = reader.data()?;
+        let (l, r) = (reader.u32()?, reader.u32()?);
+        let profile = reader.u8()?;
+        let profile = decode_range_profile(profile)
+            .ok_or_else(|| reader.error(&format!("unknown range profile {}", profile)))?;
+
+        let mut range = if reader.bool()? {
+            Range::signed(l, r)
+        } else {
+            Range::with_profile(l, r, profile)
+        };
+        range.data = data;
+
+        Ok(range)
+    }
+}
+
+/// Returns the sum of 1..=n
+fn triangle(n: u64) -> u64 {
+    (u128::from(n) * (u128::from(n) + 1) / 2) as u64
 }
 
 impl Expr for Range {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        self.data.prev = self.range.sample(rng);
+        let (l, r) = if self.r > self.l { (self.l, self.r) } else { (self.r, self.l) };
+
+        self.data.prev = match self.profile {
+            RangeProfile::Uniform => self.range.sample(rng) ^ self.bias(),
+            RangeProfile::LinearUp => r - self.linear_offset(rng),
+            RangeProfile::LinearDown => l + self.linear_offset(rng),
+            RangeProfile::Normal => self.normal(l, r, rng),
+            RangeProfile::Triangular => l + self.triangular_offset(l, r, rng),
+        };
         self.data.done = true;
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        if self.signed {
+            let (l, r) = (self.l as i32, self.r as i32);
+            let (min, max) = (l.min(r), l.max(r));
+
+            // Bounds spanning zero wrap around in unsigned order
+            return if min < 0 && max >= 0 {
+                Some((u32::MIN, u32::MAX))
+            } else {
+                Some((min as u32, max as u32))
+            };
+        }
+
+        Some((self.l.min(self.r), self.l.max(self.r)))
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        let (l, r) = if self.signed {
+            (self.l ^ SIGN_BIAS, self.r ^ SIGN_BIAS)
+        } else {
+            (self.l, self.r)
+        };
+        let (min, max) = (l.min(r), l.max(r));
+        if u64::from(max - min) >= MAX_POSSIBLE_VALUES as u64 {
+            return None;
+        }
+
+        let mut values: Vec<u32> = if self.signed {
+            (min..=max).map(|value| value ^ SIGN_BIAS).collect()
+        } else {
+            (min..=max).collect()
+        };
+        values.sort_unstable();
+
+        Some(values)
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::RANGE);
+        writer.data(&self.data);
+        writer.u32(self.l);
+        writer.u32(self.r);
+        writer.u8(encode_range_profile(self.profile));
+        writer.bool(self.signed);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn as_range(&self) -> Option<&Range> {
+        Some(self)
+    }
+
+    fn as_range_mut(&mut self) -> Option<&mut Range> {
+        Some(self)
+    }
 }
 
 impl fmt::Display for Range {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[0x{:x}, 0x{:x}]", self.l, self.r)
+        if self.signed {
+            let bound = |bound: u32| {
+                let bound = bound as i32;
+                if bound < 0 {
+                    format!("-0x{:x}", bound.unsigned_abs())
+                } else {
+                    format!("0x{:x}", bound)
+                }
+            };
+
+            return write!(f, "[{}, {}]", bound(self.l), bound(self.r));
+        }
+
+        write!(f, "[0x{:x}, 0x{:x}]", self.l, self.r)?;
+
+        match self.profile {
+            RangeProfile::Uniform => Ok(()),
+            profile => write!(f, ": {}", profile),
+        }
     }
 }
 
@@ -96,6 +313,13 @@ mod tests {
             assert!(values[&u32::MAX] > 0);
         }
 
+        #[test]
+        fn triangle_full_range() {
+            assert_eq!(triangle(0), 0);
+            assert_eq!(triangle(4), 10);
+            assert_eq!(triangle(1 << 32), (1 << 31) * ((1 << 32) + 1));
+        }
+
         #[test]
         #[ignore]
         fn full_range() {
@@ -0,0 +1,167 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+use super::operation::{apply_binary, binary_bounds, binary_values, BinaryDoneMode};
+use rvs_parser::ast;
+
+use std::fmt::{self, Write};
+
+/// A chain of the same associative and commutative binary operation on any number of operands
+///
+/// Equivalent to the tree of `Binary` expressions it was collapsed from (e.g. `a & b & c & d`)
+/// but evaluated in a single loop.  Operands are evaluated left to right as in the tree so
+/// values, done, and RNG draws are identical.
+///
+/// Only valid for operations where the grouping does not change the result.  See
+/// `Reduce::is_reducible`.
+///
+/// # Done
+///
+/// As for the equivalent tree.  For `BinaryDoneMode::Latched`, done once every operand has
+/// indicated done at least once.  For `BinaryDoneMode::Aligned`, done on calls where every
+/// operand indicates done.
+#[derive(Clone)]
+pub struct Reduce {
+    data: ExprData,
+    operation: ast::BinaryOpcode,
+    operands: Vec<Box<dyn Expr>>,
+    /// The done latches of each operand for `BinaryDoneMode::Latched`
+    latches: Vec<bool>,
+    done_mode: BinaryDoneMode,
+}
+
+impl Reduce {
+    /// `operands` must not be empty
+    pub fn new(operation: ast::BinaryOpcode, operands: Vec<Box<dyn Expr>>) -> Reduce {
+        assert!(!operands.is_empty());
+        debug_assert!(Reduce::is_reducible(&operation));
+
+        Reduce {
+            data: Default::default(),
+            operation,
+            latches: vec![false; operands.len()],
+            operands,
+            done_mode: Default::default(),
+        }
+    }
+
+    pub fn with_done_mode(mut self, done_mode: BinaryDoneMode) -> Reduce {
+        self.done_mode = done_mode;
+        self
+    }
+
+    /// Returns true if chains of `operation` can be collapsed into a `Reduce`
+    pub fn is_reducible(operation: &ast::BinaryOpcode) -> bool {
+        matches!(
+            *operation,
+            ast::BinaryOpcode::And
+                | ast::BinaryOpcode::Or
+                | ast::BinaryOpcode::Xor
+                | ast::BinaryOpcode::Add
+                | ast::BinaryOpcode::Mul
+        )
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Reduce> {
+        let data = reader.data()?;
+        let operation = reader.binary_opcode()?;
+        reader.check(Reduce::is_reducible(&operation), "operation is not reducible")?;
+        let operands = reader.exprs()?;
+        reader.check(!operands.is_empty(), "no operands")?;
+
+        Ok(Reduce {
+            data,
+            operation,
+            latches: operands.iter().map(|_| reader.bool()).collect::<TransformResult<_>>()?,
+            operands,
+            done_mode: reader.done_mode()?,
+        })
+    }
+}
+
+impl Expr for Reduce {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mut value = 0;
+        let mut done = true;
+
+        for (i, operand) in self.operands.iter_mut().enumerate() {
+            let operand_value = operand.next(rng);
+            value = if i == 0 {
+                operand_value
+            } else {
+                apply_binary(&self.operation, value, operand_value)
+            };
+
+            done &= match self.done_mode {
+                BinaryDoneMode::Latched => {
+                    self.latches[i] |= operand.done();
+                    self.latches[i]
+                }
+                BinaryDoneMode::Aligned => operand.done(),
+            };
+        }
+
+        self.data.prev = value;
+        self.data.done = done;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let mut operands = self.operands.iter();
+        let first = operands.next().unwrap().value_bounds()?;
+
+        operands.try_fold(first, |bounds, operand| {
+            binary_bounds(&self.operation, bounds, operand.value_bounds()?)
+        })
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        let mut operands = self.operands.iter();
+        let first = operands.next().unwrap().possible_values()?;
+
+        operands.try_fold(first, |values, operand| {
+            binary_values(&self.operation, &values, &operand.possible_values()?, false)
+        })
+    }
+
+    fn label(&self) -> String {
+        self.operation.to_string()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::REDUCE);
+        writer.data(&self.data);
+        writer.binary_opcode(&self.operation);
+        writer.exprs(&self.operands);
+        for &latch in &self.latches {
+            writer.bool(latch);
+        }
+        writer.done_mode(self.done_mode);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.operands.iter().map(|operand| &**operand).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.operands.iter_mut().collect()
+    }
+}
+
+impl fmt::Display for Reduce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('(')?;
+        for (i, operand) in self.operands.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", self.operation)?;
+            }
+            operand.fmt(f)?;
+        }
+        f.write_char(')')
+    }
+}
@@ -0,0 +1,120 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns each value of a sub-expression a random number of times
+///
+/// Each batch draws a count from the count sub-expression then draws a value from the value
+/// sub-expression and returns it `count` times.  A count of 0 is skipped by drawing another
+/// count.
+///
+/// # Done
+///
+/// Done on the last value of a batch if the value sub-expression is done.  The done of the count
+/// sub-expression is ignored.
+#[derive(Clone)]
+pub struct Repeat {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    remaining: u32,
+    max_attempts: u32,
+}
+
+impl Repeat {
+    /// `max_attempts` is the maximum number of times the count is re-sampled to find a non-zero
+    /// count
+    pub fn new(expr: Box<dyn Expr>, count: Box<dyn Expr>, max_attempts: u32) -> Repeat {
+        Repeat {
+            data: Default::default(),
+            expr,
+            count,
+            remaining: 0,
+            max_attempts,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Repeat> {
+        Ok(Repeat {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            count: reader.expr()?,
+            remaining: reader.u32()?,
+            max_attempts: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Repeat {
+    /// Uses a count of 1 if the count sub-expression does not return a non-zero count within
+    /// `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.remaining == 0 {
+            let mut count = self.count.next(rng);
+
+            let mut attempts = 0;
+            while count == 0 {
+                if attempts == self.max_attempts {
+                    budget::limit_exceeded(
+                        self.max_attempts,
+                        format!("the expression `{}` failed to return a non-zero count", self),
+                    );
+                    break;
+                }
+
+                if !budget::consume() {
+                    break;
+                }
+                count = self.count.next(rng);
+                attempts += 1;
+            }
+
+            self.data.prev = self.expr.next(rng);
+            // A count of 0 remains if the budget or the resample limit is exhausted
+            self.remaining = count.max(1);
+        }
+
+        self.remaining -= 1;
+        self.data.done = self.remaining == 0 && self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    /// The count does not produce values
+    fn is_empty(&self) -> bool {
+        self.expr.is_empty()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::REPEAT);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        writer.expr(&*self.count);
+        writer.u32(self.remaining);
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr, &*self.count]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr, &mut self.count]
+    }
+}
+
+impl fmt::Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Repeat({}, {})", self.expr, self.count)
+    }
+}
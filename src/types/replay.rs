@@ -0,0 +1,79 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// Returns previously recorded values in order
+///
+/// Used by `Model::replay` in place of the original expression.  The RNG is not used.
+///
+/// # Done
+///
+/// Done is indicated with the last recorded value.
+///
+/// # Exhaustion
+///
+/// Once all recorded values have been returned, the last recorded value is held and done is
+/// indicated on every call.  Returns 0 if no values were recorded.
+#[derive(Clone)]
+pub struct Replay {
+    data: ExprData,
+    variable_name: String,
+    values: Vec<u32>,
+    index: usize,
+}
+
+impl Replay {
+    pub fn new(variable_name: &str, values: Vec<u32>) -> Replay {
+        Replay {
+            data: Default::default(),
+            variable_name: variable_name.into(),
+            values,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Replay> {
+        let replay = Replay {
+            data: reader.data()?,
+            variable_name: reader.string()?,
+            values: reader.u32s()?,
+            index: reader.len()?,
+        };
+        reader.check(replay.index <= replay.values.len(), "replay index out of range")?;
+
+        Ok(replay)
+    }
+}
+
+impl Expr for Replay {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        if self.index < self.values.len() {
+            self.data.prev = self.values[self.index];
+            self.index += 1;
+        }
+
+        self.data.done = self.index == self.values.len();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::REPLAY);
+        writer.data(&self.data);
+        writer.str(&self.variable_name);
+        writer.u32s(&self.values);
+        writer.len(self.index);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Replay({})", self.variable_name)
+    }
+}
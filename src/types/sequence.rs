@@ -1,6 +1,7 @@
 use crate::types::Value;
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
 
 use std::num::Wrapping;
 use std::fmt;
@@ -13,14 +14,17 @@ pub struct Sequence {
     last: Box<dyn Expr>,
     increment: Box<dyn Expr>,
     compare: bool,
+    count: usize,
+    position: Option<usize>,
 }
 
 impl Sequence {
+    /// Returns `None` if the increment sub-expression returns 0
+    ///
     /// # Panics
     ///
     /// * If `args.len()` < 1 OR > 3
-    /// * If increment is 0
-    pub fn new(mut args: Vec<Box<dyn Expr>>, rng: &mut CrateRng) -> Sequence {
+    pub fn new(mut args: Vec<Box<dyn Expr>>, rng: &mut CrateRng) -> Option<Sequence> {
         let len = args.len();
         let mut drain = args.drain(..);
         let (first, last, increment): (Box<dyn Expr>, Box<dyn Expr>, Box<dyn Expr>) = match len {
@@ -49,30 +53,26 @@ impl Sequence {
             last,
             increment,
             compare: false,
+            count: 0,
+            position: None,
         };
 
-        sequence.init_params(rng);
-
-        sequence
+        if sequence.init_params(rng) {
+            Some(sequence)
+        } else {
+            None
+        }
     }
 
-    fn init_params(&mut self, rng: &mut CrateRng) {
+    /// Returns false if the increment is 0
+    fn init_params(&mut self, rng: &mut CrateRng) -> bool {
         self.init_next(rng);
         self.init_last(rng);
-        self.init_increment(rng);
+        let increment = self.increment.next(rng);
 
         self.compare = self.compare();
-    }
-
-    fn init_increment(&mut self, rng: &mut CrateRng) {
-        let increment = self.increment.next(rng);
 
-        if increment == 0 {
-            panic!(
-                "the increment sub-expression `{}` returned 0 in the expression `{}`",
-                self.increment, self
-            );
-        }
+        increment != 0
     }
 
     fn init_last(&mut self, rng: &mut CrateRng) {
@@ -101,7 +101,26 @@ impl Sequence {
 
     fn done(&mut self, rng: &mut CrateRng) {
         self.data.done = true;
-        self.init_params(rng);
+        self.count = 0;
+        if !self.init_params(rng) {
+            panic!(
+                "the increment sub-expression `{}` returned 0 in the expression `{}`",
+                self.increment, self
+            );
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Sequence> {
+        Ok(Sequence {
+            data: reader.data()?,
+            next: Wrapping(reader.u32()?),
+            first: reader.expr()?,
+            last: reader.expr()?,
+            increment: reader.expr()?,
+            compare: reader.bool()?,
+            count: reader.u64()? as usize,
+            position: reader.option_len()?,
+        })
     }
 }
 
@@ -112,6 +131,8 @@ impl Expr for Sequence {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
         self.data.prev = self.next.0;
         self.data.done = false;
+        self.position = Some(self.count);
+        self.count += 1;
 
         if self.is_last() {
             self.done(rng);
@@ -126,9 +147,34 @@ impl Expr for Sequence {
         self.data.prev
     }
 
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::SEQUENCE);
+        writer.data(&self.data);
+        writer.u32(self.next.0);
+        writer.expr(&*self.first);
+        writer.expr(&*self.last);
+        writer.expr(&*self.increment);
+        writer.bool(self.compare);
+        writer.u64(self.count as u64);
+        writer.option_len(self.position);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    /// Returns the number of values returned before the most recent value in the current cycle
+    fn cycle_position(&self) -> Option<usize> {
+        self.position
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.first, &*self.last, &*self.increment]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.first, &mut self.last, &mut self.increment]
+    }
 }
 
 impl fmt::Display for Sequence {
@@ -0,0 +1,108 @@
+use std::fmt;
+use rand::seq::SliceRandom;
+
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, union_bounds, Expr, ExprData, Reader, Writer};
+
+/// Returns the values of its sub-expressions in a freshly shuffled order each cycle
+///
+/// # Done
+///
+/// Done is indicated on the call that completes the last sub-expression of the current order.
+/// A new order is shuffled at the same time.
+#[derive(Clone)]
+pub struct Shuffle {
+    data: ExprData,
+    children: Vec<Box<dyn Expr>>,
+    order: Vec<usize>,
+    index: usize,
+}
+
+impl Shuffle {
+    pub fn new(children: Vec<Box<dyn Expr>>, rng: &mut CrateRng) -> Shuffle {
+        let mut order: Vec<usize> = (0..children.len()).collect();
+        order.shuffle(rng);
+
+        Shuffle {
+            data: Default::default(),
+            children,
+            order,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Shuffle> {
+        let shuffle = Shuffle {
+            data: reader.data()?,
+            children: reader.exprs()?,
+            order: reader.lens()?,
+            index: reader.len()?,
+        };
+
+        let len = shuffle.children.len();
+        reader.check(shuffle.order.len() == len, "order length differs from child count")?;
+        reader.check(shuffle.order.iter().all(|&index| index < len), "order entry out of range")?;
+        reader.check(shuffle.index < len, "shuffle index out of range")?;
+
+        Ok(shuffle)
+    }
+}
+
+impl Expr for Shuffle {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let child_index = self.order[self.index];
+        self.data.prev = self.children[child_index].next(rng);
+
+        self.data.done = false;
+        if self.children[child_index].done() {
+            self.index += 1;
+            if self.index == self.order.len() {
+                self.index = 0;
+                self.data.done = true;
+                self.order.shuffle(rng);
+            }
+        }
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        union_bounds(&self.children)
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::SHUFFLE);
+        writer.data(&self.data);
+        writer.exprs(&self.children);
+        writer.lens(&self.order);
+        writer.len(self.index);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.children.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.children.iter_mut().collect()
+    }
+}
+
+impl fmt::Display for Shuffle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Shuffle(")?;
+        for child in &self.children {
+            write!(f, "{}, ", child)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,91 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use rand::Rng;
+
+use std::fmt;
+
+/// Returns a running timestamp that advances by a step with random jitter
+///
+/// Each call advances the timestamp by `step + j` where `j` is uniform in `[-jitter, jitter]`.
+/// The increment is clamped to `[0, u32::MAX]` so the timestamp never decreases except on wrap.
+/// The first value is the first increment.  The timestamp wraps on u32 overflow.
+///
+/// # Done
+///
+/// Done is passed through from the step sub-expression.  The timestamp is not cleared when done.
+#[derive(Clone)]
+pub struct Timestamp {
+    data: ExprData,
+    step: Box<dyn Expr>,
+    jitter: Box<dyn Expr>,
+    timestamp: u32,
+}
+
+impl Timestamp {
+    pub fn new(step: Box<dyn Expr>, jitter: Box<dyn Expr>) -> Timestamp {
+        Timestamp {
+            data: Default::default(),
+            step,
+            jitter,
+            timestamp: 0,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Timestamp> {
+        Ok(Timestamp {
+            data: reader.data()?,
+            step: reader.expr()?,
+            jitter: reader.expr()?,
+            timestamp: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Timestamp {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let step = i64::from(self.step.next(rng));
+        let jitter = i64::from(self.jitter.next(rng));
+
+        let increment = step + rng.gen_range(-jitter, jitter + 1);
+        let increment = increment.clamp(0, i64::from(u32::MAX)) as u32;
+
+        self.timestamp = self.timestamp.wrapping_add(increment);
+        self.data.done = self.step.done();
+        self.data.prev = self.timestamp;
+
+        self.data.prev
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::TIMESTAMP);
+        writer.data(&self.data);
+        writer.expr(&*self.step);
+        writer.expr(&*self.jitter);
+        writer.u32(self.timestamp);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.step, &*self.jitter]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.step, &mut self.jitter]
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Timestamp({}, {})", self.step, self.jitter)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{budget, tag, Expr, ExprData, Reader, Writer};
+
+use rand_distr::{Distribution, Normal};
+use std::fmt;
+
+/// The maximum distance of the range of a `TruncNormal` from its mean in standard deviations
+///
+/// A range further from the mean would almost never be drawn.
+pub const MAX_TRUNC_NORMAL_STDDEVS: u32 = 6;
+
+/// Returns a normally distributed value truncated to the range [min, max]
+///
+/// Normal draws are rounded to the nearest integer and re-drawn until they fall within the
+/// range.  The range must be within `MAX_TRUNC_NORMAL_STDDEVS` of the mean.
+#[derive(Clone)]
+pub struct TruncNormal {
+    data: ExprData,
+    mean: u32,
+    stddev: u32,
+    min: u32,
+    max: u32,
+    max_attempts: u32,
+    normal: Normal<f64>,
+}
+
+impl TruncNormal {
+    /// `min` must not be greater than `max`
+    ///
+    /// `max_attempts` is the maximum number of times the normal draw is re-drawn to find a value
+    /// within the range
+    pub fn new(mean: u32, stddev: u32, min: u32, max: u32, max_attempts: u32) -> TruncNormal {
+        assert!(min <= max);
+
+        TruncNormal {
+            data: Default::default(),
+            mean,
+            stddev,
+            min,
+            max,
+            max_attempts,
+            normal: Normal::new(f64::from(mean), f64::from(stddev)).unwrap(),
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<TruncNormal> {
+        let data = reader.data()?;
+        let (mean, stddev) = (reader.u32()?, reader.u32()?);
+        let (min, max) = (reader.u32()?, reader.u32()?);
+        reader.check(min <= max, "bounds in reverse order")?;
+
+        let mut trunc_normal = TruncNormal::new(mean, stddev, min, max, reader.u32()?);
+        trunc_normal.data = data;
+
+        Ok(trunc_normal)
+    }
+}
+
+impl Expr for TruncNormal {
+    /// Clamps the last draw to the range if a value within the range is not drawn within
+    /// `max_attempts`.  See `Transform::set_max_resample_attempts`.
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let (min, max) = (f64::from(self.min), f64::from(self.max));
+
+        let mut value = self.normal.sample(rng).round();
+
+        let mut attempts = 0;
+        while value < min || value > max {
+            if attempts == self.max_attempts {
+                budget::limit_exceeded(
+                    self.max_attempts,
+                    format!(
+                        "the expression `{}` failed to return a value within [{}, {}]",
+                        self, self.min, self.max
+                    ),
+                );
+                break;
+            }
+
+            if !budget::consume() {
+                break;
+            }
+            value = self.normal.sample(rng).round();
+            attempts += 1;
+        }
+
+        self.data.prev = value.clamp(min, max) as u32;
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((self.min, self.max))
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::TRUNC_NORMAL);
+        writer.data(&self.data);
+        writer.u32(self.mean);
+        writer.u32(self.stddev);
+        writer.u32(self.min);
+        writer.u32(self.max);
+        writer.u32(self.max_attempts);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for TruncNormal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TruncNormal({}, {}, {}, {})", self.mean, self.stddev, self.min, self.max)
+    }
+}
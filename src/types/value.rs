@@ -1,31 +1,53 @@
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
 
 use std::fmt;
 
 #[derive(Clone)]
 pub struct Value {
     data: ExprData,
+    value: u32,
 }
 
 impl Value {
     pub fn new(value: u32) -> Value {
         Value {
-            data: ExprData {
-                prev: value,
-                done: false,
-            },
+            data: Default::default(),
+            value,
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Value> {
+        Ok(Value {
+            data: reader.data()?,
+            value: reader.u32()?,
+        })
+    }
 }
 
 impl Expr for Value {
     fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.value;
         self.data.done = true;
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((self.value, self.value))
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        Some(vec![self.value])
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::VALUE);
+        writer.data(&self.data);
+        writer.u32(self.value);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
@@ -33,6 +55,6 @@ impl Expr for Value {
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "0x{:x}", self.data.prev)
+        write!(f, "0x{:x}", self.value)
     }
 }
@@ -1,7 +1,9 @@
 use std::fmt;
+use std::rc::{Rc, Weak};
 
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData, VariableWeak};
+use crate::error::TransformResult;
+use crate::model::{substitution, tag, Expr, ExprData, Model, Reader, VariableWeak, Writer};
 
 #[derive(Clone)]
 pub struct Next {
@@ -17,6 +19,17 @@ pub struct Prev {
     data: ExprData,
 }
 
+/// Returns 1 if the variable indicated done on its most recent call to `next` and 0 otherwise
+///
+/// Reads the variable without advancing it.  Returns 0 before the first call to `next` of the
+/// variable.
+#[derive(Clone)]
+pub struct DoneFlag {
+    variable: VariableWeak,
+    variable_name: String,
+    data: ExprData,
+}
+
 impl Next {
     pub fn new(variable_name: &str, variable: VariableWeak) -> Next {
         Next {
@@ -25,14 +38,28 @@ impl Next {
             data: Default::default(),
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Next> {
+        Ok(Next {
+            data: reader.data()?,
+            variable_name: reader.string()?,
+            variable: Weak::new(),
+        })
+    }
 }
 
 impl Expr for Next {
+    /// Returns the override value without advancing the variable if the variable is overridden.
+    /// See `Variable::next_with_override`.
+    ///
     /// # Errors
     ///
     /// If Weak pointer cannot be upgraded, next() will return previous value.
     fn next(&mut self, _rng: &mut CrateRng) -> u32 {
-        if let Some(variable) = self.variable.upgrade() {
+        if let Some(value) = substitution::lookup(&self.variable_name) {
+            self.data.prev = value;
+            self.data.done = true;
+        } else if let Some(variable) = self.variable.upgrade() {
             self.data.prev = variable.borrow_mut().next();
             self.data.done = variable.borrow().done();
         }
@@ -40,9 +67,35 @@ impl Expr for Next {
         self.data.prev
     }
 
+    /// Returns the bounds of the variable
+    ///
+    /// Returns `None` if the variable is being evaluated (e.g. a reference cycle).
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let variable = self.variable.upgrade()?;
+        let variable = variable.try_borrow().ok()?;
+
+        variable.value_bounds()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        is_deterministic(&self.variable)
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::NEXT);
+        writer.data(&self.data);
+        writer.str(&self.variable_name);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn relink(&mut self, model: &Model) {
+        if let Some(variable) = model.get_unbuilt_variable_by_name(&self.variable_name) {
+            self.variable = Rc::downgrade(variable);
+        }
+    }
 }
 
 impl fmt::Display for Next {
@@ -59,14 +112,28 @@ impl Prev {
             data: Default::default(),
         }
     }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Prev> {
+        Ok(Prev {
+            data: reader.data()?,
+            variable_name: reader.string()?,
+            variable: Weak::new(),
+        })
+    }
 }
 
 impl Expr for Prev {
+    /// Returns the override value if the variable is overridden.  See
+    /// `Variable::next_with_override`.
+    ///
     /// # Errors
     ///
     /// If Weak pointer cannot be upgraded, next() will return previous value.
     fn next(&mut self, _rng: &mut CrateRng) -> u32 {
-        if let Some(variable) = self.variable.upgrade() {
+        if let Some(value) = substitution::lookup(&self.variable_name) {
+            self.data.prev = value;
+            self.data.done = true;
+        } else if let Some(variable) = self.variable.upgrade() {
             self.data.prev = variable.borrow().prev();
             self.data.done = variable.borrow().done();
         }
@@ -74,9 +141,35 @@ impl Expr for Prev {
         self.data.prev
     }
 
+    /// Returns the bounds of the variable extended to 0
+    ///
+    /// The previous value of a variable is 0 before its first call to `next`.
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        let variable = self.variable.upgrade()?;
+        let variable = variable.try_borrow().ok()?;
+
+        variable.value_bounds().map(|(_, max)| (0, max))
+    }
+
+    fn is_deterministic(&self) -> bool {
+        is_deterministic(&self.variable)
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::PREV);
+        writer.data(&self.data);
+        writer.str(&self.variable_name);
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn relink(&mut self, model: &Model) {
+        if let Some(variable) = model.get_unbuilt_variable_by_name(&self.variable_name) {
+            self.variable = Rc::downgrade(variable);
+        }
+    }
 }
 
 impl fmt::Display for Prev {
@@ -84,3 +177,77 @@ impl fmt::Display for Prev {
         write!(f, "{}.prev", self.variable_name)
     }
 }
+
+impl DoneFlag {
+    pub fn new(variable_name: &str, variable: VariableWeak) -> DoneFlag {
+        DoneFlag {
+            variable,
+            variable_name: variable_name.into(),
+            data: Default::default(),
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<DoneFlag> {
+        Ok(DoneFlag {
+            data: reader.data()?,
+            variable_name: reader.string()?,
+            variable: Weak::new(),
+        })
+    }
+}
+
+impl Expr for DoneFlag {
+    /// # Errors
+    ///
+    /// If Weak pointer cannot be upgraded, next() will return previous value.
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        if let Some(variable) = self.variable.upgrade() {
+            self.data.done = variable.borrow().done();
+            self.data.prev = self.data.done as u32;
+        }
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((0, 1))
+    }
+
+    fn is_deterministic(&self) -> bool {
+        is_deterministic(&self.variable)
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::DONE_FLAG);
+        writer.data(&self.data);
+        writer.str(&self.variable_name);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn relink(&mut self, model: &Model) {
+        if let Some(variable) = model.get_unbuilt_variable_by_name(&self.variable_name) {
+            self.variable = Rc::downgrade(variable);
+        }
+    }
+}
+
+impl fmt::Display for DoneFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.done", self.variable_name)
+    }
+}
+
+/// Returns true if the referenced variable is deterministic
+///
+/// Returns false if the variable is being evaluated.
+fn is_deterministic(variable: &VariableWeak) -> bool {
+    match variable.upgrade() {
+        Some(variable) => variable
+            .try_borrow()
+            .is_ok_and(|variable| variable.is_deterministic()),
+        None => true,
+    }
+}
@@ -0,0 +1,85 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::fmt;
+
+/// A random walk that adds the values of its step sub-expression to a position
+///
+/// The position starts at `start` and each value is the position after adding the next step.
+/// Steps are interpreted as `i32` so negative steps (E.g. `[-5, 5]`) walk down.  Ranges anywhere
+/// in the step order their bounds as `i32` E.g. `[-5, 5] + 1`.  The position saturates at 0 and
+/// `u32::MAX`.
+///
+/// # Done
+///
+/// Done is passed through from the step sub-expression.
+#[derive(Clone)]
+pub struct Walk {
+    data: ExprData,
+    start: u32,
+    step: Box<dyn Expr>,
+    position: u32,
+}
+
+impl Walk {
+    pub fn new(start: u32, step: Box<dyn Expr>) -> Walk {
+        Walk {
+            data: Default::default(),
+            start,
+            step,
+            position: start,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Walk> {
+        Ok(Walk {
+            data: reader.data()?,
+            start: reader.u32()?,
+            step: reader.expr()?,
+            position: reader.u32()?,
+        })
+    }
+}
+
+impl Expr for Walk {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let step = self.step.next(rng) as i32;
+
+        self.position = if step < 0 {
+            self.position.saturating_sub(step.unsigned_abs())
+        } else {
+            self.position.saturating_add(step as u32)
+        };
+        self.data.prev = self.position;
+        self.data.done = self.step.done();
+
+        self.data.prev
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::WALK);
+        writer.data(&self.data);
+        writer.u32(self.start);
+        writer.expr(&*self.step);
+        writer.u32(self.position);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.step]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.step]
+    }
+}
+
+impl fmt::Display for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Walk(0x{:x}, {})", self.start, self.step)
+    }
+}
@@ -1,58 +1,176 @@
 use crate::transform::CrateRng;
-use crate::model::{Expr, ExprData};
+use crate::error::TransformResult;
+use crate::model::{budget, tag, union_bounds, union_values, Expr, ExprData, Reader, Writer};
 
 use rand::distributions::Distribution;
 use rand::distributions::uniform::Uniform;
 use rand::seq::SliceRandom;
+use std::collections::HashSet;
 use std::fmt;
 
+/// Samples sub-expressions with replacement
+///
+/// Sub-expressions are selected with probability proportional to their weight via a search of the
+/// cumulative weights.  An empty pool (i.e. no sub-expressions or all weights 0) always returns 0
+/// and is always done.
 #[derive(Clone)]
 pub struct WeightedWithReplacement {
     data: ExprData,
     weights: Vec<u32>,
     children: Vec<Box<dyn Expr>>,
-    range: Uniform<usize>,
-    pool: Vec<usize>,
-    pool_index: Option<usize>,
+    range: Uniform<u64>,
+    /// The sum of the weights up to and including each sub-expression
+    cumulative: Vec<u64>,
+    /// The sub-expression being evaluated until it is done
+    active: Option<usize>,
+    selected: Option<usize>,
+    /// Used instead of the RNG passed to `next` for selection if present
+    selection_rng: Option<CrateRng>,
 }
 
 impl WeightedWithReplacement {
     pub fn new(weights: Vec<u32>, children: Vec<Box<dyn Expr>>) -> WeightedWithReplacement {
-        let pool = populate_pool(&weights);
+        let cumulative: Vec<u64> = weights
+            .iter()
+            .scan(0, |sum, &weight| {
+                *sum += u64::from(weight);
+                Some(*sum)
+            })
+            .collect();
+        let total = cumulative.last().copied().unwrap_or(0);
 
         WeightedWithReplacement {
             data: Default::default(),
             weights,
             children,
-            range: Uniform::new(0, pool.len()),
-            pool,
-            pool_index: None,
+            range: Uniform::new(0, total.max(1)),
+            cumulative,
+            active: None,
+            selected: None,
+            selection_rng: None,
         }
     }
+
+    /// Selects sub-expressions using `selection_rng` instead of the RNG passed to `next`
+    ///
+    /// Decouples selection from the draws made by the sub-expressions.
+    pub fn with_selection_rng(mut self, selection_rng: CrateRng) -> WeightedWithReplacement {
+        self.selection_rng = Some(selection_rng);
+        self
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<WeightedWithReplacement> {
+        let data = reader.data()?;
+        let weights = reader.u32s()?;
+        let children = reader.exprs()?;
+        reader.check(weights.len() == children.len(), "weight count differs from child count")?;
+
+        let mut sample = WeightedWithReplacement::new(weights, children);
+        sample.data = data;
+        sample.active = reader.option_len()?;
+        sample.selected = reader.option_len()?;
+        sample.selection_rng = reader.option_rng()?;
+        reader.check(
+            sample.active.is_none_or(|active| active < sample.children.len()),
+            "active child out of range",
+        )?;
+
+        Ok(sample)
+    }
 }
 
 impl Expr for WeightedWithReplacement {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        let pool_index = match self.pool_index {
-            Some(pool_index) => pool_index,
-            None => self.range.sample(rng),
+        if self.is_empty() {
+            return empty_pool_next(&mut self.data);
+        }
+
+        let child_index = match self.active {
+            Some(child_index) => child_index,
+            None => {
+                let point = self.range.sample(self.selection_rng.as_mut().unwrap_or(rng));
+                self.cumulative.partition_point(|&sum| sum <= point)
+            }
         };
-        let child_index = self.pool[pool_index];
+        self.selected = Some(child_index);
 
         self.data.prev = self.children[child_index].next(rng);
         self.data.done = self.children[child_index].done();
-        self.pool_index = if self.data.done {
+        self.active = if self.data.done {
             None
         } else {
-            Some(pool_index)
+            Some(child_index)
         };
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        if self.is_empty() {
+            return Some((0, 0));
+        }
+
+        union_bounds(&self.children)
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        if self.is_empty() {
+            return Some(vec![0]);
+        }
+
+        union_values(&self.children)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cumulative.last().is_none_or(|&total| total == 0)
+    }
+
+    fn label(&self) -> String {
+        "r{}".into()
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::WEIGHTED_WITH_REPLACEMENT);
+        writer.data(&self.data);
+        writer.u32s(&self.weights);
+        writer.exprs(&self.children);
+        writer.option_len(self.active);
+        writer.option_len(self.selected);
+        writer.option_rng(self.selection_rng.as_ref());
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn last_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    fn weights(&self) -> Option<&[u32]> {
+        Some(&self.weights)
+    }
+
+    fn fork_rngs(&mut self, rng: &mut CrateRng) {
+        fork_selection_rng(&mut self.selection_rng, rng);
+
+        for child in self.children_mut() {
+            child.fork_rngs(rng);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.children.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.children.iter_mut().collect()
+    }
 }
 
 impl fmt::Display for WeightedWithReplacement {
@@ -65,6 +183,12 @@ impl fmt::Display for WeightedWithReplacement {
     }
 }
 
+/// Samples sub-expressions without replacement
+///
+/// Each cycle selects every sub-expression position exactly `weight` times.  Uniqueness applies to
+/// positions, not values.  E.g. `{1, 1, 2}` returns `1` twice per cycle.  Use
+/// `with_distinct_values` to prevent a value from repeating within a cycle.  An empty pool (i.e. no
+/// sub-expressions or all weights 0) always returns 0 and is always done.
 #[derive(Clone)]
 pub struct WeightedWithoutReplacement {
     data: ExprData,
@@ -72,6 +196,25 @@ pub struct WeightedWithoutReplacement {
     children: Vec<Box<dyn Expr>>,
     pool: Vec<usize>,
     pool_index: usize,
+    in_order: bool,
+    /// Never shuffles.  Every cycle follows declaration order.
+    fixed_order: bool,
+    selected: Option<usize>,
+    /// Used instead of the RNG passed to `next` for shuffling if present
+    selection_rng: Option<CrateRng>,
+    distinct_values: Option<DistinctValues>,
+}
+
+/// Tracks values for sampling without replacement with distinct values
+#[derive(Clone)]
+struct DistinctValues {
+    /// The value of each constant sub-expression
+    constants: Vec<Option<u32>>,
+    /// Values of constant sub-expressions.  Never returned by other sub-expressions.
+    reserved: HashSet<u32>,
+    /// Values returned in the current cycle
+    seen: HashSet<u32>,
+    max_attempts: u32,
 }
 
 impl WeightedWithoutReplacement {
@@ -89,35 +232,281 @@ impl WeightedWithoutReplacement {
             children,
             pool,
             pool_index: 0,
+            in_order: false,
+            fixed_order: false,
+            selected: None,
+            selection_rng: None,
+            distinct_values: None,
+        }
+    }
+
+    /// Creates a WeightedWithoutReplacement whose first cycle follows declaration order
+    ///
+    /// Subsequent cycles are shuffled.
+    pub fn new_in_order(
+        weights: Vec<u32>,
+        children: Vec<Box<dyn Expr>>,
+    ) -> WeightedWithoutReplacement {
+        let pool = populate_pool(&weights);
+
+        WeightedWithoutReplacement {
+            data: Default::default(),
+            weights,
+            children,
+            pool,
+            pool_index: 0,
+            in_order: true,
+            fixed_order: false,
+            selected: None,
+            selection_rng: None,
+            distinct_values: None,
         }
     }
+
+    /// Creates a WeightedWithoutReplacement where every cycle follows declaration order
+    ///
+    /// A sub-expression with a weight greater than 1 is selected that many times in a row.
+    pub fn new_fixed_order(
+        weights: Vec<u32>,
+        children: Vec<Box<dyn Expr>>,
+    ) -> WeightedWithoutReplacement {
+        WeightedWithoutReplacement {
+            fixed_order: true,
+            ..WeightedWithoutReplacement::new_in_order(weights, children)
+        }
+    }
+
+    /// Shuffles using `selection_rng` instead of the RNG passed to `next`
+    ///
+    /// Decouples selection from the draws made by the sub-expressions.  Only affects shuffles
+    /// after the first cycle.  The initial shuffle is done by `new`.
+    pub fn with_selection_rng(mut self, selection_rng: CrateRng) -> WeightedWithoutReplacement {
+        self.selection_rng = Some(selection_rng);
+        self
+    }
+
+    /// Prevents a value from repeating within a cycle
+    ///
+    /// `constants` contains the value of each constant sub-expression.  Constant values must be
+    /// unique with a weight of 1.  Other sub-expressions are re-sampled up to `max_attempts` times
+    /// when they return a constant value or a value already returned in the current cycle.
+    /// Exceeding the limit panics.
+    pub fn with_distinct_values(
+        mut self,
+        constants: Vec<Option<u32>>,
+        max_attempts: u32,
+    ) -> WeightedWithoutReplacement {
+        let reserved = constants.iter().flatten().cloned().collect();
+
+        self.distinct_values = Some(DistinctValues {
+            constants,
+            reserved,
+            seen: HashSet::new(),
+            max_attempts,
+        });
+        self
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<WeightedWithoutReplacement> {
+        let sample = WeightedWithoutReplacement {
+            data: reader.data()?,
+            weights: reader.u32s()?,
+            children: reader.exprs()?,
+            pool: reader.lens()?,
+            pool_index: reader.len()?,
+            in_order: reader.bool()?,
+            fixed_order: reader.bool()?,
+            selected: reader.option_len()?,
+            selection_rng: reader.option_rng()?,
+            distinct_values: if reader.bool()? {
+                let constants = (0..reader.len()?)
+                    .map(|_| reader.option_u32())
+                    .collect::<TransformResult<Vec<_>>>()?;
+
+                Some(DistinctValues {
+                    reserved: constants.iter().flatten().cloned().collect(),
+                    constants,
+                    seen: reader.u32s()?.into_iter().collect(),
+                    max_attempts: reader.u32()?,
+                })
+            } else {
+                None
+            },
+        };
+
+        let len = sample.children.len();
+        reader.check(sample.weights.len() == len, "weight count differs from child count")?;
+        reader.check(sample.pool.iter().all(|&index| index < len), "pool entry out of range")?;
+        reader.check(
+            sample.pool.is_empty() || sample.pool_index < sample.pool.len(),
+            "pool index out of range",
+        )?;
+        reader.check(
+            sample.distinct_values.as_ref().is_none_or(|distinct| distinct.constants.len() == len),
+            "constant count differs from child count",
+        )?;
+
+        Ok(sample)
+    }
 }
 
 impl Expr for WeightedWithoutReplacement {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.pool.is_empty() {
+            return empty_pool_next(&mut self.data);
+        }
+
         let child_index = self.pool[self.pool_index];
+        self.selected = Some(child_index);
         self.data.prev = self.children[child_index].next(rng);
 
+        if let Some(ref mut distinct) = self.distinct_values {
+            if distinct.constants[child_index].is_none() {
+                let mut attempts = 0;
+                while distinct.reserved.contains(&self.data.prev)
+                    || distinct.seen.contains(&self.data.prev)
+                {
+                    if attempts == distinct.max_attempts {
+                        budget::limit_exceeded(
+                            distinct.max_attempts,
+                            format!(
+                                "the expression `{}` failed to return a value not yet returned this cycle",
+                                self.children[child_index]
+                            ),
+                        );
+                        break;
+                    }
+
+                    if !budget::consume() {
+                        break;
+                    }
+                    attempts += 1;
+                    self.data.prev = self.children[child_index].next(rng);
+                }
+            }
+
+            distinct.seen.insert(self.data.prev);
+        }
+
         self.data.done = false;
         if self.children[child_index].done() {
             self.pool_index += 1;
             if self.pool_index == self.pool.len() {
                 self.pool_index = 0;
                 self.data.done = true;
-                self.pool.shuffle(rng);
+                if !self.fixed_order {
+                    self.pool.shuffle(self.selection_rng.as_mut().unwrap_or(rng));
+                }
+                if let Some(ref mut distinct) = self.distinct_values {
+                    distinct.seen.clear();
+                }
             }
         }
 
         self.data.prev
     }
 
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        if self.pool.is_empty() {
+            return Some((0, 0));
+        }
+
+        union_bounds(&self.children)
+    }
+
+    fn possible_values(&self) -> Option<Vec<u32>> {
+        if self.pool.is_empty() {
+            return Some(vec![0]);
+        }
+
+        union_values(&self.children)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    fn label(&self) -> String {
+        if self.fixed_order {
+            "s{}".into()
+        } else if self.in_order {
+            "o{}".into()
+        } else if self.distinct_values.is_some() {
+            "u{}".into()
+        } else {
+            "{}".into()
+        }
+    }
+
+    /// Uses the RNG unless fixed order
+    fn is_deterministic(&self) -> bool {
+        self.fixed_order && self.children.iter().all(|child| child.is_deterministic())
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::WEIGHTED_WITHOUT_REPLACEMENT);
+        writer.data(&self.data);
+        writer.u32s(&self.weights);
+        writer.exprs(&self.children);
+        writer.lens(&self.pool);
+        writer.len(self.pool_index);
+        writer.bool(self.in_order);
+        writer.bool(self.fixed_order);
+        writer.option_len(self.selected);
+        writer.option_rng(self.selection_rng.as_ref());
+
+        writer.bool(self.distinct_values.is_some());
+        if let Some(ref distinct) = self.distinct_values {
+            writer.len(distinct.constants.len());
+            for &constant in &distinct.constants {
+                writer.option_u32(constant);
+            }
+
+            let mut seen: Vec<u32> = distinct.seen.iter().cloned().collect();
+            seen.sort_unstable();
+            writer.u32s(&seen);
+            writer.u32(distinct.max_attempts);
+        }
+    }
+
     fn data(&self) -> &ExprData {
         &self.data
     }
+
+    fn last_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    fn weights(&self) -> Option<&[u32]> {
+        Some(&self.weights)
+    }
+
+    fn fork_rngs(&mut self, rng: &mut CrateRng) {
+        fork_selection_rng(&mut self.selection_rng, rng);
+
+        for child in self.children_mut() {
+            child.fork_rngs(rng);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        self.children.iter().map(|child| &**child).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        self.children.iter_mut().collect()
+    }
 }
 
 impl fmt::Display for WeightedWithoutReplacement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.fixed_order {
+            write!(f, "s")?;
+        } else if self.in_order {
+            write!(f, "o")?;
+        } else if self.distinct_values.is_some() {
+            write!(f, "u")?;
+        }
         write!(f, "{{")?;
         for (i, child) in self.children.iter().enumerate() {
             write!(f, "{}: {}, ", self.weights[i], child)?;
@@ -126,6 +515,21 @@ impl fmt::Display for WeightedWithoutReplacement {
     }
 }
 
+/// Implements `next` for an empty pool
+fn empty_pool_next(data: &mut ExprData) -> u32 {
+    data.prev = 0;
+    data.done = true;
+
+    data.prev
+}
+
+/// Replaces a selection RNG, if any, with one derived from `rng`
+fn fork_selection_rng(selection_rng: &mut Option<CrateRng>, rng: &mut CrateRng) {
+    if selection_rng.is_some() {
+        *selection_rng = Some(rng.derive());
+    }
+}
+
 /// Converts weights into a pool of indexes
 ///
 /// The index of each weight is added to the pool <weight> times.
@@ -143,9 +547,40 @@ fn populate_pool(weights: &[u32]) -> Vec<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transform::Seed;
 
     #[test]
     fn test_populate_pool() {
         assert_eq!(populate_pool(&[3, 1, 2]), [0, 0, 0, 1, 2, 2]);
     }
+
+    #[test]
+    fn empty_with_replacement() {
+        let mut rng = Seed::from_u32(0).to_rng();
+        let mut expr = WeightedWithReplacement::new(Vec::new(), Vec::new());
+
+        for _ in 0..4 {
+            assert_eq!(expr.next(&mut rng), 0);
+            assert!(expr.done());
+        }
+        assert_eq!(expr.last_selected(), None);
+        assert_eq!(expr.value_bounds(), Some((0, 0)));
+    }
+
+    #[test]
+    fn empty_without_replacement() {
+        let mut rng = Seed::from_u32(0).to_rng();
+        let mut expr = WeightedWithoutReplacement::new(Vec::new(), Vec::new(), &mut rng);
+
+        for _ in 0..4 {
+            assert_eq!(expr.next(&mut rng), 0);
+            assert!(expr.done());
+        }
+        assert_eq!(expr.last_selected(), None);
+
+        let mut expr = WeightedWithoutReplacement::new_in_order(Vec::new(), Vec::new())
+            .with_distinct_values(Vec::new(), 10);
+        assert_eq!(expr.next(&mut rng), 0);
+        assert!(expr.done());
+    }
 }
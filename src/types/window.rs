@@ -0,0 +1,231 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Tracks the extremum of the last `size` values via a monotonic deque
+///
+/// Holds the candidates for the extremum of the current and future windows with their positions.
+/// Each value is pushed and popped at most once so updates are amortized O(1).
+#[derive(Clone)]
+struct MonotonicWindow {
+    size: u64,
+    /// The position of the next value
+    position: u64,
+    /// Candidates ordered by position.  The front is the extremum.
+    candidates: VecDeque<(u64, u32)>,
+}
+
+impl MonotonicWindow {
+    fn new(size: u32) -> MonotonicWindow {
+        MonotonicWindow {
+            size: u64::from(size),
+            position: 0,
+            candidates: VecDeque::new(),
+        }
+    }
+
+    /// Adds a value and returns the extremum of the window
+    ///
+    /// `supersedes(a, b)` returns true if `a` makes `b` irrelevant as a candidate.
+    fn push(&mut self, value: u32, supersedes: fn(u32, u32) -> bool) -> u32 {
+        while let Some(&(_, back)) = self.candidates.back() {
+            if !supersedes(value, back) {
+                break;
+            }
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.position, value));
+        self.position += 1;
+
+        while let Some(&(position, _)) = self.candidates.front() {
+            if position + self.size >= self.position {
+                break;
+            }
+            self.candidates.pop_front();
+        }
+
+        self.candidates.front().unwrap().1
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u64(self.size);
+        writer.u64(self.position);
+        writer.len(self.candidates.len());
+        for &(position, value) in &self.candidates {
+            writer.u64(position);
+            writer.u32(value);
+        }
+    }
+
+    fn decode(reader: &mut Reader) -> TransformResult<MonotonicWindow> {
+        let window = MonotonicWindow {
+            size: reader.u64()?,
+            position: reader.u64()?,
+            candidates: (0..reader.len()?)
+                .map(|_| Ok((reader.u64()?, reader.u32()?)))
+                .collect::<TransformResult<_>>()?,
+        };
+        reader.check(window.size > 0, "window size is 0")?;
+        reader.check(
+            window.candidates.iter().all(|&(position, _)| position < window.position),
+            "candidate position out of range",
+        )?;
+
+        Ok(window)
+    }
+}
+
+/// Returns the minimum of the last `size` values of a sub-expression
+///
+/// The sub-expression is advanced once per call.  Until `size` values have been drawn, returns the
+/// minimum of the values drawn so far.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct WindowMin {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    window: MonotonicWindow,
+}
+
+/// Returns the maximum of the last `size` values of a sub-expression
+///
+/// The sub-expression is advanced once per call.  Until `size` values have been drawn, returns the
+/// maximum of the values drawn so far.
+///
+/// # Done
+///
+/// Done is passed through from the sub-expression.
+#[derive(Clone)]
+pub struct WindowMax {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    window: MonotonicWindow,
+}
+
+impl WindowMin {
+    /// `size` must be at least 1
+    pub fn new(expr: Box<dyn Expr>, size: u32) -> WindowMin {
+        assert!(size > 0);
+
+        WindowMin {
+            data: Default::default(),
+            expr,
+            window: MonotonicWindow::new(size),
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<WindowMin> {
+        Ok(WindowMin {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            window: MonotonicWindow::decode(reader)?,
+        })
+    }
+}
+
+impl Expr for WindowMin {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = self.expr.next(rng);
+
+        self.data.prev = self.window.push(value, |value, candidate| value <= candidate);
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::WINDOW_MIN);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        self.window.encode(writer);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for WindowMin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WindowMin({}, {})", self.expr, self.window.size)
+    }
+}
+
+impl WindowMax {
+    /// `size` must be at least 1
+    pub fn new(expr: Box<dyn Expr>, size: u32) -> WindowMax {
+        assert!(size > 0);
+
+        WindowMax {
+            data: Default::default(),
+            expr,
+            window: MonotonicWindow::new(size),
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<WindowMax> {
+        Ok(WindowMax {
+            data: reader.data()?,
+            expr: reader.expr()?,
+            window: MonotonicWindow::decode(reader)?,
+        })
+    }
+}
+
+impl Expr for WindowMax {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = self.expr.next(rng);
+
+        self.data.prev = self.window.push(value, |value, candidate| value >= candidate);
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        self.expr.value_bounds()
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::WINDOW_MAX);
+        writer.data(&self.data);
+        writer.expr(&*self.expr);
+        self.window.encode(writer);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+
+    fn children(&self) -> Vec<&dyn Expr> {
+        vec![&*self.expr]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Expr>> {
+        vec![&mut self.expr]
+    }
+}
+
+impl fmt::Display for WindowMax {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WindowMax({}, {})", self.expr, self.window.size)
+    }
+}
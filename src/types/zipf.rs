@@ -0,0 +1,245 @@
+use crate::transform::CrateRng;
+use crate::error::TransformResult;
+use crate::model::{tag, Expr, ExprData, Reader, Writer};
+
+use rand::distributions::Distribution;
+use rand::distributions::uniform::Uniform;
+use std::fmt;
+
+/// The largest number of indices supported by `Zipf`
+///
+/// Bounds the size and precompute cost of the cumulative distribution.
+pub const MAX_ZIPF_N: u32 = 1 << 20;
+
+/// The sum of all integer weights of a `Zipf` distribution
+const TOTAL_WEIGHT: u128 = 1 << 40;
+
+/// The number of fraction bits of the fixed-point logarithms used to build the weights
+const LOG_FRACTION_BITS: u32 = 32;
+
+/// The number of fraction bits of the fixed-point powers used to build the weights
+const FRACTION_BITS: u32 = 62;
+
+/// 1 in the fixed-point format of the powers
+const ONE: u128 = 1 << FRACTION_BITS;
+
+/// Returns an index in the range [1, n] with Zipfian probabilities
+///
+/// Index `k` is returned with probability proportional to `1 / k^s` where `s = s_num / s_den`.
+/// An `s` of 0 is uniform.  The cumulative distribution is precomputed as integer weights and
+/// sampled with a single draw.  Every index keeps a weight of at least 1.  The weights are computed
+/// in fixed point so they are the same on all targets.
+#[derive(Clone)]
+pub struct Zipf {
+    data: ExprData,
+    n: u32,
+    s_num: u32,
+    s_den: u32,
+    cdf: Vec<u64>,
+    range: Uniform<u64>,
+}
+
+impl Zipf {
+    /// `n` must be in the range [1, MAX_ZIPF_N] and `s_den` must be non-zero
+    pub fn new(n: u32, s_num: u32, s_den: u32) -> Zipf {
+        assert!((1..=MAX_ZIPF_N).contains(&n));
+        assert!(s_den != 0);
+
+        // k^-s = 2^-(s * log2(k))
+        let roots = roots();
+        let weights: Vec<u128> = (1..=n)
+            .map(|k| exp2_neg(log2(k) * u128::from(s_num) / u128::from(s_den), &roots))
+            .collect();
+        let sum: u128 = weights.iter().sum();
+
+        let mut cdf = Vec::with_capacity(weights.len());
+        let mut total = 0u64;
+        for weight in weights {
+            total += ((weight * TOTAL_WEIGHT + sum / 2) / sum).max(1) as u64;
+            cdf.push(total);
+        }
+
+        Zipf {
+            data: Default::default(),
+            n,
+            s_num,
+            s_den,
+            cdf,
+            range: Uniform::new(0, total),
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> TransformResult<Zipf> {
+        let data = reader.data()?;
+        let (n, s_num, s_den) = (reader.u32()?, reader.u32()?, reader.u32()?);
+        reader.check((1..=MAX_ZIPF_N).contains(&n), "Zipf n out of range")?;
+        reader.check(s_den != 0, "Zipf exponent denominator is 0")?;
+
+        let mut zipf = Zipf::new(n, s_num, s_den);
+        zipf.data = data;
+
+        Ok(zipf)
+    }
+}
+
+/// Returns log2(k) with `LOG_FRACTION_BITS` fraction bits
+///
+/// The fraction bits are found one at a time by repeated squaring of the mantissa.
+fn log2(k: u32) -> u128 {
+    let integer = 31 - k.leading_zeros();
+    let mut log = u128::from(integer) << LOG_FRACTION_BITS;
+
+    // k / 2^integer in [1, 2)
+    let mut mantissa = (u128::from(k) << FRACTION_BITS) >> integer;
+    for bit in (0..LOG_FRACTION_BITS).rev() {
+        mantissa = (mantissa * mantissa) >> FRACTION_BITS;
+        if mantissa >= 2 * ONE {
+            mantissa >>= 1;
+            log |= 1 << bit;
+        }
+    }
+
+    log
+}
+
+/// Returns 2^(-2^-i) for i in [1, LOG_FRACTION_BITS] with `FRACTION_BITS` fraction bits
+///
+/// Each is the square root of the previous starting from 2^-1.
+fn roots() -> Vec<u128> {
+    let mut root = ONE / 2;
+
+    (0..LOG_FRACTION_BITS)
+        .map(|_| {
+            root = isqrt(root << FRACTION_BITS);
+            root
+        })
+        .collect()
+}
+
+/// Returns 2^-x with `FRACTION_BITS` fraction bits for x with `LOG_FRACTION_BITS` fraction bits
+///
+/// `roots` is the result of `roots`.
+fn exp2_neg(x: u128, roots: &[u128]) -> u128 {
+    let integer = x >> LOG_FRACTION_BITS;
+    if integer >= u128::from(FRACTION_BITS) {
+        return 0;
+    }
+
+    let mut power = ONE;
+    for (i, root) in roots.iter().enumerate() {
+        if (x >> (LOG_FRACTION_BITS - 1 - i as u32)) & 1 == 1 {
+            power = (power * root) >> FRACTION_BITS;
+        }
+    }
+
+    power >> integer
+}
+
+/// Returns the floor of the square root of `x`
+fn isqrt(x: u128) -> u128 {
+    if x == 0 {
+        return 0;
+    }
+
+    // Newton's method converges to the floor from any initial value not below it
+    let mut root = 1 << ((128 - x.leading_zeros()).div_ceil(2));
+    loop {
+        let next = (root + x / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+impl Expr for Zipf {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let u = self.range.sample(rng);
+        let index = self.cdf.partition_point(|&cumulative| cumulative <= u);
+
+        self.data.prev = index as u32 + 1;
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn value_bounds(&self) -> Option<(u32, u32)> {
+        Some((1, self.n))
+    }
+
+    /// Uses the RNG
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.u8(tag::ZIPF);
+        writer.data(&self.data);
+        writer.u32(self.n);
+        writer.u32(self.s_num);
+        writer.u32(self.s_den);
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Zipf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Zipf({}, {}, {})", self.n, self.s_num, self.s_den)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Seed;
+
+    #[test]
+    fn zero_exponent_is_uniform() {
+        let zipf = Zipf::new(4, 0, 1);
+
+        let step = zipf.cdf[0];
+        assert_eq!(zipf.cdf, [step, 2 * step, 3 * step, 4 * step]);
+    }
+
+    #[test]
+    fn fixed_point() {
+        assert_eq!(log2(1), 0);
+        assert_eq!(log2(8), 3 << LOG_FRACTION_BITS);
+        assert_eq!(exp2_neg(3 << LOG_FRACTION_BITS, &roots()), ONE / 8);
+
+        let roots = roots();
+        for &(k, s) in &[(3, 1.0), (10, 1.5), (1000, 0.5)] {
+            let x = (log2(k) as f64 * s) as u128;
+            let expected = f64::from(k).powf(-s);
+            let actual = exp2_neg(x, &roots) as f64 / ONE as f64;
+            assert!((actual - expected).abs() < 1e-9, "{}^-{}: {} != {}", k, s, actual, expected);
+        }
+    }
+
+    #[test]
+    fn integer_exponent() {
+        let zipf = Zipf::new(4, 2, 1);
+
+        // Proportional to 1, 1/4, 1/9, 1/16 to within the precision of the logarithms
+        let sum = 1.0 + 1.0 / 4.0 + 1.0 / 9.0 + 1.0 / 16.0;
+        let expected = [1.0, 1.25, 1.25 + 1.0 / 9.0, sum];
+        for (&cumulative, expected) in zipf.cdf.iter().zip(&expected) {
+            let expected = (expected / sum * TOTAL_WEIGHT as f64).round() as u64;
+            assert!(cumulative.abs_diff(expected) < 1 << 10, "{} != {}", cumulative, expected);
+        }
+    }
+
+    #[test]
+    fn within_bounds() {
+        let mut rng = Seed::from_u32(0).to_rng();
+        let mut zipf = Zipf::new(10, 3, 2);
+
+        for _ in 0..1000 {
+            let value = zipf.next(&mut rng);
+            assert!((1..=10).contains(&value));
+        }
+    }
+}
@@ -0,0 +1,48 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn running_sum() {
+    let a = expr_to_var("Accumulate(Pattern(1, 4, 2))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..6).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 5, 7, 8, 12, 14]);
+}
+
+#[test]
+fn first_value_is_first_draw() {
+    let a = expr_to_var("Accumulate([1, 4])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let first = a.next();
+    assert!((1..=4).contains(&first));
+
+    let mut prev = first;
+    for _ in 0..100 {
+        let value = a.next();
+        assert!((prev + 1..=prev + 4).contains(&value));
+        prev = value;
+    }
+}
+
+#[test]
+fn wraps() {
+    let a = expr_to_var("Accumulate(0xffff_fffe)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xffff_fffe);
+    assert_eq!(a.next(), 0xffff_fffc);
+    assert_eq!(a.next(), 0xffff_fffa);
+}
+
+#[test]
+fn done() {
+    let a = expr_to_var("Accumulate(Pattern(1, 2))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(actual, [(1, false), (3, true), (4, false), (6, true)]);
+}
@@ -0,0 +1,56 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn no_carry() {
+    let a = expr_to_var("AddCarry(0xffff_fffe, 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xffff_ffff);
+    assert!(!a.done());
+}
+
+#[test]
+fn carry() {
+    let a = expr_to_var("AddCarry(0xffff_ffff, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 2);
+    assert!(a.done());
+}
+
+#[test]
+fn done_exactly_on_carry() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = [0, 0xffff_ffff]; b = [0, 0xffff_ffff]; c = AddCarry(a.prev, b.prev);",
+    )
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+    let c = model.get_variable_by_name("c").unwrap();
+
+    let mut carries = 0;
+    for _ in 0..1000 {
+        let x = a.borrow_mut().next();
+        let y = b.borrow_mut().next();
+        let sum = c.borrow_mut().next();
+        let carry = u64::from(x) + u64::from(y) > u64::from(u32::MAX);
+
+        assert_eq!(sum, x.wrapping_add(y));
+        assert_eq!(c.borrow().done(), carry);
+        carries += carry as u32;
+    }
+
+    assert!(carries > 0 && carries < 1000);
+}
+
+#[test]
+fn sequence_of_operands() {
+    let a = expr_to_var("AddCarry(Pattern(0xffff_ffff, 0x8000_0000, 0), Pattern(0, 0x8000_0000, 1))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..3).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(actual, [(0xffff_ffff, false), (0, true), (1, false)]);
+}
@@ -0,0 +1,61 @@
+mod util;
+use crate::util::*;
+
+fn values(expr: &str, count: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn sum() {
+    assert_eq!(
+        values("Sum(Pattern(1, 2, 3, 4), 2)", 4),
+        [(3, false), (7, true), (3, false), (7, true)]
+    );
+    assert_eq!(values("Sum(Pattern(1, 2, 3), 5)", 3), [(9, false), (10, false), (11, true)]);
+}
+
+#[test]
+fn sum_of_range() {
+    let a = expr_to_var("Sum([0, 10], 5)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        assert!(a.next() <= 50);
+    }
+}
+
+#[test]
+fn sum_truncated() {
+    assert_eq!(values("Sum(0xffff_ffff, 2)", 1), [(0xffff_fffe, true)]);
+}
+
+#[test]
+fn mean() {
+    assert_eq!(values("Mean(Pattern(1, 2, 3, 6), 4)", 2), [(3, true), (3, true)]);
+}
+
+#[test]
+fn mean_rounds_down() {
+    assert_eq!(values("Mean(Pattern(1, 2), 2)", 1), [(1, true)]);
+    assert_eq!(values("Mean(Pattern(2, 2, 3), 3)", 1), [(2, true)]);
+}
+
+#[test]
+fn mean_without_overflow() {
+    assert_eq!(values("Mean(Pattern(0xffff_ffff, 0xffff_fffd), 2)", 1), [(0xffff_fffe, true)]);
+}
+
+#[test]
+fn zero_count() {
+    assert_eq!(values("Sum(Pattern(1, 2), 0)", 2), [(0, true), (0, true)]);
+    assert_eq!(values("Mean(Pattern(1, 2), 0)", 2), [(0, true), (0, true)]);
+}
+
+#[test]
+fn display() {
+    assert_eq!(expr_to_var("Sum(1, 2 + 3)").unwrap().borrow().to_string(), "Sum(0x1, 5)");
+    assert_eq!(expr_to_var("Mean(1, 4)").unwrap().borrow().to_string(), "Mean(0x1, 4)");
+}
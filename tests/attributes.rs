@@ -0,0 +1,55 @@
+use std::rc::Rc;
+
+fn parse(s: &str) -> rvs::Result<rvs::Model> {
+    rvs::parse(&Default::default(), s)
+}
+
+#[test]
+fn string_and_number_values() {
+    let model = parse("@field(\"addr\") @width(32) @mask(0xff) a = 5;").unwrap();
+    let a = Rc::clone(model.get_variable_by_name("a").unwrap());
+    let a = a.borrow();
+
+    assert_eq!(a.attribute("field"), Some("addr"));
+    assert_eq!(a.attribute("width"), Some("32"));
+    assert_eq!(a.attribute("mask"), Some("255"));
+}
+
+#[test]
+fn not_found() {
+    let model = parse("@width(32) a = 5; b = 6;").unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap().borrow();
+    assert_eq!(a.attribute("field"), None);
+
+    let b = model.get_variable_by_name("b").unwrap().borrow();
+    assert_eq!(b.attributes().count(), 0);
+}
+
+#[test]
+fn does_not_affect_evaluation() {
+    let model = parse("@width(32) a = 5;").unwrap();
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.next(), 5);
+}
+
+#[test]
+fn duplicate_key() {
+    let error = match parse("@width(32)\n@width(16) a = 5;") {
+        Ok(_) => panic!("expected error"),
+        Err(error) => error,
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "error at 1:1: Duplicate attribute 'width'"
+    );
+}
+
+#[test]
+fn display() {
+    let model = parse("@field(\"addr\")\n@width(32)\na = 5;").unwrap();
+
+    assert_eq!(model.to_string(), "@field(\"addr\") @width(\"32\") a = 0x5;\n");
+}
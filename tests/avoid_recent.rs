@@ -0,0 +1,67 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn no_repeats_within_window() {
+    let a = expr_to_var("AvoidRecent([0, 15], 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..10_000).map(|_| a.next()).collect();
+
+    assert!(values
+        .windows(5)
+        .all(|window| window.iter().cloned().collect::<HashSet<u32>>().len() == 5));
+    assert_eq!(values.iter().cloned().collect::<HashSet<u32>>().len(), 16);
+}
+
+#[test]
+fn warm_up() {
+    let a = expr_to_var("AvoidRecent(Pattern(1, 1, 2, 1, 3, 4, 1), 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 2, 3, 4]);
+}
+
+#[test]
+fn window_of_zero_passes_through() {
+    let a = expr_to_var("AvoidRecent(Pattern(1, 1, 2), 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..3).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 1, 2]);
+}
+
+#[test]
+fn window_not_less_than_value_count_is_an_error() {
+    assert!(expr_to_var("AvoidRecent([0, 15], 15)").is_ok());
+    assert!(expr_to_var("AvoidRecent([0, 15], 16)").is_err());
+    assert!(expr_to_var("AvoidRecent(5, 1)").is_err());
+}
+
+#[test]
+fn window_not_less_than_possible_value_count_is_an_error() {
+    assert!(expr_to_var("AvoidRecent(Pattern(0, 100), 4)").is_err());
+    assert!(expr_to_var("AvoidRecent([0, 3] & 2, 2)").is_err());
+    assert!(expr_to_var("AvoidRecent(Pattern(0, 50, 100), 2)").is_ok());
+}
+
+#[test]
+fn repeats_hit_resample_limit() {
+    let model = model_with("a = AvoidRecent(Pattern(1, 2, 2, 2, 3), 1);", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 1);
+    assert_eq!(a.next(), 2);
+    assert!(next_checked_error(&mut a, 1).starts_with("resample limit of 1 exceeded"));
+    // The last value drawn is returned
+    assert_eq!(a.prev(), 2);
+}
@@ -0,0 +1,39 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn byte_swap() {
+    let a = expr_to_var("ByteSwap(Pattern(0x1234_5678, 0xff, 0))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..3).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0x7856_3412, 0xff00_0000, 0]);
+}
+
+#[test]
+fn bit_reverse() {
+    let a = expr_to_var("BitReverse(Pattern(1, 0x8000_0000, 0xf0, 0xffff_ffff))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0x8000_0000, 1, 0x0f00_0000, 0xffff_ffff]);
+}
+
+#[test]
+fn done_passed_through() {
+    for expr in &["ByteSwap(Pattern(1, 2))", "BitReverse(Pattern(1, 2))"] {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        let actual: Vec<bool> = (0..4)
+            .map(|_| {
+                a.next();
+                a.done()
+            })
+            .collect();
+
+        assert_eq!(actual, [false, true, false, true]);
+    }
+}
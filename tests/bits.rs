@@ -0,0 +1,40 @@
+mod util;
+use crate::util::*;
+
+fn max_value(expr: &str) -> u32 {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..10_000).map(|_| a.next()).max().unwrap()
+}
+
+#[test]
+fn bounds() {
+    assert_eq!(max_value("Bits(1)"), 1);
+    assert_eq!(max_value("Bits(4)"), 0xf);
+    assert_eq!(max_value("Bits(8)"), 0xff);
+}
+
+#[test]
+fn wide_bounds() {
+    let a = expr_to_var("Bits(12)").unwrap();
+    assert_eq!(a.borrow().to_string(), "[0x0, 0xfff]");
+
+    let a = expr_to_var("Bits(32)").unwrap();
+    assert_eq!(a.borrow().to_string(), "[0x0, 0xffffffff]");
+}
+
+#[test]
+fn zero_width() {
+    let a = expr_to_var("Bits(0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..10 {
+        assert_eq!(a.next(), 0);
+    }
+}
+
+#[test]
+fn too_wide() {
+    assert!(expr_to_var("Bits(33)").is_err());
+}
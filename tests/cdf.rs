@@ -0,0 +1,85 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn distribution() {
+    let a = expr_to_var("CDF({10: 0, 30: 1, 100: 2})").unwrap();
+    let mut a = a.borrow_mut();
+
+    let n = 100_000;
+    let mut counts = [0usize; 3];
+    for _ in 0..n {
+        counts[a.next() as usize] += 1;
+        assert!(a.done());
+    }
+
+    let expected = [0.10, 0.20, 0.70];
+    for (count, expected) in counts.iter().zip(&expected) {
+        let actual = *count as f64 / n as f64;
+        assert!((actual - expected).abs() < 0.01, "{} vs {}", actual, expected);
+    }
+}
+
+#[test]
+fn single_entry() {
+    let a = expr_to_var("CDF({100: [5, 6]})").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        assert!((5..=6).contains(&a.next()));
+    }
+}
+
+#[test]
+fn holds_selection_until_done() {
+    let a = expr_to_var("CDF({50: Pattern(1, 2, 3), 100: Pattern(4, 5, 6)})").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..20 {
+        let first = a.next();
+        assert!(first == 1 || first == 4);
+        assert_eq!(a.next(), first + 1);
+        assert_eq!(a.next(), first + 2);
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn weights() {
+    let a = expr_to_var("CDF({10: 0, 30: 1, 100: 2})").unwrap();
+    let a = a.borrow();
+
+    assert_eq!(a.weights(), Some(&[10, 20, 70][..]));
+}
+
+#[test]
+fn non_monotonic_is_an_error() {
+    match expr_to_var("CDF({30: 0, 10: 1, 100: 2})") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "error at 1:1: CDF() cumulative percentages must be increasing but found 10 after 30"
+        ),
+        Ok(_) => panic!("expected an error"),
+    }
+    assert!(expr_to_var("CDF({30: 0, 30: 1, 100: 2})").is_err());
+    assert!(expr_to_var("CDF({0: 0, 100: 1})").is_err());
+}
+
+#[test]
+fn not_ending_at_100_is_an_error() {
+    match expr_to_var("CDF({10: 0, 90: 1})") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "error at 1:1: CDF() cumulative percentages must end at 100 but end at 90"
+        ),
+        Ok(_) => panic!("expected an error"),
+    }
+    assert!(expr_to_var("CDF({10: 0, 110: 1})").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = CDF({10: 0, 100: 1});").unwrap();
+
+    assert_eq!(model.to_string(), "a = CDF({10: 0x0, 100: 0x1, });\n");
+}
@@ -0,0 +1,82 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn basic() {
+    let a = expr_to_var("~{1, 2, 3} within [0, 7]").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [0, 4, 5, 6, 7].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn done() {
+    let a = expr_to_var("~{1} within [0, 7]").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..10 {
+        a.next();
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn members_outside_range_are_ignored() {
+    let a = expr_to_var("~{2, 8, 100} within [0, 3]").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [0, 1, 3].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn reversed_bounds() {
+    let a = expr_to_var("~{5} within [7, 4]").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [4, 6, 7].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn entire_range_is_an_error() {
+    match expr_to_var("~{0, 1, 2} within [0, 2]") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "error at 1:1: the complement excludes every value in the range [0x0, 0x2]"
+        ),
+        Ok(_) => panic!("expected an error"),
+    }
+    assert!(expr_to_var("~{0, 1, 2, 3} within [2, 0]").is_err());
+}
+
+#[test]
+fn range_too_large_is_an_error() {
+    assert!(expr_to_var("~{0} within [0, 0xffffffff]").is_err());
+    assert!(expr_to_var("~{0} within [0, 0xfffff]").is_ok());
+}
+
+#[test]
+fn inverse_is_unchanged() {
+    let a = expr_to_var("~1").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xffff_fffe);
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("~{3, 1, 1, 9} within [0, 7]").unwrap();
+    let a = a.borrow();
+
+    assert_eq!(a.to_string(), "~{0x1, 0x3, } within [0x0, 0x7]");
+}
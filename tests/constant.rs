@@ -0,0 +1,86 @@
+mod util;
+use crate::util::*;
+
+use std::rc::Rc;
+
+#[test]
+fn basic() {
+    let model = model("const MASK = 0xff; a = [0, 0xffff] & MASK;");
+
+    for _ in 0..100 {
+        assert!(next_by_name(&model, "a") <= 0xff);
+    }
+}
+
+#[test]
+fn same_value_for_every_reference() {
+    let model = model("const X = [0, 0xffff_ffff]; a = X - X; b = X;");
+    let x = next_by_name(&model, "X");
+
+    for _ in 0..100 {
+        assert_eq!(next_by_name(&model, "a"), 0);
+        assert_eq!(next_by_name(&model, "b"), x);
+    }
+}
+
+#[test]
+fn does_not_perturb_neighbors() {
+    let without = model("a = [0, 0xffff_ffff]; b = [0, 0xffff_ffff];");
+    let with = model("a = [0, 0xffff_ffff]; const X = [0, 10]; b = [0, 0xffff_ffff];");
+
+    for _ in 0..100 {
+        assert_eq!(next_by_name(&without, "a"), next_by_name(&with, "a"));
+        assert_eq!(next_by_name(&without, "b"), next_by_name(&with, "b"));
+    }
+}
+
+#[test]
+fn references_do_not_advance() {
+    let model = model("const X = Pattern(1, 2, 3); a = X;");
+
+    for _ in 0..4 {
+        assert_eq!(next_by_name(&model, "a"), 1);
+    }
+    assert_eq!(next_by_name(&model, "X"), 1);
+}
+
+#[test]
+fn constant_references_constant() {
+    let model = model("enum E { A = 4, } const X = 2; const Y = X * E::A; a = Y;");
+
+    assert_eq!(next_by_name(&model, "a"), 8);
+}
+
+#[test]
+fn variable_reference() {
+    match rvs::parse(&Default::default(), "a = [0, 10];\nconst X = a + 1;") {
+        Ok(_) => panic!("expected error"),
+        Err(error) => assert_eq!(
+            error.to_string(),
+            "error at 2:1: const expressions can't reference variable 'a'"
+        ),
+    }
+}
+
+#[test]
+fn is_constant() {
+    let model = model("const X = 1; a = 2;");
+
+    assert!(Rc::clone(model.get_variable_by_name("X").unwrap()).borrow().is_constant());
+    assert!(!Rc::clone(model.get_variable_by_name("a").unwrap()).borrow().is_constant());
+}
+
+#[test]
+fn override_with_variable() {
+    let model = model("const X = 1; X = Pattern(2, 3);");
+
+    assert_eq!(next_by_name(&model, "X"), 2);
+    assert_eq!(next_by_name(&model, "X"), 3);
+}
+
+#[test]
+fn display() {
+    let model = model("const X = 1 + 2; a = X;");
+
+    assert_eq!(model.to_string(), "const X = 0x3;\na = 0x3;\n");
+}
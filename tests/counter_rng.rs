@@ -0,0 +1,131 @@
+mod util;
+use crate::util::*;
+
+use std::convert::TryInto;
+
+fn model(s: &str, seed: u32, counter_rng: bool) -> rvs::Model {
+    model_with(s, rvs::Seed::from_u32(seed), |transform| {
+        transform.set_counter_rng(counter_rng)
+    })
+    .unwrap()
+}
+
+fn values(model: &rvs::Model, name: &str, n: usize) -> Vec<u32> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..n).map(|_| variable.next()).collect()
+}
+
+const SOURCE: &str = "
+    a = [0, 0xffffffff];
+    b = r{1, 2, 3, 4, 5};
+    c = {10: 0, 90: [1, 0xffff]};
+    d = a + b;
+";
+
+#[test]
+fn reproducible() {
+    for name in &["a", "b", "c", "d"] {
+        assert_eq!(
+            values(&model(SOURCE, 1, true), name, 100),
+            values(&model(SOURCE, 1, true), name, 100)
+        );
+    }
+}
+
+#[test]
+fn seed() {
+    assert!(values(&model(SOURCE, 1, true), "a", 100) != values(&model(SOURCE, 2, true), "a", 100));
+}
+
+#[test]
+fn differs_from_default() {
+    assert!(values(&model(SOURCE, 1, true), "a", 100) != values(&model(SOURCE, 1, false), "a", 100));
+}
+
+/// Per-variable RNGs make evaluation order irrelevant with or without counter mode
+#[test]
+fn evaluation_order() {
+    for &counter_rng in &[true, false] {
+        let forward = model(SOURCE, 1, counter_rng);
+        let expected_a = values(&forward, "a", 100);
+        let expected_b = values(&forward, "b", 100);
+
+        let reverse = model(SOURCE, 1, counter_rng);
+        let actual_b = values(&reverse, "b", 100);
+        let actual_a = values(&reverse, "a", 100);
+        assert_eq!(expected_a, actual_a);
+        assert_eq!(expected_b, actual_b);
+
+        let interleaved = model(SOURCE, 1, counter_rng);
+        let mut a = interleaved.get_variable_by_name("a").unwrap().borrow_mut();
+        let mut b = interleaved.get_variable_by_name("b").unwrap().borrow_mut();
+        for i in 0..100 {
+            assert_eq!(a.next(), expected_a[i]);
+            assert_eq!(b.next(), expected_b[i]);
+        }
+    }
+}
+
+/// The n-th draw as documented by `Seed::to_counter_rng`
+fn counter_draw(seed: &rvs::Seed, n: u64) -> u32 {
+    let bytes = seed.as_bytes();
+    let low = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let high = u64::from_le_bytes(bytes[8..].try_into().unwrap());
+    let key = low ^ high.rotate_left(32);
+
+    let mut z = key.wrapping_add(n.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    ((z ^ (z >> 31)) >> 32) as u32
+}
+
+#[test]
+fn draw_computed_from_index() {
+    let seed = rvs::Seed::from_u32(1);
+
+    let counter = model("a = [0, 0xffffffff];", 1, true);
+    let mut a = counter.get_variable_by_name("a").unwrap().borrow_mut();
+    for n in 1..=100 {
+        assert_eq!(a.next(), counter_draw(&seed, n));
+    }
+
+    // The default PCG stream is not a function of the index
+    let default = model("a = [0, 0xffffffff];", 1, false);
+    let mut a = default.get_variable_by_name("a").unwrap().borrow_mut();
+    assert!((1..=100).any(|n| a.next() != counter_draw(&seed, n)));
+}
+
+#[test]
+fn range() {
+    let model = model("a = [3, 7];", 1, true);
+    let values = values(&model, "a", 1000);
+
+    assert!(values.iter().all(|value| (3..=7).contains(value)));
+    for expected in 3..=7 {
+        assert!(values.contains(&expected));
+    }
+}
+
+#[test]
+fn reset_rng() {
+    let model = model(SOURCE, 1, true);
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    let expected: Vec<u32> = (0..10).map(|_| variable.next()).collect();
+    variable.reset_rng();
+    let actual: Vec<u32> = (0..10).map(|_| variable.next()).collect();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn fork() {
+    let model = model(SOURCE, 1, true);
+    let variable = model.get_variable_by_name("a").unwrap().borrow();
+
+    let mut fork0 = variable.fork();
+    let mut fork1 = variable.fork();
+    let values0: Vec<u32> = (0..10).map(|_| fork0.next()).collect();
+    let values1: Vec<u32> = (0..10).map(|_| fork1.next()).collect();
+    assert_eq!(values0, values1);
+}
@@ -0,0 +1,98 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn none_before_next() {
+    let a = expr_to_var("Pattern(0, 1)").unwrap();
+    let a = a.borrow();
+
+    assert_eq!(a.current(), None);
+    assert_eq!(a.prev(), 0);
+}
+
+#[test]
+fn equals_last_next() {
+    let exprs = [
+        "5",
+        "0b10xx",
+        "[0, 100]",
+        "[0, 100]: linear_up",
+        "Pattern(1, 2, 3)",
+        "Sequence(4)",
+        "r{1, 2, 3}",
+        "{1: 1, 2: 2, 3: 3}",
+        "o{1, [2, 3]}",
+        "u{1, [2, 9]}",
+        "Choose(4, 5)",
+        "[0, 10] + [0, 10]",
+        "Pattern(1, 2) + Pattern(3, 4, 5)",
+        "~[0, 3]",
+        "-Pattern(1, 2)",
+        "[0, 9] except {3}",
+        "[0, 9] in {3, 4}",
+        "Done(Sequence(3))",
+        "Once([0, 5])",
+        "Shuffle(1, 2, 3)",
+        "Accumulate(Pattern(1, 2))",
+        "Bits(4)",
+        "NoRepeat([0, 3])",
+        "AvoidRecent([0, 7], 3)",
+        "Repeat([0, 9], 3)",
+        "Zipf(10, 1)",
+        "GrayCode(Sequence(8))",
+        "Sum(Pattern(1, 2), 3)",
+        "let x = [0, 9] in x + x",
+    ];
+
+    for expr in exprs.iter() {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        for _ in 0..20 {
+            let value = a.next();
+            assert_eq!(a.current(), Some(value), "{}", expr);
+            assert_eq!(a.prev(), value, "{}", expr);
+        }
+    }
+}
+
+#[test]
+fn references() {
+    let model = rvs::parse(&Default::default(), "a = [0, 9]; b = a.next + 1; c = a.prev;").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+    let c = model.get_variable_by_name("c").unwrap();
+
+    for _ in 0..20 {
+        let value = b.borrow_mut().next();
+        assert_eq!(b.borrow().current(), Some(value));
+        assert_eq!(a.borrow().current(), Some(value - 1));
+
+        let value = c.borrow_mut().next();
+        assert_eq!(c.borrow().current(), Some(value));
+        assert_eq!(a.borrow().current(), Some(value));
+    }
+}
+
+#[test]
+fn value_transform_and_output_mask() {
+    let a = expr_to_var("[0, 0xff]").unwrap();
+    let mut a = a.borrow_mut();
+    a.set_value_transform(Some(std::rc::Rc::new(|value| value + 0x100)));
+    a.set_output_mask(0x1ff);
+
+    let value = a.next();
+    assert!(value & 0x100 != 0);
+    assert_eq!(a.current(), Some(value));
+}
+
+#[test]
+fn reset() {
+    let a = expr_to_var("Pattern(1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    a.reset();
+
+    assert_eq!(a.current(), None);
+}
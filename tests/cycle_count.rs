@@ -0,0 +1,80 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn pattern() {
+    let a = expr_to_var("Pattern(1, 2, 3, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.cycle_count(), 0);
+    let counts: Vec<u64> = (0..10)
+        .map(|_| {
+            a.next();
+            a.cycle_count()
+        })
+        .collect();
+
+    assert_eq!(counts, [0, 0, 0, 1, 1, 1, 1, 2, 2, 2]);
+}
+
+#[test]
+fn sample_without_replacement() {
+    let a = expr_to_var("{1, 2, 3}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..9 {
+        a.next();
+    }
+
+    assert_eq!(a.cycle_count(), 3);
+}
+
+#[test]
+fn not_cyclic() {
+    for expr in &["5", "[0, 3]", "r{1, 2}"] {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        for _ in 0..10 {
+            a.next();
+        }
+
+        assert_eq!(a.cycle_count(), 0, "{}", expr);
+    }
+}
+
+#[test]
+fn reset() {
+    let a = expr_to_var("Pattern(1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..5 {
+        a.next();
+    }
+    assert_eq!(a.cycle_count(), 2);
+
+    a.reset();
+    assert_eq!(a.cycle_count(), 0);
+
+    a.next();
+    a.next();
+    assert_eq!(a.cycle_count(), 1);
+}
+
+#[test]
+fn checkpoint() {
+    let mut model = rvs::parse(&Default::default(), "a = Pattern(1, 2);").unwrap();
+    let a = std::rc::Rc::clone(model.get_variable_by_name("a").unwrap());
+
+    a.borrow_mut().next();
+    a.borrow_mut().next();
+    model.checkpoint("one").unwrap();
+
+    for _ in 0..4 {
+        a.borrow_mut().next();
+    }
+    assert_eq!(a.borrow().cycle_count(), 3);
+
+    assert!(model.restore("one"));
+    assert_eq!(a.borrow().cycle_count(), 1);
+}
@@ -0,0 +1,51 @@
+mod util;
+use crate::util::*;
+
+fn positions(expr: &str, count: usize) -> Vec<Option<usize>> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count)
+        .map(|_| {
+            a.next();
+            a.cycle_position()
+        })
+        .collect()
+}
+
+#[test]
+fn pattern() {
+    assert_eq!(
+        positions("Pattern(5, 6, 7, 8)", 8),
+        [0, 1, 2, 3, 0, 1, 2, 3].iter().map(|&p| Some(p)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn pattern_of_multi_value_children() {
+    assert_eq!(
+        positions("Pattern(Pattern(1, 2), 3)", 6),
+        [0, 0, 1, 0, 0, 1].iter().map(|&p| Some(p)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn sequence() {
+    assert_eq!(
+        positions("Sequence(10, 16, 2)", 8),
+        [0, 1, 2, 3, 0, 1, 2, 3].iter().map(|&p| Some(p)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn before_next() {
+    let a = expr_to_var("Pattern(1, 2)").unwrap();
+
+    assert_eq!(a.borrow().cycle_position(), None);
+}
+
+#[test]
+fn not_cyclic() {
+    assert_eq!(positions("[0, 3]", 2), [None, None]);
+    assert_eq!(positions("r{1, 2}", 2), [None, None]);
+}
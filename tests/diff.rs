@@ -0,0 +1,40 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn equivalent_sources_are_equal() {
+    let a = model("a = 1+2;");
+    let b = model("a = (1)+(2);");
+
+    assert!(a.structurally_eq(&b));
+    assert!(a.diff(&b).is_empty());
+}
+
+#[test]
+fn ignores_evaluation_state() {
+    let a = model("a = [0, 10];");
+    let b = model("a = [0, 10];");
+
+    for _ in 0..5 {
+        a.get_variable_by_name("a").unwrap().borrow_mut().next();
+    }
+
+    assert!(a.structurally_eq(&b));
+}
+
+#[test]
+fn different_expressions_differ() {
+    let a = model("a = 1+2;");
+    let b = model("a = 2+1;");
+
+    assert!(!a.structurally_eq(&b));
+    assert_eq!(a.diff(&b), vec!["a: (0x1 + 0x2) != (0x2 + 0x1)"]);
+}
+
+#[test]
+fn missing_variables_differ() {
+    let a = model("a = 1; b = 2;");
+    let b = model("a = 1; c = 3;");
+
+    assert_eq!(a.diff(&b), vec!["b: only in left", "c: only in right"]);
+}
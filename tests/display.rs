@@ -11,3 +11,24 @@ fn precendence() {
 
     assert_eq!(model.to_string(), "a = ((0xa + 0x6) * 0x8);\n");
 }
+
+#[test]
+fn in_order_without_replacement() {
+    let model = rvs::parse(&Default::default(), "a = o{1, 2: 2};").unwrap();
+
+    assert_eq!(model.to_string(), "a = o{1: 0x1, 2: 0x2, };\n");
+}
+
+#[test]
+fn distinct_values_without_replacement() {
+    let model = rvs::parse(&Default::default(), "a = u{1, [2, 3]};").unwrap();
+
+    assert_eq!(model.to_string(), "a = u{1: 0x1, 1: [0x2, 0x3], };\n");
+}
+
+#[test]
+fn abs_diff() {
+    let model = rvs::parse(&Default::default(), "a = 1 <-> 2;").unwrap();
+
+    assert_eq!(model.to_string(), "a = (0x1 <-> 0x2);\n");
+}
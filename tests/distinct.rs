@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+#[test]
+fn values_are_distinct() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = [0, 3]; b = [0, 3]; c = [0, 3]; distinct(a, b, c);",
+    )
+    .unwrap();
+
+    for _ in 0..100 {
+        let values = model.next_group(&["a", "b", "c"]).unwrap();
+        let unique: HashSet<u32> = values.iter().cloned().collect();
+        assert_eq!(unique.len(), 3, "{:?}", values);
+        assert!(values.iter().all(|&value| value <= 3));
+    }
+}
+
+#[test]
+fn members_outside_group_ignored() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = 1; b = [0, 1]; c = 1; distinct(a, b, c);",
+    )
+    .unwrap();
+
+    for _ in 0..10 {
+        assert_eq!(model.next_group(&["a", "b"]).unwrap(), vec![1, 0]);
+    }
+}
+
+#[test]
+fn overlapping_constraints() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = 1; b = [0, 2]; c = 2; distinct(a, b); distinct(c, b);",
+    )
+    .unwrap();
+
+    for _ in 0..100 {
+        assert_eq!(model.next_group(&["a", "b", "c"]).unwrap(), vec![1, 0, 2]);
+    }
+}
+
+fn constraint_error(s: &str, names: &[&str]) -> String {
+    let model = rvs::parse(&Default::default(), s).unwrap();
+
+    match model.next_group(names) {
+        Err(rvs::Error::Constraint(e)) => e.to_string(),
+        _ => panic!("expected a constraint error"),
+    }
+}
+
+#[test]
+fn infeasible() {
+    let s = "a = [0, 1]; b = [0, 1]; c = [0, 1]; distinct(a, b, c);";
+    assert_eq!(
+        constraint_error(s, &["a", "b", "c"]),
+        "could not satisfy distinct(a, b, c): the variables can't return distinct values"
+    );
+
+    let s = "a = 1; b = {1, 2}; c = Pattern(2, 1); d = [0, 9]; distinct(a, b, c, d);";
+    assert_eq!(
+        constraint_error(s, &["a", "b", "c", "d"]),
+        "could not satisfy distinct(a, b, c, d): the variables can't return distinct values"
+    );
+}
+
+#[test]
+fn infeasible_detected_before_evaluation() {
+    let model =
+        rvs::parse(&Default::default(), "a = Pattern(1, 2); b = 1; c = 1; distinct(b, c);").unwrap();
+
+    assert!(model.next_group(&["a", "b", "c"]).is_err());
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow().current(), None);
+}
+
+#[test]
+fn feasible_by_matching() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = 1; b = {1, 2}; c = Pattern(2, 3); distinct(a, b, c);",
+    )
+    .unwrap();
+
+    for _ in 0..10 {
+        assert_eq!(model.next_group(&["a", "b", "c"]).unwrap()[..2], [1, 2]);
+    }
+}
+
+#[test]
+fn resample_limit() {
+    assert_eq!(
+        constraint_error("a = [0, 1]; b = a.prev; distinct(a, b);", &["a", "b"]),
+        "resample limit of 10000 exceeded: could not satisfy distinct(a, b)"
+    );
+}
+
+#[test]
+fn unknown_variable_in_group() {
+    let model = rvs::parse(&Default::default(), "a = 1;").unwrap();
+
+    assert!(model.next_group(&["b"]).is_err());
+}
+
+#[test]
+fn unknown_variable_in_constraint() {
+    match rvs::parse(&Default::default(), "a = 1; distinct(a, b);") {
+        Err(rvs::Error::Transform(e)) => {
+            assert!(e.to_string().contains("distinct references unknown variable 'b'"))
+        }
+        _ => panic!("expected a transform error"),
+    }
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = 1; b = 2; distinct(a, b);").unwrap();
+
+    assert_eq!(model.to_string(), "a = 0x1;\nb = 0x2;\ndistinct(a, b);\n");
+}
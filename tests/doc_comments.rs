@@ -0,0 +1,97 @@
+mod util;
+use crate::util::*;
+
+fn doc(model: &rvs::Model, name: &str) -> Option<String> {
+    model
+        .get_variable_by_name(name)
+        .unwrap()
+        .borrow()
+        .doc()
+        .map(str::to_owned)
+}
+
+#[test]
+fn basic() {
+    let model = model("
+        /// The base address
+        a = 0x1000;
+    ");
+
+    assert_eq!(doc(&model, "a"), Some("The base address".into()));
+}
+
+#[test]
+fn multiple_lines() {
+    let model = model("
+        /// The base address
+        ///
+        ///   Must be aligned
+        a = 0x1000;
+    ");
+
+    assert_eq!(doc(&model, "a"), Some("The base address\n\n  Must be aligned".into()));
+}
+
+#[test]
+fn with_attributes() {
+    let model = model("
+        /// The width
+        @units(\"bits\")
+        a = 8;
+    ");
+    let a = model.get_variable_by_name("a").unwrap().borrow();
+
+    assert_eq!(a.doc(), Some("The width"));
+    assert_eq!(a.attribute("units"), Some("bits"));
+}
+
+#[test]
+fn not_attached() {
+    let model = model("
+        /// Not attached to a
+        // because of this comment
+
+        /// Not attached to b because of the blank line
+
+        b = 2;
+        c = 3; /// Not attached to d
+        d = 4;
+        //// Not a doc comment
+        e = 5;
+        /// Not attached to any variable
+        const F = 6;
+        /// Not attached to any variable either
+    ");
+
+    for name in &["b", "c", "d", "e"] {
+        assert_eq!(doc(&model, name), None, "{}", name);
+    }
+}
+
+#[test]
+fn same_line() {
+    let model = model("
+        /// Attached to a only
+        a = 1; b = 2;
+    ");
+
+    assert_eq!(doc(&model, "a"), Some("Attached to a only".into()));
+    assert_eq!(doc(&model, "b"), None);
+}
+
+#[test]
+fn display() {
+    let model = model("
+        /// The width
+        /// In \"bits\"
+        @units(\"bits\") a = 8;
+    ");
+
+    assert_eq!(model.to_string(), "/// The width\n/// In \"bits\"\n@units(\"bits\") a = 0x8;\n");
+    assert_eq!(doc(&self::model(&model.to_string()), "a"), Some("The width\nIn \"bits\"".into()));
+}
+
+#[test]
+fn duplicate() {
+    assert!(rvs::parse(&Default::default(), "/// A\n@doc(\"B\") a = 1;").is_err());
+}
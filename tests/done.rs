@@ -8,7 +8,7 @@ fn done_defaults_to_false() {
     let a = expr_to_var("Done(1)").unwrap();
     let a = a.borrow();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 }
 
 #[test]
@@ -40,7 +40,7 @@ fn always_done() {
     let mut a = a.borrow_mut();
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(iter::repeat(true).take(4))
+        .zip(iter::repeat_n(true, 4))
         .cycle()
         .take(32)
         .collect();
@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+
+mod util;
+use crate::util::*;
+
+fn values(expr: &str) -> HashSet<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..1000).map(|_| a.next()).collect()
+}
+
+#[test]
+fn low_bits() {
+    assert_eq!(values("0b10xx"), [8, 9, 10, 11].iter().cloned().collect());
+}
+
+#[test]
+fn mixed() {
+    assert_eq!(values("0bx0x1"), [1, 3, 9, 11].iter().cloned().collect());
+}
+
+#[test]
+fn all_dont_care() {
+    assert_eq!(values("0bxxx"), (0..8).collect());
+}
+
+#[test]
+fn hex_nibble() {
+    assert_eq!(values("0xa?"), (0xa0..=0xaf).collect());
+}
+
+#[test]
+fn in_expression() {
+    assert_eq!(values("0b1x << 4"), [0x20, 0x30].iter().cloned().collect());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = 0b10xx; b = 0x?1;").unwrap();
+
+    assert_eq!(model.to_string(), "a = 0b10xx;\nb = 0bxxxx0001;\n");
+}
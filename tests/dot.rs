@@ -0,0 +1,69 @@
+mod util;
+use crate::util::*;
+
+fn labels(dot: &str) -> Vec<&str> {
+    dot.lines()
+        .filter_map(|line| line.split("[label=\"").nth(1))
+        .map(|label| label.trim_end_matches("\"];"))
+        .collect()
+}
+
+fn edge_count(dot: &str) -> usize {
+    dot.lines().filter(|line| line.contains("->")).count()
+}
+
+#[test]
+fn binary() {
+    let a = expr_to_var("(1 + 2) * 3").unwrap();
+    let dot = a.borrow().to_dot();
+
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert_eq!(labels(&dot), ["*", "+", "0x1", "0x2", "0x3"]);
+    assert_eq!(edge_count(&dot), 4);
+    assert!(dot.contains("    n0 -> n1;\n"));
+    assert!(dot.contains("    n1 -> n2;\n"));
+    assert!(dot.contains("    n1 -> n3;\n"));
+    assert!(dot.contains("    n0 -> n4;\n"));
+}
+
+#[test]
+fn functions_and_samples() {
+    let a = expr_to_var("Pattern(r{1, 2}, ~[0, 3] except {1, 2}, let x = 4 in x)").unwrap();
+    let dot = a.borrow().to_dot();
+
+    assert_eq!(
+        labels(&dot),
+        [
+            "Pattern",
+            "r{}",
+            "0x1",
+            "0x2",
+            "except {0x1, 0x2}",
+            "~",
+            "[0x0, 0x3]",
+            "let x",
+            "0x4",
+            "x",
+        ]
+    );
+    assert_eq!(edge_count(&dot), 9);
+}
+
+#[test]
+fn leaf() {
+    let a = expr_to_var("5").unwrap();
+
+    assert_eq!(a.borrow().to_dot(), "digraph {\n    n0 [label=\"0x5\"];\n}\n");
+}
+
+#[test]
+fn does_not_advance() {
+    let a = expr_to_var("Pattern(1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    a.to_dot();
+
+    assert_eq!(a.next(), 2);
+}
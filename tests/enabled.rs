@@ -0,0 +1,89 @@
+mod util;
+use crate::util::*;
+
+fn model_b_values(s: &str) -> Vec<u32> {
+    let model = model(s);
+    let b = model.get_variable_by_name("b").unwrap();
+    let values = (0..10).map(|_| b.borrow_mut().next()).collect();
+    values
+}
+
+#[test]
+fn disabled_returns_default() {
+    let model = model("a = [0, 1000]; b = [0, 1000];");
+    let reference = model_b_values("a = [0, 1000]; b = [0, 1000];");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    a.borrow_mut().set_enabled(false);
+    a.borrow_mut().set_disabled_value(Some(7));
+    assert!(!a.borrow().is_enabled());
+
+    let values: Vec<u32> = (0..10)
+        .map(|_| {
+            assert_eq!(a.borrow_mut().next(), 7);
+            b.borrow_mut().next()
+        })
+        .collect();
+    assert_eq!(values, reference);
+    assert_eq!(a.borrow().current(), None);
+}
+
+#[test]
+fn holds_prev_by_default() {
+    let model = model("a = Pattern(1, 2, 3);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 1);
+    a.set_enabled(false);
+    assert_eq!(a.next(), 1);
+    assert_eq!(a.next(), 1);
+    a.set_enabled(true);
+    assert_eq!(a.next(), 2);
+}
+
+#[test]
+fn references_see_default() {
+    let model = model("a = Pattern(1, 2, 3); b = a + 10;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    a.borrow_mut().set_enabled(false);
+    a.borrow_mut().set_disabled_value(Some(5));
+    assert_eq!(b.borrow_mut().next(), 15);
+    assert_eq!(b.borrow_mut().next(), 15);
+
+    a.borrow_mut().set_enabled(true);
+    assert_eq!(b.borrow_mut().next(), 11);
+}
+
+#[test]
+fn prev_sees_default() {
+    let model = model("a = Pattern(1, 2, 3); b = a.prev + 10;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 1);
+    a.borrow_mut().set_enabled(false);
+    a.borrow_mut().set_disabled_value(Some(5));
+    assert_eq!(a.borrow().prev(), 5);
+    assert_eq!(b.borrow_mut().next(), 15);
+    assert_eq!(a.borrow().current(), Some(1));
+
+    a.borrow_mut().set_enabled(true);
+    assert_eq!(a.borrow().prev(), 1);
+    assert_eq!(b.borrow_mut().next(), 11);
+}
+
+#[test]
+fn skip_while_disabled() {
+    let model = model("a = Pattern(1, 2, 3);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_enabled(false);
+    a.skip(2);
+    a.set_enabled(true);
+    assert_eq!(a.next(), 1);
+}
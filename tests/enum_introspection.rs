@@ -0,0 +1,25 @@
+#[test]
+fn enums() {
+    let model = rvs::parse(
+        &Default::default(),
+        "enum Command { Read, Write = 4, Erase, } enum Size { Byte = 1, }",
+    )
+    .unwrap();
+
+    let names: Vec<&str> = model.enum_names().collect();
+    assert_eq!(names, ["Command", "Size"]);
+
+    let members: Vec<(&str, u32)> = model.enum_members("Command").unwrap().collect();
+    assert_eq!(members, [("Read", 0), ("Write", 4), ("Erase", 5)]);
+
+    assert!(model.enum_members("Missing").is_none());
+}
+
+#[test]
+fn forked() {
+    let model = rvs::parse(&Default::default(), "enum Size { Byte = 1, }").unwrap();
+    let fork = model.fork();
+
+    let members: Vec<(&str, u32)> = fork.enum_members("Size").unwrap().collect();
+    assert_eq!(members, [("Byte", 1)]);
+}
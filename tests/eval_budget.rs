@@ -0,0 +1,57 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn within_budget() {
+    let a = expr_to_var("[0, 3] except {0}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        let value = a.next_with_budget(1000).unwrap();
+        assert!(value != 0);
+    }
+}
+
+#[test]
+fn budget_exceeded() {
+    let a = expr_to_var("NoRepeat(r{1: 0, 999: 7})").unwrap();
+    let mut a = a.borrow_mut();
+
+    match (0..100).map(|_| a.next_with_budget(5)).find(|result| result.is_err()) {
+        Some(Err(rvs::Error::Budget(e))) => {
+            assert_eq!(e.to_string(), "evaluation budget of 5 re-samples exceeded")
+        }
+        _ => panic!("expected the budget to be exceeded"),
+    }
+
+    // Remains usable
+    a.next();
+}
+
+#[test]
+fn exceeded_count_remains_usable() {
+    let a = expr_to_var("Repeat(5, r{99: 0, 1: 2})").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!((0..100).any(|_| a.next_with_budget(1).is_err()));
+
+    // Remains usable
+    for _ in 0..10 {
+        assert_eq!(a.next(), 5);
+    }
+}
+
+#[test]
+fn resample_limit_is_an_error() {
+    let model = model_with("a = NoRepeat(Pattern(7, 7, 7, 8));", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next_with_budget(100_000).unwrap();
+    let error = a.next_with_budget(100_000).unwrap_err().to_string();
+    assert!(error.starts_with("resample limit of 1 exceeded"), "{}", error);
+    assert_eq!(a.next_with_budget(100_000).unwrap(), 8);
+}
@@ -0,0 +1,125 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn never_yields_excluded_values() {
+    let a = expr_to_var("[0, 100] except {13, 37, 42}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: HashSet<u32> = (0..10_000).map(|_| a.next()).collect();
+
+    assert!(!values.contains(&13));
+    assert!(!values.contains(&37));
+    assert!(!values.contains(&42));
+    assert_eq!(values.len(), 98);
+}
+
+#[test]
+fn exclusions_outside_range_are_ignored() {
+    let a = expr_to_var("[0, 3] except {4, 100}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [0, 1, 2, 3].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..100).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn done_follows_sub_expr() {
+    let a = expr_to_var("Pattern(0, 1, 2, 3) except {1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (2, false), (3, true)]
+        .into_iter()
+        .cycle()
+        .take(9)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..expected.len()).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn done_is_latched_across_excluded_values() {
+    let a = expr_to_var("Pattern(0, 1, 2, 3) except {3}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![
+        (0, false),
+        (1, false),
+        (2, false),
+        (0, true),
+        (1, false),
+        (2, false),
+        (0, true),
+    ];
+    let actual: Vec<(u32, bool)> = (0..expected.len()).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn excluding_entire_range_is_an_error() {
+    assert!(expr_to_var("[0, 2] except {0, 1, 2}").is_err());
+    assert!(expr_to_var("[2, 0] except {0, 1, 2, 3}").is_err());
+}
+
+#[test]
+fn excluding_every_value_of_a_finite_sub_expr_is_an_error() {
+    assert!(expr_to_var("Pattern(1, 2, 3) except {1, 2, 3}").is_err());
+    assert!(expr_to_var("{1, 3} except {1, 3}").is_err());
+    assert!(expr_to_var("Pattern(1, 3) except {1, 2, 3}").is_err());
+    assert!(expr_to_var("Pattern(0, [1, 2]) except {0, 1, 2}").is_err());
+}
+
+#[test]
+fn excluding_some_values_of_a_finite_sub_expr_is_not_an_error() {
+    assert!(expr_to_var("Pattern(1, 2, 3) except {1, 2}").is_ok());
+    assert!(expr_to_var("Pattern(0, [1, 2]) except {0, 1}").is_ok());
+}
+
+#[test]
+fn excluding_every_value_of_an_operation_is_an_error() {
+    assert!(expr_to_var("([0, 1] * 2) except {0, 2}").is_err());
+    assert!(expr_to_var("Pattern(1 + 1, 5) except {2, 5}").is_err());
+    assert!(expr_to_var("~Pattern(0, 1) except {0xffffffff, 0xfffffffe}").is_err());
+    assert!(expr_to_var("([0, 1] * 2) except {0}").is_ok());
+}
+
+fn transform_with_max_resample_attempts(s: &str, attempts: u32) -> rvs::Model {
+    model_with(s, Default::default(), |transform| {
+        transform.set_max_resample_attempts(attempts)
+    })
+    .unwrap()
+}
+
+#[test]
+fn exceeding_max_resample_attempts() {
+    let model = transform_with_max_resample_attempts("a = Pattern(1, 2) except {1};", 1);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(
+        next_checked_error(&mut a, 1),
+        "resample limit of 1 exceeded: the expression `(Pattern(0x1, 0x2, ) except {0x1, })` failed to return a non-excluded value"
+    );
+    // The previous value is returned
+    assert_eq!(a.prev(), 0);
+    assert_eq!(a.next(), 2);
+    assert_eq!(a.next(), 2);
+}
+
+#[test]
+#[should_panic(expected = "must not be 0")]
+fn zero_max_resample_attempts() {
+    transform_with_max_resample_attempts("a = 1;", 0);
+}
+
+#[test]
+fn max_resample_attempts() {
+    let model = transform_with_max_resample_attempts("a = Pattern(1, 2, 3) except {1, 2};", 3);
+
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow_mut().next(), 3);
+}
@@ -0,0 +1,94 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, done_mode: rvs::BinaryDoneMode, flatten: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| {
+        transform.set_binary_done_mode(done_mode);
+        transform.set_flatten_operations(flatten);
+    })
+    .unwrap()
+}
+
+fn values(model: &rvs::Model, name: &str) -> Vec<(u32, bool)> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..100)
+        .map(|_| {
+            let value = variable.next();
+            (value, variable.done())
+        })
+        .collect()
+}
+
+/// Returns an arithmetic expression nested `depth` operations deep with random and stateful leaves
+fn deep(depth: usize) -> String {
+    let operators = ["+", "*", "-", "^", "|", "&", ">>", "<<"];
+    let leaves = ["[1, 100]", "Sequence(3)", "r{1, 2, 3}", "Pattern(7, 9)", "a", "5"];
+
+    (0..depth).fold("[0, 0xffff]".to_owned(), |expr, i| {
+        let leaf = leaves[i % leaves.len()];
+        match i % 5 {
+            0 => format!("~({} {} {})", expr, operators[i % operators.len()], leaf),
+            1 => format!("({} {} {})", leaf, operators[i % operators.len()], expr),
+            _ => format!("({} {} {})", expr, operators[i % operators.len()], leaf),
+        }
+    })
+}
+
+fn source() -> String {
+    format!("
+        a = Sequence(4);
+        b = {};
+        c = (1 + 2) * [0, 9] / [1, 3] - (3 << 2) % Pattern(5, 7);
+        d = -(a + r{{1, 2}}) + Pattern(1, 2, 3);
+    ", deep(20))
+}
+
+#[test]
+fn same_values() {
+    let source = source();
+
+    for &done_mode in &[rvs::BinaryDoneMode::Latched, rvs::BinaryDoneMode::Aligned] {
+        let flattened = model(&source, done_mode, true);
+        let tree = model(&source, done_mode, false);
+
+        for name in &["a", "b", "c", "d"] {
+            assert_eq!(values(&flattened, name), values(&tree, name), "{} {:?}", name, done_mode);
+        }
+    }
+}
+
+#[test]
+fn same_display() {
+    let source = source();
+    let done_mode = Default::default();
+
+    assert_eq!(
+        model(&source, done_mode, true).to_string(),
+        model(&source, done_mode, false).to_string()
+    );
+}
+
+#[test]
+fn division_by_zero() {
+    let error = match model_with("a = [0, 1] + 1 / 0;", Default::default(), |transform| {
+        transform.set_flatten_operations(true)
+    }) {
+        Ok(_) => panic!("expected an error"),
+        Err(error) => error,
+    };
+
+    assert!(error.to_string().contains("division by zero"));
+}
+
+#[test]
+fn empty_operand() {
+    let error = match model_with("a = ([0, 1] + 1) * {0: 5};", Default::default(), |transform| {
+        transform.set_flatten_operations(true)
+    }) {
+        Ok(_) => panic!("expected an error"),
+        Err(error) => error,
+    };
+
+    assert!(error.to_string().contains("never produces a value"), "{}", error);
+}
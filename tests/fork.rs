@@ -0,0 +1,26 @@
+#[test]
+fn references_point_into_fork() {
+    let model = rvs::parse(&Default::default(), "a = Pattern(1, 2, 3); b = a.prev;").unwrap();
+    let fork = model.fork();
+
+    fork.get_variable_by_name("a").unwrap().borrow_mut().next();
+    fork.get_variable_by_name("a").unwrap().borrow_mut().next();
+
+    assert_eq!(fork.get_variable_by_name("b").unwrap().borrow_mut().next(), 2);
+    assert_eq!(model.get_variable_by_name("b").unwrap().borrow_mut().next(), 0);
+}
+
+#[test]
+fn does_not_perturb_original() {
+    let model = rvs::parse(&Default::default(), "a = [0, 0xffff_ffff];").unwrap();
+    let reference = rvs::parse(&Default::default(), "a = [0, 0xffff_ffff];").unwrap();
+
+    let fork = model.fork();
+    fork.get_variable_by_name("a").unwrap().borrow_mut().next();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let r = reference.get_variable_by_name("a").unwrap();
+    for _ in 0..8 {
+        assert_eq!(a.borrow_mut().next(), r.borrow_mut().next());
+    }
+}
@@ -0,0 +1,73 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn holds_last_value() {
+    let reference_model = model("a = [0, 0xffffffff];");
+    let model = model("a = [0, 0xffffffff];");
+    let a = model.get_variable_by_name("a").unwrap();
+    let reference = reference_model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+    let mut reference = reference.borrow_mut();
+
+    for _ in 0..5 {
+        assert_eq!(a.next(), reference.next());
+    }
+
+    let frozen = a.prev();
+    a.freeze();
+    assert!(a.is_frozen());
+    for _ in 0..10 {
+        assert_eq!(a.next(), frozen);
+    }
+    a.skip(3);
+    assert_eq!(a.next(), frozen);
+
+    a.unfreeze();
+    assert!(!a.is_frozen());
+    for _ in 0..10 {
+        assert_eq!(a.next(), reference.next());
+    }
+}
+
+#[test]
+fn before_next() {
+    let model = model("a = Pattern(1, 2, 3);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.freeze();
+    assert_eq!(a.next(), 0);
+    assert_eq!(a.current(), None);
+    a.unfreeze();
+    assert_eq!(a.next(), 1);
+}
+
+#[test]
+fn references_see_frozen_value() {
+    let model = model("a = Pattern(1, 2, 3); b = a + 10;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(b.borrow_mut().next(), 11);
+    a.borrow_mut().freeze();
+    assert_eq!(b.borrow_mut().next(), 11);
+    assert_eq!(b.borrow_mut().next(), 11);
+    a.borrow_mut().unfreeze();
+    assert_eq!(b.borrow_mut().next(), 12);
+}
+
+#[test]
+fn ignores_disabled_value() {
+    let model = model("a = Pattern(1, 2, 3);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    a.set_enabled(false);
+    a.set_disabled_value(Some(7));
+    a.freeze();
+    assert_eq!(a.next(), 1);
+    a.unfreeze();
+    assert_eq!(a.next(), 7);
+}
@@ -0,0 +1,136 @@
+fn next_groups(s: &str, names: &[&str], n: usize) -> Vec<Vec<u32>> {
+    let model = rvs::parse(&Default::default(), s).unwrap();
+
+    (0..n).map(|_| model.next_group(names).unwrap()).collect()
+}
+
+#[test]
+fn holds_when_gate_is_zero() {
+    let groups = next_groups(
+        "a = Sequence(100); b = Pattern(1, 0, 0, 1, 1); gate a by b;",
+        &["a", "b"],
+        6,
+    );
+
+    assert_eq!(
+        groups,
+        vec![
+            vec![0, 1],
+            vec![0, 0],
+            vec![0, 0],
+            vec![1, 1],
+            vec![2, 1],
+            vec![3, 1],
+        ]
+    );
+}
+
+#[test]
+fn gate_after_gated_variable() {
+    let groups = next_groups(
+        "a = Sequence(100); b = Pattern(1, 0, 2); gate a by b;",
+        &["b", "a"],
+        4,
+    );
+
+    assert_eq!(groups, vec![vec![1, 0], vec![0, 0], vec![2, 1], vec![1, 2]]);
+}
+
+#[test]
+fn first_evaluation_advances() {
+    let groups = next_groups("a = Sequence(100); b = 0; gate a by b;", &["a", "b"], 3);
+
+    assert_eq!(groups, vec![vec![0, 0], vec![0, 0], vec![0, 0]]);
+}
+
+#[test]
+fn chained() {
+    let groups = next_groups(
+        "a = Sequence(100); b = Sequence(100); c = Pattern(1, 0); gate a by b; gate b by c;",
+        &["a", "b", "c"],
+        4,
+    );
+
+    // `a` is gated by the value of `b` whether `b` advanced or held
+    assert_eq!(
+        groups,
+        vec![vec![0, 0, 1], vec![0, 0, 0], vec![1, 1, 1], vec![2, 1, 0]]
+    );
+}
+
+#[test]
+fn gate_outside_group() {
+    let groups = next_groups("a = Sequence(100); b = 0; gate a by b;", &["a"], 3);
+
+    assert_eq!(groups, vec![vec![0], vec![1], vec![2]]);
+}
+
+#[test]
+fn held_member_not_paired() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = Sequence(100); b = [0, 99]; g = Pattern(1, 0); gate a by g; pair(a, b, 100);",
+    )
+    .unwrap();
+
+    for i in 0..8 {
+        let values = model.next_group(&["a", "b", "g"]).unwrap();
+        let a = model.get_variable_by_name("a").unwrap().borrow();
+
+        if values[2] == 0 {
+            assert_eq!(values[0], a.prev());
+        } else {
+            assert_eq!(values[0], values[1]);
+        }
+        assert_eq!(a.count(), i / 2 + 1);
+    }
+}
+
+#[test]
+fn held_member_not_resampled() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = Pattern(5, 6, 7); b = Pattern(1, 5, 5, 2); g = Pattern(1, 0);
+         gate a by g; distinct(b, a);",
+    )
+    .unwrap();
+
+    assert_eq!(model.next_group(&["g", "a", "b"]).unwrap(), vec![1, 5, 1]);
+    // `a` is held so `b` is re-sampled even though `a` is the later member of the constraint
+    assert_eq!(model.next_group(&["g", "a", "b"]).unwrap(), vec![0, 5, 2]);
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow().count(), 1);
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = 1; b = 0; gate a by b;").unwrap();
+
+    assert_eq!(model.to_string(), "a = 0x1;\nb = 0x0;\ngate a by b;\n");
+}
+
+fn transform_error(s: &str) -> String {
+    match rvs::parse(&Default::default(), s) {
+        Err(e) => e.to_string(),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        transform_error("a = 1; gate a by b;"),
+        "error at 1:8: gate references unknown variable 'b'"
+    );
+    assert_eq!(
+        transform_error("a = 1; b = 0; c = 0; gate a by b; gate a by c;"),
+        "error at 1:35: variable 'a' is already gated by 'b'"
+    );
+    assert_eq!(
+        transform_error("a = 1; gate a by a;"),
+        "error at 1:8: gating 'a' by 'a' forms a cycle"
+    );
+    assert_eq!(
+        transform_error("a = 1; b = 0; gate a by b; gate b by a;"),
+        "error at 1:28: gating 'b' by 'a' forms a cycle"
+    );
+}
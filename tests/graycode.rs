@@ -0,0 +1,58 @@
+mod util;
+use crate::util::*;
+
+fn values(expr: &str, count: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn three_bits() {
+    let expected: Vec<(u32, bool)> = [0, 1, 3, 2, 6, 7, 5, 4]
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| (value, i == 7))
+        .cycle()
+        .take(24)
+        .collect();
+
+    assert_eq!(values("GrayCode(3)", 24), expected);
+}
+
+#[test]
+fn adjacent_codes_differ_by_one_bit() {
+    let codes: Vec<u32> = values("GrayCode(10)", 1024).into_iter().map(|(v, _)| v).collect();
+
+    for (a, b) in codes.iter().zip(codes.iter().cycle().skip(1)) {
+        assert_eq!((a ^ b).count_ones(), 1);
+    }
+
+    let mut sorted = codes.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..1024).collect::<Vec<u32>>());
+}
+
+#[test]
+fn zero_width() {
+    assert_eq!(values("GrayCode(0)", 3), [(0, true), (0, true), (0, true)]);
+}
+
+#[test]
+fn full_width() {
+    assert_eq!(
+        values("GrayCode(32)", 4),
+        [(0, false), (1, false), (3, false), (2, false)]
+    );
+}
+
+#[test]
+fn too_wide() {
+    assert!(expr_to_var("GrayCode(33)").is_err());
+}
+
+#[test]
+fn display() {
+    assert_eq!(expr_to_var("GrayCode(1 + 2)").unwrap().borrow().to_string(), "GrayCode(3)");
+}
@@ -0,0 +1,75 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn qualified_names() {
+    let model = model("group net { a = 1; b = 2; }");
+
+    let names: Vec<&str> = model.variables_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["net.a", "net.b"]);
+    assert!(model.get_variable_by_name("a").is_none());
+    assert_eq!(next_by_name(&model, "net.b"), 2);
+}
+
+#[test]
+fn short_names_within_group() {
+    let model = model("a = 10; group net { a = 1; b = a + 1; c = net.a + 2; }");
+
+    assert_eq!(next_by_name(&model, "net.b"), 2);
+    assert_eq!(next_by_name(&model, "net.c"), 3);
+}
+
+#[test]
+fn outer_names_within_group() {
+    let model = model("const BASE = 100; x = 5; group net { a = BASE + x; }");
+
+    assert_eq!(next_by_name(&model, "net.a"), 105);
+}
+
+#[test]
+fn full_name_from_outside() {
+    let model = model("group net { a = 7; } b = net.a.prev + net.a;");
+
+    assert_eq!(next_by_name(&model, "b"), 7);
+
+    match rvs::parse(&Default::default(), "group net { a = 7; } b = a;") {
+        Err(rvs::Error::Transform(e)) => assert!(e.to_string().contains("Could not find symbol 'a'")),
+        _ => panic!("expected a transform error"),
+    }
+}
+
+#[test]
+fn nested_groups() {
+    let model = model("group top { a = 1; group net { b = a + 1; group phy { c = b + 1; } } }");
+
+    let names: Vec<&str> = model.variables_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["top.a", "top.net.b", "top.net.phy.c"]);
+    assert_eq!(next_by_name(&model, "top.net.phy.c"), 3);
+}
+
+#[test]
+fn enums_and_constants() {
+    let model = model("group net { enum Kind { A = 3, B } const K = Kind::B; a = K + Kind::A; }");
+
+    assert_eq!(next_by_name(&model, "net.K"), 4);
+    assert_eq!(next_by_name(&model, "net.a"), 7);
+    assert_eq!(model.enum_names().collect::<Vec<_>>(), ["net.Kind"]);
+}
+
+#[test]
+fn distinct_within_group() {
+    let model = model("group net { a = [0, 1]; b = [0, 1]; distinct(a, b); }");
+
+    for _ in 0..10 {
+        let values = model.next_group(&["net.a", "net.b"]).unwrap();
+        assert_ne!(values[0], values[1]);
+    }
+}
+
+#[test]
+fn display_round_trips() {
+    let original = model("group net { a = 1; b = a; }");
+    let reparsed = model(&original.to_string());
+
+    assert!(original.structurally_eq(&reparsed));
+}
@@ -0,0 +1,54 @@
+mod util;
+use crate::util::*;
+
+fn trajectory(expr: &str, n: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| a.next()).collect()
+}
+
+#[test]
+fn fnv1a() {
+    // FNV-1a of four zero bytes
+    assert_eq!(trajectory("Hash(0)", 1), [0x4b95_f515]);
+}
+
+#[test]
+fn identical_sequences() {
+    assert_eq!(
+        trajectory("Hash(Pattern(1, 2, 3))", 10),
+        trajectory("Hash(Sequence(1, 3))", 10)
+    );
+}
+
+#[test]
+fn different_sequences_diverge() {
+    let a = trajectory("Hash(Pattern(1, 2, 3))", 10);
+    let b = trajectory("Hash(Pattern(1, 2, 4))", 10);
+
+    assert_eq!(a[..2], b[..2]);
+    assert!(a[2..].iter().zip(&b[2..]).all(|(a, b)| a != b));
+}
+
+#[test]
+fn reset() {
+    let a = expr_to_var("Hash(Pattern(1, 2, 3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<u32> = (0..4).map(|_| a.next()).collect();
+    a.reset();
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn done() {
+    let a = expr_to_var("Hash(Pattern(1, 2))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<bool> = (0..4).map(|_| { a.next(); a.done() }).collect();
+
+    assert_eq!(actual, [false, true, false, true]);
+}
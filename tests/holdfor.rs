@@ -0,0 +1,88 @@
+mod util;
+use crate::util::*;
+
+fn values_and_done(expr: &str, n: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn holds() {
+    assert_eq!(
+        values_and_done("HoldFor(Pattern(1, 2, 3), Pattern(2, 3, 1))", 8),
+        [
+            (1, false),
+            (1, true),
+            (2, false),
+            (2, false),
+            (2, true),
+            (3, true),
+            (1, false),
+            (1, true),
+        ]
+    );
+}
+
+#[test]
+fn zero_count_redraws() {
+    let actual: Vec<u32> = values_and_done("HoldFor(Pattern(1, 2, 3), Pattern(2, 0, 1))", 6)
+        .into_iter()
+        .map(|(value, _)| value)
+        .collect();
+
+    assert_eq!(actual, [1, 1, 3, 1, 1, 3]);
+}
+
+/// Returns the first `n` held values (one per hold)
+fn held_values(expr: &str, n: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = Vec::new();
+    while values.len() < n {
+        let value = a.next();
+        if a.done() {
+            values.push(value);
+        }
+    }
+
+    values
+}
+
+#[test]
+fn value_stream_independent_of_count() {
+    let expected = held_values("HoldFor([0, 0xffff], 1)", 20);
+
+    for count in ["[1, 3]", "r{1, 2: [1, 4]}", "Pattern(3, 1)"].iter() {
+        let actual = held_values(&format!("HoldFor([0, 0xffff], {})", count), 20);
+
+        assert_eq!(actual, expected, "{}", count);
+    }
+}
+
+#[test]
+fn constant_zero_count_is_an_error() {
+    assert!(expr_to_var("HoldFor([0, 9], 0)").is_err());
+}
+
+#[test]
+fn always_zero_count_is_an_error() {
+    assert!(expr_to_var("HoldFor([0, 9], Pattern(0))").is_err());
+    assert!(expr_to_var("HoldFor([0, 9], [0, 1] & 0)").is_err());
+}
+
+#[test]
+fn zero_count_limit() {
+    let model = model_with("a = HoldFor([0, 9], Pattern(0, 0, 1));", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!(next_checked_error(&mut a, 1).starts_with("resample limit of 1 exceeded"));
+    // A count of 1 is used
+    assert!(a.done());
+}
@@ -0,0 +1,60 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, independent_selection: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| {
+        transform.set_independent_selection(independent_selection)
+    })
+    .unwrap()
+}
+
+fn selections(s: &str, independent_selection: bool) -> Vec<usize> {
+    let model = model(s, independent_selection);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    (0..100)
+        .map(|_| {
+            a.next();
+            a.last_selected().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn with_replacement_stable() {
+    assert_eq!(
+        selections("a = r{[0, 9], [10, 19], [20, 29]};", true),
+        selections("a = r{[0, 9] + [0, 9], [10, 19], Bits(Pattern(3, 4)) + 20};", true)
+    );
+}
+
+#[test]
+fn without_replacement_stable() {
+    assert_eq!(
+        selections("a = {[0, 9], [10, 19], [20, 29]};", true),
+        selections("a = {[0, 9] + [0, 9], [10, 18] + [0, 1], [20, 29]};", true)
+    );
+}
+
+#[test]
+fn shared_by_default() {
+    assert_ne!(
+        selections("a = r{[0, 9], [10, 19], [20, 29]};", false),
+        selections("a = r{[0, 9] + [0, 9], [10, 19], [20, 29]};", false)
+    );
+}
+
+#[test]
+fn fork_diverges() {
+    let model = model("a = r{[0, 9], [10, 19], [20, 29], [30, 39]};", true);
+    let fork = model.fork();
+
+    let original: Vec<u32> = (0..32)
+        .map(|_| model.get_variable_by_name("a").unwrap().borrow_mut().next() / 10)
+        .collect();
+    let forked: Vec<u32> = (0..32)
+        .map(|_| fork.get_variable_by_name("a").unwrap().borrow_mut().next() / 10)
+        .collect();
+
+    assert_ne!(original, forked);
+}
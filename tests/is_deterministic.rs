@@ -0,0 +1,63 @@
+mod util;
+use crate::util::*;
+
+fn is_deterministic(expr: &str) -> bool {
+    expr_to_var(expr).unwrap().borrow().is_deterministic()
+}
+
+fn values(s: &str, seed: u32, n: usize) -> Vec<u32> {
+    let model = model_with(s, rvs::Seed::from_u32(seed), |_| {}).unwrap();
+
+    let mut variable = model.get_variable_by_name("a").unwrap().borrow_mut();
+    (0..n).map(|_| variable.next()).collect()
+}
+
+#[test]
+fn deterministic() {
+    assert!(is_deterministic("5"));
+    assert!(is_deterministic("Pattern(1, 2, 3)"));
+    assert!(is_deterministic("s{1, 2}"));
+    assert!(is_deterministic("Sequence(4) * 2 + 1"));
+    assert!(is_deterministic("Accumulate(Pattern(1, 2)) except {3}"));
+    assert!(is_deterministic("let x = Pattern(1, 2) in x + x"));
+}
+
+#[test]
+fn not_deterministic() {
+    assert!(!is_deterministic("[0, 1]"));
+    assert!(!is_deterministic("r{1, 2}"));
+    assert!(!is_deterministic("{1, 2}"));
+    assert!(!is_deterministic("s{1, [2, 3]}"));
+    assert!(!is_deterministic("Zipf(10, 1)"));
+    assert!(!is_deterministic("Pattern(1, [2, 3])"));
+}
+
+#[test]
+fn references() {
+    let model = rvs::parse(
+        &Default::default(),
+        "r = [0, 9]; p = Pattern(1, 2); a = p + 1; b = r.prev; c = a.done + p.copy; d = a + r;",
+    ).unwrap();
+    let is_deterministic = |name: &str| {
+        model.get_variable_by_name(name).unwrap().borrow().is_deterministic()
+    };
+
+    assert!(is_deterministic("a"));
+    assert!(!is_deterministic("b"));
+    assert!(is_deterministic("c"));
+    assert!(!is_deterministic("d"));
+}
+
+#[test]
+fn seed_independent() {
+    let source = "a = Accumulate(Pattern(1, 2, 3)) * Sequence(2, 5);";
+    assert!(rvs::parse(&Default::default(), source)
+        .unwrap()
+        .get_variable_by_name("a")
+        .unwrap()
+        .borrow()
+        .is_deterministic());
+
+    assert_eq!(values(source, 0, 50), values(source, 1, 50));
+    assert_eq!(values(source, 0, 50), values(source, 0xdead_beef, 50));
+}
@@ -0,0 +1,36 @@
+#[test]
+fn one_round() {
+    let mut model =
+        rvs::parse(&Default::default(), "a = [0, 9]; b = Pattern(5, 6); c = [100, 200];").unwrap();
+
+    let values: Vec<(String, u32)> = model.iter_values().unwrap().collect();
+
+    let names: Vec<&str> = values.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+
+    assert!((0..=9).contains(&values[0].1));
+    assert_eq!(values[1].1, 5);
+    assert!((100..=200).contains(&values[2].1));
+
+    let values: Vec<(String, u32)> = model.iter_values().unwrap().collect();
+    assert_eq!(values[1], ("b".into(), 6));
+}
+
+#[test]
+fn references() {
+    let mut model = rvs::parse(&Default::default(), "a = Pattern(1, 2, 3); b = a;").unwrap();
+
+    let values: Vec<(String, u32)> = model.iter_values().unwrap().collect();
+
+    assert_eq!(values, [("a".into(), 1), ("b".into(), 2)]);
+}
+
+#[test]
+fn on_demand() {
+    let mut model = rvs::parse(&Default::default(), "a = 1; b = 2;").unwrap();
+
+    let mut values = model.iter_values().unwrap();
+    assert_eq!(values.next(), Some(("a".into(), 1)));
+    assert_eq!(values.next(), Some(("b".into(), 2)));
+    assert_eq!(values.next(), None);
+}
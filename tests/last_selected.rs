@@ -0,0 +1,77 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn not_available_before_next() {
+    let a = expr_to_var("r{1, 2, 3}").unwrap();
+    let a = a.borrow();
+
+    assert_eq!(a.last_selected(), None);
+}
+
+#[test]
+fn not_available_for_non_sample() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    assert_eq!(a.last_selected(), None);
+}
+
+fn assert_index_matches_value(expr: &str) {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        let value = a.next();
+        assert_eq!(a.last_selected(), Some((value / 10) as usize));
+    }
+}
+
+#[test]
+fn with_replacement() {
+    assert_index_matches_value("r{0, 10, 20, 30}");
+}
+
+#[test]
+fn without_replacement() {
+    assert_index_matches_value("{0, 10, 20, 30}");
+}
+
+#[test]
+fn weighted_with_replacement() {
+    assert_index_matches_value("r{1: 0, 3: 10, 2: 20}");
+}
+
+#[test]
+fn weighted_without_replacement() {
+    assert_index_matches_value("{1: 0, 3: 10, 2: 20}");
+}
+
+#[test]
+fn in_order() {
+    let a = expr_to_var("o{2: 5, 1: 6, 7}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<Option<usize>> = (0..4)
+        .map(|_| {
+            a.next();
+            a.last_selected()
+        })
+        .collect();
+
+    assert_eq!(actual, [Some(0), Some(0), Some(1), Some(2)]);
+}
+
+#[test]
+fn sub_expression_not_done() {
+    let a = expr_to_var("r{Pattern(0, 1), Pattern(10, 11)}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..50 {
+        let index = (a.next() / 10) as usize;
+        assert_eq!(a.last_selected(), Some(index));
+        assert_eq!(a.next() / 10, index as u32);
+        assert_eq!(a.last_selected(), Some(index));
+    }
+}
@@ -0,0 +1,122 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, lazy: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| transform.set_lazy(lazy)).unwrap()
+}
+
+fn values(model: &rvs::Model, name: &str) -> Vec<u32> {
+    let mut variable = model.get(name).unwrap().unwrap().borrow_mut();
+
+    (0..20).map(|_| variable.next()).collect()
+}
+
+#[test]
+fn unreferenced_invalid_variable() {
+    let model = model("a = [0, 9]; b = unknown; c = Sequence(0, 1, 0);", true);
+
+    assert_eq!(values(&model, "a").len(), 20);
+}
+
+#[test]
+fn error_at_first_access() {
+    let model = model("a = [0, 9];\nb = unknown;", true);
+
+    let result = model.get("b");
+    assert!(result.is_err());
+    let message = result.err().unwrap().to_string();
+    assert!(message.contains("2:5"), "{}", message);
+    assert!(message.contains("Could not find symbol 'unknown'"), "{}", message);
+
+    // Retried rather than silently returning the placeholder
+    assert!(model.get("b").is_err());
+    assert!(model.build_all().is_err());
+}
+
+#[test]
+fn built_by_accessors() {
+    let model = model("a = Pattern(1, 2); b = Pattern(3, 4); c = unknown;", true);
+
+    let a = model.get_variable_by_name("a").unwrap();
+    assert!(model.is_built(0));
+    assert_eq!(a.borrow_mut().next(), 1);
+    assert!(!a.borrow().done());
+
+    let b = model.get_variable_by_index(1).unwrap();
+    assert!(model.is_built(1));
+    assert_eq!(b.borrow_mut().next(), 3);
+
+    assert!(model.get_variable_by_name("c").is_none());
+    assert!(model.get_variable_by_index(2).is_none());
+}
+
+#[test]
+fn iter_values_builds() {
+    let mut model = model("a = Pattern(1, 2); b = a;", true);
+
+    let values: Vec<(String, u32)> = model.iter_values().unwrap().collect();
+    assert_eq!(values, [("a".into(), 1), ("b".into(), 2)]);
+
+    let mut model = self::model("a = 1; b = unknown;", true);
+    assert!(model.iter_values().is_err());
+}
+
+#[test]
+fn built_on_first_access() {
+    let s = "enum E { X = 5 } const C = 10; a = [0, C] + E::X; b = Pattern(a, a.prev);";
+    let model = model(s, true);
+
+    let a = model.get_variable_index("a").unwrap();
+    assert!(!model.is_built(a));
+
+    assert_eq!(values(&model, "a"), values(&self::model(s, false), "a"));
+    assert!(model.is_built(a));
+}
+
+#[test]
+fn references_build_transitively() {
+    let s = "a = [0, 9]; b = a + 100;";
+    let model = model(s, true);
+
+    let a = model.get_variable_index("a").unwrap();
+    let b = model.get_variable_index("b").unwrap();
+
+    assert_eq!(values(&model, "b"), values(&self::model(s, false), "b"));
+    assert!(model.is_built(a));
+    assert!(model.is_built(b));
+}
+
+#[test]
+fn symbols_defined_later_are_not_visible() {
+    let model = model("a = b; b = 1;", true);
+
+    assert!(model.get("a").is_err());
+}
+
+#[test]
+fn groups() {
+    let model = model("group g { a = 5; b = a + 1; }", true);
+
+    assert_eq!(model.get("g.b").unwrap().unwrap().borrow_mut().next(), 6);
+}
+
+#[test]
+fn checkpoint_builds_deferred() {
+    let mut model = model("a = [0, 9]; b = a + Pattern(10, 20);", true);
+
+    model.checkpoint("start").unwrap();
+    assert!(model.is_built(0));
+    assert!(model.is_built(1));
+
+    let b = values(&model, "b");
+    assert!(model.restore("start"));
+    assert_eq!(values(&model, "b"), b);
+}
+
+#[test]
+fn checkpoint_fails_on_invalid_variable() {
+    let mut model = model("a = [0, 9]; b = unknown;", true);
+
+    assert!(model.checkpoint("start").is_err());
+    assert!(!model.restore("start"));
+}
@@ -0,0 +1,89 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn uses_share_draw() {
+    let a = expr_to_var("let base = [0, 0xfff] in base | (base << 16)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = Vec::new();
+    for _ in 0..1000 {
+        let value = a.next();
+        assert_eq!(value >> 16, value & 0xffff);
+        assert!(value & 0xffff <= 0xfff);
+        values.push(value);
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    assert!(values.len() > 100);
+}
+
+#[test]
+fn evaluated_once_per_next() {
+    let a = expr_to_var("let x = Pattern(1, 2, 3) in x + x * 10").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [11, 22, 33, 11]);
+}
+
+#[test]
+fn nested_and_shadowed() {
+    let a = expr_to_var("let x = 1 in let y = x + 1 in let x = 10 in x + y").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 12);
+}
+
+#[test]
+fn binding_shadows_variable() {
+    let model = rvs::parse(&Default::default(), "x = 5; a = let x = 1 in x + 1; b = x;").unwrap();
+
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow_mut().next(), 2);
+    assert_eq!(model.get_variable_by_name("b").unwrap().borrow_mut().next(), 5);
+}
+
+#[test]
+fn binding_out_of_scope() {
+    assert!(rvs::parse(&Default::default(), "a = (let x = 1 in x) + x;").is_err());
+    assert!(rvs::parse(&Default::default(), "a = let x = x in x;").is_err());
+}
+
+#[test]
+fn done_follows_body() {
+    let a = expr_to_var("let x = 1 in Pattern(x, x + 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(actual, [(1, false), (2, true), (1, false), (2, true)]);
+}
+
+#[test]
+fn transform_time_arguments() {
+    let a = expr_to_var("let x = 5 in [0, x]").unwrap();
+    let mut a = a.borrow_mut();
+    let mut values: Vec<u32> = (0..100).map(|_| a.next()).collect();
+    values.sort_unstable();
+    values.dedup();
+    assert_eq!(values, [0, 1, 2, 3, 4, 5]);
+
+    let a = expr_to_var("let x = 3 in Pattern([x, x], x + 1)").unwrap();
+    let mut a = a.borrow_mut();
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+    assert_eq!(actual, [3, 4, 3, 4]);
+
+    let a = expr_to_var("let p = 100 in Maybe(p, 1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+    assert_eq!(actual, [1, 1, 1, 1]);
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("let x = 1 in x + 2").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "(let x = 0x1 in (x + 0x2))");
+}
@@ -0,0 +1,125 @@
+use rvs::{Parser, SearchPath};
+
+fn parse(parser: &mut Parser, s: &str) -> Result<(), String> {
+    parser.parse(s).map_err(|e| e.to_string())
+}
+
+#[test]
+fn max_variables() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_variables(3);
+
+    assert!(parse(&mut parser, "a = 1;\nb = 2;").is_ok());
+    assert_eq!(
+        parse(&mut parser, "const c = 3;\nd = 4;"),
+        Err("error at 2:1: the number of variables exceeds the limit of 3".into())
+    );
+    // The failed source is not added to the AST
+    assert_eq!(parser.ast().len(), 2);
+}
+
+#[test]
+fn max_variables_in_groups() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_variables(1);
+
+    assert_eq!(
+        parse(&mut parser, "group g {\n    a = 1;\n    b = 2;\n}"),
+        Err("error at 3:5: the number of variables exceeds the limit of 1".into())
+    );
+}
+
+#[test]
+fn max_children() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_children(4);
+
+    assert!(parse(&mut parser, "a = Pattern(1, 2, 3, 4);").is_ok());
+    assert_eq!(
+        parse(&mut parser, "b = 1 + Pattern(1, 2, 3, 4, 5);"),
+        Err("error at 1:1: `Pattern` has 5 arguments which exceeds the limit of 4".into())
+    );
+    assert_eq!(
+        parse(&mut parser, "c = {1, 2, 3, 4, 5};"),
+        Err("error at 1:1: weighted sample has 5 entries which exceeds the limit of 4".into())
+    );
+}
+
+#[test]
+fn max_nodes() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_nodes(6);
+
+    // Variable, Type, Number, Number
+    assert!(parse(&mut parser, "a = Pattern(1, 2);").is_ok());
+    assert_eq!(
+        parse(&mut parser, "b = 1 + 2;"),
+        Err("error at 1:1: the number of AST nodes exceeds the limit of 6".into())
+    );
+    assert!(parse(&mut parser, "b = 1;").is_ok());
+}
+
+#[test]
+fn parse_reader() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_variables(2);
+
+    let source = "a = 1;\nb = 2;\nc = 3;\n";
+    assert_eq!(
+        parser.parse_reader(source.as_bytes()).map_err(|e| e.to_string()),
+        Err("error at 3:1: the number of variables exceeds the limit of 2".into())
+    );
+}
+
+#[test]
+fn parse_recover() {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.set_max_children(2);
+
+    let errors = parser.parse_recover("a = Pattern(1, 2, 3);\nb = 1 +;\nc = Pattern(1, 2);");
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].to_string().contains("`Pattern` has 3 arguments"));
+    assert_eq!(parser.ast().len(), 1);
+}
+
+#[test]
+fn unlimited_by_default() {
+    let source: String = (0..1000).map(|i| format!("v{} = {};\n", i, i)).collect();
+
+    assert!(rvs::parse(&SearchPath::default(), &source).is_ok());
+}
+
+/// Returns the error of transforming `s` with a `max_children` of `max_children`
+fn transform_error(s: &str, max_children: usize) -> Option<String> {
+    let mut parser = Parser::new(&SearchPath::default());
+    parser.parse(s).unwrap();
+
+    let mut transform = rvs::Transform::new(Default::default());
+    transform.set_max_children(max_children);
+    transform.transform(&mut rvs::Model::new(), parser.ast()).err().map(|e| e.to_string())
+}
+
+#[test]
+fn max_children_entries() {
+    assert_eq!(transform_error("a = r{1, 2, 3, 4};", 4), None);
+    assert!(transform_error("a = r{1, 2, 3, 4, 5};", 4)
+        .unwrap()
+        .contains("weighted sample has more than 4 entries which exceeds the limit"));
+}
+
+#[test]
+fn max_children_pool() {
+    // Samples with replacement don't expand their weights
+    assert_eq!(transform_error("a = r{4000000000: 1};", 4), None);
+    assert_eq!(transform_error("a = s{3: 1, 1: 2};", 4), None);
+
+    let error = transform_error("a = {4000000000: 1};", 4).unwrap();
+    assert!(
+        error.contains("the pool of the sample has 4000000000 entries which exceeds the limit of 4"),
+        "{}",
+        error
+    );
+    assert!(transform_error("a = o{4: 1, 1: 2};", 4).is_some());
+    assert!(transform_error("a = s{0.001: 1, 0.999: 2};", 4).is_some());
+}
@@ -0,0 +1,77 @@
+use log::{Level, Log, Metadata, Record};
+
+use std::sync::{Mutex, Once};
+
+struct CaptureLogger;
+
+static RECORDS: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+static INIT: Once = Once::new();
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        RECORDS
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+fn init() {
+    INIT.call_once(|| {
+        log::set_logger(&CaptureLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+/// Tests run in parallel so only look for records unique to the test
+fn logged(level: Level, message: &str) -> bool {
+    RECORDS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|record| record.0 == level && record.1 == message)
+}
+
+#[test]
+fn variable_defined() {
+    init();
+
+    rvs::parse(&Default::default(), "logged_variable = 1;").unwrap();
+
+    assert!(logged(Level::Debug, "defined variable 'logged_variable'"));
+}
+
+#[test]
+fn swapped_range_warning() {
+    init();
+
+    rvs::parse(&Default::default(), "swapped = [7, 3];").unwrap();
+
+    assert!(logged(
+        Level::Warn,
+        "warning at 1:1: the range [0x7, 0x3] has a low bound greater than its high bound and is treated as [0x3, 0x7]"
+    ));
+}
+
+#[test]
+fn unsatisfiable_constraint() {
+    init();
+
+    let model = rvs::parse(
+        &Default::default(),
+        "x = 0; y = 0; distinct(x, y);",
+    )
+    .unwrap();
+
+    assert!(model.next_group(&["x", "y"]).is_err());
+    assert!(logged(
+        Level::Warn,
+        "could not satisfy distinct(x, y): the variables can't return distinct values"
+    ));
+}
@@ -0,0 +1,52 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn split() {
+    let a = expr_to_var("Maybe(80, 1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let ones = (0..10_000).filter(|_| a.next() == 1).count();
+
+    assert!((7_800..=8_200).contains(&ones), "{}", ones);
+}
+
+#[test]
+fn sub_expressions() {
+    let a = expr_to_var("Maybe(50, [0, 9], Pattern(0x10, 0x20))").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1_000 {
+        let value = a.next();
+        assert!((0..=9).contains(&value) || value == 0x10 || value == 0x20, "{}", value);
+    }
+}
+
+#[test]
+fn always_else() {
+    let a = expr_to_var("Maybe(0, 1, Pattern(2, 3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [2, 3, 2, 3]);
+}
+
+#[test]
+fn always_then() {
+    let a = expr_to_var("Maybe(100, 1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!((0..100).all(|_| a.next() == 1));
+}
+
+#[test]
+fn percent_out_of_range() {
+    match expr_to_var("Maybe(101, 1, 2)") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "error at 1:1: Maybe() percent must be in the range [0, 100] but found 101"
+        ),
+        Ok(_) => panic!("expected an error"),
+    }
+}
@@ -0,0 +1,65 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+fn assert_membership(expr: &str, members: &HashSet<u32>) {
+    let a = expr_to_var(format!("Pattern(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10) in {{{}}}", expr)).unwrap();
+    let mut a = a.borrow_mut();
+
+    for value in 0..=10 {
+        assert_eq!(a.next(), members.contains(&value) as u32, "{}", value);
+    }
+}
+
+#[test]
+fn values() {
+    assert_membership("1, 2, 3, 5, 8", &[1, 2, 3, 5, 8].iter().cloned().collect());
+}
+
+#[test]
+fn ranges_expanded() {
+    assert_membership("[2, 4], 7, [10, 9]", &[2, 3, 4, 7, 9, 10].iter().cloned().collect());
+}
+
+#[test]
+fn empty_set() {
+    assert_membership("", &HashSet::new());
+}
+
+#[test]
+fn random_draws() {
+    let model = rvs::parse(
+        &Default::default(),
+        "x = [0, 100]; a = x in {1, 2, 3, 5, 8, [50, 60]};",
+    )
+    .unwrap();
+    let x = model.get_variable_by_name("x").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let members: HashSet<u32> = [1, 2, 3, 5, 8].iter().cloned().chain(50..=60).collect();
+
+    let mut counts = [0, 0];
+    for _ in 0..10_000 {
+        let is_member = a.borrow_mut().next();
+        let value = x.borrow().prev();
+
+        assert_eq!(is_member, members.contains(&value) as u32, "{}", value);
+        counts[is_member as usize] += 1;
+    }
+
+    assert!(counts[0] > 0);
+    assert!(counts[1] > 0);
+}
+
+#[test]
+fn done_follows_sub_expr() {
+    let a = expr_to_var("Pattern(0, 1, 2) in {1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..6).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(
+        actual,
+        [(0, false), (1, false), (0, true), (0, false), (1, false), (0, true)]
+    );
+}
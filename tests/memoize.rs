@@ -0,0 +1,53 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, memoize: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| transform.set_memoize(memoize)).unwrap()
+}
+
+fn values(model: &rvs::Model, name: &str) -> Vec<(u32, bool)> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..20)
+        .map(|_| {
+            let value = variable.next();
+            (value, variable.done())
+        })
+        .collect()
+}
+
+const SOURCE: &str = "
+    const C = 3;
+    a = (1 << 4) * C + 7;
+    b = MulMod(ByteSwap(0x1234), BitReverse(1), 0xffff_fffb) ^ Decode(4, 8);
+    c = (1 + 2) * [0, 9] + Pattern(1, 2) * (C - 1);
+    d = a + (2 * 2);
+    e = let x = [0, 9] in x + (1 + 1);
+";
+
+#[test]
+fn same_values() {
+    let memoized = model(SOURCE, true);
+    let evaluated = model(SOURCE, false);
+
+    for name in &["a", "b", "c", "d", "e"] {
+        assert_eq!(values(&memoized, name), values(&evaluated, name), "{}", name);
+    }
+}
+
+#[test]
+fn same_display() {
+    assert_eq!(model(SOURCE, true).to_string(), model(SOURCE, false).to_string());
+}
+
+#[test]
+fn reset() {
+    let model = model("a = (1 + 2) * 3;", true);
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow().prev(), 0);
+    assert_eq!(a.borrow_mut().next(), 9);
+    a.borrow_mut().reset();
+    assert_eq!(a.borrow().prev(), 0);
+    assert_eq!(a.borrow_mut().next(), 9);
+}
@@ -0,0 +1,84 @@
+mod util;
+use crate::util::*;
+
+fn values_and_done(expr: &str, n: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn wraps() {
+    assert_eq!(
+        values_and_done("ModCounter(2, 4)", 8),
+        [
+            (2, false),
+            (3, true),
+            (0, false),
+            (1, false),
+            (2, false),
+            (3, true),
+            (0, false),
+            (1, false),
+        ]
+    );
+}
+
+#[test]
+fn start_at_zero() {
+    let actual: Vec<u32> = values_and_done("ModCounter(0, 3)", 6).into_iter().map(|(value, _)| value).collect();
+
+    assert_eq!(actual, [0, 1, 2, 0, 1, 2]);
+}
+
+#[test]
+fn modulus_of_one() {
+    assert_eq!(values_and_done("ModCounter(0, 1)", 3), [(0, true), (0, true), (0, true)]);
+}
+
+#[test]
+fn max_modulus() {
+    assert_eq!(
+        values_and_done("ModCounter(0xffff_fffe, 0xffff_ffff)", 2),
+        [(0xffff_fffe, true), (0, false)]
+    );
+}
+
+#[test]
+fn cycle_count() {
+    let a = expr_to_var("ModCounter(2, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..10 {
+        a.next();
+    }
+
+    assert_eq!(a.cycle_count(), 3);
+}
+
+#[test]
+fn zero_modulus() {
+    match expr_to_var("ModCounter(0, 0)") {
+        Err(e) => assert_eq!(e.to_string(), "error at 1:1: ModCounter() modulus must not be 0"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn start_not_less_than_modulus() {
+    match expr_to_var("ModCounter(4, 4)") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "error at 1:1: ModCounter() start must be less than the modulus but found start 4 and modulus 4"
+        ),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn value_bounds() {
+    let a = expr_to_var("ModCounter(2, 4)").unwrap();
+
+    assert_eq!(a.borrow().value_bounds(), Some((0, 3)));
+}
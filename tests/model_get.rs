@@ -0,0 +1,79 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn after_transform() {
+    let model = rvs::parse(&Default::default(), "a = 5;").unwrap();
+
+    assert_eq!(model.get("a").unwrap().unwrap().borrow_mut().next(), 5);
+    assert!(model.get("b").unwrap().is_none());
+}
+
+#[test]
+fn before_transform() {
+    let model = rvs::Model::new();
+
+    match model.get("a") {
+        Err(rvs::Error::Transform(e)) => assert_eq!(
+            e.to_string(),
+            "cannot get variable 'a': the model has not been transformed"
+        ),
+        _ => panic!("expected a transform error"),
+    }
+}
+
+#[test]
+fn failed_step_reported() {
+    assert!(matches!(
+        rvs::parse(&Default::default(), "a = ;"),
+        Err(rvs::Error::Parse(_))
+    ));
+    assert!(matches!(
+        rvs::parse(&Default::default(), "a = b;"),
+        Err(rvs::Error::Transform(_))
+    ));
+}
+
+#[test]
+fn get_result_defined() {
+    let model = rvs::parse(&Default::default(), "a = 5;").unwrap();
+
+    assert_eq!(model.get_result("a").unwrap().borrow_mut().next(), 5);
+}
+
+#[test]
+fn get_result_undefined() {
+    let model = rvs::parse(&Default::default(), "a = 5;").unwrap();
+
+    match model.get_result("b") {
+        Err(rvs::Error::Lookup(e)) => {
+            assert_eq!(e, rvs::LookupError::Undefined("b".into()));
+            assert_eq!(e.to_string(), "variable 'b' is not defined");
+        }
+        _ => panic!("expected a lookup error"),
+    }
+}
+
+#[test]
+fn get_result_before_transform() {
+    let model = rvs::Model::new();
+
+    match model.get_result("a") {
+        Err(rvs::Error::Lookup(e)) => {
+            assert_eq!(e, rvs::LookupError::NotTransformed("a".into()))
+        }
+        _ => panic!("expected a lookup error"),
+    }
+}
+
+#[test]
+fn get_result_lazy() {
+    let model = model_with("a = 1; b = a + 1; c = unknown;", rvs::Seed::from_u32(0), |transform| {
+        transform.set_lazy(true)
+    })
+    .unwrap();
+
+    // Built on lookup
+    assert_eq!(model.get_result("b").unwrap().borrow_mut().next(), 2);
+    assert!(matches!(model.get_result("c"), Err(rvs::Error::Transform(_))));
+}
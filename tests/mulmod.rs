@@ -0,0 +1,77 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn product_exceeds_u32() {
+    let a = expr_to_var("MulMod(0xffff_ffff, 0xffff_ffff, 0xffff_fffb)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected = ((0xffff_ffffu64 * 0xffff_ffffu64) % 0xffff_fffbu64) as u32;
+    assert_eq!(a.next(), expected);
+    assert_eq!(a.next(), 16);
+}
+
+#[test]
+fn hash_mixer() {
+    let a = expr_to_var("MulMod(Pattern(1, 2, 3), 2654435761, 0x1_0000 * 0x1_0000 - 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for x in 1..=3u64 {
+        assert_eq!(u64::from(a.next()), (x * 2_654_435_761) % 0xffff_ffff);
+    }
+}
+
+#[test]
+fn modulus_of_one() {
+    let a = expr_to_var("MulMod([0, 0xffff_ffff], [0, 0xffff_ffff], 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        assert_eq!(a.next(), 0);
+    }
+}
+
+#[test]
+fn modulus_of_zero() {
+    assert!(expr_to_var("MulMod(1, 2, 0)").is_err());
+}
+
+#[test]
+fn constant_modulus_of_zero() {
+    for modulus in &["1 - 1", "0 * 7", "Bits(4) & 0"] {
+        let error = expr_to_var(format!("MulMod([0, 9], 3, {})", modulus)).err().unwrap();
+        assert!(error.to_string().contains("modulus"), "{}: {}", modulus, error);
+    }
+}
+
+#[test]
+fn modulus_always_zero() {
+    for modulus in &["Pattern(0, 0)", "{0, 0}", "[0, 0]"] {
+        let error = expr_to_var(format!("MulMod([0, 9], 3, {})", modulus)).err().unwrap();
+        assert!(error.to_string().contains("modulus is always 0"), "{}: {}", modulus, error);
+    }
+}
+
+#[test]
+fn dynamic_modulus_of_zero_wraps() {
+    let a = expr_to_var("MulMod(0xffff_ffff, 3, Pattern(0, 7))").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xffff_ffffu32.wrapping_mul(3));
+    assert_eq!(a.next(), ((0xffff_ffffu64 * 3) % 7) as u32);
+}
+
+#[test]
+fn done_when_all_operands_done() {
+    let a = expr_to_var("MulMod(Pattern(1, 2), 3, Pattern(5, 7, 11))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<bool> = (0..4)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(actual, [false, false, true, true]);
+}
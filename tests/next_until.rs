@@ -0,0 +1,33 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn each_value_in_range() {
+    let a = expr_to_var("[0, 3]").unwrap();
+    let mut a = a.borrow_mut();
+
+    for target in 0..=3 {
+        let count = a.next_until(target, 1000).unwrap();
+
+        assert!(count >= 1);
+        assert_eq!(a.prev(), target);
+    }
+}
+
+#[test]
+fn first_draw() {
+    let a = expr_to_var("Pattern(5, 6)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next_until(5, 10), Some(1));
+    assert_eq!(a.next_until(5, 10), Some(2));
+}
+
+#[test]
+fn cap() {
+    let a = expr_to_var("[0, 3]").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next_until(4, 100), None);
+    assert_eq!(a.next_until(0, 0), None);
+}
@@ -0,0 +1,77 @@
+mod util;
+use crate::util::*;
+
+fn reproducible(expr: &str) {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    for i in 0..32u8 {
+        let entropy = [i, i ^ 0xa5, 3, 7, 11];
+        let value = a.next_with_entropy(&entropy);
+        for _ in 0..4 {
+            a.next();
+            assert_eq!(a.next_with_entropy(&entropy), value);
+        }
+    }
+}
+
+#[test]
+fn range_reproducible() {
+    reproducible("[0, 0xffff_ffff]");
+}
+
+#[test]
+fn sample_reproducible() {
+    reproducible("r{0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15}");
+}
+
+#[test]
+fn entropy_varies_value() {
+    let a = expr_to_var("[0, 0xffff_ffff]").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values: Vec<u32> = (0..32u8).map(|i| a.next_with_entropy(&[i])).collect();
+    values.sort_unstable();
+    values.dedup();
+
+    assert_eq!(values.len(), 32);
+}
+
+#[test]
+fn rng_not_advanced() {
+    let a = expr_to_var("[0, 0xffff_ffff]").unwrap();
+    let mut a = a.borrow_mut();
+    let b = expr_to_var("[0, 0xffff_ffff]").unwrap();
+    let mut b = b.borrow_mut();
+
+    a.next_with_entropy(&[1, 2, 3]);
+
+    for _ in 0..8 {
+        assert_eq!(a.next(), b.next());
+    }
+}
+
+#[test]
+fn insufficient_entropy() {
+    let a = expr_to_var("[0, 0xffff_ffff]").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next_with_entropy(&[]), a.next_with_entropy(&[]));
+    assert_eq!(a.next_with_entropy(&[0]), a.next_with_entropy(&[]));
+    assert_eq!(a.prev(), a.next_with_entropy(&[]));
+}
+
+#[test]
+fn every_byte_contributes() {
+    let a = expr_to_var("[0, 0xffff_ffff]").unwrap();
+    let mut a = a.borrow_mut();
+
+    let empty = a.next_with_entropy(&[]);
+    let mut repeated = [0u8; 17];
+    repeated[0] = 1;
+    repeated[16] = 1;
+
+    assert_ne!(a.next_with_entropy(&[1; 32]), empty);
+    assert_ne!(a.next_with_entropy(&repeated), empty);
+    assert_ne!(a.next_with_entropy(&[1, 2]), a.next_with_entropy(&[2, 1]));
+}
@@ -0,0 +1,69 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn basic() {
+    let model = model("a = [0, 1000]; b = a + 1;");
+    let b = model.get_variable_by_name("b").unwrap();
+
+    for _ in 0..10 {
+        assert_eq!(b.borrow_mut().next_with_override("a", 10), 11);
+    }
+}
+
+#[test]
+fn overridden_variable_not_advanced() {
+    let model = model("a = Pattern(1, 2, 3); b = a + 1;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    b.borrow_mut().next_with_override("a", 10);
+    assert_eq!(a.borrow().current(), None);
+    assert_eq!(b.borrow_mut().next(), 2);
+}
+
+#[test]
+fn applies_to_one_call() {
+    let model = model("a = Pattern(1, 2, 3); b = a + 1;");
+    let b = model.get_variable_by_name("b").unwrap();
+    let mut b = b.borrow_mut();
+
+    assert_eq!(b.next_with_override("a", 10), 11);
+    assert_eq!(b.next(), 2);
+    assert_eq!(b.next_with_override("a", 20), 21);
+    assert_eq!(b.next(), 3);
+}
+
+#[test]
+fn nested() {
+    let model = model("a = [0, 1000]; b = a + 1; c = b * 2;");
+    let c = model.get_variable_by_name("c").unwrap();
+
+    assert_eq!(c.borrow_mut().next_with_override("a", 10), 22);
+}
+
+#[test]
+fn prev() {
+    let model = model("a = 5; b = a.prev + 1;");
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(b.borrow_mut().next_with_override("a", 10), 11);
+}
+
+#[test]
+fn not_referenced() {
+    let model = model("a = [0, 1000]; b = Pattern(1, 2); c = b + 1;");
+    let c = model.get_variable_by_name("c").unwrap();
+    let mut c = c.borrow_mut();
+
+    assert_eq!(c.next_with_override("a", 10), 2);
+    assert_eq!(c.next_with_override("a", 10), 3);
+}
+
+#[test]
+fn self_not_overridden() {
+    let model = model("a = Pattern(1, 2);");
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow_mut().next_with_override("a", 10), 1);
+}
@@ -0,0 +1,61 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn no_consecutive_repeats() {
+    let a = expr_to_var("NoRepeat([0, 3])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..10_000).map(|_| a.next()).collect();
+
+    assert!(values.windows(2).all(|pair| pair[0] != pair[1]));
+    assert_eq!(values.iter().cloned().collect::<HashSet<u32>>().len(), 4);
+}
+
+#[test]
+fn non_consecutive_repeats_allowed() {
+    let a = expr_to_var("NoRepeat(Pattern(1, 1, 2, 1))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 2, 1, 2]);
+}
+
+#[test]
+fn constant_is_an_error() {
+    assert!(expr_to_var("NoRepeat(5)").is_err());
+    assert!(rvs::parse(&Default::default(), "const C = 5; a = NoRepeat(C);").is_err());
+}
+
+#[test]
+fn single_value_is_an_error() {
+    assert!(expr_to_var("NoRepeat([7, 7])").is_err());
+    assert!(expr_to_var("NoRepeat(Pattern(7, 7))").is_err());
+    assert!(expr_to_var("NoRepeat([0, 1] & 0)").is_err());
+    assert!(expr_to_var("NoRepeat(Pattern(7, 8))").is_ok());
+}
+
+#[test]
+fn repeats_hit_resample_limit() {
+    let model = model_with("a = NoRepeat(Pattern(7, 7, 7, 8));", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 7);
+    assert!(next_checked_error(&mut a, 1).starts_with("resample limit of 1 exceeded"));
+    // The repeated value is returned
+    assert_eq!(a.prev(), 7);
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("NoRepeat([0, 3])").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "NoRepeat([0x0, 0x3])");
+}
@@ -8,7 +8,7 @@ fn done_defaults_to_false() {
     let a = expr_to_var("Once([0, 1])").unwrap();
     let a = a.borrow();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 }
 
 #[test]
@@ -16,8 +16,86 @@ fn yields_constant_value_and_always_done() {
     let a = expr_to_var("Once(Pattern(1, 2, 3))").unwrap();
     let mut a = a.borrow_mut();
 
-    let expected: Vec<(u32, bool)> = iter::repeat((1, true)).take(32).collect();
+    let expected: Vec<(u32, bool)> = iter::repeat_n((1, true), 32).collect();
     let actual: Vec<(u32, bool)> = (0..expected.len()).map(|_| (a.next(), a.done())).collect();
 
     assert_eq!(expected, actual);
 }
+
+fn exhausted_model(expr: &str, exhausted_mode: rvs::ExhaustedMode) -> rvs::Model {
+    model_with(&format!("a = {};", expr), Default::default(), |transform| {
+        transform.set_exhausted_mode(exhausted_mode)
+    })
+    .unwrap()
+}
+
+fn exhausted_values(exhausted_mode: rvs::ExhaustedMode) -> Vec<(u32, bool)> {
+    let model = exhausted_model("Once(Pattern(1, 2, 3))", exhausted_mode);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    (0..4).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn exhausted_hold() {
+    assert_eq!(
+        exhausted_values(rvs::ExhaustedMode::Hold),
+        [(1, true), (1, true), (1, true), (1, true)]
+    );
+}
+
+#[test]
+fn exhausted_zero() {
+    assert_eq!(
+        exhausted_values(rvs::ExhaustedMode::Zero),
+        [(1, true), (0, true), (0, true), (0, true)]
+    );
+}
+
+#[test]
+fn exhausted_wrap() {
+    assert_eq!(
+        exhausted_values(rvs::ExhaustedMode::Wrap),
+        [(1, true), (2, false), (3, true), (1, false)]
+    );
+}
+
+#[test]
+fn exhausted_flag() {
+    assert_eq!(
+        exhausted_values(rvs::ExhaustedMode::Flag),
+        [(1, true), (1, true), (1, true), (1, true)]
+    );
+
+    let model = exhausted_model("Once(Pattern(1, 2, 3))", rvs::ExhaustedMode::Flag);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.next(), 1);
+    assert!(!a.exhausted());
+    assert_eq!(a.next(), 1);
+    assert!(a.exhausted());
+    a.reset();
+    assert!(!a.exhausted());
+}
+
+#[test]
+fn exhausted_flag_referenced() {
+    let model = exhausted_model("Once(Pattern(1, 2, 3)); b = a + 1", rvs::ExhaustedMode::Flag);
+    let mut b = model.get_variable_by_name("b").unwrap().borrow_mut();
+
+    assert_eq!(b.next(), 2);
+    assert!(!b.exhausted());
+    assert_eq!(b.next(), 2);
+    assert!(b.exhausted());
+}
+
+#[test]
+fn exhausted_reset() {
+    let model = exhausted_model("Once(Pattern(1, 2, 3))", rvs::ExhaustedMode::Zero);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.next(), 1);
+    assert_eq!(a.next(), 0);
+    a.reset();
+    assert_eq!(a.next(), 1);
+}
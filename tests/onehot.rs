@@ -0,0 +1,71 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn one_hot() {
+    let a = expr_to_var("OneHot(Sequence(8))").unwrap();
+    let mut a = a.borrow_mut();
+
+    for index in 0..8 {
+        assert_eq!(a.next(), 1 << index, "{}", index);
+    }
+}
+
+#[test]
+fn one_hot_index_masked() {
+    let a = expr_to_var("OneHot(Pattern(31, 32, 33, 0xffff_ffff))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0x8000_0000, 1, 2, 0x8000_0000]);
+}
+
+#[test]
+fn decode() {
+    let a = expr_to_var("Decode(Pattern(0, 4, 30, 36), 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0xf, 0xf0, 0xc000_0000, 0xf0]);
+}
+
+#[test]
+fn decode_width() {
+    let a = expr_to_var("Decode(3, 0)").unwrap();
+    assert_eq!(a.borrow_mut().next(), 0);
+
+    let b = expr_to_var("Decode(Pattern(0, 8), 32)").unwrap();
+    let mut b = b.borrow_mut();
+
+    assert_eq!(b.next(), 0xffff_ffff);
+    assert_eq!(b.next(), 0xffff_ff00);
+
+    let c = expr_to_var("Decode(5, 40)").unwrap();
+    assert_eq!(c.borrow_mut().next(), 0xffff_ffe0);
+}
+
+#[test]
+fn done_passed_through() {
+    for expr in &["OneHot(Pattern(1, 2))", "Decode(Pattern(1, 2), 2)"] {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        let actual: Vec<bool> = (0..4)
+            .map(|_| {
+                a.next();
+                a.done()
+            })
+            .collect();
+
+        assert_eq!(actual, [false, true, false, true], "{}", expr);
+    }
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("OneHot(Decode(1, 2))").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "OneHot(Decode(0x1, 2))");
+}
@@ -13,9 +13,8 @@ fn yields_sum() {
     let expected: Vec<(u32, bool)> = l.zip(r)
         .map(|(l, r)| l + r)
         .zip(
-            iter::repeat(false)
-                .take(3)
-                .chain(iter::repeat(true).take(21)),
+            iter::repeat_n(false, 3)
+                .chain(iter::repeat_n(true, 21)),
         )
         .collect();
 
@@ -71,3 +70,146 @@ fn overflow_neg() {
 
     assert_eq!(0, a.next());
 }
+
+/// Characterizes done for operands with different cycle lengths
+///
+/// Done latches once both operands have been done at least once.  It does not wait for the
+/// operands to align on a common cycle boundary.
+#[test]
+fn done_latches_for_misaligned_operands() {
+    let a = expr_to_var("Pattern(0, 0) + Pattern(0, 0, 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<bool> = iter::repeat_n(false, 2)
+        .chain(iter::repeat_n(true, 58))
+        .collect();
+    let actual: Vec<bool> = (0..60)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn done_follows_operand_for_unary() {
+    let a = expr_to_var("~Pattern(0, 0, 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<bool> = vec![false, false, true].into_iter().cycle().take(30).collect();
+    let actual: Vec<bool> = (0..30)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(expected, actual);
+}
+
+fn aligned_model(expr: &str) -> rvs::Model {
+    model_with(&format!("a = {};", expr), Default::default(), |transform| {
+        transform.set_binary_done_mode(rvs::BinaryDoneMode::Aligned)
+    })
+    .unwrap()
+}
+
+/// Aligned done is only indicated on the common cycle boundary (the LCM of the operand cycle
+/// lengths)
+#[test]
+fn aligned_done_at_lcm_boundary() {
+    let model = aligned_model("Pattern(0, 0) + Pattern(0, 0, 0)");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    let expected: Vec<bool> = (1..=60).map(|i| i % 6 == 0).collect();
+    let actual: Vec<bool> = (0..60)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn aligned_done_nested() {
+    let model = aligned_model("Pattern(0, 0) + Pattern(0, 0, 0) + Pattern(0, 0, 0, 0)");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    let expected: Vec<bool> = (1..=48).map(|i| i % 12 == 0).collect();
+    let actual: Vec<bool> = (0..48)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn abs_diff() {
+    let cases: [(u32, u32, u32); 4] = [(3, 10, 7), (10, 3, 7), (0, 0xffff_ffff, 0xffff_ffff), (7, 7, 0)];
+    for &(x, y, expected) in &cases {
+        let a = expr_to_var(format!("{} <-> {}", x, y)).unwrap();
+        let b = expr_to_var(format!("{} <-> {}", y, x)).unwrap();
+
+        assert_eq!(a.borrow_mut().next(), expected);
+        assert_eq!(b.borrow_mut().next(), expected);
+    }
+}
+
+#[test]
+fn abs_diff_symmetric() {
+    let a = expr_to_var("Pattern(0, 5, 9) <-> Pattern(9, 2, 9)").unwrap();
+    let b = expr_to_var("Pattern(9, 2, 9) <-> Pattern(0, 5, 9)").unwrap();
+    let mut a = a.borrow_mut();
+    let mut b = b.borrow_mut();
+
+    let actual: Vec<u32> = (0..3).map(|_| a.next()).collect();
+    assert_eq!(actual, [9, 3, 0]);
+
+    let actual: Vec<u32> = (0..3).map(|_| b.next()).collect();
+    assert_eq!(actual, [9, 3, 0]);
+}
+
+fn transform_error(expr: &str) -> String {
+    match expr_to_var(expr) {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn empty_operand() {
+    assert_eq!(
+        transform_error("Pattern(1, 2) + {0: 1, 0: 2}"),
+        "error at 1:1: the operand `{0: 0x1, 0: 0x2, }` of `+` never produces a value because all weights of its samples are 0"
+    );
+    assert!(transform_error("r{0: 1} * 2").contains("`r{0: 0x1, }` of `*`"));
+    assert!(transform_error("~o{0: 1}").contains("`o{0: 0x1, }` of `~`"));
+}
+
+#[test]
+fn empty_wrapped_operand() {
+    assert!(transform_error("Repeat({0: 1}, 2) + 1").contains("never produces a value"));
+    assert!(transform_error("Pattern({0: 1}, r{0: 2}) * 2").contains("never produces a value"));
+    assert!(transform_error("~Repeat(Pattern({0: 1}), 3)").contains("never produces a value"));
+
+    // Not empty because one of the sub-expressions produces values
+    assert!(expr_to_var("Pattern({0: 1}, 5) + 1").is_ok());
+}
+
+#[test]
+fn empty_nested_sample_operand() {
+    // The outer sample is not empty even though it can select an empty sample
+    let a = expr_to_var("r{1: {0: 1}, 1: 2} + 1").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..10 {
+        assert!([1, 3].contains(&a.next()));
+    }
+}
@@ -0,0 +1,64 @@
+mod util;
+use crate::util::*;
+
+use std::rc::Rc;
+
+fn model_with_output_mask(s: &str, output_mask: u32) -> rvs::Model {
+    model_with(s, Default::default(), |transform| transform.set_output_mask(output_mask)).unwrap()
+}
+
+const VARIABLES: &str = "
+    const C = 0xffff;
+    a = [0, 0xffff_ffff];
+    b = r{0x1234_5678, 0xffff_ffff};
+    c = a + 0xf000;
+    d = Pattern(C, 0x1_0000);
+";
+
+#[test]
+fn all_variables_fit_in_mask() {
+    let model = model_with_output_mask(VARIABLES, 0xfff);
+
+    let mut max = 0;
+    for _ in 0..1000 {
+        for (_, variable) in model.variables_iter() {
+            let value = variable.borrow_mut().next();
+            assert!(value <= 0xfff, "0x{:x}", value);
+            max = max.max(value);
+        }
+    }
+
+    assert_eq!(max, 0xfff);
+}
+
+#[test]
+fn mask_of_zero() {
+    let model = model_with_output_mask(VARIABLES, 0);
+
+    for _ in 0..100 {
+        for (_, variable) in model.variables_iter() {
+            assert_eq!(variable.borrow_mut().next(), 0);
+        }
+    }
+}
+
+#[test]
+fn unmasked_by_default() {
+    let model = rvs::parse(&Default::default(), VARIABLES).unwrap();
+
+    let b = model.get_variable_by_name("b").unwrap();
+    let values: Vec<u32> = (0..100).map(|_| b.borrow_mut().next()).collect();
+    assert!(values.contains(&0xffff_ffff));
+    assert!(values.contains(&0x1234_5678));
+}
+
+#[test]
+fn masked_after_value_transform() {
+    let model = model_with_output_mask("a = 0x12;", 0xff);
+
+    let a = model.get_variable_by_name("a").unwrap();
+    a.borrow_mut()
+        .set_value_transform(Some(Rc::new(|value| value << 4)));
+
+    assert_eq!(a.borrow_mut().next(), 0x20);
+}
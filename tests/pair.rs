@@ -0,0 +1,99 @@
+fn samples(rho: u32) -> Vec<(f64, f64)> {
+    // All variables start from the same seed.  The extra range offsets the stream of `b` so that
+    // `a` and `b` are independent.
+    let s = format!("a = [0, 1000]; b = [0, 1] * 0 + [0, 1000]; pair(a, b, {});", rho);
+    let model = rvs::parse(&Default::default(), &s).unwrap();
+
+    (0..2000)
+        .map(|_| {
+            let values = model.next_group(&["a", "b"]).unwrap();
+            (f64::from(values[0]), f64::from(values[1]))
+        })
+        .collect()
+}
+
+fn correlation(samples: &[(f64, f64)]) -> f64 {
+    let n = samples.len() as f64;
+    let mean_a = samples.iter().map(|s| s.0).sum::<f64>() / n;
+    let mean_b = samples.iter().map(|s| s.1).sum::<f64>() / n;
+
+    let cov: f64 = samples.iter().map(|s| (s.0 - mean_a) * (s.1 - mean_b)).sum();
+    let var_a: f64 = samples.iter().map(|s| (s.0 - mean_a).powi(2)).sum();
+    let var_b: f64 = samples.iter().map(|s| (s.1 - mean_b).powi(2)).sum();
+
+    cov / (var_a * var_b).sqrt()
+}
+
+#[test]
+fn correlation_increases_with_rho() {
+    let correlations: Vec<f64> = [0, 25, 50, 75, 100]
+        .iter()
+        .map(|&rho| correlation(&samples(rho)))
+        .collect();
+
+    println!("{:?}", correlations);
+    assert!(correlations[0].abs() < 0.1);
+    assert!(correlations.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!((correlations[4] - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn max_rho_equal() {
+    assert!(samples(rvs::MAX_PAIR_RHO).iter().all(|s| s.0 == s.1));
+}
+
+#[test]
+fn zero_rho_independent() {
+    let paired = rvs::parse(&Default::default(), "a = [0, 9]; b = [0, 9]; pair(a, b, 0);").unwrap();
+    let unpaired = rvs::parse(&Default::default(), "a = [0, 9]; b = [0, 9];").unwrap();
+
+    for _ in 0..100 {
+        assert_eq!(
+            paired.next_group(&["a", "b"]).unwrap(),
+            unpaired.next_group(&["a", "b"]).unwrap()
+        );
+    }
+}
+
+#[test]
+fn members_advanced_once() {
+    let model =
+        rvs::parse(&Default::default(), "a = Sequence(100); b = 50; pair(a, b, 50);").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+
+    // The shared value is the next value of `a` drawn without advancing it
+    for (i, expected) in [[0, 25], [1, 26], [2, 26]].iter().enumerate() {
+        assert_eq!(model.next_group(&["a", "b"]).unwrap(), expected);
+        assert_eq!(a.borrow().prev(), i as u32);
+        assert_eq!(a.borrow().count(), i as u64 + 1);
+    }
+}
+
+#[test]
+fn values_stay_in_range() {
+    let model =
+        rvs::parse(&Default::default(), "a = [10, 20]; b = [30, 40]; pair(a, b, 50);").unwrap();
+
+    for _ in 0..100 {
+        let values = model.next_group(&["a", "b"]).unwrap();
+        assert!((10..=20).contains(&values[0]));
+        assert!((20..=40).contains(&values[1]));
+    }
+}
+
+#[test]
+fn invalid_rho() {
+    assert!(rvs::parse(&Default::default(), "a = 1; b = 2; pair(a, b, 101);").is_err());
+}
+
+#[test]
+fn unknown_variable() {
+    assert!(rvs::parse(&Default::default(), "a = 1; pair(a, b, 50);").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = 1; b = 2; pair(a, b, 50);").unwrap();
+
+    assert_eq!(model.to_string(), "a = 0x1;\nb = 0x2;\npair(a, b, 50);\n");
+}
@@ -10,3 +10,27 @@ fn basic() {
     let result = b.borrow_mut().next();
     assert_eq!(result, 2);
 }
+
+#[test]
+fn unknown_variable_reports_line() {
+    let error = match rvs::parse(&Default::default(), "a = 1;\nb = 2;\n  c = a + d;") {
+        Ok(_) => panic!("expected error"),
+        Err(error) => error,
+    };
+
+    assert_eq!(error.to_string(), "error at 3:11: Could not find symbol 'd'");
+}
+
+#[test]
+fn unknown_variable_reports_reference_location() {
+    let error = rvs::parse(&Default::default(), "a = 1;\nb = a +\n    c.copy.next;").err().unwrap();
+
+    assert_eq!(error.to_string(), "error at 3:5: Could not find symbol 'c'");
+}
+
+#[test]
+fn forward_reference_is_an_error() {
+    let error = rvs::parse(&Default::default(), "b = a.next; a = 5;").err().unwrap();
+
+    assert_eq!(error.to_string(), "error at 1:5: Could not find symbol 'a'");
+}
@@ -0,0 +1,94 @@
+mod util;
+use crate::util::*;
+
+use std::env::current_dir;
+
+fn recover(s: &str) -> (Vec<rvs::Error>, usize) {
+    let search_path = Default::default();
+    let mut parser = rvs::Parser::new(&search_path);
+    let errors = parser.parse_recover(s);
+
+    (errors, parser.ast().len())
+}
+
+#[test]
+fn skips_malformed_statement() {
+    let (errors, statements) = recover("a = 1;\nb = (2 +;\nc = 3;\n");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(statements, 2);
+    assert!(errors[0].to_string().contains("2:"), "{}", errors[0]);
+}
+
+#[test]
+fn statements_on_one_line() {
+    let (errors, statements) = recover("a = 1; b = ; c = 3;");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(statements, 2);
+    assert!(errors[0].to_string().starts_with("error at 1:12"), "{}", errors[0]);
+}
+
+#[test]
+fn multiple_errors() {
+    let (errors, statements) = recover("a = ;\nb = 1;\nc = [1, 2;\nd = 2;\ne = Pattern(;\n");
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(statements, 2);
+}
+
+#[test]
+fn unterminated_statement() {
+    let (errors, statements) = recover("a = 1;\nb = 2");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(statements, 1);
+}
+
+#[test]
+fn no_errors() {
+    let (errors, statements) = recover("enum E { A, B } a = 1; group g { b = 2; }");
+
+    assert!(errors.is_empty());
+    assert_eq!(statements, 3);
+}
+
+#[test]
+fn names_starting_with_keywords() {
+    let (errors, statements) = recover("groupsize = {1,\n 2};\nenumerated = {3}\n;\n");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(statements, 2);
+}
+
+#[test]
+fn parse_all_errors() {
+    let errors = rvs::parse_all_errors(&Default::default(), "a = ;\nb = a;\nc = ;\n").err().unwrap();
+    assert_eq!(errors.len(), 2);
+
+    let model = rvs::parse_all_errors(&Default::default(), "a = 1; b = a + 1;").ok().unwrap();
+    assert_eq!(model.get_variable_by_name("b").unwrap().borrow_mut().next(), 2);
+
+    let errors = rvs::parse_all_errors(&Default::default(), "a = b;").err().unwrap();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn error_in_group() {
+    let (errors, statements) = recover("group g {\n    a = ;\n}\nb = 1;\n");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(statements, 1);
+}
+
+#[test]
+fn import_is_idempotent_across_statements() {
+    let search_path = rvs::SearchPath::new(vec![current_dir().unwrap().join("tests/parse_reader")]);
+    let mut parser = rvs::Parser::new(&search_path);
+
+    let errors = parser.parse_recover("import a;\na = 5;\nb = ;\nimport a;\n");
+    assert_eq!(errors.len(), 1);
+
+    let model = transform_with(&parser, Default::default(), |_| {}).unwrap();
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow_mut().next(), 5);
+}
@@ -0,0 +1,102 @@
+fn assert_err(bytes: &[u8]) {
+    assert!(rvs::parse_fuzz(bytes).is_err(), "{:?}", String::from_utf8_lossy(bytes));
+}
+
+#[test]
+fn valid() {
+    let model = rvs::parse_fuzz(b"a = [0, 3]; b = a + 1;").unwrap();
+
+    assert!(model.get_variable_by_name("b").is_some());
+}
+
+#[test]
+fn invalid_utf8() {
+    assert_err(b"a = \xff\xfe;");
+    assert_err(b"\xc3\x28 = 1;");
+    assert!(rvs::parse_fuzz(b"a = 1; // \xff\n").is_ok());
+}
+
+#[test]
+fn truncated() {
+    let s = b"enum E { A, B } a = r{1: [0, 3], 2: Pattern(E::A, 5)} except {2};";
+
+    for len in 0..s.len() {
+        let _ = rvs::parse_fuzz(&s[..len]);
+    }
+}
+
+#[test]
+fn deeply_nested() {
+    let n = 100_000;
+    assert_err(format!("a = {}1{};", "(".repeat(n), ")".repeat(n)).as_bytes());
+    assert_err(format!("a = {}1;", "~".repeat(n)).as_bytes());
+    assert_err(format!("a = 1{};", " + 1".repeat(n)).as_bytes());
+    assert_err(format!("a = 1{};", ".done".repeat(n)).as_bytes());
+    assert_err(format!("{}a = 1;", "group g { ".repeat(n)).as_bytes());
+}
+
+#[test]
+fn within_limits() {
+    let nesting = rvs::MAX_FUZZ_NESTING;
+    assert!(rvs::parse_fuzz(format!("a = {}1{};", "(".repeat(nesting), ")".repeat(nesting)).as_bytes()).is_ok());
+
+    // Minus one for the `=`
+    let operators = rvs::MAX_FUZZ_OPERATORS - 1;
+    assert!(rvs::parse_fuzz(format!("a = {}1;", "~".repeat(operators)).as_bytes()).is_ok());
+    assert!(rvs::parse_fuzz(format!("a = {}1;", "-".repeat(operators)).as_bytes()).is_ok());
+}
+
+#[test]
+fn huge_literals() {
+    assert_err(b"a = 99999999999999999999;");
+    assert_err(b"a = 0x1_0000_0000;");
+    assert_err(b"a = 5G;");
+}
+
+#[test]
+fn import_rejected() {
+    assert_err(b"import a;");
+    assert_err(b"import 'Cargo.toml';");
+}
+
+#[test]
+fn pattern_file_rejected() {
+    assert_err(b"a = PatternFile('Cargo.toml');");
+    assert_err(b"a = 1 + Pattern(1, PatternFile('Cargo.toml'));");
+
+    // Only the expression is rejected, not the name
+    assert!(rvs::parse_fuzz(b"a = 1; // PatternFile\n").is_ok());
+    assert!(rvs::parse_fuzz(b"PatternFiles = 1;").is_ok());
+}
+
+#[test]
+fn division_by_zero() {
+    assert_err(b"a = 1 / 0;");
+    assert_err(b"a = 1 % 0;");
+    assert_err(b"const A = 1 / (1 - 1);");
+    assert_err(b"a = [0, 1 / (1 - 1)];");
+}
+
+#[test]
+fn division_by_zero_at_transform_time() {
+    for s in &[
+        "a = [0, 1 / (1 - 1)];",
+        "a = [0, 1 % (1 - 1)];",
+        "const A = 1 / (1 - 1);",
+        "a = Sequence(0, 10, 5 / (1 - 1));",
+        "a = let x = 1 / (1 - 1) in x;",
+    ] {
+        match rvs::parse(&Default::default(), s) {
+            Err(rvs::Error::Transform(e)) => {
+                assert!(e.to_string().contains("division by zero"), "{}", e)
+            }
+            _ => panic!("expected a transform error for {}", s),
+        }
+    }
+}
+
+#[test]
+fn zero_increment() {
+    assert_err(b"a = Sequence(0, 10, 0);");
+    assert_err(b"a = Sequence(0, 10, 1 - 1);");
+}
@@ -0,0 +1,73 @@
+use std::env::current_dir;
+use std::io::Cursor;
+
+#[test]
+fn large_input() {
+    let mut source = String::new();
+    for i in 0..50_000 {
+        source.push_str(&format!("// variable {}\nv{} = Pattern(\n    {},\n    {}\n);\n", i, i, i, i + 1));
+    }
+    source.push_str("enum E {\n    A,\n    B = 5,\n}\nlast = E::B + v49999;\n");
+    assert!(source.len() > 2_000_000);
+
+    let model = rvs::parse_reader(&Default::default(), Cursor::new(source.as_bytes())).unwrap();
+
+    for i in [0, 1234, 49_999].iter() {
+        let variable = model.get_variable_by_name(&format!("v{}", i)).unwrap();
+        let mut variable = variable.borrow_mut();
+        assert_eq!(variable.next(), *i);
+        assert_eq!(variable.next(), i + 1);
+    }
+
+    let last = model.get_variable_by_name("last").unwrap();
+    assert_eq!(last.borrow_mut().next(), 5 + 49_999);
+}
+
+#[test]
+fn multiple_statements_per_line() {
+    let source = "a = 1; b = [0,\n1]; c = 2;\n";
+
+    let model = rvs::parse_reader(&Default::default(), Cursor::new(source)).unwrap();
+
+    assert_eq!(model.to_string(), rvs::parse(&Default::default(), source).unwrap().to_string());
+}
+
+#[test]
+fn parse_error_line() {
+    let source = "a = 1;\n\nb = Pattern(1,\n  2 +);\n";
+
+    let error = match rvs::parse_reader(&Default::default(), Cursor::new(source)) {
+        Ok(_) => panic!("expected error"),
+        Err(error) => error,
+    };
+
+    assert!(error.to_string().starts_with("error at 4:6:"), "{}", error);
+}
+
+#[test]
+fn transform_error_line() {
+    let source = "a = 1;\n// comment\n\n  b = c;\n";
+
+    let error = match rvs::parse_reader(&Default::default(), Cursor::new(source)) {
+        Ok(_) => panic!("expected error"),
+        Err(error) => error,
+    };
+
+    assert_eq!(error.to_string(), "error at 4:7: Could not find symbol 'c'");
+}
+
+#[test]
+fn missing_terminator() {
+    assert!(rvs::parse_reader(&Default::default(), Cursor::new("a = 1;\nb = 2")).is_err());
+}
+
+#[test]
+fn import_is_idempotent_across_statements() {
+    let search_path = rvs::SearchPath::new(vec![current_dir().unwrap().join("tests/parse_reader")]);
+    let source = "import a;\na = 5;\nimport a;\n";
+
+    let model = rvs::parse_reader(&search_path, Cursor::new(source)).unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    assert_eq!(a.borrow_mut().next(), 5);
+}
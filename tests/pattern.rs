@@ -7,7 +7,7 @@ fn yields_a_repeating_pattern() {
     let mut a = a.borrow_mut();
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .collect();
@@ -22,10 +22,10 @@ fn selects_another_subexpr_when_current_subexpr_done() {
     let a = expr_to_var("Pattern(Pattern(0, 1), Pattern(2, 3))").unwrap();
     let mut a = a.borrow_mut();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .collect();
@@ -34,3 +34,14 @@ fn selects_another_subexpr_when_current_subexpr_done() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn done_clears_on_next_cycle() {
+    let a = expr_to_var("Pattern(0, 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (1, true)].into_iter().cycle().take(40).collect();
+    let actual: Vec<(u32, bool)> = (0..40).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
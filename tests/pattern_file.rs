@@ -0,0 +1,112 @@
+use std::env::current_dir;
+use std::fs;
+
+fn search_path() -> rvs::SearchPath {
+    rvs::SearchPath::new(vec![current_dir().unwrap().join("tests/pattern_file")])
+}
+
+fn error(s: &str) -> String {
+    match rvs::parse(&search_path(), s) {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn values(model: &rvs::Model, name: &str, n: usize) -> Vec<(u32, bool)> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..n)
+        .map(|_| {
+            let value = variable.next();
+            (value, variable.done())
+        })
+        .collect()
+}
+
+#[test]
+fn basic() {
+    let model = rvs::parse(&search_path(), "a = PatternFile('values.txt');").unwrap();
+
+    let expected: Vec<(u32, bool)> = [3, 1, 4, 1, 5, 9, 2, 3]
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| (value, i == 6))
+        .collect();
+    assert_eq!(values(&model, "a", 8), expected);
+}
+
+#[test]
+fn same_as_pattern() {
+    let model = rvs::parse(
+        &search_path(),
+        "a = PatternFile(\"values.txt\"); b = Pattern(3, 1, 4, 1, 5, 9, 2);",
+    ).unwrap();
+
+    assert_eq!(values(&model, "a", 21), values(&model, "b", 21));
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&search_path(), "a = PatternFile('values.txt') + 1;").unwrap();
+    let a = model.get_variable_by_name("a").unwrap().borrow();
+
+    assert_eq!(a.to_string(), "(PatternFile('values.txt') + 0x1)");
+}
+
+#[test]
+fn missing() {
+    let error = error("a = PatternFile('missing.txt');");
+
+    assert!(error.contains("PatternFile() cannot read 'missing.txt'"));
+}
+
+#[test]
+fn empty() {
+    let error = error("a = PatternFile('empty.txt');");
+
+    assert!(error.contains("PatternFile() 'empty.txt' contains no values"));
+}
+
+#[test]
+fn malformed() {
+    let error = error("a = PatternFile('malformed.txt');");
+
+    assert!(error.contains("PatternFile() 'malformed.txt' line 2: expected an integer but found 'four'"));
+}
+
+#[test]
+fn large() {
+    let path = std::env::temp_dir().join(format!("rvs_pattern_file_{}.txt", std::process::id()));
+    let contents: String = (0..100_000u32).map(|i| format!("{}\n", i)).collect();
+    fs::write(&path, contents).unwrap();
+
+    let model = rvs::parse(
+        &Default::default(),
+        &format!("a = PatternFile('{}');", path.display()),
+    );
+    fs::remove_file(&path).unwrap();
+
+    let model = model.unwrap();
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    for i in 0..100_000 {
+        assert_eq!(a.next(), i);
+    }
+    assert!(a.done());
+}
+
+#[test]
+fn max_children() {
+    let mut parser = rvs::Parser::new(&search_path());
+    parser.parse("a = PatternFile('values.txt');").unwrap();
+
+    let transform = |max_children| {
+        let mut transform = rvs::Transform::new(Default::default());
+        transform.set_search_path(search_path());
+        transform.set_max_children(max_children);
+        transform.transform(&mut rvs::Model::new(), parser.ast()).map_err(|e| e.to_string())
+    };
+
+    assert!(transform(7).is_ok());
+    let error = transform(6).unwrap_err();
+    assert!(error.contains("'values.txt' has more than 6 values which exceeds the limit"), "{}", error);
+}
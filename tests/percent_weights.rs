@@ -0,0 +1,45 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashMap;
+
+#[test]
+fn distribution() {
+    let a = expr_to_var("r{70%: 0, 30%: 1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut actual: HashMap<u32, u32> = HashMap::new();
+
+    for _ in 0..1000 {
+        let entry = actual.entry(a.next()).or_insert(0);
+        *entry += 1;
+    }
+
+    println!("expected:{{0: 700, 1: 300}} actual:{:?}", actual);
+    assert!(actual[&0] >= 700 - 50 && actual[&0] <= 700 + 50);
+    assert!(actual[&1] >= 300 - 50 && actual[&1] <= 300 + 50);
+}
+
+#[test]
+fn without_replacement() {
+    let a = expr_to_var("{50%: 0, 50%: 1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = vec![a.next(), a.next()];
+    values.sort();
+    assert_eq!(values, vec![0, 1]);
+}
+
+#[test]
+fn invalid_sum() {
+    let result = expr_to_var("r{70%: 0, 20%: 1}");
+
+    assert!(result.is_err());
+    assert!(format!("{}", result.err().unwrap()).contains("must sum to 100%, found 90%"));
+}
+
+#[test]
+fn mixed_with_plain_weights() {
+    assert!(expr_to_var("r{70%: 0, 30: 1}").is_err());
+    assert!(expr_to_var("r{100%: 0, 1}").is_err());
+}
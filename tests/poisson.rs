@@ -0,0 +1,63 @@
+mod util;
+use crate::util::*;
+
+fn samples(expr: &str, n: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| a.next()).collect()
+}
+
+fn mean_and_variance(samples: &[u32]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().map(|&value| f64::from(value)).sum::<f64>() / n;
+    let variance = samples
+        .iter()
+        .map(|&value| (f64::from(value) - mean).powi(2))
+        .sum::<f64>()
+        / n;
+
+    (mean, variance)
+}
+
+#[test]
+fn small_lambda() {
+    // lambda = 2.5
+    let (mean, variance) = mean_and_variance(&samples("Poisson(5, 2)", 10_000));
+
+    assert!((mean - 2.5).abs() < 0.1, "{}", mean);
+    assert!((variance - 2.5).abs() < 0.2, "{}", variance);
+}
+
+#[test]
+fn large_lambda() {
+    let (mean, variance) = mean_and_variance(&samples("Poisson(1000, 1)", 10_000));
+
+    assert!((mean - 1000.0).abs() < 2.0, "{}", mean);
+    assert!((variance - 1000.0).abs() < 60.0, "{}", variance);
+}
+
+#[test]
+fn very_large_lambda() {
+    let samples = samples("Poisson(0xffff_ffff, 1)", 100);
+    let (mean, _) = mean_and_variance(&samples);
+
+    // Values above u32::MAX saturate
+    assert!(mean > 4.29e9, "{}", mean);
+}
+
+#[test]
+fn zero_lambda() {
+    assert_eq!(samples("Poisson(0, 3)", 10), vec![0; 10]);
+}
+
+#[test]
+fn zero_denominator() {
+    assert!(expr_to_var("Poisson(1, 0)").is_err());
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("Poisson(5, 2)").unwrap();
+    assert_eq!(a.borrow().to_string(), "Poisson(5, 2)");
+}
@@ -0,0 +1,43 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn basic() {
+    let a = expr_to_var("PopCount(Pattern(0, 1, 0x8000_0001, 0xf0f0, 0x7fff_ffff, 0xffff_ffff))")
+        .unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..6).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0, 1, 2, 8, 31, 32]);
+}
+
+#[test]
+fn range() {
+    let a = expr_to_var("PopCount([0, 0xffff_ffff])").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        let value = a.next();
+        assert!((4..=28).contains(&value), "{}", value);
+    }
+
+    assert_eq!(a.value_bounds(), Some((0, 32)));
+}
+
+#[test]
+fn done_passed_through() {
+    let a = expr_to_var("PopCount(Pattern(1, 3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(actual, [(1, false), (2, true), (1, false), (2, true)]);
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = PopCount([0, 0xff]);").unwrap();
+
+    assert_eq!(model.to_string(), "a = PopCount([0x0, 0xff]);\n");
+}
@@ -0,0 +1,58 @@
+use std::rc::Rc;
+
+/// One expression per type.  `other` is a variable defined by `model`.
+const EXPRS: &[&str] = &[
+    "5",
+    "[1, 10]",
+    "Pattern(1, 2, 3)",
+    "Sequence(1, 10)",
+    "r{1, 2, 3}",
+    "{1, 2, 3}",
+    "o{1, 2, 3}",
+    "Done(Pattern(1, 2))",
+    "Once([1, 10])",
+    "Bits(4) + 1",
+    "Shuffle(1, 2, 3)",
+    "MulMod([1, 10], 3, 7) + 1",
+    "[1, 10] except {5}",
+    "Accumulate([1, 10])",
+    "Pattern(1, 2) + 10",
+    "~Pattern(1, 2)",
+    "-Pattern(1, 2)",
+    "other",
+    "other.prev + 1",
+    "other.copy",
+];
+
+fn model(expr: &str) -> rvs::Model {
+    let s = format!("other = Pattern(1, 2, 3);\na = {};", expr);
+
+    rvs::parse(&Default::default(), &s).unwrap()
+}
+
+#[test]
+fn zero_before_next() {
+    for expr in EXPRS {
+        let model = model(expr);
+        let a = Rc::clone(model.get_variable_by_name("a").unwrap());
+
+        assert_eq!(a.borrow().prev(), 0, "{}", expr);
+    }
+}
+
+#[test]
+fn last_next_after_next() {
+    for expr in EXPRS {
+        let model = model(expr);
+        let a = Rc::clone(model.get_variable_by_name("a").unwrap());
+        let other = Rc::clone(model.get_variable_by_name("other").unwrap());
+
+        for _ in 0..8 {
+            other.borrow_mut().next();
+            let value = a.borrow_mut().next();
+
+            assert_eq!(a.borrow().prev(), value, "{}", expr);
+            assert_ne!(value, 0, "{}", expr);
+        }
+    }
+}
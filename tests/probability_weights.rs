@@ -0,0 +1,121 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashMap;
+
+#[test]
+fn weighted_choice() {
+    let a = expr_to_var("(0.25: [0, 10], 0.75: 100)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut low = 0;
+    let mut high = 0;
+    for _ in 0..4000 {
+        match a.next() {
+            0..=10 => low += 1,
+            100 => high += 1,
+            value => panic!("unexpected value {}", value),
+        }
+    }
+
+    println!("expected:{{low: 1000, high: 3000}} actual:{{low: {}, high: {}}}", low, high);
+    assert!((1000 - 100..=1000 + 100).contains(&low));
+    assert!((3000 - 100..=3000 + 100).contains(&high));
+}
+
+#[test]
+fn selected_expression_is_driven() {
+    let a = expr_to_var("(0.5: Pattern(1, 2, 3), 0.5: 10 + Sequence(3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut pattern = Vec::new();
+    let mut sequence = Vec::new();
+    for _ in 0..100 {
+        let value = a.next();
+        if value < 10 {
+            pattern.push(value);
+        } else {
+            sequence.push(value);
+        }
+    }
+
+    // Each branch advances only when selected
+    assert!(pattern.iter().zip([1, 2, 3].iter().cycle()).all(|(a, b)| a == b));
+    assert!(sequence.iter().zip([10, 11, 12, 13].iter().cycle()).all(|(a, b)| a == b));
+}
+
+#[test]
+fn other_weights() {
+    let a = expr_to_var("(30%: 0, 70%: 1)").unwrap();
+    let mut a = a.borrow_mut();
+    let mut actual: HashMap<u32, u32> = HashMap::new();
+    for _ in 0..1000 {
+        *actual.entry(a.next()).or_insert(0) += 1;
+    }
+    assert!(actual[&0] >= 300 - 50 && actual[&0] <= 300 + 50);
+
+    let a = expr_to_var("(1: 0, 3: 1)").unwrap();
+    let mut a = a.borrow_mut();
+    let ones = (0..1000).filter(|_| a.next() == 1).count();
+    assert!((750 - 50..=750 + 50).contains(&ones));
+}
+
+#[test]
+fn in_samples() {
+    let a = expr_to_var("r{0.1: 0, 0.25: 1, 0.65: 2}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut actual: HashMap<u32, u32> = HashMap::new();
+    for _ in 0..2000 {
+        *actual.entry(a.next()).or_insert(0) += 1;
+    }
+
+    println!("expected:{{0: 200, 1: 500, 2: 1300}} actual:{:?}", actual);
+    assert!(actual[&0] >= 200 - 60 && actual[&0] <= 200 + 60);
+    assert!(actual[&1] >= 500 - 80 && actual[&1] <= 500 + 80);
+    assert!(actual[&2] >= 1300 - 100 && actual[&2] <= 1300 + 100);
+
+    // Without replacement selects each position in proportion per cycle
+    let a = expr_to_var("{0.5: 0, 0.5: 1}").unwrap();
+    let mut a = a.borrow_mut();
+    let mut values = vec![a.next(), a.next()];
+    values.sort();
+    assert_eq!(values, vec![0, 1]);
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("(0.25: 0, 0.75: 1)").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "r{1: 0x0, 3: 0x1, }");
+}
+
+#[test]
+fn invalid_sum() {
+    let error = expr_to_var("(0.25: 0, 0.7: 1)").err().unwrap();
+
+    assert!(error.to_string().contains("must sum to 1, found 0.95"), "{}", error);
+}
+
+#[test]
+fn mixed_with_other_weights() {
+    assert!(expr_to_var("r{0.5: 0, 1: 1}").is_err());
+    assert!(expr_to_var("r{0.5: 0, 50%: 1}").is_err());
+    assert!(expr_to_var("r{0.5: 0, 1}").is_err());
+}
+
+#[test]
+fn parenthesized_expression() {
+    let a = expr_to_var("(1 + 2) * (3)").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 9);
+}
+
+#[test]
+fn fine_probabilities_with_replacement() {
+    let a = expr_to_var("r{0.000000001: 0, 0.999999999: 1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.weights(), Some(&[1, 999_999_999][..]));
+    assert!((0..100).all(|_| a.next() == 1));
+}
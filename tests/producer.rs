@@ -0,0 +1,111 @@
+mod util;
+use crate::util::*;
+
+fn parser(s: &str) -> rvs::Parser {
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.parse(s).unwrap();
+
+    parser
+}
+
+fn serial(parser: &rvs::Parser, seed: rvs::Seed, name: &str, n: usize) -> Vec<u32> {
+    let model = transform_with(parser, seed, |_| {}).unwrap();
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..n).map(|_| variable.next()).collect()
+}
+
+const SOURCE: &str = "
+    a = [0, 0xffffffff];
+    b = r{1, 2, 3: [4, 7]} + a + Pattern(1, 2, 3);
+";
+
+#[test]
+fn matches_serial() {
+    let parser = parser(SOURCE);
+    let expected = serial(&parser, rvs::Seed::from_u32(5), "b", 10_000);
+
+    let mut producer = rvs::spawn_producer(&parser, rvs::Seed::from_u32(5), "b", 64).unwrap();
+    let actual: Vec<u32> = (0..10_000).map(|_| producer.next()).collect();
+    producer.stop();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn configured() {
+    let parser = parser(SOURCE);
+    let seed = rvs::Seed::from_u32(5);
+    let model = transform_with(&parser, seed.clone(), |transform| {
+        transform.set_output_mask(0xff)
+    })
+    .unwrap();
+    let mut variable = model.get_variable_by_name("b").unwrap().borrow_mut();
+    let expected: Vec<u32> = (0..100).map(|_| variable.next()).collect();
+
+    let mut producer = rvs::spawn_producer_with(&parser, seed, "b", 8, |transform| {
+        transform.set_output_mask(0xff)
+    })
+    .unwrap();
+    let actual: Vec<u32> = (0..100).map(|_| producer.next()).collect();
+
+    assert_eq!(expected, actual);
+    assert!(actual.iter().all(|&value| value <= 0xff));
+}
+
+#[test]
+fn try_next() {
+    let parser = parser(SOURCE);
+    let expected = serial(&parser, rvs::Seed::from_u32(5), "a", 100);
+
+    let mut producer = rvs::spawn_producer(&parser, rvs::Seed::from_u32(5), "a", 8).unwrap();
+    let mut actual = Vec::new();
+    while actual.len() < 100 {
+        if let Some(value) = producer.try_next() {
+            actual.push(value);
+        }
+    }
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn rendezvous() {
+    let parser = parser(SOURCE);
+    let expected = serial(&parser, Default::default(), "a", 100);
+
+    let mut producer = rvs::spawn_producer(&parser, Default::default(), "a", 0).unwrap();
+    let actual: Vec<u32> = (0..100).map(|_| producer.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn drop_while_full() {
+    let parser = parser(SOURCE);
+    let producer = rvs::spawn_producer(&parser, Default::default(), "a", 4).unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    drop(producer);
+}
+
+#[test]
+fn undefined_variable() {
+    let parser = parser(SOURCE);
+
+    match rvs::spawn_producer(&parser, Default::default(), "c", 4) {
+        Err(e) => assert_eq!(e.to_string(), "variable 'c' is not defined"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "returned 0")]
+fn propagates_panic() {
+    let parser = parser("a = Sequence(0, 10, Pattern(1, 0));");
+    let mut producer = rvs::spawn_producer(&parser, Default::default(), "a", 4).unwrap();
+
+    for _ in 0..100 {
+        producer.next();
+    }
+}
@@ -0,0 +1,67 @@
+mod util;
+use crate::util::*;
+
+fn values_and_done(expr: &str, n: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| (a.next(), a.done())).collect()
+}
+
+fn values(expr: &str, n: usize) -> Vec<u32> {
+    values_and_done(expr, n).into_iter().map(|(value, _)| value).collect()
+}
+
+#[test]
+fn ascending() {
+    assert_eq!(
+        values_and_done("Ramp(0, 100, 5)", 6),
+        [(0, false), (25, false), (50, false), (75, false), (100, true), (0, false)]
+    );
+}
+
+#[test]
+fn descending() {
+    assert_eq!(values("Ramp(100, 0, 5)", 6), [100, 75, 50, 25, 0, 100]);
+}
+
+#[test]
+fn rounding() {
+    assert_eq!(values("Ramp(0, 10, 4)", 4), [0, 3, 7, 10]);
+    assert_eq!(values("Ramp(10, 0, 4)", 4), [10, 7, 3, 0]);
+    assert_eq!(values("Ramp(0, 1, 3)", 3), [0, 1, 1]);
+}
+
+#[test]
+fn exact_endpoints() {
+    for &(start, end, steps) in [(0, 0xffff_ffff, 7), (0xffff_ffff, 0, 3), (3, 1000, 999), (5, 5, 4)].iter() {
+        let actual = values(&format!("Ramp({}, {}, {})", start, end, steps), steps as usize);
+
+        assert_eq!(actual[0], start);
+        assert_eq!(actual[steps as usize - 1], end);
+    }
+}
+
+#[test]
+fn evenly_spaced() {
+    let actual = values("Ramp(10, 1010, 11)", 11);
+    let spacing: Vec<u32> = actual.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+    assert_eq!(spacing, [100; 10]);
+}
+
+#[test]
+fn one_step() {
+    assert_eq!(
+        values_and_done("Ramp(7, 100, 1)", 3),
+        [(7, true), (7, true), (7, true)]
+    );
+}
+
+#[test]
+fn zero_steps() {
+    match expr_to_var("Ramp(0, 10, 0)") {
+        Err(e) => assert_eq!(e.to_string(), "error at 1:1: Ramp() steps must not be 0"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
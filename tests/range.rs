@@ -25,12 +25,21 @@ fn equal_limits() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn equal_limits_other_than_one() {
+    let a = expr_to_var("[5, 5]").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 5);
+    assert_eq!(a.next(), 5);
+}
+
 #[test]
 fn done_after_each_next() {
     let a = expr_to_var("[0, 8]").unwrap();
     let mut a = a.borrow_mut();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 
     let expected: Vec<bool> = (0..20).map(|_| true).collect();
     let actual: Vec<bool> = (0..20)
@@ -0,0 +1,31 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn get_and_set() {
+    let model = model("a = [10, 0];");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.range_bounds(), Some((10, 0)));
+
+    assert!(a.set_range_bounds(1000, 2000));
+    assert_eq!(a.range_bounds(), Some((1000, 2000)));
+    assert_eq!(a.to_string(), "[0x3e8, 0x7d0]");
+
+    for _ in 0..100 {
+        let value = a.next();
+        assert!((1000..=2000).contains(&value));
+    }
+}
+
+#[test]
+fn not_a_range() {
+    let model = model("a = Pattern([0, 1]); b = [3, 3];");
+
+    for name in &["a", "b"] {
+        let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+        assert_eq!(variable.range_bounds(), None);
+        assert!(!variable.set_range_bounds(0, 1));
+    }
+}
@@ -0,0 +1,134 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashMap;
+
+fn histogram(expr: &str) -> HashMap<u32, u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut histogram = HashMap::new();
+    for _ in 0..10_000 {
+        *histogram.entry(a.next()).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+#[test]
+fn linear_up_favors_high_end() {
+    let histogram = histogram("[0, 3]: linear_up");
+
+    println!("expected:{{0: 1000, 1: 2000, 2: 3000, 3: 4000}} actual:{:?}", histogram);
+    assert_eq!(histogram.len(), 4);
+    assert!(histogram[&0] < histogram[&1]);
+    assert!(histogram[&1] < histogram[&2]);
+    assert!(histogram[&2] < histogram[&3]);
+    assert!((3600..=4400).contains(&histogram[&3]));
+    assert!((800..=1200).contains(&histogram[&0]));
+}
+
+#[test]
+fn linear_down_favors_low_end() {
+    let histogram = histogram("[10, 13]: linear_down");
+
+    assert_eq!(histogram.len(), 4);
+    assert!(histogram[&10] > histogram[&11]);
+    assert!(histogram[&12] > histogram[&13]);
+}
+
+#[test]
+fn reversed_bounds() {
+    let histogram = histogram("[3, 0]: linear_up");
+
+    assert_eq!(histogram.len(), 4);
+    assert!(histogram[&3] > histogram[&0]);
+}
+
+#[test]
+fn inclusive_bounds() {
+    for expr in ["[0xffff_fffe, 0xffff_ffff]: linear_up", "[0, 0xffff_ffff]: linear_down"].iter() {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        for _ in 0..1000 {
+            a.next();
+        }
+    }
+
+    let histogram = histogram("[0xffff_fffe, 0xffff_ffff]: linear_up");
+    assert_eq!(histogram.len(), 2);
+    assert!(histogram[&0xffff_ffff] > histogram[&0xffff_fffe]);
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = [0, 100]: linear;").unwrap();
+
+    assert_eq!(model.to_string(), "a = [0x0, 0x64]: linear_up;\n");
+}
+
+#[test]
+fn equal_bounds() {
+    let a = expr_to_var("[5, 5]: linear_down").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 5);
+}
+
+#[test]
+fn normal_peaks_at_midpoint() {
+    let histogram = histogram("[0, 10]~normal");
+
+    println!("{:?}", histogram);
+    assert!(histogram.keys().all(|&value| value <= 10));
+    assert!((1900..=2400).contains(&histogram[&5]));
+    assert!(histogram[&5] > histogram[&3]);
+    assert!(histogram[&3] > histogram[&1]);
+    assert!(histogram[&5] > histogram[&7]);
+    assert!(histogram[&7] > histogram[&9]);
+
+    // Symmetric
+    let low: u32 = (0..5).map(|value| histogram.get(&value).unwrap_or(&0)).sum();
+    let high: u32 = (6..=10).map(|value| histogram.get(&value).unwrap_or(&0)).sum();
+    assert!((low as i32 - high as i32).abs() < 400);
+}
+
+#[test]
+fn triangular_peaks_at_midpoint() {
+    let histogram = histogram("[0, 3]~triangular");
+
+    println!("expected:{{0: 1250, 1: 3750, 2: 3750, 3: 1250}} actual:{:?}", histogram);
+    assert_eq!(histogram.len(), 4);
+    assert!((1050..=1450).contains(&histogram[&0]));
+    assert!((3450..=4050).contains(&histogram[&1]));
+    assert!((3450..=4050).contains(&histogram[&2]));
+    assert!((1050..=1450).contains(&histogram[&3]));
+}
+
+#[test]
+fn suffix_in_expression() {
+    let histogram = histogram("[0, 10]~normal + 5");
+
+    assert!(histogram.keys().all(|&value| (5..=15).contains(&value)));
+    assert!(histogram[&10] > histogram[&6]);
+    assert!(histogram[&10] > histogram[&14]);
+}
+
+#[test]
+fn full_range_profiles() {
+    for expr in ["[0, 0xffff_ffff]~normal", "[0, 0xffff_ffff]~triangular"].iter() {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        for _ in 0..1000 {
+            a.next();
+        }
+    }
+}
+
+#[test]
+fn suffix_display() {
+    let model = rvs::parse(&Default::default(), "a = [0, 100]~normal; b = [0, 100]~triangular;").unwrap();
+
+    assert_eq!(model.to_string(), "a = [0x0, 0x64]: normal;\nb = [0x0, 0x64]: triangular;\n");
+}
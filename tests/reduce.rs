@@ -0,0 +1,83 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, done_mode: rvs::BinaryDoneMode, reduce: bool, flatten: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| {
+        transform.set_binary_done_mode(done_mode);
+        transform.set_reduce_operations(reduce);
+        transform.set_flatten_operations(flatten);
+    })
+    .unwrap()
+}
+
+fn values(model: &rvs::Model, name: &str) -> Vec<(u32, bool)> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..100)
+        .map(|_| {
+            let value = variable.next();
+            (value, variable.done())
+        })
+        .collect()
+}
+
+/// Returns a chain of `n` operands of `operator` with random and stateful operands
+fn chain(operator: &str, n: usize) -> String {
+    let operands = ["[0, 0xffffffff]", "Pattern(0xff, 0xf0f0, 7)", "Sequence(3)", "r{1, 2, 3}", "a"];
+
+    (0..n).map(|i| operands[i % operands.len()]).collect::<Vec<_>>().join(&format!(" {} ", operator))
+}
+
+fn source() -> String {
+    format!(
+        "
+        a = Sequence(4);
+        b = {};
+        c = {};
+        d = {};
+        e = {};
+        f = {};
+        g = (1 + [0, 9]) + ((a + Pattern(1, 2)) + (3 + 4)) - 5 * (a * 2 * Sequence(5));
+        ",
+        chain("&", 16),
+        chain("|", 7),
+        chain("^", 5),
+        chain("+", 9),
+        chain("*", 3),
+    )
+}
+
+#[test]
+fn same_values() {
+    let source = source();
+
+    for &done_mode in &[rvs::BinaryDoneMode::Latched, rvs::BinaryDoneMode::Aligned] {
+        for &flatten in &[false, true] {
+            let tree = model(&source, done_mode, false, false);
+            let reduced = model(&source, done_mode, true, flatten);
+
+            for name in &["a", "b", "c", "d", "e", "f", "g"] {
+                assert_eq!(values(&reduced, name), values(&tree, name), "{} {:?} {}", name, done_mode, flatten);
+            }
+        }
+    }
+}
+
+#[test]
+fn display() {
+    let model = model(
+        "a = Sequence(4); b = a & [0, 1] & 2 & 3; c = 1 + (a + 2) * 3 * 4 - 5;",
+        Default::default(),
+        true,
+        false,
+    );
+
+    assert_eq!(
+        model.get_variable_by_name("b").unwrap().borrow().to_string(),
+        "(a & [0x0, 0x1] & 0x2 & 0x3)"
+    );
+    assert_eq!(
+        model.get_variable_by_name("c").unwrap().borrow().to_string(),
+        "((0x1 + ((a + 0x2) * 0x3 * 0x4)) - 0x5)"
+    );
+}
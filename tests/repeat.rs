@@ -0,0 +1,73 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn variable_length_batches() {
+    let a = expr_to_var("Repeat(Pattern(1, 2, 3), Pattern(2, 3, 1))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..12).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 1, 2, 2, 2, 3, 1, 1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn zero_count_skipped() {
+    let a = expr_to_var("Repeat(Pattern(1, 2, 3), Pattern(2, 0, 1, 3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..9).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [1, 1, 2, 3, 3, 3, 1, 1, 2]);
+}
+
+#[test]
+fn done_at_end_of_batch() {
+    let a = expr_to_var("Repeat(Pattern(1, 2), 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<bool> = (0..8)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(actual, [false, false, false, true, false, false, false, true]);
+}
+
+#[test]
+fn random() {
+    let a = expr_to_var("Repeat([10, 20], [1, 3])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert!(values.iter().all(|&value| (10..=20).contains(&value)));
+    assert!(values.windows(2).any(|pair| pair[0] == pair[1]));
+}
+
+#[test]
+fn constant_zero_count_is_an_error() {
+    assert!(expr_to_var("Repeat([0, 9], 0)").is_err());
+}
+
+#[test]
+fn always_zero_count_is_an_error() {
+    assert!(expr_to_var("Repeat([0, 9], Pattern(0))").is_err());
+    assert!(expr_to_var("Repeat([0, 9], [0, 1] & 0)").is_err());
+}
+
+#[test]
+fn zero_count_limit() {
+    let model = model_with("a = Repeat([0, 9], Pattern(0, 0, 1));", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!(next_checked_error(&mut a, 1).starts_with("resample limit of 1 exceeded"));
+    // A count of 1 is used
+    assert!(a.done());
+}
@@ -0,0 +1,135 @@
+mod util;
+use crate::util::*;
+
+use std::io::Cursor;
+
+const SOURCE: &str = "a = [0, 0xffff_ffff]; b = a + Pattern(1, 2); c = r{1, 2, 3};";
+
+fn model(seed: u32) -> rvs::Model {
+    model_with(SOURCE, rvs::Seed::from_u32(seed), |_| {}).unwrap()
+}
+
+/// Interleaves calls to all variables.  Returns values in call order.
+fn session(model: &rvs::Model) -> Vec<u32> {
+    let mut values = Vec::new();
+
+    for i in 0..20 {
+        values.push(next_by_name(model, "a"));
+        if i % 2 == 0 {
+            values.push(next_by_name(model, "b"));
+        }
+        if i % 3 == 0 {
+            values.push(next_by_name(model, "c"));
+        }
+    }
+
+    values
+}
+
+#[test]
+fn replay_with_different_seed() {
+    let mut recorded = model(1);
+    recorded.start_recording();
+    let expected = session(&recorded);
+    let log = recorded.stop_recording();
+
+    let mut replayed = model(2);
+    assert_ne!(session(&model(2)), expected);
+    replayed.replay(&log);
+
+    assert_eq!(session(&replayed), expected);
+}
+
+#[test]
+fn references_are_not_logged() {
+    let mut model = model(0);
+    model.start_recording();
+    next_by_name(&model, "b");
+    next_by_name(&model, "a");
+    let log = model.stop_recording();
+
+    let names: Vec<&str> = log.entries().iter().map(|entry| entry.0.as_str()).collect();
+    assert_eq!(names, ["b", "a"]);
+}
+
+#[test]
+fn not_logged_after_stop() {
+    let mut model = model(0);
+    model.start_recording();
+    next_by_name(&model, "a");
+    let log = model.stop_recording();
+    next_by_name(&model, "a");
+
+    assert_eq!(log.entries().len(), 1);
+}
+
+#[test]
+fn write_read() {
+    let mut model = model(0);
+    model.start_recording();
+    session(&model);
+    let log = model.stop_recording();
+
+    let mut buffer = Vec::new();
+    log.write(&mut buffer).unwrap();
+    let read = rvs::ReplayLog::read(Cursor::new(buffer)).unwrap();
+
+    assert_eq!(read, log);
+}
+
+#[test]
+fn read_invalid() {
+    let error = rvs::ReplayLog::read(Cursor::new("a 1\nb x\n")).unwrap_err();
+
+    assert_eq!(error.to_string(), "invalid replay log entry on line 2: 'b x'");
+}
+
+#[test]
+fn exhausted_holds_last_value() {
+    let mut model = model(0);
+    model.start_recording();
+    let first = next_by_name(&model, "a");
+    let last = next_by_name(&model, "a");
+    let log = model.stop_recording();
+
+    model.replay(&log);
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(actual, [(first, false), (last, true), (last, true), (last, true)]);
+}
+
+#[test]
+fn not_recorded() {
+    let mut model = model(0);
+    model.start_recording();
+    next_by_name(&model, "a");
+    let log = model.stop_recording();
+
+    model.replay(&log);
+    let c = model.get_variable_by_name("c").unwrap();
+    let mut c = c.borrow_mut();
+
+    assert_eq!((c.next(), c.done()), (0, true));
+}
+
+#[test]
+fn skipped_values_are_logged() {
+    let skip_then_next = |model: &rvs::Model| {
+        let a = model.get_variable_by_name("a").unwrap();
+        a.borrow_mut().skip(3);
+        a.borrow_mut().next()
+    };
+
+    let mut recorded = model(1);
+    recorded.start_recording();
+    let expected = skip_then_next(&recorded);
+    let log = recorded.stop_recording();
+    assert_eq!(log.entries().len(), 4);
+
+    let mut replayed = model(2);
+    replayed.replay(&log);
+
+    assert_eq!(skip_then_next(&replayed), expected);
+}
@@ -0,0 +1,104 @@
+mod util;
+use crate::util::*;
+
+fn next_n(model: &rvs::Model, name: &str, count: usize) -> Vec<u32> {
+    let variable = model.get_variable_by_name(name).unwrap();
+
+    (0..count).map(|_| variable.borrow_mut().next()).collect()
+}
+
+const VARIABLES: &str = "
+    a = Pattern(1, 2, 3, 4);
+    b = Pattern(10, 12, 14, 16);
+    c = Pattern(a, 100);
+    d = o{7, 8, 9};
+";
+
+#[test]
+fn reset_all_replays_from_start() {
+    let mut model = rvs::parse(&Default::default(), VARIABLES).unwrap();
+
+    let expected: Vec<Vec<u32>> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|name| next_n(&model, name, 3))
+        .collect();
+
+    model.reset_all();
+    for name in &["a", "b", "c", "d"] {
+        assert_eq!(model.get_variable_by_name(name).unwrap().borrow().prev(), 0);
+        assert!(!model.get_variable_by_name(name).unwrap().borrow().done());
+    }
+
+    let actual: Vec<Vec<u32>> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|name| next_n(&model, name, 3))
+        .collect();
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual[2], [4, 100, 1]);
+}
+
+#[test]
+fn reset_one() {
+    let model = rvs::parse(&Default::default(), VARIABLES).unwrap();
+
+    assert_eq!(next_n(&model, "a", 3), [1, 2, 3]);
+    assert_eq!(next_n(&model, "b", 2), [10, 12]);
+
+    model.get_variable_by_name("a").unwrap().borrow_mut().reset();
+
+    assert_eq!(next_n(&model, "a", 3), [1, 2, 3]);
+    assert_eq!(next_n(&model, "b", 2), [14, 16]);
+}
+
+#[test]
+fn rng_not_reset() {
+    let mut model = rvs::parse(&Default::default(), "a = [0, 0xffff_ffff];").unwrap();
+
+    let before = next_n(&model, "a", 8);
+    model.reset_all();
+    let after = next_n(&model, "a", 8);
+
+    assert_ne!(before, after);
+}
+
+#[test]
+fn reset_forked() {
+    let model = rvs::parse(&Default::default(), VARIABLES).unwrap();
+    let mut fork = model.fork();
+
+    next_n(&model, "a", 2);
+    next_n(&fork, "c", 3);
+    fork.reset_all();
+
+    assert_eq!(next_n(&fork, "c", 2), [1, 2]);
+    assert_eq!(next_n(&model, "a", 1), [3]);
+}
+
+fn selections(a: &mut rvs::Variable, count: usize) -> Vec<(usize, u32)> {
+    (0..count)
+        .map(|_| {
+            let value = a.next();
+            (a.last_selected().unwrap(), value)
+        })
+        .collect()
+}
+
+#[test]
+fn selection_rng_reset() {
+    let model = model_with("a = r{[0, 9], [10, 19], [20, 29]};", Default::default(), |transform| {
+        transform.set_independent_selection(true)
+    })
+    .unwrap();
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    let expected = selections(&mut a, 32);
+    a.reset();
+    let actual = selections(&mut a, 32);
+
+    let indexes = |values: &[(usize, u32)]| -> Vec<usize> {
+        values.iter().map(|&(index, _)| index).collect()
+    };
+    assert_eq!(indexes(&actual), indexes(&expected));
+    assert_ne!(actual, expected);
+}
@@ -0,0 +1,76 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn matches_fresh_stream() {
+    let source = "a = [0, 0xffff];";
+    let fresh = model(source);
+    let expected: Vec<u32> = {
+        let mut a = fresh.get_variable_by_name("a").unwrap().borrow_mut();
+        (0..20).map(|_| a.next()).collect()
+    };
+
+    let model = model(source);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    for _ in 0..5 {
+        a.next();
+    }
+
+    a.reset_rng();
+    let actual: Vec<u32> = (0..20).map(|_| a.next()).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn preserves_traversal() {
+    let model = model("a = Pattern(1, [0, 0xffff], 2, [0, 0xffff]);");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.next(), 1);
+    let first = a.next();
+    assert_eq!(a.next(), 2);
+
+    a.reset_rng();
+    assert_eq!(a.prev(), 2);
+    assert!(!a.done());
+    assert_eq!(a.next(), first);
+    assert!(a.done());
+    assert_eq!(a.next(), 1);
+}
+
+#[test]
+fn deterministic() {
+    let model = model("a = Pattern(1, 2, 3);");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    a.next();
+    a.reset_rng();
+    assert_eq!(a.next(), 2);
+    assert_eq!(a.next(), 3);
+}
+
+#[test]
+fn preserves_draw_count() {
+    let model = model("a = [0, 0xffff];");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    a.set_rng_draw_counting(true);
+
+    a.next();
+    let draws = a.rng_draws().unwrap();
+    a.reset_rng();
+    assert_eq!(a.rng_draws(), Some(draws));
+}
+
+#[test]
+fn fork() {
+    let model = model("a = [0, 0xffff];");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    a.next();
+
+    let mut fork = a.fork();
+    let expected: Vec<u32> = (0..10).map(|_| fork.next()).collect();
+
+    fork.reset_rng();
+    let actual: Vec<u32> = (0..10).map(|_| fork.next()).collect();
+    assert_eq!(actual, expected);
+}
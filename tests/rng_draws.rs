@@ -0,0 +1,81 @@
+mod util;
+use crate::util::*;
+
+fn model(s: &str, count_rng_draws: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| {
+        transform.set_rng_draw_counting(count_rng_draws)
+    })
+    .unwrap()
+}
+
+fn draws_per_next(s: &str) -> Vec<u64> {
+    let model = model(s, true);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(a.rng_draws(), Some(0));
+
+    let mut last = 0;
+    (0..10)
+        .map(|_| {
+            a.next();
+            let draws = a.rng_draws().unwrap();
+            let delta = draws - last;
+            last = draws;
+            delta
+        })
+        .collect()
+}
+
+#[test]
+fn range() {
+    assert_eq!(draws_per_next("a = [0, 9];"), [1; 10]);
+}
+
+#[test]
+fn binary_of_ranges() {
+    assert_eq!(draws_per_next("a = [0, 9] + [10, 19];"), [2; 10]);
+}
+
+#[test]
+fn sample() {
+    // One u64 (two words) to select plus one word for the selected range
+    let expected = if cfg!(target_pointer_width = "64") { 3 } else { 2 };
+
+    assert_eq!(draws_per_next("a = r{[0, 9], [10, 19]};"), [expected; 10]);
+}
+
+#[test]
+fn no_draws() {
+    assert_eq!(draws_per_next("a = Pattern(1, 2, 3);"), [0; 10]);
+}
+
+#[test]
+fn referenced_variables_count_separately() {
+    let model = model("b = [0, 9]; a = b + [0, 9];", true);
+    model.get_variable_by_name("a").unwrap().borrow_mut().next();
+
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow().rng_draws(), Some(1));
+    assert_eq!(model.get_variable_by_name("b").unwrap().borrow().rng_draws(), Some(1));
+}
+
+#[test]
+fn disabled_by_default() {
+    let model = model("a = [0, 9];", false);
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    a.next();
+
+    assert_eq!(a.rng_draws(), None);
+}
+
+#[test]
+fn counting_does_not_change_values() {
+    let counted = model("a = r{[0, 9], [10, 19]};", true);
+    let uncounted = model("a = r{[0, 9], [10, 19]};", false);
+
+    for _ in 0..20 {
+        assert_eq!(
+            counted.get_variable_by_name("a").unwrap().borrow_mut().next(),
+            uncounted.get_variable_by_name("a").unwrap().borrow_mut().next()
+        );
+    }
+}
@@ -0,0 +1,48 @@
+mod util;
+use crate::util::*;
+
+use rvs::JsonFormat;
+
+fn sample_to_json(s: &str, n: usize, format: JsonFormat) -> String {
+    let model = model(s);
+
+    model.sample_to_json(n, format).unwrap()
+}
+
+#[test]
+fn numbers_by_default() {
+    assert_eq!(
+        sample_to_json("a = Pattern(9, 255);", 3, Default::default()),
+        "{\"a\": {\"values\": [9, 255, 9], \"first_done\": 1}}"
+    );
+}
+
+#[test]
+fn hex_strings() {
+    assert_eq!(
+        sample_to_json("a = Pattern(9, 255); b = 0xffffffff;", 2, JsonFormat::HexString),
+        "{\"a\": {\"values\": [\"0x9\", \"0xff\"], \"first_done\": 1}, \
+         \"b\": {\"values\": [\"0xffffffff\", \"0xffffffff\"], \"first_done\": 0}}"
+    );
+}
+
+#[test]
+fn never_done() {
+    assert_eq!(
+        sample_to_json("a = Sequence(10);", 2, JsonFormat::Number),
+        "{\"a\": {\"values\": [0, 1], \"first_done\": null}}"
+    );
+}
+
+#[test]
+fn lazy() {
+    let model = model_with("a = 5; b = a;", Default::default(), |transform| {
+        transform.set_lazy(true)
+    })
+    .unwrap();
+
+    assert_eq!(
+        model.sample_to_json(1, JsonFormat::Number).unwrap(),
+        "{\"a\": {\"values\": [5], \"first_done\": 0}, \"b\": {\"values\": [5], \"first_done\": 0}}"
+    );
+}
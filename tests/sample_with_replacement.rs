@@ -35,7 +35,7 @@ fn done_when_sub_expr_done() {
     let a = expr_to_var("r{Pattern(0, 1), Pattern(2, 3)}").unwrap();
     let mut a = a.borrow_mut();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 
     let expected: Vec<bool> = vec![false, true].into_iter().cycle().take(32).collect();
     let actual: Vec<bool> = (0..expected.len())
@@ -47,3 +47,26 @@ fn done_when_sub_expr_done() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn choose_allows_immediate_repeats() {
+    let a = expr_to_var("Choose(1, 2, 4, 8)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert!(values.windows(2).any(|pair| pair[0] == pair[1]));
+    assert_eq!(values.iter().cloned().collect::<HashSet<u32>>().len(), 4);
+}
+
+#[test]
+fn without_replacement_does_not_repeat_within_a_cycle() {
+    let a = expr_to_var("{1, 2, 4, 8}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert!(values
+        .chunks(4)
+        .all(|cycle| cycle.iter().cloned().collect::<HashSet<u32>>().len() == 4));
+}
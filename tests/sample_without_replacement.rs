@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use rvs::testing::{assert_stream, stream};
+
 mod util;
 use crate::util::*;
 
@@ -52,10 +54,10 @@ fn test_done(expr: &str) {
     let a = expr_to_var(expr).unwrap();
     let mut a = a.borrow_mut();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .map(|(_, done)| (0, done))
@@ -74,3 +76,133 @@ fn done_when_all_done() {
 fn done_when_all_done2() {
     test_done("{0, 0, 0, 0}");
 }
+
+fn first_cycle(expr: &str, seed: u32) -> Vec<u32> {
+    stream(expr, seed, 8).unwrap()
+}
+
+#[test]
+fn in_order_first_cycle_follows_declaration() {
+    for seed in 0..16 {
+        assert_stream("o{1, 2, 3, 4, 5, 6, 7, 8}", seed, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
+
+#[test]
+fn in_order_later_cycles_shuffled() {
+    let a = expr_to_var("o{1, 2, 3, 4, 5, 6, 7, 8}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let in_order: Vec<u32> = (1..=8).collect();
+    let mut shuffled = 0;
+    for cycle in 0..16 {
+        let mut values: Vec<u32> = (0..8).map(|_| a.next()).collect();
+        if cycle > 0 && values != in_order {
+            shuffled += 1;
+        }
+
+        values.sort_unstable();
+        assert_eq!(values, in_order);
+    }
+
+    assert!(shuffled > 10);
+}
+
+#[test]
+fn first_cycle_shuffled_by_default() {
+    let in_order: Vec<u32> = (1..=8).collect();
+    let shuffled = (0..16)
+        .filter(|seed| first_cycle("{1, 2, 3, 4, 5, 6, 7, 8}", *seed) != in_order)
+        .count();
+
+    assert!(shuffled > 10);
+}
+
+#[test]
+fn in_order_weighted() {
+    assert_stream("o{2: 1, 3: 2, 3}", 0, &[1, 1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn fixed_order_every_cycle() {
+    for seed in 0..16 {
+        assert_stream("s{1, 2, 3}", seed, &[1, 2, 3, 1, 2, 3, 1, 2]);
+    }
+}
+
+#[test]
+fn fixed_order_weighted() {
+    assert_stream("s{2: 1, 1: 2}", 0, &[1, 1, 2, 1, 1, 2]);
+}
+
+#[test]
+fn fixed_order_done() {
+    let a = expr_to_var("s{1, 2, 3}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..6).map(|_| (a.next(), a.done())).collect();
+    assert_eq!(
+        actual,
+        [(1, false), (2, false), (3, true), (1, false), (2, false), (3, true)]
+    );
+}
+
+#[test]
+fn fixed_order_display() {
+    let a = expr_to_var("s{1, 2: 2}").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "s{1: 0x1, 2: 0x2, }");
+}
+
+fn sorted_cycles(expr: &str, len: usize) -> Vec<Vec<u32>> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..16)
+        .map(|_| {
+            let mut values: Vec<u32> = (0..len).map(|_| a.next()).collect();
+            values.sort_unstable();
+            values
+        })
+        .collect()
+}
+
+#[test]
+fn positions_unique_by_default() {
+    for values in sorted_cycles("{1, 1, 2}", 3) {
+        assert_eq!(values, [1, 1, 2]);
+    }
+}
+
+#[test]
+fn distinct_values_impossible() {
+    assert!(expr_to_var("u{1, 1, 2}").is_err());
+    assert!(expr_to_var("u{2: 1, 2}").is_err());
+    assert!(expr_to_var("u{Expand([1, 2]), 2}").is_err());
+}
+
+#[test]
+fn distinct_values_resamples() {
+    for values in sorted_cycles("u{1, [1, 2], 3}", 3) {
+        assert_eq!(values, [1, 2, 3]);
+    }
+}
+
+#[test]
+fn distinct_values_fewer_possible_values_than_positions() {
+    assert!(expr_to_var("u{[1, 2], [1, 2], [1, 2]}").is_err());
+    assert!(expr_to_var("u{Pattern(1, 2), [1, 2], 2: 3}").is_err());
+    assert!(expr_to_var("u{[1, 2], [1, 2], 3}").is_ok());
+}
+
+#[test]
+fn distinct_values_resample_limit() {
+    let model = model_with("a = u{Pattern(1, 1, 1, 2)};", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!(next_checked_error(&mut a, 2).starts_with("resample limit of 1 exceeded"));
+}
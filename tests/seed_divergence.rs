@@ -0,0 +1,41 @@
+use rvs::Seed;
+
+#[test]
+fn range_diverges_between_distinct_seeds() {
+    let divergence =
+        rvs::seed_divergence("[0, 0xffff]", Seed::from_u32(1), Seed::from_u32(2), 1000).unwrap();
+
+    assert!(divergence > 990);
+}
+
+#[test]
+fn identical_seeds_do_not_diverge() {
+    let divergence =
+        rvs::seed_divergence("[0, 0xffff]", Seed::from_u32(1), Seed::from_u32(1), 1000).unwrap();
+
+    assert_eq!(divergence, 0);
+}
+
+#[test]
+fn constant_does_not_diverge() {
+    let divergence =
+        rvs::seed_divergence("5 + 3", Seed::from_u32(1), Seed::from_u32(2), 1000).unwrap();
+
+    assert_eq!(divergence, 0);
+}
+
+#[test]
+fn errors() {
+    assert!(rvs::seed_divergence("[0, ", Seed::from_u32(1), Seed::from_u32(2), 1).is_err());
+    assert!(rvs::seed_divergence("a", Seed::from_u32(1), Seed::from_u32(2), 1).is_err());
+}
+
+#[test]
+fn more_than_one_statement() {
+    let divergence = |expr| rvs::seed_divergence(expr, Seed::from_u32(1), Seed::from_u32(2), 1);
+
+    let error = divergence("1; b = 2").unwrap_err().to_string();
+    assert!(error.contains("expected a single expression but found `1; b = 2`"), "{}", error);
+    // Would otherwise replace the variable of the expression with a constant
+    assert!(divergence("1; const variable = 2").is_err());
+}
@@ -7,7 +7,7 @@ fn last() {
     let mut a = a.borrow_mut();
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .collect();
@@ -24,7 +24,7 @@ fn first_last() {
     let mut a = a.borrow_mut();
 
     let expected: Vec<(u32, bool)> = (first..last + 1)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .collect();
@@ -39,7 +39,7 @@ fn first_last_increment() {
     let mut a = a.borrow_mut();
 
     let expected: Vec<(u32, bool)> = (0..4)
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .map(|(i, done)| (i * 4, done))
@@ -57,7 +57,7 @@ fn decrement() {
 
     let expected: Vec<(u32, bool)> = (last..first + 1)
         .rev()
-        .zip(vec![false, false, false, true].into_iter())
+        .zip(vec![false, false, false, true])
         .cycle()
         .take(16)
         .collect();
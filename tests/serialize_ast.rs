@@ -0,0 +1,100 @@
+mod util;
+use crate::util::*;
+
+use std::env::current_dir;
+use std::fs;
+
+fn search_path() -> rvs::SearchPath {
+    rvs::SearchPath::new(vec![current_dir().unwrap().join("tests/pattern_file")])
+}
+
+fn transform(parser: &rvs::Parser) -> rvs::Model {
+    transform_with(parser, rvs::Seed::from_u32(7), |transform| {
+        transform.set_search_path(search_path())
+    })
+    .unwrap()
+}
+
+fn values(model: &rvs::Model) -> Vec<(String, Vec<u32>)> {
+    model
+        .variables_iter()
+        .map(|(name, variable)| {
+            let mut variable = variable.borrow_mut();
+            (name.to_owned(), (0..100).map(|_| variable.next()).collect())
+        })
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    let source = fs::read_to_string("tests/serialize_ast/varied.rvs").unwrap();
+    let mut parser = rvs::Parser::new(&search_path());
+    parser.parse(&source).unwrap();
+
+    let bytes = parser.serialize_ast();
+    let deserialized = rvs::Parser::deserialize_ast(&search_path(), &bytes).unwrap();
+
+    assert_eq!(format!("{:?}", deserialized.ast()), format!("{:?}", parser.ast()));
+    assert_eq!(deserialized.serialize_ast(), bytes);
+
+    let expected = values(&transform(&parser));
+    assert_eq!(expected.len(), 20);
+    assert_eq!(values(&transform(&deserialized)), expected);
+}
+
+#[test]
+fn spans_and_doc_comments() {
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.parse("/// The first\na = 1;\n  b = unknown;").unwrap();
+    let deserialized = rvs::Parser::deserialize_ast(&Default::default(), &parser.serialize_ast()).unwrap();
+
+    match transform_with(&deserialized, Default::default(), |_| {}) {
+        Err(e) => assert!(e.to_string().starts_with("error at 3:7:"), "{}", e),
+        Ok(_) => panic!("expected an error"),
+    }
+
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.parse("/// The first\na = 1;").unwrap();
+    let deserialized = rvs::Parser::deserialize_ast(&Default::default(), &parser.serialize_ast()).unwrap();
+    let model = transform_with(&deserialized, Default::default(), |_| {}).unwrap();
+    assert_eq!(model.get_variable_by_name("a").unwrap().borrow().doc(), Some("The first"));
+}
+
+fn deserialize_error(bytes: &[u8]) -> String {
+    match rvs::Parser::deserialize_ast(&Default::default(), bytes) {
+        Err(e) => e.to_string(),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn version_mismatch() {
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.parse("a = 1;").unwrap();
+    let mut bytes = parser.serialize_ast();
+    bytes[4] = bytes[4].wrapping_add(1);
+
+    assert_eq!(
+        deserialize_error(&bytes),
+        format!(
+            "serialized AST format version {} is not supported (expected {})",
+            rvs_parser::binary::FORMAT_VERSION + 1,
+            rvs_parser::binary::FORMAT_VERSION
+        )
+    );
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(deserialize_error(b"a = 1;"), "not a serialized AST");
+
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.parse("a = Pattern(1, 2);").unwrap();
+    let bytes = parser.serialize_ast();
+
+    assert!(deserialize_error(&bytes[..bytes.len() - 1]).contains("unexpected end of input"));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(deserialize_error(&trailing).contains("trailing bytes"));
+}
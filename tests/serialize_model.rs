@@ -0,0 +1,144 @@
+mod util;
+use crate::util::*;
+
+use std::env::current_dir;
+use std::fs;
+
+fn search_path() -> rvs::SearchPath {
+    rvs::SearchPath::new(vec![current_dir().unwrap().join("tests/pattern_file")])
+}
+
+fn transform<F>(configure: F) -> rvs::Model
+where
+    F: FnOnce(&mut rvs::Transform),
+{
+    let source = fs::read_to_string("tests/serialize_model/varied.rvs").unwrap();
+    let mut parser = rvs::Parser::new(&search_path());
+    parser.parse(&source).unwrap();
+
+    transform_with(&parser, rvs::Seed::from_u32(7), |transform| {
+        transform.set_search_path(search_path());
+        configure(transform);
+    })
+    .unwrap()
+}
+
+fn values(model: &rvs::Model, n: usize) -> Vec<(String, Vec<u32>)> {
+    model
+        .variables_iter()
+        .map(|(name, variable)| {
+            let mut variable = variable.borrow_mut();
+            (name.to_owned(), (0..n).map(|_| variable.next()).collect())
+        })
+        .collect()
+}
+
+fn deserialize(bytes: &[u8]) -> rvs::Result<rvs::Model> {
+    let mut model = rvs::Model::new();
+    model.deserialize(bytes)?;
+
+    Ok(model)
+}
+
+fn assert_round_trip(model: rvs::Model) {
+    // Serialize mid-stream so that evaluation state is covered too
+    values(&model, 10);
+
+    let bytes = model.serialize().unwrap();
+    let deserialized = deserialize(&bytes).unwrap();
+    assert_eq!(deserialized.serialize().unwrap(), bytes);
+
+    let expected = values(&model, 100);
+    assert_eq!(expected.len(), 22);
+    assert_eq!(values(&deserialized, 100), expected);
+    assert_eq!(
+        deserialized.next_group(&["a", "base"]).unwrap(),
+        model.next_group(&["a", "base"]).unwrap()
+    );
+}
+
+#[test]
+fn round_trip() {
+    assert_round_trip(transform(|_| {}));
+}
+
+#[test]
+fn round_trip_with_options() {
+    assert_round_trip(transform(|transform| {
+        transform.set_memoize(true);
+        transform.set_flatten_operations(true);
+    }));
+    assert_round_trip(transform(|transform| {
+        transform.set_reduce_operations(true);
+        transform.set_independent_selection(true);
+    }));
+    assert_round_trip(transform(|transform| {
+        transform.set_counter_rng(true);
+        transform.set_rng_draw_counting(true);
+        transform.set_lazy(true);
+    }));
+}
+
+#[test]
+fn enums_and_warnings() {
+    let model = model_with("enum Kind { A, B = 5 }\na = Kind::B;\nb = [3, 3];", Default::default(), |_| {}).unwrap();
+    let deserialized = deserialize(&model.serialize().unwrap()).unwrap();
+
+    let members: Vec<_> = deserialized.enum_members("Kind").unwrap().collect();
+    assert_eq!(members, vec![("A", 0), ("B", 5)]);
+    assert_eq!(deserialized.warnings().len(), model.warnings().len());
+    assert_eq!(next_by_name(&deserialized, "a"), 5);
+    assert_eq!(next_by_name(&deserialized, "b"), 3);
+}
+
+#[test]
+fn replaces_existing_variables() {
+    let bytes = model_with("a = 1; b = Pattern(1, 2);", Default::default(), |_| {})
+        .unwrap()
+        .serialize()
+        .unwrap();
+
+    let mut model = model_with("a = 7; b = 9; c = b + 1;", Default::default(), |_| {}).unwrap();
+    model.deserialize(&bytes).unwrap();
+
+    assert_eq!(next_by_name(&model, "a"), 1);
+    // References to replaced variables follow the deserialized variables
+    assert_eq!(next_by_name(&model, "c"), 2);
+    assert_eq!(next_by_name(&model, "b"), 2);
+}
+
+#[test]
+fn rejects_invalid_input() {
+    let bytes = model_with("a = Pattern(1, 2);", Default::default(), |_| {})
+        .unwrap()
+        .serialize()
+        .unwrap();
+
+    let error = |bytes: &[u8]| deserialize(bytes).err().expect("expected an error").to_string();
+
+    assert_eq!(error(b"RVSA"), "not a serialized model");
+
+    let mut other_version = bytes.clone();
+    other_version[4] += 1;
+    assert!(error(&other_version).contains("format version 2 is not supported"), "{}", error(&other_version));
+
+    let mut corrupt = bytes.clone();
+    corrupt[12] ^= 1;
+    assert!(error(&corrupt).contains("checksum mismatch"), "{}", error(&corrupt));
+
+    assert!(error(&bytes[..6]).contains("unexpected end of input"), "{}", error(&bytes[..6]));
+}
+
+#[test]
+fn leaves_model_unmodified_on_error() {
+    let bytes = model_with("a = 1;", Default::default(), |_| {})
+        .unwrap()
+        .serialize()
+        .unwrap();
+
+    let mut model = model_with("b = 2;", Default::default(), |_| {}).unwrap();
+    assert!(model.deserialize(&bytes[..bytes.len() - 1]).is_err());
+
+    assert!(model.get_variable_by_name("a").is_none());
+    assert_eq!(next_by_name(&model, "b"), 2);
+}
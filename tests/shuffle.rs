@@ -0,0 +1,40 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn each_cycle_is_a_permutation() {
+    let a = expr_to_var("Shuffle(1, 2, 3, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut orders = Vec::new();
+    for _ in 0..100 {
+        let cycle: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+        let dones: Vec<bool> = cycle.iter().map(|&(_, done)| done).collect();
+        assert_eq!(dones, [false, false, false, true]);
+
+        let values: Vec<u32> = cycle.iter().map(|&(value, _)| value).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(sorted, [1, 2, 3, 4]);
+
+        orders.push(values);
+    }
+
+    // Orders are reshuffled each cycle
+    assert!(orders.iter().any(|order| *order != orders[0]));
+}
+
+#[test]
+fn selects_another_subexpr_when_current_subexpr_done() {
+    let a = expr_to_var("Shuffle(Pattern(0, 1), Pattern(2, 3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        match a.next() {
+            0 => assert_eq!(a.next(), 1),
+            2 => assert_eq!(a.next(), 3),
+            value => panic!("unexpected value {}", value),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+mod util;
+use crate::util::*;
+
+fn values(model: &rvs::Model, name: &str, n: usize) -> Vec<u32> {
+    let mut variable = model.get_variable_by_name(name).unwrap().borrow_mut();
+
+    (0..n).map(|_| variable.next()).collect()
+}
+
+#[test]
+fn attribute() {
+    let model = model("@signed a = 0xffffffff; b = 0xffffffff;");
+
+    assert!(model.get_variable_by_name("a").unwrap().borrow().is_signed());
+    assert!(!model.get_variable_by_name("b").unwrap().borrow().is_signed());
+    assert_eq!(model.to_string(), "@signed a = 0xffffffff;\nb = 0xffffffff;\n");
+}
+
+#[test]
+fn json() {
+    let model = model("@signed a = 0xffffffff; b = 0xffffffff; @signed c = 5;");
+
+    assert_eq!(
+        model.sample_to_json(1, rvs::JsonFormat::Number).unwrap(),
+        "{\"a\": {\"values\": [-1], \"first_done\": 0}, \
+         \"b\": {\"values\": [4294967295], \"first_done\": 0}, \
+         \"c\": {\"values\": [5], \"first_done\": 0}}"
+    );
+}
+
+#[test]
+fn json_hex() {
+    let model = model("@signed a = Pattern(0xfffffff0, 0x10);");
+
+    assert_eq!(
+        model.sample_to_json(2, rvs::JsonFormat::HexString).unwrap(),
+        "{\"a\": {\"values\": [\"-0x10\", \"0x10\"], \"first_done\": 1}}"
+    );
+}
+
+#[test]
+fn comparisons() {
+    let model = model(
+        "
+        @signed a = Pattern(-1 < 1, -1 > 1, 0x7fffffff > 0x80000000, -5 < -3);
+        b = Pattern(-1 < 1, -1 > 1, 0x7fffffff > 0x80000000, -5 < -3);
+        ",
+    );
+
+    assert_eq!(values(&model, "a", 4), vec![1, 0, 1, 1]);
+    assert_eq!(values(&model, "b", 4), vec![0, 1, 0, 1]);
+}
+
+#[test]
+fn flattened_comparisons() {
+    let transform = |flatten: bool| {
+        let model = model_with(
+            "@signed a = ([-2, 2] < 0) + ([-2, 2] > 0) * 2;",
+            Default::default(),
+            |transform| transform.set_flatten_operations(flatten),
+        )
+        .unwrap();
+
+        values(&model, "a", 100)
+    };
+
+    let expected = transform(false);
+    assert!(expected.iter().all(|&value| value <= 3));
+    assert!(expected.contains(&1));
+    assert!(expected.contains(&2));
+    assert_eq!(transform(true), expected);
+}
+
+#[test]
+fn stats() {
+    let model = model("@signed a = Pattern(-3, 1, 5);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_stats_enabled(true);
+    for _ in 0..3 {
+        a.next();
+    }
+
+    let stats = a.stats().unwrap();
+    assert_eq!(stats.min as i32, -3);
+    assert_eq!(stats.max, 5);
+    assert_eq!(stats.mean(), 1);
+}
+
+#[test]
+fn summary() {
+    let model = model("@signed a = Pattern(-3, 1, 5);");
+
+    assert_eq!(
+        model.summary_to_json(3).unwrap(),
+        "{\"a\": {\"min\": -3, \"max\": 5, \"mean\": 1, \"distinct\": 3, \"first_done\": 2}}"
+    );
+}
@@ -0,0 +1,57 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn matches_fresh_stream() {
+    for expr in ["[0, 1000]", "Pattern(1, 2, 3)", "{1, 2, 3} + [0, 10]", "Shuffle(1, 2, 3, 4)"].iter() {
+        let fresh = expr_to_var(expr).unwrap();
+        let expected: Vec<u32> = (0..7).map(|_| fresh.borrow_mut().next()).collect();
+
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+        a.skip(5);
+        assert_eq!(a.prev(), expected[4], "{}", expr);
+        assert_eq!(a.next(), expected[5], "{}", expr);
+        assert_eq!(a.next(), expected[6], "{}", expr);
+    }
+}
+
+#[test]
+fn zero() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.skip(0);
+    assert_eq!(a.current(), None);
+    assert_eq!(a.next(), 1);
+}
+
+#[test]
+fn cycle_state() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.skip(3);
+    assert!(a.done());
+    assert!(a.done_edge());
+    assert_eq!(a.cycle_position(), Some(2));
+
+    a.skip(4);
+    assert_eq!(a.prev(), 1);
+    assert!(!a.done());
+    assert_eq!(a.cycle_count(), 2);
+    assert_eq!(a.next(), 2);
+}
+
+#[test]
+fn not_in_stats() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+    a.set_stats_enabled(true);
+
+    a.skip(2);
+    a.next();
+
+    let stats = a.stats().unwrap();
+    assert_eq!((stats.min, stats.max), (3, 3));
+}
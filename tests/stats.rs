@@ -0,0 +1,56 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn disabled_by_default() {
+    let a = expr_to_var("[0, 10]").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+
+    assert_eq!(a.stats(), None);
+}
+
+#[test]
+fn undefined_without_samples() {
+    let a = expr_to_var("[0, 10]").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_stats_enabled(true);
+
+    assert_eq!(a.stats(), None);
+}
+
+#[test]
+fn range() {
+    let a = expr_to_var("[0, 100]").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_stats_enabled(true);
+    for _ in 0..10_000 {
+        a.next();
+    }
+
+    let stats = a.stats().unwrap();
+    assert_eq!(stats.count, 10_000);
+    assert_eq!(stats.min, 0);
+    assert_eq!(stats.max, 100);
+    assert!((48..=52).contains(&stats.mean()));
+}
+
+#[test]
+fn pattern() {
+    let a = expr_to_var("Pattern(1, 2, 3, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_stats_enabled(true);
+    for _ in 0..4 {
+        a.next();
+    }
+
+    let stats = a.stats().unwrap();
+    assert_eq!(stats.min, 1);
+    assert_eq!(stats.max, 4);
+    assert_eq!(stats.sum, 10);
+    assert_eq!(stats.mean(), 3);
+}
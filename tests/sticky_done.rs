@@ -0,0 +1,79 @@
+mod util;
+use crate::util::*;
+
+fn model_with_sticky_done(s: &str, sticky_done: bool) -> rvs::Model {
+    model_with(s, Default::default(), |transform| transform.set_sticky_done(sticky_done)).unwrap()
+}
+
+fn dones(variable: &mut rvs::Variable, n: usize) -> Vec<bool> {
+    (0..n)
+        .map(|_| {
+            variable.next();
+            variable.done()
+        })
+        .collect()
+}
+
+#[test]
+fn sticky_until_reset() {
+    let model = model_with_sticky_done("a = Pattern(1, 2, 3);", true);
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert!(a.borrow().is_sticky_done());
+    assert!(!a.borrow().done());
+    assert_eq!(
+        dones(&mut a.borrow_mut(), 7),
+        vec![false, false, true, true, true, true, true]
+    );
+
+    a.borrow_mut().reset();
+    assert!(!a.borrow().done());
+    assert_eq!(dones(&mut a.borrow_mut(), 4), vec![false, false, true, true]);
+}
+
+#[test]
+fn disabled_by_default() {
+    let model = model_with_sticky_done("a = Pattern(1, 2, 3);", false);
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert!(!a.borrow().is_sticky_done());
+    assert_eq!(dones(&mut a.borrow_mut(), 6), vec![false, false, true, false, false, true]);
+}
+
+#[test]
+fn values_and_cycle_count_unaffected() {
+    let model = model_with_sticky_done("a = Pattern(1, 2, 3);", true);
+    let a = model.get_variable_by_name("a").unwrap();
+
+    let values: Vec<u32> = (0..6).map(|_| a.borrow_mut().next()).collect();
+
+    assert_eq!(values, vec![1, 2, 3, 1, 2, 3]);
+    assert_eq!(a.borrow().cycle_count(), 2);
+    assert!(a.borrow().done_edge());
+}
+
+#[test]
+fn done_every_call() {
+    let model = model_with_sticky_done("a = Once([0, 10]); b = 5;", true);
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(dones(&mut a.borrow_mut(), 3), vec![true, true, true]);
+    assert_eq!(dones(&mut b.borrow_mut(), 3), vec![true, true, true]);
+}
+
+#[test]
+fn seen_by_references() {
+    let model = model_with_sticky_done("a = Pattern(1, 2); b = a.done;", true);
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    let values: Vec<u32> = (0..4)
+        .map(|_| {
+            a.borrow_mut().next();
+            b.borrow_mut().next()
+        })
+        .collect();
+
+    assert_eq!(values, vec![0, 1, 1, 1]);
+}
@@ -0,0 +1,76 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn constant() {
+    let summaries = model("a = 5;").summarize(10).unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    let a = &summaries[0];
+    assert_eq!(a.name, "a");
+    assert_eq!(a.distinct, 1);
+    let stats = a.stats.unwrap();
+    assert_eq!((stats.min, stats.max, stats.mean()), (5, 5, 5));
+    assert_eq!(stats.count, 10);
+    assert_eq!(a.first_done, Some(0));
+}
+
+#[test]
+fn range() {
+    let summaries = model("a = [2, 9];").summarize(1000).unwrap();
+
+    let a = &summaries[0];
+    let stats = a.stats.unwrap();
+    assert!(stats.min >= 2);
+    assert!(stats.max <= 9);
+    assert_eq!(a.distinct, 8);
+}
+
+#[test]
+fn pattern() {
+    let summaries = model("a = Pattern(1, 2, 3);").summarize(6).unwrap();
+
+    let a = &summaries[0];
+    let stats = a.stats.unwrap();
+    assert_eq!((stats.min, stats.max, stats.mean()), (1, 3, 2));
+    assert_eq!(a.distinct, 3);
+    assert_eq!(a.first_done, Some(2));
+}
+
+#[test]
+fn no_values() {
+    let summaries = model("a = 5;").summarize(0).unwrap();
+
+    assert_eq!(summaries[0].stats, None);
+    assert_eq!(summaries[0].distinct, 0);
+    assert_eq!(summaries[0].first_done, None);
+}
+
+#[test]
+fn text() {
+    let summary = model("a = Pattern(1, 2, 3); long_name = 0x10;").summary(6).unwrap();
+
+    assert_eq!(
+        summary,
+        "name       min   max   mean  distinct  first_done\n\
+         a          0x1   0x3   0x2   3         2\n\
+         long_name  0x10  0x10  0x10  1         0\n"
+    );
+}
+
+#[test]
+fn json() {
+    let json = model("a = Pattern(1, 2, 3); b = 5;").summary_to_json(0).unwrap();
+
+    assert_eq!(
+        json,
+        "{\"a\": {\"min\": null, \"max\": null, \"mean\": null, \"distinct\": 0, \"first_done\": null}, \
+         \"b\": {\"min\": null, \"max\": null, \"mean\": null, \"distinct\": 0, \"first_done\": null}}"
+    );
+
+    let json = model("a = Pattern(1, 2, 3);").summary_to_json(3).unwrap();
+    assert_eq!(
+        json,
+        "{\"a\": {\"min\": 1, \"max\": 3, \"mean\": 2, \"distinct\": 3, \"first_done\": 2}}"
+    );
+}
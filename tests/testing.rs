@@ -0,0 +1,60 @@
+use rvs::testing::{assert_stream, stream};
+
+use std::panic;
+
+/// Returns the panic message of `f`
+fn panic_message<F: FnOnce() + panic::UnwindSafe>(f: F) -> String {
+    match panic::catch_unwind(f) {
+        Ok(()) => panic!("expected a panic"),
+        Err(payload) => match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(_) => panic!("expected a String panic payload"),
+        },
+    }
+}
+
+#[test]
+fn stream_matches_variable() {
+    let values = stream("[0, 0xffff_ffff]", 5, 8).unwrap();
+
+    assert_eq!(values.len(), 8);
+    assert_eq!(stream("[0, 0xffff_ffff]", 5, 8).unwrap(), values);
+    assert_ne!(stream("[0, 0xffff_ffff]", 6, 8).unwrap(), values);
+    assert_eq!(stream("[0, 0xffff_ffff]", 5, 4).unwrap(), values[..4]);
+}
+
+#[test]
+fn stream_error() {
+    assert!(stream("[0, ", 0, 1).is_err());
+    assert!(stream("1; b = 2", 0, 1).is_err());
+    assert!(stream("1; const variable = 2", 0, 1).is_err());
+}
+
+#[test]
+fn matching() {
+    assert_stream("Pattern(1, 2, 3)", 0, &[1, 2, 3, 1]);
+    assert_stream("Pattern(1, 2, 3)", 0, &[]);
+}
+
+#[test]
+fn mismatch_message() {
+    let message = panic_message(|| assert_stream("Pattern(1, 2, 0xffff_ffff)", 0x5eed, &[1, 2, 4, 2]));
+
+    assert_eq!(
+        message,
+        "the stream of `Pattern(1, 2, 0xffff_ffff)` with seed 0x5eed differs from the expected stream at index 2
+  index    expected      actual
+      0         0x1         0x1
+      1         0x2         0x2
+      2         0x4  0xffffffff  <--
+      3         0x2         0x1  <--
+"
+    );
+}
+
+#[test]
+fn invalid_expression_message() {
+    let message = panic_message(|| assert_stream("Pattern(1, ", 0, &[1]));
+
+    assert!(message.starts_with("failed to evaluate `Pattern(1, `: "), "{}", message);
+}
@@ -0,0 +1,63 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn non_decreasing_with_average_step() {
+    let a = expr_to_var("Timestamp(100, 10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..10_000).map(|_| a.next()).collect();
+
+    assert!(values.windows(2).all(|pair| pair[1] - pair[0] >= 90 && pair[1] - pair[0] <= 110));
+
+    let average = f64::from(values[values.len() - 1] - values[0]) / (values.len() - 1) as f64;
+    assert!((average - 100.0).abs() < 1.0, "average step {}", average);
+}
+
+#[test]
+fn no_jitter() {
+    let a = expr_to_var("Timestamp(5, 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [5, 10, 15, 20]);
+}
+
+#[test]
+fn jitter_larger_than_step_never_decreases() {
+    let a = expr_to_var("Timestamp(1, 10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..10_000).map(|_| a.next()).collect();
+
+    assert!(values.windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!(values.windows(2).any(|pair| pair[1] == pair[0]));
+}
+
+#[test]
+fn wraps() {
+    let a = expr_to_var("Timestamp(0x8000_0000, 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..3).map(|_| a.next()).collect();
+
+    assert_eq!(actual, [0x8000_0000, 0, 0x8000_0000]);
+}
+
+#[test]
+fn increment_saturates() {
+    let a = expr_to_var("Timestamp(0xffff_ffff, 0xffff_ffff)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        a.next();
+    }
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("Timestamp(100, [0, 10])").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "Timestamp(0x64, [0x0, 0xa])");
+}
@@ -0,0 +1,81 @@
+mod util;
+use crate::util::*;
+
+fn samples(expr: &str, n: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| a.next()).collect()
+}
+
+#[test]
+fn in_range_near_mean() {
+    let samples = samples("TruncNormal(50, 10, 30, 90)", 10_000);
+
+    assert!(samples.iter().all(|value| (30..=90).contains(value)));
+
+    // The truncation at mean - 2 stddev shifts the mean up slightly (~50.5)
+    let mean = samples.iter().map(|&value| f64::from(value)).sum::<f64>() / samples.len() as f64;
+    assert!((mean - 50.5).abs() < 1.0, "{}", mean);
+
+    // ~68% within one stddev
+    let within = samples.iter().filter(|&&value| (40..=60).contains(&value)).count();
+    assert!(within > 6_500 && within < 7_500, "{}", within);
+}
+
+#[test]
+fn narrow_range() {
+    let samples = samples("TruncNormal(100, 10, 95, 96)", 1_000);
+
+    assert!(samples.contains(&95));
+    assert!(samples.contains(&96));
+    assert!(samples.iter().all(|value| (95..=96).contains(value)));
+}
+
+#[test]
+fn zero_stddev() {
+    assert_eq!(samples("TruncNormal(5, 0, 0, 10)", 10), vec![5; 10]);
+}
+
+#[test]
+fn near_zero() {
+    let samples = samples("TruncNormal(0, 5, 0, 3)", 1_000);
+
+    assert!(samples.iter().all(|&value| value <= 3));
+}
+
+#[test]
+fn invalid() {
+    assert!(expr_to_var("TruncNormal(50, 10, 90, 30)").is_err());
+    assert!(expr_to_var("TruncNormal(50, 0, 0, 10)").is_err());
+    assert!(expr_to_var("TruncNormal(50, 10, 0)").is_err());
+}
+
+#[test]
+fn range_far_from_mean_is_an_error() {
+    assert!(expr_to_var("TruncNormal(0, 1, 1000, 2000)").is_err());
+    assert!(expr_to_var("TruncNormal(2000, 1, 0, 1000)").is_err());
+    assert!(expr_to_var("TruncNormal(0, 1, 7, 8)").is_err());
+    assert!(expr_to_var("TruncNormal(0, 1, 6, 8)").is_ok());
+}
+
+#[test]
+fn resample_limit() {
+    let model = model_with("a = TruncNormal(0, 1, 5, 6);", Default::default(), |transform| {
+        transform.set_max_resample_attempts(1)
+    })
+    .unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!(next_checked_error(&mut a, 1).starts_with("resample limit of 1 exceeded"));
+    // The draw is clamped to the range
+    assert_eq!(a.prev(), 5);
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("TruncNormal(50, 10, 0, 100)").unwrap();
+
+    assert_eq!(a.borrow().to_string(), "TruncNormal(50, 10, 0, 100)");
+}
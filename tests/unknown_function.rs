@@ -0,0 +1,62 @@
+fn parse(s: &str) -> Result<(), String> {
+    let mut parser = rvs::Parser::new(&Default::default());
+    parser.set_unknown_function_errors(true);
+
+    parser.parse(s).map_err(|e| e.to_string())
+}
+
+#[test]
+fn suggestion() {
+    assert_eq!(
+        parse("a = WeightedSmple(1: 2, 3: 4);").unwrap_err(),
+        "error at 1:5: unknown function 'WeightedSmple', did you mean 'WeightedSample'?\n\
+         a = WeightedSmple(1: 2, 3: 4);\n    ^"
+    );
+}
+
+#[test]
+fn case_insensitive() {
+    assert!(parse("a = pattern(1, 2);").unwrap_err().contains("did you mean 'Pattern'?"));
+}
+
+#[test]
+fn nested() {
+    assert_eq!(
+        parse("a = [0, 1] + Shufle (1, 2);").unwrap_err(),
+        "error at 1:14: unknown function 'Shufle', did you mean 'Shuffle'?\n\
+         a = [0, 1] + Shufle (1, 2);\n             ^"
+    );
+}
+
+#[test]
+fn lists_functions() {
+    let error = parse("a = Frobnicate(1);").unwrap_err();
+
+    assert!(error.starts_with("error at 1:5: unknown function 'Frobnicate', expected one of Accumulate, "));
+    assert!(error.contains("Pattern, "));
+    assert!(error.contains("WeightedSample, "));
+}
+
+#[test]
+fn known_function_errors_are_unchanged() {
+    let error = parse("a = Pattern(1, +);").unwrap_err();
+
+    assert!(!error.contains("unknown function"));
+}
+
+#[test]
+fn disabled_by_default() {
+    let mut parser = rvs::Parser::new(&Default::default());
+    let error = parser.parse("a = Smple(1);").unwrap_err().to_string();
+
+    assert!(error.starts_with("error at 1:10: expected"));
+}
+
+/// There is no `Sample` function (sampling is `{...}` and `r{...}`) so nothing is near `Smple`
+#[test]
+fn no_near_function() {
+    let error = parse("a = Smple(1);").unwrap_err();
+
+    assert!(error.starts_with("error at 1:5: unknown function 'Smple', expected one of Accumulate, "));
+    assert!(!error.contains("did you mean"));
+}
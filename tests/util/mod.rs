@@ -1,9 +1,8 @@
-use rvs;
-
 use std::io;
 use std::rc::Rc;
 use std::cell::RefCell;
 
+#[allow(dead_code)]
 pub fn expr_to_var<S>(expr: S) -> rvs::Result<Rc<RefCell<Box<rvs::Variable>>>>
 where
     S: AsRef<str>,
@@ -19,3 +18,62 @@ where
         ))),
     }
 }
+
+/// Parses and transforms `s` with the default search path and seed
+///
+/// Panics if `s` fails to parse or transform.
+#[allow(dead_code)]
+pub fn model(s: &str) -> rvs::Model {
+    rvs::parse(&Default::default(), s).unwrap()
+}
+
+/// Returns the next value of the variable `name` of `model`
+///
+/// Panics if the variable does not exist.
+#[allow(dead_code)]
+pub fn next_by_name(model: &rvs::Model, name: &str) -> u32 {
+    model.get_variable_by_name(name).unwrap().borrow_mut().next()
+}
+
+/// Returns the message of the error returned by `Variable::next_checked` for a variable
+///
+/// Panics if `next_checked` succeeds `n` times.
+#[allow(dead_code)]
+pub fn next_checked_error(variable: &mut rvs::Variable, n: usize) -> String {
+    for _ in 0..n {
+        if let Err(e) = variable.next_checked() {
+            return e.to_string();
+        }
+    }
+
+    panic!("expected an error within {} calls", n)
+}
+
+/// Parses `s` and transforms it with a `Transform` configured by `configure`
+///
+/// E.g. `model_with("a = [0, 1];", Default::default(), |t| t.set_lazy(true))`
+#[allow(dead_code)]
+pub fn model_with<F>(s: &str, seed: rvs::Seed, configure: F) -> rvs::Result<rvs::Model>
+where
+    F: FnOnce(&mut rvs::Transform),
+{
+    let search_path: rvs::SearchPath = Default::default();
+    let mut parser = rvs::Parser::new(&search_path);
+    parser.parse(s)?;
+
+    transform_with(&parser, seed, configure)
+}
+
+/// Transforms the statements parsed by `parser` with a `Transform` configured by `configure`
+#[allow(dead_code)]
+pub fn transform_with<F>(parser: &rvs::Parser, seed: rvs::Seed, configure: F) -> rvs::Result<rvs::Model>
+where
+    F: FnOnce(&mut rvs::Transform),
+{
+    let mut model = rvs::Model::new();
+    let mut transform = rvs::Transform::new(seed);
+    configure(&mut transform);
+    transform.transform(&mut model, parser.ast())?;
+
+    Ok(model)
+}
@@ -0,0 +1,100 @@
+mod util;
+use crate::util::*;
+
+fn bounds(expr: &str) -> Option<(u32, u32)> {
+    let a = expr_to_var(expr).unwrap();
+    let a = a.borrow();
+
+    a.value_bounds()
+}
+
+/// Asserts that `expr` stays within its bounds
+fn check(expr: &str) {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+    let (min, max) = a.value_bounds().unwrap();
+
+    for _ in 0..1000 {
+        let value = a.next();
+        assert!(min <= value && value <= max, "{} not in [{}, {}]", value, min, max);
+    }
+}
+
+#[test]
+fn leaves() {
+    assert_eq!(bounds("5"), Some((5, 5)));
+    assert_eq!(bounds("[7, 3]"), Some((3, 7)));
+    assert_eq!(bounds("0b1x0x"), Some((8, 13)));
+    assert_eq!(bounds("Zipf(10, 1)"), Some((1, 10)));
+}
+
+#[test]
+fn samples() {
+    assert_eq!(bounds("Pattern(4, [1, 2], 9)"), Some((1, 9)));
+    assert_eq!(bounds("r{3, 8: [4, 6]}"), Some((3, 6)));
+    assert_eq!(bounds("{3, 5}"), Some((3, 5)));
+    assert_eq!(bounds("Shuffle(2, 1)"), Some((1, 2)));
+    assert_eq!(bounds("[0, 9] except {0}"), Some((0, 9)));
+}
+
+#[test]
+fn operations() {
+    assert_eq!(bounds("[0, 10] + [0, 5]"), Some((0, 15)));
+    assert_eq!(bounds("[10, 20] - [0, 5]"), Some((5, 20)));
+    assert_eq!(bounds("[2, 3] * [4, 5]"), Some((8, 15)));
+    assert_eq!(bounds("[10, 20] / [2, 5]"), Some((2, 10)));
+    assert_eq!(bounds("[0, 100] % 8"), Some((0, 7)));
+    assert_eq!(bounds("[0, 100] & 0xf"), Some((0, 15)));
+    assert_eq!(bounds("[1, 4] | 8"), Some((8, 15)));
+    assert_eq!(bounds("[1, 3] << 4"), Some((16, 48)));
+    assert_eq!(bounds("[16, 48] >> [1, 4]"), Some((1, 24)));
+    assert_eq!(bounds("[0, 3] <-> [10, 12]"), Some((7, 12)));
+    assert_eq!(bounds("~[0, 0xff]"), Some((0xffff_ff00, 0xffff_ffff)));
+    assert_eq!(bounds("(1 + 2) * [0, 3]"), Some((0, 9)));
+
+    check("[0, 10] + [0, 5]");
+    check("[10, 20] - [0, 5]");
+    check("[0, 3] <-> [10, 12]");
+    check("[16, 48] >> [1, 4]");
+    check("[1, 4] ^ [8, 9]");
+}
+
+#[test]
+fn unknown() {
+    assert_eq!(bounds("[0, 0xffff_ffff] + 1"), None);
+    assert_eq!(bounds("[0, 5] - [0, 1]"), None);
+    assert_eq!(bounds("Sequence(10)"), None);
+    assert_eq!(bounds("-[0, 2]"), None);
+    assert_eq!(bounds("-[1, 2]"), Some((0xffff_fffe, 0xffff_ffff)));
+    assert_eq!(bounds("1 / [0, 1]"), None);
+}
+
+#[test]
+fn overflow() {
+    assert_eq!(bounds("[0x8000_0000, 0x8000_0001] + [0x8000_0000, 0x8000_0001]"), None);
+    assert_eq!(bounds("[0x1_0000, 0x1_0001] * [0x1_0000, 0x1_0001]"), None);
+
+    let a = expr_to_var("[0x8000_0000, 0x8000_0001] + [0x8000_0000, 0x8000_0001] except {1}");
+    assert!(a.is_ok());
+}
+
+#[test]
+fn variables() {
+    let model = rvs::parse(&Default::default(), "a = [1, 5]; b = a + 1; c = a.prev;").unwrap();
+
+    let bounds = |name| model.get_variable_by_name(name).unwrap().borrow().value_bounds();
+    assert_eq!(bounds("b"), Some((2, 6)));
+    assert_eq!(bounds("c"), Some((0, 5)));
+}
+
+#[test]
+fn output_mask() {
+    let a = expr_to_var("[0x10, 0x1ff]").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_output_mask(0xfff);
+    assert_eq!(a.value_bounds(), Some((0x10, 0x1ff)));
+
+    a.set_output_mask(0xff);
+    assert_eq!(a.value_bounds(), Some((0, 0xff)));
+}
@@ -0,0 +1,32 @@
+mod util;
+use crate::util::*;
+
+use std::rc::Rc;
+
+#[test]
+fn applied_to_next_and_prev() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_value_transform(Some(Rc::new(|value| value * 2)));
+
+    for expected in &[2, 4, 6] {
+        assert_eq!(a.next(), *expected);
+        assert_eq!(a.prev(), *expected);
+    }
+}
+
+#[test]
+fn collected_by_stats() {
+    let a = expr_to_var("Pattern(1, 2, 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.set_stats_enabled(true);
+    a.set_value_transform(Some(Rc::new(|value| value + 10)));
+    for _ in 0..3 {
+        a.next();
+    }
+
+    let stats = a.stats().unwrap();
+    assert_eq!((stats.min, stats.max), (11, 13));
+}
@@ -0,0 +1,74 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn pattern() {
+    let model = model("a = Pattern(1, 2, 3, 4);");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    assert_eq!(
+        a.state(),
+        rvs::VariableState {
+            prev: 0,
+            done: false,
+            count: 0,
+            cycle_count: 0,
+            cycle_position: None,
+        }
+    );
+
+    for _ in 0..6 {
+        a.next();
+    }
+    assert_eq!(
+        a.state(),
+        rvs::VariableState {
+            prev: 2,
+            done: false,
+            count: 6,
+            cycle_count: 1,
+            cycle_position: Some(1),
+        }
+    );
+
+    a.skip(2);
+    assert_eq!(
+        a.state(),
+        rvs::VariableState {
+            prev: 4,
+            done: true,
+            count: 8,
+            cycle_count: 2,
+            cycle_position: Some(3),
+        }
+    );
+
+    a.reset();
+    assert_eq!(a.state().count, 0);
+}
+
+#[test]
+fn disabled_not_counted() {
+    let model = model("a = [0, 10];");
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    a.next();
+    a.set_enabled(false);
+    a.next();
+    assert_eq!(a.state().count, 1);
+}
+
+#[test]
+fn checkpoint_restores_count() {
+    let mut model = model("a = Pattern(1, 2, 3);");
+    let a = model.get_variable_by_name("a").unwrap().clone();
+
+    a.borrow_mut().next();
+    model.checkpoint("start").unwrap();
+    a.borrow_mut().next();
+    a.borrow_mut().next();
+    assert_eq!(a.borrow().count(), 3);
+
+    assert!(model.restore("start"));
+    assert_eq!(a.borrow().count(), 1);
+}
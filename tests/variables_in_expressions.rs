@@ -94,3 +94,142 @@ fn prev() {
         assert_eq!(b.borrow_mut().done(), done);
     }
 }
+
+#[test]
+fn copy_next() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = Pattern(0, 1, 2, 3); b = a.copy.next; c = a.next;",
+    )
+    .unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(b.borrow_mut().next(), 0);
+    assert_eq!(b.borrow_mut().next(), 1);
+    assert_eq!(b.borrow_mut().next(), 2);
+    assert_eq!(a.borrow().prev(), 0);
+    assert_eq!(a.borrow_mut().next(), 0);
+    assert_eq!(b.borrow_mut().next(), 3);
+    assert_eq!(a.borrow_mut().next(), 1);
+
+    let c = model.get_variable_by_name("c").unwrap();
+    assert_eq!(c.borrow_mut().next(), 2);
+    assert_eq!(a.borrow().prev(), 2);
+}
+
+#[test]
+fn prev_prev() {
+    let model =
+        rvs::parse(&Default::default(), "a = Pattern(5, 6); b = a.prev.prev;").unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(b.borrow_mut().next(), 0);
+    assert_eq!(a.borrow_mut().next(), 5);
+    assert_eq!(b.borrow_mut().next(), 5);
+    assert_eq!(b.borrow_mut().next(), 5);
+}
+
+#[test]
+fn copy_prev() {
+    let model = rvs::parse(&Default::default(), "a = Pattern(5, 6); b = a.copy.prev;").unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 5);
+    for _ in 0..4 {
+        assert_eq!(b.borrow_mut().next(), 0);
+    }
+    assert_eq!(a.borrow_mut().next(), 6);
+    assert_eq!(model.warnings().len(), 1);
+}
+
+#[test]
+fn method_chain_after_prev() {
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.prev.next;").is_err());
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.prev.copy;").is_err());
+}
+
+#[test]
+fn method_chain_on_constant() {
+    let model = rvs::parse(
+        &Default::default(),
+        "const C = 7; enum E { A = 3, } a = C.copy.next + E::A.prev.prev;",
+    )
+    .unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    assert_eq!(a.borrow_mut().next(), 10);
+}
+
+#[test]
+fn done() {
+    let model =
+        rvs::parse(&Default::default(), "a = Pattern(1, 2, 3, 4); b = a.done;").unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    // Not done before the first call to `next`
+    assert_eq!(b.borrow_mut().next(), 0);
+
+    for _ in 0..3 {
+        for expected in [0, 0, 0, 1].iter() {
+            a.borrow_mut().next();
+            assert_eq!(b.borrow_mut().next(), *expected);
+        }
+    }
+
+    // Does not advance
+    assert_eq!(b.borrow_mut().next(), 1);
+    assert_eq!(a.borrow_mut().next(), 1);
+}
+
+#[test]
+fn done_in_expression() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = Pattern(1, 2, 3, 4); b = a + a.done * 0x100;",
+    )
+    .unwrap();
+
+    let b = model.get_variable_by_name("b").unwrap();
+    let mut b = b.borrow_mut();
+
+    let actual: Vec<u32> = (0..4).map(|_| b.next()).collect();
+    assert_eq!(actual, [1, 2, 3, 0x104]);
+}
+
+#[test]
+fn done_method_chains() {
+    let model = rvs::parse(&Default::default(), "a = Pattern(5, 6); b = a.next.done;").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+    a.borrow_mut().next();
+    a.borrow_mut().next();
+    assert_eq!(b.borrow_mut().next(), 1);
+
+    let model = rvs::parse(&Default::default(), "a = Pattern(5, 6); b = a.copy.done;").unwrap();
+    assert_eq!(model.get_variable_by_name("b").unwrap().borrow_mut().next(), 0);
+    assert_eq!(model.warnings().len(), 1);
+
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.done.next;").is_err());
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.done.done;").is_err());
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.prev.done;").is_err());
+}
+
+#[test]
+fn done_on_constant() {
+    let model = rvs::parse(
+        &Default::default(),
+        "const C = 7; enum E { A = 3, } a = C.done + E::A.done + (let x = 5 in x.done);",
+    )
+    .unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    assert_eq!(a.borrow_mut().next(), 3);
+}
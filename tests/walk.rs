@@ -0,0 +1,94 @@
+mod util;
+use crate::util::*;
+
+fn samples(expr: &str, n: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..n).map(|_| a.next()).collect()
+}
+
+#[test]
+fn trajectory() {
+    assert_eq!(samples("Walk(10, Pattern(3, -5, 1))", 6), vec![13, 8, 9, 12, 7, 8]);
+}
+
+#[test]
+fn saturates_at_zero() {
+    assert_eq!(samples("Walk(3, -2)", 4), vec![1, 0, 0, 0]);
+    assert_eq!(samples("Walk(0, Pattern(-1, 1))", 4), vec![0, 1, 0, 1]);
+}
+
+#[test]
+fn saturates_at_max() {
+    assert_eq!(
+        samples("Walk(0xffff_fffd, 2)", 3),
+        vec![0xffff_ffff, 0xffff_ffff, 0xffff_ffff]
+    );
+    assert_eq!(samples("Walk(0xffff_ffff, Pattern(1, -1))", 2), vec![0xffff_ffff, 0xffff_fffe]);
+}
+
+/// Returns the differences between consecutive positions of a walk starting at 1000
+fn walk_steps(expr: &str, n: usize) -> Vec<i64> {
+    std::iter::once(1000)
+        .chain(samples(expr, n))
+        .collect::<Vec<u32>>()
+        .windows(2)
+        .map(|pair| i64::from(pair[1]) - i64::from(pair[0]))
+        .collect()
+}
+
+#[test]
+fn signed_range_step() {
+    let steps = walk_steps("Walk(1000, [-5, 5])", 1000);
+
+    assert!(steps.iter().all(|step| (-5..=5).contains(step)));
+    assert!(steps.contains(&-5));
+    assert!(steps.contains(&5));
+}
+
+#[test]
+fn signed_range_in_step_expression() {
+    let steps = walk_steps("Walk(1000, [-5, 5] + 1)", 1000);
+
+    assert!(steps.iter().all(|step| (-4..=6).contains(step)));
+    assert!(steps.contains(&-4));
+    assert!(steps.contains(&6));
+
+    let steps = walk_steps("Walk(1000, Pattern([-3, -1], [1, 3]))", 1000);
+
+    assert!(steps.iter().step_by(2).all(|step| (-3..=-1).contains(step)));
+    assert!(steps.iter().skip(1).step_by(2).all(|step| (1..=3).contains(step)));
+}
+
+#[test]
+fn profiled_range_in_step() {
+    assert!(expr_to_var("Walk(1000, [-5, 5]: linear_up)").is_err());
+    assert!(expr_to_var("Walk(1000, ([-5, 5]: linear_up) + 1)").is_err());
+}
+
+#[test]
+fn done() {
+    let a = expr_to_var("Walk(0, Pattern(1, 2))").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    assert!(!a.done());
+    a.next();
+    assert!(a.done());
+}
+
+#[test]
+fn display() {
+    let a = expr_to_var("Walk(100, [-5, 5])").unwrap();
+    assert_eq!(a.borrow().to_string(), "Walk(0x64, [-0x5, 0x5])");
+
+    let a = expr_to_var("Walk(100, Pattern(1, 2))").unwrap();
+    assert_eq!(a.borrow().to_string(), "Walk(0x64, Pattern(0x1, 0x2, ))");
+}
+
+#[test]
+fn invalid() {
+    assert!(expr_to_var("Walk(0)").is_err());
+    assert!(expr_to_var("Walk(0, 1, 2)").is_err());
+}
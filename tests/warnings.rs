@@ -0,0 +1,54 @@
+mod util;
+use crate::util::*;
+
+fn warnings(s: &str) -> Vec<String> {
+    let model = model_with(s, Default::default(), |_| {}).unwrap();
+
+    model.warnings().iter().map(|w| w.to_string()).collect()
+}
+
+#[test]
+fn reversed_literal_range() {
+    assert_eq!(
+        warnings("a = 1;\nb = [5, 2];"),
+        ["warning at 2:1: the range [0x5, 0x2] has a low bound greater than its high bound and is treated as [0x2, 0x5]"]
+    );
+}
+
+#[test]
+fn ordered_literal_range() {
+    assert!(warnings("a = [2, 5];").is_empty());
+    assert!(warnings("a = [2, 2];").is_empty());
+}
+
+#[test]
+fn reversed_constant_range() {
+    assert_eq!(warnings("const HI = 7; a = [HI, 1];").len(), 1);
+    assert_eq!(warnings("enum E { A = 3, B = 1 } a = [E::A, E::B];").len(), 1);
+}
+
+#[test]
+fn expression_driven_range() {
+    assert!(warnings("a = [5 + 1, 2];").is_empty());
+    assert!(warnings("b = 5; a = [b, 2];").is_empty());
+}
+
+#[test]
+fn nested() {
+    assert_eq!(warnings("a = Pattern(1, r{[9, 0], 3});").len(), 1);
+}
+
+#[test]
+fn warnings_accumulate() {
+    assert_eq!(warnings("a = [5, 2]; b = [1, 0]; c = [0, 1];").len(), 2);
+}
+
+#[test]
+fn collected_by_model() {
+    let mut model = rvs::parse(&Default::default(), "a = [5, 2];").unwrap();
+
+    assert_eq!(model.warnings().len(), 1);
+
+    model.clear_warnings();
+    assert!(model.warnings().is_empty());
+}
@@ -41,7 +41,7 @@ fn done_when_sub_expr_done() {
     let a = expr_to_var("r{1: Pattern(0, 1), 1: Pattern(2, 3)}").unwrap();
     let mut a = a.borrow_mut();
 
-    assert_eq!(a.done(), false);
+    assert!(!a.done());
 
     let expected: Vec<bool> = vec![false, true].into_iter().cycle().take(32).collect();
     let actual: Vec<bool> = (0..expected.len())
@@ -53,3 +53,97 @@ fn done_when_sub_expr_done() {
 
     assert_eq!(expected, actual);
 }
+
+fn histogram(expr: &str, samples: usize) -> HashMap<u32, u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut actual: HashMap<u32, u32> = HashMap::new();
+    for _ in 0..samples {
+        let entry = actual.entry(a.next()).or_insert(0);
+        *entry += 1;
+    }
+
+    actual
+}
+
+#[test]
+fn nested_group_distribution() {
+    let actual = histogram("r{3: (1: 0, 1: 5), 1: 9}", 8000);
+
+    println!("expected:{{0: 3000, 5: 3000, 9: 2000}} actual:{:?}", actual);
+    assert_eq!(actual.len(), 3);
+    assert!((3000 - 200..=3000 + 200).contains(&actual[&0]));
+    assert!((3000 - 200..=3000 + 200).contains(&actual[&5]));
+    assert!((2000 - 200..=2000 + 200).contains(&actual[&9]));
+}
+
+#[test]
+fn deeply_nested_group_distribution() {
+    let actual = histogram("r{(1: 0, 1: (1: 1, 3: 2)), 2: 3}", 8000);
+
+    println!("expected:{{0: 1333, 1: 333, 2: 1000, 3: 5333}} actual:{:?}", actual);
+    assert_eq!(actual.len(), 4);
+    assert!((1333 - 150..=1333 + 150).contains(&actual[&0]));
+    assert!((333 - 100..=333 + 100).contains(&actual[&1]));
+    assert!((1000 - 150..=1000 + 150).contains(&actual[&2]));
+    assert!((5333 - 250..=5333 + 250).contains(&actual[&3]));
+}
+
+#[test]
+fn group_is_sugar_for_nested_sample() {
+    let model = rvs::parse(&Default::default(), "a = r{3: (1: 0, 1: 5), 1: 9};").unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = r{3: r{1: 0x0, 1: 0x5, }, 1: 0x9, };\n"
+    );
+}
+
+#[test]
+fn empty_group() {
+    assert!(expr_to_var("r{3: (), 1: 9}").is_err());
+}
+
+#[test]
+fn parenthesized_expr_is_not_a_group() {
+    let model = rvs::parse(&Default::default(), "a = r{(1 + 2), (3)};").unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = r{1: (0x1 + 0x2), 1: 0x3, };\n"
+    );
+}
+
+#[test]
+fn all_zero_weights() {
+    let a = expr_to_var("r{0: 1, 0: 2}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..4 {
+        assert_eq!(a.next(), 0);
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn weighted_ranges() {
+    let a = expr_to_var("WeightedSample(10: [0, 9], 90: [10, 99])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut high = 0;
+    for _ in 0..10_000 {
+        let value = a.next();
+        match a.last_selected() {
+            Some(0) => assert!(value <= 9, "{}", value),
+            Some(1) => {
+                assert!((10..=99).contains(&value), "{}", value);
+                high += 1;
+            }
+            selected => panic!("unexpected selection {:?}", selected),
+        }
+    }
+
+    println!("expected:9000 actual:{}", high);
+    assert!((9000 - 200..=9000 + 200).contains(&high));
+}
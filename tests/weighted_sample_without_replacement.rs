@@ -20,3 +20,16 @@ fn distribution() {
         assert_eq!(expected, actual);
     }
 }
+
+#[test]
+fn all_zero_weights() {
+    for expr in ["{0: 1, 0: 2}", "o{0: 1}", "u{0: [1, 2]}"].iter() {
+        let a = expr_to_var(expr).unwrap();
+        let mut a = a.borrow_mut();
+
+        for _ in 0..4 {
+            assert_eq!(a.next(), 0);
+            assert!(a.done());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+mod util;
+use crate::util::*;
+
+fn weights(model: &rvs::Model, name: &str) -> Option<Vec<u32>> {
+    let variable = model.get_variable_by_name(name).unwrap().borrow();
+
+    variable.weights().map(|weights| weights.to_vec())
+}
+
+#[test]
+fn weighted() {
+    let model = model(
+        "a = WeightedSample(3: 0, 1: 1);
+         b = {3: 0, 1: 1};
+         c = r{25%: 0, 75%: 1};
+         d = r{0.5: 0, 0.25: 1, 0.25: 2};
+         e = r{0, 1, 2};",
+    );
+
+    assert_eq!(weights(&model, "a"), Some(vec![3, 1]));
+    assert_eq!(weights(&model, "b"), Some(vec![3, 1]));
+    assert_eq!(weights(&model, "c"), Some(vec![25, 75]));
+    assert_eq!(weights(&model, "d"), Some(vec![2, 1, 1]));
+    assert_eq!(weights(&model, "e"), Some(vec![1, 1, 1]));
+}
+
+#[test]
+fn not_weighted() {
+    let model = model("a = [0, 1]; b = {3: 0, 1: 1} + 1; c = Pattern(1, 2);");
+
+    for name in &["a", "b", "c"] {
+        assert_eq!(weights(&model, name), None, "{}", name);
+    }
+}
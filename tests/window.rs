@@ -0,0 +1,89 @@
+mod util;
+use crate::util::*;
+
+fn values(expr: &str, count: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| a.next()).collect()
+}
+
+#[test]
+fn window_min() {
+    assert_eq!(
+        values("WindowMin(Pattern(5, 3, 8, 6, 7, 9, 1, 4), 3)", 10),
+        [5, 3, 3, 3, 6, 6, 1, 1, 1, 3]
+    );
+}
+
+#[test]
+fn window_max() {
+    assert_eq!(
+        values("WindowMax(Pattern(5, 3, 8, 6, 7, 2, 1, 4), 3)", 10),
+        [5, 5, 8, 8, 8, 7, 7, 4, 5, 5]
+    );
+}
+
+#[test]
+fn repeated_values() {
+    assert_eq!(values("WindowMin(Pattern(2, 2, 3, 3, 3), 2)", 6), [2, 2, 2, 3, 3, 2]);
+    assert_eq!(values("WindowMax(Pattern(3, 3, 2, 2, 2), 2)", 6), [3, 3, 3, 2, 2, 3]);
+}
+
+#[test]
+fn size_one_passes_through() {
+    assert_eq!(values("WindowMin(Pattern(5, 3, 8), 1)", 4), [5, 3, 8, 5]);
+    assert_eq!(values("WindowMax(Pattern(5, 3, 8), 1)", 4), [5, 3, 8, 5]);
+}
+
+#[test]
+fn window_larger_than_values() {
+    assert_eq!(values("WindowMin(Pattern(5, 3, 8, 1), 100)", 6), [5, 3, 3, 1, 1, 1]);
+}
+
+#[test]
+fn done_passed_through() {
+    let a = expr_to_var("WindowMin(Pattern(1, 2), 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<bool> = (0..4)
+        .map(|_| {
+            a.next();
+            a.done()
+        })
+        .collect();
+
+    assert_eq!(actual, [false, true, false, true]);
+}
+
+#[test]
+fn random() {
+    let a = expr_to_var("[0, 99]").unwrap();
+    let min = expr_to_var("WindowMin([0, 99], 5)").unwrap();
+    let max = expr_to_var("WindowMax([0, 99], 5)").unwrap();
+
+    // All variables start from the same seed so the windows see the values of `a`
+    let mut recent = Vec::new();
+    for _ in 0..1000 {
+        recent.push(a.borrow_mut().next());
+        let window = &recent[recent.len().saturating_sub(5)..];
+
+        assert_eq!(min.borrow_mut().next(), *window.iter().min().unwrap());
+        assert_eq!(max.borrow_mut().next(), *window.iter().max().unwrap());
+    }
+}
+
+#[test]
+fn zero_size() {
+    match expr_to_var("WindowMin([0, 9], 0)") {
+        Err(e) => assert_eq!(e.to_string(), "error at 1:1: WindowMin() window size must be at least 1"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = WindowMin([0, 9], 4); b = WindowMax(a, 2);").unwrap();
+
+    assert_eq!(model.to_string(), "a = WindowMin([0x0, 0x9], 4);\nb = WindowMax(a, 2);\n");
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+mod util;
+use crate::util::*;
+
+fn histogram(expr: &str, samples: usize) -> HashMap<u32, usize> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut histogram = HashMap::new();
+    for _ in 0..samples {
+        *histogram.entry(a.next()).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+#[test]
+fn first_index_dominates() {
+    let histogram = histogram("Zipf(10, 1)", 10_000);
+
+    // P(1) = 1 / H(10) ~= 0.34 and P(2) ~= 0.17
+    assert!(histogram[&1] > 3_000);
+    for index in 2..=10 {
+        assert!(histogram[&1] > histogram[&index]);
+    }
+    assert!(histogram.keys().all(|index| (1..=10).contains(index)));
+}
+
+#[test]
+fn rational_exponent() {
+    let histogram = histogram("Zipf(10, 3, 2)", 10_000);
+
+    // P(1) = 1 / H(10, 1.5) ~= 0.50
+    assert!(histogram[&1] > 4_500);
+}
+
+#[test]
+fn zero_exponent_is_uniform() {
+    let histogram = histogram("Zipf(4, 0)", 10_000);
+
+    assert_eq!(histogram.len(), 4);
+    for index in 1..=4 {
+        assert!(histogram[&index] > 2_200);
+        assert!(histogram[&index] < 2_800);
+    }
+}
+
+#[test]
+fn single_index() {
+    let a = expr_to_var("Zipf(1, 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..10 {
+        assert_eq!(a.next(), 1);
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn bad_arguments() {
+    assert!(expr_to_var("Zipf(0, 1)").is_err());
+    assert!(expr_to_var(format!("Zipf({}, 1)", rvs::MAX_ZIPF_N + 1)).is_err());
+    assert!(expr_to_var("Zipf(10, 1, 0)").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = Zipf(10, 1);").unwrap();
+
+    assert_eq!(model.to_string(), "a = Zipf(10, 1, 1);\n");
+}